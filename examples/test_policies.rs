@@ -1,15 +1,20 @@
-// Run from project dir: cargo run --example test_policies
-use lazyops::azure::AzureCli;
+// Run from project dir: cargo run --example test_policies -- <pr-id>
+use lazyops::azure::{AzureCli, DevOpsBackend};
 use lazyops::config::Config;
 
 #[tokio::main]
 async fn main() {
+    let Some(pr_id) = std::env::args().nth(1).and_then(|a| a.parse::<i32>().ok()) else {
+        eprintln!("Usage: test_policies <pr-id>");
+        std::process::exit(1);
+    };
+
     let config = Config::load();
     let project = &config.projects[0];
     let client = AzureCli::new(project);
 
-    eprintln!("Testing list_pr_policies for PR #7917...");
-    match client.list_pr_policies(7917).await {
+    eprintln!("Testing list_pr_policies for PR #{pr_id}...");
+    match client.list_pr_policies(pr_id).await {
         Ok(policies) => {
             eprintln!("SUCCESS: Got {} policies", policies.len());
             for p in &policies {
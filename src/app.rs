@@ -1,10 +1,30 @@
+use crate::artifacts::{ArtifactBrowser, ArtifactDownload, LoadedArtifact};
 use crate::azure::{
-    AzureCli, Pipeline, PipelineRun, Release, ReleaseDefinition, Sprint, TimelineRecord, User,
-    WorkItem, WorkItemRelation,
+    AzureCli, Backend, Board, DevOpsBackend, PRChange, PRCommentAnchor, PRIteration, Pipeline,
+    PipelineRun, PipelineRunRequest, Release, ReleaseDefinition, Sprint, TeamCapacity,
+    TimelineRecord, User, WorkItem, WorkItemComment, WorkItemFields, WorkItemRelation,
 };
-use crate::cache::{self, CICDCacheEntry, CacheEntry};
-use crate::config::Config;
+use crate::board::{BoardLayout, KanbanBoard};
+use crate::bulk::{BulkOperation, BulkOutcome};
+use crate::burndown::{Burndown, SprintProgress};
+use crate::cache::{self, CICDCacheEntry, Cache, CacheEntry};
+use crate::capacity::CapacityPlan;
+use crate::config::{Config, SavedQuery};
+use crate::discussion::{CommentDraft, CommentsUpdate};
+use crate::failure_summary::{FailureIssue, FailureSummary, IssueCounts};
+use crate::history::HistoryEntry;
+use crate::keymap::{KeyChord, Keymap};
+use crate::log_view::{LogTarget, LogView, Severity};
+use crate::pipeline_trigger::{PipelineTriggerDialog, TriggerOptions};
+use crate::pr_complete::PRCompleteDialog;
+use crate::pr_create::{Candidate, PRCreateDialog};
+use crate::pr_diff::{FileDiff, PRFiles};
+use crate::query_browser::QueryBrowser;
 use crate::terminal::EmbeddedTerminal;
+use crate::test_results::{BuildTests, FLAKY_HISTORY_RUNS};
+use crate::work_item_create::WorkItemCreateDialog;
+use crate::work_item_form::{FieldKind, WorkItemForm};
+use crate::work_item_link::{LinkDialog, LinkOutcome, LinkTarget};
 use anyhow::Result;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
@@ -36,6 +56,11 @@ pub enum CICDLoadResult {
     },
     PipelineRunCanceled(i32),
     PipelineRunRetriggered(PipelineRun),
+    /// Run queued from the pipeline trigger dialog or a preset
+    PipelineRunQueued {
+        pipeline_id: i32,
+        run: PipelineRun,
+    },
     ReleaseCanceled(i32),
     ReleaseEnvironmentCanceled {
         release_id: i32,
@@ -62,11 +87,112 @@ pub enum PRLoadResult {
     PRThreads(Vec<crate::azure::PRThread>),
     PRPolicies(Vec<crate::azure::PRPolicy>),
     PRWorkItems(serde_json::Value),
-    PRVoted { pr_id: i32, vote: String },
-    PRCommented { pr_id: i32 },
+    PRVoted {
+        pr_id: i32,
+        vote: String,
+    },
+    PRCommented {
+        pr_id: i32,
+    },
+    PRUpdated(Box<crate::azure::PullRequest>),
+    PRThreadUpdated {
+        pr_id: i32,
+        thread_id: i32,
+        status: String,
+    },
+    Branches {
+        repository: String,
+        branches: Vec<String>,
+    },
+    PRCreated(Box<crate::azure::PullRequest>),
+    PRCreateFailed(String),
+    PRIterations {
+        pr_id: i32,
+        iterations: Vec<PRIteration>,
+    },
+    PRChanges {
+        pr_id: i32,
+        iteration: usize,
+        changes: Vec<PRChange>,
+    },
+    PRFileDiff {
+        pr_id: i32,
+        diff: Box<FileDiff>,
+    },
+    PRFilesFailed(String),
+    PRCompletePolicies {
+        pr_id: i32,
+        policies: Vec<crate::azure::PRPolicy>,
+    },
+    PRCompleted {
+        pr: Box<crate::azure::PullRequest>,
+        auto_complete: bool,
+    },
+    PRCompleteFailed(String),
     Error(String),
 }
 
+/// Work item updates a bulk operation runs at once
+const BULK_CONCURRENCY: usize = 8;
+
+/// Relation batches the background loader fetches at once
+const RELATION_BATCH_CONCURRENCY: usize = 4;
+
+/// Run timelines fetched at once for the error / warning counts
+const RUN_TIMELINE_CONCURRENCY: usize = 4;
+
+/// Builds whose test results are fetched at once
+const BUILD_TESTS_CONCURRENCY: usize = 4;
+
+/// Result of the background relation loader
+#[derive(Debug)]
+pub enum RelationsLoad {
+    /// Relations of a listed work item; `None` when they could not be loaded
+    Relations(i32, Option<Vec<WorkItemRelation>>),
+    /// Title of a parent that is not in the list, such as a feature in no sprint
+    ParentTitle(i32, String),
+    /// A batch could not be loaded; its items are fetched again on the next start
+    Failed(String),
+}
+
+/// Reviewer votes offered by the vote dialog: (vote name, label)
+pub const PR_VOTES: &[(&str, &str)] = &[
+    ("approve", "Approve"),
+    ("approve-with-suggestions", "Approve with suggestions"),
+    ("wait-for-author", "Wait for author"),
+    ("reject", "Reject"),
+    ("reset", "Reset vote"),
+];
+
+/// Where a composed PR comment is posted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommentTarget {
+    NewThread,
+    Reply {
+        thread_id: i32,
+    },
+    /// New thread on lines of a changed file
+    Lines(PRCommentAnchor),
+}
+
+/// Comment being written in the PR comment composer
+#[derive(Debug, Clone)]
+pub struct PRCommentDraft {
+    pub pr_id: i32,
+    pub repository_id: String,
+    pub target: CommentTarget,
+    pub text: String,
+}
+
+/// Status change applied to a pull request through `update_pr`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PRUpdate {
+    Abandon,
+    Reactivate,
+    MarkDraft,
+    Publish,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Focus {
     WorkItems,
@@ -88,7 +214,25 @@ pub enum InputMode {
     ReleaseTriggerDialog,
     #[allow(dead_code)]
     ApprovalConfirm,
-    ConfirmAction, // For cancel/retrigger confirmation dialog
+    ConfirmAction,         // For cancel/retrigger confirmation dialog
+    PRVote,                // Vote picker for the selected PR
+    PRComment,             // Multi-line comment composer
+    PRCreate,              // Full-screen PR creation dialog
+    PRComplete,            // Merge strategy / policy dialog for completing a PR
+    WorkItemForm,          // Editable fields form in the Details tab
+    WorkItemCreate,        // Dialog creating work items / child tasks
+    EditTags,              // "+tag -tag" prompt for the selected / marked items
+    MoveIteration,         // Sprint picker moving the selected / marked items
+    QueryBrowser,          // Saved / project WIQL queries picker
+    QueryEditor,           // Ad-hoc WIQL editor
+    SprintStats,           // Burndown and progress panel for the sprint
+    CommentComposer,       // Work item discussion comment being written
+    LinkWorkItem,          // Parent / related / PR / commit link picker
+    TeamCapacity,          // Capacity against remaining work per member
+    PipelineTriggerDialog, // Branch, parameters, variables and stages of a new run
+    LogSearch,             // Regex search prompt of the log preview
+    FailureSummary,        // Errors and warnings of a run, jumping to their log lines
+    Artifacts,             // Artifacts of a run, their files and downloads
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -96,6 +240,8 @@ pub enum PreviewTab {
     #[default]
     Details,
     References,
+    Discussion,
+    History,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -105,6 +251,14 @@ pub enum View {
     PRs,
     #[allow(clippy::upper_case_acronyms)]
     CICD,
+    Board,
+}
+
+impl View {
+    /// Views listing the sprint's work items (as a tree or as board cards)
+    pub fn shows_work_items(self) -> bool {
+        matches!(self, View::Tasks | View::Board)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -171,6 +325,7 @@ pub enum PRDrillDown {
     #[default]
     Repos,
     PRs,
+    Files, // Changed files of the previewed PR
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -207,6 +362,23 @@ pub enum CICDFocus {
     Preview,
 }
 
+/// Tab of the preview of a selected pipeline run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RunPreviewTab {
+    #[default]
+    Details,
+    Tests,
+}
+
+impl RunPreviewTab {
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Details => Self::Tests,
+            Self::Tests => Self::Details,
+        }
+    }
+}
+
 /// Pipeline-specific drill-down state
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum PipelineDrillDown {
@@ -305,6 +477,21 @@ pub enum ConfirmActionType {
         release_id: i32,
         environment_name: String,
     },
+    UpdatePullRequest {
+        pr_id: i32,
+        title: String,
+        update: PRUpdate,
+    },
+    DeleteComment {
+        work_item_id: i32,
+        comment_id: i32,
+    },
+    RemoveLink {
+        work_item_id: i32,
+        relation: WorkItemRelation,
+        /// The link as the References tab shows it
+        description: String,
+    },
 }
 
 /// Confirmation dialog state for cancel/retrigger actions
@@ -332,6 +519,14 @@ impl ConfirmActionDialog {
             ConfirmActionType::CancelReleaseEnvironment { .. } => "Cancel Stage?",
             ConfirmActionType::RetriggerReleaseEnvironment { .. } => "Redeploy Stage?",
             ConfirmActionType::RejectApproval { .. } => "Reject Approval?",
+            ConfirmActionType::UpdatePullRequest { update, .. } => match update {
+                PRUpdate::Abandon => "Abandon Pull Request?",
+                PRUpdate::Reactivate => "Reactivate Pull Request?",
+                PRUpdate::MarkDraft => "Mark as Draft?",
+                PRUpdate::Publish => "Publish Draft?",
+            },
+            ConfirmActionType::DeleteComment { .. } => "Delete Comment?",
+            ConfirmActionType::RemoveLink { .. } => "Remove Link?",
         }
     }
 
@@ -362,6 +557,24 @@ impl ConfirmActionDialog {
             ConfirmActionType::RejectApproval {
                 environment_name, ..
             } => format!("Reject approval for '{environment_name}'?"),
+            ConfirmActionType::UpdatePullRequest {
+                pr_id,
+                title,
+                update,
+            } => match update {
+                PRUpdate::Abandon => format!("Abandon PR #{pr_id} '{title}'?"),
+                PRUpdate::Reactivate => format!("Reactivate PR #{pr_id} '{title}'?"),
+                PRUpdate::MarkDraft => format!("Mark PR #{pr_id} '{title}' as draft?"),
+                PRUpdate::Publish => format!("Publish PR #{pr_id} '{title}' for review?"),
+            },
+            ConfirmActionType::DeleteComment { work_item_id, .. } => {
+                format!("Delete your comment on #{work_item_id}?")
+            }
+            ConfirmActionType::RemoveLink {
+                work_item_id,
+                description,
+                ..
+            } => format!("Unlink {description} from #{work_item_id}?"),
         }
     }
 }
@@ -370,23 +583,57 @@ impl PreviewTab {
     pub fn next(&self) -> Self {
         match self {
             Self::Details => Self::References,
-            Self::References => Self::Details,
+            Self::References => Self::Discussion,
+            Self::Discussion => Self::History,
+            Self::History => Self::Details,
         }
     }
 
     pub fn prev(&self) -> Self {
         match self {
-            Self::Details => Self::References,
+            Self::Details => Self::History,
             Self::References => Self::Details,
+            Self::Discussion => Self::References,
+            Self::History => Self::Discussion,
         }
     }
 }
 
-pub struct App {
+/// A work item's update timeline, or why it could not be loaded
+pub type HistoryResult = Result<Vec<HistoryEntry>, String>;
+
+/// A work item's comments, or why they could not be loaded
+pub type CommentsResult = Result<Vec<WorkItemComment>, String>;
+
+/// A sprint's team capacity, or why it could not be loaded
+pub type CapacityResult = Result<TeamCapacity, String>;
+
+/// A build's timeline, or why it could not be loaded
+pub type TimelineResult = Result<Vec<TimelineRecord>, String>;
+
+/// A build's test results, or why they could not be loaded
+pub type TestsResult = Result<BuildTests, String>;
+
+/// A build's artifacts, or why they could not be loaded
+pub type ArtifactsResult = Result<Vec<LoadedArtifact>, String>;
+
+pub struct App<B: DevOpsBackend = Backend> {
     // Config
     pub config: Config,
     pub current_project_idx: usize,
 
+    /// Data source (unbound; `client()` binds it to the current project)
+    pub backend: B,
+    /// On-disk cache, in `cache_dir` of the config
+    pub cache: Cache,
+    /// Running on the bundled demo data: nothing is written to the config
+    pub demo: bool,
+
+    /// Normal-mode key dispatch built from `[keybindings]`
+    pub keymap: Keymap,
+    /// Keys typed so far of an unfinished sequence (e.g. the first `g` of `gg`)
+    pub pending_keys: Vec<KeyChord>,
+
     // UI state
     pub focus: Focus,
     pub input_mode: InputMode,
@@ -427,11 +674,22 @@ pub struct App {
     pub selected_task_idx: usize,
     pub build_log_lines: Vec<String>,
     pub log_scroll: usize,
+    /// Search, filter and timestamps of the log preview
+    pub log_view: LogView,
+    /// Issue of the failure summary to land on once its log is loaded
+    pub pending_log_issue: Option<FailureIssue>,
     pub selected_run_id: Option<i32>,
     pub current_pipeline_id: Option<i32>,
     pub current_release_def_id: Option<i32>,
     pub current_log_id: Option<i32>,
     pub pipeline_runs_limited: bool, // True if showing limited (10) runs
+    /// Errors and warnings of the listed runs, by build ID
+    pub run_issue_counts: std::collections::HashMap<i32, IssueCounts>,
+    pub run_issue_counts_rx: Option<mpsc::Receiver<(i32, IssueCounts)>>,
+    pub run_preview_tab: RunPreviewTab,
+    /// Test results of finished runs, by build ID
+    pub build_tests: std::collections::HashMap<i32, TestsResult>,
+    pub build_tests_rx: Option<mpsc::Receiver<(i32, TestsResult)>>,
     pub pinned_pipelines: HashSet<i32>,
     pub pinned_releases: HashSet<i32>,
 
@@ -455,6 +713,21 @@ pub struct App {
 
     // Release trigger dialog state
     pub release_trigger_dialog: Option<ReleaseTriggerDialog>,
+    pub pipeline_trigger_dialog: Option<PipelineTriggerDialog>,
+    /// Channel for the options of the pipeline trigger dialog, with the
+    /// pipeline's ID
+    pub pipeline_trigger_rx: Option<mpsc::Receiver<(i32, Result<TriggerOptions, String>)>>,
+    /// "Why did it fail?" panel of a run
+    pub failure_summary: Option<FailureSummary>,
+    pub failure_summary_rx: Option<mpsc::Receiver<(i32, TimelineResult)>>,
+    /// Artifacts panel of a run
+    pub artifact_browser: Option<ArtifactBrowser>,
+    pub artifact_browser_rx: Option<mpsc::Receiver<(i32, ArtifactsResult)>>,
+    /// Download in progress, and the channel reporting how it ended
+    pub artifact_download: Option<ArtifactDownload>,
+    pub artifact_download_rx: Option<mpsc::Receiver<Result<(), String>>>,
+    /// Directory the last download went to
+    pub download_dir: Option<String>,
     pub approval_dialog: Option<(String, String)>, // (approval_type, stage_name)
     pub confirm_action_dialog: Option<ConfirmActionDialog>,
 
@@ -483,6 +756,11 @@ pub struct App {
 
     // Input
     pub search_query: String,
+    pub work_item_form: Option<WorkItemForm>,
+    pub work_item_create_dialog: Option<WorkItemCreateDialog>,
+    pub query_browser: Option<QueryBrowser>,
+    /// WIQL query listed instead of the selected sprint
+    pub active_query: Option<SavedQuery>,
 
     // Filters
     pub filter_state: Option<String>,
@@ -493,6 +771,58 @@ pub struct App {
     pub pinned_items: HashSet<i32>,
     pub force_collapsed: bool, // When true, don't auto-expand during filtering
 
+    // Multi-select
+    /// Work items marked for bulk operations
+    pub marked_items: HashSet<i32>,
+    /// List index where range selection started
+    pub visual_anchor: Option<usize>,
+    /// Errors from the last bulk update, per work item
+    pub bulk_failures: std::collections::HashMap<i32, String>,
+    /// Channel for the running bulk update
+    pub bulk_rx: Option<mpsc::Receiver<BulkOutcome>>,
+
+    // Kanban board
+    /// Team boards, loaded when the Board view is first opened
+    pub boards: Option<Vec<Board>>,
+    /// Swimlanes folded away on the board
+    pub collapsed_lanes: HashSet<String>,
+
+    // Sprint analytics
+    /// Burndown of the last sprint the analytics panel was opened for
+    pub burndown: Option<Burndown>,
+    /// Channel for the revision history being loaded: the burndown and how
+    /// many items' histories failed to load
+    pub burndown_rx: Option<mpsc::Receiver<(Option<Burndown>, usize)>>,
+
+    // Team capacity
+    /// Capacity and days off of the last sprint the capacity view was opened
+    /// for, with its sprint ID
+    pub team_capacity: Option<(String, CapacityResult)>,
+    pub capacity_rx: Option<mpsc::Receiver<(String, CapacityResult)>>,
+
+    // History tab
+    /// Update timelines per work item, loaded when the History tab shows them
+    pub work_item_history: std::collections::HashMap<i32, HistoryResult>,
+    /// Channel for the timeline being loaded, with its work item ID
+    pub history_rx: Option<(i32, mpsc::Receiver<HistoryResult>)>,
+
+    // Discussion tab
+    /// Comments per work item, newest first, loaded when the Discussion tab
+    /// shows them
+    pub work_item_comments: std::collections::HashMap<i32, CommentsResult>,
+    /// Work items whose comments are being loaded
+    pub comments_loading: HashSet<i32>,
+    pub comments_tx: Option<mpsc::Sender<CommentsUpdate>>,
+    pub comments_rx: Option<mpsc::Receiver<CommentsUpdate>>,
+    /// Highlighted comment: (work item ID, index)
+    pub selected_comment: Option<(i32, usize)>,
+    pub comment_draft: Option<CommentDraft>,
+
+    // Links
+    pub link_dialog: Option<LinkDialog>,
+    /// Channel for the link change in flight
+    pub link_rx: Option<mpsc::Receiver<LinkOutcome>>,
+
     // Flattened work items for display
     pub visible_items: Vec<VisibleWorkItem>,
 
@@ -521,7 +851,7 @@ pub struct App {
     pub relations_loaded: HashSet<i32>,
 
     // Channel for receiving loaded relations from background task
-    pub relations_rx: Option<mpsc::Receiver<(i32, Option<Vec<WorkItemRelation>>)>>,
+    pub relations_rx: Option<mpsc::Receiver<RelationsLoad>>,
     // Track if background loader is running
     pub relations_loader_active: bool,
 
@@ -536,6 +866,7 @@ pub struct App {
     pub embedded_terminal: Option<EmbeddedTerminal>,
     pub terminal_mode: bool,
     pub log_file_path: Option<String>,
+    pub editor_file: Option<(String, String)>, // (temp file, field name) open in $EDITOR
 
     // PR View state
     pub pr_focus: PRFocus,
@@ -569,6 +900,11 @@ pub struct App {
     pub current_repo_id: Option<String>,
     pub current_repo_name: Option<String>,
     pub selected_pr_detail: Option<crate::azure::PullRequest>,
+    pub pr_comment_draft: Option<PRCommentDraft>,
+    pub pr_create_dialog: Option<PRCreateDialog>,
+    pub pr_complete_dialog: Option<PRCompleteDialog>,
+    pub pr_files: Option<PRFiles>,
+    pub pr_file_list_state: ListState,
 }
 
 #[derive(Debug, Clone)]
@@ -588,6 +924,85 @@ pub struct ParsedRelation {
 }
 
 impl App {
+    /// Create an app using the backend selected in config
+    pub fn new(config: Config) -> Self {
+        let backend = Backend::from_settings(&config.settings);
+        Self::with_backend(config, backend)
+    }
+
+    /// Flatten hierarchical work items back to a flat list
+    pub fn flatten_work_items(items: &[WorkItem]) -> Vec<WorkItem> {
+        let mut result = Vec::new();
+        fn collect(items: &[WorkItem], result: &mut Vec<WorkItem>) {
+            for item in items {
+                // Clone item without children (they'll be added separately)
+                let mut flat_item = item.clone();
+                flat_item.children.clear();
+                result.push(flat_item);
+                // Recursively collect children
+                collect(&item.children, result);
+            }
+        }
+        collect(items, &mut result);
+        result
+    }
+}
+
+/// Index of the project to open: last used project first, then the config
+/// default, then the first configured project
+pub fn startup_project_idx(config: &Config) -> usize {
+    Cache::new(config.cache_dir.clone())
+        .load_last_project()
+        .and_then(|name| config.projects.iter().position(|p| p.name == name))
+        .or_else(|| {
+            config
+                .default_project
+                .as_ref()
+                .and_then(|name| config.projects.iter().position(|p| &p.name == name))
+        })
+        .unwrap_or(0)
+}
+
+/// Report a comment post, edit or delete, with the list reloaded after it worked
+async fn comments_outcome<B: DevOpsBackend>(
+    client: &B,
+    work_item_id: i32,
+    result: Result<String>,
+) -> CommentsUpdate {
+    let comments = match &result {
+        Ok(_) => Some(
+            client
+                .get_work_item_comments(work_item_id)
+                .await
+                .map_err(|e| e.to_string()),
+        ),
+        Err(_) => None,
+    };
+    CommentsUpdate {
+        work_item_id,
+        comments,
+        outcome: Some(result.map_err(|e| e.to_string())),
+    }
+}
+
+/// Timeline of a build, from the cache while it is fresh
+async fn fetch_timeline<B: DevOpsBackend>(
+    client: &B,
+    cache: &Cache,
+    project: &str,
+    build_id: i32,
+    ttl: u64,
+) -> Result<Vec<TimelineRecord>> {
+    if let Some((cached, false)) = cache.load_timeline(project, build_id, ttl) {
+        return Ok(cached.records);
+    }
+    let records = client.get_build_timeline(build_id).await?;
+    let cache_entry = cache::TimelineCacheEntry::new(build_id, records.clone());
+    let _ = cache.save_timeline(project, &cache_entry);
+    Ok(records)
+}
+
+impl<B: DevOpsBackend> App<B> {
     /// Get the PR list for the current (or last active) pane
     pub fn pull_requests(&self) -> &[crate::azure::PullRequest] {
         let pane = if self.pr_focus.is_list() {
@@ -635,20 +1050,19 @@ impl App {
         };
     }
 
-    pub fn new(config: Config) -> Self {
-        // Try last used project first, then config default, then 0
-        let default_idx = cache::load_last_project()
-            .and_then(|name| config.projects.iter().position(|p| p.name == name))
-            .or_else(|| {
-                config
-                    .default_project
-                    .as_ref()
-                    .and_then(|name| config.projects.iter().position(|p| &p.name == name))
-            })
-            .unwrap_or(0);
+    /// Create an app with an explicit backend
+    pub fn with_backend(config: Config, backend: B) -> Self {
+        let default_idx = startup_project_idx(&config);
+        let keymap = Keymap::from_config(&config.keybindings);
+        let cache = Cache::new(config.cache_dir.clone());
 
         Self {
             config,
+            backend,
+            cache,
+            demo: false,
+            keymap,
+            pending_keys: Vec::new(),
             current_project_idx: default_idx,
             focus: Focus::WorkItems,
             input_mode: InputMode::Normal,
@@ -685,11 +1099,18 @@ impl App {
             selected_task_idx: 0,
             build_log_lines: Vec::new(),
             log_scroll: 0,
+            log_view: LogView::default(),
+            pending_log_issue: None,
             selected_run_id: None,
             current_pipeline_id: None,
             current_release_def_id: None,
             current_log_id: None,
             pipeline_runs_limited: false,
+            run_issue_counts: std::collections::HashMap::new(),
+            run_issue_counts_rx: None,
+            run_preview_tab: RunPreviewTab::default(),
+            build_tests: std::collections::HashMap::new(),
+            build_tests_rx: None,
             pinned_pipelines: HashSet::new(),
             pinned_releases: HashSet::new(),
             live_preview_enabled: false,
@@ -705,6 +1126,15 @@ impl App {
             pending_approvals_count: 0,
             approvals_loading: false,
             release_trigger_dialog: None,
+            pipeline_trigger_dialog: None,
+            pipeline_trigger_rx: None,
+            failure_summary: None,
+            failure_summary_rx: None,
+            artifact_browser: None,
+            artifact_browser_rx: None,
+            artifact_download: None,
+            artifact_download_rx: None,
+            download_dir: None,
             approval_dialog: None,
             confirm_action_dialog: None,
             status_message: None,
@@ -723,10 +1153,34 @@ impl App {
             preview_scroll_max: 0,
             refs_scroll: 0,
             search_query: String::new(),
+            work_item_form: None,
+            work_item_create_dialog: None,
+            query_browser: None,
+            active_query: None,
             filter_state: None,
             filter_assignee: None,
             expanded_items: HashSet::new(),
             pinned_items: HashSet::new(),
+            marked_items: HashSet::new(),
+            visual_anchor: None,
+            bulk_failures: std::collections::HashMap::new(),
+            bulk_rx: None,
+            boards: None,
+            collapsed_lanes: HashSet::new(),
+            burndown: None,
+            burndown_rx: None,
+            team_capacity: None,
+            capacity_rx: None,
+            work_item_history: std::collections::HashMap::new(),
+            history_rx: None,
+            work_item_comments: std::collections::HashMap::new(),
+            comments_loading: HashSet::new(),
+            comments_tx: None,
+            comments_rx: None,
+            selected_comment: None,
+            comment_draft: None,
+            link_dialog: None,
+            link_rx: None,
             force_collapsed: false,
             visible_items: Vec::new(),
             cache_age: None,
@@ -745,6 +1199,7 @@ impl App {
             embedded_terminal: None,
             terminal_mode: false,
             log_file_path: None,
+            editor_file: None,
 
             // PR state
             pr_focus: PRFocus::default(),
@@ -778,6 +1233,11 @@ impl App {
             current_repo_id: None,
             current_repo_name: None,
             selected_pr_detail: None,
+            pr_comment_draft: None,
+            pr_create_dialog: None,
+            pr_complete_dialog: None,
+            pr_files: None,
+            pr_file_list_state: ListState::default(),
         }
     }
 
@@ -820,14 +1280,36 @@ impl App {
     pub fn poll_relations(&mut self) {
         // Collect results first to avoid borrow issues
         let mut results = Vec::new();
+        let mut finished = false;
         if let Some(rx) = &mut self.relations_rx {
-            while let Ok(item) = rx.try_recv() {
-                results.push(item);
+            loop {
+                match rx.try_recv() {
+                    Ok(item) => results.push(item),
+                    Err(mpsc::error::TryRecvError::Empty) => break,
+                    Err(mpsc::error::TryRecvError::Disconnected) => {
+                        finished = true;
+                        break;
+                    }
+                }
             }
         }
         // Then apply them
-        for (id, relations) in results {
-            self.update_work_item_relations(id, relations);
+        for result in results {
+            match result {
+                RelationsLoad::Relations(id, relations) => {
+                    self.update_work_item_relations(id, relations)
+                }
+                RelationsLoad::ParentTitle(id, title) => {
+                    self.relation_titles.insert(format!("wi:{id}"), title);
+                }
+                RelationsLoad::Failed(e) => self.set_error(format!("Failed to load links: {e}")),
+            }
+        }
+        // Keep the relations for the next start
+        if finished {
+            self.relations_rx = None;
+            self.relations_loader_active = false;
+            self.save_to_cache();
         }
     }
 
@@ -863,14 +1345,12 @@ impl App {
                                     }
                                 }
                             }
-                            "Fixed in Commit" => {
-                                if parts.len() >= 2 {
-                                    let hash = parts[parts.len() - 1];
-                                    let repo = parts[parts.len() - 2];
-                                    let key = format!("commit:{hash}");
-                                    if !existing.contains_key(&key) {
-                                        commits.push((repo.to_string(), hash.to_string()));
-                                    }
+                            "Fixed in Commit" if parts.len() >= 2 => {
+                                let hash = parts[parts.len() - 1];
+                                let repo = parts[parts.len() - 2];
+                                let key = format!("commit:{hash}");
+                                if !existing.contains_key(&key) {
+                                    commits.push((repo.to_string(), hash.to_string()));
                                 }
                             }
                             _ => {}
@@ -896,40 +1376,21 @@ impl App {
         self.titles_rx = Some(rx);
         self.titles_loader_active = true;
 
-        let client_info = self
-            .current_project()
-            .map(|p| (p.organization.clone(), p.project.clone()));
-
-        if let Some((org, project)) = client_info {
+        if let Some(client) = self.client() {
             tokio::spawn(async move {
                 // Fetch PR titles in parallel
                 let pr_futures: Vec<_> = pr_ids
                     .into_iter()
                     .map(|pr_id| {
-                        let org = org.clone();
+                        let client = client.clone();
                         let tx = tx.clone();
                         async move {
-                            let output = tokio::process::Command::new("az")
-                                .args(["repos", "pr", "show"])
-                                .args(["--id", &pr_id])
-                                .args(["--org", &org])
-                                .args(["--output", "json"])
-                                .output()
-                                .await;
-
-                            if let Ok(o) = output {
-                                if o.status.success() {
-                                    if let Ok(json) =
-                                        serde_json::from_slice::<serde_json::Value>(&o.stdout)
-                                    {
-                                        if let Some(title) =
-                                            json.get("title").and_then(|t| t.as_str())
-                                        {
-                                            let key = format!("pr:{pr_id}");
-                                            let _ = tx.send((key, title.to_string())).await;
-                                        }
-                                    }
-                                }
+                            let Ok(id) = pr_id.parse::<i32>() else {
+                                return;
+                            };
+                            if let Ok(pr) = client.get_pull_request(id).await {
+                                let key = format!("pr:{pr_id}");
+                                let _ = tx.send((key, pr.title)).await;
                             }
                         }
                     })
@@ -939,39 +1400,14 @@ impl App {
                 let commit_futures: Vec<_> = commits
                     .into_iter()
                     .map(|(repo_guid, hash)| {
-                        let org = org.clone();
-                        let project = project.clone();
+                        let client = client.clone();
                         let tx = tx.clone();
                         async move {
-                            let output = tokio::process::Command::new("az")
-                                .args(["devops", "invoke"])
-                                .args(["--area", "git"])
-                                .args(["--resource", "commits"])
-                                .args([
-                                    "--route-parameters",
-                                    &format!("project={project}"),
-                                    &format!("repositoryId={repo_guid}"),
-                                    &format!("commitId={hash}"),
-                                ])
-                                .args(["--org", &org])
-                                .args(["--output", "json"])
-                                .output()
-                                .await;
-
-                            if let Ok(o) = output {
-                                if o.status.success() {
-                                    if let Ok(json) =
-                                        serde_json::from_slice::<serde_json::Value>(&o.stdout)
-                                    {
-                                        if let Some(comment) =
-                                            json.get("comment").and_then(|c| c.as_str())
-                                        {
-                                            let title = comment.lines().next().unwrap_or(comment);
-                                            let key = format!("commit:{hash}");
-                                            let _ = tx.send((key, title.to_string())).await;
-                                        }
-                                    }
-                                }
+                            if let Ok(commit) = client.get_commit(&repo_guid, &hash).await {
+                                let comment = commit.comment;
+                                let title = comment.lines().next().unwrap_or(&comment);
+                                let key = format!("commit:{hash}");
+                                let _ = tx.send((key, title.to_string())).await;
                             }
                         }
                     })
@@ -984,59 +1420,84 @@ impl App {
         }
     }
 
-    /// Start background relation loader
+    /// Start background relation loader: relations of the listed items and
+    /// titles of their parents outside the list, fetched in batches
     pub fn start_relations_loader(&mut self) {
+        use futures::stream::StreamExt;
+
         if self.relations_loader_active {
             return; // Already running
         }
 
         let ids = self.get_ids_needing_relations();
-        if ids.is_empty() {
+        let parents = self.get_outside_parent_ids();
+        if ids.is_empty() && parents.is_empty() {
             return;
         }
+        let Some(client) = self.client() else {
+            return;
+        };
 
         let (tx, rx) = mpsc::channel(100);
         self.relations_rx = Some(rx);
         self.relations_loader_active = true;
 
-        // Clone what we need for the background task
-        let client_info = self
-            .current_project()
-            .map(|p| (p.organization.clone(), p.project.clone()));
-
-        if let Some((org, _project)) = client_info {
-            tokio::spawn(async move {
-                for id in ids {
-                    // Create a fresh client for each request
-                    let output = tokio::process::Command::new("az")
-                        .args(["boards", "work-item", "show"])
-                        .args(["--id", &id.to_string()])
-                        .args(["--expand", "relations"])
-                        .args(["--org", &org])
-                        .args(["--output", "json"])
-                        .output()
-                        .await;
+        let delay = self.settings().api_delay_ms;
+        let listed: HashSet<i32> = ids.iter().copied().collect();
+        let batches: Vec<Vec<i32>> = ids
+            .into_iter()
+            .chain(parents)
+            .collect::<Vec<_>>()
+            .chunks(crate::azure::backend::WORK_ITEMS_BATCH_SIZE)
+            .map(<[i32]>::to_vec)
+            .collect();
+        tokio::spawn(async move {
+            let mut results = std::pin::pin!(futures::stream::iter(batches)
+                .map(|batch| {
+                    let client = client.clone();
+                    async move {
+                        let result = client.get_work_items_with_relations(&batch).await;
+                        // Small delay between requests to avoid overwhelming API
+                        tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
+                        (batch, result)
+                    }
+                })
+                .buffer_unordered(RELATION_BATCH_CONCURRENCY));
 
-                    let relations = output.ok().and_then(|o| {
-                        if o.status.success() {
-                            serde_json::from_slice::<WorkItem>(&o.stdout)
-                                .ok()
-                                .and_then(|wi| wi.relations)
-                        } else {
-                            None
+            while let Some((batch, result)) = results.next().await {
+                // The batch stays unloaded, so a refresh fetches it again
+                let items = match result {
+                    Ok(items) => items,
+                    Err(e) => {
+                        if tx.send(RelationsLoad::Failed(e.to_string())).await.is_err() {
+                            return;
                         }
-                    });
-
-                    // Send result back (ignore error if receiver dropped)
-                    if tx.send((id, relations)).await.is_err() {
-                        break; // Receiver dropped, stop loading
+                        continue;
+                    }
+                };
+                let mut loads = Vec::with_capacity(batch.len());
+                for id in batch {
+                    let item = items.iter().find(|w| w.id == id);
+                    if !listed.contains(&id) {
+                        loads.extend(
+                            item.map(|w| RelationsLoad::ParentTitle(id, w.fields.title.clone())),
+                        );
+                    } else if let Some(item) = item {
+                        // Loaded without links is still loaded
+                        let relations = item.relations.clone().unwrap_or_default();
+                        loads.push(RelationsLoad::Relations(id, Some(relations)));
+                    } else {
+                        loads.push(RelationsLoad::Relations(id, None));
                     }
-
-                    // Small delay between requests to avoid overwhelming API
-                    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
                 }
-            });
-        }
+                for load in loads {
+                    // Receiver dropped, stop loading
+                    if tx.send(load).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
     }
 
     #[allow(dead_code)] // Used by events.rs
@@ -1063,13 +1524,16 @@ impl App {
             None => return false,
         };
 
-        if let Some(entry) = cache::load(&project_name) {
+        if let Some(entry) = self.cache.load(&project_name) {
             let age = entry.age_seconds();
             let sprint_path = entry.sprint_path.clone();
             self.sprints = entry.sprints;
             // Rebuild hierarchy since children field is not serialized
             self.work_items = AzureCli::build_hierarchy(entry.work_items);
             self.cache_age = Some(age);
+            // Cached relations are shown until the loader fetches them again
+            self.relations_loaded.clear();
+            self.restore_relations(entry.relations);
 
             // Restore filters and pinned items
             self.filter_state = entry.filter_state;
@@ -1105,6 +1569,10 @@ impl App {
 
     /// Save current data to cache
     pub fn save_to_cache(&self) {
+        // The cache holds the sprint; query results are never cached
+        if self.active_query.is_some() {
+            return;
+        }
         let project_name = match self.current_project() {
             Some(p) => p.name.clone(),
             None => return,
@@ -1116,43 +1584,48 @@ impl App {
 
         // Flatten hierarchical work items back to flat list for serialization
         // (children field is #[serde(skip)] so we need flat list with parent_id)
-        let flat_items = Self::flatten_work_items(&self.work_items);
-
-        let entry = CacheEntry::new(
-            self.sprints.clone(),
-            flat_items,
-            self.users.clone(),
-            sprint_path,
-            self.filter_state.clone(),
-            self.filter_assignee.clone(),
-            self.pinned_items.clone(),
-        );
-        let _ = cache::save(&project_name, &entry);
-    }
-
-    /// Flatten hierarchical work items back to a flat list
-    fn flatten_work_items(items: &[WorkItem]) -> Vec<WorkItem> {
-        let mut result = Vec::new();
-        fn collect(items: &[WorkItem], result: &mut Vec<WorkItem>) {
-            for item in items {
-                // Clone item without children (they'll be added separately)
-                let mut flat_item = item.clone();
-                flat_item.children.clear();
-                result.push(flat_item);
-                // Recursively collect children
-                collect(&item.children, result);
-            }
+        let mut flat_items = App::flatten_work_items(&self.work_items);
+        // Relations are kept once, by ID, for the items that have them
+        let relations = self.cache_relations();
+        for item in &mut flat_items {
+            item.relations = None;
         }
-        collect(items, &mut result);
-        result
+
+        let entry = CacheEntry {
+            relations,
+            ..CacheEntry::new(
+                self.sprints.clone(),
+                flat_items,
+                self.users.clone(),
+                sprint_path,
+                self.filter_state.clone(),
+                self.filter_assignee.clone(),
+                self.pinned_items.clone(),
+            )
+        };
+        let _ = self.cache.save(&project_name, &entry);
     }
 
     pub fn current_project(&self) -> Option<&crate::config::ProjectConfig> {
         self.config.projects.get(self.current_project_idx)
     }
 
-    pub fn client(&self) -> Option<AzureCli> {
-        self.current_project().map(AzureCli::new)
+    /// Global `[settings]` with the current project's overrides applied
+    pub fn settings(&self) -> crate::config::Settings {
+        match self.current_project() {
+            Some(project) => self.config.settings.with_overrides(&project.overrides),
+            None => self.config.settings.clone(),
+        }
+    }
+
+    /// Cache TTL for fast-changing data (runs, releases, logs, PR lists):
+    /// `cache_expiry`, capped at [`cache::CICD_CACHE_TTL_SECS`]
+    fn short_cache_ttl(&self) -> u64 {
+        self.settings().cache_expiry.min(cache::CICD_CACHE_TTL_SECS)
+    }
+
+    pub fn client(&self) -> Option<B> {
+        self.current_project().map(|p| self.backend.for_project(p))
     }
 
     // CI/CD data loading (kept for potential direct API use, currently using background loaders)
@@ -1187,7 +1660,7 @@ impl App {
         let client = self
             .client()
             .ok_or_else(|| anyhow::anyhow!("No project configured"))?;
-        self.pipeline_runs = client.list_pipeline_runs(pipeline_id).await?;
+        self.pipeline_runs = client.list_pipeline_runs(pipeline_id, None).await?;
         Ok(())
     }
 
@@ -1207,7 +1680,7 @@ impl App {
             None => return false,
         };
 
-        if let Some(entry) = cache::load_cicd(&project_name) {
+        if let Some(entry) = self.cache.load_cicd(&project_name) {
             let age = entry.age_seconds();
             self.pipelines = entry.pipelines;
             self.releases = entry.release_definitions;
@@ -1249,7 +1722,7 @@ impl App {
             self.pinned_pipelines.clone(),
             self.pinned_releases.clone(),
         );
-        let _ = cache::save_cicd(&project_name, &entry);
+        let _ = self.cache.save_cicd(&project_name, &entry);
     }
 
     /// Start background CI/CD data loader (checks cache first)
@@ -1264,9 +1737,9 @@ impl App {
         }
 
         // Extract project info first to avoid borrow issues
-        let (org, proj, project_name) = match self.current_project() {
-            Some(p) => (p.organization.clone(), p.project.clone(), p.name.clone()),
-            None => return,
+        let (client, project_name) = match (self.client(), self.current_project()) {
+            (Some(c), Some(p)) => (c, p.name.clone()),
+            _ => return,
         };
 
         // Clone pinned sets to preserve them when saving to cache
@@ -1277,55 +1750,28 @@ impl App {
         self.cicd_rx = Some(rx);
         self.cicd_loading = true;
 
+        let cache = self.cache.clone();
         tokio::spawn(async move {
             let mut pipelines_result: Option<Vec<Pipeline>> = None;
             let mut releases_result: Option<Vec<ReleaseDefinition>> = None;
 
             // Load pipelines
-            let output = tokio::process::Command::new("az")
-                .args(["pipelines", "list"])
-                .args(["--org", &org])
-                .args(["--project", &proj])
-                .args(["--output", "json"])
-                .output()
-                .await;
-
-            if let Ok(o) = output {
-                if o.status.success() {
-                    if let Ok(pipelines) = serde_json::from_slice::<Vec<Pipeline>>(&o.stdout) {
-                        pipelines_result = Some(pipelines.clone());
-                        let _ = tx.send(CICDLoadResult::Pipelines(pipelines)).await;
-                    }
-                }
+            if let Ok(pipelines) = client.list_pipelines().await {
+                pipelines_result = Some(pipelines.clone());
+                let _ = tx.send(CICDLoadResult::Pipelines(pipelines)).await;
             }
 
             // Load release definitions
-            let output = tokio::process::Command::new("az")
-                .args(["pipelines", "release", "definition", "list"])
-                .args(["--org", &org])
-                .args(["--project", &proj])
-                .args(["--output", "json"])
-                .output()
-                .await;
-
-            if let Ok(o) = output {
-                if o.status.success() {
-                    if let Ok(defs) = serde_json::from_slice::<Vec<ReleaseDefinition>>(&o.stdout) {
-                        let filtered: Vec<_> = defs
-                            .into_iter()
-                            .filter(|d| !d.is_deleted && !d.is_disabled)
-                            .collect();
-                        releases_result = Some(filtered.clone());
-                        let _ = tx.send(CICDLoadResult::ReleaseDefinitions(filtered)).await;
-                    }
-                }
+            if let Ok(defs) = client.list_release_definitions().await {
+                releases_result = Some(defs.clone());
+                let _ = tx.send(CICDLoadResult::ReleaseDefinitions(defs)).await;
             }
 
             // Save to cache after loading (preserve pinned items)
             if let (Some(pipelines), Some(releases)) = (pipelines_result, releases_result) {
                 let entry =
                     CICDCacheEntry::new(pipelines, releases, pinned_pipelines, pinned_releases);
-                let _ = cache::save_cicd(&project_name, &entry);
+                let _ = cache.save_cicd(&project_name, &entry);
             }
         });
     }
@@ -1348,6 +1794,12 @@ impl App {
             }
         }
 
+        // Before handling the results, which may start the next loader
+        if channel_closed {
+            self.cicd_loading = false;
+            self.cicd_rx = None;
+        }
+
         for result in results {
             match result {
                 CICDLoadResult::Pipelines(pipelines) => {
@@ -1378,6 +1830,7 @@ impl App {
                     if !self.pipeline_runs.is_empty() {
                         self.selected_pipeline_run_idx = 0;
                     }
+                    self.load_run_issue_counts();
                 }
                 CICDLoadResult::Releases(releases) => {
                     self.release_list = releases;
@@ -1423,6 +1876,7 @@ impl App {
                 CICDLoadResult::ReleaseTaskLog(lines) => {
                     self.release_task_logs = lines;
                     self.log_scroll = 0;
+                    self.log_view.reset();
                 }
                 CICDLoadResult::Timeline(records) => {
                     self.timeline_records = records;
@@ -1457,6 +1911,9 @@ impl App {
                 CICDLoadResult::BuildLog(lines) => {
                     self.build_log_lines = lines;
                     self.log_scroll = 0;
+                    self.log_view.reset();
+                    self.land_on_log_issue();
+                    self.pending_log_issue = None;
                 }
                 CICDLoadResult::PendingApprovals(approvals) => {
                     self.pending_approvals_count = approvals.len();
@@ -1537,6 +1994,15 @@ impl App {
                         self.start_pipeline_runs_loader(pipeline_id);
                     }
                 }
+                CICDLoadResult::PipelineRunQueued { pipeline_id, run } => {
+                    let build_num = run.build_number.as_deref().unwrap_or("?");
+                    self.set_status(format!("Run #{build_num} queued"));
+                    self.cicd_loading = false;
+                    // Show the pipeline's runs with the new one on top
+                    self.pipeline_drill_down = PipelineDrillDown::Runs;
+                    self.selected_pipeline_run_idx = 0;
+                    self.force_refresh_pipeline_runs(pipeline_id);
+                }
                 CICDLoadResult::ReleaseCanceled(release_id) => {
                     self.set_status(format!("Release {release_id} abandoned"));
                     // Reset loading state so we can start a new loader
@@ -1571,11 +2037,6 @@ impl App {
                 }
             }
         }
-
-        if channel_closed {
-            self.cicd_loading = false;
-            self.cicd_rx = None;
-        }
     }
 
     /// Force refresh CI/CD data (bypasses cache)
@@ -1585,9 +2046,9 @@ impl App {
         }
 
         // Extract project info first to avoid borrow issues
-        let (org, proj, project_name) = match self.current_project() {
-            Some(p) => (p.organization.clone(), p.project.clone(), p.name.clone()),
-            None => return,
+        let (client, project_name) = match (self.client(), self.current_project()) {
+            (Some(c), Some(p)) => (c, p.name.clone()),
+            _ => return,
         };
 
         // Clone pinned sets to preserve them when saving to cache
@@ -1598,55 +2059,28 @@ impl App {
         self.cicd_rx = Some(rx);
         self.cicd_loading = true;
 
+        let cache = self.cache.clone();
         tokio::spawn(async move {
             let mut pipelines_result: Option<Vec<Pipeline>> = None;
             let mut releases_result: Option<Vec<ReleaseDefinition>> = None;
 
             // Load pipelines
-            let output = tokio::process::Command::new("az")
-                .args(["pipelines", "list"])
-                .args(["--org", &org])
-                .args(["--project", &proj])
-                .args(["--output", "json"])
-                .output()
-                .await;
-
-            if let Ok(o) = output {
-                if o.status.success() {
-                    if let Ok(pipelines) = serde_json::from_slice::<Vec<Pipeline>>(&o.stdout) {
-                        pipelines_result = Some(pipelines.clone());
-                        let _ = tx.send(CICDLoadResult::Pipelines(pipelines)).await;
-                    }
-                }
+            if let Ok(pipelines) = client.list_pipelines().await {
+                pipelines_result = Some(pipelines.clone());
+                let _ = tx.send(CICDLoadResult::Pipelines(pipelines)).await;
             }
 
             // Load release definitions
-            let output = tokio::process::Command::new("az")
-                .args(["pipelines", "release", "definition", "list"])
-                .args(["--org", &org])
-                .args(["--project", &proj])
-                .args(["--output", "json"])
-                .output()
-                .await;
-
-            if let Ok(o) = output {
-                if o.status.success() {
-                    if let Ok(defs) = serde_json::from_slice::<Vec<ReleaseDefinition>>(&o.stdout) {
-                        let filtered: Vec<_> = defs
-                            .into_iter()
-                            .filter(|d| !d.is_deleted && !d.is_disabled)
-                            .collect();
-                        releases_result = Some(filtered.clone());
-                        let _ = tx.send(CICDLoadResult::ReleaseDefinitions(filtered)).await;
-                    }
-                }
+            if let Ok(defs) = client.list_release_definitions().await {
+                releases_result = Some(defs.clone());
+                let _ = tx.send(CICDLoadResult::ReleaseDefinitions(defs)).await;
             }
 
             // Save to cache after loading (preserve pinned items)
             if let (Some(pipelines), Some(releases)) = (pipelines_result, releases_result) {
                 let entry =
                     CICDCacheEntry::new(pipelines, releases, pinned_pipelines, pinned_releases);
-                let _ = cache::save_cicd(&project_name, &entry);
+                let _ = cache.save_cicd(&project_name, &entry);
             }
         });
     }
@@ -1673,9 +2107,9 @@ impl App {
         limit: Option<u32>,
         force: bool,
     ) {
-        let (org, proj, proj_name) = match self.current_project() {
-            Some(p) => (p.organization.clone(), p.project.clone(), p.name.clone()),
-            None => return,
+        let (client, proj_name) = match (self.client(), self.current_project()) {
+            (Some(c), Some(p)) => (c, p.name.clone()),
+            _ => return,
         };
 
         // Track current pipeline for "load more"
@@ -1685,10 +2119,12 @@ impl App {
         // Stale-while-revalidate: use cache immediately, refresh in background if stale
         let needs_fetch = if !force && limit.is_some() {
             if let Some((cached, needs_refresh)) =
-                cache::load_pipeline_runs(&proj_name, pipeline_id)
+                self.cache
+                    .load_pipeline_runs(&proj_name, pipeline_id, self.short_cache_ttl())
             {
                 self.pipeline_runs = cached.runs;
                 self.cicd_loading = false;
+                self.load_run_issue_counts();
                 needs_refresh // Only fetch if stale
             } else {
                 true // No cache, must fetch
@@ -1705,31 +2141,13 @@ impl App {
         self.cicd_rx = Some(rx);
         self.cicd_loading = true;
 
+        let cache = self.cache.clone();
         tokio::spawn(async move {
-            let mut cmd = tokio::process::Command::new("az");
-            cmd.args(["pipelines", "runs", "list"])
-                .args(["--org", &org])
-                .args(["--project", &proj])
-                .args(["--pipeline-ids", &pipeline_id.to_string()]);
-
-            if let Some(top) = limit {
-                cmd.args(["--top", &top.to_string()]);
-            }
-
-            cmd.args(["--output", "json"]);
-
-            let output = cmd.output().await;
-
-            if let Ok(o) = output {
-                if o.status.success() {
-                    if let Ok(runs) = serde_json::from_slice::<Vec<PipelineRun>>(&o.stdout) {
-                        // Save to cache
-                        let cache_entry =
-                            cache::PipelineRunsCacheEntry::new(pipeline_id, runs.clone());
-                        let _ = cache::save_pipeline_runs(&proj_name, &cache_entry);
-                        let _ = tx.send(CICDLoadResult::PipelineRuns(runs)).await;
-                    }
-                }
+            if let Ok(runs) = client.list_pipeline_runs(pipeline_id, limit).await {
+                // Save to cache
+                let cache_entry = cache::PipelineRunsCacheEntry::new(pipeline_id, runs.clone());
+                let _ = cache.save_pipeline_runs(&proj_name, &cache_entry);
+                let _ = tx.send(CICDLoadResult::PipelineRuns(runs)).await;
             }
         });
     }
@@ -1745,9 +2163,9 @@ impl App {
     }
 
     fn start_releases_loader_impl(&mut self, definition_id: i32, force: bool) {
-        let (org, proj, proj_name) = match self.current_project() {
-            Some(p) => (p.organization.clone(), p.project.clone(), p.name.clone()),
-            None => return,
+        let (client, proj_name) = match (self.client(), self.current_project()) {
+            (Some(c), Some(p)) => (c, p.name.clone()),
+            _ => return,
         };
 
         // Store current definition for refresh
@@ -1755,7 +2173,10 @@ impl App {
 
         // Stale-while-revalidate: use cache immediately, refresh in background if stale
         let needs_fetch = if !force {
-            if let Some((cached, needs_refresh)) = cache::load_releases(&proj_name, definition_id) {
+            if let Some((cached, needs_refresh)) =
+                self.cache
+                    .load_releases(&proj_name, definition_id, self.short_cache_ttl())
+            {
                 self.release_list = cached.releases;
                 self.cicd_loading = false;
                 needs_refresh // Only fetch if stale
@@ -1774,26 +2195,13 @@ impl App {
         self.cicd_rx = Some(rx);
         self.cicd_loading = true;
 
+        let cache = self.cache.clone();
         tokio::spawn(async move {
-            let output = tokio::process::Command::new("az")
-                .args(["pipelines", "release", "list"])
-                .args(["--org", &org])
-                .args(["--project", &proj])
-                .args(["--definition-id", &definition_id.to_string()])
-                .args(["--output", "json"])
-                .output()
-                .await;
-
-            if let Ok(o) = output {
-                if o.status.success() {
-                    if let Ok(releases) = serde_json::from_slice::<Vec<Release>>(&o.stdout) {
-                        // Save to cache
-                        let cache_entry =
-                            cache::ReleasesCacheEntry::new(definition_id, releases.clone());
-                        let _ = cache::save_releases(&proj_name, &cache_entry);
-                        let _ = tx.send(CICDLoadResult::Releases(releases)).await;
-                    }
-                }
+            if let Ok(releases) = client.list_releases(Some(definition_id)).await {
+                // Save to cache
+                let cache_entry = cache::ReleasesCacheEntry::new(definition_id, releases.clone());
+                let _ = cache.save_releases(&proj_name, &cache_entry);
+                let _ = tx.send(CICDLoadResult::Releases(releases)).await;
             }
         });
     }
@@ -1801,9 +2209,8 @@ impl App {
     /// Start background loader for release detail (to get environments/stages)
     #[allow(dead_code)]
     pub fn start_release_detail_loader(&mut self, release_idx: usize, release_id: i32) {
-        let (org, proj, _proj_name) = match self.current_project() {
-            Some(p) => (p.organization.clone(), p.project.clone(), p.name.clone()),
-            None => return,
+        let Some(client) = self.client() else {
+            return;
         };
 
         let (tx, rx) = mpsc::channel(10);
@@ -1811,23 +2218,10 @@ impl App {
         // Don't set cicd_loading - this is a background detail fetch
 
         tokio::spawn(async move {
-            let output = tokio::process::Command::new("az")
-                .args(["pipelines", "release", "show"])
-                .args(["--org", &org])
-                .args(["--project", &proj])
-                .args(["--id", &release_id.to_string()])
-                .args(["--output", "json"])
-                .output()
-                .await;
-
-            if let Ok(o) = output {
-                if o.status.success() {
-                    if let Ok(release) = serde_json::from_slice::<Release>(&o.stdout) {
-                        let _ = tx
-                            .send(CICDLoadResult::ReleaseDetail(release_idx, release))
-                            .await;
-                    }
-                }
+            if let Ok(release) = client.get_release(release_id).await {
+                let _ = tx
+                    .send(CICDLoadResult::ReleaseDetail(release_idx, release))
+                    .await;
             }
         });
     }
@@ -1840,9 +2234,8 @@ impl App {
             return;
         }
 
-        let (org, proj, _proj_name) = match self.current_project() {
-            Some(p) => (p.organization.clone(), p.project.clone(), p.name.clone()),
-            None => return,
+        let Some(client) = self.client() else {
+            return;
         };
 
         // Don't clear - keep previous data visible until new data arrives
@@ -1857,22 +2250,9 @@ impl App {
         self.cicd_loading = true;
 
         tokio::spawn(async move {
-            let output = tokio::process::Command::new("az")
-                .args(["pipelines", "release", "show"])
-                .args(["--org", &org])
-                .args(["--project", &proj])
-                .args(["--id", &release_id.to_string()])
-                .args(["--output", "json"])
-                .output()
-                .await;
-
-            if let Ok(o) = output {
-                if o.status.success() {
-                    if let Ok(release) = serde_json::from_slice::<Release>(&o.stdout) {
-                        if let Some(envs) = release.environments {
-                            let _ = tx.send(CICDLoadResult::ReleaseStages(envs)).await;
-                        }
-                    }
+            if let Ok(release) = client.get_release(release_id).await {
+                if let Some(envs) = release.environments {
+                    let _ = tx.send(CICDLoadResult::ReleaseStages(envs)).await;
                 }
             }
         });
@@ -1897,6 +2277,9 @@ impl App {
 
     /// Start background loader for release task log
     pub fn start_release_task_log_loader(&mut self, log_url: &str) {
+        let Some(client) = self.client() else {
+            return;
+        };
         let log_url = log_url.to_string();
         self.release_task_logs.clear();
         self.log_scroll = 0;
@@ -1906,37 +2289,8 @@ impl App {
         self.cicd_loading = true;
 
         tokio::spawn(async move {
-            // Get access token for Azure DevOps
-            let token_output = tokio::process::Command::new("az")
-                .args(["account", "get-access-token"])
-                .args(["--resource", "499b84ac-1321-427f-aa17-267ca6975798"])
-                .args(["--query", "accessToken"])
-                .args(["-o", "tsv"])
-                .output()
-                .await;
-
-            if let Ok(token_out) = token_output {
-                if token_out.status.success() {
-                    let token = String::from_utf8_lossy(&token_out.stdout)
-                        .trim()
-                        .to_string();
-
-                    // Fetch log using curl
-                    let output = tokio::process::Command::new("curl")
-                        .args(["-s", &log_url])
-                        .args(["-H", &format!("Authorization: Bearer {token}")])
-                        .output()
-                        .await;
-
-                    if let Ok(o) = output {
-                        if o.status.success() {
-                            let log_content = String::from_utf8_lossy(&o.stdout);
-                            let lines: Vec<String> =
-                                log_content.lines().map(|l| l.to_string()).collect();
-                            let _ = tx.send(CICDLoadResult::ReleaseTaskLog(lines)).await;
-                        }
-                    }
-                }
+            if let Ok(lines) = client.get_release_task_log(&log_url).await {
+                let _ = tx.send(CICDLoadResult::ReleaseTaskLog(lines)).await;
             }
         });
     }
@@ -1952,16 +2306,19 @@ impl App {
     }
 
     fn start_timeline_loader_impl(&mut self, build_id: i32, force: bool) {
-        let (org, proj, proj_name) = match self.current_project() {
-            Some(p) => (p.organization.clone(), p.project.clone(), p.name.clone()),
-            None => return,
+        let (client, proj_name) = match (self.client(), self.current_project()) {
+            (Some(c), Some(p)) => (c, p.name.clone()),
+            _ => return,
         };
 
         self.selected_run_id = Some(build_id);
 
         // Stale-while-revalidate: use cache immediately, refresh in background if stale
         let needs_fetch = if !force {
-            if let Some((cached, needs_refresh)) = cache::load_timeline(&proj_name, build_id) {
+            if let Some((cached, needs_refresh)) =
+                self.cache
+                    .load_timeline(&proj_name, build_id, self.short_cache_ttl())
+            {
                 self.timeline_records = cached.records;
                 self.cicd_loading = false;
                 needs_refresh // Only fetch if stale
@@ -1980,33 +2337,13 @@ impl App {
         self.cicd_rx = Some(rx);
         self.cicd_loading = true;
 
+        let cache = self.cache.clone();
         tokio::spawn(async move {
-            let output = tokio::process::Command::new("az")
-                .args(["devops", "invoke"])
-                .args(["--area", "build"])
-                .args(["--resource", "timeline"])
-                .args([
-                    "--route-parameters",
-                    &format!("project={proj}"),
-                    &format!("buildId={build_id}"),
-                ])
-                .args(["--org", &org])
-                .args(["--output", "json"])
-                .output()
-                .await;
-
-            if let Ok(o) = output {
-                if o.status.success() {
-                    if let Ok(resp) =
-                        serde_json::from_slice::<crate::azure::TimelineResponse>(&o.stdout)
-                    {
-                        // Save to cache
-                        let cache_entry =
-                            cache::TimelineCacheEntry::new(build_id, resp.records.clone());
-                        let _ = cache::save_timeline(&proj_name, &cache_entry);
-                        let _ = tx.send(CICDLoadResult::Timeline(resp.records)).await;
-                    }
-                }
+            if let Ok(records) = client.get_build_timeline(build_id).await {
+                // Save to cache
+                let cache_entry = cache::TimelineCacheEntry::new(build_id, records.clone());
+                let _ = cache.save_timeline(&proj_name, &cache_entry);
+                let _ = tx.send(CICDLoadResult::Timeline(records)).await;
             }
         });
     }
@@ -2023,9 +2360,9 @@ impl App {
     }
 
     fn start_log_loader_impl(&mut self, build_id: i32, log_id: i32, force: bool) {
-        let (org, proj, proj_name) = match self.current_project() {
-            Some(p) => (p.organization.clone(), p.project.clone(), p.name.clone()),
-            None => return,
+        let (client, proj_name) = match (self.client(), self.current_project()) {
+            (Some(c), Some(p)) => (c, p.name.clone()),
+            _ => return,
         };
 
         // Store for refresh
@@ -2034,7 +2371,8 @@ impl App {
         // Stale-while-revalidate: use cache immediately, refresh in background if stale
         let needs_fetch = if !force {
             if let Some((cached, needs_refresh)) =
-                cache::load_build_log(&proj_name, build_id, log_id)
+                self.cache
+                    .load_build_log(&proj_name, build_id, log_id, self.short_cache_ttl())
             {
                 self.build_log_lines = cached.lines;
                 self.cicd_loading = false;
@@ -2054,34 +2392,13 @@ impl App {
         self.cicd_rx = Some(rx);
         self.cicd_loading = true;
 
+        let cache = self.cache.clone();
         tokio::spawn(async move {
-            let output = tokio::process::Command::new("az")
-                .args(["devops", "invoke"])
-                .args(["--area", "build"])
-                .args(["--resource", "logs"])
-                .args([
-                    "--route-parameters",
-                    &format!("project={proj}"),
-                    &format!("buildId={build_id}"),
-                    &format!("logId={log_id}"),
-                ])
-                .args(["--org", &org])
-                .args(["--output", "json"])
-                .output()
-                .await;
-
-            if let Ok(o) = output {
-                if o.status.success() {
-                    if let Ok(resp) =
-                        serde_json::from_slice::<crate::azure::BuildLogResponse>(&o.stdout)
-                    {
-                        // Save to cache
-                        let cache_entry =
-                            cache::BuildLogCacheEntry::new(build_id, log_id, resp.value.clone());
-                        let _ = cache::save_build_log(&proj_name, &cache_entry);
-                        let _ = tx.send(CICDLoadResult::BuildLog(resp.value)).await;
-                    }
-                }
+            if let Ok(lines) = client.get_build_log(build_id, log_id).await {
+                // Save to cache
+                let cache_entry = cache::BuildLogCacheEntry::new(build_id, log_id, lines.clone());
+                let _ = cache.save_build_log(&proj_name, &cache_entry);
+                let _ = tx.send(CICDLoadResult::BuildLog(lines)).await;
             }
         });
     }
@@ -2118,10 +2435,8 @@ impl App {
 
         self.live_preview_last_poll = std::time::Instant::now();
 
-        // Get current project info
-        let (org, proj) = match self.current_project() {
-            Some(p) => (p.organization.clone(), p.project.clone()),
-            None => return,
+        let Some(client) = self.client() else {
+            return;
         };
 
         // Get or create channel
@@ -2137,40 +2452,17 @@ impl App {
         let change_id = self.live_preview_change_id;
 
         tokio::spawn(async move {
-            let mut cmd = tokio::process::Command::new("az");
-            cmd.args(["devops", "invoke"])
-                .args(["--area", "build"])
-                .args(["--resource", "timeline"])
-                .args([
-                    "--route-parameters",
-                    &format!("project={proj}"),
-                    &format!("buildId={build_id}"),
-                ]);
-
-            // Add changeId for delta polling - returns empty if no changes
-            if let Some(change_id) = change_id {
-                cmd.args(["--query-parameters", &format!("changeId={change_id}")]);
-            }
-
-            cmd.args(["--org", &org]).args(["--output", "json"]);
-
-            if let Ok(output) = cmd.output().await {
-                if output.status.success() {
-                    if let Ok(response) =
-                        serde_json::from_slice::<crate::azure::TimelineResponse>(&output.stdout)
-                    {
-                        // If no records returned and we had a changeId, nothing changed
-                        if !response.records.is_empty() || change_id.is_none() {
-                            let _ = tx
-                                .send(CICDLoadResult::TimelineDelta {
-                                    build_id,
-                                    records: response.records,
-                                    change_id: response.change_id,
-                                })
-                                .await;
-                        }
-                    }
-                }
+            // Delta polling - returns None if nothing changed since change_id
+            if let Ok(Some((records, change_id))) =
+                client.get_build_timeline_delta(build_id, change_id).await
+            {
+                let _ = tx
+                    .send(CICDLoadResult::TimelineDelta {
+                        build_id,
+                        records,
+                        change_id,
+                    })
+                    .await;
             }
         });
     }
@@ -2217,34 +2509,16 @@ impl App {
         }
         self.approvals_loading = true;
 
-        let (org, proj) = match self.current_project() {
-            Some(p) => (p.organization.clone(), p.project.clone()),
-            None => return,
+        let Some(client) = self.client() else {
+            return;
         };
 
         let (tx, rx) = mpsc::channel(10);
         self.cicd_rx = Some(rx);
 
         tokio::spawn(async move {
-            let output = tokio::process::Command::new("az")
-                .args(["devops", "invoke"])
-                .args(["--area", "release"])
-                .args(["--resource", "approvals"])
-                .args(["--route-parameters", &format!("project={proj}")])
-                .args(["--query-parameters", "statusFilter=pending"])
-                .args(["--org", &org])
-                .args(["--output", "json"])
-                .output()
-                .await;
-
-            if let Ok(o) = output {
-                if o.status.success() {
-                    if let Ok(resp) =
-                        serde_json::from_slice::<crate::azure::ApprovalsResponse>(&o.stdout)
-                    {
-                        let _ = tx.send(CICDLoadResult::PendingApprovals(resp.value)).await;
-                    }
-                }
+            if let Ok(approvals) = client.get_pending_approvals().await {
+                let _ = tx.send(CICDLoadResult::PendingApprovals(approvals)).await;
             }
         });
     }
@@ -2258,604 +2532,1026 @@ impl App {
         ));
         self.input_mode = InputMode::ReleaseTriggerDialog;
 
-        let (org, proj) = match self.current_project() {
-            Some(p) => (p.organization.clone(), p.project.clone()),
-            None => return,
+        let Some(client) = self.client() else {
+            return;
         };
 
         let (tx, rx) = mpsc::channel(10);
         self.cicd_rx = Some(rx);
 
         tokio::spawn(async move {
-            let output = tokio::process::Command::new("az")
-                .args(["devops", "invoke"])
-                .args(["--area", "release"])
-                .args(["--resource", "definitions"])
-                .args([
-                    "--route-parameters",
-                    &format!("project={proj}"),
-                    &format!("definitionId={definition_id}"),
-                ])
-                .args(["--org", &org])
-                .args(["--output", "json"])
-                .output()
-                .await;
-
-            match output {
-                Ok(o) => {
-                    if o.status.success() {
-                        match serde_json::from_slice::<crate::azure::ReleaseDefinitionDetail>(
-                            &o.stdout,
-                        ) {
-                            Ok(detail) => {
-                                let _ = tx
-                                    .send(CICDLoadResult::ReleaseDefinitionDetail(detail))
-                                    .await;
-                            }
-                            Err(e) => {
-                                // Try parsing as wrapped response (some API versions wrap in "value")
-                                #[derive(serde::Deserialize)]
-                                struct Wrapper {
-                                    environments: Vec<crate::azure::ReleaseDefinitionEnvironment>,
-                                }
-                                if let Ok(w) = serde_json::from_slice::<Wrapper>(&o.stdout) {
-                                    let detail = crate::azure::ReleaseDefinitionDetail {
-                                        id: definition_id,
-                                        name: Some(definition_name),
-                                        environments: w.environments,
-                                        artifacts: vec![],
-                                    };
-                                    let _ = tx
-                                        .send(CICDLoadResult::ReleaseDefinitionDetail(detail))
-                                        .await;
-                                } else {
-                                    eprintln!("Failed to parse release definition: {e}");
-                                    eprintln!("Response: {}", String::from_utf8_lossy(&o.stdout));
-                                }
-                            }
-                        }
-                    } else {
-                        eprintln!("API error: {}", String::from_utf8_lossy(&o.stderr));
+            match client.get_release_definition_detail(definition_id).await {
+                Ok(mut detail) => {
+                    if detail.name.is_none() {
+                        detail.name = Some(definition_name);
                     }
+                    let _ = tx
+                        .send(CICDLoadResult::ReleaseDefinitionDetail(detail))
+                        .await;
+                }
+                Err(e) => {
+                    let _ = tx.send(CICDLoadResult::Error(e.to_string())).await;
                 }
-                Err(e) => eprintln!("Command failed: {e}"),
             }
         });
     }
 
-    /// Get tasks from timeline (filtered to type=Task only, sorted by order)
-    pub fn get_timeline_tasks(&self) -> Vec<&TimelineRecord> {
-        let mut tasks: Vec<_> = self
-            .timeline_records
-            .iter()
-            .filter(|r| r.record_type.as_deref() == Some("Task"))
-            .collect();
-        tasks.sort_by_key(|r| r.order.unwrap_or(999));
-        tasks
+    // ========== Pipeline Trigger ==========
+
+    /// Open the trigger dialog for a pipeline, filled in with the settings it
+    /// was last triggered with
+    pub fn open_pipeline_trigger(&mut self, pipeline_id: i32, pipeline_name: String) {
+        let Some(project) = self.current_project().map(|p| p.name.clone()) else {
+            return;
+        };
+        let Some(client) = self.client() else {
+            return;
+        };
+        let preset = self.cache.load_trigger_preset(&project, pipeline_id);
+        let dialog = PipelineTriggerDialog::new(pipeline_id, pipeline_name, preset);
+        let git_ref = dialog.preset_branch().map(str::to_string);
+        self.pipeline_trigger_dialog = Some(dialog);
+        self.input_mode = InputMode::PipelineTriggerDialog;
+
+        let (tx, rx) = mpsc::channel(1);
+        self.pipeline_trigger_rx = Some(rx);
+        tokio::spawn(async move {
+            let result =
+                crate::pipeline_trigger::load_options(&client, pipeline_id, git_ref.as_deref())
+                    .await
+                    .map_err(|e| e.to_string());
+            let _ = tx.send((pipeline_id, result)).await;
+        });
     }
 
-    /// Trigger a new release
-    pub fn trigger_release(&mut self, definition_id: i32, description: Option<String>) {
-        let (org, proj) = match self.current_project() {
-            Some(p) => (p.organization.clone(), p.project.clone()),
-            None => {
-                self.set_error("No project configured");
+    /// Fill the trigger dialog in once its options have loaded
+    pub fn poll_pipeline_trigger(&mut self) {
+        let Some(rx) = &mut self.pipeline_trigger_rx else {
+            return;
+        };
+        let (pipeline_id, result) = match rx.try_recv() {
+            Ok(loaded) => loaded,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                self.pipeline_trigger_rx = None;
                 return;
             }
         };
+        self.pipeline_trigger_rx = None;
+        let Some(dialog) = self
+            .pipeline_trigger_dialog
+            .as_mut()
+            .filter(|d| d.pipeline_id == pipeline_id)
+        else {
+            return;
+        };
+        match result {
+            Ok(options) => dialog.load(options),
+            Err(e) => {
+                self.pipeline_trigger_dialog = None;
+                self.input_mode = InputMode::Normal;
+                self.set_error(format!("Failed to load pipeline: {e}"));
+            }
+        }
+    }
 
-        self.set_status("Creating release...");
+    /// Queue the run as filled in and keep it as the pipeline's preset
+    pub fn submit_pipeline_trigger(&mut self) {
+        let Some(dialog) = &self.pipeline_trigger_dialog else {
+            return;
+        };
+        if let Some(problem) = dialog.validate() {
+            self.set_error(problem);
+            return;
+        }
+        let (pipeline_id, request) = (dialog.pipeline_id, dialog.request());
+        if let Some(project) = self.current_project().map(|p| p.name.clone()) {
+            let _ = self
+                .cache
+                .save_trigger_preset(&project, pipeline_id, &request);
+        }
+        self.pipeline_trigger_dialog = None;
+        self.input_mode = InputMode::Normal;
+        self.queue_pipeline_run(pipeline_id, request);
+    }
+
+    /// Run a pipeline again with its preset; without one the trigger dialog
+    /// opens instead
+    pub fn trigger_pipeline_preset(&mut self, pipeline_id: i32, pipeline_name: String) {
+        let preset = self
+            .current_project()
+            .and_then(|p| self.cache.load_trigger_preset(&p.name, pipeline_id))
+            .filter(|p| !p.branch.is_empty());
+        match preset {
+            Some(request) => self.queue_pipeline_run(pipeline_id, request),
+            None => self.open_pipeline_trigger(pipeline_id, pipeline_name),
+        }
+    }
 
+    fn queue_pipeline_run(&mut self, pipeline_id: i32, request: PipelineRunRequest) {
+        let Some(client) = self.client() else {
+            self.set_error("No project configured");
+            return;
+        };
         let (tx, rx) = mpsc::channel(10);
         self.cicd_rx = Some(rx);
-
+        self.cicd_loading = true;
+        self.set_status(format!(
+            "Queueing a run on {}...",
+            crate::pipeline_trigger::ref_label(&request.branch)
+        ));
         tokio::spawn(async move {
-            let mut cmd = tokio::process::Command::new("az");
-            cmd.args(["pipelines", "release", "create"])
-                .args(["--definition-id", &definition_id.to_string()])
-                .args(["--org", &org])
-                .args(["--project", &proj])
-                .args(["--output", "json"]);
+            let result = match client.trigger_pipeline(pipeline_id, &request).await {
+                Ok(run) => CICDLoadResult::PipelineRunQueued { pipeline_id, run },
+                Err(e) => CICDLoadResult::Error(e.to_string()),
+            };
+            let _ = tx.send(result).await;
+        });
+    }
 
-            if let Some(desc) = &description {
-                cmd.args(["--description", desc]);
-            }
+    // ========== Log Search ==========
 
-            let output = cmd.output().await;
+    /// Lines of the log the CI/CD preview shows, if it shows one
+    pub fn shown_log(&self) -> Option<&[String]> {
+        let lines = if self.release_drill_down == ReleaseDrillDown::Tasks {
+            &self.release_task_logs
+        } else if self.release_drill_down == ReleaseDrillDown::None
+            && self.pipeline_drill_down == PipelineDrillDown::Tasks
+        {
+            &self.build_log_lines
+        } else {
+            return None;
+        };
+        Some(lines.as_slice()).filter(|l| !l.is_empty())
+    }
 
-            match output {
-                Ok(o) => {
-                    if o.status.success() {
-                        match serde_json::from_slice::<crate::azure::Release>(&o.stdout) {
-                            Ok(release) => {
-                                let _ = tx.send(CICDLoadResult::ReleaseCreated(release)).await;
-                            }
-                            Err(e) => {
-                                // Send error as status
-                                let _ = tx
-                                    .send(CICDLoadResult::Error(format!("Parse error: {e}")))
-                                    .await;
-                            }
-                        }
-                    } else {
-                        let stderr = String::from_utf8_lossy(&o.stderr);
-                        let _ = tx
-                            .send(CICDLoadResult::Error(format!(
-                                "Release failed: {}",
-                                stderr.lines().next().unwrap_or(&stderr)
-                            )))
-                            .await;
-                    }
-                }
-                Err(e) => {
-                    let _ = tx
-                        .send(CICDLoadResult::Error(format!("Command failed: {e}")))
-                        .await;
-                }
-            }
-        });
+    /// Open the search prompt of the shown log
+    pub fn start_log_search(&mut self) {
+        if self.shown_log().is_none() {
+            self.set_status("Open a task's log to search it");
+            return;
+        }
+        self.log_view.start_search();
+        self.input_mode = InputMode::LogSearch;
     }
 
-    /// Approve a release stage by finding its pending approval
-    pub fn approve_stage(&mut self, env_id: i32, stage_name: &str) {
-        let (org, proj) = match self.current_project() {
-            Some(p) => (p.organization.clone(), p.project.clone()),
-            None => {
-                self.set_error("No project configured");
-                return;
-            }
+    /// Keep the typed pattern and go to its first match from the top of the view
+    pub fn finish_log_search(&mut self) {
+        if !self.log_view.finish_search() {
+            return;
+        }
+        self.input_mode = InputMode::Normal;
+        if self.log_view.has_pattern() {
+            self.log_jump(LogTarget::Match, true);
+        }
+    }
+
+    /// Scroll to the next (or previous) match, error or warning of the shown log
+    pub fn log_jump(&mut self, target: LogTarget, forward: bool) {
+        if target == LogTarget::Match && !self.log_view.has_pattern() {
+            self.set_status("No search: press / to search the log");
+            return;
+        }
+        let scroll = self.log_scroll;
+        let lines = if self.release_drill_down == ReleaseDrillDown::Tasks {
+            &self.release_task_logs
+        } else {
+            &self.build_log_lines
         };
+        if self.shown_log().is_none() {
+            return;
+        }
+        match self.log_view.jump(lines, scroll, target, forward) {
+            Some(scroll) => self.log_scroll = scroll,
+            None => self.set_status(match target {
+                LogTarget::Match => format!("Pattern not found: {}", self.log_view.query),
+                LogTarget::Severity(Severity::Error) => "No errors in this log".to_string(),
+                LogTarget::Severity(Severity::Warning) => "No warnings in this log".to_string(),
+            }),
+        }
+    }
 
-        self.set_status(format!("Looking for approval for {stage_name}..."));
+    /// Show only the lines matching the search, or all lines again
+    pub fn toggle_log_filter(&mut self) {
+        if self.shown_log().is_none() {
+            return;
+        }
+        if !self.log_view.has_pattern() {
+            self.set_status("No search: press / to search the log");
+            return;
+        }
+        let lines = if self.release_drill_down == ReleaseDrillDown::Tasks {
+            &self.release_task_logs
+        } else {
+            &self.build_log_lines
+        };
+        self.log_scroll = self.log_view.toggle_filter(lines, self.log_scroll);
+    }
 
-        let (tx, rx) = mpsc::channel(10);
-        self.cicd_rx = Some(rx);
-        let stage_name = stage_name.to_string();
+    // ========== Failure Summary ==========
 
-        tokio::spawn(async move {
-            // Step 1: Get pending approvals
-            let output = tokio::process::Command::new("az")
-                .args(["devops", "invoke"])
-                .args(["--area", "release"])
-                .args(["--resource", "approvals"])
-                .args(["--route-parameters", &format!("project={proj}")])
-                .args(["--query-parameters", "statusFilter=pending"])
-                .args(["--org", &org])
-                .args(["--output", "json"])
-                .output()
-                .await;
+    /// Count the errors and warnings of the listed runs that finished
+    fn load_run_issue_counts(&mut self) {
+        use futures::stream::StreamExt;
 
-            let approvals_response = match output {
-                Ok(o) if o.status.success() => {
-                    match serde_json::from_slice::<crate::azure::ApprovalsResponse>(&o.stdout) {
-                        Ok(resp) => resp,
-                        Err(e) => {
-                            let _ = tx
-                                .send(CICDLoadResult::Error(format!("Parse error: {e}")))
-                                .await;
-                            return;
-                        }
-                    }
-                }
-                Ok(o) => {
-                    let _ = tx
-                        .send(CICDLoadResult::Error(format!(
-                            "API error: {}",
-                            String::from_utf8_lossy(&o.stderr)
-                        )))
-                        .await;
-                    return;
-                }
-                Err(e) => {
-                    let _ = tx
-                        .send(CICDLoadResult::Error(format!("Command failed: {e}")))
-                        .await;
-                    return;
-                }
-            };
+        let (client, proj_name) = match (self.client(), self.current_project()) {
+            (Some(c), Some(p)) => (c, p.name.clone()),
+            _ => return,
+        };
+        let build_ids: Vec<i32> = self
+            .pipeline_runs
+            .iter()
+            .filter(|r| r.status.as_deref() == Some("completed"))
+            .filter(|r| !self.run_issue_counts.contains_key(&r.id))
+            .map(|r| r.id)
+            .collect();
+        if build_ids.is_empty() {
+            return;
+        }
 
-            // Step 2: Find approval for this environment
-            let approval = approvals_response
-                .value
-                .iter()
-                .find(|a| a.release_environment.as_ref().map(|e| e.id) == Some(env_id));
+        let ttl = self.short_cache_ttl();
+        let (tx, rx) = mpsc::channel(build_ids.len());
+        self.run_issue_counts_rx = Some(rx);
+        let cache = self.cache.clone();
+        tokio::spawn(async move {
+            let mut results = std::pin::pin!(futures::stream::iter(build_ids)
+                .map(|build_id| {
+                    let client = client.clone();
+                    let cache = cache.clone();
+                    let proj_name = proj_name.clone();
+                    async move {
+                        let timeline =
+                            fetch_timeline(&client, &cache, &proj_name, build_id, ttl).await;
+                        (build_id, timeline)
+                    }
+                })
+                .buffer_unordered(RUN_TIMELINE_CONCURRENCY));
 
-            let approval_id = match approval {
-                Some(a) => a.id,
-                None => {
-                    let _ = tx
-                        .send(CICDLoadResult::Error(format!(
-                            "No pending approval for {stage_name}"
-                        )))
-                        .await;
-                    return;
+            while let Some((build_id, timeline)) = results.next().await {
+                if let Ok(records) = timeline {
+                    let counts = crate::failure_summary::counts(&records);
+                    if tx.send((build_id, counts)).await.is_err() {
+                        break;
+                    }
                 }
-            };
-
-            // Step 3: Approve it
-            let body = serde_json::json!([{
-                "id": approval_id,
-                "status": "approved",
-                "comments": "Approved via lazyops"
-            }]);
-            let body_str = body.to_string();
-
-            let temp_path = std::env::temp_dir().join(format!("approval_{approval_id}.json"));
-            if let Err(e) = tokio::fs::write(&temp_path, &body_str).await {
-                let _ = tx
-                    .send(CICDLoadResult::Error(format!(
-                        "Failed to write temp file: {e}"
-                    )))
-                    .await;
-                return;
             }
+        });
+    }
 
-            let approve_output = tokio::process::Command::new("az")
-                .args(["devops", "invoke"])
-                .args(["--area", "release"])
-                .args(["--resource", "approvals"])
-                .args(["--route-parameters", &format!("project={proj}")])
-                .args(["--http-method", "PATCH"])
-                .args(["--in-file", temp_path.to_str().unwrap()])
-                .args(["--org", &org])
-                .args(["--output", "json"])
-                .output()
-                .await;
-
-            // Clean up temp file
-            let _ = tokio::fs::remove_file(&temp_path).await;
-
-            match approve_output {
-                Ok(o) if o.status.success() => {
-                    // Get release_id from the approval response if possible
-                    let release_id = approval
-                        .and_then(|a| a.release.as_ref().map(|r| r.id))
-                        .unwrap_or(0);
-                    let _ = tx
-                        .send(CICDLoadResult::ApprovalUpdated {
-                            approval_id,
-                            release_id,
-                            status: "approved".to_string(),
-                        })
-                        .await;
-                }
-                Ok(o) => {
-                    let stderr = String::from_utf8_lossy(&o.stderr);
-                    let _ = tx
-                        .send(CICDLoadResult::Error(format!(
-                            "Approval failed: {}",
-                            stderr.lines().next().unwrap_or(&stderr)
-                        )))
-                        .await;
+    /// Pick up the error / warning counts of the listed runs
+    pub fn poll_run_issue_counts(&mut self) {
+        let Some(rx) = &mut self.run_issue_counts_rx else {
+            return;
+        };
+        loop {
+            match rx.try_recv() {
+                Ok((build_id, counts)) => {
+                    self.run_issue_counts.insert(build_id, counts);
                 }
-                Err(e) => {
-                    let _ = tx
-                        .send(CICDLoadResult::Error(format!("Command failed: {e}")))
-                        .await;
+                Err(mpsc::error::TryRecvError::Empty) => return,
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    self.run_issue_counts_rx = None;
+                    return;
                 }
             }
-        });
+        }
     }
 
-    /// Approve all pending stages for the current release
-    pub fn approve_all_pending_stages(&mut self) {
-        // Collect all stages with pending approvals
-        let pending_env_ids: Vec<i32> = self
-            .release_stages
+    /// ID and number of the drilled-into or selected run
+    fn focused_run(&self) -> Option<(i32, String)> {
+        let build_id = match self.pipeline_drill_down {
+            PipelineDrillDown::Tasks => self.selected_run_id,
+            PipelineDrillDown::Runs => self
+                .pipeline_runs
+                .get(self.selected_pipeline_run_idx)
+                .map(|r| r.id),
+            PipelineDrillDown::None => None,
+        }?;
+        let build_number = self
+            .pipeline_runs
             .iter()
-            .filter(|stage| {
-                stage
-                    .pre_deploy_approvals
-                    .iter()
-                    .any(|a| a.status.as_deref() == Some("pending"))
-            })
-            .map(|stage| stage.id)
-            .collect();
+            .find(|r| r.id == build_id)
+            .and_then(|r| r.build_number.clone())
+            .unwrap_or_else(|| build_id.to_string());
+        Some((build_id, build_number))
+    }
 
-        if pending_env_ids.is_empty() {
-            self.set_status("No pending approvals to approve");
+    /// Open the "Why did it fail?" panel of the drilled-into or selected run
+    pub fn open_failure_summary(&mut self) {
+        let Some((build_id, build_number)) = self.focused_run() else {
+            self.set_status("Select a run to see why it failed");
+            return;
+        };
+
+        let mut summary = FailureSummary::new(build_id, build_number);
+        self.input_mode = InputMode::FailureSummary;
+        // The drilled-into run's timeline is already there
+        if self.pipeline_drill_down == PipelineDrillDown::Tasks
+            && self.selected_run_id == Some(build_id)
+            && !self.timeline_records.is_empty()
+        {
+            summary.load(self.timeline_records.clone());
+            self.failure_summary = Some(summary);
             return;
         }
+        self.failure_summary = Some(summary);
 
-        let (org, proj) = match self.current_project() {
-            Some(p) => (p.organization.clone(), p.project.clone()),
-            None => {
-                self.set_error("No project configured");
+        let (client, proj_name) = match (self.client(), self.current_project()) {
+            (Some(c), Some(p)) => (c, p.name.clone()),
+            _ => return,
+        };
+        let ttl = self.short_cache_ttl();
+        let (tx, rx) = mpsc::channel(1);
+        self.failure_summary_rx = Some(rx);
+        let cache = self.cache.clone();
+        tokio::spawn(async move {
+            let timeline = fetch_timeline(&client, &cache, &proj_name, build_id, ttl)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send((build_id, timeline)).await;
+        });
+    }
+
+    /// Pick up the timeline of the failure summary
+    pub fn poll_failure_summary(&mut self) {
+        let Some(rx) = &mut self.failure_summary_rx else {
+            return;
+        };
+        let (build_id, timeline) = match rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                self.failure_summary_rx = None;
                 return;
             }
         };
+        self.failure_summary_rx = None;
+        let Some(summary) = self
+            .failure_summary
+            .as_mut()
+            .filter(|s| s.build_id == build_id)
+        else {
+            return;
+        };
+        match timeline {
+            Ok(records) => {
+                let counts = crate::failure_summary::counts(&records);
+                summary.load(records);
+                self.run_issue_counts.insert(build_id, counts);
+            }
+            Err(e) => {
+                self.failure_summary = None;
+                if self.input_mode == InputMode::FailureSummary {
+                    self.input_mode = InputMode::Normal;
+                }
+                self.set_error(format!("Failed to load timeline: {e}"));
+            }
+        }
+    }
 
-        // Get release_id for refresh after approval
-        let release_id = self
-            .release_list
-            .get(self.selected_release_item_idx)
-            .map(|r| r.id)
-            .unwrap_or(0);
-
-        let count = pending_env_ids.len();
-        self.set_status(format!("Approving {count} stage(s)..."));
-
-        let (tx, rx) = mpsc::channel(10);
-        self.cicd_rx = Some(rx);
+    pub fn close_failure_summary(&mut self) {
+        self.failure_summary = None;
+        self.failure_summary_rx = None;
+        self.input_mode = InputMode::Normal;
+    }
 
-        tokio::spawn(async move {
-            // Step 1: Get all pending approvals
-            let output = tokio::process::Command::new("az")
-                .args(["devops", "invoke"])
-                .args(["--area", "release"])
-                .args(["--resource", "approvals"])
-                .args(["--route-parameters", &format!("project={proj}")])
-                .args(["--query-parameters", "statusFilter=pending"])
-                .args(["--org", &org])
-                .args(["--output", "json"])
-                .output()
-                .await;
+    /// Show the selected issue of the failure summary: its run's tasks with
+    /// the issue's task selected and its log scrolled to the issue
+    pub fn open_failure_issue(&mut self) {
+        let Some(summary) = self.failure_summary.take() else {
+            return;
+        };
+        self.failure_summary_rx = None;
+        self.input_mode = InputMode::Normal;
+        let Some(issue) = summary.selected_issue().cloned() else {
+            return;
+        };
 
-            let approvals_response = match output {
-                Ok(o) if o.status.success() => {
-                    match serde_json::from_slice::<crate::azure::ApprovalsResponse>(&o.stdout) {
-                        Ok(resp) => resp,
-                        Err(e) => {
-                            let _ = tx
-                                .send(CICDLoadResult::Error(format!("Parse error: {e}")))
-                                .await;
-                            return;
-                        }
-                    }
-                }
-                Ok(o) => {
-                    let _ = tx
-                        .send(CICDLoadResult::Error(format!(
-                            "API error: {}",
-                            String::from_utf8_lossy(&o.stderr)
-                        )))
-                        .await;
-                    return;
-                }
-                Err(e) => {
-                    let _ = tx
-                        .send(CICDLoadResult::Error(format!("Command failed: {e}")))
-                        .await;
-                    return;
-                }
-            };
+        let build_id = summary.build_id;
+        if let Some(idx) = self.pipeline_runs.iter().position(|r| r.id == build_id) {
+            self.selected_pipeline_run_idx = idx;
+        }
+        if self.selected_run_id != Some(build_id) {
+            self.stop_live_preview();
+        }
+        self.selected_run_id = Some(build_id);
+        self.pipeline_drill_down = PipelineDrillDown::Tasks;
+        self.timeline_records = summary.records;
+        self.selected_task_idx = self
+            .get_timeline_tasks()
+            .iter()
+            .position(|t| t.id == issue.record_id)
+            .unwrap_or(0);
+        self.build_log_lines.clear();
+        self.log_scroll = 0;
+        self.log_view.reset();
 
-            // Step 2: Find approvals for our environments
-            let approvals_to_approve: Vec<_> = approvals_response
-                .value
-                .iter()
-                .filter(|a| {
-                    a.release_environment
-                        .as_ref()
-                        .map(|e| pending_env_ids.contains(&e.id))
-                        .unwrap_or(false)
-                })
-                .collect();
+        let Some(log_id) = issue.log_id else {
+            self.set_status(format!("{} has no log", issue.task));
+            return;
+        };
+        self.cicd_focus = CICDFocus::Preview;
+        self.pending_log_issue = Some(issue);
+        self.start_log_loader(build_id, log_id);
+        // A cached log is there already; a stale one is landed on again
+        // once reloaded
+        self.land_on_log_issue();
+        if !self.cicd_loading {
+            self.pending_log_issue = None;
+        }
+    }
 
-            if approvals_to_approve.is_empty() {
-                let _ = tx
-                    .send(CICDLoadResult::Error(
-                        "No matching approvals found".to_string(),
-                    ))
-                    .await;
-                return;
+    /// Scroll the build log to the issue picked in the failure summary
+    fn land_on_log_issue(&mut self) {
+        let Some(issue) = &self.pending_log_issue else {
+            return;
+        };
+        // Another task's log was opened meanwhile
+        if self.build_log_lines.is_empty() || issue.log_id != self.current_log_id {
+            return;
+        }
+        match issue.find_in(&self.build_log_lines) {
+            Some(line) => {
+                self.log_scroll = self.log_view.land(&self.build_log_lines, line);
             }
+            None => self.set_status("Issue not found in the log"),
+        }
+    }
 
-            // Step 3: Approve all at once
-            let body: Vec<_> = approvals_to_approve
-                .iter()
-                .map(|a| {
-                    serde_json::json!({
-                        "id": a.id,
-                        "status": "approved",
-                        "comments": "Approved via lazyops"
-                    })
-                })
-                .collect();
-            let body_str = serde_json::Value::Array(body).to_string();
+    // ========== Run Tests ==========
 
-            let temp_path = std::env::temp_dir().join("approval_all.json");
-            if let Err(e) = tokio::fs::write(&temp_path, &body_str).await {
-                let _ = tx
-                    .send(CICDLoadResult::Error(format!(
-                        "Failed to write temp file: {e}"
-                    )))
-                    .await;
-                return;
-            }
+    /// Run whose Tests tab the preview shows
+    pub fn tests_tab_run(&self) -> Option<&PipelineRun> {
+        if self.pipeline_drill_down != PipelineDrillDown::Runs
+            || self.release_drill_down != ReleaseDrillDown::None
+            || self.run_preview_tab != RunPreviewTab::Tests
+        {
+            return None;
+        }
+        self.pipeline_runs.get(self.selected_pipeline_run_idx)
+    }
 
-            let approve_output = tokio::process::Command::new("az")
-                .args(["devops", "invoke"])
-                .args(["--area", "release"])
-                .args(["--resource", "approvals"])
-                .args(["--route-parameters", &format!("project={proj}")])
-                .args(["--http-method", "PATCH"])
-                .args(["--in-file", temp_path.to_str().unwrap()])
-                .args(["--org", &org])
-                .args(["--output", "json"])
-                .output()
-                .await;
+    /// Switch the run preview between its details and its tests
+    pub fn toggle_run_preview_tab(&mut self) {
+        if self.pipeline_drill_down == PipelineDrillDown::Runs
+            && self.release_drill_down == ReleaseDrillDown::None
+        {
+            self.run_preview_tab = self.run_preview_tab.toggle();
+            self.cicd_preview_scroll = 0;
+        }
+    }
+
+    /// Finished runs listed before `build_id`, newest first
+    fn earlier_runs(&self, build_id: i32) -> impl Iterator<Item = &PipelineRun> {
+        self.pipeline_runs
+            .iter()
+            .skip_while(move |r| r.id != build_id)
+            .skip(1)
+            .filter(|r| r.status.as_deref() == Some("completed"))
+            .take(FLAKY_HISTORY_RUNS)
+    }
 
-            // Clean up temp file
-            let _ = tokio::fs::remove_file(&temp_path).await;
+    /// Loaded test results of the runs before `build_id`, for flaky tests
+    pub fn earlier_build_tests(&self, build_id: i32) -> Vec<&BuildTests> {
+        self.earlier_runs(build_id)
+            .filter_map(|r| self.build_tests.get(&r.id)?.as_ref().ok())
+            .collect()
+    }
 
-            match approve_output {
-                Ok(o) if o.status.success() => {
-                    let _ = tx
-                        .send(CICDLoadResult::ApprovalUpdated {
-                            approval_id: 0, // Multiple approvals
-                            release_id,
-                            status: format!("approved {} stage(s)", approvals_to_approve.len()),
-                        })
-                        .await;
-                }
-                Ok(o) => {
-                    let stderr = String::from_utf8_lossy(&o.stderr);
-                    let _ = tx
-                        .send(CICDLoadResult::Error(format!(
-                            "Approval failed: {}",
-                            stderr.lines().next().unwrap_or(&stderr)
-                        )))
-                        .await;
+    /// Pick up loaded test results, and load those of the run the Tests tab
+    /// shows (and of the runs before it) once nothing else is loading
+    pub fn poll_build_tests(&mut self) {
+        if let Some(rx) = &mut self.build_tests_rx {
+            loop {
+                match rx.try_recv() {
+                    Ok((build_id, tests)) => {
+                        self.build_tests.insert(build_id, tests);
+                    }
+                    Err(mpsc::error::TryRecvError::Empty) => return,
+                    Err(mpsc::error::TryRecvError::Disconnected) => break,
                 }
-                Err(e) => {
-                    let _ = tx
-                        .send(CICDLoadResult::Error(format!("Command failed: {e}")))
-                        .await;
+            }
+            self.build_tests_rx = None;
+        }
+
+        // Results of a running build are not final yet
+        let Some(run) = self
+            .tests_tab_run()
+            .filter(|r| r.status.as_deref() == Some("completed"))
+        else {
+            return;
+        };
+        let build_ids: Vec<i32> = std::iter::once(run.id)
+            .chain(self.earlier_runs(run.id).map(|r| r.id))
+            .filter(|id| !self.build_tests.contains_key(id))
+            .collect();
+        if !build_ids.is_empty() {
+            self.load_build_tests(build_ids);
+        }
+    }
+
+    fn load_build_tests(&mut self, build_ids: Vec<i32>) {
+        use futures::stream::StreamExt;
+
+        let Some(client) = self.client() else {
+            return;
+        };
+        let (tx, rx) = mpsc::channel(build_ids.len());
+        self.build_tests_rx = Some(rx);
+        tokio::spawn(async move {
+            let mut results = std::pin::pin!(futures::stream::iter(build_ids)
+                .map(|build_id| {
+                    let client = client.clone();
+                    async move {
+                        let tests = crate::test_results::load(&client, build_id).await;
+                        (build_id, tests.map_err(|e| e.to_string()))
+                    }
+                })
+                .buffer_unordered(BUILD_TESTS_CONCURRENCY));
+
+            while let Some(result) = results.next().await {
+                if tx.send(result).await.is_err() {
+                    break;
                 }
             }
         });
     }
 
-    pub fn set_status(&mut self, msg: impl Into<String>) {
-        self.status_message = Some(msg.into());
-        self.status_is_error = false;
-        self.status_set_at = Some(std::time::Instant::now());
+    // ========== Run Artifacts ==========
+
+    /// Open the artifacts panel of the drilled-into or selected run
+    pub fn open_artifacts(&mut self) {
+        let Some((build_id, build_number)) = self.focused_run() else {
+            self.set_status("Select a run to browse its artifacts");
+            return;
+        };
+        let Some(client) = self.client() else {
+            return;
+        };
+        self.artifact_browser = Some(ArtifactBrowser::new(build_id, build_number));
+        self.input_mode = InputMode::Artifacts;
+
+        let (tx, rx) = mpsc::channel(1);
+        self.artifact_browser_rx = Some(rx);
+        tokio::spawn(async move {
+            let artifacts = crate::artifacts::load(&client, build_id)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send((build_id, artifacts)).await;
+        });
     }
 
-    pub fn set_error(&mut self, msg: impl Into<String>) {
-        self.status_message = Some(msg.into());
-        self.status_is_error = true;
-        self.status_set_at = Some(std::time::Instant::now());
+    /// Pick up the artifacts of the artifacts panel
+    pub fn poll_artifacts(&mut self) {
+        let Some(rx) = &mut self.artifact_browser_rx else {
+            return;
+        };
+        let (build_id, artifacts) = match rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                self.artifact_browser_rx = None;
+                return;
+            }
+        };
+        self.artifact_browser_rx = None;
+        let Some(browser) = self
+            .artifact_browser
+            .as_mut()
+            .filter(|b| b.build_id == build_id)
+        else {
+            return;
+        };
+        match artifacts {
+            Ok(artifacts) => browser.load(artifacts),
+            Err(e) => {
+                self.close_artifacts();
+                self.set_error(format!("Failed to load artifacts: {e}"));
+            }
+        }
     }
 
-    pub fn clear_status(&mut self) {
-        self.status_message = None;
-        self.status_is_error = false;
-        self.status_set_at = None;
+    /// Close the artifacts panel; a download keeps going
+    pub fn close_artifacts(&mut self) {
+        self.artifact_browser = None;
+        self.artifact_browser_rx = None;
+        if self.input_mode == InputMode::Artifacts {
+            self.input_mode = InputMode::Normal;
+        }
     }
 
-    /// Clear status message if it's older than 5 seconds
-    pub fn clear_expired_status(&mut self) {
-        if let Some(set_at) = self.status_set_at {
-            if set_at.elapsed() > std::time::Duration::from_secs(5) {
-                self.clear_status();
+    /// Open the selected artifact or folder, or ask where to download it
+    pub fn artifacts_enter(&mut self) {
+        if let Some(browser) = &mut self.artifact_browser {
+            if !browser.loading && !browser.enter() {
+                self.ask_download_destination();
             }
         }
     }
 
-    /// Execute a confirmed action (cancel/retrigger)
-    pub fn execute_confirmed_action(&mut self, action_type: ConfirmActionType) {
-        let client_info = self
-            .current_project()
-            .map(|p| (p.organization.clone(), p.project.clone()));
+    /// Ask where the selected artifact, file or folder goes
+    pub fn ask_download_destination(&mut self) {
+        if self.artifact_download.is_some() {
+            self.set_status("Wait for the current download to finish");
+            return;
+        }
+        let dir = self
+            .download_dir
+            .clone()
+            .unwrap_or_else(crate::artifacts::default_download_dir);
+        if let Some(browser) = &mut self.artifact_browser {
+            if browser.selected_download().is_some() {
+                browser.destination = Some(dir);
+            }
+        }
+    }
 
-        let Some((org, project)) = client_info else {
-            self.set_error("No project configured");
+    /// Download the selected artifact, file or folder to the directory typed
+    /// in; it is written next to its destination and moved there once complete
+    pub fn start_artifact_download(&mut self) {
+        let Some(browser) = &mut self.artifact_browser else {
+            return;
+        };
+        let Some(dir) = browser.destination.take() else {
+            return;
+        };
+        let Some(request) = browser.selected_download() else {
+            return;
+        };
+        let Some(client) = self.client() else {
             return;
         };
+        let name = request.file_name();
+        let path = crate::artifacts::destination_path(&dir, &name);
+        let download = ArtifactDownload::new(name, path.clone(), request.size());
+        let part = download.part_path();
+        self.download_dir = Some(dir.trim().to_string());
+        self.set_status(format!("Downloading {}", download.name));
+        self.artifact_download = Some(download);
+
+        let (tx, rx) = mpsc::channel(1);
+        self.artifact_download_rx = Some(rx);
+        tokio::spawn(async move {
+            let result = async {
+                if let Some(dir) = path.parent() {
+                    tokio::fs::create_dir_all(dir).await?;
+                }
+                client
+                    .download_artifact(&request.artifact, request.item.as_ref(), &part)
+                    .await?;
+                tokio::fs::rename(&part, &path).await?;
+                anyhow::Ok(())
+            }
+            .await;
+            if result.is_err() {
+                let _ = tokio::fs::remove_file(&part).await;
+            }
+            let _ = tx.send(result.map_err(|e| e.to_string())).await;
+        });
+    }
 
-        // Get or create the sender channel
-        let tx = if let Some(tx) = &self.cicd_tx {
-            tx.clone()
-        } else {
-            let (tx, rx) = tokio::sync::mpsc::channel(10);
-            self.cicd_rx = Some(rx);
-            self.cicd_tx = Some(tx.clone());
-            tx
+    /// Track the bytes written by the download and report how it ended
+    pub fn poll_artifact_download(&mut self) {
+        let Some(download) = &mut self.artifact_download else {
+            return;
+        };
+        let finished = match &mut self.artifact_download_rx {
+            Some(rx) => match rx.try_recv() {
+                Ok(result) => Some(result),
+                Err(mpsc::error::TryRecvError::Empty) => None,
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    Some(Err("Download stopped".to_string()))
+                }
+            },
+            None => Some(Err("Download stopped".to_string())),
+        };
+        let Some(result) = finished else {
+            if let Ok(metadata) = std::fs::metadata(download.part_path()) {
+                if metadata.len() != download.done {
+                    download.done = metadata.len();
+                    let label = format!("Downloading {}", download.label());
+                    self.set_status(label);
+                }
+            }
+            return;
         };
 
-        self.cicd_loading = true;
+        let path = download.path.display().to_string();
+        self.artifact_download = None;
+        self.artifact_download_rx = None;
+        match result {
+            Ok(()) => self.set_status(format!("Downloaded {path}")),
+            Err(e) => self.set_error(format!("Download failed: {e}")),
+        }
+    }
 
-        match action_type {
-            ConfirmActionType::CancelPipelineRun {
-                run_id,
-                build_number,
-            } => {
-                self.set_status(format!("Canceling build #{build_number}..."));
-                tokio::spawn(async move {
-                    let output = tokio::process::Command::new("az")
-                        .args(["pipelines", "build", "update"])
-                        .args(["--id", &run_id.to_string()])
-                        .args(["--status", "cancelling"])
-                        .args(["--org", &org])
-                        .args(["--project", &project])
-                        .args(["--output", "json"])
-                        .output()
-                        .await;
+    /// Get tasks from timeline (filtered to type=Task only, sorted by order)
+    pub fn get_timeline_tasks(&self) -> Vec<&TimelineRecord> {
+        let mut tasks: Vec<_> = self
+            .timeline_records
+            .iter()
+            .filter(|r| r.record_type.as_deref() == Some("Task"))
+            .collect();
+        tasks.sort_by_key(|r| r.order.unwrap_or(999));
+        tasks
+    }
 
-                    let result = match output {
-                        Ok(o) if o.status.success() => CICDLoadResult::PipelineRunCanceled(run_id),
-                        Ok(o) => {
-                            CICDLoadResult::Error(String::from_utf8_lossy(&o.stderr).to_string())
-                        }
-                        Err(e) => CICDLoadResult::Error(e.to_string()),
-                    };
-                    let _ = tx.send(result).await;
-                });
+    /// Trigger a new release
+    pub fn trigger_release(&mut self, definition_id: i32, description: Option<String>) {
+        let client = match self.client() {
+            Some(c) => c,
+            None => {
+                self.set_error("No project configured");
+                return;
             }
+        };
 
-            ConfirmActionType::RetriggerPipelineRun {
-                pipeline_id,
-                branch,
-                build_number,
-            } => {
-                self.set_status(format!("Retriggering #{build_number}..."));
-                tokio::spawn(async move {
-                    let output = tokio::process::Command::new("az")
-                        .args(["pipelines", "run"])
-                        .args(["--id", &pipeline_id.to_string()])
-                        .args(["--branch", &branch])
-                        .args(["--org", &org])
-                        .args(["--project", &project])
-                        .args(["--output", "json"])
-                        .output()
-                        .await;
+        self.set_status("Creating release...");
 
-                    let result = match output {
-                        Ok(o) if o.status.success() => match serde_json::from_slice(&o.stdout) {
-                            Ok(run) => CICDLoadResult::PipelineRunRetriggered(run),
-                            Err(e) => CICDLoadResult::Error(format!("Parse error: {e}")),
-                        },
-                        Ok(o) => {
-                            CICDLoadResult::Error(String::from_utf8_lossy(&o.stderr).to_string())
-                        }
-                        Err(e) => CICDLoadResult::Error(e.to_string()),
-                    };
-                    let _ = tx.send(result).await;
-                });
+        let (tx, rx) = mpsc::channel(10);
+        self.cicd_rx = Some(rx);
+
+        tokio::spawn(async move {
+            match client
+                .create_release(definition_id, description.as_deref())
+                .await
+            {
+                Ok(release) => {
+                    let _ = tx.send(CICDLoadResult::ReleaseCreated(release)).await;
+                }
+                Err(e) => {
+                    let _ = tx.send(CICDLoadResult::Error(e.to_string())).await;
+                }
             }
+        });
+    }
 
-            ConfirmActionType::CancelRelease {
-                release_id,
-                release_name,
-            } => {
-                self.set_status(format!("Abandoning {release_name}..."));
-                tokio::spawn(async move {
-                    let body = serde_json::json!({"status": "abandoned"});
-                    let temp_path =
-                        std::env::temp_dir().join(format!("cancel_release_{release_id}.json"));
+    /// Approve a release stage by finding its pending approval
+    pub fn approve_stage(&mut self, env_id: i32, stage_name: &str) {
+        let Some(client) = self.client() else {
+            self.set_error("No project configured");
+            return;
+        };
 
-                    if let Err(e) = tokio::fs::write(&temp_path, body.to_string()).await {
-                        let _ = tx.send(CICDLoadResult::Error(e.to_string())).await;
-                        return;
-                    }
+        self.set_status(format!("Looking for approval for {stage_name}..."));
 
-                    let output = tokio::process::Command::new("az")
-                        .args(["devops", "invoke"])
-                        .args(["--area", "release"])
-                        .args(["--resource", "releases"])
-                        .args([
-                            "--route-parameters",
-                            &format!("project={project}"),
-                            &format!("releaseId={release_id}"),
-                        ])
-                        .args(["--api-version", "7.1"])
-                        .args(["--http-method", "PATCH"])
-                        .args(["--in-file", temp_path.to_str().unwrap()])
-                        .args(["--org", &org])
-                        .args(["--output", "json"])
-                        .output()
-                        .await;
+        let (tx, rx) = mpsc::channel(10);
+        self.cicd_rx = Some(rx);
+        let stage_name = stage_name.to_string();
 
-                    let _ = tokio::fs::remove_file(&temp_path).await;
+        tokio::spawn(async move {
+            // Step 1: Get pending approvals
+            let approvals = match client.get_pending_approvals().await {
+                Ok(approvals) => approvals,
+                Err(e) => {
+                    let _ = tx.send(CICDLoadResult::Error(e.to_string())).await;
+                    return;
+                }
+            };
 
-                    let result = match output {
-                        Ok(o) if o.status.success() => CICDLoadResult::ReleaseCanceled(release_id),
-                        Ok(o) => {
-                            CICDLoadResult::Error(String::from_utf8_lossy(&o.stderr).to_string())
-                        }
-                        Err(e) => CICDLoadResult::Error(e.to_string()),
+            // Step 2: Find approval for this environment
+            let approval = approvals
+                .iter()
+                .find(|a| a.release_environment.as_ref().map(|e| e.id) == Some(env_id));
+
+            let approval_id = match approval {
+                Some(a) => a.id,
+                None => {
+                    let _ = tx
+                        .send(CICDLoadResult::Error(format!(
+                            "No pending approval for {stage_name}"
+                        )))
+                        .await;
+                    return;
+                }
+            };
+
+            // Step 3: Approve it
+            match client
+                .update_approvals(&[approval_id], "approved", "Approved via lazyops")
+                .await
+            {
+                Ok(()) => {
+                    // Get release_id from the approval response if possible
+                    let release_id = approval
+                        .and_then(|a| a.release.as_ref().map(|r| r.id))
+                        .unwrap_or(0);
+                    let _ = tx
+                        .send(CICDLoadResult::ApprovalUpdated {
+                            approval_id,
+                            release_id,
+                            status: "approved".to_string(),
+                        })
+                        .await;
+                }
+                Err(e) => {
+                    let _ = tx.send(CICDLoadResult::Error(e.to_string())).await;
+                }
+            }
+        });
+    }
+
+    /// Approve all pending stages for the current release
+    pub fn approve_all_pending_stages(&mut self) {
+        // Collect all stages with pending approvals
+        let pending_env_ids: Vec<i32> = self
+            .release_stages
+            .iter()
+            .filter(|stage| {
+                stage
+                    .pre_deploy_approvals
+                    .iter()
+                    .any(|a| a.status.as_deref() == Some("pending"))
+            })
+            .map(|stage| stage.id)
+            .collect();
+
+        if pending_env_ids.is_empty() {
+            self.set_status("No pending approvals to approve");
+            return;
+        }
+
+        let Some(client) = self.client() else {
+            self.set_error("No project configured");
+            return;
+        };
+
+        // Get release_id for refresh after approval
+        let release_id = self
+            .release_list
+            .get(self.selected_release_item_idx)
+            .map(|r| r.id)
+            .unwrap_or(0);
+
+        let count = pending_env_ids.len();
+        self.set_status(format!("Approving {count} stage(s)..."));
+
+        let (tx, rx) = mpsc::channel(10);
+        self.cicd_rx = Some(rx);
+
+        tokio::spawn(async move {
+            // Step 1: Get all pending approvals
+            let approvals = match client.get_pending_approvals().await {
+                Ok(approvals) => approvals,
+                Err(e) => {
+                    let _ = tx.send(CICDLoadResult::Error(e.to_string())).await;
+                    return;
+                }
+            };
+
+            // Step 2: Find approvals for our environments
+            let approval_ids: Vec<i32> = approvals
+                .iter()
+                .filter(|a| {
+                    a.release_environment
+                        .as_ref()
+                        .map(|e| pending_env_ids.contains(&e.id))
+                        .unwrap_or(false)
+                })
+                .map(|a| a.id)
+                .collect();
+
+            if approval_ids.is_empty() {
+                let _ = tx
+                    .send(CICDLoadResult::Error(
+                        "No matching approvals found".to_string(),
+                    ))
+                    .await;
+                return;
+            }
+
+            // Step 3: Approve all at once
+            match client
+                .update_approvals(&approval_ids, "approved", "Approved via lazyops")
+                .await
+            {
+                Ok(()) => {
+                    let _ = tx
+                        .send(CICDLoadResult::ApprovalUpdated {
+                            approval_id: 0, // Multiple approvals
+                            release_id,
+                            status: format!("approved {} stage(s)", approval_ids.len()),
+                        })
+                        .await;
+                }
+                Err(e) => {
+                    let _ = tx.send(CICDLoadResult::Error(e.to_string())).await;
+                }
+            }
+        });
+    }
+
+    pub fn set_status(&mut self, msg: impl Into<String>) {
+        self.status_message = Some(msg.into());
+        self.status_is_error = false;
+        self.status_set_at = Some(std::time::Instant::now());
+    }
+
+    pub fn set_error(&mut self, msg: impl Into<String>) {
+        self.status_message = Some(msg.into());
+        self.status_is_error = true;
+        self.status_set_at = Some(std::time::Instant::now());
+    }
+
+    pub fn clear_status(&mut self) {
+        self.status_message = None;
+        self.status_is_error = false;
+        self.status_set_at = None;
+    }
+
+    /// Clear status message if it's older than 5 seconds
+    pub fn clear_expired_status(&mut self) {
+        if let Some(set_at) = self.status_set_at {
+            if set_at.elapsed() > std::time::Duration::from_secs(5) {
+                self.clear_status();
+            }
+        }
+    }
+
+    /// Execute a confirmed action (cancel/retrigger)
+    pub fn execute_confirmed_action(&mut self, action_type: ConfirmActionType) {
+        let Some(client) = self.client() else {
+            self.set_error("No project configured");
+            return;
+        };
+
+        // PR updates report back through the PR channel
+        if let ConfirmActionType::UpdatePullRequest { pr_id, update, .. } = action_type {
+            self.update_pull_request(pr_id, update);
+            return;
+        }
+        if let ConfirmActionType::DeleteComment {
+            work_item_id,
+            comment_id,
+        } = action_type
+        {
+            self.delete_comment(work_item_id, comment_id);
+            return;
+        }
+        if let ConfirmActionType::RemoveLink {
+            work_item_id,
+            relation,
+            ..
+        } = action_type
+        {
+            self.remove_link(work_item_id, relation);
+            return;
+        }
+
+        // Get or create the sender channel
+        let tx = if let Some(tx) = &self.cicd_tx {
+            tx.clone()
+        } else {
+            let (tx, rx) = tokio::sync::mpsc::channel(10);
+            self.cicd_rx = Some(rx);
+            self.cicd_tx = Some(tx.clone());
+            tx
+        };
+
+        self.cicd_loading = true;
+
+        match action_type {
+            ConfirmActionType::CancelPipelineRun {
+                run_id,
+                build_number,
+            } => {
+                self.set_status(format!("Canceling build #{build_number}..."));
+                tokio::spawn(async move {
+                    let result = match client.cancel_pipeline_run(run_id).await {
+                        Ok(()) => CICDLoadResult::PipelineRunCanceled(run_id),
+                        Err(e) => CICDLoadResult::Error(e.to_string()),
+                    };
+                    let _ = tx.send(result).await;
+                });
+            }
+
+            ConfirmActionType::RetriggerPipelineRun {
+                pipeline_id,
+                branch,
+                build_number,
+            } => {
+                self.set_status(format!("Retriggering #{build_number}..."));
+                tokio::spawn(async move {
+                    let result = match client
+                        .trigger_pipeline(pipeline_id, &PipelineRunRequest::branch(&branch))
+                        .await
+                    {
+                        Ok(run) => CICDLoadResult::PipelineRunRetriggered(run),
+                        Err(e) => CICDLoadResult::Error(e.to_string()),
+                    };
+                    let _ = tx.send(result).await;
+                });
+            }
+
+            ConfirmActionType::CancelRelease {
+                release_id,
+                release_name,
+            } => {
+                self.set_status(format!("Abandoning {release_name}..."));
+                tokio::spawn(async move {
+                    let result = match client.cancel_release(release_id).await {
+                        Ok(()) => CICDLoadResult::ReleaseCanceled(release_id),
+                        Err(e) => CICDLoadResult::Error(e.to_string()),
                     };
                     let _ = tx.send(result).await;
                 });
@@ -2869,46 +3565,14 @@ impl App {
             } => {
                 self.set_status(format!("Canceling {environment_name}..."));
                 tokio::spawn(async move {
-                    let body = serde_json::json!({
-                        "status": "canceled",
-                        "comment": "Canceled from lazyops"
-                    });
-                    let temp_path = std::env::temp_dir()
-                        .join(format!("cancel_env_{release_id}_{environment_id}.json"));
-
-                    if let Err(e) = tokio::fs::write(&temp_path, body.to_string()).await {
-                        let _ = tx.send(CICDLoadResult::Error(e.to_string())).await;
-                        return;
-                    }
-
-                    let output = tokio::process::Command::new("az")
-                        .args(["devops", "invoke"])
-                        .args(["--area", "release"])
-                        .args(["--resource", "environments"])
-                        .args([
-                            "--route-parameters",
-                            &format!("project={project}"),
-                            &format!("releaseId={release_id}"),
-                            &format!("environmentId={environment_id}"),
-                        ])
-                        .args(["--api-version", "7.1"])
-                        .args(["--http-method", "PATCH"])
-                        .args(["--in-file", temp_path.to_str().unwrap()])
-                        .args(["--org", &org])
-                        .args(["--output", "json"])
-                        .output()
-                        .await;
-
-                    let _ = tokio::fs::remove_file(&temp_path).await;
-
-                    let result = match output {
-                        Ok(o) if o.status.success() => CICDLoadResult::ReleaseEnvironmentCanceled {
+                    let result = match client
+                        .cancel_release_environment(release_id, environment_id)
+                        .await
+                    {
+                        Ok(()) => CICDLoadResult::ReleaseEnvironmentCanceled {
                             release_id,
                             environment_name,
                         },
-                        Ok(o) => {
-                            CICDLoadResult::Error(String::from_utf8_lossy(&o.stderr).to_string())
-                        }
                         Err(e) => CICDLoadResult::Error(e.to_string()),
                     };
                     let _ = tx.send(result).await;
@@ -2923,48 +3587,14 @@ impl App {
             } => {
                 self.set_status(format!("Redeploying {environment_name}..."));
                 tokio::spawn(async move {
-                    let body = serde_json::json!({
-                        "status": "inProgress",
-                        "comment": "Redeployed from lazyops"
-                    });
-                    let temp_path = std::env::temp_dir()
-                        .join(format!("redeploy_env_{release_id}_{environment_id}.json"));
-
-                    if let Err(e) = tokio::fs::write(&temp_path, body.to_string()).await {
-                        let _ = tx.send(CICDLoadResult::Error(e.to_string())).await;
-                        return;
-                    }
-
-                    let output = tokio::process::Command::new("az")
-                        .args(["devops", "invoke"])
-                        .args(["--area", "release"])
-                        .args(["--resource", "environments"])
-                        .args([
-                            "--route-parameters",
-                            &format!("project={project}"),
-                            &format!("releaseId={release_id}"),
-                            &format!("environmentId={environment_id}"),
-                        ])
-                        .args(["--api-version", "7.1"])
-                        .args(["--http-method", "PATCH"])
-                        .args(["--in-file", temp_path.to_str().unwrap()])
-                        .args(["--org", &org])
-                        .args(["--output", "json"])
-                        .output()
-                        .await;
-
-                    let _ = tokio::fs::remove_file(&temp_path).await;
-
-                    let result = match output {
-                        Ok(o) if o.status.success() => {
-                            CICDLoadResult::ReleaseEnvironmentRedeployed {
-                                release_id,
-                                environment_name,
-                            }
-                        }
-                        Ok(o) => {
-                            CICDLoadResult::Error(String::from_utf8_lossy(&o.stderr).to_string())
-                        }
+                    let result = match client
+                        .redeploy_release_environment(release_id, environment_id)
+                        .await
+                    {
+                        Ok(()) => CICDLoadResult::ReleaseEnvironmentRedeployed {
+                            release_id,
+                            environment_name,
+                        },
                         Err(e) => CICDLoadResult::Error(e.to_string()),
                     };
                     let _ = tx.send(result).await;
@@ -2978,50 +3608,24 @@ impl App {
             } => {
                 self.set_status(format!("Rejecting approval for {environment_name}..."));
                 tokio::spawn(async move {
-                    // Azure DevOps Approvals API expects an array of approval objects with id included
-                    let body = serde_json::json!([{
-                        "id": approval_id,
-                        "status": "rejected",
-                        "comments": "Rejected from lazyops"
-                    }]);
-                    let temp_path =
-                        std::env::temp_dir().join(format!("reject_approval_{approval_id}.json"));
-
-                    if let Err(e) = tokio::fs::write(&temp_path, body.to_string()).await {
-                        let _ = tx.send(CICDLoadResult::Error(e.to_string())).await;
-                        return;
-                    }
-
-                    // Use bulk approvals endpoint (no approvalId in route) with array body
-                    let output = tokio::process::Command::new("az")
-                        .args(["devops", "invoke"])
-                        .args(["--area", "release"])
-                        .args(["--resource", "approvals"])
-                        .args(["--route-parameters", &format!("project={project}")])
-                        .args(["--http-method", "PATCH"])
-                        .args(["--api-version", "7.1"])
-                        .args(["--in-file", temp_path.to_str().unwrap()])
-                        .args(["--org", &org])
-                        .args(["--output", "json"])
-                        .output()
-                        .await;
-
-                    let _ = tokio::fs::remove_file(&temp_path).await;
-
-                    let result = match output {
-                        Ok(o) if o.status.success() => CICDLoadResult::ApprovalUpdated {
+                    let result = match client
+                        .update_approvals(&[approval_id], "rejected", "Rejected from lazyops")
+                        .await
+                    {
+                        Ok(()) => CICDLoadResult::ApprovalUpdated {
                             approval_id,
                             release_id,
                             status: "rejected".to_string(),
                         },
-                        Ok(o) => {
-                            CICDLoadResult::Error(String::from_utf8_lossy(&o.stderr).to_string())
-                        }
                         Err(e) => CICDLoadResult::Error(e.to_string()),
                     };
                     let _ = tx.send(result).await;
                 });
             }
+
+            ConfirmActionType::UpdatePullRequest { .. }
+            | ConfirmActionType::DeleteComment { .. }
+            | ConfirmActionType::RemoveLink { .. } => {} // Handled above
         }
     }
 
@@ -3044,14 +3648,25 @@ impl App {
         Ok(())
     }
 
-    pub async fn load_work_items(&mut self) -> Result<()> {
-        let client = self
-            .client()
-            .ok_or_else(|| anyhow::anyhow!("No project configured"))?;
-        let sprint = self.sprints.get(self.selected_sprint_idx);
+    /// Select a sprint by name (case-insensitive). Returns false if not found.
+    pub fn select_sprint_by_name(&mut self, name: &str) -> bool {
+        match self
+            .sprints
+            .iter()
+            .position(|s| s.name.eq_ignore_ascii_case(name))
+        {
+            Some(idx) => {
+                self.selected_sprint_idx = idx;
+                self.sprint_list_state.select(Some(idx));
+                true
+            }
+            None => false,
+        }
+    }
 
-        if let Some(sprint) = sprint {
-            self.work_items = client.get_sprint_work_items(&sprint.path).await?;
+    pub async fn load_work_items(&mut self) -> Result<()> {
+        if let Some(items) = self.fetch_work_items().await? {
+            self.work_items = items;
             self.extract_users_from_work_items();
             self.rebuild_visible_items();
             if !self.visible_items.is_empty() {
@@ -3062,8 +3677,27 @@ impl App {
         Ok(())
     }
 
+    /// Work items of the active query, or of the selected sprint
+    pub async fn fetch_work_items(&self) -> Result<Option<Vec<WorkItem>>> {
+        let client = self
+            .client()
+            .ok_or_else(|| anyhow::anyhow!("No project configured"))?;
+
+        if let Some(query) = &self.active_query {
+            let items = client.query_work_items(&query.wiql).await?;
+            return Ok(Some(AzureCli::build_hierarchy(items)));
+        }
+        match self.sprints.get(self.selected_sprint_idx) {
+            Some(sprint) => Ok(Some(client.get_sprint_work_items(&sprint.path).await?)),
+            None => Ok(None),
+        }
+    }
+
     pub async fn load_users(&mut self) -> Result<()> {
-        self.current_user = AzureCli::get_current_user().await.ok();
+        self.current_user = match self.client() {
+            Some(client) => client.get_current_user().await.ok(),
+            None => None,
+        };
         Ok(())
     }
 
@@ -3106,6 +3740,17 @@ impl App {
         let matcher = &self.fuzzy_matcher;
         let pinned = &self.pinned_items;
         let force_collapsed = self.force_collapsed;
+        // The board shows cards of every level, whatever is folded in the tree
+        let all_ids: HashSet<i32>;
+        let expanded = if self.current_view == View::Board {
+            all_ids = App::flatten_work_items(&self.work_items)
+                .iter()
+                .map(|item| item.id)
+                .collect();
+            &all_ids
+        } else {
+            &self.expanded_items
+        };
 
         #[allow(clippy::too_many_arguments)]
         fn flatten(
@@ -3242,7 +3887,7 @@ impl App {
             flatten(
                 &self.work_items[idx..idx + 1],
                 &mut self.visible_items,
-                &self.expanded_items,
+                expanded,
                 pinned,
                 0,
                 &query,
@@ -3258,7 +3903,7 @@ impl App {
             flatten(
                 &self.work_items[idx..idx + 1],
                 &mut self.visible_items,
-                &self.expanded_items,
+                expanded,
                 pinned,
                 0,
                 &query,
@@ -3282,6 +3927,19 @@ impl App {
         }
     }
 
+    /// Switch between the work item list and the board, keeping the
+    /// selected work item
+    pub fn switch_work_item_view(&mut self, view: View) {
+        let selected_id = self.selected_work_item().map(|v| v.item.id);
+        self.current_view = view;
+        self.rebuild_visible_items();
+        if let Some(pos) =
+            selected_id.and_then(|id| self.visible_items.iter().position(|v| v.item.id == id))
+        {
+            self.work_item_list_state.select(Some(pos));
+        }
+    }
+
     pub fn selected_work_item(&self) -> Option<&VisibleWorkItem> {
         self.work_item_list_state
             .selected()
@@ -3294,6 +3952,10 @@ impl App {
 
     /// Toggle pin on currently selected item (or its parent if it's a child)
     pub fn toggle_pin(&mut self) {
+        if self.has_marks() {
+            self.toggle_pin_marked();
+            return;
+        }
         if let Some(vi) = self.selected_work_item() {
             // If it's a child, find and pin/unpin its parent instead
             let id = if vi.depth > 0 {
@@ -3567,7 +4229,7 @@ impl App {
         if len == 0 {
             return;
         }
-        let jump = 10; // Jump 10 items
+        let jump = self.settings().page_jump;
         let i = self
             .work_item_list_state
             .selected()
@@ -3582,7 +4244,7 @@ impl App {
         if len == 0 {
             return;
         }
-        let jump = 10; // Jump 10 items
+        let jump = self.settings().page_jump;
         let i = self
             .work_item_list_state
             .selected()
@@ -3732,6 +4394,25 @@ impl App {
         ids
     }
 
+    /// Parents of listed work items that are not listed themselves and whose
+    /// title is not known yet
+    pub fn get_outside_parent_ids(&self) -> Vec<i32> {
+        let items = App::flatten_work_items(&self.work_items);
+        let listed: HashSet<i32> = items.iter().map(|item| item.id).collect();
+        let mut ids: Vec<i32> = Vec::new();
+        for parent_id in items.iter().filter_map(|item| item.fields.parent_id) {
+            if !listed.contains(&parent_id)
+                && !ids.contains(&parent_id)
+                && !self
+                    .relation_titles
+                    .contains_key(&format!("wi:{parent_id}"))
+            {
+                ids.push(parent_id);
+            }
+        }
+        ids
+    }
+
     /// Cache all loaded relations before refresh
     pub fn cache_relations(
         &self,
@@ -3848,10 +4529,11 @@ impl App {
         if len == 0 {
             return;
         }
+        let jump = self.settings().page_jump;
         let i = self
             .relations_list_state
             .selected()
-            .map(|i| (i + 10).min(len - 1))
+            .map(|i| (i + jump).min(len - 1))
             .unwrap_or(0);
         self.relations_list_state.select(Some(i));
     }
@@ -3861,10 +4543,11 @@ impl App {
         if len == 0 {
             return;
         }
+        let jump = self.settings().page_jump;
         let i = self
             .relations_list_state
             .selected()
-            .map(|i| i.saturating_sub(10))
+            .map(|i| i.saturating_sub(jump))
             .unwrap_or(0);
         self.relations_list_state.select(Some(i));
     }
@@ -4041,8 +4724,9 @@ impl App {
         }
     }
 
-    /// Find title for a work item by ID (from loaded work items)
-    fn find_work_item_title(&self, id: i32) -> Option<String> {
+    /// Find title for a work item by ID (from loaded work items, or parents
+    /// outside the list)
+    pub fn find_work_item_title(&self, id: i32) -> Option<String> {
         fn search(items: &[WorkItem], id: i32) -> Option<String> {
             for item in items {
                 if item.id == id {
@@ -4055,6 +4739,7 @@ impl App {
             None
         }
         search(&self.work_items, id)
+            .or_else(|| self.relation_titles.get(&format!("wi:{id}")).cloned())
     }
 
     /// Get URL for opening a relation in Azure DevOps
@@ -4088,21 +4773,21 @@ impl App {
     }
 
     // Filter helpers
-    pub fn available_filter_states(&self) -> Vec<&'static str> {
-        vec![
-            "All",
-            "New",
-            "In Progress",
-            "Done In Stage",
-            "Done Not Released",
-            "Done",
-            "Tested w/Bugs",
-            "Removed",
-        ]
+    pub fn available_filter_states(&self) -> Vec<String> {
+        let mut states: Vec<String> = self
+            .settings()
+            .get_states()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        if !states.iter().any(|s| s == "All") {
+            states.insert(0, "All".to_string());
+        }
+        states
     }
 
     /// Get filtered states based on fuzzy input
-    pub fn filtered_states(&self) -> Vec<&'static str> {
+    pub fn filtered_states(&self) -> Vec<String> {
         let states = self.available_filter_states();
         if self.filter_input.is_empty() {
             return states;
@@ -4155,11 +4840,19 @@ impl App {
     }
 
     /// Get filtered edit states based on fuzzy input (for changing state)
-    pub fn filtered_edit_states(&self) -> Vec<&'static str> {
-        let states = self
-            .selected_work_item()
-            .map(|w| w.item.available_states())
-            .unwrap_or_default();
+    /// Custom `states` from settings replace the per-type defaults.
+    pub fn filtered_edit_states(&self) -> Vec<String> {
+        let settings = self.settings();
+        let states: Vec<String> = if settings.states.is_empty() {
+            self.selected_work_item()
+                .map(|w| w.item.available_states())
+                .unwrap_or_default()
+                .into_iter()
+                .map(String::from)
+                .collect()
+        } else {
+            settings.states.into_iter().filter(|s| s != "All").collect()
+        };
         if self.filter_input.is_empty() {
             return states;
         }
@@ -4188,350 +4881,2414 @@ impl App {
             .collect()
     }
 
-    // ========== Embedded Terminal Methods ==========
+    // ========== Work Item Fields Form ==========
 
-    /// Open log viewer in nvim with auto-reload
-    /// Creates a temp file and spawns nvim with autoread settings
-    pub fn open_log_viewer(&mut self, cols: u16, rows: u16) -> anyhow::Result<()> {
-        // Create temp log file
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_secs())
-            .unwrap_or(0);
-        let log_path = format!("/tmp/lazyops-{timestamp}.log");
+    /// Open the editable fields form for the selected work item in the Details tab
+    pub async fn open_work_item_form(&mut self) {
+        let Some(item) = self.selected_work_item().map(|w| w.item.clone()) else {
+            return;
+        };
+        // The list query only carries common fields; fetch the full item for custom ones
+        let item = match self.client() {
+            Some(client) => client.get_work_item(item.id).await.unwrap_or(item),
+            None => item,
+        };
+        self.work_item_form = Some(WorkItemForm::new(&item));
+        self.input_mode = InputMode::WorkItemForm;
+        self.focus = Focus::Preview;
+        self.preview_tab = PreviewTab::Details;
+    }
+
+    /// Close the form without saving
+    pub fn discard_work_item_form(&mut self) {
+        if self.work_item_form.take().is_some_and(|f| f.is_dirty()) {
+            self.set_status("Discarded changes");
+        }
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Send the edited fields as one update and reload the list
+    pub async fn save_work_item_form(&mut self) {
+        let Some(form) = &mut self.work_item_form else {
+            return;
+        };
+        form.finish_edit();
+        let id = form.id;
+        let updates = match form.updates() {
+            Ok(updates) => updates,
+            Err(e) => {
+                self.set_error(e);
+                return;
+            }
+        };
+        if updates.is_empty() {
+            self.discard_work_item_form();
+            self.set_status("No changes");
+            return;
+        }
+        let Some(client) = self.client() else {
+            return;
+        };
+        match client.update_work_item(id, &updates).await {
+            Ok(_) => {
+                self.work_item_form = None;
+                self.input_mode = InputMode::Normal;
+                let count = updates.len();
+                self.set_status(format!(
+                    "Updated #{id} ({count} field{})",
+                    if count == 1 { "" } else { "s" }
+                ));
+                let _ = self.load_work_items().await;
+            }
+            Err(e) => self.set_error(format!("Failed to update #{id}: {e}")),
+        }
+    }
+
+    // ========== Work Item Creation ==========
+
+    /// Open the create dialog for the selected sprint, under the selected item
+    pub fn open_work_item_create(&mut self) {
+        let iteration = self
+            .selected_sprint()
+            .map(|s| s.path.clone())
+            .unwrap_or_default();
+        let parent = self.selected_work_item().map(|w| &w.item);
+        self.work_item_create_dialog = Some(WorkItemCreateDialog::new(iteration, parent));
+        self.input_mode = InputMode::WorkItemCreate;
+    }
+
+    /// Create one work item per title line and add them to the list in place
+    pub async fn submit_work_item_create(&mut self) {
+        let Some(dialog) = &self.work_item_create_dialog else {
+            return;
+        };
+        if let Some(problem) = dialog.validate() {
+            self.set_error(problem);
+            return;
+        }
+        let requests = dialog.to_requests();
+        let Some(client) = self.client() else {
+            return;
+        };
+
+        let mut created = Vec::new();
+        let mut failure = None;
+        for request in &requests {
+            match client.create_work_item(request).await {
+                Ok(mut item) => {
+                    // Not every response carries System.Parent
+                    item.fields.parent_id = item.fields.parent_id.or(request.parent_id);
+                    created.push(item);
+                }
+                Err(e) => {
+                    failure = Some(format!("Failed to create \"{}\": {e}", request.title()));
+                    break;
+                }
+            }
+        }
+
+        let ids: Vec<String> = created.iter().map(|w| format!("#{}", w.id)).collect();
+        self.insert_work_items(created);
+        match failure {
+            Some(error) if ids.is_empty() => self.set_error(error),
+            Some(error) => self.set_error(format!("Created {}; {error}", ids.join(", "))),
+            None => {
+                self.work_item_create_dialog = None;
+                self.input_mode = InputMode::Normal;
+                self.set_status(format!("Created {}", ids.join(", ")));
+            }
+        }
+        // Keep the dialog for a retry, without the titles that went through
+        if let Some(dialog) = &mut self.work_item_create_dialog {
+            dialog.titles = requests
+                .iter()
+                .skip(ids.len())
+                .map(|r| r.title())
+                .collect::<Vec<_>>()
+                .join("\n");
+        }
+    }
+
+    /// Add new work items of the selected sprint to the hierarchy without a reload
+    pub fn insert_work_items(&mut self, items: Vec<WorkItem>) {
+        let sprint_path = self.selected_sprint().map(|s| s.path.clone());
+        let items: Vec<WorkItem> = items
+            .into_iter()
+            .filter(|w| w.fields.iteration_path == sprint_path)
+            .collect();
+        let Some(first_id) = items.first().map(|w| w.id) else {
+            return;
+        };
+
+        for parent_id in items.iter().filter_map(|w| w.fields.parent_id) {
+            self.expanded_items.insert(parent_id);
+        }
+        let mut flat = App::flatten_work_items(&self.work_items);
+        flat.extend(items);
+        self.work_items = crate::azure::AzureCli::build_hierarchy(flat);
+        self.rebuild_visible_items();
+        if let Some(idx) = self
+            .visible_items
+            .iter()
+            .position(|v| v.item.id == first_id)
+        {
+            self.work_item_list_state.select(Some(idx));
+        }
+        self.save_to_cache();
+    }
+
+    // ========== Multi-select & Bulk Operations ==========
+
+    /// Whether the item at a list index is marked (explicitly or by the range)
+    pub fn is_marked(&self, idx: usize, id: i32) -> bool {
+        self.marked_items.contains(&id) || self.visual_range().is_some_and(|r| r.contains(&idx))
+    }
+
+    fn visual_range(&self) -> Option<std::ops::RangeInclusive<usize>> {
+        let anchor = self.visual_anchor?;
+        let cursor = self.work_item_list_state.selected()?;
+        Some(anchor.min(cursor)..=anchor.max(cursor))
+    }
+
+    pub fn has_marks(&self) -> bool {
+        self.visual_anchor.is_some() || !self.marked_items.is_empty()
+    }
+
+    /// Marked IDs in list order; marks hidden by filters or collapsed parents still count
+    pub fn marked_ids(&self) -> Vec<i32> {
+        let mut ids: Vec<i32> = self
+            .visible_items
+            .iter()
+            .enumerate()
+            .filter(|(idx, vi)| self.is_marked(*idx, vi.item.id))
+            .map(|(_, vi)| vi.item.id)
+            .collect();
+        let mut hidden: Vec<i32> = self
+            .marked_items
+            .iter()
+            .filter(|id| !ids.contains(id))
+            .copied()
+            .collect();
+        hidden.sort();
+        ids.extend(hidden);
+        ids
+    }
+
+    /// Mark or unmark the selected item
+    pub fn toggle_mark(&mut self) {
+        if let Some(id) = self.selected_work_item().map(|vi| vi.item.id) {
+            if !self.marked_items.remove(&id) {
+                self.marked_items.insert(id);
+            }
+        }
+    }
+
+    /// Start range selection at the cursor, or keep the range and stop
+    pub fn toggle_visual(&mut self) {
+        if self.visual_anchor.is_some() {
+            self.marked_items.extend(self.marked_ids());
+            self.visual_anchor = None;
+        } else {
+            self.visual_anchor = self.work_item_list_state.selected();
+        }
+    }
+
+    /// Mark the selected item and everything below it
+    pub fn mark_children(&mut self) {
+        let Some(vi) = self.selected_work_item() else {
+            return;
+        };
+        fn collect(item: &WorkItem, ids: &mut Vec<i32>) {
+            ids.push(item.id);
+            for child in &item.children {
+                collect(child, ids);
+            }
+        }
+        let mut ids = Vec::new();
+        collect(&vi.item, &mut ids);
+        let id = vi.item.id;
+
+        let count = ids.len();
+        self.marked_items.extend(ids);
+        if count > 1 {
+            self.expanded_items.insert(id);
+            self.rebuild_visible_items();
+        }
+        self.set_status(format!("{} marked", self.marked_ids().len()));
+    }
+
+    /// Mark every item in the list, i.e. everything matching the current filters
+    pub fn mark_visible(&mut self) {
+        self.marked_items
+            .extend(self.visible_items.iter().map(|vi| vi.item.id));
+        self.set_status(format!("{} marked", self.marked_ids().len()));
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.marked_items.clear();
+        self.visual_anchor = None;
+        self.bulk_failures.clear();
+    }
+
+    /// Dropdown title, with the number of items a bulk edit will touch
+    pub fn edit_title(&self, title: &str) -> String {
+        if self.has_marks() {
+            format!(" {title} ({} marked) ", self.marked_ids().len())
+        } else {
+            format!(" {title} ")
+        }
+    }
+
+    /// Marked items, or the selected one when nothing is marked
+    fn bulk_targets(&self) -> Vec<WorkItem> {
+        let ids = if self.has_marks() {
+            self.marked_ids()
+        } else {
+            self.selected_work_item()
+                .map(|vi| vec![vi.item.id])
+                .unwrap_or_default()
+        };
+        let flat = App::flatten_work_items(&self.work_items);
+        ids.iter()
+            .filter_map(|id| flat.iter().find(|w| w.id == *id).cloned())
+            .collect()
+    }
+
+    /// Apply an operation to the marked (or selected) items in the background
+    pub fn start_bulk(&mut self, operation: BulkOperation) {
+        use futures::stream::StreamExt;
+
+        if self.bulk_rx.is_some() {
+            self.set_error("A bulk update is still running");
+            return;
+        }
+        let targets = self.bulk_targets();
+        let Some(client) = self.client().filter(|_| !targets.is_empty()) else {
+            return;
+        };
+        // Keep the range marked while the update runs
+        if self.visual_anchor.is_some() {
+            self.toggle_visual();
+        }
+
+        let (tx, rx) = mpsc::channel(1);
+        self.bulk_rx = Some(rx);
+        self.set_status(format!("Updating {} work items...", targets.len()));
+
+        let requests: Vec<_> = targets
+            .iter()
+            .map(|item| (item.id, operation.updates_for(item)))
+            .collect();
+        tokio::spawn(async move {
+            let results: Vec<_> = futures::stream::iter(requests)
+                .map(|(id, updates)| {
+                    let client = client.clone();
+                    async move {
+                        if updates.is_empty() {
+                            return (id, Ok(None));
+                        }
+                        (id, client.update_work_item(id, &updates).await.map(Some))
+                    }
+                })
+                .buffer_unordered(BULK_CONCURRENCY)
+                .collect()
+                .await;
+
+            let mut outcome = BulkOutcome {
+                operation,
+                updated: Vec::new(),
+                unchanged: 0,
+                failed: Vec::new(),
+            };
+            for (id, result) in results {
+                match result {
+                    Ok(Some(item)) => outcome.updated.push(item),
+                    Ok(None) => outcome.unchanged += 1,
+                    Err(e) => outcome.failed.push((id, e.to_string())),
+                }
+            }
+            outcome.failed.sort_by_key(|(id, _)| *id);
+            let _ = tx.send(outcome).await;
+        });
+    }
+
+    /// Apply a finished bulk update: failed items stay marked with their errors
+    pub fn poll_bulk_results(&mut self) {
+        let Some(rx) = &mut self.bulk_rx else {
+            return;
+        };
+        let outcome = match rx.try_recv() {
+            Ok(outcome) => outcome,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                self.bulk_rx = None;
+                return;
+            }
+        };
+        self.bulk_rx = None;
+
+        self.replace_work_items(outcome.updated.clone());
+        self.marked_items = outcome.failed.iter().map(|(id, _)| *id).collect();
+        self.bulk_failures = outcome.failed.iter().cloned().collect();
+        if outcome.failed.is_empty() {
+            self.set_status(outcome.summary());
+        } else {
+            self.set_error(outcome.summary());
+        }
+        self.save_to_cache();
+    }
+
+    /// Swap in updated copies of work items, dropping any moved out of the sprint
+    fn replace_work_items(&mut self, updated: Vec<WorkItem>) {
+        if updated.is_empty() {
+            return;
+        }
+        let selected_id = self.selected_work_item().map(|vi| vi.item.id);
+        let sprint_path = self.selected_sprint().map(|s| s.path.clone());
+        let in_list = |item: &WorkItem| {
+            self.active_query.is_some() || item.fields.iteration_path == sprint_path
+        };
+
+        let flat: Vec<WorkItem> = App::flatten_work_items(&self.work_items)
+            .into_iter()
+            .filter_map(|old| match updated.iter().find(|w| w.id == old.id) {
+                Some(new) => {
+                    let mut new = new.clone();
+                    new.fields.parent_id = new.fields.parent_id.or(old.fields.parent_id);
+                    new.relations = new.relations.or(old.relations);
+                    in_list(&new).then_some(new)
+                }
+                None => Some(old),
+            })
+            .collect();
+        self.work_items = AzureCli::build_hierarchy(flat);
+        self.rebuild_visible_items();
+        // Their timelines gained an entry; reload them when shown again
+        for item in &updated {
+            self.work_item_history.remove(&item.id);
+        }
+        if matches!(&self.history_rx, Some((id, _)) if updated.iter().any(|w| w.id == *id)) {
+            self.history_rx = None;
+        }
+
+        let idx = selected_id
+            .and_then(|id| self.visible_items.iter().position(|vi| vi.item.id == id))
+            .or_else(|| self.work_item_list_state.selected())
+            .map(|idx| idx.min(self.visible_items.len().saturating_sub(1)));
+        self.work_item_list_state
+            .select(idx.filter(|_| !self.visible_items.is_empty()));
+    }
+
+    /// Pin the top-level items of the marks, or unpin them when all are pinned
+    fn toggle_pin_marked(&mut self) {
+        let marked: HashSet<i32> = self.marked_ids().into_iter().collect();
+        fn contains_marked(item: &WorkItem, marked: &HashSet<i32>) -> bool {
+            marked.contains(&item.id) || item.children.iter().any(|c| contains_marked(c, marked))
+        }
+        let roots: Vec<i32> = self
+            .work_items
+            .iter()
+            .filter(|item| contains_marked(item, &marked))
+            .map(|item| item.id)
+            .collect();
+
+        if roots.iter().all(|id| self.pinned_items.contains(id)) {
+            for id in &roots {
+                self.pinned_items.remove(id);
+            }
+            self.set_status(format!("Unpinned {} items", roots.len()));
+        } else {
+            self.pinned_items.extend(roots.iter().copied());
+            self.set_status(format!("Pinned {} items", roots.len()));
+        }
+        self.rebuild_visible_items();
+        self.save_to_cache();
+    }
+
+    // ========== WIQL Queries ==========
+
+    /// Open the Queries picker with saved queries and the project's query folders
+    pub async fn open_query_browser(&mut self) {
+        let project = match self.client() {
+            Some(client) => match client.list_queries().await {
+                Ok(items) => items,
+                Err(e) => {
+                    self.set_error(format!("Failed to load project queries: {e}"));
+                    Vec::new()
+                }
+            },
+            None => Vec::new(),
+        };
+        self.query_browser = Some(QueryBrowser::new(&self.config.queries, &project));
+        self.input_mode = InputMode::QueryBrowser;
+    }
+
+    /// List the results of a WIQL query in place of the sprint
+    pub async fn run_query(&mut self, query: SavedQuery) -> bool {
+        let previous = self.active_query.replace(query);
+        self.set_loading(true, "Running query...");
+        let result = self.load_work_items().await;
+        self.set_loading(false, "");
+
+        match result {
+            Ok(()) => {
+                self.query_browser = None;
+                self.input_mode = InputMode::Normal;
+                let count = App::flatten_work_items(&self.work_items).len();
+                self.set_status(format!("{count} work items"));
+                // Relations for the new items
+                self.relations_loader_active = false;
+                self.start_relations_loader();
+                true
+            }
+            Err(e) => {
+                self.active_query = previous;
+                self.set_error(format!("Query failed: {e}"));
+                false
+            }
+        }
+    }
+
+    /// Run the WIQL editor's query; once it works, a named query is saved to the config
+    pub async fn run_query_editor(&mut self) {
+        let Some(editor) = self.query_browser.as_ref().and_then(|b| b.editor.as_ref()) else {
+            return;
+        };
+        let query = match editor.query() {
+            Ok(query) => query,
+            Err(problem) => {
+                self.set_error(problem);
+                return;
+            }
+        };
+
+        if query.name.is_empty() {
+            self.run_query(SavedQuery {
+                name: "Ad-hoc query".to_string(),
+                ..query
+            })
+            .await;
+        } else if self.run_query(query.clone()).await {
+            let name = query.name.clone();
+            if self.demo {
+                self.config.remember_query(query);
+                self.set_status(format!("Kept \"{name}\" for this session (demo)"));
+                return;
+            }
+            match self.config.save_query(query) {
+                Ok(path) => self.set_status(format!("Saved \"{name}\" to {}", path.display())),
+                Err(e) => self.set_error(format!("Failed to save query: {e}")),
+            }
+        }
+    }
+
+    /// Go back to listing the selected sprint
+    pub fn clear_active_query(&mut self) {
+        self.active_query = None;
+    }
+
+    // ========== Kanban Board ==========
+
+    /// Load the team's boards; without any, the board shows state columns
+    pub async fn load_boards(&mut self) {
+        let Some(client) = self.client() else {
+            return;
+        };
+        self.set_loading(true, "Loading board...");
+        let result = client.get_boards().await;
+        self.set_loading(false, "");
+        self.boards = Some(match result {
+            Ok(boards) => boards,
+            Err(e) => {
+                self.set_error(format!("Failed to load boards, showing states: {e}"));
+                Vec::new()
+            }
+        });
+        self.select_board_card();
+    }
+
+    /// Board for the listed items, and where their cards go
+    pub fn board_layout(&self) -> (KanbanBoard, BoardLayout) {
+        let settings = self.settings();
+        let board = KanbanBoard::pick(
+            self.boards.as_deref().unwrap_or_default(),
+            &settings.get_states(),
+            &self.visible_items,
+        );
+        let layout = board.layout(&self.visible_items, &self.collapsed_lanes);
+        (board, layout)
+    }
+
+    /// Keep the selection on a card of the board
+    pub fn select_board_card(&mut self) {
+        let (_, layout) = self.board_layout();
+        let selected = self.work_item_list_state.selected();
+        if selected.and_then(|idx| layout.find(idx)).is_none() {
+            self.work_item_list_state.select(layout.first());
+        }
+    }
+
+    /// Move the board cursor `dx` columns or `dy` cards
+    pub fn board_step(&mut self, dx: isize, dy: isize) {
+        let (_, layout) = self.board_layout();
+        let next = layout.step(self.work_item_list_state.selected(), dx, dy);
+        self.work_item_list_state.select(next);
+    }
+
+    /// Jump to the first or last card of the column
+    pub fn board_edge(&mut self, last: bool) {
+        let (_, layout) = self.board_layout();
+        let next = layout.edge(self.work_item_list_state.selected(), last);
+        self.work_item_list_state.select(next);
+    }
+
+    /// Fold away the swimlane of the selected card
+    pub fn collapse_board_lane(&mut self) {
+        let (board, layout) = self.board_layout();
+        let Some((lane, _)) = self
+            .work_item_list_state
+            .selected()
+            .and_then(|idx| layout.find(idx))
+        else {
+            return;
+        };
+        if board.lanes.len() < 2 {
+            return;
+        }
+        let name = board.lanes[lane].clone();
+        self.set_status(format!(
+            "Collapsed {} lane",
+            if name.is_empty() { "default" } else { &name }
+        ));
+        self.collapsed_lanes.insert(name);
+        self.select_board_card();
+    }
+
+    /// Expand every swimlane, or collapse them all when none is collapsed
+    pub fn toggle_board_lanes(&mut self) {
+        if self.collapsed_lanes.is_empty() {
+            let (board, _) = self.board_layout();
+            if board.lanes.len() < 2 {
+                return;
+            }
+            self.collapsed_lanes.extend(board.lanes);
+        } else {
+            self.collapsed_lanes.clear();
+        }
+        self.select_board_card();
+    }
+
+    /// Move the selected card to the next column left or right
+    pub async fn move_board_card(&mut self, dx: isize) {
+        let (board, _) = self.board_layout();
+        let Some(entry) = self.selected_work_item() else {
+            return;
+        };
+        let item = entry.item.clone();
+        let Some(col) = board
+            .column_of(&item)
+            .and_then(|col| col.checked_add_signed(dx))
+            .filter(|col| *col < board.columns.len())
+        else {
+            return;
+        };
+        let Some(client) = self.client() else {
+            return;
+        };
+        let column = &board.columns[col];
+        match client
+            .update_work_item(item.id, &board.move_updates(&item, col))
+            .await
+        {
+            Ok(mut updated) => {
+                if board.column_field.is_some() {
+                    let name = serde_json::Value::from(column.name.as_str());
+                    updated
+                        .fields
+                        .set(WorkItemFields::BOARD_COLUMN, Some(&name));
+                }
+                self.replace_work_items(vec![updated]);
+                let (_, layout) = self.board_layout();
+                if column.over_limit(layout.counts[col]) {
+                    self.set_error(format!(
+                        "Moved #{} to {} - over its WIP limit of {}",
+                        item.id, column.name, column.item_limit
+                    ));
+                } else {
+                    self.set_status(format!("Moved #{} to {}", item.id, column.name));
+                }
+            }
+            Err(e) => self.set_error(format!("Failed to move #{}: {e}", item.id)),
+        }
+    }
+
+    // ========== Sprint Analytics ==========
+
+    /// Progress of the listed sprint items
+    pub fn sprint_progress(&self) -> SprintProgress {
+        SprintProgress::from_items(&self.work_items)
+    }
+
+    /// Open the sprint analytics panel; the burndown loads unless it is
+    /// already there for this sprint
+    pub fn open_sprint_stats(&mut self) {
+        if self.active_query.is_some() {
+            self.set_error("Sprint analytics are not available for query results");
+            return;
+        }
+        let Some(sprint) = self.selected_sprint() else {
+            self.set_error("No sprint selected");
+            return;
+        };
+        let loaded = self
+            .burndown
+            .as_ref()
+            .is_some_and(|b| b.sprint_path == sprint.path);
+        self.input_mode = InputMode::SprintStats;
+        if !loaded && self.burndown_rx.is_none() {
+            self.load_burndown();
+        }
+    }
+
+    /// Rebuild the burndown from the revision history of every sprint item
+    pub fn load_burndown(&mut self) {
+        use futures::stream::StreamExt;
+
+        let Some(sprint) = self.selected_sprint().cloned() else {
+            return;
+        };
+        let Some(client) = self.client() else {
+            return;
+        };
+        let ids: Vec<i32> = App::flatten_work_items(&self.work_items)
+            .iter()
+            .map(|item| item.id)
+            .collect();
+
+        let (tx, rx) = mpsc::channel(1);
+        self.burndown = None;
+        self.burndown_rx = Some(rx);
+        tokio::spawn(async move {
+            let results: Vec<_> = futures::stream::iter(ids)
+                .map(|id| {
+                    let client = client.clone();
+                    async move { (id, client.get_work_item_revisions(id).await) }
+                })
+                .buffer_unordered(BULK_CONCURRENCY)
+                .collect()
+                .await;
+
+            let mut revisions = std::collections::HashMap::new();
+            let mut failed = 0;
+            for (id, result) in results {
+                match result {
+                    Ok(revs) => {
+                        revisions.insert(id, revs);
+                    }
+                    Err(_) => failed += 1,
+                }
+            }
+            let today = chrono::Utc::now().date_naive();
+            let burndown = Burndown::build(&sprint, &revisions, today);
+            let _ = tx.send((burndown, failed)).await;
+        });
+    }
+
+    /// Pick up a finished burndown
+    pub fn poll_burndown(&mut self) {
+        let Some(rx) = &mut self.burndown_rx else {
+            return;
+        };
+        let (burndown, failed) = match rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                self.burndown_rx = None;
+                return;
+            }
+        };
+        self.burndown_rx = None;
+
+        if burndown.is_none() {
+            self.set_error("The sprint has no start and finish dates");
+        } else if failed > 0 {
+            self.set_error(format!("Could not load the history of {failed} work items"));
+        }
+        self.burndown = burndown;
+    }
+
+    // ========== Team Capacity ==========
+
+    /// Open the team capacity view; capacity loads unless it is already there
+    /// for this sprint
+    pub fn open_team_capacity(&mut self) {
+        if self.active_query.is_some() {
+            self.set_error("Team capacity is not available for query results");
+            return;
+        }
+        let Some(sprint) = self.selected_sprint() else {
+            self.set_error("No sprint selected");
+            return;
+        };
+        let loaded = self
+            .team_capacity
+            .as_ref()
+            .is_some_and(|(id, _)| *id == sprint.id);
+        self.input_mode = InputMode::TeamCapacity;
+        if !loaded && self.capacity_rx.is_none() {
+            self.load_team_capacity();
+        }
+    }
+
+    /// Load capacity, days off and working days for the selected sprint
+    pub fn load_team_capacity(&mut self) {
+        let Some(sprint_id) = self.selected_sprint().map(|s| s.id.clone()) else {
+            return;
+        };
+        let Some(client) = self.client() else {
+            return;
+        };
+        let (tx, rx) = mpsc::channel(1);
+        self.team_capacity = None;
+        self.capacity_rx = Some(rx);
+        tokio::spawn(async move {
+            let result = client
+                .get_team_capacity(&sprint_id)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send((sprint_id, result)).await;
+        });
+    }
+
+    /// Pick up loaded team capacity
+    pub fn poll_team_capacity(&mut self) {
+        let Some(rx) = &mut self.capacity_rx else {
+            return;
+        };
+        let (sprint_id, result) = match rx.try_recv() {
+            Ok(loaded) => loaded,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                self.capacity_rx = None;
+                return;
+            }
+        };
+        self.capacity_rx = None;
+        if let Err(e) = &result {
+            self.set_error(format!("Failed to load team capacity: {e}"));
+        }
+        self.team_capacity = Some((sprint_id, result));
+    }
+
+    /// Capacity of the selected sprint against the remaining work of its
+    /// listed items, from today on; `None` until capacity has loaded
+    pub fn capacity_plan(&self) -> Option<CapacityPlan> {
+        let sprint = self.selected_sprint()?;
+        let (id, Ok(capacity)) = self.team_capacity.as_ref()? else {
+            return None;
+        };
+        if *id != sprint.id {
+            return None;
+        }
+        let today = chrono::Utc::now().date_naive();
+        CapacityPlan::build(sprint, capacity, &self.users, &self.work_items, today)
+    }
+
+    /// Pick up a loaded history timeline, then start loading the selected
+    /// item's one if the History tab shows it and it is not there yet
+    pub fn poll_history(&mut self) {
+        if let Some((id, rx)) = &mut self.history_rx {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.work_item_history.insert(*id, result);
+                    self.history_rx = None;
+                }
+                Err(mpsc::error::TryRecvError::Empty) => return,
+                Err(mpsc::error::TryRecvError::Disconnected) => self.history_rx = None,
+            }
+        }
+
+        if self.current_view != View::Tasks || self.preview_tab != PreviewTab::History {
+            return;
+        }
+        let Some(id) = self.selected_work_item().map(|vi| vi.item.id) else {
+            return;
+        };
+        if self.work_item_history.contains_key(&id) {
+            return;
+        }
+        let Some(client) = self.client() else {
+            return;
+        };
+        let (tx, rx) = mpsc::channel(1);
+        self.history_rx = Some((id, rx));
+        tokio::spawn(async move {
+            let result = client
+                .get_work_item_updates(id)
+                .await
+                .map(|updates| crate::history::timeline(&updates))
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result).await;
+        });
+    }
+
+    // ========== Discussion Methods ==========
+
+    fn comments_sender(&mut self) -> mpsc::Sender<CommentsUpdate> {
+        if let Some(tx) = &self.comments_tx {
+            return tx.clone();
+        }
+        let (tx, rx) = mpsc::channel(20);
+        self.comments_rx = Some(rx);
+        self.comments_tx = Some(tx.clone());
+        tx
+    }
+
+    /// Forget loaded comments and drop the results of requests in flight
+    pub fn clear_comments(&mut self) {
+        self.work_item_comments.clear();
+        self.comments_loading.clear();
+        self.comments_tx = None;
+        self.comments_rx = None;
+        self.selected_comment = None;
+    }
+
+    /// Apply finished comment requests, then start loading the selected
+    /// item's comments if the Discussion tab shows them and they are not there yet
+    pub fn poll_comments(&mut self) {
+        let mut updates = Vec::new();
+        if let Some(rx) = &mut self.comments_rx {
+            while let Ok(update) = rx.try_recv() {
+                updates.push(update);
+            }
+        }
+        for update in updates {
+            let id = update.work_item_id;
+            if let Some(comments) = update.comments {
+                self.comments_loading.remove(&id);
+                self.work_item_comments.insert(id, comments);
+            }
+            match update.outcome {
+                Some(Ok(message)) => {
+                    // Comments are revisions too
+                    self.work_item_history.remove(&id);
+                    self.set_status(message);
+                }
+                Some(Err(e)) => self.set_error(e),
+                None => {}
+            }
+        }
+
+        if self.current_view != View::Tasks || self.preview_tab != PreviewTab::Discussion {
+            return;
+        }
+        let Some(id) = self.selected_work_item().map(|vi| vi.item.id) else {
+            return;
+        };
+        if self.work_item_comments.contains_key(&id) || self.comments_loading.contains(&id) {
+            return;
+        }
+        let Some(client) = self.client() else {
+            return;
+        };
+        let tx = self.comments_sender();
+        self.comments_loading.insert(id);
+        tokio::spawn(async move {
+            let comments = client
+                .get_work_item_comments(id)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx
+                .send(CommentsUpdate {
+                    work_item_id: id,
+                    comments: Some(comments),
+                    outcome: None,
+                })
+                .await;
+        });
+    }
+
+    /// Loaded comments of the selected work item, newest first
+    pub fn selected_comments(&self) -> &[WorkItemComment] {
+        self.selected_work_item()
+            .and_then(|vi| self.work_item_comments.get(&vi.item.id))
+            .and_then(|result| result.as_ref().ok())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Index of the highlighted comment; the newest when another item was
+    /// looked at last
+    pub fn selected_comment_index(&self) -> usize {
+        let len = self.selected_comments().len();
+        match (self.selected_comment, self.selected_work_item()) {
+            (Some((id, idx)), Some(vi)) if vi.item.id == id => idx.min(len.saturating_sub(1)),
+            _ => 0,
+        }
+    }
+
+    pub fn selected_work_item_comment(&self) -> Option<&WorkItemComment> {
+        self.selected_comments().get(self.selected_comment_index())
+    }
+
+    fn select_comment(&mut self, step: impl FnOnce(usize, usize) -> usize) {
+        let len = self.selected_comments().len();
+        let Some(id) = self.selected_work_item().map(|vi| vi.item.id) else {
+            return;
+        };
+        if len == 0 {
+            return;
+        }
+        let idx = step(self.selected_comment_index(), len - 1);
+        self.selected_comment = Some((id, idx));
+    }
+
+    pub fn comments_next(&mut self) {
+        self.select_comment(|idx, last| (idx + 1).min(last));
+    }
+
+    pub fn comments_prev(&mut self) {
+        self.select_comment(|idx, _| idx.saturating_sub(1));
+    }
+
+    pub fn comments_page_down(&mut self) {
+        let jump = self.settings().page_jump;
+        self.select_comment(|idx, last| (idx + jump).min(last));
+    }
+
+    pub fn comments_page_up(&mut self) {
+        let jump = self.settings().page_jump;
+        self.select_comment(|idx, _| idx.saturating_sub(jump));
+    }
+
+    /// Start writing a comment on the selected work item
+    pub fn open_comment_composer(&mut self) {
+        let Some(id) = self.selected_work_item().map(|vi| vi.item.id) else {
+            self.set_status("Select a work item first");
+            return;
+        };
+        self.preview_tab = PreviewTab::Discussion;
+        self.preview_scroll = 0;
+        self.comment_draft = Some(CommentDraft::new(id));
+        self.input_mode = InputMode::CommentComposer;
+    }
+
+    /// Own highlighted comment, or why it cannot be changed
+    fn own_selected_comment(&mut self, verb: &str) -> Option<(i32, &WorkItemComment)> {
+        let me = self.current_user.clone();
+        let id = self.selected_work_item().map(|vi| vi.item.id)?;
+        let Some(comment) = self.selected_work_item_comment() else {
+            self.set_status(format!("No comment to {verb}"));
+            return None;
+        };
+        if !crate::discussion::is_own(comment, me.as_deref()) {
+            self.set_status(format!("You can only {verb} your own comments"));
+            return None;
+        }
+        self.selected_work_item_comment().map(|c| (id, c))
+    }
+
+    /// Edit the highlighted comment, if we wrote it
+    pub fn edit_selected_comment(&mut self) {
+        let Some((id, comment)) = self.own_selected_comment("edit") else {
+            return;
+        };
+        let draft = CommentDraft::edit(
+            id,
+            comment.id,
+            crate::markdown::html_to_markdown(&comment.text),
+        );
+        // Keep the mentions the comment has as links when it is posted again
+        let mentions = crate::discussion::mention_addresses(&comment.text);
+        self.comment_draft = Some(CommentDraft {
+            mentions: self
+                .users
+                .iter()
+                .filter(|user| {
+                    mentions
+                        .iter()
+                        .any(|a| a.eq_ignore_ascii_case(&user.unique_name))
+                })
+                .cloned()
+                .collect(),
+            ..draft
+        });
+        self.input_mode = InputMode::CommentComposer;
+    }
+
+    /// Ask before deleting the highlighted comment, if we wrote it
+    pub fn confirm_delete_comment(&mut self) {
+        let Some((work_item_id, comment)) = self.own_selected_comment("delete") else {
+            return;
+        };
+        let comment_id = comment.id;
+        self.confirm_action_dialog =
+            Some(ConfirmActionDialog::new(ConfirmActionType::DeleteComment {
+                work_item_id,
+                comment_id,
+            }));
+        self.input_mode = InputMode::ConfirmAction;
+    }
+
+    /// Post the comment being composed, or save the edited one
+    pub fn submit_comment(&mut self) {
+        let Some(draft) = self.comment_draft.take() else {
+            return;
+        };
+        self.input_mode = InputMode::Normal;
+        if draft.text.trim().is_empty() {
+            self.set_status("Empty comment discarded");
+            return;
+        }
+        let Some(client) = self.client() else {
+            return;
+        };
+        let tx = self.comments_sender();
+        let id = draft.work_item_id;
+        let html = draft.html();
+        self.set_status(match draft.comment_id {
+            Some(_) => format!("Saving comment on #{id}..."),
+            None => format!("Posting comment on #{id}..."),
+        });
+        // The newest comment comes first, so that is where a new one shows up
+        if draft.comment_id.is_none() {
+            self.selected_comment = Some((id, 0));
+        }
+
+        tokio::spawn(async move {
+            let result = match draft.comment_id {
+                Some(comment_id) => client
+                    .update_work_item_comment(id, comment_id, &html)
+                    .await
+                    .map(|_| format!("Updated comment on #{id}")),
+                None => client
+                    .add_work_item_comment(id, &html)
+                    .await
+                    .map(|_| format!("Commented on #{id}")),
+            };
+            let _ = tx.send(comments_outcome(&client, id, result).await).await;
+        });
+    }
+
+    fn delete_comment(&mut self, work_item_id: i32, comment_id: i32) {
+        let Some(client) = self.client() else {
+            return;
+        };
+        let tx = self.comments_sender();
+        self.set_status(format!("Deleting comment on #{work_item_id}..."));
+        tokio::spawn(async move {
+            let result = client
+                .delete_work_item_comment(work_item_id, comment_id)
+                .await
+                .map(|()| format!("Deleted comment on #{work_item_id}"));
+            let _ = tx
+                .send(comments_outcome(&client, work_item_id, result).await)
+                .await;
+        });
+    }
+
+    // ========== Work Item Links ==========
+
+    /// Open the link picker for the selected work item
+    pub async fn open_link_dialog(&mut self) {
+        let Some(item) = self.selected_work_item().map(|vi| vi.item.clone()) else {
+            self.set_status("Select a work item first");
+            return;
+        };
+        // Commits need a repository to be looked up in
+        let mut repositories = self.repositories.clone();
+        if repositories.is_empty() {
+            if let Some(client) = self.client() {
+                match client.list_repositories().await {
+                    Ok(repos) => repositories = repos,
+                    Err(e) => self.set_error(format!("Failed to load repositories: {e}")),
+                }
+            }
+        }
+        let items = App::flatten_work_items(&self.work_items);
+        self.link_dialog = Some(LinkDialog::new(&item, &items, repositories));
+        self.input_mode = InputMode::LinkWorkItem;
+    }
+
+    /// Targets the link dialog offers for its search
+    pub fn link_candidates(&self) -> Vec<LinkTarget> {
+        let Some(dialog) = &self.link_dialog else {
+            return Vec::new();
+        };
+        let items = App::flatten_work_items(&self.work_items);
+        let mut pull_requests: Vec<crate::azure::PullRequest> = Vec::new();
+        for pr in [&self.pr_active, &self.pr_mine, &self.pr_completed]
+            .into_iter()
+            .flatten()
+        {
+            if !pull_requests
+                .iter()
+                .any(|p| p.pull_request_id == pr.pull_request_id)
+            {
+                pull_requests.push(pr.clone());
+            }
+        }
+        dialog.candidates(&items, &pull_requests)
+    }
+
+    /// Link the selected item to the highlighted candidate
+    pub fn submit_link(&mut self) {
+        let Some(dialog) = &self.link_dialog else {
+            return;
+        };
+        let Some(target) = self.link_candidates().get(dialog.cursor).cloned() else {
+            self.set_status(format!(
+                "Enter a {}",
+                dialog.kind.placeholder().to_lowercase()
+            ));
+            return;
+        };
+        if self.link_rx.is_some() {
+            self.set_error("A link change is still running");
+            return;
+        }
+        let Some(client) = self.client() else {
+            return;
+        };
+        let Some(dialog) = self.link_dialog.take() else {
+            return;
+        };
+        self.input_mode = InputMode::Normal;
+
+        let id = dialog.work_item_id;
+        let kind = dialog.kind;
+        // Parents record their children as links, so reparenting changes both
+        let (parent, others, message) = match &target {
+            LinkTarget::WorkItem { id: target_id, .. }
+                if kind == crate::work_item_link::LinkKind::Parent =>
+            {
+                let others = dialog.parent_id.into_iter().chain([*target_id]).collect();
+                let message = format!("Moved #{id} under #{target_id}");
+                (Some(Some(*target_id)), others, message)
+            }
+            LinkTarget::WorkItem { id: target_id, .. } => {
+                let message = format!("Linked #{target_id} to #{id} as {}", kind.label());
+                (None, vec![*target_id], message)
+            }
+            LinkTarget::NoParent(parent_id) => {
+                let message = format!("Removed #{id} from under #{parent_id}");
+                (Some(None), vec![*parent_id], message)
+            }
+            LinkTarget::PullRequest { id: pr_id, .. } => {
+                (None, Vec::new(), format!("Linked PR !{pr_id} to #{id}"))
+            }
+            LinkTarget::Commit { hash, .. } => {
+                let short = &hash[..hash.len().min(7)];
+                (None, Vec::new(), format!("Linked commit {short} to #{id}"))
+            }
+        };
+        let org_url = self
+            .current_project()
+            .map(|p| p.organization.clone())
+            .unwrap_or_default();
+        let repositories = dialog.repositories;
+
+        let (tx, rx) = mpsc::channel(1);
+        self.link_rx = Some(rx);
+        self.set_status(format!("Linking #{id}..."));
+        tokio::spawn(async move {
+            let result = async {
+                let (add, remove) = crate::work_item_link::link_changes(
+                    &client,
+                    &org_url,
+                    kind,
+                    &target,
+                    &repositories,
+                )
+                .await?;
+                client.update_work_item_links(id, &add, &remove).await
+            }
+            .await
+            .map(|item| (item, message))
+            .map_err(|e| format!("Failed to link #{id}: {e}"));
+            let _ = tx
+                .send(LinkOutcome {
+                    parent,
+                    others,
+                    result,
+                })
+                .await;
+        });
+    }
+
+    /// Ask before removing the selected reference from the work item
+    pub fn confirm_remove_link(&mut self) {
+        let Some(work_item_id) = self.selected_work_item().map(|vi| vi.item.id) else {
+            return;
+        };
+        let Some(relation) = self.selected_relation().cloned() else {
+            self.set_status("No reference to remove");
+            return;
+        };
+        let description = self.parse_relation(&relation).description;
+        self.confirm_action_dialog =
+            Some(ConfirmActionDialog::new(ConfirmActionType::RemoveLink {
+                work_item_id,
+                relation,
+                description,
+            }));
+        self.input_mode = InputMode::ConfirmAction;
+    }
+
+    fn remove_link(&mut self, work_item_id: i32, relation: WorkItemRelation) {
+        if self.link_rx.is_some() {
+            self.set_error("A link change is still running");
+            return;
+        }
+        let Some(client) = self.client() else {
+            return;
+        };
+        // Removing a child link moves the child to the top level
+        let others = relation.target_id().into_iter().collect();
+        let (tx, rx) = mpsc::channel(1);
+        self.link_rx = Some(rx);
+        self.set_status(format!("Removing link from #{work_item_id}..."));
+        tokio::spawn(async move {
+            let result = client
+                .update_work_item_links(work_item_id, &[], &[relation])
+                .await
+                .map(|item| (item, format!("Removed link from #{work_item_id}")))
+                .map_err(|e| format!("Failed to remove link: {e}"));
+            let _ = tx
+                .send(LinkOutcome {
+                    parent: None,
+                    others,
+                    result,
+                })
+                .await;
+        });
+    }
+
+    /// Apply a finished link change: the item takes its new place in the
+    /// hierarchy, and the other ends reload their links
+    pub fn poll_links(&mut self) {
+        let Some(rx) = &mut self.link_rx else {
+            return;
+        };
+        let outcome = match rx.try_recv() {
+            Ok(outcome) => outcome,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                self.link_rx = None;
+                return;
+            }
+        };
+        self.link_rx = None;
+
+        let (mut item, message) = match outcome.result {
+            Ok(done) => done,
+            Err(e) => {
+                self.set_error(e);
+                return;
+            }
+        };
+        if let Some(parent) = outcome.parent {
+            // Not every response carries System.Parent, and the list keeps the
+            // old one when it is missing
+            item.fields.parent_id = parent;
+            fn set_parent(items: &mut [WorkItem], id: i32, parent: Option<i32>) {
+                for item in items {
+                    if item.id == id {
+                        item.fields.parent_id = parent;
+                    }
+                    set_parent(&mut item.children, id, parent);
+                }
+            }
+            set_parent(&mut self.work_items, item.id, parent);
+            self.expanded_items.extend(parent);
+        }
+        for id in &outcome.others {
+            self.relations_loaded.remove(id);
+        }
+        self.relations_loaded.insert(item.id);
+        self.replace_work_items(vec![item]);
+        self.set_status(message);
+        self.start_relations_loader();
+        self.save_to_cache();
+    }
+
+    // ========== Embedded Terminal Methods ==========
+
+    /// Edit the selected HTML form field as Markdown in `$EDITOR`
+    pub fn edit_form_field_in_editor(&mut self, cols: u16, rows: u16) -> anyhow::Result<()> {
+        let Some(form) = &self.work_item_form else {
+            return Ok(());
+        };
+        let Some(field) = form.selected_field().filter(|f| f.kind == FieldKind::Html) else {
+            return Ok(());
+        };
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let path = format!("/tmp/lazyops-{}-{timestamp}.md", form.id);
+        std::fs::write(&path, &field.value)?;
+
+        let mut terminal = EmbeddedTerminal::new(cols, rows)?;
+        terminal.spawn_editor(&path)?;
+
+        self.editor_file = Some((path, field.name.clone()));
+        self.embedded_terminal = Some(terminal);
+        self.terminal_mode = true;
+
+        Ok(())
+    }
+
+    /// Pick up the edited file once the user quits `$EDITOR`
+    pub fn poll_editor(&mut self) {
+        let exited = self
+            .embedded_terminal
+            .as_mut()
+            .is_some_and(|t| t.editor_exited());
+        if exited && self.editor_file.is_some() {
+            self.finish_editor();
+        }
+    }
+
+    /// Copy the `$EDITOR` file back into the form and leave terminal mode
+    pub fn finish_editor(&mut self) {
+        let Some((path, field)) = self.editor_file.take() else {
+            return;
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => {
+                if let Some(form) = &mut self.work_item_form {
+                    form.set_value(&field, text.trim_end().to_string());
+                }
+                self.set_status("Description edited - Ctrl+s to save");
+            }
+            Err(e) => self.set_error(format!("Failed to read {path}: {e}")),
+        }
+        let _ = std::fs::remove_file(&path);
+        self.close_embedded_terminal();
+    }
+
+    /// Open log viewer in nvim with auto-reload
+    /// Creates a temp file and spawns nvim with autoread settings
+    pub fn open_log_viewer(&mut self, cols: u16, rows: u16) -> anyhow::Result<()> {
+        // Create temp log file
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let log_path = format!("/tmp/lazyops-{timestamp}.log");
+
+        // Write current log content to temp file
+        if !self.build_log_lines.is_empty() {
+            std::fs::write(&log_path, self.build_log_lines.join("\n"))?;
+        } else {
+            std::fs::write(&log_path, "")?;
+        }
+
+        // Spawn embedded terminal with nvim
+        let mut terminal = EmbeddedTerminal::new(cols, rows)?;
+        terminal.spawn_log_viewer(&log_path)?;
+
+        self.embedded_terminal = Some(terminal);
+        self.terminal_mode = true;
+        self.log_file_path = Some(log_path);
+
+        Ok(())
+    }
+
+    /// Update the log file with new content (for live updates)
+    #[allow(dead_code)]
+    pub fn update_log_file(&self) -> anyhow::Result<()> {
+        if let Some(ref path) = self.log_file_path {
+            std::fs::write(path, self.build_log_lines.join("\n"))?;
+        }
+        Ok(())
+    }
+
+    /// Close the embedded terminal and cleanup
+    pub fn close_embedded_terminal(&mut self) {
+        if let Some(ref mut term) = self.embedded_terminal {
+            term.stop();
+        }
+        self.embedded_terminal = None;
+        self.terminal_mode = false;
+
+        // Cleanup temp file
+        if let Some(ref path) = self.log_file_path {
+            let _ = std::fs::remove_file(path);
+        }
+        self.log_file_path = None;
+    }
+
+    /// Send data to the embedded terminal
+    pub fn send_to_terminal(&mut self, data: &[u8]) -> anyhow::Result<()> {
+        if let Some(ref mut term) = self.embedded_terminal {
+            term.write(data)?;
+        }
+        Ok(())
+    }
+
+    /// Resize the embedded terminal
+    pub fn resize_terminal(&mut self, cols: u16, rows: u16) -> anyhow::Result<()> {
+        if let Some(ref mut term) = self.embedded_terminal {
+            term.resize(cols, rows)?;
+        }
+        Ok(())
+    }
+
+    /// Start background PR data loader
+    pub fn start_pr_loader(&mut self) {
+        if self.pr_loading {
+            return;
+        }
+
+        // Try cache first
+        if let Some(project) = self.current_project() {
+            let project_name = project.name.clone();
+            if let Some(cached) = self.cache.load_pr(&project_name) {
+                let age = cached.age_seconds();
+                self.repositories = cached.repos;
+                if !self.repositories.is_empty() {
+                    self.repo_list_state.select(Some(0));
+                }
+                let mins = age / 60;
+                if mins > 0 {
+                    self.set_status(format!("Repos cached ({mins}m ago) - r to refresh"));
+                } else {
+                    self.set_status(format!("Repos cached ({age}s ago) - r to refresh"));
+                }
+                // Still set up the channel for later PR loads
+                let (tx, rx) = mpsc::channel(50);
+                self.pr_rx = Some(rx);
+                self.pr_tx = Some(tx);
+
+                // Auto-restore last repo
+                if let Some(last_repo) = self.cache.load_last_repo(&project_name) {
+                    if let Some(idx) = self.repositories.iter().position(|r| r.name == last_repo) {
+                        self.selected_repo_idx = idx;
+                        self.repo_list_state.select(Some(idx));
+                        self.pr_drill_down = PRDrillDown::PRs;
+                        self.load_prs_for_repo();
+                    }
+                }
+                return;
+            }
+        }
+
+        self.pr_loading = true;
+
+        let (tx, rx) = mpsc::channel(50);
+        self.pr_rx = Some(rx);
+        self.pr_tx = Some(tx.clone());
+
+        let client_info = self.client();
+
+        if let Some(client) = client_info {
+            let tx_repos = tx.clone();
+            tokio::spawn(async move {
+                match client.list_repositories().await {
+                    Ok(repos) => {
+                        let _ = tx_repos.send(PRLoadResult::Repositories(repos)).await;
+                    }
+                    Err(e) => {
+                        let _ = tx_repos.send(PRLoadResult::Error(e.to_string())).await;
+                    }
+                }
+            });
+        }
+    }
+
+    /// Start PR loader bypassing cache (for refresh)
+    pub fn start_pr_loader_fresh(&mut self) {
+        if self.pr_loading {
+            return;
+        }
+        self.pr_loading = true;
+
+        let (tx, rx) = mpsc::channel(50);
+        self.pr_rx = Some(rx);
+        self.pr_tx = Some(tx.clone());
+
+        let client_info = self.client();
+
+        if let Some(client) = client_info {
+            let tx_repos = tx.clone();
+            tokio::spawn(async move {
+                match client.list_repositories().await {
+                    Ok(repos) => {
+                        let _ = tx_repos.send(PRLoadResult::Repositories(repos)).await;
+                    }
+                    Err(e) => {
+                        let _ = tx_repos.send(PRLoadResult::Error(e.to_string())).await;
+                    }
+                }
+            });
+        }
+    }
+
+    /// Load all 4 PR panes for the selected repository (non-blocking, stale-while-revalidate)
+    pub fn load_prs_for_repo(&mut self) {
+        let repo_name = match self.repositories.get(self.selected_repo_idx) {
+            Some(r) => r.name.clone(),
+            None => return,
+        };
+        let repo_id = self.repositories[self.selected_repo_idx].id.clone();
+
+        self.current_repo_id = Some(repo_id);
+        self.current_repo_name = Some(repo_name.clone());
+
+        // Persist last repo choice
+        if let Some(project) = self.current_project() {
+            let _ = self.cache.save_last_repo(&project.name, &repo_name);
+        }
+
+        // Try PR list cache first (stale-while-revalidate)
+        if let Some(project) = self.current_project() {
+            if let Some(cached) = self.cache.load_pr_list(&project.name, &repo_name) {
+                let age = cached.age_seconds();
+                let stale = cached.needs_refresh(self.short_cache_ttl());
+                self.pr_active = cached.active;
+                self.pr_mine = cached.mine;
+                self.pr_completed = cached.completed;
+                self.pr_abandoned = cached.abandoned;
+                if !self.pull_requests().is_empty() {
+                    self.pr_list_state.select(Some(0));
+                }
+                let mins = age / 60;
+                if mins > 0 {
+                    self.set_status(format!("PRs cached ({mins}m ago)"));
+                } else {
+                    self.set_status(format!("PRs cached ({age}s ago)"));
+                }
+                if !stale {
+                    return;
+                }
+            }
+        }
+
+        self.fetch_pr_panes(&repo_name);
+    }
+
+    /// Fetch all 4 PR panes for a repository in the background
+    fn fetch_pr_panes(&self, repo_name: &str) {
+        let current_user = self.current_user.clone();
+
+        if let Some(tx) = &self.pr_tx {
+            // Fire off all 4 loads in parallel
+            for &pane in &[
+                PRFocus::Active,
+                PRFocus::Mine,
+                PRFocus::Completed,
+                PRFocus::Abandoned,
+            ] {
+                let tx = tx.clone();
+                let client = self.client();
+                let repo = repo_name.to_string();
+                let user = current_user.clone();
+                let status = pane.filter_status().to_string();
+                let is_mine = pane.is_mine();
+
+                if let Some(client) = client {
+                    tokio::spawn(async move {
+                        let creator = if is_mine { user.as_deref() } else { None };
+                        match client
+                            .list_pull_requests(Some(&repo), &status, creator, Some(50))
+                            .await
+                        {
+                            Ok(prs) => {
+                                let _ = tx.send(PRLoadResult::PullRequests(pane, prs)).await;
+                            }
+                            Err(e) => {
+                                let _ = tx.send(PRLoadResult::Error(e.to_string())).await;
+                            }
+                        }
+                    });
+                }
+            }
+        }
+    }
+
+    /// Refresh only the current pane's PRs (non-blocking)
+    #[allow(dead_code)]
+    pub fn refresh_current_pr_pane(&mut self) {
+        let repo_name = match &self.current_repo_name {
+            Some(n) => n.clone(),
+            None => return,
+        };
+
+        let pane = if self.pr_focus.is_list() {
+            self.pr_focus
+        } else {
+            self.pr_last_list_focus
+        };
+        let current_user = self.current_user.clone();
+        let status = pane.filter_status().to_string();
+        let is_mine = pane.is_mine();
+
+        if let Some(tx) = &self.pr_tx {
+            let tx = tx.clone();
+            let client = self.client();
+
+            if let Some(client) = client {
+                tokio::spawn(async move {
+                    let creator = if is_mine {
+                        current_user.as_deref()
+                    } else {
+                        None
+                    };
+                    match client
+                        .list_pull_requests(Some(&repo_name), &status, creator, Some(50))
+                        .await
+                    {
+                        Ok(prs) => {
+                            let _ = tx.send(PRLoadResult::PullRequests(pane, prs)).await;
+                        }
+                        Err(e) => {
+                            let _ = tx.send(PRLoadResult::Error(e.to_string())).await;
+                        }
+                    }
+                });
+            }
+        }
+    }
+
+    /// Load PR details (threads, policies) for selected PR
+    pub fn load_pr_detail(&mut self) {
+        let pr = match self.pull_requests().get(self.selected_pr_idx) {
+            Some(p) => p.clone(),
+            None => return,
+        };
+        let pr_id = pr.pull_request_id;
+        let repo_id = self.current_repo_id.clone().unwrap_or_default();
+
+        self.selected_pr_detail = Some(pr);
+        self.pr_threads.clear();
+        self.pr_policies.clear();
+        self.pr_preview_scroll = 0;
+        self.selected_thread_idx = 0;
+
+        eprintln!(
+            "[DEBUG] load_pr_detail: pr_tx={}, pr_id={}",
+            self.pr_tx.is_some(),
+            pr_id
+        );
+        if let Some(tx) = &self.pr_tx {
+            let tx_threads = tx.clone();
+            let tx_policies = tx.clone();
+
+            let client = self.client();
+            eprintln!("[DEBUG] load_pr_detail: client={}", client.is_some());
+
+            if let Some(client) = client {
+                let client2 = client.clone();
+
+                // Load threads
+                tokio::spawn(async move {
+                    match client.list_pr_threads(&repo_id, pr_id).await {
+                        Ok(threads) => {
+                            let _ = tx_threads.send(PRLoadResult::PRThreads(threads)).await;
+                        }
+                        Err(e) => {
+                            let _ = tx_threads.send(PRLoadResult::Error(e.to_string())).await;
+                        }
+                    }
+                });
+
+                // Load policies
+                tokio::spawn(async move {
+                    match client2.list_pr_policies(pr_id).await {
+                        Ok(policies) => {
+                            eprintln!(
+                                "[DEBUG] Loaded {} policies for PR #{}",
+                                policies.len(),
+                                pr_id
+                            );
+                            let _ = tx_policies.send(PRLoadResult::PRPolicies(policies)).await;
+                        }
+                        Err(e) => {
+                            eprintln!("[DEBUG] Policy load error for PR #{pr_id}: {e}");
+                            let _ = tx_policies.send(PRLoadResult::Error(e.to_string())).await;
+                        }
+                    }
+                });
+            }
+        }
+    }
 
-        // Write current log content to temp file
-        if !self.build_log_lines.is_empty() {
-            std::fs::write(&log_path, self.build_log_lines.join("\n"))?;
+    /// The PR being previewed, or the one selected in the list
+    pub fn selected_pr(&self) -> Option<&crate::azure::PullRequest> {
+        if self.pr_focus == PRFocus::Preview || self.pr_drill_down == PRDrillDown::Files {
+            self.selected_pr_detail.as_ref()
+        } else if self.pr_drill_down == PRDrillDown::PRs {
+            self.pull_requests().get(self.selected_pr_idx)
         } else {
-            std::fs::write(&log_path, "")?;
+            None
         }
+    }
 
-        // Spawn embedded terminal with nvim
-        let mut terminal = EmbeddedTerminal::new(cols, rows)?;
-        terminal.spawn_log_viewer(&log_path)?;
+    /// Comment threads shown in the Threads tab (skips deleted and system-only threads)
+    pub fn visible_pr_threads(&self) -> Vec<&crate::azure::PRThread> {
+        self.pr_threads.iter().filter(|t| t.is_visible()).collect()
+    }
 
-        self.embedded_terminal = Some(terminal);
-        self.terminal_mode = true;
-        self.log_file_path = Some(log_path);
+    /// Thread selected in the Threads tab, or anchored to the diff cursor line
+    /// (only while the preview has focus)
+    pub fn selected_pr_thread(&self) -> Option<&crate::azure::PRThread> {
+        if let Some(diff) = self.focused_file_diff() {
+            return diff.thread_at_cursor(&self.pr_threads);
+        }
+        if self.pr_focus != PRFocus::Preview || self.pr_preview_tab != PRPreviewTab::Threads {
+            return None;
+        }
+        self.visible_pr_threads()
+            .get(self.selected_thread_idx)
+            .copied()
+    }
 
-        Ok(())
+    /// Repository ID for a PR, falling back to the current repository
+    fn pr_repository_id(&self, pr: &crate::azure::PullRequest) -> String {
+        pr.repository
+            .as_ref()
+            .map(|r| r.id.clone())
+            .or_else(|| self.current_repo_id.clone())
+            .unwrap_or_default()
     }
 
-    /// Update the log file with new content (for live updates)
-    #[allow(dead_code)]
-    pub fn update_log_file(&self) -> anyhow::Result<()> {
-        if let Some(ref path) = self.log_file_path {
-            std::fs::write(path, self.build_log_lines.join("\n"))?;
+    /// Sender for PR results, creating the channel if the PR loader hasn't
+    fn pr_sender(&mut self) -> mpsc::Sender<PRLoadResult> {
+        if let Some(tx) = &self.pr_tx {
+            return tx.clone();
         }
-        Ok(())
+        let (tx, rx) = mpsc::channel(50);
+        self.pr_rx = Some(rx);
+        self.pr_tx = Some(tx.clone());
+        tx
     }
 
-    /// Close the embedded terminal and cleanup
-    pub fn close_embedded_terminal(&mut self) {
-        if let Some(ref mut term) = self.embedded_terminal {
-            term.stop();
-        }
-        self.embedded_terminal = None;
-        self.terminal_mode = false;
+    /// Repository new PRs go to: the open one, the project default, then the last used
+    fn pr_create_repository(&self) -> Option<String> {
+        self.current_repo_name.clone().or_else(|| {
+            let project = self.current_project()?;
+            project
+                .repository
+                .clone()
+                .or_else(|| self.cache.load_last_repo(&project.name))
+        })
+    }
 
-        // Cleanup temp file
-        if let Some(ref path) = self.log_file_path {
-            let _ = std::fs::remove_file(path);
+    /// Open the PR creation dialog (linked to the selected work item in the Tasks view)
+    pub fn open_pr_create(&mut self) {
+        let Some(repository) = self.pr_create_repository() else {
+            self.set_error(
+                "No repository: open one in the PRs view or set `repository` for the project",
+            );
+            return;
+        };
+        let dialog = match self.selected_work_item() {
+            Some(item) if self.current_view == View::Tasks => PRCreateDialog::for_work_item(
+                repository.clone(),
+                item.item.id,
+                item.item.fields.title.clone(),
+            ),
+            _ => PRCreateDialog::new(repository.clone()),
+        };
+        self.pr_create_dialog = Some(dialog);
+        self.input_mode = InputMode::PRCreate;
+
+        let Some(client) = self.client() else {
+            return;
+        };
+        let tx = self.pr_sender();
+        tokio::spawn(async move {
+            let result = match client.list_branches(&repository).await {
+                Ok(refs) => {
+                    let mut branches: Vec<String> = refs
+                        .iter()
+                        .map(|r| crate::azure::PullRequest::short_branch(&r.name).to_string())
+                        .collect();
+                    branches.sort();
+                    PRLoadResult::Branches {
+                        repository,
+                        branches,
+                    }
+                }
+                Err(e) => PRLoadResult::PRCreateFailed(format!("Failed to load branches: {e}")),
+            };
+            let _ = tx.send(result).await;
+        });
+    }
+
+    /// Candidates for the focused picker in the PR creation dialog
+    pub fn pr_create_candidates(&self) -> Vec<Candidate> {
+        let Some(dialog) = &self.pr_create_dialog else {
+            return Vec::new();
+        };
+        let work_items = App::flatten_work_items(&self.work_items);
+        dialog.candidates(&self.users, &work_items, self.current_user.as_deref())
+    }
+
+    /// Apply the highlighted picker candidate
+    pub fn pick_pr_create_candidate(&mut self) {
+        let candidates = self.pr_create_candidates();
+        if let Some(dialog) = &mut self.pr_create_dialog {
+            if let Some(candidate) = candidates.into_iter().nth(dialog.cursor) {
+                dialog.pick(candidate);
+            }
         }
-        self.log_file_path = None;
     }
 
-    /// Send data to the embedded terminal
-    pub fn send_to_terminal(&mut self, data: &[u8]) -> anyhow::Result<()> {
-        if let Some(ref mut term) = self.embedded_terminal {
-            term.write(data)?;
+    /// Create the PR described by the dialog
+    pub fn submit_pr_create(&mut self) {
+        let Some(dialog) = &mut self.pr_create_dialog else {
+            return;
+        };
+        if dialog.submitting {
+            return;
         }
-        Ok(())
+        if let Some(problem) = dialog.validate() {
+            self.set_error(problem);
+            return;
+        }
+        dialog.submitting = true;
+        let request = dialog.to_request();
+
+        let Some(client) = self.client() else {
+            return;
+        };
+        let tx = self.pr_sender();
+        self.set_status("Creating pull request...");
+        tokio::spawn(async move {
+            let result = match client.create_pr(&request).await {
+                Ok(pr) => PRLoadResult::PRCreated(Box::new(pr)),
+                Err(e) => PRLoadResult::PRCreateFailed(e.to_string()),
+            };
+            let _ = tx.send(result).await;
+        });
     }
 
-    /// Resize the embedded terminal
-    pub fn resize_terminal(&mut self, cols: u16, rows: u16) -> anyhow::Result<()> {
-        if let Some(ref mut term) = self.embedded_terminal {
-            term.resize(cols, rows)?;
+    /// Open the completion dialog for the selected PR and evaluate its policies
+    pub fn open_pr_complete(&mut self) {
+        let Some(pr) = self.selected_pr() else {
+            self.set_status("Select a pull request first");
+            return;
+        };
+        let status = pr.status.as_deref().unwrap_or("active");
+        if status != "active" {
+            self.set_status(format!("Cannot complete a {status} PR"));
+            return;
         }
-        Ok(())
+        if pr.is_draft {
+            self.set_status("Publish the draft before completing it");
+            return;
+        }
+        let dialog = PRCompleteDialog::new(pr);
+        let pr_id = dialog.pr_id;
+        self.pr_complete_dialog = Some(dialog);
+        self.input_mode = InputMode::PRComplete;
+
+        let Some(client) = self.client() else {
+            return;
+        };
+        let tx = self.pr_sender();
+        tokio::spawn(async move {
+            let result = match client.list_pr_policies(pr_id).await {
+                Ok(policies) => PRLoadResult::PRCompletePolicies { pr_id, policies },
+                Err(e) => PRLoadResult::PRCompleteFailed(format!("Failed to load policies: {e}")),
+            };
+            let _ = tx.send(result).await;
+        });
     }
 
-    /// Start background PR data loader
-    pub fn start_pr_loader(&mut self) {
-        if self.pr_loading {
+    /// Complete (or set auto-complete on) the PR in the completion dialog
+    pub fn submit_pr_complete(&mut self) {
+        let Some(dialog) = &mut self.pr_complete_dialog else {
+            return;
+        };
+        if dialog.submitting {
+            return;
+        }
+        if let Some(problem) = dialog.validate() {
+            self.set_error(problem);
             return;
         }
+        dialog.submitting = true;
+        let pr_id = dialog.pr_id;
+        let auto_complete = dialog.auto_complete;
+        let options = dialog.to_options();
 
-        // Try cache first
-        if let Some(project) = self.current_project() {
-            let project_name = project.name.clone();
-            if let Some(cached) = cache::load_pr(&project_name) {
-                let age = cached.age_seconds();
-                self.repositories = cached.repos;
-                if !self.repositories.is_empty() {
-                    self.repo_list_state.select(Some(0));
-                }
-                let mins = age / 60;
-                if mins > 0 {
-                    self.set_status(format!("Repos cached ({mins}m ago) - r to refresh"));
-                } else {
-                    self.set_status(format!("Repos cached ({age}s ago) - r to refresh"));
-                }
-                // Still set up the channel for later PR loads
-                let (tx, rx) = mpsc::channel(50);
-                self.pr_rx = Some(rx);
-                self.pr_tx = Some(tx);
+        let Some(client) = self.client() else {
+            return;
+        };
+        let tx = self.pr_sender();
+        self.set_status(if auto_complete {
+            format!("Setting auto-complete on PR #{pr_id}...")
+        } else {
+            format!("Completing PR #{pr_id}...")
+        });
+        tokio::spawn(async move {
+            let result = match client.complete_pr(pr_id, &options, auto_complete).await {
+                Ok(pr) => PRLoadResult::PRCompleted {
+                    pr: Box::new(pr),
+                    auto_complete,
+                },
+                Err(e) => PRLoadResult::PRCompleteFailed(e.to_string()),
+            };
+            let _ = tx.send(result).await;
+        });
+    }
 
-                // Auto-restore last repo
-                if let Some(last_repo) = cache::load_last_repo(&project_name) {
-                    if let Some(idx) = self.repositories.iter().position(|r| r.name == last_repo) {
-                        self.selected_repo_idx = idx;
-                        self.repo_list_state.select(Some(idx));
-                        self.pr_drill_down = PRDrillDown::PRs;
-                        self.load_prs_for_repo();
-                    }
-                }
+    /// Drill into the files changed by the selected PR
+    pub fn open_pr_files(&mut self) {
+        if self.pr_focus.is_list() {
+            if self.pull_requests().get(self.selected_pr_idx).is_none() {
+                self.set_status("Select a pull request first");
                 return;
             }
+            self.load_pr_detail();
+            self.pr_last_list_focus = self.pr_focus;
         }
+        let Some(pr) = &self.selected_pr_detail else {
+            return;
+        };
+        self.pr_files = Some(PRFiles::new(pr.pull_request_id, self.pr_repository_id(pr)));
+        self.pr_drill_down = PRDrillDown::Files;
+        self.pr_focus = self.pr_last_list_focus;
+        self.pr_search_query.clear();
+        self.load_pr_iterations();
+    }
 
-        self.pr_loading = true;
+    /// Back from the file list to the PR list
+    pub fn close_pr_files(&mut self) {
+        self.pr_files = None;
+        self.pr_drill_down = PRDrillDown::PRs;
+        self.pr_focus = self.pr_last_list_focus;
+        self.pr_search_query.clear();
+    }
 
-        let (tx, rx) = mpsc::channel(50);
-        self.pr_rx = Some(rx);
-        self.pr_tx = Some(tx.clone());
+    /// Reload iterations (and then the changed files) of the open PR
+    pub fn load_pr_iterations(&mut self) {
+        let Some(files) = &mut self.pr_files else {
+            return;
+        };
+        files.loading = true;
+        let pr_id = files.pr_id;
+        let repository_id = files.repository_id.clone();
 
-        let client_info = self.current_project().map(crate::azure::AzureCli::new);
+        let Some(client) = self.client() else {
+            return;
+        };
+        let tx = self.pr_sender();
+        tokio::spawn(async move {
+            let result = match client.list_pr_iterations(&repository_id, pr_id).await {
+                Ok(iterations) => PRLoadResult::PRIterations { pr_id, iterations },
+                Err(e) => PRLoadResult::PRFilesFailed(format!("Failed to load iterations: {e}")),
+            };
+            let _ = tx.send(result).await;
+        });
+    }
 
-        if let Some(client) = client_info {
-            let tx_repos = tx.clone();
-            tokio::spawn(async move {
-                match client.list_repositories().await {
-                    Ok(repos) => {
-                        let _ = tx_repos.send(PRLoadResult::Repositories(repos)).await;
-                    }
-                    Err(e) => {
-                        let _ = tx_repos.send(PRLoadResult::Error(e.to_string())).await;
-                    }
-                }
-            });
-        }
+    /// Load the files changed in the selected iteration
+    fn load_pr_changes(&mut self) {
+        let Some(files) = &mut self.pr_files else {
+            return;
+        };
+        let Some((iteration_id, compare_to)) = files.compared_iterations() else {
+            // Nothing pushed yet
+            files.loading = false;
+            return;
+        };
+        files.loading = true;
+        let pr_id = files.pr_id;
+        let iteration = files.iteration;
+        let repository_id = files.repository_id.clone();
+
+        let Some(client) = self.client() else {
+            return;
+        };
+        let tx = self.pr_sender();
+        tokio::spawn(async move {
+            let result = match client
+                .list_pr_changes(&repository_id, pr_id, iteration_id, compare_to)
+                .await
+            {
+                Ok(changes) => PRLoadResult::PRChanges {
+                    pr_id,
+                    iteration,
+                    changes,
+                },
+                Err(e) => PRLoadResult::PRFilesFailed(format!("Failed to load changes: {e}")),
+            };
+            let _ = tx.send(result).await;
+        });
     }
 
-    /// Start PR loader bypassing cache (for refresh)
-    pub fn start_pr_loader_fresh(&mut self) {
-        if self.pr_loading {
+    /// Switch the file list between all changes and single iterations
+    pub fn cycle_pr_iteration(&mut self, forward: bool) {
+        let Some(files) = &mut self.pr_files else {
             return;
+        };
+        if files.cycle_iteration(forward) {
+            let label = files.iteration_label();
+            self.set_status(label);
+            self.load_pr_changes();
+        } else {
+            self.set_status("This pull request has a single iteration");
         }
-        self.pr_loading = true;
+    }
 
-        let (tx, rx) = mpsc::channel(50);
-        self.pr_rx = Some(rx);
-        self.pr_tx = Some(tx.clone());
+    /// Changed files in the list, filtered by the search query
+    pub fn pr_visible_changes(&self) -> Vec<&PRChange> {
+        let Some(files) = &self.pr_files else {
+            return Vec::new();
+        };
+        files
+            .changes
+            .iter()
+            .filter(|c| {
+                self.pr_search_query.is_empty()
+                    || self
+                        .fuzzy_matcher
+                        .fuzzy_match(&c.path, &self.pr_search_query)
+                        .is_some()
+            })
+            .collect()
+    }
 
-        let client_info = self.current_project().map(crate::azure::AzureCli::new);
+    pub fn selected_pr_change(&self) -> Option<&PRChange> {
+        let selected = self.pr_files.as_ref()?.selected;
+        self.pr_visible_changes().get(selected).copied()
+    }
 
-        if let Some(client) = client_info {
-            let tx_repos = tx.clone();
-            tokio::spawn(async move {
-                match client.list_repositories().await {
-                    Ok(repos) => {
-                        let _ = tx_repos.send(PRLoadResult::Repositories(repos)).await;
-                    }
-                    Err(e) => {
-                        let _ = tx_repos.send(PRLoadResult::Error(e.to_string())).await;
-                    }
-                }
-            });
+    /// Move the file list selection by `delta` rows
+    pub fn move_pr_file(&mut self, delta: isize) {
+        let count = self.pr_visible_changes().len();
+        if let Some(files) = &mut self.pr_files {
+            files.selected = files
+                .selected
+                .saturating_add_signed(delta)
+                .min(count.saturating_sub(1));
         }
     }
 
-    /// Load all 4 PR panes for the selected repository (non-blocking, stale-while-revalidate)
-    pub fn load_prs_for_repo(&mut self) {
-        let repo_name = match self.repositories.get(self.selected_repo_idx) {
-            Some(r) => r.name.clone(),
-            None => return,
-        };
-        let repo_id = self.repositories[self.selected_repo_idx].id.clone();
+    pub fn select_pr_file(&mut self, idx: usize) {
+        if let Some(files) = &mut self.pr_files {
+            files.selected = idx;
+        }
+    }
 
-        self.current_repo_id = Some(repo_id);
-        self.current_repo_name = Some(repo_name.clone());
+    /// Diff being reviewed in the focused preview pane
+    pub fn focused_file_diff(&self) -> Option<&FileDiff> {
+        if self.pr_drill_down != PRDrillDown::Files || self.pr_focus != PRFocus::Preview {
+            return None;
+        }
+        self.pr_files.as_ref()?.diff.as_ref()
+    }
 
-        // Persist last repo choice
-        if let Some(project) = self.current_project() {
-            let _ = cache::save_last_repo(&project.name, &repo_name);
+    pub fn focused_file_diff_mut(&mut self) -> Option<&mut FileDiff> {
+        if self.pr_drill_down != PRDrillDown::Files || self.pr_focus != PRFocus::Preview {
+            return None;
         }
+        self.pr_files.as_mut()?.diff.as_mut()
+    }
 
-        // Try PR list cache first (stale-while-revalidate)
-        if let Some(project) = self.current_project() {
-            if let Some(cached) = cache::load_pr_list(&project.name, &repo_name) {
-                let age = cached.age_seconds();
-                let stale = cached.needs_refresh();
-                self.pr_active = cached.active;
-                self.pr_mine = cached.mine;
-                self.pr_completed = cached.completed;
-                self.pr_abandoned = cached.abandoned;
-                if !self.pull_requests().is_empty() {
-                    self.pr_list_state.select(Some(0));
-                }
-                let mins = age / 60;
-                if mins > 0 {
-                    self.set_status(format!("PRs cached ({mins}m ago)"));
-                } else {
-                    self.set_status(format!("PRs cached ({age}s ago)"));
+    /// Load the selected file's versions and show their diff in the preview
+    pub fn open_pr_file_diff(&mut self) {
+        let Some(change) = self.selected_pr_change().cloned() else {
+            return;
+        };
+        let Some(files) = &mut self.pr_files else {
+            return;
+        };
+        let (Some(iterations), Some((base, head))) =
+            (files.compared_iterations(), files.compared_commits())
+        else {
+            self.set_status("Iterations are still loading");
+            return;
+        };
+        if files.diff.as_ref().map(|d| &d.change) == Some(&change) {
+            // Already open: keep the cursor where it was
+            self.pr_focus = PRFocus::Preview;
+            return;
+        }
+        files.diff = None;
+        let pr_id = files.pr_id;
+        let repository_id = files.repository_id.clone();
+        self.pr_focus = PRFocus::Preview;
+
+        let Some(client) = self.client() else {
+            return;
+        };
+        let tx = self.pr_sender();
+        tokio::spawn(async move {
+            let old_path = change.original_path.as_deref().unwrap_or(&change.path);
+            let (old, new) = tokio::join!(
+                async {
+                    if change.is_add() {
+                        return Ok(String::new());
+                    }
+                    client
+                        .get_file_content(&repository_id, old_path, &base)
+                        .await
+                },
+                async {
+                    if change.is_delete() {
+                        return Ok(String::new());
+                    }
+                    client
+                        .get_file_content(&repository_id, &change.path, &head)
+                        .await
                 }
-                if !stale {
-                    return;
+            );
+            let result = match (old, new) {
+                (Ok(old), Ok(new)) => PRLoadResult::PRFileDiff {
+                    pr_id,
+                    diff: Box::new(FileDiff::new(change, &old, &new, iterations)),
+                },
+                (Err(e), _) | (_, Err(e)) => {
+                    PRLoadResult::PRFilesFailed(format!("Failed to load {}: {e}", change.path))
                 }
-            }
-        }
+            };
+            let _ = tx.send(result).await;
+        });
+    }
 
-        // Background refresh
-        let current_user = self.current_user.clone();
+    /// Open the vote dialog for the selected PR
+    pub fn open_pr_vote(&mut self) {
+        if self.selected_pr().is_none() {
+            self.set_status("Select a pull request first");
+            return;
+        }
+        self.dropdown_list_state.select(Some(0));
+        self.input_mode = InputMode::PRVote;
+    }
 
-        if let Some(tx) = &self.pr_tx {
-            // Fire off all 4 loads in parallel
-            for &pane in &[
-                PRFocus::Active,
-                PRFocus::Mine,
-                PRFocus::Completed,
-                PRFocus::Abandoned,
-            ] {
-                let tx = tx.clone();
-                let client = self.current_project().map(crate::azure::AzureCli::new);
-                let repo = repo_name.clone();
-                let user = current_user.clone();
-                let status = pane.filter_status().to_string();
-                let is_mine = pane.is_mine();
+    /// Cast a vote on the selected PR, then refresh its reviewers
+    pub fn vote_on_pr(&mut self, vote: &'static str) {
+        let (Some(pr_id), Some(client)) =
+            (self.selected_pr().map(|p| p.pull_request_id), self.client())
+        else {
+            return;
+        };
+        let tx = self.pr_sender();
+        self.set_status(format!("Voting '{vote}' on PR #{pr_id}..."));
 
-                if let Some(client) = client {
-                    tokio::spawn(async move {
-                        let creator = if is_mine { user.as_deref() } else { None };
-                        match client
-                            .list_pull_requests(Some(&repo), &status, creator, Some(50))
-                            .await
-                        {
-                            Ok(prs) => {
-                                let _ = tx.send(PRLoadResult::PullRequests(pane, prs)).await;
-                            }
-                            Err(e) => {
-                                let _ = tx.send(PRLoadResult::Error(e.to_string())).await;
-                            }
-                        }
-                    });
-                }
+        tokio::spawn(async move {
+            if let Err(e) = client.set_pr_vote(pr_id, vote).await {
+                let _ = tx.send(PRLoadResult::Error(e.to_string())).await;
+                return;
             }
-        }
+            let _ = tx
+                .send(PRLoadResult::PRVoted {
+                    pr_id,
+                    vote: vote.to_string(),
+                })
+                .await;
+            if let Ok(pr) = client.get_pull_request(pr_id).await {
+                let _ = tx.send(PRLoadResult::PRDetail(Box::new(pr))).await;
+            }
+        });
     }
 
-    /// Refresh only the current pane's PRs (non-blocking)
-    #[allow(dead_code)]
-    pub fn refresh_current_pr_pane(&mut self) {
-        let repo_name = match &self.current_repo_name {
-            Some(n) => n.clone(),
-            None => return,
+    /// Open the comment composer: a new thread, or a reply to the selected thread
+    pub fn open_pr_comment(&mut self, reply: bool) {
+        let Some(pr) = self.selected_pr() else {
+            self.set_status("Select a pull request first");
+            return;
         };
+        let pr_id = pr.pull_request_id;
+        let repository_id = self.pr_repository_id(pr);
 
-        let pane = if self.pr_focus.is_list() {
-            self.pr_focus
+        let target = if reply {
+            match self.selected_pr_thread() {
+                Some(thread) => CommentTarget::Reply {
+                    thread_id: thread.id,
+                },
+                None => {
+                    self.set_status("Select a thread in the Threads tab to reply");
+                    return;
+                }
+            }
+        } else if let Some(diff) = self.focused_file_diff() {
+            match diff.comment_anchor() {
+                Some(anchor) => CommentTarget::Lines(anchor),
+                None => {
+                    self.set_status("Move to a code line to comment on it");
+                    return;
+                }
+            }
         } else {
-            self.pr_last_list_focus
+            CommentTarget::NewThread
         };
-        let current_user = self.current_user.clone();
-        let status = pane.filter_status().to_string();
-        let is_mine = pane.is_mine();
 
-        if let Some(tx) = &self.pr_tx {
-            let tx = tx.clone();
-            let client = self.current_project().map(crate::azure::AzureCli::new);
+        self.pr_comment_draft = Some(PRCommentDraft {
+            pr_id,
+            repository_id,
+            target,
+            text: String::new(),
+        });
+        self.input_mode = InputMode::PRComment;
+    }
 
-            if let Some(client) = client {
-                tokio::spawn(async move {
-                    let creator = if is_mine {
-                        current_user.as_deref()
-                    } else {
-                        None
-                    };
-                    match client
-                        .list_pull_requests(Some(&repo_name), &status, creator, Some(50))
-                        .await
-                    {
-                        Ok(prs) => {
-                            let _ = tx.send(PRLoadResult::PullRequests(pane, prs)).await;
-                        }
-                        Err(e) => {
-                            let _ = tx.send(PRLoadResult::Error(e.to_string())).await;
-                        }
-                    }
-                });
+    /// Post the comment being composed
+    pub fn submit_pr_comment(&mut self) {
+        let Some(draft) = self.pr_comment_draft.take() else {
+            return;
+        };
+        self.input_mode = InputMode::Normal;
+        let content = draft.text.trim().to_string();
+        if content.is_empty() {
+            self.set_status("Empty comment discarded");
+            return;
+        }
+        if let CommentTarget::Lines(_) = draft.target {
+            if let Some(diff) = self.focused_file_diff_mut() {
+                diff.anchor = None;
             }
         }
+        let Some(client) = self.client() else {
+            return;
+        };
+        let tx = self.pr_sender();
+        self.set_status(format!("Posting comment on PR #{}...", draft.pr_id));
+
+        tokio::spawn(async move {
+            let PRCommentDraft {
+                pr_id,
+                repository_id,
+                target,
+                ..
+            } = draft;
+            let result = match target {
+                CommentTarget::NewThread => {
+                    client
+                        .add_pr_comment(&repository_id, pr_id, &content, None)
+                        .await
+                }
+                CommentTarget::Lines(anchor) => {
+                    client
+                        .add_pr_comment(&repository_id, pr_id, &content, Some(&anchor))
+                        .await
+                }
+                CommentTarget::Reply { thread_id } => {
+                    client
+                        .reply_pr_thread(&repository_id, pr_id, thread_id, &content)
+                        .await
+                }
+            };
+            let result = match result {
+                Ok(()) => PRLoadResult::PRCommented { pr_id },
+                Err(e) => PRLoadResult::Error(e.to_string()),
+            };
+            let _ = tx.send(result).await;
+        });
     }
 
-    /// Load PR details (threads, policies) for selected PR
-    pub fn load_pr_detail(&mut self) {
-        let pr = match self.pull_requests().get(self.selected_pr_idx) {
-            Some(p) => p.clone(),
-            None => return,
+    /// Resolve the selected thread, or reactivate it if already resolved
+    pub fn toggle_pr_thread_status(&mut self) {
+        let Some(thread) = self.selected_pr_thread() else {
+            self.set_status("Select a thread in the Threads tab first");
+            return;
+        };
+        let thread_id = thread.id;
+        let status = match thread.status.as_deref() {
+            Some("active") | Some("pending") | None => "fixed",
+            _ => "active",
+        };
+        let (Some(pr), Some(client)) = (self.selected_pr_detail.as_ref(), self.client()) else {
+            return;
         };
         let pr_id = pr.pull_request_id;
-        let repo_id = self.current_repo_id.clone().unwrap_or_default();
-
-        self.selected_pr_detail = Some(pr);
-        self.pr_threads.clear();
-        self.pr_policies.clear();
-        self.pr_preview_scroll = 0;
+        let repository_id = self.pr_repository_id(pr);
+        let tx = self.pr_sender();
 
-        eprintln!(
-            "[DEBUG] load_pr_detail: pr_tx={}, pr_id={}",
-            self.pr_tx.is_some(),
-            pr_id
-        );
-        if let Some(tx) = &self.pr_tx {
-            let tx_threads = tx.clone();
-            let tx_policies = tx.clone();
+        tokio::spawn(async move {
+            let result = match client
+                .set_pr_thread_status(&repository_id, pr_id, thread_id, status)
+                .await
+            {
+                Ok(()) => PRLoadResult::PRThreadUpdated {
+                    pr_id,
+                    thread_id,
+                    status: status.to_string(),
+                },
+                Err(e) => PRLoadResult::Error(e.to_string()),
+            };
+            let _ = tx.send(result).await;
+        });
+    }
 
-            let client = self.current_project().map(crate::azure::AzureCli::new);
-            eprintln!("[DEBUG] load_pr_detail: client={}", client.is_some());
+    /// Ask to toggle draft (`draft`) or abandon/reactivate the selected PR
+    pub fn confirm_pr_update(&mut self, draft: bool) {
+        let Some(pr) = self.selected_pr() else {
+            self.set_status("Select a pull request first");
+            return;
+        };
+        let status = pr.status.as_deref().unwrap_or("active");
+        let update = match (draft, status) {
+            (true, "active") if pr.is_draft => PRUpdate::Publish,
+            (true, "active") => PRUpdate::MarkDraft,
+            (true, _) => {
+                self.set_status(format!("Cannot change draft state of a {status} PR"));
+                return;
+            }
+            (false, "active") => PRUpdate::Abandon,
+            (false, "abandoned") => PRUpdate::Reactivate,
+            (false, _) => {
+                self.set_status(format!("Cannot abandon or reactivate a {status} PR"));
+                return;
+            }
+        };
+        self.confirm_action_dialog = Some(ConfirmActionDialog::new(
+            ConfirmActionType::UpdatePullRequest {
+                pr_id: pr.pull_request_id,
+                title: pr.title.clone(),
+                update,
+            },
+        ));
+        self.input_mode = InputMode::ConfirmAction;
+    }
 
-            if let Some(client) = client {
-                let client2 = self
-                    .current_project()
-                    .map(crate::azure::AzureCli::new)
-                    .unwrap();
+    /// Apply a confirmed PR status/draft change
+    fn update_pull_request(&mut self, pr_id: i32, update: PRUpdate) {
+        let Some(client) = self.client() else {
+            return;
+        };
+        let tx = self.pr_sender();
+        let (status, draft) = match update {
+            PRUpdate::Abandon => (Some("abandoned"), None),
+            PRUpdate::Reactivate => (Some("active"), None),
+            PRUpdate::MarkDraft => (None, Some(true)),
+            PRUpdate::Publish => (None, Some(false)),
+        };
+        self.set_status(format!("Updating PR #{pr_id}..."));
 
-                // Load threads
-                tokio::spawn(async move {
-                    match client.list_pr_threads(&repo_id, pr_id).await {
-                        Ok(threads) => {
-                            let _ = tx_threads.send(PRLoadResult::PRThreads(threads)).await;
-                        }
-                        Err(e) => {
-                            let _ = tx_threads.send(PRLoadResult::Error(e.to_string())).await;
-                        }
-                    }
-                });
+        tokio::spawn(async move {
+            let result = match client.update_pr(pr_id, status, None, None, draft).await {
+                Ok(pr) => PRLoadResult::PRUpdated(Box::new(pr)),
+                Err(e) => PRLoadResult::Error(e.to_string()),
+            };
+            let _ = tx.send(result).await;
+        });
+    }
 
-                // Load policies
-                tokio::spawn(async move {
-                    match client2.list_pr_policies(pr_id).await {
-                        Ok(policies) => {
-                            eprintln!(
-                                "[DEBUG] Loaded {} policies for PR #{}",
-                                policies.len(),
-                                pr_id
-                            );
-                            let _ = tx_policies.send(PRLoadResult::PRPolicies(policies)).await;
-                        }
-                        Err(e) => {
-                            eprintln!("[DEBUG] Policy load error for PR #{pr_id}: {e}");
-                            let _ = tx_policies.send(PRLoadResult::Error(e.to_string())).await;
-                        }
-                    }
-                });
-            }
+    /// Reload comment threads if `pr_id` is still being previewed
+    pub fn reload_pr_threads(&self, pr_id: i32) {
+        let Some(pr) = self
+            .selected_pr_detail
+            .as_ref()
+            .filter(|p| p.pull_request_id == pr_id)
+        else {
+            return;
+        };
+        let repository_id = self.pr_repository_id(pr);
+        if let (Some(tx), Some(client)) = (self.pr_tx.clone(), self.client()) {
+            tokio::spawn(async move {
+                let result = match client.list_pr_threads(&repository_id, pr_id).await {
+                    Ok(threads) => PRLoadResult::PRThreads(threads),
+                    Err(e) => PRLoadResult::Error(e.to_string()),
+                };
+                let _ = tx.send(result).await;
+            });
         }
     }
 
@@ -4560,10 +7317,10 @@ impl App {
                     // Save to cache
                     if let Some(project) = self.current_project() {
                         let entry = cache::PRCacheEntry::new(self.repositories.clone());
-                        let _ = cache::save_pr(&project.name, &entry);
+                        let _ = self.cache.save_pr(&project.name, &entry);
 
                         // Auto-restore last repo
-                        if let Some(last_repo) = cache::load_last_repo(&project.name) {
+                        if let Some(last_repo) = self.cache.load_last_repo(&project.name) {
                             if let Some(idx) =
                                 self.repositories.iter().position(|r| r.name == last_repo)
                             {
@@ -4608,7 +7365,7 @@ impl App {
                                 self.pr_completed.clone(),
                                 self.pr_abandoned.clone(),
                             );
-                            let _ = cache::save_pr_list(&project.name, &entry);
+                            let _ = self.cache.save_pr_list(&project.name, &entry);
                         }
                     }
                 }
@@ -4630,9 +7387,148 @@ impl App {
                 }
                 PRLoadResult::PRCommented { pr_id } => {
                     self.set_status(format!("Comment added to PR #{pr_id}"));
+                    self.reload_pr_threads(pr_id);
+                }
+                PRLoadResult::PRUpdated(pr) => {
+                    let pr_id = pr.pull_request_id;
+                    let status = pr.status.clone().unwrap_or_default();
+                    let draft = if pr.is_draft { " (draft)" } else { "" };
+                    if let Some(detail) = self
+                        .selected_pr_detail
+                        .as_mut()
+                        .filter(|d| d.pull_request_id == pr_id)
+                    {
+                        *detail = *pr;
+                    }
+                    self.set_status(format!("PR #{pr_id} is now {status}{draft}"));
+                    // Status changes move the PR between panes
+                    if let Some(repo_name) = self.current_repo_name.clone() {
+                        self.fetch_pr_panes(&repo_name);
+                    }
+                }
+                PRLoadResult::PRThreadUpdated {
+                    pr_id,
+                    thread_id,
+                    status,
+                } => {
+                    if let Some(thread) = self.pr_threads.iter_mut().find(|t| t.id == thread_id) {
+                        thread.status = Some(status.clone());
+                    }
+                    self.set_status(format!("Thread on PR #{pr_id} marked {status}"));
                 }
                 PRLoadResult::PRDetail(pr) => {
-                    self.selected_pr_detail = Some(*pr);
+                    let current = self.selected_pr_detail.as_ref().map(|d| d.pull_request_id);
+                    if current.is_none() || current == Some(pr.pull_request_id) {
+                        self.selected_pr_detail = Some(*pr);
+                    }
+                }
+                PRLoadResult::Branches {
+                    repository,
+                    branches,
+                } => {
+                    if let Some(dialog) = self
+                        .pr_create_dialog
+                        .as_mut()
+                        .filter(|d| d.repository == repository)
+                    {
+                        dialog.set_branches(branches);
+                    }
+                }
+                PRLoadResult::PRCreated(pr) => {
+                    self.pr_create_dialog = None;
+                    if self.input_mode == InputMode::PRCreate {
+                        self.input_mode = InputMode::Normal;
+                    }
+                    self.set_status(format!("Created PR #{}: {}", pr.pull_request_id, pr.title));
+                    let repo = pr.repository.as_ref().map(|r| r.name.as_str());
+                    if let Some(repo_name) = self.current_repo_name.clone() {
+                        if repo.is_none() || repo == Some(repo_name.as_str()) {
+                            self.fetch_pr_panes(&repo_name);
+                        }
+                    }
+                }
+                PRLoadResult::PRIterations { pr_id, iterations } => {
+                    if let Some(files) = self.pr_files.as_mut().filter(|f| f.pr_id == pr_id) {
+                        files.iterations = iterations;
+                        if files.iteration > files.iterations.len() {
+                            files.iteration = 0;
+                        }
+                        self.load_pr_changes();
+                    }
+                }
+                PRLoadResult::PRChanges {
+                    pr_id,
+                    iteration,
+                    changes,
+                } => {
+                    if let Some(files) = self
+                        .pr_files
+                        .as_mut()
+                        .filter(|f| f.pr_id == pr_id && f.iteration == iteration)
+                    {
+                        files.changes = changes;
+                        files.loading = false;
+                        files.selected = files.selected.min(files.changes.len().saturating_sub(1));
+                        let count = files.changes.len();
+                        let label = files.iteration_label();
+                        self.set_status(format!("{count} changed files ({label})"));
+                    }
+                }
+                PRLoadResult::PRFileDiff { pr_id, diff } => {
+                    if let Some(files) = self.pr_files.as_mut().filter(|f| f.pr_id == pr_id) {
+                        files.diff = Some(*diff);
+                    }
+                }
+                PRLoadResult::PRFilesFailed(message) => {
+                    if let Some(files) = &mut self.pr_files {
+                        files.loading = false;
+                    }
+                    self.set_error(message);
+                }
+                PRLoadResult::PRCompletePolicies { pr_id, policies } => {
+                    if let Some(dialog) = self
+                        .pr_complete_dialog
+                        .as_mut()
+                        .filter(|d| d.pr_id == pr_id)
+                    {
+                        dialog.set_policies(policies);
+                    }
+                }
+                PRLoadResult::PRCompleted { pr, auto_complete } => {
+                    self.pr_complete_dialog = None;
+                    if self.input_mode == InputMode::PRComplete {
+                        self.input_mode = InputMode::Normal;
+                    }
+                    let pr_id = pr.pull_request_id;
+                    if let Some(detail) = self
+                        .selected_pr_detail
+                        .as_mut()
+                        .filter(|d| d.pull_request_id == pr_id)
+                    {
+                        *detail = (*pr).clone();
+                    }
+                    if auto_complete {
+                        self.set_status(format!("Auto-complete set on PR #{pr_id}"));
+                    } else {
+                        self.set_status(format!("Completed PR #{pr_id}: {}", pr.title));
+                    }
+                    if let Some(repo_name) = self.current_repo_name.clone() {
+                        self.fetch_pr_panes(&repo_name);
+                    }
+                }
+                PRLoadResult::PRCompleteFailed(msg) => {
+                    if let Some(dialog) = &mut self.pr_complete_dialog {
+                        dialog.submitting = false;
+                        dialog.loading_policies = false;
+                    }
+                    self.set_error(msg);
+                }
+                PRLoadResult::PRCreateFailed(msg) => {
+                    if let Some(dialog) = &mut self.pr_create_dialog {
+                        dialog.submitting = false;
+                        dialog.loading_branches = false;
+                    }
+                    self.set_error(msg);
                 }
                 PRLoadResult::Error(msg) => {
                     self.pr_loading = false;
@@ -4788,6 +7684,9 @@ mod tests {
                 remaining_work: None,
                 original_estimate: None,
                 completed_work: None,
+                area_path: None,
+                priority: None,
+                other: Default::default(),
             },
             relations: None,
             children: vec![],
@@ -4817,6 +7716,9 @@ mod tests {
                 remaining_work: None,
                 original_estimate: None,
                 completed_work: None,
+                area_path: None,
+                priority: None,
+                other: Default::default(),
             },
             relations: None,
             children: vec![],
@@ -4992,6 +7894,59 @@ mod tests {
         );
     }
 
+    // Tests for settings with per-project overrides
+    fn config_with_overrides() -> Config {
+        toml::from_str(
+            r#"
+            [settings]
+            page_jump = 2
+            states = ["Open", "Closed"]
+
+            [[projects]]
+            name = "p"
+            organization = "https://dev.azure.com/org"
+            project = "P"
+            team = "T"
+            page_jump = 5
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_settings_applies_project_overrides() {
+        let app = App::new(config_with_overrides());
+        let settings = app.settings();
+        assert_eq!(settings.page_jump, 5);
+        assert_eq!(settings.states, vec!["Open", "Closed"]);
+    }
+
+    #[test]
+    fn test_list_jump_uses_page_jump() {
+        let mut app = App::new(config_with_overrides());
+        app.work_items = (1..=10)
+            .map(|i| make_work_item(i, &format!("Task {i}"), "Open", None))
+            .collect();
+        app.rebuild_visible_items();
+        app.work_item_list_state.select(Some(0));
+
+        app.list_jump_down();
+        assert_eq!(app.work_item_list_state.selected(), Some(5));
+        app.list_jump_up();
+        assert_eq!(app.work_item_list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_custom_states_drive_dropdowns() {
+        let mut app = App::new(config_with_overrides());
+        app.work_items = vec![make_work_item(1, "Task 1", "Open", None)];
+        app.rebuild_visible_items();
+        app.work_item_list_state.select(Some(0));
+
+        assert_eq!(app.filtered_states(), vec!["All", "Open", "Closed"]);
+        assert_eq!(app.filtered_edit_states(), vec!["Open", "Closed"]);
+    }
+
     // Tests for cicd_search_query filtering
     #[test]
     fn test_sorted_pipeline_indices_cicd_search() {
@@ -5057,13 +8012,17 @@ mod tests {
     #[test]
     fn test_preview_tab_next() {
         assert_eq!(PreviewTab::Details.next(), PreviewTab::References);
-        assert_eq!(PreviewTab::References.next(), PreviewTab::Details);
+        assert_eq!(PreviewTab::References.next(), PreviewTab::Discussion);
+        assert_eq!(PreviewTab::Discussion.next(), PreviewTab::History);
+        assert_eq!(PreviewTab::History.next(), PreviewTab::Details);
     }
 
     #[test]
     fn test_preview_tab_prev() {
-        assert_eq!(PreviewTab::Details.prev(), PreviewTab::References);
+        assert_eq!(PreviewTab::Details.prev(), PreviewTab::History);
         assert_eq!(PreviewTab::References.prev(), PreviewTab::Details);
+        assert_eq!(PreviewTab::Discussion.prev(), PreviewTab::References);
+        assert_eq!(PreviewTab::History.prev(), PreviewTab::Discussion);
     }
 
     // Tests for ConfirmActionDialog description
@@ -0,0 +1,465 @@
+//! Artifacts of a pipeline run: what a build published with the sizes, the
+//! file tree inside container artifacts, and downloads of a file, folder or
+//! whole artifact to a local directory
+
+use crate::azure::{ArtifactItem, BuildArtifact, DevOpsBackend};
+use anyhow::Result;
+use std::path::PathBuf;
+
+/// An artifact with the files of its container
+#[derive(Debug, Clone)]
+pub struct LoadedArtifact {
+    pub artifact: BuildArtifact,
+    /// Files and folders of a container artifact; pipeline artifacts can only
+    /// be downloaded whole
+    pub items: Option<Vec<ArtifactItem>>,
+}
+
+impl LoadedArtifact {
+    pub fn size(&self) -> Option<u64> {
+        match &self.items {
+            Some(items) => Some(items.iter().filter_map(|i| i.file_length).sum()),
+            None => self.artifact.reported_size(),
+        }
+    }
+
+    /// Folder of the container holding the artifact
+    fn root(&self) -> Option<&str> {
+        self.artifact.container().map(|(_, folder)| folder)
+    }
+}
+
+/// Artifacts of a build, with the files of its container artifacts
+pub async fn load<B: DevOpsBackend>(client: &B, build_id: i32) -> Result<Vec<LoadedArtifact>> {
+    let artifacts = client.list_build_artifacts(build_id).await?;
+    let mut loaded = Vec::with_capacity(artifacts.len());
+    for artifact in artifacts {
+        let items = match artifact.container() {
+            Some((container_id, folder)) => {
+                Some(client.list_artifact_items(container_id, folder).await?)
+            }
+            None => None,
+        };
+        loaded.push(LoadedArtifact { artifact, items });
+    }
+    Ok(loaded)
+}
+
+/// File or folder directly inside the folder being browsed
+#[derive(Debug, Clone, Copy)]
+pub struct ArtifactEntry<'a> {
+    pub item: &'a ArtifactItem,
+    pub name: &'a str,
+    /// Files under a folder add up to its size
+    pub size: u64,
+}
+
+/// Entries directly inside `folder`: folders first, then files, by name
+pub fn entries<'a>(items: &'a [ArtifactItem], folder: &str) -> Vec<ArtifactEntry<'a>> {
+    let prefix = format!("{folder}/");
+    let mut entries: Vec<ArtifactEntry> = items
+        .iter()
+        .filter_map(|item| {
+            let name = item.path.strip_prefix(&prefix)?;
+            if name.is_empty() || name.contains('/') {
+                return None;
+            }
+            let size = if item.is_folder() {
+                let inside = format!("{}/", item.path);
+                items
+                    .iter()
+                    .filter(|i| i.path.starts_with(&inside))
+                    .filter_map(|i| i.file_length)
+                    .sum()
+            } else {
+                item.file_length.unwrap_or(0)
+            };
+            Some(ArtifactEntry { item, name, size })
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        (!a.item.is_folder(), a.name.to_lowercase())
+            .cmp(&(!b.item.is_folder(), b.name.to_lowercase()))
+    });
+    entries
+}
+
+/// `512 B`, `1.5 MB`, ...
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Last segment of a container path
+fn base_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// What a download fetches
+#[derive(Debug, Clone)]
+pub struct DownloadRequest {
+    pub artifact: BuildArtifact,
+    /// File or folder inside the artifact; `None` downloads all of it
+    pub item: Option<ArtifactItem>,
+}
+
+impl DownloadRequest {
+    /// Name of the downloaded file: files keep theirs, folders and whole
+    /// artifacts come as zips
+    pub fn file_name(&self) -> String {
+        match &self.item {
+            Some(item) if !item.is_folder() => base_name(&item.path).to_string(),
+            Some(item) => format!("{}.zip", base_name(&item.path)),
+            None => format!("{}.zip", self.artifact.name),
+        }
+    }
+
+    /// Bytes to expect; zips are built on the fly, so only files know theirs
+    pub fn size(&self) -> Option<u64> {
+        self.item
+            .as_ref()
+            .filter(|i| !i.is_folder())
+            .and_then(|i| i.file_length)
+    }
+}
+
+/// Where a download named `file_name` goes in `dir`; a leading `~` is the
+/// home directory
+pub fn destination_path(dir: &str, file_name: &str) -> PathBuf {
+    let dir = dir.trim();
+    let dir = match dir.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => match dirs::home_dir() {
+            Some(home) => home.join(rest.trim_start_matches('/')),
+            None => PathBuf::from(dir),
+        },
+        _ => PathBuf::from(dir),
+    };
+    dir.join(file_name)
+}
+
+/// The user's Downloads folder, else the current directory
+pub fn default_download_dir() -> String {
+    dirs::download_dir()
+        .map(|d| d.to_string_lossy().into_owned())
+        .unwrap_or_else(|| ".".to_string())
+}
+
+/// A download in progress
+#[derive(Debug, Clone)]
+pub struct ArtifactDownload {
+    pub name: String,
+    pub path: PathBuf,
+    pub total: Option<u64>,
+    /// Bytes written so far
+    pub done: u64,
+}
+
+impl ArtifactDownload {
+    pub fn new(name: String, path: PathBuf, total: Option<u64>) -> Self {
+        Self {
+            name,
+            path,
+            total,
+            done: 0,
+        }
+    }
+
+    /// Written to while downloading, renamed to `path` once complete
+    pub fn part_path(&self) -> PathBuf {
+        let mut part = self.path.clone().into_os_string();
+        part.push(".part");
+        PathBuf::from(part)
+    }
+
+    /// Share downloaded, when the size is known
+    pub fn ratio(&self) -> Option<f64> {
+        let total = self.total.filter(|&t| t > 0)?;
+        Some((self.done as f64 / total as f64).min(1.0))
+    }
+
+    /// `app.js  45% (1.2 MB of 2.7 MB)`, or just the bytes so far
+    pub fn label(&self) -> String {
+        match (self.ratio(), self.total) {
+            (Some(ratio), Some(total)) => format!(
+                "{}  {:.0}% ({} of {})",
+                self.name,
+                ratio * 100.0,
+                format_size(self.done),
+                format_size(total)
+            ),
+            _ => format!("{}  {}", self.name, format_size(self.done)),
+        }
+    }
+}
+
+/// The artifacts panel of a run
+#[derive(Debug, Clone)]
+pub struct ArtifactBrowser {
+    pub build_id: i32,
+    pub build_number: String,
+    pub artifacts: Vec<LoadedArtifact>,
+    pub loading: bool,
+    /// Artifact being browsed and the folder shown; `None` lists the artifacts
+    pub open: Option<(usize, String)>,
+    pub selected: usize,
+    /// Directory being typed in, while asking where a download goes
+    pub destination: Option<String>,
+}
+
+impl ArtifactBrowser {
+    pub fn new(build_id: i32, build_number: String) -> Self {
+        Self {
+            build_id,
+            build_number,
+            artifacts: Vec::new(),
+            loading: true,
+            open: None,
+            selected: 0,
+            destination: None,
+        }
+    }
+
+    pub fn load(&mut self, artifacts: Vec<LoadedArtifact>) {
+        self.artifacts = artifacts;
+        self.loading = false;
+        self.open = None;
+        self.selected = 0;
+    }
+
+    /// Entries of the open folder
+    pub fn entries(&self) -> Vec<ArtifactEntry<'_>> {
+        let Some((index, folder)) = &self.open else {
+            return Vec::new();
+        };
+        match self.artifacts.get(*index).and_then(|a| a.items.as_deref()) {
+            Some(items) => entries(items, folder),
+            None => Vec::new(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self.open {
+            Some(_) => self.entries().len(),
+            None => self.artifacts.len(),
+        }
+    }
+
+    pub fn next(&mut self) {
+        if self.selected + 1 < self.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// `drop/web`, or nothing while listing the artifacts
+    pub fn location(&self) -> Option<&str> {
+        self.open.as_ref().map(|(_, folder)| folder.as_str())
+    }
+
+    /// Open the selected container artifact or folder; false when it is
+    /// something to download instead
+    pub fn enter(&mut self) -> bool {
+        let folder = match &self.open {
+            None => self
+                .artifacts
+                .get(self.selected)
+                .filter(|a| a.items.is_some())
+                .and_then(|a| a.root())
+                .map(|root| (self.selected, root.to_string())),
+            Some((index, _)) => self
+                .entries()
+                .get(self.selected)
+                .filter(|e| e.item.is_folder())
+                .map(|e| (*index, e.item.path.clone())),
+        };
+        match folder {
+            Some(folder) => {
+                self.open = Some(folder);
+                self.selected = 0;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Up a folder, or from the artifact's root back to the artifacts;
+    /// false when already listing the artifacts
+    pub fn up(&mut self) -> bool {
+        let Some((index, folder)) = self.open.take() else {
+            return false;
+        };
+        let root = self.artifacts.get(index).and_then(|a| a.root());
+        match folder.rsplit_once('/') {
+            Some((parent, _)) if Some(folder.as_str()) != root => {
+                self.open = Some((index, parent.to_string()));
+                self.selected = self
+                    .entries()
+                    .iter()
+                    .position(|e| e.item.path == folder)
+                    .unwrap_or(0);
+            }
+            _ => self.selected = index,
+        }
+        true
+    }
+
+    /// Download of the selected artifact, file or folder
+    pub fn selected_download(&self) -> Option<DownloadRequest> {
+        match &self.open {
+            None => self.artifacts.get(self.selected).map(|a| DownloadRequest {
+                artifact: a.artifact.clone(),
+                item: None,
+            }),
+            Some((index, _)) => {
+                let artifact = self.artifacts.get(*index)?.artifact.clone();
+                let item = self.entries().get(self.selected)?.item.clone();
+                Some(DownloadRequest {
+                    artifact,
+                    item: Some(item),
+                })
+            }
+        }
+    }
+
+    pub fn insert(&mut self, c: char) {
+        if let Some(destination) = &mut self.destination {
+            destination.push(c);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(destination) = &mut self.destination {
+            destination.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::azure::ArtifactResource;
+
+    fn item(item_type: &str, path: &str, length: Option<u64>) -> ArtifactItem {
+        ArtifactItem {
+            container_id: 7,
+            item_type: item_type.to_string(),
+            path: path.to_string(),
+            file_length: length,
+        }
+    }
+
+    fn browser() -> ArtifactBrowser {
+        let container = BuildArtifact {
+            name: "drop".to_string(),
+            resource: ArtifactResource {
+                resource_type: Some("Container".to_string()),
+                data: Some("#/7/drop".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let pipeline = BuildArtifact {
+            name: "coverage".to_string(),
+            resource: ArtifactResource {
+                resource_type: Some("PipelineArtifact".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut browser = ArtifactBrowser::new(1, "1".to_string());
+        browser.load(vec![
+            LoadedArtifact {
+                artifact: container,
+                items: Some(vec![
+                    item("folder", "drop", None),
+                    item("file", "drop/readme.txt", Some(100)),
+                    item("folder", "drop/bin", None),
+                    item("file", "drop/bin/app.exe", Some(2048)),
+                    item("folder", "drop/bin/lib", None),
+                    item("file", "drop/bin/lib/core.dll", Some(1024)),
+                ]),
+            },
+            LoadedArtifact {
+                artifact: pipeline,
+                items: None,
+            },
+        ]);
+        browser
+    }
+
+    #[test]
+    fn test_browse_folders() {
+        let mut browser = browser();
+        assert_eq!(browser.artifacts[0].size(), Some(3172));
+        assert_eq!(browser.artifacts[1].size(), None);
+
+        // Folders first, each as big as what is under it
+        assert!(browser.enter());
+        assert_eq!(browser.location(), Some("drop"));
+        let listed: Vec<(&str, u64)> = browser.entries().iter().map(|e| (e.name, e.size)).collect();
+        assert_eq!(listed, [("bin", 3072), ("readme.txt", 100)]);
+
+        assert!(browser.enter());
+        assert!(browser.enter());
+        assert_eq!(browser.location(), Some("drop/bin/lib"));
+        // A file is downloaded, not opened
+        assert!(!browser.enter());
+        let download = browser.selected_download().unwrap();
+        assert_eq!(download.file_name(), "core.dll");
+        assert_eq!(download.size(), Some(1024));
+
+        // Back up, landing on the folder we came from
+        assert!(browser.up());
+        assert_eq!(browser.location(), Some("drop/bin"));
+        assert_eq!(browser.entries()[browser.selected].name, "lib");
+        let download = browser.selected_download().unwrap();
+        assert_eq!(download.file_name(), "lib.zip");
+        assert_eq!(download.size(), None);
+        assert!(browser.up());
+        assert!(browser.up());
+        assert_eq!(browser.location(), None);
+        assert_eq!(browser.selected, 0);
+        assert!(!browser.up());
+
+        // Pipeline artifacts can't be browsed
+        browser.next();
+        assert!(!browser.enter());
+        assert_eq!(
+            browser.selected_download().unwrap().file_name(),
+            "coverage.zip"
+        );
+    }
+
+    #[test]
+    fn test_download_progress() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(4 * 1024 * 1024), "4.0 MB");
+
+        let path = destination_path("/tmp/out ", "app.exe");
+        assert_eq!(path, PathBuf::from("/tmp/out/app.exe"));
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(destination_path("~/dl", "a"), home.join("dl").join("a"));
+        }
+
+        let mut download = ArtifactDownload::new("app.exe".to_string(), path, Some(2048));
+        assert_eq!(download.part_path(), PathBuf::from("/tmp/out/app.exe.part"));
+        download.done = 1024;
+        assert_eq!(download.label(), "app.exe  50% (1.0 KB of 2.0 KB)");
+        download.total = None;
+        assert_eq!(download.ratio(), None);
+        assert_eq!(download.label(), "app.exe  1.0 KB");
+    }
+}
@@ -0,0 +1,865 @@
+use crate::azure::types::*;
+use crate::azure::{AzureCli, FakeBackend, RestClient};
+use crate::config::{BackendKind, ProjectConfig, Settings};
+use anyhow::Result;
+use std::future::Future;
+use std::path::Path;
+
+/// Maximum number of IDs accepted by the work items batch endpoint
+pub const WORK_ITEMS_BATCH_SIZE: usize = 200;
+
+/// Data source for everything the UI shows or changes in Azure DevOps.
+///
+/// Implementations are cheap to clone and are bound to a single project;
+/// `for_project` hands out a handle for another project that shares the
+/// underlying connections and credentials.
+pub trait DevOpsBackend: Clone + Send + Sync + 'static {
+    /// Return a handle targeting `project`
+    fn for_project(&self, project: &ProjectConfig) -> Self;
+
+    // Boards
+
+    /// Get iterations (sprints) for the team
+    fn get_sprints(&self) -> impl Future<Output = Result<Vec<Sprint>>> + Send;
+
+    /// Capacity, days off and working days of the team for a sprint
+    fn get_team_capacity(
+        &self,
+        iteration_id: &str,
+    ) -> impl Future<Output = Result<TeamCapacity>> + Send;
+
+    /// Get work items for a sprint iteration path (as a parent/child hierarchy)
+    fn get_sprint_work_items(
+        &self,
+        iteration_path: &str,
+    ) -> impl Future<Output = Result<Vec<WorkItem>>> + Send;
+
+    /// Run a WIQL query; work items come back flat, in query order
+    fn query_work_items(&self, wiql: &str) -> impl Future<Output = Result<Vec<WorkItem>>> + Send;
+
+    /// Folders and queries of "My Queries" and "Shared Queries", with their WIQL
+    fn list_queries(&self) -> impl Future<Output = Result<Vec<QueryHierarchyItem>>> + Send;
+
+    /// The team's Kanban boards, one per backlog level, with columns and swimlanes
+    fn get_boards(&self) -> impl Future<Output = Result<Vec<Board>>> + Send;
+
+    /// Get single work item by ID (with relations)
+    fn get_work_item(&self, id: i32) -> impl Future<Output = Result<WorkItem>> + Send;
+
+    /// Work items with their relations in one request, for at most
+    /// `WORK_ITEMS_BATCH_SIZE` IDs; IDs that no longer exist are left out
+    fn get_work_items_with_relations(
+        &self,
+        ids: &[i32],
+    ) -> impl Future<Output = Result<Vec<WorkItem>>> + Send;
+
+    /// Every revision of a work item as a full field snapshot, oldest first
+    fn get_work_item_revisions(
+        &self,
+        id: i32,
+    ) -> impl Future<Output = Result<Vec<WorkItem>>> + Send;
+
+    /// Update history of a work item, oldest first
+    fn get_work_item_updates(
+        &self,
+        id: i32,
+    ) -> impl Future<Output = Result<Vec<WorkItemUpdate>>> + Send;
+
+    /// Discussion comments of a work item, newest first
+    fn get_work_item_comments(
+        &self,
+        id: i32,
+    ) -> impl Future<Output = Result<Vec<WorkItemComment>>> + Send;
+
+    /// Post a comment (HTML) to a work item's discussion
+    fn add_work_item_comment(
+        &self,
+        id: i32,
+        text: &str,
+    ) -> impl Future<Output = Result<WorkItemComment>> + Send;
+
+    /// Replace the text (HTML) of a comment
+    fn update_work_item_comment(
+        &self,
+        id: i32,
+        comment_id: i32,
+        text: &str,
+    ) -> impl Future<Output = Result<WorkItemComment>> + Send;
+
+    fn delete_work_item_comment(
+        &self,
+        id: i32,
+        comment_id: i32,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Update work item fields by reference name (a JSON Patch on the REST API)
+    fn update_work_item(
+        &self,
+        id: i32,
+        updates: &[FieldUpdate],
+    ) -> impl Future<Output = Result<WorkItem>> + Send;
+
+    /// Remove links from a work item and add others; returns the item with
+    /// its links. Adding a parent replaces the current one
+    fn update_work_item_links(
+        &self,
+        id: i32,
+        add: &[WorkItemRelation],
+        remove: &[WorkItemRelation],
+    ) -> impl Future<Output = Result<WorkItem>> + Send;
+
+    /// Create a work item, optionally as the child of another
+    fn create_work_item(&self, item: &NewWorkItem)
+        -> impl Future<Output = Result<WorkItem>> + Send;
+
+    /// Get the signed-in user's unique name (email)
+    fn get_current_user(&self) -> impl Future<Output = Result<String>> + Send;
+
+    // Pipelines
+
+    /// List all pipeline definitions
+    fn list_pipelines(&self) -> impl Future<Output = Result<Vec<Pipeline>>> + Send;
+
+    /// List runs for a pipeline, newest first (optionally limited)
+    fn list_pipeline_runs(
+        &self,
+        pipeline_id: i32,
+        top: Option<u32>,
+    ) -> impl Future<Output = Result<Vec<PipelineRun>>> + Send;
+
+    /// Get a single pipeline run (build) by ID
+    fn get_pipeline_run(&self, run_id: i32) -> impl Future<Output = Result<PipelineRun>> + Send;
+
+    /// Build definition of a pipeline (repository, YAML path, variables)
+    fn get_pipeline_definition(
+        &self,
+        pipeline_id: i32,
+    ) -> impl Future<Output = Result<PipelineDefinition>> + Send;
+
+    /// Queue a new run of a pipeline on a branch, with runtime parameters,
+    /// variables and stages to skip
+    fn trigger_pipeline(
+        &self,
+        pipeline_id: i32,
+        request: &PipelineRunRequest,
+    ) -> impl Future<Output = Result<PipelineRun>> + Send;
+
+    /// Cancel a running pipeline build
+    fn cancel_pipeline_run(&self, run_id: i32) -> impl Future<Output = Result<()>> + Send;
+
+    /// Get build timeline with optional changeId for delta updates.
+    /// Returns None if nothing changed since `last_change_id`.
+    fn get_build_timeline_delta(
+        &self,
+        build_id: i32,
+        last_change_id: Option<i32>,
+    ) -> impl Future<Output = Result<Option<(Vec<TimelineRecord>, Option<i32>)>>> + Send;
+
+    /// Get build timeline (jobs, tasks, stages)
+    fn get_build_timeline(
+        &self,
+        build_id: i32,
+    ) -> impl Future<Output = Result<Vec<TimelineRecord>>> + Send {
+        async move {
+            let delta = self.get_build_timeline_delta(build_id, None).await?;
+            Ok(delta.map(|(records, _)| records).unwrap_or_default())
+        }
+    }
+
+    /// Get build log content
+    fn get_build_log(
+        &self,
+        build_id: i32,
+        log_id: i32,
+    ) -> impl Future<Output = Result<Vec<String>>> + Send;
+
+    /// Test runs published by a build, with their result counts per outcome
+    fn list_test_runs(&self, build_id: i32) -> impl Future<Output = Result<Vec<TestRun>>> + Send;
+
+    /// Failed results of a test run, with error messages and stack traces
+    fn get_failed_test_results(
+        &self,
+        test_run_id: i32,
+    ) -> impl Future<Output = Result<Vec<TestCaseResult>>> + Send;
+
+    /// Artifacts published by a build
+    fn list_build_artifacts(
+        &self,
+        build_id: i32,
+    ) -> impl Future<Output = Result<Vec<BuildArtifact>>> + Send;
+
+    /// Every file and folder under `folder` of a file container
+    fn list_artifact_items(
+        &self,
+        container_id: i64,
+        folder: &str,
+    ) -> impl Future<Output = Result<Vec<ArtifactItem>>> + Send;
+
+    /// Download a whole artifact (as a zip), or a file or folder (as a zip)
+    /// inside a container artifact, writing it to `dest`
+    fn download_artifact(
+        &self,
+        artifact: &BuildArtifact,
+        item: Option<&ArtifactItem>,
+        dest: &Path,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    // Releases
+
+    /// List release definitions (deleted/disabled ones filtered out)
+    fn list_release_definitions(
+        &self,
+    ) -> impl Future<Output = Result<Vec<ReleaseDefinition>>> + Send;
+
+    /// Get release definition details (for trigger dialog)
+    fn get_release_definition_detail(
+        &self,
+        definition_id: i32,
+    ) -> impl Future<Output = Result<ReleaseDefinitionDetail>> + Send;
+
+    /// List releases (optionally filtered by definition)
+    fn list_releases(
+        &self,
+        definition_id: Option<i32>,
+    ) -> impl Future<Output = Result<Vec<Release>>> + Send;
+
+    /// Get release details (includes environments)
+    fn get_release(&self, release_id: i32) -> impl Future<Output = Result<Release>> + Send;
+
+    /// Create a new release
+    fn create_release(
+        &self,
+        definition_id: i32,
+        description: Option<&str>,
+    ) -> impl Future<Output = Result<Release>> + Send;
+
+    /// Abandon/cancel a release
+    fn cancel_release(&self, release_id: i32) -> impl Future<Output = Result<()>> + Send;
+
+    /// Cancel a specific release environment/stage
+    fn cancel_release_environment(
+        &self,
+        release_id: i32,
+        environment_id: i32,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Redeploy/retrigger a specific release environment/stage
+    fn redeploy_release_environment(
+        &self,
+        release_id: i32,
+        environment_id: i32,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Fetch a release task log from the `logUrl` reported on the task
+    fn get_release_task_log(
+        &self,
+        log_url: &str,
+    ) -> impl Future<Output = Result<Vec<String>>> + Send;
+
+    // Approvals
+
+    /// Get pending approvals for the current user (including group approvals)
+    fn get_pending_approvals(&self) -> impl Future<Output = Result<Vec<Approval>>> + Send;
+
+    /// Approve or reject several release approvals in one request
+    fn update_approvals(
+        &self,
+        approval_ids: &[i32],
+        status: &str,
+        comments: &str,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    // Repos and pull requests
+
+    /// List all repositories
+    fn list_repositories(&self) -> impl Future<Output = Result<Vec<Repository>>> + Send;
+
+    /// List pull requests with optional filters
+    fn list_pull_requests(
+        &self,
+        repository: Option<&str>,
+        status: &str,
+        creator: Option<&str>,
+        top: Option<i32>,
+    ) -> impl Future<Output = Result<Vec<PullRequest>>> + Send;
+
+    /// Get a single pull request by ID
+    fn get_pull_request(&self, id: i32) -> impl Future<Output = Result<PullRequest>> + Send;
+
+    /// Get a commit with its message; `commit_id` may be abbreviated
+    fn get_commit(
+        &self,
+        repository_id: &str,
+        commit_id: &str,
+    ) -> impl Future<Output = Result<GitCommitRef>> + Send;
+
+    /// List threads (comments) on a pull request
+    fn list_pr_threads(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+    ) -> impl Future<Output = Result<Vec<PRThread>>> + Send;
+
+    /// List the iterations (pushes) of a pull request
+    fn list_pr_iterations(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+    ) -> impl Future<Output = Result<Vec<PRIteration>>> + Send;
+
+    /// Files changed in an iteration, compared to iteration `compare_to`
+    /// (0 compares to the target branch)
+    fn list_pr_changes(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+        iteration_id: i32,
+        compare_to: i32,
+    ) -> impl Future<Output = Result<Vec<PRChange>>> + Send;
+
+    /// Content of a file at a commit
+    fn get_file_content(
+        &self,
+        repository_id: &str,
+        path: &str,
+        commit_id: &str,
+    ) -> impl Future<Output = Result<String>> + Send;
+
+    /// Content of a file at the tip of a branch or at a tag (full ref name)
+    fn get_file_at_ref(
+        &self,
+        repository_id: &str,
+        path: &str,
+        git_ref: &str,
+    ) -> impl Future<Output = Result<String>> + Send;
+
+    /// List policies evaluated on a pull request
+    fn list_pr_policies(&self, pr_id: i32) -> impl Future<Output = Result<Vec<PRPolicy>>> + Send;
+
+    /// Set vote on a pull request
+    /// Votes: "approve", "approve-with-suggestions", "reject", "reset", "wait-for-author"
+    fn set_pr_vote(&self, pr_id: i32, vote: &str) -> impl Future<Output = Result<()>> + Send;
+
+    /// Update a pull request (status, title, description, draft)
+    fn update_pr(
+        &self,
+        pr_id: i32,
+        status: Option<&str>,
+        title: Option<&str>,
+        description: Option<&str>,
+        draft: Option<bool>,
+    ) -> impl Future<Output = Result<PullRequest>> + Send;
+
+    /// Complete a pull request now, or set it to auto-complete once policies pass
+    fn complete_pr(
+        &self,
+        pr_id: i32,
+        options: &PRCompletionOptions,
+        auto_complete: bool,
+    ) -> impl Future<Output = Result<PullRequest>> + Send;
+
+    /// List the branches of a repository
+    fn list_branches(&self, repository: &str) -> impl Future<Output = Result<Vec<GitRef>>> + Send;
+
+    /// List the tags of a repository
+    fn list_tags(&self, repository: &str) -> impl Future<Output = Result<Vec<GitRef>>> + Send;
+
+    /// Create a new pull request (with reviewers, work items and completion options)
+    fn create_pr(&self, pr: &NewPullRequest) -> impl Future<Output = Result<PullRequest>> + Send;
+
+    /// Add a comment to a pull request (creates a new thread), optionally
+    /// anchored to lines of a changed file
+    fn add_pr_comment(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+        content: &str,
+        anchor: Option<&PRCommentAnchor>,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Reply to an existing comment thread
+    fn reply_pr_thread(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+        thread_id: i32,
+        content: &str,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Set a comment thread's status ("active", "fixed", "wontFix", "closed", ...)
+    fn set_pr_thread_status(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+        thread_id: i32,
+        status: &str,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// List work items linked to a pull request
+    #[allow(dead_code)] // Not wired into the PR view yet
+    fn list_pr_work_items(
+        &self,
+        pr_id: i32,
+    ) -> impl Future<Output = Result<serde_json::Value>> + Send;
+}
+
+/// Backend selected by the `backend` setting in config.toml
+#[derive(Clone)]
+pub enum Backend {
+    Cli(AzureCli),
+    Rest(RestClient),
+    /// In-memory fixtures (`--demo`)
+    Fake(FakeBackend),
+}
+
+impl Backend {
+    /// Create an unbound backend; use `for_project` to target a project
+    pub fn from_settings(settings: &Settings) -> Self {
+        match settings.backend {
+            BackendKind::Cli => {
+                Backend::Cli(AzureCli::default().with_timeout(settings.api_timeout))
+            }
+            BackendKind::Rest => Backend::Rest(RestClient::with_timeout(settings.api_timeout)),
+        }
+    }
+}
+
+macro_rules! dispatch {
+    ($self:ident, $client:ident => $call:expr) => {
+        match $self {
+            Backend::Cli($client) => $call.await,
+            Backend::Rest($client) => $call.await,
+            Backend::Fake($client) => $call.await,
+        }
+    };
+}
+
+impl DevOpsBackend for Backend {
+    fn for_project(&self, project: &ProjectConfig) -> Self {
+        match self {
+            Backend::Cli(c) => Backend::Cli(c.for_project(project)),
+            Backend::Rest(c) => Backend::Rest(c.for_project(project)),
+            Backend::Fake(c) => Backend::Fake(c.for_project(project)),
+        }
+    }
+
+    async fn get_sprints(&self) -> Result<Vec<Sprint>> {
+        dispatch!(self, c => c.get_sprints())
+    }
+
+    async fn get_team_capacity(&self, iteration_id: &str) -> Result<TeamCapacity> {
+        dispatch!(self, c => c.get_team_capacity(iteration_id))
+    }
+
+    async fn get_sprint_work_items(&self, iteration_path: &str) -> Result<Vec<WorkItem>> {
+        dispatch!(self, c => c.get_sprint_work_items(iteration_path))
+    }
+
+    async fn query_work_items(&self, wiql: &str) -> Result<Vec<WorkItem>> {
+        dispatch!(self, c => c.query_work_items(wiql))
+    }
+
+    async fn list_queries(&self) -> Result<Vec<QueryHierarchyItem>> {
+        dispatch!(self, c => c.list_queries())
+    }
+
+    async fn get_boards(&self) -> Result<Vec<Board>> {
+        dispatch!(self, c => c.get_boards())
+    }
+
+    async fn get_work_item(&self, id: i32) -> Result<WorkItem> {
+        dispatch!(self, c => c.get_work_item(id))
+    }
+
+    async fn get_work_items_with_relations(&self, ids: &[i32]) -> Result<Vec<WorkItem>> {
+        dispatch!(self, c => c.get_work_items_with_relations(ids))
+    }
+
+    async fn get_work_item_revisions(&self, id: i32) -> Result<Vec<WorkItem>> {
+        dispatch!(self, c => c.get_work_item_revisions(id))
+    }
+
+    async fn get_work_item_updates(&self, id: i32) -> Result<Vec<WorkItemUpdate>> {
+        dispatch!(self, c => c.get_work_item_updates(id))
+    }
+
+    async fn get_work_item_comments(&self, id: i32) -> Result<Vec<WorkItemComment>> {
+        dispatch!(self, c => c.get_work_item_comments(id))
+    }
+
+    async fn add_work_item_comment(&self, id: i32, text: &str) -> Result<WorkItemComment> {
+        dispatch!(self, c => c.add_work_item_comment(id, text))
+    }
+
+    async fn update_work_item_comment(
+        &self,
+        id: i32,
+        comment_id: i32,
+        text: &str,
+    ) -> Result<WorkItemComment> {
+        dispatch!(self, c => c.update_work_item_comment(id, comment_id, text))
+    }
+
+    async fn delete_work_item_comment(&self, id: i32, comment_id: i32) -> Result<()> {
+        dispatch!(self, c => c.delete_work_item_comment(id, comment_id))
+    }
+
+    async fn update_work_item(&self, id: i32, updates: &[FieldUpdate]) -> Result<WorkItem> {
+        dispatch!(self, c => c.update_work_item(id, updates))
+    }
+
+    async fn update_work_item_links(
+        &self,
+        id: i32,
+        add: &[WorkItemRelation],
+        remove: &[WorkItemRelation],
+    ) -> Result<WorkItem> {
+        dispatch!(self, c => c.update_work_item_links(id, add, remove))
+    }
+
+    async fn create_work_item(&self, item: &NewWorkItem) -> Result<WorkItem> {
+        dispatch!(self, c => c.create_work_item(item))
+    }
+
+    async fn get_current_user(&self) -> Result<String> {
+        dispatch!(self, c => c.get_current_user())
+    }
+
+    async fn list_pipelines(&self) -> Result<Vec<Pipeline>> {
+        dispatch!(self, c => c.list_pipelines())
+    }
+
+    async fn list_pipeline_runs(
+        &self,
+        pipeline_id: i32,
+        top: Option<u32>,
+    ) -> Result<Vec<PipelineRun>> {
+        dispatch!(self, c => c.list_pipeline_runs(pipeline_id, top))
+    }
+
+    async fn get_pipeline_run(&self, run_id: i32) -> Result<PipelineRun> {
+        dispatch!(self, c => c.get_pipeline_run(run_id))
+    }
+
+    async fn get_pipeline_definition(&self, pipeline_id: i32) -> Result<PipelineDefinition> {
+        dispatch!(self, c => c.get_pipeline_definition(pipeline_id))
+    }
+
+    async fn trigger_pipeline(
+        &self,
+        pipeline_id: i32,
+        request: &PipelineRunRequest,
+    ) -> Result<PipelineRun> {
+        dispatch!(self, c => c.trigger_pipeline(pipeline_id, request))
+    }
+
+    async fn cancel_pipeline_run(&self, run_id: i32) -> Result<()> {
+        dispatch!(self, c => c.cancel_pipeline_run(run_id))
+    }
+
+    async fn get_build_timeline_delta(
+        &self,
+        build_id: i32,
+        last_change_id: Option<i32>,
+    ) -> Result<Option<(Vec<TimelineRecord>, Option<i32>)>> {
+        dispatch!(self, c => c.get_build_timeline_delta(build_id, last_change_id))
+    }
+
+    async fn get_build_log(&self, build_id: i32, log_id: i32) -> Result<Vec<String>> {
+        dispatch!(self, c => c.get_build_log(build_id, log_id))
+    }
+
+    async fn list_test_runs(&self, build_id: i32) -> Result<Vec<TestRun>> {
+        dispatch!(self, c => c.list_test_runs(build_id))
+    }
+
+    async fn get_failed_test_results(&self, test_run_id: i32) -> Result<Vec<TestCaseResult>> {
+        dispatch!(self, c => c.get_failed_test_results(test_run_id))
+    }
+
+    async fn list_build_artifacts(&self, build_id: i32) -> Result<Vec<BuildArtifact>> {
+        dispatch!(self, c => c.list_build_artifacts(build_id))
+    }
+
+    async fn list_artifact_items(
+        &self,
+        container_id: i64,
+        folder: &str,
+    ) -> Result<Vec<ArtifactItem>> {
+        dispatch!(self, c => c.list_artifact_items(container_id, folder))
+    }
+
+    async fn download_artifact(
+        &self,
+        artifact: &BuildArtifact,
+        item: Option<&ArtifactItem>,
+        dest: &Path,
+    ) -> Result<()> {
+        dispatch!(self, c => c.download_artifact(artifact, item, dest))
+    }
+
+    async fn list_release_definitions(&self) -> Result<Vec<ReleaseDefinition>> {
+        dispatch!(self, c => c.list_release_definitions())
+    }
+
+    async fn get_release_definition_detail(
+        &self,
+        definition_id: i32,
+    ) -> Result<ReleaseDefinitionDetail> {
+        dispatch!(self, c => c.get_release_definition_detail(definition_id))
+    }
+
+    async fn list_releases(&self, definition_id: Option<i32>) -> Result<Vec<Release>> {
+        dispatch!(self, c => c.list_releases(definition_id))
+    }
+
+    async fn get_release(&self, release_id: i32) -> Result<Release> {
+        dispatch!(self, c => c.get_release(release_id))
+    }
+
+    async fn create_release(
+        &self,
+        definition_id: i32,
+        description: Option<&str>,
+    ) -> Result<Release> {
+        dispatch!(self, c => c.create_release(definition_id, description))
+    }
+
+    async fn cancel_release(&self, release_id: i32) -> Result<()> {
+        dispatch!(self, c => c.cancel_release(release_id))
+    }
+
+    async fn cancel_release_environment(&self, release_id: i32, environment_id: i32) -> Result<()> {
+        dispatch!(self, c => c.cancel_release_environment(release_id, environment_id))
+    }
+
+    async fn redeploy_release_environment(
+        &self,
+        release_id: i32,
+        environment_id: i32,
+    ) -> Result<()> {
+        dispatch!(self, c => c.redeploy_release_environment(release_id, environment_id))
+    }
+
+    async fn get_release_task_log(&self, log_url: &str) -> Result<Vec<String>> {
+        dispatch!(self, c => c.get_release_task_log(log_url))
+    }
+
+    async fn get_pending_approvals(&self) -> Result<Vec<Approval>> {
+        dispatch!(self, c => c.get_pending_approvals())
+    }
+
+    async fn update_approvals(
+        &self,
+        approval_ids: &[i32],
+        status: &str,
+        comments: &str,
+    ) -> Result<()> {
+        dispatch!(self, c => c.update_approvals(approval_ids, status, comments))
+    }
+
+    async fn list_repositories(&self) -> Result<Vec<Repository>> {
+        dispatch!(self, c => c.list_repositories())
+    }
+
+    async fn list_pull_requests(
+        &self,
+        repository: Option<&str>,
+        status: &str,
+        creator: Option<&str>,
+        top: Option<i32>,
+    ) -> Result<Vec<PullRequest>> {
+        dispatch!(self, c => c.list_pull_requests(repository, status, creator, top))
+    }
+
+    async fn get_pull_request(&self, id: i32) -> Result<PullRequest> {
+        dispatch!(self, c => c.get_pull_request(id))
+    }
+
+    async fn get_commit(&self, repository_id: &str, commit_id: &str) -> Result<GitCommitRef> {
+        dispatch!(self, c => c.get_commit(repository_id, commit_id))
+    }
+
+    async fn list_pr_threads(&self, repository_id: &str, pr_id: i32) -> Result<Vec<PRThread>> {
+        dispatch!(self, c => c.list_pr_threads(repository_id, pr_id))
+    }
+
+    async fn list_pr_iterations(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+    ) -> Result<Vec<PRIteration>> {
+        dispatch!(self, c => c.list_pr_iterations(repository_id, pr_id))
+    }
+
+    async fn list_pr_changes(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+        iteration_id: i32,
+        compare_to: i32,
+    ) -> Result<Vec<PRChange>> {
+        dispatch!(self, c => c.list_pr_changes(repository_id, pr_id, iteration_id, compare_to))
+    }
+
+    async fn get_file_content(
+        &self,
+        repository_id: &str,
+        path: &str,
+        commit_id: &str,
+    ) -> Result<String> {
+        dispatch!(self, c => c.get_file_content(repository_id, path, commit_id))
+    }
+
+    async fn get_file_at_ref(
+        &self,
+        repository_id: &str,
+        path: &str,
+        git_ref: &str,
+    ) -> Result<String> {
+        dispatch!(self, c => c.get_file_at_ref(repository_id, path, git_ref))
+    }
+
+    async fn list_pr_policies(&self, pr_id: i32) -> Result<Vec<PRPolicy>> {
+        dispatch!(self, c => c.list_pr_policies(pr_id))
+    }
+
+    async fn set_pr_vote(&self, pr_id: i32, vote: &str) -> Result<()> {
+        dispatch!(self, c => c.set_pr_vote(pr_id, vote))
+    }
+
+    async fn update_pr(
+        &self,
+        pr_id: i32,
+        status: Option<&str>,
+        title: Option<&str>,
+        description: Option<&str>,
+        draft: Option<bool>,
+    ) -> Result<PullRequest> {
+        dispatch!(self, c => c.update_pr(pr_id, status, title, description, draft))
+    }
+
+    async fn complete_pr(
+        &self,
+        pr_id: i32,
+        options: &PRCompletionOptions,
+        auto_complete: bool,
+    ) -> Result<PullRequest> {
+        dispatch!(self, c => c.complete_pr(pr_id, options, auto_complete))
+    }
+
+    async fn list_branches(&self, repository: &str) -> Result<Vec<GitRef>> {
+        dispatch!(self, c => c.list_branches(repository))
+    }
+
+    async fn list_tags(&self, repository: &str) -> Result<Vec<GitRef>> {
+        dispatch!(self, c => c.list_tags(repository))
+    }
+
+    async fn create_pr(&self, pr: &NewPullRequest) -> Result<PullRequest> {
+        dispatch!(self, c => c.create_pr(pr))
+    }
+
+    async fn add_pr_comment(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+        content: &str,
+        anchor: Option<&PRCommentAnchor>,
+    ) -> Result<()> {
+        dispatch!(self, c => c.add_pr_comment(repository_id, pr_id, content, anchor))
+    }
+
+    async fn reply_pr_thread(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+        thread_id: i32,
+        content: &str,
+    ) -> Result<()> {
+        dispatch!(self, c => c.reply_pr_thread(repository_id, pr_id, thread_id, content))
+    }
+
+    async fn set_pr_thread_status(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+        thread_id: i32,
+        status: &str,
+    ) -> Result<()> {
+        dispatch!(self, c => c.set_pr_thread_status(repository_id, pr_id, thread_id, status))
+    }
+
+    async fn list_pr_work_items(&self, pr_id: i32) -> Result<serde_json::Value> {
+        dispatch!(self, c => c.list_pr_work_items(pr_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_project(name: &str) -> ProjectConfig {
+        ProjectConfig {
+            name: name.to_string(),
+            organization: "https://dev.azure.com/contoso".to_string(),
+            project: format!("{name}-project"),
+            team: format!("{name} Team"),
+            repository: None,
+            overrides: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_from_settings_defaults_to_cli() {
+        let backend = Backend::from_settings(&Settings::default());
+        assert!(matches!(backend, Backend::Cli(_)));
+    }
+
+    #[test]
+    fn test_from_settings_rest() {
+        let settings = Settings {
+            backend: BackendKind::Rest,
+            ..Settings::default()
+        };
+        assert!(matches!(
+            Backend::from_settings(&settings),
+            Backend::Rest(_)
+        ));
+    }
+
+    #[test]
+    fn test_for_project_binds_project() {
+        let backend = Backend::from_settings(&Settings::default());
+        match backend.for_project(&make_project("alpha")) {
+            Backend::Cli(cli) => {
+                assert_eq!(cli.organization, "https://dev.azure.com/contoso");
+                assert_eq!(cli.project, "alpha-project");
+                assert_eq!(cli.team, "alpha Team");
+            }
+            _ => panic!("expected az CLI backend"),
+        }
+    }
+
+    #[test]
+    fn test_for_project_applies_timeout_override() {
+        let settings = Settings {
+            api_timeout: 45,
+            ..Settings::default()
+        };
+        let backend = Backend::from_settings(&settings);
+        let mut slow = make_project("onprem");
+        slow.overrides.api_timeout = Some(120);
+
+        match (
+            backend.for_project(&make_project("cloud")),
+            backend.for_project(&slow),
+        ) {
+            (Backend::Cli(cloud), Backend::Cli(onprem)) => {
+                assert_eq!(cloud.timeout_secs, 45);
+                assert_eq!(onprem.timeout_secs, 120);
+            }
+            _ => panic!("expected az CLI backend"),
+        }
+    }
+}
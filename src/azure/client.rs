@@ -1,10 +1,24 @@
+use crate::azure::backend::DevOpsBackend;
+use crate::azure::rest::{
+    artifact_download_url, build_uri, completion_options_body, container_url, new_thread_body,
+    run_pipeline_body, version_descriptor,
+};
 use crate::azure::types::*;
 use crate::config::ProjectConfig;
 use anyhow::{bail, Context, Result};
 use std::collections::HashMap;
+use std::path::Path;
 use std::time::Duration;
 use tokio::process::Command;
 
+/// Work item comments are only served by a preview version of the API
+pub(crate) const COMMENTS_API_VERSION: &str = "7.1-preview.4";
+
+/// Azure DevOps resource ID used when requesting access tokens from az
+pub const AZURE_DEVOPS_RESOURCE: &str = "499b84ac-1321-427f-aa17-267ca6975798";
+
+/// Backend that shells out to the Azure CLI (`az`) for every request
+#[derive(Debug, Clone)]
 pub struct AzureCli {
     pub organization: String,
     pub project: String,
@@ -12,17 +26,24 @@ pub struct AzureCli {
     pub timeout_secs: u64,
 }
 
-impl AzureCli {
-    pub fn new(config: &ProjectConfig) -> Self {
+impl Default for AzureCli {
+    fn default() -> Self {
         Self {
-            organization: config.organization.clone(),
-            project: config.project.clone(),
-            team: config.team.clone(),
+            organization: String::new(),
+            project: String::new(),
+            team: String::new(),
             timeout_secs: 30, // Default timeout
         }
     }
+}
+
+impl AzureCli {
+    /// Client for a project, using its `api_timeout` override if set
+    #[allow(dead_code)] // Used by test_commands binary and examples
+    pub fn new(config: &ProjectConfig) -> Self {
+        Self::default().for_project(config)
+    }
 
-    #[allow(dead_code)] // Public API for custom timeout configuration
     pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
         self.timeout_secs = timeout_secs;
         self
@@ -60,24 +81,7 @@ impl AzureCli {
         serde_json::from_str(&stdout).context("Failed to parse az output")
     }
 
-    /// Get iterations (sprints) for the team
-    pub async fn get_sprints(&self) -> Result<Vec<Sprint>> {
-        self.exec(&["boards", "iteration", "team", "list", "--team", &self.team])
-            .await
-    }
-
-    /// Get work items for a sprint iteration path
-    pub async fn get_sprint_work_items(&self, iteration_path: &str) -> Result<Vec<WorkItem>> {
-        let wiql = format!(
-            r#"SELECT [System.Id], [System.Title], [System.State], [System.WorkItemType], [System.AssignedTo], [System.Parent], [System.Description], [System.IterationPath], [System.Tags], [Microsoft.VSTS.Scheduling.RemainingWork], [Microsoft.VSTS.Scheduling.OriginalEstimate], [Microsoft.VSTS.Scheduling.CompletedWork] FROM WorkItems WHERE [System.IterationPath] = '{iteration_path}' ORDER BY [System.WorkItemType], [System.Title]"#
-        );
-
-        let items = self.query_work_items(&wiql).await?;
-        Ok(Self::build_hierarchy(items))
-    }
-
     /// Execute az command WITHOUT project arg (for work-item show which doesn't accept it)
-    #[allow(dead_code)] // Used by test_commands binary
     async fn exec_no_project<T: serde::de::DeserializeOwned>(&self, args: &[&str]) -> Result<T> {
         let timeout = Duration::from_secs(self.timeout_secs);
         let future = Command::new("az")
@@ -104,80 +108,207 @@ impl AzureCli {
         serde_json::from_str(&stdout).context("Failed to parse az output")
     }
 
-    /// Get single work item by ID (with relations)
-    #[allow(dead_code)]
-    pub async fn get_work_item(&self, id: i32) -> Result<WorkItem> {
-        self.exec_no_project(&[
-            "boards",
-            "work-item",
-            "show",
-            "--id",
-            &id.to_string(),
-            "--expand",
-            "relations",
-        ])
-        .await
+    /// `az devops invoke` for a resource, without the request specifics
+    fn invoke_command(
+        &self,
+        area: &str,
+        resource: &str,
+        route_parameters: &[String],
+        api_version: &str,
+    ) -> Command {
+        let mut cmd = Command::new("az");
+        cmd.args(["devops", "invoke"])
+            .args(["--area", area])
+            .args(["--resource", resource]);
+        if !route_parameters.is_empty() {
+            cmd.arg("--route-parameters").args(route_parameters);
+        }
+        cmd.args(["--api-version", api_version])
+            .args(["--org", &self.organization])
+            .args(["--output", "json"]);
+        cmd
     }
 
-    /// Query work items by WIQL - returns full work items directly
-    async fn query_work_items(&self, wiql: &str) -> Result<Vec<WorkItem>> {
-        // WIQL query returns work items with all requested fields directly
-        let items: Vec<WorkItem> = self.exec(&["boards", "query", "--wiql", wiql]).await?;
+    /// Run a GET through `az devops invoke` and parse the JSON response
+    async fn invoke<T: serde::de::DeserializeOwned>(
+        &self,
+        area: &str,
+        resource: &str,
+        route_parameters: &[String],
+        query_parameters: &[String],
+    ) -> Result<T> {
+        let mut cmd = self.invoke_command(area, resource, route_parameters, "7.1");
+        if !query_parameters.is_empty() {
+            cmd.arg("--query-parameters").args(query_parameters);
+        }
+
+        let output = tokio::time::timeout(Duration::from_secs(self.timeout_secs), cmd.output())
+            .await
+            .context("Azure CLI request timed out")?
+            .with_context(|| format!("Failed to get {resource}"))?;
 
-        Ok(items)
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Failed to get {resource}: {}", stderr.trim());
+        }
+        serde_json::from_slice(&output.stdout)
+            .with_context(|| format!("Failed to parse {resource} response"))
     }
 
-    /// Update a work item field
-    pub async fn update_work_item(&self, id: i32, field: &str, value: &str) -> Result<WorkItem> {
-        let field_arg = match field {
-            "state" => "--state",
-            "title" => "--title",
-            "assigned-to" => "--assigned-to",
-            _ => anyhow::bail!("Unknown field: {field}"),
-        };
+    /// Run `az devops invoke` with a JSON body (written to a temp file since
+    /// invoke only accepts `--in-file`)
+    async fn invoke_with_body(
+        &self,
+        area: &str,
+        resource: &str,
+        route_parameters: &[String],
+        http_method: &str,
+        body: &serde_json::Value,
+        temp_name: &str,
+    ) -> Result<std::process::Output> {
+        let temp_path =
+            std::env::temp_dir().join(format!("{temp_name}_{}.json", std::process::id()));
+        tokio::fs::write(&temp_path, body.to_string()).await?;
 
-        // work-item update doesn't accept --project
-        self.exec_no_project(&[
-            "boards",
-            "work-item",
-            "update",
-            "--id",
-            &id.to_string(),
-            field_arg,
-            value,
-        ])
-        .await
+        let mut cmd = self.invoke_command(area, resource, route_parameters, "7.1");
+        cmd.args(["--http-method", http_method])
+            .args(["--in-file", temp_path.to_str().unwrap()]);
+        let output = tokio::time::timeout(Duration::from_secs(self.timeout_secs), cmd.output())
+            .await
+            .context("Azure CLI request timed out");
+
+        // Clean up temp file
+        let _ = tokio::fs::remove_file(&temp_path).await;
+
+        output?.context("Failed to run az devops invoke")
     }
 
-    /// Get team members (kept for API compatibility but users are extracted from work items)
-    #[allow(dead_code)]
-    pub async fn get_team_members(&self) -> Result<Vec<User>> {
-        Ok(vec![]) // Users are now extracted from work items in App
+    /// Send a JSON Patch document to a work item through `az devops invoke`;
+    /// `what` names the change in the error message
+    async fn patch_work_item(
+        &self,
+        id: i32,
+        body: &serde_json::Value,
+        what: &str,
+        query_parameters: &[&str],
+    ) -> Result<WorkItem> {
+        let temp_path =
+            std::env::temp_dir().join(format!("patch_{id}_{}.json", std::process::id()));
+        tokio::fs::write(&temp_path, body.to_string()).await?;
+
+        let mut cmd = self.invoke_command("wit", "workitems", &[format!("id={id}")], "7.1");
+        cmd.args(["--http-method", "PATCH"])
+            .args(["--in-file", temp_path.to_str().unwrap()])
+            .args(["--media-type", "application/json-patch+json"]);
+        if !query_parameters.is_empty() {
+            cmd.arg("--query-parameters").args(query_parameters);
+        }
+        let output = tokio::time::timeout(Duration::from_secs(self.timeout_secs), cmd.output())
+            .await
+            .context("Azure CLI request timed out");
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        let output = output?.context("Failed to run az devops invoke")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Failed to update {what}: {}", stderr.trim());
+        }
+        serde_json::from_slice(&output.stdout).context("Failed to parse work item response")
+    }
+
+    /// Run a request against a work item's comments, which only a preview API
+    /// version serves, and parse the JSON response (if any)
+    async fn invoke_comments<T: serde::de::DeserializeOwned>(
+        &self,
+        id: i32,
+        comment_id: Option<i32>,
+        http_method: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<Option<T>> {
+        let mut route_parameters = vec![
+            format!("project={}", self.project),
+            format!("workItemId={id}"),
+        ];
+        if let Some(comment_id) = comment_id {
+            route_parameters.push(format!("commentId={comment_id}"));
+        }
+        let mut cmd =
+            self.invoke_command("wit", "comments", &route_parameters, COMMENTS_API_VERSION);
+        cmd.args(["--http-method", http_method]);
+
+        let temp_path =
+            std::env::temp_dir().join(format!("comment_{id}_{}.json", std::process::id()));
+        if let Some(body) = body {
+            tokio::fs::write(&temp_path, body.to_string()).await?;
+            cmd.args(["--in-file", temp_path.to_str().unwrap()]);
+        }
+        let output = tokio::time::timeout(Duration::from_secs(self.timeout_secs), cmd.output())
+            .await
+            .context("Azure CLI request timed out");
+        if body.is_some() {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+        }
+        let output = output?.context("Failed to run az devops invoke")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Comment request failed: {}", stderr.trim());
+        }
+        if output.stdout.iter().all(u8::is_ascii_whitespace) {
+            return Ok(None);
+        }
+        serde_json::from_slice(&output.stdout)
+            .map(Some)
+            .context("Failed to parse comments response")
     }
 
-    /// Get current user email from az account
-    pub async fn get_current_user() -> Result<String> {
+    /// Resolve an email / unique name to an identity ID
+    async fn resolve_identity(&self, name: &str) -> Result<String> {
         #[derive(serde::Deserialize)]
-        struct Account {
-            user: AccountUser,
+        struct Identity {
+            id: String,
         }
         #[derive(serde::Deserialize)]
-        struct AccountUser {
-            name: String,
+        struct Response {
+            #[serde(default)]
+            value: Vec<Identity>,
         }
 
-        let timeout = Duration::from_secs(10);
-        let future = Command::new("az")
-            .args(["account", "show", "--output", "json"])
-            .output();
+        let response: Response = self
+            .invoke(
+                "IMS",
+                "Identities",
+                &[],
+                &[
+                    "searchFilter=General".to_string(),
+                    format!("filterValue={name}"),
+                ],
+            )
+            .await?;
+        response
+            .value
+            .into_iter()
+            .next()
+            .map(|i| i.id)
+            .with_context(|| format!("Unknown user: {name}"))
+    }
 
-        let output = tokio::time::timeout(timeout, future)
-            .await
-            .context("Azure CLI request timed out")?
-            .context("Failed to get current user")?;
+    /// Turn the `mailto:` mention links of a comment into identity mentions,
+    /// as the REST backend does; unknown addresses stay plain links
+    async fn resolve_mentions(&self, html: &str) -> String {
+        let mut html = html.to_string();
+        for address in crate::discussion::mention_addresses(&html) {
+            if let Ok(id) = self.resolve_identity(&address).await {
+                html = crate::discussion::link_identity(&html, &address, &id);
+            }
+        }
+        html
+    }
 
-        let account: Account = serde_json::from_slice(&output.stdout)?;
-        Ok(account.user.name)
+    /// Get team members (kept for API compatibility but users are extracted from work items)
+    #[allow(dead_code)]
+    pub async fn get_team_members(&self) -> Result<Vec<User>> {
+        Ok(vec![]) // Users are now extracted from work items in App
     }
 
     /// Build parent-child hierarchy from flat list (preserves original order from WIQL)
@@ -236,75 +367,371 @@ impl AzureCli {
             .filter_map(|&id| build_tree(id, &mut by_id, &children_map, 0))
             .collect()
     }
+}
 
-    /// List all pipeline definitions
-    #[allow(dead_code)]
-    pub async fn list_pipelines(&self) -> Result<Vec<Pipeline>> {
-        let output = Command::new("az")
-            .args(["pipelines", "list"])
-            .args(["--org", &self.organization])
-            .args(["--project", &self.project])
-            .args(["--output", "json"])
-            .output()
+/// Work item fields loaded for the sprint backlog (shared by all backends)
+pub(crate) const SPRINT_FIELDS: &[&str] = &[
+    "System.Id",
+    "System.Title",
+    "System.State",
+    "System.WorkItemType",
+    "System.AssignedTo",
+    "System.Parent",
+    "System.Description",
+    "System.IterationPath",
+    "System.Tags",
+    "Microsoft.VSTS.Scheduling.RemainingWork",
+    "Microsoft.VSTS.Scheduling.OriginalEstimate",
+    "Microsoft.VSTS.Scheduling.CompletedWork",
+    "System.BoardColumn",
+    "System.BoardLane",
+];
+
+/// WIQL with its column list replaced by the fields the work item list needs
+/// (`az boards query` returns only the selected columns)
+pub(crate) fn with_list_fields(wiql: &str) -> String {
+    let upper = wiql.to_ascii_uppercase();
+    let is_keyword = |idx: usize, len: usize| {
+        let before = upper[..idx].chars().next_back();
+        let after = upper[idx + len..].chars().next();
+        before.is_none_or(|c| c.is_whitespace() || c == ']')
+            && after.is_some_and(char::is_whitespace)
+    };
+    let select = upper
+        .match_indices("SELECT")
+        .find(|(i, _)| is_keyword(*i, 6));
+    let from = upper.match_indices("FROM").find(|(i, _)| is_keyword(*i, 4));
+    let (Some((select, _)), Some((from, _))) = (select, from) else {
+        return wiql.to_string();
+    };
+    if from < select {
+        return wiql.to_string();
+    }
+    let columns: Vec<String> = SPRINT_FIELDS.iter().map(|f| format!("[{f}]")).collect();
+    format!(
+        "{}SELECT {} {}",
+        &wiql[..select],
+        columns.join(", "),
+        &wiql[from..]
+    )
+}
+
+/// WIQL query for all work items in a sprint
+pub(crate) fn sprint_wiql(iteration_path: &str) -> String {
+    let columns: Vec<String> = SPRINT_FIELDS.iter().map(|f| format!("[{f}]")).collect();
+    format!(
+        "SELECT {} FROM WorkItems WHERE [System.IterationPath] = '{iteration_path}' ORDER BY [System.WorkItemType], [System.Title]",
+        columns.join(", ")
+    )
+}
+
+impl DevOpsBackend for AzureCli {
+    fn for_project(&self, project: &ProjectConfig) -> Self {
+        Self {
+            organization: project.organization.clone(),
+            project: project.project.clone(),
+            team: project.team.clone(),
+            timeout_secs: project.overrides.api_timeout.unwrap_or(self.timeout_secs),
+        }
+    }
+
+    async fn get_sprints(&self) -> Result<Vec<Sprint>> {
+        self.exec(&["boards", "iteration", "team", "list", "--team", &self.team])
+            .await
+    }
+
+    async fn get_team_capacity(&self, iteration_id: &str) -> Result<TeamCapacity> {
+        let route = |iteration: Option<&str>| {
+            let mut params = vec![
+                format!("project={}", self.project),
+                format!("team={}", self.team),
+            ];
+            params.extend(iteration.map(|id| format!("iterationId={id}")));
+            params
+        };
+        let iteration = route(Some(iteration_id));
+        let mut capacity: TeamCapacity = self.invoke("work", "capacities", &iteration, &[]).await?;
+        let days_off: TeamDaysOff = self.invoke("work", "teamdaysoff", &iteration, &[]).await?;
+        let settings: TeamSettings = self
+            .invoke("work", "teamsettings", &route(None), &[])
             .await?;
+        capacity.team_days_off = days_off.days_off;
+        capacity.working_days = settings.working_days;
+        Ok(capacity)
+    }
 
-        if !output.status.success() {
-            let err = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Failed to list pipelines: {err}");
+    async fn get_sprint_work_items(&self, iteration_path: &str) -> Result<Vec<WorkItem>> {
+        let items = self.query_work_items(&sprint_wiql(iteration_path)).await?;
+        Ok(Self::build_hierarchy(items))
+    }
+
+    async fn query_work_items(&self, wiql: &str) -> Result<Vec<WorkItem>> {
+        // WIQL query returns work items with all requested fields directly
+        self.exec(&["boards", "query", "--wiql", &with_list_fields(wiql)])
+            .await
+    }
+
+    async fn list_queries(&self) -> Result<Vec<QueryHierarchyItem>> {
+        #[derive(serde::Deserialize)]
+        struct Response {
+            #[serde(default)]
+            value: Vec<QueryHierarchyItem>,
         }
 
-        let pipelines: Vec<Pipeline> = serde_json::from_slice(&output.stdout)?;
-        Ok(pipelines)
+        let response: Response = self
+            .invoke(
+                "wit",
+                "queries",
+                &[format!("project={}", self.project)],
+                &["$depth=2".to_string(), "$expand=wiql".to_string()],
+            )
+            .await?;
+        Ok(response.value)
     }
 
-    /// List runs for a specific pipeline
-    #[allow(dead_code)]
-    pub async fn list_pipeline_runs(&self, pipeline_id: i32) -> Result<Vec<PipelineRun>> {
-        let output = Command::new("az")
-            .args(["pipelines", "runs", "list"])
-            .args(["--org", &self.organization])
-            .args(["--project", &self.project])
-            .args(["--pipeline-ids", &pipeline_id.to_string()])
-            .args(["--output", "json"])
-            .output()
+    async fn get_boards(&self) -> Result<Vec<Board>> {
+        #[derive(serde::Deserialize)]
+        struct Response {
+            #[serde(default)]
+            value: Vec<Board>,
+        }
+
+        let route = |id: Option<&str>| {
+            let mut params = vec![
+                format!("project={}", self.project),
+                format!("team={}", self.team),
+            ];
+            params.extend(id.map(|id| format!("id={id}")));
+            params
+        };
+        // The list only carries names; columns and lanes come with each board
+        let list: Response = self.invoke("work", "boards", &route(None), &[]).await?;
+        let mut boards = Vec::with_capacity(list.value.len());
+        for board in &list.value {
+            boards.push(
+                self.invoke("work", "boards", &route(Some(&board.id)), &[])
+                    .await?,
+            );
+        }
+        Ok(boards)
+    }
+
+    async fn get_work_item(&self, id: i32) -> Result<WorkItem> {
+        self.exec_no_project(&[
+            "boards",
+            "work-item",
+            "show",
+            "--id",
+            &id.to_string(),
+            "--expand",
+            "relations",
+        ])
+        .await
+    }
+
+    async fn get_work_items_with_relations(&self, ids: &[i32]) -> Result<Vec<WorkItem>> {
+        #[derive(serde::Deserialize)]
+        struct Response {
+            #[serde(default)]
+            value: Vec<Option<WorkItem>>,
+        }
+
+        let Some(first) = ids.first() else {
+            return Ok(Vec::new());
+        };
+        // One `az` call per batch instead of `work-item show` per item; the
+        // endpoint rejects a field list together with an expand
+        let output = self
+            .invoke_with_body(
+                "wit",
+                "workitemsbatch",
+                &[format!("project={}", self.project)],
+                "POST",
+                &serde_json::json!({ "ids": ids, "$expand": "relations", "errorPolicy": "omit" }),
+                &format!("workitems_batch_{first}"),
+            )
             .await?;
 
         if !output.status.success() {
-            let err = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Failed to list pipeline runs: {err}");
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Failed to fetch work items: {stderr}");
         }
+        let response: Response =
+            serde_json::from_slice(&output.stdout).context("Failed to parse work items")?;
+        Ok(response.value.into_iter().flatten().collect())
+    }
 
-        let runs: Vec<PipelineRun> = serde_json::from_slice(&output.stdout)?;
-        Ok(runs)
+    async fn get_work_item_revisions(&self, id: i32) -> Result<Vec<WorkItem>> {
+        #[derive(serde::Deserialize)]
+        struct Response {
+            #[serde(default)]
+            value: Vec<WorkItem>,
+        }
+
+        let response: Response = self
+            .invoke(
+                "wit",
+                "revisions",
+                &[format!("project={}", self.project), format!("id={id}")],
+                &[],
+            )
+            .await?;
+        Ok(response.value)
     }
 
-    /// Trigger a pipeline run
-    #[allow(dead_code)]
-    pub async fn trigger_pipeline(&self, pipeline_id: i32, branch: &str) -> Result<PipelineRun> {
-        let output = Command::new("az")
-            .args(["pipelines", "run"])
-            .args(["--org", &self.organization])
-            .args(["--project", &self.project])
-            .args(["--id", &pipeline_id.to_string()])
-            .args(["--branch", branch])
-            .args(["--output", "json"])
-            .output()
+    async fn get_work_item_updates(&self, id: i32) -> Result<Vec<WorkItemUpdate>> {
+        #[derive(serde::Deserialize)]
+        struct Response {
+            #[serde(default)]
+            value: Vec<WorkItemUpdate>,
+        }
+
+        let response: Response = self
+            .invoke(
+                "wit",
+                "updates",
+                &[format!("project={}", self.project), format!("id={id}")],
+                &[],
+            )
             .await?;
+        Ok(response.value)
+    }
 
-        if !output.status.success() {
-            let err = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Failed to trigger pipeline: {err}");
+    async fn get_work_item_comments(&self, id: i32) -> Result<Vec<WorkItemComment>> {
+        let list: Option<WorkItemCommentList> = self.invoke_comments(id, None, "GET", None).await?;
+        Ok(list.unwrap_or_default().comments)
+    }
+
+    async fn add_work_item_comment(&self, id: i32, text: &str) -> Result<WorkItemComment> {
+        let text = self.resolve_mentions(text).await;
+        let body = serde_json::json!({ "text": text });
+        self.invoke_comments(id, None, "POST", Some(&body))
+            .await?
+            .context("Empty response to the new comment")
+    }
+
+    async fn update_work_item_comment(
+        &self,
+        id: i32,
+        comment_id: i32,
+        text: &str,
+    ) -> Result<WorkItemComment> {
+        let text = self.resolve_mentions(text).await;
+        let body = serde_json::json!({ "text": text });
+        self.invoke_comments(id, Some(comment_id), "PATCH", Some(&body))
+            .await?
+            .context("Empty response to the comment update")
+    }
+
+    async fn delete_work_item_comment(&self, id: i32, comment_id: i32) -> Result<()> {
+        self.invoke_comments::<serde_json::Value>(id, Some(comment_id), "DELETE", None)
+            .await?;
+        Ok(())
+    }
+
+    async fn update_work_item(&self, id: i32, updates: &[FieldUpdate]) -> Result<WorkItem> {
+        // `work-item update --fields` can only set a field to "", so this goes
+        // through invoke as a JSON Patch that removes cleared fields
+        let body = FieldUpdate::patch_document(updates);
+        self.patch_work_item(id, &body, &format!("#{id}"), &[])
+            .await
+    }
+
+    async fn update_work_item_links(
+        &self,
+        id: i32,
+        add: &[WorkItemRelation],
+        remove: &[WorkItemRelation],
+    ) -> Result<WorkItem> {
+        // `work-item relation add` only knows work item targets, so this goes
+        // through invoke as a JSON Patch; links are removed by index, so they
+        // come from the current revision
+        let item = self.get_work_item(id).await?;
+        let body = WorkItemRelation::patch_document(&item, add, remove)?;
+        self.patch_work_item(
+            id,
+            &body,
+            &format!("links of #{id}"),
+            &["$expand=relations"],
+        )
+        .await
+    }
+
+    async fn create_work_item(&self, item: &NewWorkItem) -> Result<WorkItem> {
+        let fields: Vec<String> = item
+            .fields
+            .iter()
+            .filter(|u| u.field != WorkItemFields::TITLE)
+            .filter_map(|u| match &u.value {
+                Some(serde_json::Value::String(s)) => Some(format!("{}={s}", u.field)),
+                Some(v) => Some(format!("{}={v}", u.field)),
+                None => None,
+            })
+            .collect();
+
+        let mut args = vec![
+            "boards",
+            "work-item",
+            "create",
+            "--type",
+            &item.work_item_type,
+            "--title",
+            item.title(),
+        ];
+        if !fields.is_empty() {
+            args.push("--fields");
+            args.extend(fields.iter().map(String::as_str));
+        }
+        let created: WorkItem = self.exec(&args).await?;
+
+        let Some(parent_id) = item.parent_id else {
+            return Ok(created);
+        };
+        // relation add doesn't accept --project
+        let id = created.id.to_string();
+        let parent = parent_id.to_string();
+        self.exec_no_project(&[
+            "boards",
+            "work-item",
+            "relation",
+            "add",
+            "--id",
+            &id,
+            "--relation-type",
+            "parent",
+            "--target-id",
+            &parent,
+        ])
+        .await
+    }
+
+    async fn get_current_user(&self) -> Result<String> {
+        #[derive(serde::Deserialize)]
+        struct Account {
+            user: AccountUser,
         }
+        #[derive(serde::Deserialize)]
+        struct AccountUser {
+            name: String,
+        }
+
+        let timeout = Duration::from_secs(10);
+        let future = Command::new("az")
+            .args(["account", "show", "--output", "json"])
+            .output();
+
+        let output = tokio::time::timeout(timeout, future)
+            .await
+            .context("Azure CLI request timed out")?
+            .context("Failed to get current user")?;
 
-        let run: PipelineRun = serde_json::from_slice(&output.stdout)?;
-        Ok(run)
+        let account: Account = serde_json::from_slice(&output.stdout)?;
+        Ok(account.user.name)
     }
 
-    /// List all release definitions
-    #[allow(dead_code)]
-    pub async fn list_release_definitions(&self) -> Result<Vec<ReleaseDefinition>> {
+    async fn list_pipelines(&self) -> Result<Vec<Pipeline>> {
         let output = Command::new("az")
-            .args(["pipelines", "release", "definition", "list"])
+            .args(["pipelines", "list"])
             .args(["--org", &self.organization])
             .args(["--project", &self.project])
             .args(["--output", "json"])
@@ -313,92 +740,130 @@ impl AzureCli {
 
         if !output.status.success() {
             let err = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Failed to list release definitions: {err}");
+            anyhow::bail!("Failed to list pipelines: {err}");
         }
 
-        let definitions: Vec<ReleaseDefinition> = serde_json::from_slice(&output.stdout)?;
-        // Filter out deleted/disabled
-        Ok(definitions
-            .into_iter()
-            .filter(|d| !d.is_deleted && !d.is_disabled)
-            .collect())
+        let pipelines: Vec<Pipeline> = serde_json::from_slice(&output.stdout)?;
+        Ok(pipelines)
     }
 
-    /// List releases (optionally filtered by definition)
-    #[allow(dead_code)]
-    pub async fn list_releases(&self, definition_id: Option<i32>) -> Result<Vec<Release>> {
+    async fn list_pipeline_runs(
+        &self,
+        pipeline_id: i32,
+        top: Option<u32>,
+    ) -> Result<Vec<PipelineRun>> {
         let mut cmd = Command::new("az");
-        cmd.args(["pipelines", "release", "list"])
+        cmd.args(["pipelines", "runs", "list"])
             .args(["--org", &self.organization])
             .args(["--project", &self.project])
-            .args(["--output", "json"]);
+            .args(["--pipeline-ids", &pipeline_id.to_string()]);
 
-        if let Some(def_id) = definition_id {
-            cmd.args(["--definition-id", &def_id.to_string()]);
+        if let Some(top) = top {
+            cmd.args(["--top", &top.to_string()]);
         }
 
-        let output = cmd.output().await?;
+        let output = cmd.args(["--output", "json"]).output().await?;
 
         if !output.status.success() {
             let err = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Failed to list releases: {err}");
+            anyhow::bail!("Failed to list pipeline runs: {err}");
         }
 
-        let releases: Vec<Release> = serde_json::from_slice(&output.stdout)?;
-        Ok(releases)
+        let runs: Vec<PipelineRun> = serde_json::from_slice(&output.stdout)?;
+        Ok(runs)
     }
 
-    /// Get release details (includes environments)
-    #[allow(dead_code)]
-    pub async fn get_release(&self, release_id: i32) -> Result<Release> {
-        let output = Command::new("az")
-            .args(["pipelines", "release", "show"])
-            .args(["--org", &self.organization])
-            .args(["--project", &self.project])
-            .args(["--id", &release_id.to_string()])
-            .args(["--output", "json"])
-            .output()
-            .await?;
+    async fn get_pipeline_run(&self, run_id: i32) -> Result<PipelineRun> {
+        self.invoke(
+            "build",
+            "builds",
+            &[
+                format!("project={}", self.project),
+                format!("buildId={run_id}"),
+            ],
+            &[],
+        )
+        .await
+    }
+
+    async fn get_pipeline_definition(&self, pipeline_id: i32) -> Result<PipelineDefinition> {
+        self.invoke(
+            "build",
+            "definitions",
+            &[
+                format!("project={}", self.project),
+                format!("definitionId={pipeline_id}"),
+            ],
+            &[],
+        )
+        .await
+    }
+
+    async fn trigger_pipeline(
+        &self,
+        pipeline_id: i32,
+        request: &PipelineRunRequest,
+    ) -> Result<PipelineRun> {
+        #[derive(serde::Deserialize)]
+        struct Run {
+            id: i32,
+        }
+
+        // `az pipelines run` can't skip stages, so go through the Runs API
+        let output = self
+            .invoke_with_body(
+                "pipelines",
+                "runs",
+                &[
+                    format!("project={}", self.project),
+                    format!("pipelineId={pipeline_id}"),
+                ],
+                "POST",
+                &run_pipeline_body(request),
+                &format!("pipeline_run_{pipeline_id}"),
+            )
+            .await
+            .context("Failed to trigger pipeline")?;
 
         if !output.status.success() {
             let err = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Failed to get release: {err}");
+            anyhow::bail!("Failed to trigger pipeline: {}", err.trim());
         }
 
-        let release: Release = serde_json::from_slice(&output.stdout)?;
-        Ok(release)
+        let run: Run = serde_json::from_slice(&output.stdout)?;
+        self.invoke(
+            "build",
+            "builds",
+            &[
+                format!("project={}", self.project),
+                format!("buildId={}", run.id),
+            ],
+            &[],
+        )
+        .await
     }
 
-    /// Get build timeline (jobs, tasks, stages)
-    #[allow(dead_code)]
-    pub async fn get_build_timeline(&self, build_id: i32) -> Result<Vec<TimelineRecord>> {
+    async fn cancel_pipeline_run(&self, run_id: i32) -> Result<()> {
         let output = Command::new("az")
-            .args(["devops", "invoke"])
-            .args(["--area", "build"])
-            .args(["--resource", "timeline"])
-            .args([
-                "--route-parameters",
-                &format!("project={}", self.project),
-                &format!("buildId={build_id}"),
-            ])
+            .args(["pipelines", "build", "update"])
+            .args(["--id", &run_id.to_string()])
+            .args(["--status", "cancelling"])
             .args(["--org", &self.organization])
+            .args(["--project", &self.project])
             .args(["--output", "json"])
             .output()
-            .await?;
+            .await
+            .context("Failed to cancel pipeline run")?;
 
         if !output.status.success() {
-            let err = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Failed to get timeline: {err}");
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Failed to cancel pipeline run: {stderr}");
         }
 
-        let response: TimelineResponse = serde_json::from_slice(&output.stdout)?;
-        Ok(response.records)
+        Ok(())
     }
 
-    /// Get build timeline with optional changeId for efficient delta updates
-    /// Returns None if no changes since last changeId (very lightweight call)
-    #[allow(dead_code)]
-    pub async fn get_build_timeline_delta(
+    async fn get_build_timeline_delta(
         &self,
         build_id: i32,
         last_change_id: Option<i32>,
@@ -439,9 +904,7 @@ impl AzureCli {
         Ok(Some((response.records, response.change_id)))
     }
 
-    /// Get build log content
-    #[allow(dead_code)]
-    pub async fn get_build_log(&self, build_id: i32, log_id: i32) -> Result<Vec<String>> {
+    async fn get_build_log(&self, build_id: i32, log_id: i32) -> Result<Vec<String>> {
         let output = Command::new("az")
             .args(["devops", "invoke"])
             .args(["--area", "build"])
@@ -466,90 +929,146 @@ impl AzureCli {
         Ok(response.value)
     }
 
-    /// Get pending approvals for the current user
-    #[allow(dead_code)]
-    pub async fn get_pending_approvals(&self) -> Result<Vec<Approval>> {
+    async fn list_test_runs(&self, build_id: i32) -> Result<Vec<TestRun>> {
+        #[derive(serde::Deserialize)]
+        struct Response {
+            #[serde(default)]
+            value: Vec<TestRun>,
+        }
+
+        let response: Response = self
+            .invoke(
+                "test",
+                "runs",
+                &[format!("project={}", self.project)],
+                &[
+                    format!("buildUri={}", build_uri(build_id)),
+                    "includeRunDetails=true".to_string(),
+                ],
+            )
+            .await?;
+        Ok(response.value)
+    }
+
+    async fn get_failed_test_results(&self, test_run_id: i32) -> Result<Vec<TestCaseResult>> {
+        #[derive(serde::Deserialize)]
+        struct Response {
+            #[serde(default)]
+            value: Vec<TestCaseResult>,
+        }
+
+        let response: Response = self
+            .invoke(
+                "test",
+                "results",
+                &[
+                    format!("project={}", self.project),
+                    format!("runId={test_run_id}"),
+                ],
+                &["outcomes=Failed".to_string(), "$top=1000".to_string()],
+            )
+            .await?;
+        Ok(response.value)
+    }
+
+    async fn list_build_artifacts(&self, build_id: i32) -> Result<Vec<BuildArtifact>> {
+        #[derive(serde::Deserialize)]
+        struct Response {
+            #[serde(default)]
+            value: Vec<BuildArtifact>,
+        }
+
+        let response: Response = self
+            .invoke(
+                "build",
+                "artifacts",
+                &[
+                    format!("project={}", self.project),
+                    format!("buildId={build_id}"),
+                ],
+                &[],
+            )
+            .await?;
+        Ok(response.value)
+    }
+
+    async fn list_artifact_items(
+        &self,
+        container_id: i64,
+        folder: &str,
+    ) -> Result<Vec<ArtifactItem>> {
+        #[derive(serde::Deserialize)]
+        struct Response {
+            #[serde(default)]
+            value: Vec<ArtifactItem>,
+        }
+
+        // File containers live outside the project; `az rest` attaches the token
+        let url = container_url(&self.organization, container_id, folder, "isShallow=false");
         let output = Command::new("az")
-            .args(["devops", "invoke"])
-            .args(["--area", "release"])
-            .args(["--resource", "approvals"])
-            .args(["--route-parameters", &format!("project={}", self.project)])
-            .args([
-                "--query-parameters",
-                "statusFilter=pending",
-                "includeMyGroupApprovals=true",
-            ])
-            .args(["--org", &self.organization])
-            .args(["--output", "json"])
+            .args(["rest", "--method", "get"])
+            .args(["--url", &url])
+            .args(["--resource", AZURE_DEVOPS_RESOURCE])
             .output()
             .await
-            .context("Failed to execute az devops invoke for approvals")?;
+            .context("Failed to list artifact files")?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            bail!("Failed to get pending approvals: {stderr}");
+            bail!("Failed to list artifact files: {}", stderr.trim());
         }
-
-        let response: ApprovalsResponse =
-            serde_json::from_slice(&output.stdout).context("Failed to parse approvals response")?;
+        let response: Response =
+            serde_json::from_slice(&output.stdout).context("Failed to parse artifact files")?;
         Ok(response.value)
     }
 
-    /// Approve or reject a release approval
-    #[allow(dead_code)]
-    pub async fn update_approval(
+    async fn download_artifact(
         &self,
-        approval_id: i32,
-        status: &str,
-        comments: Option<&str>,
-    ) -> Result<Approval> {
-        // Azure DevOps Approvals API expects an array of approval objects with id included
-        let body = serde_json::json!([{
-            "id": approval_id,
-            "status": status,
-            "comments": comments.unwrap_or("")
-        }]);
-        let body_str = serde_json::to_string(&body)?;
+        artifact: &BuildArtifact,
+        item: Option<&ArtifactItem>,
+        dest: &Path,
+    ) -> Result<()> {
+        let url = artifact_download_url(&self.organization, artifact, item)?;
+        let output = Command::new("az")
+            .args(["rest", "--method", "get"])
+            .args(["--url", &url])
+            .args(["--resource", AZURE_DEVOPS_RESOURCE])
+            .arg("--output-file")
+            .arg(dest)
+            .output()
+            .await
+            .context("Failed to download artifact")?;
 
-        // Write body to temp file since az devops invoke needs --in-file
-        let temp_path = std::env::temp_dir().join(format!("approval_{approval_id}.json"));
-        tokio::fs::write(&temp_path, &body_str).await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Failed to download {}: {}", artifact.name, stderr.trim());
+        }
+        Ok(())
+    }
 
-        // Use bulk approvals endpoint (no approvalId in route) with array body
+    async fn list_release_definitions(&self) -> Result<Vec<ReleaseDefinition>> {
         let output = Command::new("az")
-            .args(["devops", "invoke"])
-            .args(["--area", "release"])
-            .args(["--resource", "approvals"])
-            .args(["--route-parameters", &format!("project={}", self.project)])
-            .args(["--http-method", "PATCH"])
-            .args(["--api-version", "7.1"])
-            .args(["--in-file", temp_path.to_str().unwrap()])
+            .args(["pipelines", "release", "definition", "list"])
             .args(["--org", &self.organization])
+            .args(["--project", &self.project])
             .args(["--output", "json"])
             .output()
-            .await
-            .context("Failed to execute az devops invoke for approval update")?;
-
-        // Clean up temp file
-        let _ = tokio::fs::remove_file(&temp_path).await;
+            .await?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            bail!("Failed to update approval: {stderr}");
+            let err = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Failed to list release definitions: {err}");
         }
 
-        // Response is an array, extract first element
-        let approvals: Vec<Approval> =
-            serde_json::from_slice(&output.stdout).context("Failed to parse approval response")?;
-        approvals
+        let definitions: Vec<ReleaseDefinition> = serde_json::from_slice(&output.stdout)?;
+        // Filter out deleted/disabled
+        Ok(definitions
             .into_iter()
-            .next()
-            .context("No approval returned in response")
+            .filter(|d| !d.is_deleted && !d.is_disabled)
+            .collect())
     }
 
-    /// Get release definition details (for trigger dialog)
-    #[allow(dead_code)]
-    pub async fn get_release_definition_detail(
+    async fn get_release_definition_detail(
         &self,
         definition_id: i32,
     ) -> Result<ReleaseDefinitionDetail> {
@@ -573,14 +1092,69 @@ impl AzureCli {
             bail!("Failed to get release definition: {stderr}");
         }
 
-        let detail: ReleaseDefinitionDetail = serde_json::from_slice(&output.stdout)
-            .context("Failed to parse release definition detail")?;
-        Ok(detail)
+        match serde_json::from_slice::<ReleaseDefinitionDetail>(&output.stdout) {
+            Ok(detail) => Ok(detail),
+            Err(e) => {
+                // Try parsing as wrapped response (some API versions wrap in "value")
+                #[derive(serde::Deserialize)]
+                struct Wrapper {
+                    environments: Vec<ReleaseDefinitionEnvironment>,
+                }
+                let wrapper: Wrapper = serde_json::from_slice(&output.stdout)
+                    .map_err(|_| e)
+                    .context("Failed to parse release definition detail")?;
+                Ok(ReleaseDefinitionDetail {
+                    id: definition_id,
+                    name: None,
+                    environments: wrapper.environments,
+                    artifacts: vec![],
+                })
+            }
+        }
+    }
+
+    async fn list_releases(&self, definition_id: Option<i32>) -> Result<Vec<Release>> {
+        let mut cmd = Command::new("az");
+        cmd.args(["pipelines", "release", "list"])
+            .args(["--org", &self.organization])
+            .args(["--project", &self.project])
+            .args(["--output", "json"]);
+
+        if let Some(def_id) = definition_id {
+            cmd.args(["--definition-id", &def_id.to_string()]);
+        }
+
+        let output = cmd.output().await?;
+
+        if !output.status.success() {
+            let err = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Failed to list releases: {err}");
+        }
+
+        let releases: Vec<Release> = serde_json::from_slice(&output.stdout)?;
+        Ok(releases)
     }
 
-    /// Create a new release
-    #[allow(dead_code)]
-    pub async fn create_release(
+    async fn get_release(&self, release_id: i32) -> Result<Release> {
+        let output = Command::new("az")
+            .args(["pipelines", "release", "show"])
+            .args(["--org", &self.organization])
+            .args(["--project", &self.project])
+            .args(["--id", &release_id.to_string()])
+            .args(["--output", "json"])
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            let err = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Failed to get release: {err}");
+        }
+
+        let release: Release = serde_json::from_slice(&output.stdout)?;
+        Ok(release)
+    }
+
+    async fn create_release(
         &self,
         definition_id: i32,
         description: Option<&str>,
@@ -600,7 +1174,10 @@ impl AzureCli {
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            bail!("Failed to create release: {stderr}");
+            bail!(
+                "Release failed: {}",
+                stderr.lines().next().unwrap_or(&stderr)
+            );
         }
 
         let release: Release =
@@ -608,182 +1185,185 @@ impl AzureCli {
         Ok(release)
     }
 
-    /// Cancel a running pipeline build
-    #[allow(dead_code)]
-    pub async fn cancel_pipeline_run(&self, run_id: i32) -> Result<()> {
-        let output = Command::new("az")
-            .args(["pipelines", "build", "update"])
-            .args(["--id", &run_id.to_string()])
-            .args(["--status", "cancelling"])
-            .args(["--org", &self.organization])
-            .args(["--project", &self.project])
-            .args(["--output", "json"])
-            .output()
+    async fn cancel_release(&self, release_id: i32) -> Result<()> {
+        let output = self
+            .invoke_with_body(
+                "release",
+                "releases",
+                &[
+                    format!("project={}", self.project),
+                    format!("releaseId={release_id}"),
+                ],
+                "PATCH",
+                &serde_json::json!({ "status": "abandoned" }),
+                &format!("release_cancel_{release_id}"),
+            )
+            .await
+            .context("Failed to cancel release")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Failed to cancel release: {stderr}");
+        }
+
+        Ok(())
+    }
+
+    async fn cancel_release_environment(&self, release_id: i32, environment_id: i32) -> Result<()> {
+        let output = self
+            .invoke_with_body(
+                "release",
+                "environments",
+                &[
+                    format!("project={}", self.project),
+                    format!("releaseId={release_id}"),
+                    format!("environmentId={environment_id}"),
+                ],
+                "PATCH",
+                &serde_json::json!({
+                    "status": "canceled",
+                    "comment": "Canceled from lazyops"
+                }),
+                &format!("env_cancel_{release_id}_{environment_id}"),
+            )
             .await
-            .context("Failed to cancel pipeline run")?;
+            .context("Failed to cancel release environment")?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            bail!("Failed to cancel pipeline run: {stderr}");
+            bail!("Failed to cancel release environment: {stderr}");
         }
 
         Ok(())
     }
 
-    /// Retrigger a pipeline on the same branch (creates new run)
-    #[allow(dead_code)]
-    pub async fn retrigger_pipeline_run(
+    async fn redeploy_release_environment(
         &self,
-        pipeline_id: i32,
-        branch: &str,
-    ) -> Result<PipelineRun> {
-        // Reuse the existing trigger_pipeline method
-        self.trigger_pipeline(pipeline_id, branch).await
-    }
+        release_id: i32,
+        environment_id: i32,
+    ) -> Result<()> {
+        let output = self
+            .invoke_with_body(
+                "release",
+                "environments",
+                &[
+                    format!("project={}", self.project),
+                    format!("releaseId={release_id}"),
+                    format!("environmentId={environment_id}"),
+                ],
+                "PATCH",
+                &serde_json::json!({
+                    "status": "inProgress",
+                    "comment": "Redeployed from lazyops"
+                }),
+                &format!("env_redeploy_{release_id}_{environment_id}"),
+            )
+            .await
+            .context("Failed to redeploy release environment")?;
 
-    /// Abandon/cancel a release
-    #[allow(dead_code)]
-    pub async fn cancel_release(&self, release_id: i32) -> Result<()> {
-        let body = serde_json::json!({
-            "status": "abandoned"
-        });
-        let body_str = serde_json::to_string(&body)?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Failed to redeploy release environment: {stderr}");
+        }
 
-        // Write body to temp file since az devops invoke needs --in-file
-        let temp_path = std::env::temp_dir().join(format!("release_cancel_{release_id}.json"));
-        tokio::fs::write(&temp_path, &body_str).await?;
+        Ok(())
+    }
 
+    async fn get_release_task_log(&self, log_url: &str) -> Result<Vec<String>> {
+        // `az rest` attaches the access token for us
         let output = Command::new("az")
-            .args(["devops", "invoke"])
-            .args(["--area", "release"])
-            .args(["--resource", "releases"])
-            .args([
-                "--route-parameters",
-                &format!("project={}", self.project),
-                &format!("releaseId={release_id}"),
-            ])
-            .args(["--http-method", "PATCH"])
-            .args(["--in-file", temp_path.to_str().unwrap()])
-            .args(["--org", &self.organization])
-            .args(["--output", "json"])
+            .args(["rest", "--method", "get"])
+            .args(["--url", log_url])
+            .args(["--resource", AZURE_DEVOPS_RESOURCE])
             .output()
             .await
-            .context("Failed to cancel release")?;
-
-        // Clean up temp file
-        let _ = tokio::fs::remove_file(&temp_path).await;
+            .context("Failed to fetch release task log")?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            bail!("Failed to cancel release: {stderr}");
+            bail!("Failed to fetch release task log: {stderr}");
         }
 
-        Ok(())
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|l| l.to_string())
+            .collect())
     }
 
-    /// Cancel a specific release environment/stage
-    #[allow(dead_code)]
-    pub async fn cancel_release_environment(
-        &self,
-        release_id: i32,
-        environment_id: i32,
-    ) -> Result<()> {
-        let body = serde_json::json!({
-            "status": "canceled",
-            "comment": "Canceled from lazyops"
-        });
-        let body_str = serde_json::to_string(&body)?;
-
-        // Write body to temp file since az devops invoke needs --in-file
-        let temp_path =
-            std::env::temp_dir().join(format!("env_cancel_{release_id}_{environment_id}.json"));
-        tokio::fs::write(&temp_path, &body_str).await?;
-
+    async fn get_pending_approvals(&self) -> Result<Vec<Approval>> {
         let output = Command::new("az")
             .args(["devops", "invoke"])
             .args(["--area", "release"])
-            .args(["--resource", "releases/environments"])
+            .args(["--resource", "approvals"])
+            .args(["--route-parameters", &format!("project={}", self.project)])
             .args([
-                "--route-parameters",
-                &format!("project={}", self.project),
-                &format!("releaseId={release_id}"),
-                &format!("environmentId={environment_id}"),
+                "--query-parameters",
+                "statusFilter=pending",
+                "includeMyGroupApprovals=true",
             ])
-            .args(["--http-method", "PATCH"])
-            .args(["--in-file", temp_path.to_str().unwrap()])
             .args(["--org", &self.organization])
             .args(["--output", "json"])
             .output()
             .await
-            .context("Failed to cancel release environment")?;
-
-        // Clean up temp file
-        let _ = tokio::fs::remove_file(&temp_path).await;
+            .context("Failed to execute az devops invoke for approvals")?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            bail!("Failed to cancel release environment: {stderr}");
+            bail!("Failed to get pending approvals: {stderr}");
         }
 
-        Ok(())
+        let response: ApprovalsResponse =
+            serde_json::from_slice(&output.stdout).context("Failed to parse approvals response")?;
+        Ok(response.value)
     }
 
-    /// Redeploy/retrigger a specific release environment/stage
-    #[allow(dead_code)]
-    pub async fn redeploy_release_environment(
+    async fn update_approvals(
         &self,
-        release_id: i32,
-        environment_id: i32,
+        approval_ids: &[i32],
+        status: &str,
+        comments: &str,
     ) -> Result<()> {
-        let body = serde_json::json!({
-            "status": "inProgress",
-            "comment": "Redeployed from lazyops"
-        });
-        let body_str = serde_json::to_string(&body)?;
-
-        // Write body to temp file since az devops invoke needs --in-file
-        let temp_path =
-            std::env::temp_dir().join(format!("env_redeploy_{release_id}_{environment_id}.json"));
-        tokio::fs::write(&temp_path, &body_str).await?;
+        // Azure DevOps Approvals API expects an array of approval objects with id included
+        let body: Vec<_> = approval_ids
+            .iter()
+            .map(|id| {
+                serde_json::json!({
+                    "id": id,
+                    "status": status,
+                    "comments": comments
+                })
+            })
+            .collect();
 
-        let output = Command::new("az")
-            .args(["devops", "invoke"])
-            .args(["--area", "release"])
-            .args(["--resource", "releases/environments"])
-            .args([
-                "--route-parameters",
-                &format!("project={}", self.project),
-                &format!("releaseId={release_id}"),
-                &format!("environmentId={environment_id}"),
-            ])
-            .args(["--http-method", "PATCH"])
-            .args(["--in-file", temp_path.to_str().unwrap()])
-            .args(["--org", &self.organization])
-            .args(["--output", "json"])
-            .output()
+        // Use bulk approvals endpoint (no approvalId in route) with array body
+        let output = self
+            .invoke_with_body(
+                "release",
+                "approvals",
+                &[format!("project={}", self.project)],
+                "PATCH",
+                &serde_json::Value::Array(body),
+                "approvals",
+            )
             .await
-            .context("Failed to redeploy release environment")?;
-
-        // Clean up temp file
-        let _ = tokio::fs::remove_file(&temp_path).await;
+            .context("Failed to execute az devops invoke for approval update")?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            bail!("Failed to redeploy release environment: {stderr}");
+            bail!(
+                "Approval failed: {}",
+                stderr.lines().next().unwrap_or(&stderr)
+            );
         }
 
         Ok(())
     }
 
-    /// List all repositories
-    #[allow(dead_code)]
-    pub async fn list_repositories(&self) -> Result<Vec<Repository>> {
+    async fn list_repositories(&self) -> Result<Vec<Repository>> {
         self.exec(&["repos", "list"]).await
     }
 
-    /// List pull requests with optional filters
-    #[allow(dead_code)]
-    pub async fn list_pull_requests(
+    async fn list_pull_requests(
         &self,
         repository: Option<&str>,
         status: &str,
@@ -825,16 +1405,37 @@ impl AzureCli {
         Ok(prs)
     }
 
-    /// Get a single pull request by ID
-    #[allow(dead_code)]
-    pub async fn get_pull_request(&self, id: i32) -> Result<PullRequest> {
+    async fn get_pull_request(&self, id: i32) -> Result<PullRequest> {
         self.exec_no_project(&["repos", "pr", "show", "--id", &id.to_string()])
             .await
     }
 
-    /// List threads (comments) on a pull request
-    #[allow(dead_code)]
-    pub async fn list_pr_threads(&self, repository_id: &str, pr_id: i32) -> Result<Vec<PRThread>> {
+    async fn get_commit(&self, repository_id: &str, commit_id: &str) -> Result<GitCommitRef> {
+        let output = Command::new("az")
+            .args(["devops", "invoke"])
+            .args(["--area", "git"])
+            .args(["--resource", "commits"])
+            .args([
+                "--route-parameters",
+                &format!("project={}", self.project),
+                &format!("repositoryId={repository_id}"),
+                &format!("commitId={commit_id}"),
+            ])
+            .args(["--org", &self.organization])
+            .args(["--output", "json"])
+            .output()
+            .await
+            .context("Failed to get commit")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Failed to get commit: {stderr}");
+        }
+
+        Ok(serde_json::from_slice(&output.stdout)?)
+    }
+
+    async fn list_pr_threads(&self, repository_id: &str, pr_id: i32) -> Result<Vec<PRThread>> {
         let timeout = Duration::from_secs(self.timeout_secs);
         let future = Command::new("az")
             .args(["devops", "invoke"])
@@ -864,9 +1465,119 @@ impl AzureCli {
         Ok(response.value)
     }
 
-    /// List policies evaluated on a pull request
-    #[allow(dead_code)]
-    pub async fn list_pr_policies(&self, pr_id: i32) -> Result<Vec<PRPolicy>> {
+    async fn list_pr_iterations(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+    ) -> Result<Vec<PRIteration>> {
+        #[derive(serde::Deserialize)]
+        struct Response {
+            #[serde(default)]
+            value: Vec<PRIteration>,
+        }
+
+        let response: Response = self
+            .invoke(
+                "git",
+                "pullRequestIterations",
+                &[
+                    format!("project={}", self.project),
+                    format!("repositoryId={repository_id}"),
+                    format!("pullRequestId={pr_id}"),
+                ],
+                &[],
+            )
+            .await?;
+        Ok(response.value)
+    }
+
+    async fn list_pr_changes(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+        iteration_id: i32,
+        compare_to: i32,
+    ) -> Result<Vec<PRChange>> {
+        let response: PRChangesResponse = self
+            .invoke(
+                "git",
+                "pullRequestIterationChanges",
+                &[
+                    format!("project={}", self.project),
+                    format!("repositoryId={repository_id}"),
+                    format!("pullRequestId={pr_id}"),
+                    format!("iterationId={iteration_id}"),
+                ],
+                &[format!("$compareTo={compare_to}")],
+            )
+            .await?;
+        Ok(response.files())
+    }
+
+    async fn get_file_content(
+        &self,
+        repository_id: &str,
+        path: &str,
+        commit_id: &str,
+    ) -> Result<String> {
+        #[derive(serde::Deserialize)]
+        struct Item {
+            #[serde(default)]
+            content: String,
+        }
+
+        let item: Item = self
+            .invoke(
+                "git",
+                "items",
+                &[
+                    format!("project={}", self.project),
+                    format!("repositoryId={repository_id}"),
+                ],
+                &[
+                    format!("path={path}"),
+                    "includeContent=true".to_string(),
+                    "versionDescriptor.versionType=commit".to_string(),
+                    format!("versionDescriptor.version={commit_id}"),
+                ],
+            )
+            .await?;
+        Ok(item.content)
+    }
+
+    async fn get_file_at_ref(
+        &self,
+        repository_id: &str,
+        path: &str,
+        git_ref: &str,
+    ) -> Result<String> {
+        #[derive(serde::Deserialize)]
+        struct Item {
+            #[serde(default)]
+            content: String,
+        }
+
+        let (version_type, version) = version_descriptor(git_ref);
+        let item: Item = self
+            .invoke(
+                "git",
+                "items",
+                &[
+                    format!("project={}", self.project),
+                    format!("repositoryId={repository_id}"),
+                ],
+                &[
+                    format!("path={path}"),
+                    "includeContent=true".to_string(),
+                    format!("versionDescriptor.versionType={version_type}"),
+                    format!("versionDescriptor.version={version}"),
+                ],
+            )
+            .await?;
+        Ok(item.content)
+    }
+
+    async fn list_pr_policies(&self, pr_id: i32) -> Result<Vec<PRPolicy>> {
         let timeout = Duration::from_secs(self.timeout_secs);
         let future = Command::new("az")
             .args(["repos", "pr", "policy", "list"])
@@ -890,10 +1601,7 @@ impl AzureCli {
         Ok(policies)
     }
 
-    /// Set vote on a pull request
-    /// Votes: "approve", "approve-with-suggestions", "reject", "reset", "wait-for-author"
-    #[allow(dead_code)]
-    pub async fn set_pr_vote(&self, pr_id: i32, vote: &str) -> Result<()> {
+    async fn set_pr_vote(&self, pr_id: i32, vote: &str) -> Result<()> {
         let timeout = Duration::from_secs(self.timeout_secs);
         let future = Command::new("az")
             .args(["repos", "pr", "set-vote"])
@@ -916,9 +1624,7 @@ impl AzureCli {
         Ok(())
     }
 
-    /// Update a pull request (status, title, description, draft)
-    #[allow(dead_code)]
-    pub async fn update_pr(
+    async fn update_pr(
         &self,
         pr_id: i32,
         status: Option<&str>,
@@ -964,31 +1670,133 @@ impl AzureCli {
         Ok(pr)
     }
 
-    /// Create a new pull request
-    #[allow(dead_code)]
-    pub async fn create_pr(
+    async fn complete_pr(
         &self,
-        repository: &str,
-        source_branch: &str,
-        target_branch: &str,
-        title: &str,
-        description: Option<&str>,
-        draft: bool,
+        pr_id: i32,
+        options: &PRCompletionOptions,
+        auto_complete: bool,
     ) -> Result<PullRequest> {
+        let pr = self.get_pull_request(pr_id).await?;
+        let repository_id = pr
+            .repository
+            .map(|r| r.id)
+            .with_context(|| format!("PR #{pr_id} has no repository"))?;
+
+        // `az repos pr update` has no merge strategy flag: store the options
+        // through the API first, the update below keeps them
+        let body = serde_json::json!({ "completionOptions": completion_options_body(options) });
+        let output = self
+            .invoke_with_body(
+                "git",
+                "pullRequests",
+                &[
+                    format!("project={}", self.project),
+                    format!("repositoryId={repository_id}"),
+                    format!("pullRequestId={pr_id}"),
+                ],
+                "PATCH",
+                &body,
+                &format!("pr_complete_{pr_id}"),
+            )
+            .await
+            .context("Failed to set completion options")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Failed to set completion options: {stderr}");
+        }
+
+        let timeout = Duration::from_secs(self.timeout_secs);
+        let mut cmd = Command::new("az");
+        cmd.args(["repos", "pr", "update"])
+            .args(["--id", &pr_id.to_string()])
+            .args(["--org", &self.organization])
+            .args(["--output", "json"]);
+        if auto_complete {
+            cmd.args(["--auto-complete", "true"]);
+        } else {
+            cmd.args(["--status", "completed"]);
+        }
+        if options.bypass_policy == Some(true) {
+            cmd.args(["--bypass-policy", "true"]).args([
+                "--bypass-policy-reason",
+                options.bypass_reason.as_deref().unwrap_or_default(),
+            ]);
+        }
+
+        let output = tokio::time::timeout(timeout, cmd.output())
+            .await
+            .context("Azure CLI request timed out")?
+            .context("Failed to complete PR")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Failed to complete PR: {stderr}");
+        }
+
+        let pr: PullRequest = serde_json::from_slice(&output.stdout)?;
+        Ok(pr)
+    }
+
+    async fn list_branches(&self, repository: &str) -> Result<Vec<GitRef>> {
+        self.exec(&[
+            "repos",
+            "ref",
+            "list",
+            "--repository",
+            repository,
+            "--filter",
+            "heads/",
+        ])
+        .await
+    }
+
+    async fn list_tags(&self, repository: &str) -> Result<Vec<GitRef>> {
+        self.exec(&[
+            "repos",
+            "ref",
+            "list",
+            "--repository",
+            repository,
+            "--filter",
+            "tags/",
+        ])
+        .await
+    }
+
+    async fn create_pr(&self, pr: &NewPullRequest) -> Result<PullRequest> {
         let timeout = Duration::from_secs(self.timeout_secs);
+        let flag = |on: bool| if on { "true" } else { "false" };
+        let options = &pr.completion_options;
+
         let mut cmd = Command::new("az");
         cmd.args(["repos", "pr", "create"])
-            .args(["--repository", repository])
-            .args(["--source-branch", source_branch])
-            .args(["--target-branch", target_branch])
-            .args(["--title", title])
-            .args(["--draft", if draft { "true" } else { "false" }])
+            .args(["--repository", &pr.repository])
+            .args(["--source-branch", &pr.source_branch])
+            .args(["--target-branch", &pr.target_branch])
+            .args(["--title", &pr.title])
+            .args(["--draft", flag(pr.draft)])
+            .args(["--auto-complete", flag(pr.auto_complete)])
+            .args([
+                "--delete-source-branch",
+                flag(options.delete_source_branch.unwrap_or(false)),
+            ])
+            .args(["--squash", flag(options.squash_merge.unwrap_or(false))])
             .args(["--org", &self.organization])
             .args(["--project", &self.project])
             .args(["--output", "json"]);
 
-        if let Some(desc) = description {
-            cmd.args(["--description", desc]);
+        if !pr.description.is_empty() {
+            cmd.args(["--description", &pr.description]);
+        }
+        if let Some(message) = &options.merge_commit_message {
+            cmd.args(["--merge-commit-message", message]);
+        }
+        if !pr.reviewers.is_empty() {
+            cmd.arg("--reviewers").args(&pr.reviewers);
+        }
+        if !pr.work_item_ids.is_empty() {
+            cmd.arg("--work-items")
+                .args(pr.work_item_ids.iter().map(|id| id.to_string()));
         }
 
         let future = cmd.output();
@@ -1006,62 +1814,112 @@ impl AzureCli {
         Ok(pr)
     }
 
-    /// Add a comment to a pull request (creates a new thread)
-    #[allow(dead_code)]
-    pub async fn add_pr_comment(
+    async fn add_pr_comment(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+        content: &str,
+        anchor: Option<&PRCommentAnchor>,
+    ) -> Result<()> {
+        let body = new_thread_body(content, anchor);
+
+        let output = self
+            .invoke_with_body(
+                "git",
+                "pullRequestThreads",
+                &[
+                    format!("project={}", self.project),
+                    format!("repositoryId={repository_id}"),
+                    format!("pullRequestId={pr_id}"),
+                ],
+                "POST",
+                &body,
+                &format!("pr_comment_{pr_id}"),
+            )
+            .await
+            .context("Failed to add PR comment")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Failed to add PR comment: {stderr}");
+        }
+
+        Ok(())
+    }
+
+    async fn reply_pr_thread(
         &self,
         repository_id: &str,
         pr_id: i32,
+        thread_id: i32,
         content: &str,
     ) -> Result<()> {
         let body = serde_json::json!({
-            "comments": [{
-                "content": content,
-                "parentCommentId": 0,
-                "commentType": 1
-            }],
-            "status": 1
+            "content": content,
+            "parentCommentId": 1,
+            "commentType": 1
         });
-        let body_str = serde_json::to_string(&body)?;
 
-        // Write body to temp file since az devops invoke needs --in-file
-        let temp_path =
-            std::env::temp_dir().join(format!("pr_comment_{}_{}.json", pr_id, std::process::id()));
-        tokio::fs::write(&temp_path, &body_str).await?;
-
-        let output = Command::new("az")
-            .args(["devops", "invoke"])
-            .args(["--area", "git"])
-            .args(["--resource", "pullRequestThreads"])
-            .args([
-                "--route-parameters",
-                &format!("project={}", self.project),
-                &format!("repositoryId={repository_id}"),
-                &format!("pullRequestId={pr_id}"),
-            ])
-            .args(["--http-method", "POST"])
-            .args(["--in-file", temp_path.to_str().unwrap()])
-            .args(["--api-version", "7.1"])
-            .args(["--org", &self.organization])
-            .args(["--output", "json"])
-            .output()
+        let output = self
+            .invoke_with_body(
+                "git",
+                "pullRequestThreadComments",
+                &[
+                    format!("project={}", self.project),
+                    format!("repositoryId={repository_id}"),
+                    format!("pullRequestId={pr_id}"),
+                    format!("threadId={thread_id}"),
+                ],
+                "POST",
+                &body,
+                &format!("pr_reply_{pr_id}_{thread_id}"),
+            )
             .await
-            .context("Failed to add PR comment")?;
+            .context("Failed to reply to PR thread")?;
 
-        // Clean up temp file
-        let _ = tokio::fs::remove_file(&temp_path).await;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Failed to reply to PR thread: {stderr}");
+        }
+
+        Ok(())
+    }
+
+    async fn set_pr_thread_status(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+        thread_id: i32,
+        status: &str,
+    ) -> Result<()> {
+        let body = serde_json::json!({ "status": status });
+
+        let output = self
+            .invoke_with_body(
+                "git",
+                "pullRequestThreads",
+                &[
+                    format!("project={}", self.project),
+                    format!("repositoryId={repository_id}"),
+                    format!("pullRequestId={pr_id}"),
+                    format!("threadId={thread_id}"),
+                ],
+                "PATCH",
+                &body,
+                &format!("pr_thread_{pr_id}_{thread_id}"),
+            )
+            .await
+            .context("Failed to update PR thread")?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            bail!("Failed to add PR comment: {stderr}");
+            bail!("Failed to update PR thread: {stderr}");
         }
 
         Ok(())
     }
 
-    /// List work items linked to a pull request
-    #[allow(dead_code)]
-    pub async fn list_pr_work_items(&self, pr_id: i32) -> Result<serde_json::Value> {
+    async fn list_pr_work_items(&self, pr_id: i32) -> Result<serde_json::Value> {
         let timeout = Duration::from_secs(self.timeout_secs);
         let future = Command::new("az")
             .args(["repos", "pr", "work-item", "list"])
@@ -1107,6 +1965,9 @@ mod tests {
                 remaining_work: None,
                 original_estimate: None,
                 completed_work: None,
+                area_path: None,
+                priority: None,
+                other: Default::default(),
             },
             relations: None,
             children: vec![],
@@ -1114,6 +1975,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_with_list_fields_replaces_columns() {
+        let wiql = with_list_fields(
+            "select [System.Id] from WorkItems where [System.Title] contains 'from' order by [System.Id]",
+        );
+        assert!(
+            wiql.starts_with("SELECT [System.Id], [System.Title], "),
+            "{wiql}"
+        );
+        assert!(
+            wiql.ends_with(
+                " from WorkItems where [System.Title] contains 'from' order by [System.Id]"
+            ),
+            "{wiql}"
+        );
+        // Left alone when it doesn't look like a flat query
+        assert_eq!(with_list_fields("nonsense"), "nonsense");
+    }
+
     #[test]
     fn test_build_hierarchy_empty() {
         let result = AzureCli::build_hierarchy(vec![]);
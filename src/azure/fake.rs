@@ -0,0 +1,1891 @@
+use crate::azure::backend::{DevOpsBackend, WORK_ITEMS_BATCH_SIZE};
+use crate::azure::rest::vote_value;
+use crate::azure::types::*;
+use crate::azure::AzureCli;
+use crate::config::ProjectConfig;
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Fixture data bundled for `lazyops --demo`
+const DEMO_FIXTURES: &str = include_str!("../../fixtures/demo.json");
+
+/// Timeline change ID reported by the fake (timelines never change on their own)
+const TIMELINE_CHANGE_ID: i32 = 1;
+
+/// Everything the fake backend serves, shaped like the Azure DevOps responses.
+///
+/// Maps are keyed by pipeline ID (`pipeline_runs`), build ID (`timelines`,
+/// `test_runs`, `artifacts`), log ID (`build_logs`), test run ID
+/// (`test_results`), file container ID (`artifact_items`), PR ID (`pr_*`),
+/// commit ID (`commits`), log URL (`release_task_logs`), repository name
+/// (`branches`, `tags`, `files`) and sprint ID (`capacities`).
+///
+/// `pr_files` holds the versions of each file a PR touches: index 0 is the
+/// target branch, index N the source branch after iteration N, and `null`
+/// means the file doesn't exist. The fake names the commits `{pr}.{N}`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Fixtures {
+    pub current_user: String,
+    pub sprints: Vec<Sprint>,
+    /// Flat list; sprint membership comes from `System.IterationPath`
+    pub work_items: Vec<WorkItem>,
+    pub pipelines: Vec<Pipeline>,
+    pub pipeline_definitions: Vec<PipelineDefinition>,
+    pub pipeline_runs: HashMap<i32, Vec<PipelineRun>>,
+    /// Requests the runs queued through the fake were made with, by run ID
+    pub run_requests: HashMap<i32, PipelineRunRequest>,
+    pub timelines: HashMap<i32, Vec<TimelineRecord>>,
+    pub build_logs: HashMap<i32, Vec<String>>,
+    pub test_runs: HashMap<i32, Vec<TestRun>>,
+    /// Every result of a test run, whatever its outcome
+    pub test_results: HashMap<i32, Vec<TestCaseResult>>,
+    pub artifacts: HashMap<i32, Vec<BuildArtifact>>,
+    /// Every file and folder of a container, whatever its folder
+    pub artifact_items: HashMap<i64, Vec<ArtifactItem>>,
+    pub release_definitions: Vec<ReleaseDefinition>,
+    pub release_definition_details: Vec<ReleaseDefinitionDetail>,
+    pub releases: Vec<Release>,
+    pub release_task_logs: HashMap<String, Vec<String>>,
+    pub approvals: Vec<Approval>,
+    pub repositories: Vec<Repository>,
+    /// Short branch names per repository
+    pub branches: HashMap<String, Vec<String>>,
+    /// Tag names per repository
+    pub tags: HashMap<String, Vec<String>>,
+    /// File contents per repository and path, the same on every branch
+    pub files: HashMap<String, BTreeMap<String, String>>,
+    pub pull_requests: Vec<PullRequest>,
+    pub pr_threads: HashMap<i32, Vec<PRThread>>,
+    pub pr_policies: HashMap<i32, Vec<PRPolicy>>,
+    pub pr_work_items: HashMap<i32, Vec<i32>>,
+    pub pr_files: HashMap<i32, BTreeMap<String, Vec<Option<String>>>>,
+    pub commits: HashMap<String, String>,
+    pub queries: Vec<QueryHierarchyItem>,
+    pub boards: Vec<Board>,
+    /// Earlier revisions per work item, oldest first; the work item itself
+    /// is the latest revision
+    pub revisions: HashMap<i32, Vec<FixtureRevision>>,
+    /// Discussion per work item, newest first
+    pub comments: HashMap<i32, Vec<WorkItemComment>>,
+    /// Team capacity and days off per sprint ID
+    pub capacities: HashMap<String, TeamCapacity>,
+    /// Days of the week the team works, in lower case
+    pub working_days: Vec<String>,
+}
+
+/// Earlier state of a fixture work item, as the changes from the revision before
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FixtureRevision {
+    /// Fields this revision set
+    pub fields: serde_json::Map<String, serde_json::Value>,
+    /// Links as of this revision, when they changed
+    pub relations: Option<Vec<WorkItemRelation>>,
+}
+
+/// In-memory backend for offline demos and tests.
+///
+/// Reads come from fixtures; mutations (state changes, votes, approvals,
+/// cancels, ...) update the in-memory copy so later reads see them. Clones
+/// and `for_project` handles share the same data.
+#[derive(Debug, Clone)]
+pub struct FakeBackend {
+    data: Arc<Mutex<Fixtures>>,
+}
+
+impl FakeBackend {
+    pub fn new(fixtures: Fixtures) -> Self {
+        Self {
+            data: Arc::new(Mutex::new(fixtures)),
+        }
+    }
+
+    /// Load fixtures from JSON
+    pub fn from_json(json: &str) -> Result<Self> {
+        let fixtures: Fixtures = serde_json::from_str(json).context("Invalid fixture JSON")?;
+        Ok(Self::new(fixtures))
+    }
+
+    /// Backend preloaded with the bundled demo data
+    pub fn demo() -> Self {
+        Self::from_json(DEMO_FIXTURES).expect("bundled demo fixtures are valid")
+    }
+
+    /// Project entry matching the bundled demo data
+    pub fn demo_project() -> ProjectConfig {
+        ProjectConfig {
+            name: "lazyops-demo".to_string(),
+            organization: DEMO_ORG_URL.to_string(),
+            project: "Demo".to_string(),
+            team: "Demo Team".to_string(),
+            repository: None,
+            overrides: Default::default(),
+        }
+    }
+
+    /// Snapshot of the current data (for assertions)
+    #[cfg(test)]
+    pub fn fixtures(&self) -> Fixtures {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Fixtures> {
+        // A panic while holding the lock leaves plain data behind; keep serving it
+        self.data.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Identity of whoever made a revision; read-only, so not in `WorkItemFields`
+const CHANGED_BY: &str = "System.ChangedBy";
+
+/// Organization of the demo project; links to work items point into it
+const DEMO_ORG_URL: &str = "https://dev.azure.com/demo";
+
+impl Fixtures {
+    /// Keep the read-only board fields in step, like the server does: they
+    /// mirror the team's Kanban fields, and a new state moves the card to the
+    /// first column mapped to it
+    fn sync_board_fields(&self, item: &mut WorkItem, updates: &[FieldUpdate]) {
+        let updated = |suffix: &str| updates.iter().find(|u| u.field.ends_with(suffix));
+        if let Some(update) = updated("_Kanban.Lane") {
+            item.fields
+                .set(WorkItemFields::BOARD_LANE, update.value.as_ref());
+        }
+        if let Some(update) = updated("_Kanban.Column") {
+            item.fields
+                .set(WorkItemFields::BOARD_COLUMN, update.value.as_ref());
+            return;
+        }
+        if !updates.iter().any(|u| u.field == WorkItemFields::STATE) {
+            return;
+        }
+        let item_type = &item.fields.work_item_type;
+        let Some(board) = self.boards.iter().find(|b| {
+            b.columns
+                .first()
+                .is_some_and(|c| c.state_mappings.contains_key(item_type))
+        }) else {
+            return;
+        };
+        let state_of = |name: &str| {
+            board
+                .columns
+                .iter()
+                .find(|c| c.name == name)
+                .and_then(|c| c.state_mappings.get(item_type))
+        };
+        let current = item.fields.get(WorkItemFields::BOARD_COLUMN);
+        let current = current
+            .as_ref()
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        if state_of(current) == Some(&item.fields.state) {
+            return;
+        }
+        if let Some(column) = board
+            .columns
+            .iter()
+            .find(|c| c.state_mappings.get(item_type) == Some(&item.fields.state))
+        {
+            let name = serde_json::Value::from(column.name.as_str());
+            item.fields.set(WorkItemFields::BOARD_COLUMN, Some(&name));
+            if let Some(field) = &board.fields.column_field {
+                item.fields.set(&field.reference_name, Some(&name));
+            }
+        }
+    }
+
+    /// Every revision of a work item: the fixture revisions replayed in
+    /// order, then the item as it is now
+    fn revisions_of(&self, id: i32) -> Result<Vec<WorkItem>> {
+        let current = self
+            .work_items
+            .iter()
+            .find(|w| w.id == id)
+            .with_context(|| format!("Work item {id} not found"))?;
+        let mut fields = serde_json::Map::new();
+        let mut relations = None;
+        let mut revisions = Vec::new();
+        for (rev, revision) in self.revisions.get(&id).into_iter().flatten().enumerate() {
+            fields.extend(revision.fields.clone());
+            if revision.relations.is_some() {
+                relations = revision.relations.clone();
+            }
+            revisions.push(WorkItem {
+                id,
+                rev: rev as i32 + 1,
+                fields: serde_json::from_value(serde_json::Value::Object(fields.clone()))
+                    .with_context(|| format!("Invalid revision {} of #{id}", rev + 1))?,
+                relations: relations.clone(),
+                children: Vec::new(),
+                depth: 0,
+            });
+        }
+        revisions.push(current.clone());
+        Ok(revisions)
+    }
+
+    /// The update that turned `previous` into `revision`, as the server reports it
+    fn update_between(
+        update_id: i32,
+        previous: Option<&WorkItem>,
+        revision: &WorkItem,
+    ) -> WorkItemUpdate {
+        let fields_of =
+            |item: Option<&WorkItem>| match item.map(|w| serde_json::to_value(&w.fields)) {
+                Some(Ok(serde_json::Value::Object(fields))) => fields,
+                _ => serde_json::Map::new(),
+            };
+        let (old, new) = (fields_of(previous), fields_of(Some(revision)));
+        let fields = old
+            .keys()
+            .chain(new.keys())
+            .filter_map(|name| {
+                let old_value = old.get(name).filter(|v| !v.is_null()).cloned();
+                let new_value = new.get(name).filter(|v| !v.is_null()).cloned();
+                (old_value != new_value).then(|| {
+                    let change = WorkItemFieldChange {
+                        old_value,
+                        new_value,
+                    };
+                    (name.clone(), change)
+                })
+            })
+            .collect();
+
+        let links =
+            |item: Option<&WorkItem>| item.and_then(|w| w.relations.clone()).unwrap_or_default();
+        let (old_links, new_links) = (links(previous), links(Some(revision)));
+        let missing_from = |links: &[WorkItemRelation], other: &[WorkItemRelation]| {
+            links
+                .iter()
+                .filter(|l| !other.iter().any(|o| o.rel == l.rel && o.url == l.url))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        let added = missing_from(&new_links, &old_links);
+        let removed = missing_from(&old_links, &new_links);
+
+        WorkItemUpdate {
+            id: update_id,
+            rev: Some(revision.rev),
+            revised_by: revision
+                .fields
+                .get(CHANGED_BY)
+                .and_then(|v| serde_json::from_value(v).ok()),
+            revised_date: None,
+            fields,
+            relations: (!added.is_empty() || !removed.is_empty())
+                .then_some(WorkItemRelationChanges { added, removed }),
+        }
+    }
+
+    /// Record the current state of `item` as a revision before changing it
+    fn start_revision(&mut self, item: &mut WorkItem) {
+        if let Ok(serde_json::Value::Object(previous)) = serde_json::to_value(&item.fields) {
+            self.revisions
+                .entry(item.id)
+                .or_default()
+                .push(FixtureRevision {
+                    fields: previous,
+                    relations: item.relations.clone(),
+                });
+        }
+        item.fields.changed_date = Some(chrono::Utc::now());
+        let changed_by = serde_json::json!({
+            "displayName": self.display_name(&self.current_user),
+            "uniqueName": self.current_user,
+        });
+        item.fields.set(CHANGED_BY, Some(&changed_by));
+        item.rev += 1;
+    }
+
+    fn work_item_mut(&mut self, id: i32) -> Result<&mut WorkItem> {
+        self.work_items
+            .iter_mut()
+            .find(|w| w.id == id)
+            .with_context(|| format!("Work item {id} not found"))
+    }
+
+    fn pipeline_run_mut(&mut self, run_id: i32) -> Result<&mut PipelineRun> {
+        self.pipeline_runs
+            .values_mut()
+            .flat_map(|runs| runs.iter_mut())
+            .find(|r| r.id == run_id)
+            .with_context(|| format!("Pipeline run {run_id} not found"))
+    }
+
+    fn release_mut(&mut self, release_id: i32) -> Result<&mut Release> {
+        self.releases
+            .iter_mut()
+            .find(|r| r.id == release_id)
+            .with_context(|| format!("Release {release_id} not found"))
+    }
+
+    fn release_environment_mut(
+        &mut self,
+        release_id: i32,
+        environment_id: i32,
+    ) -> Result<&mut ReleaseEnvironment> {
+        self.release_mut(release_id)?
+            .environments
+            .iter_mut()
+            .flatten()
+            .find(|e| e.id == environment_id)
+            .with_context(|| format!("Environment {environment_id} not found"))
+    }
+
+    fn pull_request_mut(&mut self, pr_id: i32) -> Result<&mut PullRequest> {
+        self.pull_requests
+            .iter_mut()
+            .find(|p| p.pull_request_id == pr_id)
+            .with_context(|| format!("PR #{pr_id} not found"))
+    }
+
+    fn pr_thread_mut(&mut self, pr_id: i32, thread_id: i32) -> Result<&mut PRThread> {
+        self.pr_threads
+            .get_mut(&pr_id)
+            .and_then(|threads| threads.iter_mut().find(|t| t.id == thread_id))
+            .with_context(|| format!("Thread {thread_id} not found on PR #{pr_id}"))
+    }
+
+    /// Display name for a unique name, taken from any assignee in the data
+    fn display_name(&self, unique_name: &str) -> String {
+        self.work_items
+            .iter()
+            .filter_map(|w| w.fields.assigned_to.as_ref())
+            .find(|a| a.unique_name == unique_name)
+            .map(|a| a.display_name.clone())
+            .unwrap_or_else(|| unique_name.to_string())
+    }
+
+    fn current_user_ref(&self) -> PipelineUser {
+        PipelineUser {
+            display_name: Some(self.display_name(&self.current_user)),
+            unique_name: Some(self.current_user.clone()),
+        }
+    }
+
+    /// Number of iterations of a PR (at least one)
+    fn pr_iteration_count(&self, pr_id: i32) -> usize {
+        self.pr_files
+            .get(&pr_id)
+            .and_then(|files| files.values().map(|v| v.len().saturating_sub(1)).max())
+            .unwrap_or(0)
+            .max(1)
+    }
+
+    /// Content of a PR file after `iteration` (0 is the target branch)
+    fn pr_file_version(&self, pr_id: i32, path: &str, iteration: usize) -> Option<String> {
+        let versions = self.pr_files.get(&pr_id)?.get(path)?;
+        versions
+            .get(iteration.min(versions.len().checked_sub(1)?))
+            .cloned()
+            .flatten()
+    }
+
+    fn current_identity_ref(&self) -> IdentityRef {
+        IdentityRef {
+            display_name: Some(self.display_name(&self.current_user)),
+            unique_name: Some(self.current_user.clone()),
+            id: None,
+        }
+    }
+
+    /// A comment of a work item, which only its author may change
+    fn own_comment_mut(&mut self, id: i32, comment_id: i32) -> Result<&mut WorkItemComment> {
+        let current_user = self.current_user.clone();
+        let comment = self
+            .comments
+            .get_mut(&id)
+            .and_then(|comments| comments.iter_mut().find(|c| c.id == comment_id))
+            .with_context(|| format!("Comment {comment_id} not found on work item {id}"))?;
+        let author = comment
+            .created_by
+            .as_ref()
+            .and_then(|by| by.unique_name.as_deref());
+        if author != Some(current_user.as_str()) {
+            bail!("Only the author can change comment {comment_id}");
+        }
+        Ok(comment)
+    }
+
+    fn current_identity(&self) -> PRIdentityRef {
+        PRIdentityRef {
+            display_name: self.display_name(&self.current_user),
+            unique_name: Some(self.current_user.clone()),
+            ..Default::default()
+        }
+    }
+}
+
+/// One `[Field] op value` condition of a WIQL `WHERE` clause
+#[derive(Debug)]
+struct WiqlCondition {
+    field: String,
+    op: String,
+    value: String,
+}
+
+/// Conditions of the WIQL subset the fake understands: comparisons with `=`,
+/// `<>` or `CONTAINS` against quoted strings, numbers, `@Me` or `@project`,
+/// joined by `AND`
+fn wiql_conditions(wiql: &str) -> Result<Vec<WiqlCondition>> {
+    let upper = wiql.to_ascii_uppercase();
+    let Some(start) = upper.find(" WHERE ") else {
+        return Ok(Vec::new());
+    };
+    let end = upper.find(" ORDER BY ").unwrap_or(wiql.len());
+    let clause = &wiql[start + " WHERE ".len()..end];
+    let upper_clause = clause.to_ascii_uppercase();
+
+    let mut parts = Vec::new();
+    let mut rest = 0;
+    for (idx, _) in upper_clause.match_indices(" AND ") {
+        parts.push(&clause[rest..idx]);
+        rest = idx + " AND ".len();
+    }
+    parts.push(&clause[rest..]);
+
+    parts
+        .into_iter()
+        .map(|part| {
+            let part = part.trim().trim_start_matches('(').trim_end_matches(')');
+            let (field, rest) = part
+                .strip_prefix('[')
+                .and_then(|p| p.split_once(']'))
+                .with_context(|| format!("Unsupported WIQL condition: {part}"))?;
+            let (op, value) = rest
+                .trim()
+                .split_once(char::is_whitespace)
+                .with_context(|| format!("Unsupported WIQL condition: {part}"))?;
+            let op = op.to_ascii_uppercase();
+            if !matches!(op.as_str(), "=" | "<>" | "CONTAINS") {
+                bail!("Unsupported WIQL operator: {op}");
+            }
+            Ok(WiqlCondition {
+                field: field.to_string(),
+                op,
+                value: value.trim().trim_matches('\'').to_string(),
+            })
+        })
+        .collect()
+}
+
+impl WiqlCondition {
+    fn matches(&self, item: &WorkItem, current_user: &str) -> Result<bool> {
+        let expected = match self.value.to_ascii_lowercase().as_str() {
+            "@project" => return Ok(true),
+            "@me" => current_user.to_string(),
+            _ if self.value.starts_with('@') => bail!("Unsupported WIQL macro: {}", self.value),
+            _ => self.value.clone(),
+        };
+        let actual = match self.field.as_str() {
+            "System.Id" => item.id.to_string(),
+            "System.WorkItemType" => item.fields.work_item_type.clone(),
+            "System.TeamProject" => return Ok(true),
+            field => match item.fields.get(field) {
+                Some(serde_json::Value::String(s)) => s,
+                Some(value) => value.to_string(),
+                None => String::new(),
+            },
+        };
+        Ok(match self.op.as_str() {
+            "=" => actual.eq_ignore_ascii_case(&expected),
+            "<>" => !actual.eq_ignore_ascii_case(&expected),
+            _ => actual
+                .to_ascii_lowercase()
+                .contains(&expected.to_ascii_lowercase()),
+        })
+    }
+}
+
+impl DevOpsBackend for FakeBackend {
+    fn for_project(&self, _project: &ProjectConfig) -> Self {
+        self.clone()
+    }
+
+    async fn get_sprints(&self) -> Result<Vec<Sprint>> {
+        Ok(self.lock().sprints.clone())
+    }
+
+    async fn get_team_capacity(&self, iteration_id: &str) -> Result<TeamCapacity> {
+        let data = self.lock();
+        data.sprints
+            .iter()
+            .find(|s| s.id == iteration_id)
+            .with_context(|| format!("Sprint {iteration_id} not found"))?;
+        let mut capacity = data
+            .capacities
+            .get(iteration_id)
+            .cloned()
+            .unwrap_or_default();
+        capacity.working_days = data.working_days.clone();
+        Ok(capacity)
+    }
+
+    async fn get_sprint_work_items(&self, iteration_path: &str) -> Result<Vec<WorkItem>> {
+        let items: Vec<WorkItem> = self
+            .lock()
+            .work_items
+            .iter()
+            .filter(|w| w.fields.iteration_path.as_deref() == Some(iteration_path))
+            .cloned()
+            .collect();
+        Ok(AzureCli::build_hierarchy(items))
+    }
+
+    async fn query_work_items(&self, wiql: &str) -> Result<Vec<WorkItem>> {
+        let data = self.lock();
+        let conditions = wiql_conditions(wiql)?;
+        let mut items = Vec::new();
+        for item in &data.work_items {
+            let mut matches = true;
+            for condition in &conditions {
+                matches &= condition.matches(item, &data.current_user)?;
+            }
+            if matches {
+                items.push(item.clone());
+            }
+        }
+        Ok(items)
+    }
+
+    async fn list_queries(&self) -> Result<Vec<QueryHierarchyItem>> {
+        Ok(self.lock().queries.clone())
+    }
+
+    async fn get_boards(&self) -> Result<Vec<Board>> {
+        Ok(self.lock().boards.clone())
+    }
+
+    async fn get_work_item(&self, id: i32) -> Result<WorkItem> {
+        Ok(self.lock().work_item_mut(id)?.clone())
+    }
+
+    async fn get_work_items_with_relations(&self, ids: &[i32]) -> Result<Vec<WorkItem>> {
+        if ids.len() > WORK_ITEMS_BATCH_SIZE {
+            bail!("At most {WORK_ITEMS_BATCH_SIZE} work items per batch");
+        }
+        let data = self.lock();
+        Ok(ids
+            .iter()
+            .filter_map(|id| data.work_items.iter().find(|w| w.id == *id).cloned())
+            .collect())
+    }
+
+    async fn get_work_item_revisions(&self, id: i32) -> Result<Vec<WorkItem>> {
+        self.lock().revisions_of(id)
+    }
+
+    async fn get_work_item_updates(&self, id: i32) -> Result<Vec<WorkItemUpdate>> {
+        let revisions = self.lock().revisions_of(id)?;
+        let mut previous: Option<&WorkItem> = None;
+        let mut updates = Vec::with_capacity(revisions.len());
+        for (idx, revision) in revisions.iter().enumerate() {
+            updates.push(Fixtures::update_between(idx as i32 + 1, previous, revision));
+            previous = Some(revision);
+        }
+        Ok(updates)
+    }
+
+    async fn get_work_item_comments(&self, id: i32) -> Result<Vec<WorkItemComment>> {
+        let data = self.lock();
+        data.work_items
+            .iter()
+            .find(|w| w.id == id)
+            .with_context(|| format!("Work item {id} not found"))?;
+        Ok(data.comments.get(&id).cloned().unwrap_or_default())
+    }
+
+    async fn add_work_item_comment(&self, id: i32, text: &str) -> Result<WorkItemComment> {
+        let mut data = self.lock();
+        data.work_item_mut(id)?;
+        let author = data.current_identity_ref();
+        let comments = data.comments.entry(id).or_default();
+        let now = chrono::Utc::now();
+        let comment = WorkItemComment {
+            id: comments.iter().map(|c| c.id).max().unwrap_or(0) + 1,
+            text: text.to_string(),
+            created_by: Some(author),
+            created_date: Some(now),
+            modified_date: Some(now),
+        };
+        comments.insert(0, comment.clone());
+        Ok(comment)
+    }
+
+    async fn update_work_item_comment(
+        &self,
+        id: i32,
+        comment_id: i32,
+        text: &str,
+    ) -> Result<WorkItemComment> {
+        let mut data = self.lock();
+        let comment = data.own_comment_mut(id, comment_id)?;
+        comment.text = text.to_string();
+        comment.modified_date = Some(chrono::Utc::now());
+        Ok(comment.clone())
+    }
+
+    async fn delete_work_item_comment(&self, id: i32, comment_id: i32) -> Result<()> {
+        let mut data = self.lock();
+        data.own_comment_mut(id, comment_id)?;
+        if let Some(comments) = data.comments.get_mut(&id) {
+            comments.retain(|c| c.id != comment_id);
+        }
+        Ok(())
+    }
+
+    async fn update_work_item(&self, id: i32, updates: &[FieldUpdate]) -> Result<WorkItem> {
+        let mut data = self.lock();
+        let mut item = data.work_item_mut(id)?.clone();
+        data.start_revision(&mut item);
+        for update in updates {
+            item.fields.set(&update.field, update.value.as_ref());
+        }
+        data.sync_board_fields(&mut item, updates);
+        if let Some(assigned_to) = &mut item.fields.assigned_to {
+            assigned_to.display_name = data.display_name(&assigned_to.unique_name);
+        }
+        *data.work_item_mut(id)? = item.clone();
+        Ok(item)
+    }
+
+    async fn update_work_item_links(
+        &self,
+        id: i32,
+        add: &[WorkItemRelation],
+        remove: &[WorkItemRelation],
+    ) -> Result<WorkItem> {
+        let mut data = self.lock();
+        let mut item = data.work_item_mut(id)?.clone();
+        let mut relations = item.relations.clone().unwrap_or_default();
+        let mut parent_id = item.fields.parent_id;
+        // Both ends of a work item link record it; applied once all checks pass
+        let mut mirrored: Vec<(i32, WorkItemRelation, bool)> = Vec::new();
+        let mirror = |relation: &WorkItemRelation| {
+            let rel = WorkItemRelation::reverse_rel(&relation.rel)?;
+            Some((
+                relation.target_id()?,
+                WorkItemRelation::work_item(DEMO_ORG_URL, rel, id),
+            ))
+        };
+
+        // The demo data keeps parents in System.Parent only
+        for relation in remove {
+            if relation.rel == WorkItemRelation::PARENT {
+                if relation.target_id().is_none() || relation.target_id() != parent_id {
+                    bail!("#{id} has no such parent");
+                }
+                parent_id = None;
+                continue;
+            }
+            let idx = relations
+                .iter()
+                .position(|r| r.same_link(relation))
+                .with_context(|| format!("#{id} has no such link"))?;
+            relations.remove(idx);
+            mirrored.extend(mirror(relation).map(|(target, back)| (target, back, false)));
+        }
+        for relation in add {
+            let target = relation.target_id();
+            if target == Some(id) {
+                bail!("A work item can't link to itself");
+            }
+            if let Some(target) = target {
+                data.work_item_mut(target)?;
+            }
+            if relation.rel == WorkItemRelation::PARENT {
+                let mut ancestor = target;
+                while let Some(ancestor_id) = ancestor {
+                    if ancestor_id == id {
+                        bail!("#{id} can't be the parent of its own parent");
+                    }
+                    ancestor = data
+                        .work_items
+                        .iter()
+                        .find(|w| w.id == ancestor_id)
+                        .and_then(|w| w.fields.parent_id);
+                }
+                parent_id = target;
+                continue;
+            }
+            if relations.iter().any(|r| r.same_link(relation)) {
+                bail!("#{id} is already linked to that");
+            }
+            relations.push(relation.clone());
+            mirrored.extend(mirror(relation).map(|(target, back)| (target, back, true)));
+        }
+
+        data.start_revision(&mut item);
+        item.fields.parent_id = parent_id;
+        item.relations = Some(relations);
+        *data.work_item_mut(id)? = item.clone();
+        for (target, back, added) in mirrored {
+            let other = data.work_item_mut(target)?;
+            let links = other.relations.get_or_insert_with(Vec::new);
+            links.retain(|r| !r.same_link(&back));
+            if added {
+                links.push(back);
+            }
+        }
+        Ok(item)
+    }
+
+    async fn create_work_item(&self, new_item: &NewWorkItem) -> Result<WorkItem> {
+        let mut data = self.lock();
+        if let Some(parent_id) = new_item.parent_id {
+            data.work_item_mut(parent_id)?;
+        }
+        let id = data.work_items.iter().map(|w| w.id).max().unwrap_or(0) + 1;
+        let mut fields = WorkItemFields {
+            work_item_type: new_item.work_item_type.clone(),
+            state: "New".to_string(),
+            parent_id: new_item.parent_id,
+            ..Default::default()
+        };
+        for update in &new_item.fields {
+            fields.set(&update.field, update.value.as_ref());
+        }
+        if fields.title.trim().is_empty() {
+            bail!("Title is required");
+        }
+        if let Some(assigned_to) = &mut fields.assigned_to {
+            assigned_to.display_name = data.display_name(&assigned_to.unique_name);
+        }
+        let item = WorkItem {
+            id,
+            rev: 1,
+            fields,
+            relations: None,
+            children: Vec::new(),
+            depth: 0,
+        };
+        data.work_items.push(item.clone());
+        Ok(item)
+    }
+
+    async fn get_current_user(&self) -> Result<String> {
+        Ok(self.lock().current_user.clone())
+    }
+
+    async fn list_pipelines(&self) -> Result<Vec<Pipeline>> {
+        Ok(self.lock().pipelines.clone())
+    }
+
+    async fn list_pipeline_runs(
+        &self,
+        pipeline_id: i32,
+        top: Option<u32>,
+    ) -> Result<Vec<PipelineRun>> {
+        let data = self.lock();
+        let runs = data
+            .pipeline_runs
+            .get(&pipeline_id)
+            .cloned()
+            .unwrap_or_default();
+        let limit = top.map(|t| t as usize).unwrap_or(usize::MAX);
+        Ok(runs.into_iter().take(limit).collect())
+    }
+
+    async fn get_pipeline_run(&self, run_id: i32) -> Result<PipelineRun> {
+        self.lock()
+            .pipeline_runs
+            .values()
+            .flatten()
+            .find(|r| r.id == run_id)
+            .cloned()
+            .with_context(|| format!("Pipeline run {run_id} not found"))
+    }
+
+    async fn get_pipeline_definition(&self, pipeline_id: i32) -> Result<PipelineDefinition> {
+        let data = self.lock();
+        if let Some(definition) = data
+            .pipeline_definitions
+            .iter()
+            .find(|d| d.id == pipeline_id)
+        {
+            return Ok(definition.clone());
+        }
+        // Pipelines without fixture details are classic ones with no variables
+        let pipeline = data
+            .pipelines
+            .iter()
+            .find(|p| p.id == pipeline_id)
+            .with_context(|| format!("Pipeline {pipeline_id} not found"))?;
+        Ok(PipelineDefinition {
+            id: pipeline.id,
+            name: pipeline.name.clone(),
+            ..Default::default()
+        })
+    }
+
+    async fn trigger_pipeline(
+        &self,
+        pipeline_id: i32,
+        request: &PipelineRunRequest,
+    ) -> Result<PipelineRun> {
+        let mut data = self.lock();
+        if !data.pipelines.iter().any(|p| p.id == pipeline_id) {
+            bail!("Pipeline {pipeline_id} not found");
+        }
+
+        let id = data
+            .pipeline_runs
+            .values()
+            .flatten()
+            .map(|r| r.id)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let branch = &request.branch;
+        let source_branch = if branch.starts_with("refs/") {
+            branch.to_string()
+        } else {
+            format!("refs/heads/{branch}")
+        };
+        let run = PipelineRun {
+            id,
+            build_number: Some(format!("demo.{id}")),
+            status: Some("notStarted".to_string()),
+            source_branch: Some(source_branch),
+            queue_time: Some(chrono::Utc::now().to_rfc3339()),
+            requested_for: Some(data.current_user_ref()),
+            definition: data
+                .pipelines
+                .iter()
+                .find(|p| p.id == pipeline_id)
+                .map(|p| PipelineDefinitionRef {
+                    id: p.id,
+                    name: Some(p.name.clone()),
+                }),
+            ..Default::default()
+        };
+
+        data.pipeline_runs
+            .entry(pipeline_id)
+            .or_default()
+            .insert(0, run.clone());
+        data.run_requests.insert(id, request.clone());
+        Ok(run)
+    }
+
+    async fn cancel_pipeline_run(&self, run_id: i32) -> Result<()> {
+        let mut data = self.lock();
+        let run = data.pipeline_run_mut(run_id)?;
+        run.status = Some("completed".to_string());
+        run.result = Some("canceled".to_string());
+        Ok(())
+    }
+
+    async fn get_build_timeline_delta(
+        &self,
+        build_id: i32,
+        last_change_id: Option<i32>,
+    ) -> Result<Option<(Vec<TimelineRecord>, Option<i32>)>> {
+        if last_change_id == Some(TIMELINE_CHANGE_ID) {
+            return Ok(None);
+        }
+        let records = self
+            .lock()
+            .timelines
+            .get(&build_id)
+            .cloned()
+            .unwrap_or_default();
+        Ok(Some((records, Some(TIMELINE_CHANGE_ID))))
+    }
+
+    async fn get_build_log(&self, _build_id: i32, log_id: i32) -> Result<Vec<String>> {
+        self.lock()
+            .build_logs
+            .get(&log_id)
+            .cloned()
+            .with_context(|| format!("Log {log_id} not found"))
+    }
+
+    async fn list_test_runs(&self, build_id: i32) -> Result<Vec<TestRun>> {
+        Ok(self
+            .lock()
+            .test_runs
+            .get(&build_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn get_failed_test_results(&self, test_run_id: i32) -> Result<Vec<TestCaseResult>> {
+        Ok(self
+            .lock()
+            .test_results
+            .get(&test_run_id)
+            .into_iter()
+            .flatten()
+            .filter(|r| r.outcome.as_deref() == Some("Failed"))
+            .cloned()
+            .collect())
+    }
+
+    async fn list_build_artifacts(&self, build_id: i32) -> Result<Vec<BuildArtifact>> {
+        Ok(self
+            .lock()
+            .artifacts
+            .get(&build_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn list_artifact_items(
+        &self,
+        container_id: i64,
+        folder: &str,
+    ) -> Result<Vec<ArtifactItem>> {
+        let data = self.lock();
+        let items = data
+            .artifact_items
+            .get(&container_id)
+            .with_context(|| format!("Container {container_id} not found"))?;
+        let prefix = format!("{folder}/");
+        Ok(items
+            .iter()
+            .filter(|i| i.path == folder || i.path.starts_with(&prefix))
+            .cloned()
+            .collect())
+    }
+
+    async fn download_artifact(
+        &self,
+        artifact: &BuildArtifact,
+        item: Option<&ArtifactItem>,
+        dest: &Path,
+    ) -> Result<()> {
+        // Stand-in content: the fixtures only describe the files
+        let content = match item {
+            Some(item) => format!("{}\n", item.path),
+            None => format!("{}.zip\n", artifact.name),
+        };
+        std::fs::write(dest, content).with_context(|| format!("Failed to write {}", dest.display()))
+    }
+
+    async fn list_release_definitions(&self) -> Result<Vec<ReleaseDefinition>> {
+        Ok(self
+            .lock()
+            .release_definitions
+            .iter()
+            .filter(|d| !d.is_deleted && !d.is_disabled)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_release_definition_detail(
+        &self,
+        definition_id: i32,
+    ) -> Result<ReleaseDefinitionDetail> {
+        let data = self.lock();
+        if let Some(detail) = data
+            .release_definition_details
+            .iter()
+            .find(|d| d.id == definition_id)
+        {
+            return Ok(detail.clone());
+        }
+        let definition = data
+            .release_definitions
+            .iter()
+            .find(|d| d.id == definition_id)
+            .with_context(|| format!("Release definition {definition_id} not found"))?;
+        Ok(ReleaseDefinitionDetail {
+            id: definition.id,
+            name: Some(definition.name.clone()),
+            ..Default::default()
+        })
+    }
+
+    async fn list_releases(&self, definition_id: Option<i32>) -> Result<Vec<Release>> {
+        Ok(self
+            .lock()
+            .releases
+            .iter()
+            .filter(|r| {
+                definition_id.is_none()
+                    || r.release_definition.as_ref().map(|d| d.id) == definition_id
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn get_release(&self, release_id: i32) -> Result<Release> {
+        Ok(self.lock().release_mut(release_id)?.clone())
+    }
+
+    async fn create_release(
+        &self,
+        definition_id: i32,
+        description: Option<&str>,
+    ) -> Result<Release> {
+        let detail = self.get_release_definition_detail(definition_id).await?;
+        let _ = description; // Releases don't carry a description in our model
+
+        let mut data = self.lock();
+        let id = data.releases.iter().map(|r| r.id).max().unwrap_or(0) + 1;
+        let number = data
+            .releases
+            .iter()
+            .filter(|r| r.release_definition.as_ref().map(|d| d.id) == Some(definition_id))
+            .count()
+            + 1;
+        let environments = detail
+            .environments
+            .iter()
+            .map(|e| ReleaseEnvironment {
+                id: id * 10 + e.rank,
+                name: e.name.clone().unwrap_or_default(),
+                status: Some("notStarted".to_string()),
+                ..Default::default()
+            })
+            .collect();
+        let release = Release {
+            id,
+            name: format!("Release-{number}"),
+            status: Some("active".to_string()),
+            created_on: Some(chrono::Utc::now().to_rfc3339()),
+            release_definition: Some(ReleaseDefinitionRef {
+                id: definition_id,
+                name: detail.name.clone(),
+            }),
+            created_by: Some(data.current_user_ref()),
+            environments: Some(environments),
+        };
+
+        data.releases.insert(0, release.clone());
+        Ok(release)
+    }
+
+    async fn cancel_release(&self, release_id: i32) -> Result<()> {
+        self.lock().release_mut(release_id)?.status = Some("abandoned".to_string());
+        Ok(())
+    }
+
+    async fn cancel_release_environment(&self, release_id: i32, environment_id: i32) -> Result<()> {
+        self.lock()
+            .release_environment_mut(release_id, environment_id)?
+            .status = Some("canceled".to_string());
+        Ok(())
+    }
+
+    async fn redeploy_release_environment(
+        &self,
+        release_id: i32,
+        environment_id: i32,
+    ) -> Result<()> {
+        self.lock()
+            .release_environment_mut(release_id, environment_id)?
+            .status = Some("inProgress".to_string());
+        Ok(())
+    }
+
+    async fn get_release_task_log(&self, log_url: &str) -> Result<Vec<String>> {
+        self.lock()
+            .release_task_logs
+            .get(log_url)
+            .cloned()
+            .with_context(|| format!("Log {log_url} not found"))
+    }
+
+    async fn get_pending_approvals(&self) -> Result<Vec<Approval>> {
+        Ok(self
+            .lock()
+            .approvals
+            .iter()
+            .filter(|a| a.status.as_deref() == Some("pending"))
+            .cloned()
+            .collect())
+    }
+
+    async fn update_approvals(
+        &self,
+        approval_ids: &[i32],
+        status: &str,
+        comments: &str,
+    ) -> Result<()> {
+        let mut data = self.lock();
+        for id in approval_ids {
+            let approval = data
+                .approvals
+                .iter_mut()
+                .find(|a| a.id == *id)
+                .with_context(|| format!("Approval {id} not found"))?;
+            approval.status = Some(status.to_string());
+            approval.comments = Some(comments.to_string());
+
+            // Mirror the decision on the release stage
+            for release in &mut data.releases {
+                for env in release.environments.iter_mut().flatten() {
+                    for stage_approval in &mut env.pre_deploy_approvals {
+                        if stage_approval.id == *id {
+                            stage_approval.status = Some(status.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_repositories(&self) -> Result<Vec<Repository>> {
+        Ok(self.lock().repositories.clone())
+    }
+
+    async fn list_pull_requests(
+        &self,
+        repository: Option<&str>,
+        status: &str,
+        creator: Option<&str>,
+        top: Option<i32>,
+    ) -> Result<Vec<PullRequest>> {
+        let limit = top.map(|t| t.max(0) as usize).unwrap_or(usize::MAX);
+        Ok(self
+            .lock()
+            .pull_requests
+            .iter()
+            .filter(|pr| {
+                repository.is_none_or(|repo| {
+                    pr.repository
+                        .as_ref()
+                        .is_some_and(|r| r.name == repo || r.id == repo)
+                })
+            })
+            .filter(|pr| status == "all" || pr.status.as_deref() == Some(status))
+            .filter(|pr| {
+                creator.is_none_or(|c| {
+                    pr.created_by
+                        .as_ref()
+                        .and_then(|u| u.unique_name.as_deref())
+                        == Some(c)
+                })
+            })
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_pull_request(&self, id: i32) -> Result<PullRequest> {
+        Ok(self.lock().pull_request_mut(id)?.clone())
+    }
+
+    async fn get_commit(&self, _repository_id: &str, commit_id: &str) -> Result<GitCommitRef> {
+        self.lock()
+            .commits
+            .iter()
+            .find(|(hash, _)| hash.starts_with(commit_id))
+            .map(|(hash, comment)| GitCommitRef {
+                commit_id: hash.clone(),
+                comment: comment.clone(),
+            })
+            .with_context(|| format!("Commit {commit_id} not found"))
+    }
+
+    async fn list_pr_threads(&self, _repository_id: &str, pr_id: i32) -> Result<Vec<PRThread>> {
+        Ok(self
+            .lock()
+            .pr_threads
+            .get(&pr_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn list_pr_iterations(
+        &self,
+        _repository_id: &str,
+        pr_id: i32,
+    ) -> Result<Vec<PRIteration>> {
+        let data = self.lock();
+        data.pull_requests
+            .iter()
+            .find(|p| p.pull_request_id == pr_id)
+            .with_context(|| format!("PR #{pr_id} not found"))?;
+        let commit = |n: usize| {
+            Some(GitCommitRef {
+                commit_id: format!("{pr_id}.{n}"),
+                ..Default::default()
+            })
+        };
+        Ok((1..=data.pr_iteration_count(pr_id))
+            .map(|n| PRIteration {
+                id: n as i32,
+                description: Some(format!("Iteration {n}")),
+                source_ref_commit: commit(n),
+                target_ref_commit: commit(0),
+                common_ref_commit: commit(0),
+                ..Default::default()
+            })
+            .collect())
+    }
+
+    async fn list_pr_changes(
+        &self,
+        _repository_id: &str,
+        pr_id: i32,
+        iteration_id: i32,
+        compare_to: i32,
+    ) -> Result<Vec<PRChange>> {
+        let data = self.lock();
+        let Some(files) = data.pr_files.get(&pr_id) else {
+            return Ok(Vec::new());
+        };
+        Ok(files
+            .keys()
+            .enumerate()
+            .filter_map(|(i, path)| {
+                let before = data.pr_file_version(pr_id, path, compare_to as usize);
+                let after = data.pr_file_version(pr_id, path, iteration_id as usize);
+                let change_type = match (&before, &after) {
+                    (None, Some(_)) => "add",
+                    (Some(_), None) => "delete",
+                    (Some(a), Some(b)) if a != b => "edit",
+                    _ => return None,
+                };
+                Some(PRChange {
+                    path: path.clone(),
+                    original_path: None,
+                    change_type: change_type.to_string(),
+                    change_tracking_id: i as i32 + 1,
+                })
+            })
+            .collect())
+    }
+
+    async fn get_file_content(
+        &self,
+        _repository_id: &str,
+        path: &str,
+        commit_id: &str,
+    ) -> Result<String> {
+        let (pr_id, iteration) = commit_id
+            .split_once('.')
+            .and_then(|(pr, n)| Some((pr.parse().ok()?, n.parse().ok()?)))
+            .with_context(|| format!("Commit {commit_id} not found"))?;
+        self.lock()
+            .pr_file_version(pr_id, path, iteration)
+            .with_context(|| format!("{path} not found at {commit_id}"))
+    }
+
+    async fn get_file_at_ref(
+        &self,
+        repository_id: &str,
+        path: &str,
+        git_ref: &str,
+    ) -> Result<String> {
+        let data = self.lock();
+        let repo = data
+            .repositories
+            .iter()
+            .find(|r| r.name == repository_id || r.id == repository_id)
+            .with_context(|| format!("Repository {repository_id} not found"))?;
+        data.files
+            .get(&repo.name)
+            .and_then(|files| files.get(path.trim_start_matches('/')))
+            .cloned()
+            .with_context(|| format!("{path} not found at {git_ref}"))
+    }
+
+    async fn list_pr_policies(&self, pr_id: i32) -> Result<Vec<PRPolicy>> {
+        Ok(self
+            .lock()
+            .pr_policies
+            .get(&pr_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn set_pr_vote(&self, pr_id: i32, vote: &str) -> Result<()> {
+        let value = vote_value(vote).with_context(|| format!("Unknown vote: {vote}"))?;
+        let mut data = self.lock();
+        let me = data.current_identity();
+        let pr = data.pull_request_mut(pr_id)?;
+
+        match pr
+            .reviewers
+            .iter_mut()
+            .find(|r| r.unique_name == me.unique_name)
+        {
+            Some(reviewer) => reviewer.vote = value,
+            None => pr.reviewers.push(PRReviewer {
+                display_name: me.display_name,
+                unique_name: me.unique_name,
+                vote: value,
+                ..Default::default()
+            }),
+        }
+        Ok(())
+    }
+
+    async fn update_pr(
+        &self,
+        pr_id: i32,
+        status: Option<&str>,
+        title: Option<&str>,
+        description: Option<&str>,
+        draft: Option<bool>,
+    ) -> Result<PullRequest> {
+        let mut data = self.lock();
+        let me = data.current_identity();
+        let pr = data.pull_request_mut(pr_id)?;
+
+        if let Some(s) = status {
+            pr.status = Some(s.to_string());
+            if s == "completed" || s == "abandoned" {
+                pr.closed_by = Some(me);
+                pr.closed_date = Some(chrono::Utc::now().to_rfc3339());
+            }
+        }
+        if let Some(t) = title {
+            pr.title = t.to_string();
+        }
+        if let Some(d) = description {
+            pr.description = Some(d.to_string());
+        }
+        if let Some(is_draft) = draft {
+            pr.is_draft = is_draft;
+        }
+        Ok(pr.clone())
+    }
+
+    async fn complete_pr(
+        &self,
+        pr_id: i32,
+        options: &PRCompletionOptions,
+        auto_complete: bool,
+    ) -> Result<PullRequest> {
+        let mut data = self.lock();
+        let me = data.current_identity();
+        let blocked = data
+            .pr_policies
+            .get(&pr_id)
+            .into_iter()
+            .flatten()
+            .any(|p| p.is_blocking_failure());
+        let pr = data.pull_request_mut(pr_id)?;
+        if pr.status.as_deref() != Some("active") {
+            bail!("PR #{pr_id} is not active");
+        }
+
+        pr.completion_options = Some(options.clone());
+        if auto_complete {
+            pr.auto_complete_set_by = Some(me);
+        } else {
+            if blocked && options.bypass_policy != Some(true) {
+                bail!("PR #{pr_id} has blocking policies that have not passed");
+            }
+            pr.status = Some("completed".to_string());
+            pr.closed_by = Some(me);
+            pr.closed_date = Some(chrono::Utc::now().to_rfc3339());
+        }
+        Ok(pr.clone())
+    }
+
+    async fn list_branches(&self, repository: &str) -> Result<Vec<GitRef>> {
+        let data = self.lock();
+        let repo = data
+            .repositories
+            .iter()
+            .find(|r| r.name == repository || r.id == repository)
+            .with_context(|| format!("Repository {repository} not found"))?;
+
+        Ok(data
+            .branches
+            .get(&repo.name)
+            .into_iter()
+            .flatten()
+            .map(|b| GitRef {
+                name: format!("refs/heads/{b}"),
+                ..Default::default()
+            })
+            .collect())
+    }
+
+    async fn list_tags(&self, repository: &str) -> Result<Vec<GitRef>> {
+        let data = self.lock();
+        let repo = data
+            .repositories
+            .iter()
+            .find(|r| r.name == repository || r.id == repository)
+            .with_context(|| format!("Repository {repository} not found"))?;
+
+        Ok(data
+            .tags
+            .get(&repo.name)
+            .into_iter()
+            .flatten()
+            .map(|t| GitRef {
+                name: format!("refs/tags/{t}"),
+                ..Default::default()
+            })
+            .collect())
+    }
+
+    async fn create_pr(&self, new_pr: &NewPullRequest) -> Result<PullRequest> {
+        let mut data = self.lock();
+        let repo = data
+            .repositories
+            .iter()
+            .find(|r| r.name == new_pr.repository || r.id == new_pr.repository)
+            .with_context(|| format!("Repository {} not found", new_pr.repository))?;
+        let repo = PRRepository {
+            id: repo.id.clone(),
+            name: repo.name.clone(),
+            project: None,
+        };
+
+        let to_ref = |b: &str| {
+            if b.starts_with("refs/") {
+                b.to_string()
+            } else {
+                format!("refs/heads/{b}")
+            }
+        };
+        let (source, target) = (to_ref(&new_pr.source_branch), to_ref(&new_pr.target_branch));
+        if source == target {
+            bail!("Source and target branch must differ");
+        }
+        if let Some(existing) = data.pull_requests.iter().find(|p| {
+            p.status.as_deref() == Some("active")
+                && p.source_branch.as_deref() == Some(source.as_str())
+                && p.target_branch.as_deref() == Some(target.as_str())
+                && p.repository.as_ref().is_some_and(|r| r.id == repo.id)
+        }) {
+            bail!(
+                "An active pull request for these branches already exists (#{})",
+                existing.pull_request_id
+            );
+        }
+
+        let id = data
+            .pull_requests
+            .iter()
+            .map(|p| p.pull_request_id)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let me = data.current_identity();
+        let reviewers = new_pr
+            .reviewers
+            .iter()
+            .map(|unique_name| PRReviewer {
+                display_name: data.display_name(unique_name),
+                unique_name: Some(unique_name.clone()),
+                ..Default::default()
+            })
+            .collect();
+        let pr = PullRequest {
+            pull_request_id: id,
+            title: new_pr.title.clone(),
+            description: Some(new_pr.description.clone()).filter(|d| !d.is_empty()),
+            status: Some("active".to_string()),
+            source_branch: Some(source),
+            target_branch: Some(target),
+            is_draft: new_pr.draft,
+            creation_date: Some(chrono::Utc::now().to_rfc3339()),
+            created_by: Some(me.clone()),
+            auto_complete_set_by: new_pr.auto_complete.then_some(me),
+            completion_options: Some(new_pr.completion_options.clone()),
+            repository: Some(repo),
+            reviewers,
+            ..Default::default()
+        };
+
+        data.pull_requests.insert(0, pr.clone());
+        if !new_pr.work_item_ids.is_empty() {
+            data.pr_work_items.insert(id, new_pr.work_item_ids.clone());
+        }
+        Ok(pr)
+    }
+
+    async fn add_pr_comment(
+        &self,
+        _repository_id: &str,
+        pr_id: i32,
+        content: &str,
+        anchor: Option<&PRCommentAnchor>,
+    ) -> Result<()> {
+        let mut data = self.lock();
+        data.pull_request_mut(pr_id)?;
+        let author = data.current_identity();
+        let threads = data.pr_threads.entry(pr_id).or_default();
+        let id = threads.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+        let now = chrono::Utc::now().to_rfc3339();
+
+        threads.push(PRThread {
+            id,
+            status: Some("active".to_string()),
+            published_date: Some(now.clone()),
+            last_updated_date: Some(now.clone()),
+            comments: vec![PRComment {
+                id: 1,
+                content: Some(content.to_string()),
+                comment_type: Some("text".to_string()),
+                published_date: Some(now),
+                author: Some(author),
+            }],
+            thread_context: anchor.map(|a| a.thread_context.clone()),
+            ..Default::default()
+        });
+        Ok(())
+    }
+
+    async fn reply_pr_thread(
+        &self,
+        _repository_id: &str,
+        pr_id: i32,
+        thread_id: i32,
+        content: &str,
+    ) -> Result<()> {
+        let mut data = self.lock();
+        let author = data.current_identity();
+        let thread = data.pr_thread_mut(pr_id, thread_id)?;
+        let id = thread.comments.iter().map(|c| c.id).max().unwrap_or(0) + 1;
+        let now = chrono::Utc::now().to_rfc3339();
+
+        thread.last_updated_date = Some(now.clone());
+        thread.comments.push(PRComment {
+            id,
+            content: Some(content.to_string()),
+            comment_type: Some("text".to_string()),
+            published_date: Some(now),
+            author: Some(author),
+        });
+        Ok(())
+    }
+
+    async fn set_pr_thread_status(
+        &self,
+        _repository_id: &str,
+        pr_id: i32,
+        thread_id: i32,
+        status: &str,
+    ) -> Result<()> {
+        let mut data = self.lock();
+        let thread = data.pr_thread_mut(pr_id, thread_id)?;
+        thread.status = Some(status.to_string());
+        thread.last_updated_date = Some(chrono::Utc::now().to_rfc3339());
+        Ok(())
+    }
+
+    async fn list_pr_work_items(&self, pr_id: i32) -> Result<serde_json::Value> {
+        let data = self.lock();
+        let ids = data.pr_work_items.get(&pr_id).cloned().unwrap_or_default();
+        let items: Vec<&WorkItem> = data
+            .work_items
+            .iter()
+            .filter(|w| ids.contains(&w.id))
+            .collect();
+        Ok(serde_json::to_value(items)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_demo_fixtures_load() {
+        let data = FakeBackend::demo().fixtures();
+        assert!(!data.sprints.is_empty());
+        assert!(!data.work_items.is_empty());
+        assert!(!data.pull_requests.is_empty());
+        assert!(data.pipeline_runs.contains_key(&11));
+    }
+
+    #[tokio::test]
+    async fn test_sprint_work_items_builds_hierarchy() {
+        let backend = FakeBackend::demo();
+        let items = backend
+            .get_sprint_work_items("Demo\\Sprint 42")
+            .await
+            .unwrap();
+        let story = items.iter().find(|w| w.id == 1001).unwrap();
+        assert_eq!(story.children.len(), 2);
+        assert!(items.iter().all(|w| w.id != 990));
+    }
+
+    #[tokio::test]
+    async fn test_update_work_item_persists() {
+        let backend = FakeBackend::demo();
+        backend
+            .update_work_item(1004, &[FieldUpdate::set(WorkItemFields::STATE, "Done")])
+            .await
+            .unwrap();
+        backend
+            .update_work_item(
+                1004,
+                &[
+                    FieldUpdate::set(WorkItemFields::ASSIGNED_TO, "ada@contoso.com"),
+                    FieldUpdate::set(WorkItemFields::REMAINING_WORK, 2.5),
+                    FieldUpdate::set("Custom.Team", "Payments"),
+                    FieldUpdate::clear(WorkItemFields::TAGS),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let item = backend.get_work_item(1004).await.unwrap();
+        assert_eq!(item.fields.state, "Done");
+        assert_eq!(
+            item.fields.assigned_to.unwrap().display_name,
+            "Ada Lovelace"
+        );
+        assert_eq!(item.fields.remaining_work, Some(2.5));
+        assert_eq!(item.fields.other["Custom.Team"], "Payments");
+        assert_eq!(item.fields.tags, None);
+    }
+
+    #[tokio::test]
+    async fn test_update_work_item_links() {
+        let backend = FakeBackend::demo();
+        let link = |rel: &str, id: i32| WorkItemRelation::work_item(DEMO_ORG_URL, rel, id);
+        let item = backend
+            .update_work_item_links(
+                1002,
+                &[
+                    link(WorkItemRelation::PARENT, 1005),
+                    link("System.LinkTypes.Dependency-Reverse", 1004),
+                ],
+                &[],
+            )
+            .await
+            .unwrap();
+        assert_eq!(item.fields.parent_id, Some(1005));
+        assert_eq!(item.rev, 3);
+        // The other end records the link too
+        let other = backend.get_work_item(1004).await.unwrap();
+        assert!(
+            other
+                .relations
+                .unwrap()
+                .iter()
+                .any(|r| r.rel == "System.LinkTypes.Dependency-Forward"
+                    && r.target_id() == Some(1002))
+        );
+
+        // Duplicates, cycles and unknown links are refused
+        let dependency = link("System.LinkTypes.Dependency-Reverse", 1004);
+        assert!(backend
+            .update_work_item_links(1002, std::slice::from_ref(&dependency), &[])
+            .await
+            .is_err());
+        assert!(backend
+            .update_work_item_links(1005, &[link(WorkItemRelation::PARENT, 1002)], &[])
+            .await
+            .is_err());
+        assert!(backend
+            .update_work_item_links(1002, &[], &[link("System.LinkTypes.Related", 1004)])
+            .await
+            .is_err());
+
+        let item = backend
+            .update_work_item_links(
+                1002,
+                &[],
+                &[dependency, link(WorkItemRelation::PARENT, 1005)],
+            )
+            .await
+            .unwrap();
+        assert_eq!(item.fields.parent_id, None);
+        assert!(item.relations.unwrap().is_empty());
+        let other = backend.get_work_item(1004).await.unwrap();
+        assert!(other.relations.unwrap_or_default().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_vote_updates_current_user_reviewer() {
+        let backend = FakeBackend::demo();
+        backend.set_pr_vote(502, "approve").await.unwrap();
+        let pr = backend.get_pull_request(502).await.unwrap();
+        let me = pr
+            .reviewers
+            .iter()
+            .find(|r| r.unique_name.as_deref() == Some("ada@contoso.com"))
+            .unwrap();
+        assert_eq!(me.vote, 10);
+        assert!(backend.set_pr_vote(502, "maybe").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_approval_updates_release_stage() {
+        let backend = FakeBackend::demo();
+        backend
+            .update_approvals(&[801], "approved", "ok")
+            .await
+            .unwrap();
+
+        assert!(backend.get_pending_approvals().await.unwrap().is_empty());
+        let release = backend.get_release(401).await.unwrap();
+        let prod = &release.environments.unwrap()[1];
+        assert_eq!(
+            prod.pre_deploy_approvals[0].status.as_deref(),
+            Some("approved")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_trigger_pipeline() {
+        let backend = FakeBackend::demo();
+        backend.cancel_pipeline_run(7003).await.unwrap();
+        let request = PipelineRunRequest::branch("main");
+        let run = backend.trigger_pipeline(11, &request).await.unwrap();
+        assert_eq!(run.source_branch.as_deref(), Some("refs/heads/main"));
+        assert_eq!(backend.fixtures().run_requests[&run.id], request);
+
+        let runs = backend.list_pipeline_runs(11, Some(2)).await.unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].id, run.id);
+        assert_eq!(runs[1].result.as_deref(), Some("canceled"));
+    }
+
+    #[tokio::test]
+    async fn test_list_pull_requests_filters() {
+        let backend = FakeBackend::demo();
+        let active = backend
+            .list_pull_requests(Some("web"), "active", None, None)
+            .await
+            .unwrap();
+        assert_eq!(active.len(), 2);
+
+        let mine = backend
+            .list_pull_requests(Some("web"), "all", Some("ada@contoso.com"), None)
+            .await
+            .unwrap();
+        assert_eq!(mine.len(), 1);
+        assert_eq!(mine[0].pull_request_id, 501);
+    }
+
+    #[tokio::test]
+    async fn test_reply_and_resolve_pr_thread() {
+        let backend = FakeBackend::demo();
+        backend
+            .reply_pr_thread("repo-web", 501, 1, "Done")
+            .await
+            .unwrap();
+        backend
+            .set_pr_thread_status("repo-web", 501, 1, "fixed")
+            .await
+            .unwrap();
+
+        let threads = backend.list_pr_threads("repo-web", 501).await.unwrap();
+        assert_eq!(threads[0].status.as_deref(), Some("fixed"));
+        assert_eq!(threads[0].comments.len(), 2);
+        assert_eq!(threads[0].comments[1].content.as_deref(), Some("Done"));
+        assert!(backend
+            .set_pr_thread_status("repo-web", 501, 99, "fixed")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_complete_pr_respects_blocking_policies() {
+        let backend = FakeBackend::demo();
+        // The build policy on 501 is still running
+        let options = PRCompletionOptions::default();
+        assert!(backend.complete_pr(501, &options, false).await.is_err());
+
+        let pr = backend.complete_pr(501, &options, true).await.unwrap();
+        assert!(pr.auto_complete_set_by.is_some());
+        assert_eq!(pr.status.as_deref(), Some("active"));
+
+        let options = PRCompletionOptions {
+            merge_strategy: Some("squash".to_string()),
+            bypass_policy: Some(true),
+            bypass_reason: Some("Hotfix".to_string()),
+            ..Default::default()
+        };
+        let pr = backend.complete_pr(501, &options, false).await.unwrap();
+        assert_eq!(pr.status.as_deref(), Some("completed"));
+        let stored = pr.completion_options.unwrap();
+        assert_eq!(stored.merge_strategy.as_deref(), Some("squash"));
+    }
+
+    #[tokio::test]
+    async fn test_pr_iterations_and_changes() {
+        let backend = FakeBackend::demo();
+        let iterations = backend.list_pr_iterations("repo-web", 501).await.unwrap();
+        assert_eq!(iterations.len(), 2);
+
+        // All changes against the target branch
+        let changes = backend
+            .list_pr_changes("repo-web", 501, 2, 0)
+            .await
+            .unwrap();
+        let marker = |path: &str| {
+            changes
+                .iter()
+                .find(|c| c.path.ends_with(path))
+                .map(|c| c.marker())
+        };
+        assert_eq!(marker("PaymentStep.tsx"), Some("A"));
+        assert_eq!(marker("LegacyPayment.tsx"), Some("D"));
+
+        // Only the second push
+        let changes = backend
+            .list_pr_changes("repo-web", 501, 2, 1)
+            .await
+            .unwrap();
+        assert!(changes
+            .iter()
+            .any(|c| c.path.ends_with("PaymentStep.tsx") && c.marker() == "M"));
+
+        let content = backend
+            .get_file_content("repo-web", "/src/checkout/PaymentStep.tsx", "501.2")
+            .await
+            .unwrap();
+        assert!(content.contains("PayButton"));
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_links_work_items() {
+        let backend = FakeBackend::demo();
+        let branches = backend.list_branches("web").await.unwrap();
+        assert!(branches
+            .iter()
+            .any(|b| b.name == "refs/heads/feature/1003-address-autocomplete"));
+
+        let new_pr = NewPullRequest {
+            repository: "web".to_string(),
+            source_branch: "feature/1003-address-autocomplete".to_string(),
+            target_branch: "main".to_string(),
+            title: "Address autocomplete".to_string(),
+            reviewers: vec!["grace@contoso.com".to_string()],
+            work_item_ids: vec![1003],
+            auto_complete: true,
+            ..Default::default()
+        };
+        let pr = backend.create_pr(&new_pr).await.unwrap();
+        assert_eq!(pr.reviewers[0].display_name, "Grace Hopper");
+        assert!(pr.auto_complete_set_by.is_some());
+        assert_eq!(
+            backend.fixtures().pr_work_items[&pr.pull_request_id],
+            [1003]
+        );
+
+        // Same branches again is rejected while the first PR is active
+        assert!(backend.create_pr(&new_pr).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_timeline_delta_reports_no_change() {
+        let backend = FakeBackend::demo();
+        let (records, change_id) = backend
+            .get_build_timeline_delta(7002, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!records.is_empty());
+        assert!(backend
+            .get_build_timeline_delta(7002, change_id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -1,5 +1,11 @@
+pub mod backend;
 pub mod client;
+pub mod fake;
+pub mod rest;
 pub mod types;
 
+pub use backend::{Backend, DevOpsBackend};
 pub use client::AzureCli;
+pub use fake::FakeBackend;
+pub use rest::RestClient;
 pub use types::*;
@@ -0,0 +1,1735 @@
+use crate::azure::backend::{DevOpsBackend, WORK_ITEMS_BATCH_SIZE};
+use crate::azure::client::{
+    sprint_wiql, AZURE_DEVOPS_RESOURCE, COMMENTS_API_VERSION, SPRINT_FIELDS,
+};
+use crate::azure::types::*;
+use crate::azure::AzureCli;
+use crate::config::ProjectConfig;
+use anyhow::{bail, Context, Result};
+use reqwest::{Method, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+
+const API_VERSION: &str = "7.1";
+
+/// Environment variable holding a personal access token (same one az devops uses)
+const PAT_ENV: &str = "AZURE_DEVOPS_EXT_PAT";
+
+/// Refresh the access token this many seconds before it expires
+const TOKEN_REFRESH_MARGIN_SECS: u64 = 300;
+
+#[derive(Debug, Clone)]
+struct AccessToken {
+    value: String,
+    expires_at: u64,
+}
+
+/// Standard `{ count, value: [...] }` list envelope
+#[derive(Deserialize)]
+struct ListResponse<T> {
+    #[serde(default = "Vec::new")]
+    value: Vec<T>,
+}
+
+/// Backend that talks to the Azure DevOps REST API directly.
+///
+/// One HTTP client (with its connection pool) and one access token are shared
+/// by every clone, so polling never forks processes. The token comes from
+/// `az account get-access-token` and is refreshed shortly before it expires;
+/// set `AZURE_DEVOPS_EXT_PAT` to use a personal access token instead.
+#[derive(Clone)]
+pub struct RestClient {
+    http: reqwest::Client,
+    token: Arc<Mutex<Option<AccessToken>>>,
+    /// Per-request timeout (projects can override it, the pool is shared)
+    timeout: Duration,
+    pub organization: String,
+    pub project: String,
+    pub team: String,
+}
+
+impl Default for RestClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RestClient {
+    /// Create an unbound client; use `for_project` to target a project
+    pub fn new() -> Self {
+        Self::with_timeout(30)
+    }
+
+    pub fn with_timeout(timeout_secs: u64) -> Self {
+        let http = reqwest::Client::builder()
+            .pool_idle_timeout(Duration::from_secs(90))
+            .user_agent(concat!("lazyops/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            http,
+            token: Arc::new(Mutex::new(None)),
+            timeout: Duration::from_secs(timeout_secs),
+            organization: String::new(),
+            project: String::new(),
+            team: String::new(),
+        }
+    }
+
+    /// Organization URL without trailing slash
+    fn org_url(&self) -> &str {
+        self.organization.trim_end_matches('/')
+    }
+
+    /// `{org}/{project}/_apis/{path}`
+    fn project_api(&self, path: &str) -> String {
+        format!(
+            "{}/{}/_apis/{path}",
+            self.org_url(),
+            urlencoding::encode(&self.project)
+        )
+    }
+
+    /// `{org}/{project}/{team}/_apis/{path}`
+    fn team_api(&self, path: &str) -> String {
+        format!(
+            "{}/{}/{}/_apis/{path}",
+            self.org_url(),
+            urlencoding::encode(&self.project),
+            urlencoding::encode(&self.team)
+        )
+    }
+
+    /// `{release host}/{project}/_apis/release/{path}`
+    fn release_api(&self, path: &str) -> String {
+        format!(
+            "{}/{}/_apis/release/{path}",
+            service_url(self.org_url(), "vsrm"),
+            urlencoding::encode(&self.project)
+        )
+    }
+
+    /// Get a bearer token from az, reusing the cached one until it nearly expires
+    async fn access_token(&self) -> Result<String> {
+        let mut cached = self.token.lock().await;
+        let now = unix_now();
+
+        if let Some(token) = cached.as_ref() {
+            if token.expires_at > now + TOKEN_REFRESH_MARGIN_SECS {
+                return Ok(token.value.clone());
+            }
+        }
+
+        #[derive(Deserialize)]
+        struct TokenResponse {
+            #[serde(rename = "accessToken")]
+            access_token: String,
+            /// Unix timestamp (reported by newer az versions)
+            #[serde(default)]
+            expires_on: Option<u64>,
+        }
+
+        let output = Command::new("az")
+            .args(["account", "get-access-token"])
+            .args(["--resource", AZURE_DEVOPS_RESOURCE])
+            .args(["--output", "json"])
+            .output()
+            .await
+            .context("Failed to execute az CLI - is Azure CLI installed?")?;
+
+        if !output.status.success() {
+            bail!("Azure CLI not authenticated. Run: az login");
+        }
+
+        let response: TokenResponse =
+            serde_json::from_slice(&output.stdout).context("Failed to parse access token")?;
+        let expires_at = response.expires_on.unwrap_or(now + 45 * 60);
+
+        *cached = Some(AccessToken {
+            value: response.access_token.clone(),
+            expires_at,
+        });
+        Ok(response.access_token)
+    }
+
+    /// Attach credentials to a request
+    async fn authorize(&self, request: RequestBuilder) -> Result<RequestBuilder> {
+        if let Ok(pat) = std::env::var(PAT_ENV) {
+            if !pat.is_empty() {
+                return Ok(request.basic_auth("", Some(pat)));
+            }
+        }
+        Ok(request.bearer_auth(self.access_token().await?))
+    }
+
+    /// Send a request and return the successful response
+    async fn send(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&serde_json::Value>,
+        content_type: &str,
+    ) -> Result<reqwest::Response> {
+        let mut request = self
+            .http
+            .request(method, with_api_version(url))
+            .timeout(self.timeout)
+            .header(reqwest::header::ACCEPT, "application/json");
+        if let Some(body) = body {
+            request = request
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(body.to_string());
+        }
+        self.execute(request).await
+    }
+
+    /// Authorize and send a request, turning error statuses into errors
+    async fn execute(&self, request: RequestBuilder) -> Result<reqwest::Response> {
+        let response = self
+            .authorize(request)
+            .await?
+            .send()
+            .await
+            .context("Azure DevOps request failed")?;
+
+        let status = response.status();
+        // Azure DevOps answers unauthenticated requests with a 203 sign-in page
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::NON_AUTHORITATIVE_INFORMATION
+        {
+            bail!("Azure DevOps rejected the credentials. Run: az login");
+        }
+        if status == StatusCode::NOT_FOUND {
+            let path = response.url().path().to_string();
+            let text = response.text().await.unwrap_or_default();
+            bail!("{}", not_found_message(&path, &text));
+        }
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            bail!(
+                "Azure DevOps request failed ({status}): {}",
+                error_message(&text)
+            );
+        }
+
+        Ok(response)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let response = self.send(Method::GET, url, None, "").await?;
+        response.json().await.context("Failed to parse response")
+    }
+
+    async fn get_list<T: DeserializeOwned>(&self, url: &str) -> Result<Vec<T>> {
+        let list: ListResponse<T> = self.get(url).await?;
+        Ok(list.value)
+    }
+
+    async fn get_text(&self, url: &str) -> Result<String> {
+        let response = self.send(Method::GET, url, None, "").await?;
+        response.text().await.context("Failed to read response")
+    }
+
+    /// Stream a response body to `dest`. Unlike API calls a download has no
+    /// overall timeout: large artifacts take as long as they take
+    async fn download(&self, url: &str, dest: &Path) -> Result<()> {
+        let request = self.http.get(url).header(reqwest::header::ACCEPT, "*/*");
+        let mut response = self.execute(request).await?;
+        let mut file = tokio::fs::File::create(dest)
+            .await
+            .with_context(|| format!("Failed to create {}", dest.display()))?;
+        while let Some(chunk) = response.chunk().await.context("Download interrupted")? {
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+        Ok(())
+    }
+
+    async fn send_json<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        url: &str,
+        body: &serde_json::Value,
+    ) -> Result<T> {
+        let response = self
+            .send(method, url, Some(body), "application/json")
+            .await?;
+        response.json().await.context("Failed to parse response")
+    }
+
+    async fn send_json_no_content(
+        &self,
+        method: Method,
+        url: &str,
+        body: &serde_json::Value,
+    ) -> Result<()> {
+        self.send(method, url, Some(body), "application/json")
+            .await?;
+        Ok(())
+    }
+
+    /// Fetch work items in batches, keeping the order of `ids`
+    async fn get_work_items_batch(&self, ids: &[i32]) -> Result<Vec<WorkItem>> {
+        let mut items = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(WORK_ITEMS_BATCH_SIZE) {
+            let body = serde_json::json!({ "ids": chunk, "fields": SPRINT_FIELDS });
+            let batch: ListResponse<WorkItem> = self
+                .send_json(Method::POST, &self.project_api("wit/workitemsbatch"), &body)
+                .await?;
+            items.extend(batch.value);
+        }
+
+        let order: HashMap<i32, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        items.sort_by_key(|item| order.get(&item.id).copied().unwrap_or(usize::MAX));
+        Ok(items)
+    }
+
+    /// Identity of the signed-in user: (id, unique name)
+    async fn authenticated_user(&self) -> Result<(String, String)> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ConnectionData {
+            authenticated_user: AuthenticatedUser,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct AuthenticatedUser {
+            id: String,
+            #[serde(default)]
+            provider_display_name: String,
+            #[serde(default)]
+            properties: serde_json::Value,
+        }
+
+        let url = format!(
+            "{}/_apis/connectionData?api-version={API_VERSION}-preview",
+            self.org_url()
+        );
+        let data: ConnectionData = self.get(&url).await?;
+        let user = data.authenticated_user;
+        let account = user
+            .properties
+            .pointer("/Account/$value")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .unwrap_or(user.provider_display_name);
+        Ok((user.id, account))
+    }
+
+    /// Resolve an email / unique name to an identity ID
+    async fn resolve_identity(&self, name: &str) -> Result<String> {
+        #[derive(Deserialize)]
+        struct Identity {
+            id: String,
+        }
+
+        let url = format!(
+            "{}/_apis/identities?searchFilter=General&filterValue={}",
+            service_url(self.org_url(), "vssps"),
+            urlencoding::encode(name)
+        );
+        let identities: Vec<Identity> = self.get_list(&url).await?;
+        identities
+            .into_iter()
+            .next()
+            .map(|i| i.id)
+            .with_context(|| format!("Unknown user: {name}"))
+    }
+
+    /// Turn the `mailto:` mention links of a comment into identity mentions,
+    /// which notify the people mentioned; unknown addresses stay plain links
+    async fn resolve_mentions(&self, html: &str) -> String {
+        let mut html = html.to_string();
+        for address in crate::discussion::mention_addresses(&html) {
+            if let Ok(id) = self.resolve_identity(&address).await {
+                html = crate::discussion::link_identity(&html, &address, &id);
+            }
+        }
+        html
+    }
+
+    fn comments_api(&self, id: i32, path: &str) -> String {
+        self.project_api(&format!(
+            "wit/workItems/{id}/comments{path}?api-version={COMMENTS_API_VERSION}"
+        ))
+    }
+
+    /// Repository ID of a pull request
+    async fn pr_repository_id(&self, pr_id: i32) -> Result<String> {
+        let pr = self.get_pull_request(pr_id).await?;
+        pr.repository
+            .map(|r| r.id)
+            .with_context(|| format!("PR #{pr_id} has no repository"))
+    }
+
+    fn pr_api(&self, repository_id: &str, pr_id: i32, path: &str) -> String {
+        self.project_api(&format!(
+            "git/repositories/{repository_id}/pullRequests/{pr_id}{path}"
+        ))
+    }
+}
+
+impl DevOpsBackend for RestClient {
+    fn for_project(&self, project: &ProjectConfig) -> Self {
+        Self {
+            http: self.http.clone(),
+            token: Arc::clone(&self.token),
+            timeout: project
+                .overrides
+                .api_timeout
+                .map(Duration::from_secs)
+                .unwrap_or(self.timeout),
+            organization: project.organization.clone(),
+            project: project.project.clone(),
+            team: project.team.clone(),
+        }
+    }
+
+    async fn get_sprints(&self) -> Result<Vec<Sprint>> {
+        self.get_list(&self.team_api("work/teamsettings/iterations"))
+            .await
+    }
+
+    async fn get_team_capacity(&self, iteration_id: &str) -> Result<TeamCapacity> {
+        let iteration = format!("work/teamsettings/iterations/{iteration_id}");
+        let mut capacity: TeamCapacity = self
+            .get(&self.team_api(&format!("{iteration}/capacities")))
+            .await?;
+        let days_off: TeamDaysOff = self
+            .get(&self.team_api(&format!("{iteration}/teamdaysoff")))
+            .await?;
+        let settings: TeamSettings = self.get(&self.team_api("work/teamsettings")).await?;
+        capacity.team_days_off = days_off.days_off;
+        capacity.working_days = settings.working_days;
+        Ok(capacity)
+    }
+
+    async fn get_sprint_work_items(&self, iteration_path: &str) -> Result<Vec<WorkItem>> {
+        let items = self.query_work_items(&sprint_wiql(iteration_path)).await?;
+        Ok(AzureCli::build_hierarchy(items))
+    }
+
+    async fn query_work_items(&self, wiql: &str) -> Result<Vec<WorkItem>> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct WiqlResult {
+            #[serde(default)]
+            work_items: Vec<WorkItemRef>,
+            /// Tree and one-hop queries return links instead
+            #[serde(default)]
+            work_item_relations: Vec<WorkItemLink>,
+        }
+        #[derive(Deserialize)]
+        struct WorkItemRef {
+            id: i32,
+        }
+        #[derive(Deserialize)]
+        struct WorkItemLink {
+            target: WorkItemRef,
+        }
+
+        // Team scope so @CurrentIteration resolves
+        let body = serde_json::json!({ "query": wiql });
+        let result: WiqlResult = self
+            .send_json(Method::POST, &self.team_api("wit/wiql"), &body)
+            .await?;
+        let mut ids: Vec<i32> = result.work_items.iter().map(|w| w.id).collect();
+        for link in &result.work_item_relations {
+            if !ids.contains(&link.target.id) {
+                ids.push(link.target.id);
+            }
+        }
+
+        self.get_work_items_batch(&ids).await
+    }
+
+    async fn list_queries(&self) -> Result<Vec<QueryHierarchyItem>> {
+        self.get_list(&self.project_api("wit/queries?$depth=2&$expand=wiql"))
+            .await
+    }
+
+    async fn get_boards(&self) -> Result<Vec<Board>> {
+        // The list only carries names; columns and lanes come with each board
+        let list: Vec<Board> = self.get_list(&self.team_api("work/boards")).await?;
+        let mut boards = Vec::with_capacity(list.len());
+        for board in &list {
+            boards.push(
+                self.get(&self.team_api(&format!("work/boards/{}", board.id)))
+                    .await?,
+            );
+        }
+        Ok(boards)
+    }
+
+    async fn get_work_item(&self, id: i32) -> Result<WorkItem> {
+        self.get(&format!(
+            "{}/_apis/wit/workitems/{id}?$expand=relations",
+            self.org_url()
+        ))
+        .await
+    }
+
+    async fn get_work_items_with_relations(&self, ids: &[i32]) -> Result<Vec<WorkItem>> {
+        // The batch endpoint rejects a field list together with an expand
+        let body = serde_json::json!({ "ids": ids, "$expand": "relations", "errorPolicy": "omit" });
+        let batch: ListResponse<Option<WorkItem>> = self
+            .send_json(Method::POST, &self.project_api("wit/workitemsbatch"), &body)
+            .await?;
+        Ok(batch.value.into_iter().flatten().collect())
+    }
+
+    async fn get_work_item_revisions(&self, id: i32) -> Result<Vec<WorkItem>> {
+        self.get_list(&self.project_api(&format!("wit/workitems/{id}/revisions")))
+            .await
+    }
+
+    async fn get_work_item_updates(&self, id: i32) -> Result<Vec<WorkItemUpdate>> {
+        self.get_list(&self.project_api(&format!("wit/workitems/{id}/updates")))
+            .await
+    }
+
+    async fn get_work_item_comments(&self, id: i32) -> Result<Vec<WorkItemComment>> {
+        let mut comments = Vec::new();
+        let mut continuation: Option<String> = None;
+        loop {
+            let mut url = self.comments_api(id, "");
+            if let Some(token) = &continuation {
+                url.push_str(&format!(
+                    "&continuationToken={}",
+                    urlencoding::encode(token)
+                ));
+            }
+            let page: WorkItemCommentList = self.get(&url).await?;
+            comments.extend(page.comments);
+            match page.continuation_token {
+                Some(token) if !token.is_empty() => continuation = Some(token),
+                _ => return Ok(comments),
+            }
+        }
+    }
+
+    async fn add_work_item_comment(&self, id: i32, text: &str) -> Result<WorkItemComment> {
+        let text = self.resolve_mentions(text).await;
+        self.send_json(
+            Method::POST,
+            &self.comments_api(id, ""),
+            &serde_json::json!({ "text": text }),
+        )
+        .await
+    }
+
+    async fn update_work_item_comment(
+        &self,
+        id: i32,
+        comment_id: i32,
+        text: &str,
+    ) -> Result<WorkItemComment> {
+        let text = self.resolve_mentions(text).await;
+        self.send_json(
+            Method::PATCH,
+            &self.comments_api(id, &format!("/{comment_id}")),
+            &serde_json::json!({ "text": text }),
+        )
+        .await
+    }
+
+    async fn delete_work_item_comment(&self, id: i32, comment_id: i32) -> Result<()> {
+        self.send(
+            Method::DELETE,
+            &self.comments_api(id, &format!("/{comment_id}")),
+            None,
+            "",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn update_work_item(&self, id: i32, updates: &[FieldUpdate]) -> Result<WorkItem> {
+        let body = FieldUpdate::patch_document(updates);
+        let url = format!("{}/_apis/wit/workitems/{id}", self.org_url());
+        let response = self
+            .send(
+                Method::PATCH,
+                &url,
+                Some(&body),
+                "application/json-patch+json",
+            )
+            .await?;
+        response.json().await.context("Failed to parse response")
+    }
+
+    async fn update_work_item_links(
+        &self,
+        id: i32,
+        add: &[WorkItemRelation],
+        remove: &[WorkItemRelation],
+    ) -> Result<WorkItem> {
+        // Links are removed by index, so they come from the current revision
+        let item = self.get_work_item(id).await?;
+        let body = WorkItemRelation::patch_document(&item, add, remove)?;
+        let url = format!(
+            "{}/_apis/wit/workitems/{id}?$expand=relations",
+            self.org_url()
+        );
+        let response = self
+            .send(
+                Method::PATCH,
+                &url,
+                Some(&body),
+                "application/json-patch+json",
+            )
+            .await?;
+        response.json().await.context("Failed to parse response")
+    }
+
+    async fn create_work_item(&self, item: &NewWorkItem) -> Result<WorkItem> {
+        let body = create_work_item_body(item, self.org_url());
+        let url = self.project_api(&format!(
+            "wit/workitems/${}",
+            urlencoding::encode(&item.work_item_type)
+        ));
+        let response = self
+            .send(
+                Method::POST,
+                &url,
+                Some(&body),
+                "application/json-patch+json",
+            )
+            .await?;
+        response.json().await.context("Failed to parse response")
+    }
+
+    async fn get_current_user(&self) -> Result<String> {
+        Ok(self.authenticated_user().await?.1)
+    }
+
+    async fn list_pipelines(&self) -> Result<Vec<Pipeline>> {
+        self.get_list(&self.project_api("build/definitions")).await
+    }
+
+    async fn list_pipeline_runs(
+        &self,
+        pipeline_id: i32,
+        top: Option<u32>,
+    ) -> Result<Vec<PipelineRun>> {
+        let mut url = self.project_api(&format!(
+            "build/builds?definitions={pipeline_id}&queryOrder=queueTimeDescending"
+        ));
+        if let Some(top) = top {
+            url.push_str(&format!("&$top={top}"));
+        }
+        self.get_list(&url).await
+    }
+
+    async fn get_pipeline_run(&self, run_id: i32) -> Result<PipelineRun> {
+        self.get(&self.project_api(&format!("build/builds/{run_id}")))
+            .await
+    }
+
+    async fn get_pipeline_definition(&self, pipeline_id: i32) -> Result<PipelineDefinition> {
+        self.get(&self.project_api(&format!("build/definitions/{pipeline_id}")))
+            .await
+    }
+
+    async fn trigger_pipeline(
+        &self,
+        pipeline_id: i32,
+        request: &PipelineRunRequest,
+    ) -> Result<PipelineRun> {
+        #[derive(Deserialize)]
+        struct Run {
+            id: i32,
+        }
+
+        // Only the Runs API takes runtime parameters and stages to skip; the
+        // run it returns is the build listed under the pipeline
+        let run: Run = self
+            .send_json(
+                Method::POST,
+                &self.project_api(&format!("pipelines/{pipeline_id}/runs")),
+                &run_pipeline_body(request),
+            )
+            .await?;
+        self.get(&self.project_api(&format!("build/builds/{}", run.id)))
+            .await
+    }
+
+    async fn cancel_pipeline_run(&self, run_id: i32) -> Result<()> {
+        let body = serde_json::json!({ "status": "cancelling" });
+        self.send_json_no_content(
+            Method::PATCH,
+            &self.project_api(&format!("build/builds/{run_id}")),
+            &body,
+        )
+        .await
+    }
+
+    async fn get_build_timeline_delta(
+        &self,
+        build_id: i32,
+        last_change_id: Option<i32>,
+    ) -> Result<Option<(Vec<TimelineRecord>, Option<i32>)>> {
+        let mut url = self.project_api(&format!("build/builds/{build_id}/timeline"));
+        // changeId makes the service return nothing if the timeline is unchanged
+        if let Some(change_id) = last_change_id {
+            url.push_str(&format!("?changeId={change_id}"));
+        }
+
+        let text = self.get_text(&url).await?;
+        if text.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let response: TimelineResponse =
+            serde_json::from_str(&text).context("Failed to parse timeline response")?;
+        if response.records.is_empty() && last_change_id.is_some() {
+            return Ok(None);
+        }
+
+        Ok(Some((response.records, response.change_id)))
+    }
+
+    async fn get_build_log(&self, build_id: i32, log_id: i32) -> Result<Vec<String>> {
+        // With a JSON Accept header the log comes back as `{ count, value: [lines] }`
+        let response: BuildLogResponse = self
+            .get(&self.project_api(&format!("build/builds/{build_id}/logs/{log_id}")))
+            .await?;
+        Ok(response.value)
+    }
+
+    async fn list_test_runs(&self, build_id: i32) -> Result<Vec<TestRun>> {
+        self.get_list(&self.project_api(&format!(
+            "test/runs?buildUri={}&includeRunDetails=true",
+            urlencoding::encode(&build_uri(build_id))
+        )))
+        .await
+    }
+
+    async fn get_failed_test_results(&self, test_run_id: i32) -> Result<Vec<TestCaseResult>> {
+        self.get_list(&self.project_api(&format!(
+            "test/Runs/{test_run_id}/results?outcomes=Failed&$top=1000"
+        )))
+        .await
+    }
+
+    async fn list_build_artifacts(&self, build_id: i32) -> Result<Vec<BuildArtifact>> {
+        self.get_list(&self.project_api(&format!("build/builds/{build_id}/artifacts")))
+            .await
+    }
+
+    async fn list_artifact_items(
+        &self,
+        container_id: i64,
+        folder: &str,
+    ) -> Result<Vec<ArtifactItem>> {
+        self.get_list(&container_url(
+            self.org_url(),
+            container_id,
+            folder,
+            "isShallow=false",
+        ))
+        .await
+    }
+
+    async fn download_artifact(
+        &self,
+        artifact: &BuildArtifact,
+        item: Option<&ArtifactItem>,
+        dest: &Path,
+    ) -> Result<()> {
+        let url = artifact_download_url(self.org_url(), artifact, item)?;
+        self.download(&url, dest).await
+    }
+
+    async fn list_release_definitions(&self) -> Result<Vec<ReleaseDefinition>> {
+        let definitions: Vec<ReleaseDefinition> =
+            self.get_list(&self.release_api("definitions")).await?;
+        // Filter out deleted/disabled
+        Ok(definitions
+            .into_iter()
+            .filter(|d| !d.is_deleted && !d.is_disabled)
+            .collect())
+    }
+
+    async fn get_release_definition_detail(
+        &self,
+        definition_id: i32,
+    ) -> Result<ReleaseDefinitionDetail> {
+        self.get(&self.release_api(&format!("definitions/{definition_id}")))
+            .await
+    }
+
+    async fn list_releases(&self, definition_id: Option<i32>) -> Result<Vec<Release>> {
+        let mut url = self.release_api("releases");
+        if let Some(def_id) = definition_id {
+            url.push_str(&format!("?definitionId={def_id}"));
+        }
+        self.get_list(&url).await
+    }
+
+    async fn get_release(&self, release_id: i32) -> Result<Release> {
+        self.get(&self.release_api(&format!("releases/{release_id}")))
+            .await
+    }
+
+    async fn create_release(
+        &self,
+        definition_id: i32,
+        description: Option<&str>,
+    ) -> Result<Release> {
+        let body = serde_json::json!({
+            "definitionId": definition_id,
+            "description": description.unwrap_or_default(),
+        });
+        self.send_json(Method::POST, &self.release_api("releases"), &body)
+            .await
+            .context("Release failed")
+    }
+
+    async fn cancel_release(&self, release_id: i32) -> Result<()> {
+        let body = serde_json::json!({ "status": "abandoned" });
+        self.send_json_no_content(
+            Method::PATCH,
+            &self.release_api(&format!("releases/{release_id}")),
+            &body,
+        )
+        .await
+    }
+
+    async fn cancel_release_environment(&self, release_id: i32, environment_id: i32) -> Result<()> {
+        let body = serde_json::json!({
+            "status": "canceled",
+            "comment": "Canceled from lazyops"
+        });
+        self.send_json_no_content(
+            Method::PATCH,
+            &self.release_api(&format!(
+                "releases/{release_id}/environments/{environment_id}"
+            )),
+            &body,
+        )
+        .await
+    }
+
+    async fn redeploy_release_environment(
+        &self,
+        release_id: i32,
+        environment_id: i32,
+    ) -> Result<()> {
+        let body = serde_json::json!({
+            "status": "inProgress",
+            "comment": "Redeployed from lazyops"
+        });
+        self.send_json_no_content(
+            Method::PATCH,
+            &self.release_api(&format!(
+                "releases/{release_id}/environments/{environment_id}"
+            )),
+            &body,
+        )
+        .await
+    }
+
+    async fn get_release_task_log(&self, log_url: &str) -> Result<Vec<String>> {
+        let text = self.get_text(log_url).await?;
+        Ok(text.lines().map(|l| l.to_string()).collect())
+    }
+
+    async fn get_pending_approvals(&self) -> Result<Vec<Approval>> {
+        self.get_list(
+            &self.release_api("approvals?statusFilter=pending&includeMyGroupApprovals=true"),
+        )
+        .await
+    }
+
+    async fn update_approvals(
+        &self,
+        approval_ids: &[i32],
+        status: &str,
+        comments: &str,
+    ) -> Result<()> {
+        let body: Vec<_> = approval_ids
+            .iter()
+            .map(|id| serde_json::json!({ "id": id, "status": status, "comments": comments }))
+            .collect();
+        self.send_json_no_content(
+            Method::PATCH,
+            &self.release_api("approvals"),
+            &serde_json::Value::Array(body),
+        )
+        .await
+        .context("Approval failed")
+    }
+
+    async fn list_repositories(&self) -> Result<Vec<Repository>> {
+        self.get_list(&self.project_api("git/repositories")).await
+    }
+
+    async fn list_pull_requests(
+        &self,
+        repository: Option<&str>,
+        status: &str,
+        creator: Option<&str>,
+        top: Option<i32>,
+    ) -> Result<Vec<PullRequest>> {
+        let base = match repository {
+            Some(repo) => format!(
+                "git/repositories/{}/pullrequests",
+                urlencoding::encode(repo)
+            ),
+            None => "git/pullrequests".to_string(),
+        };
+        let mut url = self.project_api(&format!("{base}?searchCriteria.status={status}"));
+
+        if let Some(user) = creator {
+            let creator_id = self.resolve_identity(user).await?;
+            url.push_str(&format!("&searchCriteria.creatorId={creator_id}"));
+        }
+
+        if let Some(limit) = top {
+            url.push_str(&format!("&$top={limit}"));
+        }
+
+        self.get_list(&url).await
+    }
+
+    async fn get_pull_request(&self, id: i32) -> Result<PullRequest> {
+        self.get(&format!("{}/_apis/git/pullrequests/{id}", self.org_url()))
+            .await
+    }
+
+    async fn get_commit(&self, repository_id: &str, commit_id: &str) -> Result<GitCommitRef> {
+        self.get(&self.project_api(&format!(
+            "git/repositories/{repository_id}/commits/{commit_id}"
+        )))
+        .await
+    }
+
+    async fn list_pr_threads(&self, repository_id: &str, pr_id: i32) -> Result<Vec<PRThread>> {
+        self.get_list(&self.pr_api(repository_id, pr_id, "/threads"))
+            .await
+    }
+
+    async fn list_pr_iterations(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+    ) -> Result<Vec<PRIteration>> {
+        self.get_list(&self.pr_api(repository_id, pr_id, "/iterations"))
+            .await
+    }
+
+    async fn list_pr_changes(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+        iteration_id: i32,
+        compare_to: i32,
+    ) -> Result<Vec<PRChange>> {
+        let response: PRChangesResponse = self
+            .get(&self.pr_api(
+                repository_id,
+                pr_id,
+                &format!("/iterations/{iteration_id}/changes?$compareTo={compare_to}"),
+            ))
+            .await?;
+        Ok(response.files())
+    }
+
+    async fn get_file_content(
+        &self,
+        repository_id: &str,
+        path: &str,
+        commit_id: &str,
+    ) -> Result<String> {
+        #[derive(Deserialize)]
+        struct Item {
+            #[serde(default)]
+            content: String,
+        }
+
+        let item: Item = self
+            .get(&self.project_api(&format!(
+                "git/repositories/{repository_id}/items?path={}&includeContent=true\
+                 &versionDescriptor.versionType=commit&versionDescriptor.version={commit_id}",
+                urlencoding::encode(path)
+            )))
+            .await?;
+        Ok(item.content)
+    }
+
+    async fn get_file_at_ref(
+        &self,
+        repository_id: &str,
+        path: &str,
+        git_ref: &str,
+    ) -> Result<String> {
+        #[derive(Deserialize)]
+        struct Item {
+            #[serde(default)]
+            content: String,
+        }
+
+        let (version_type, version) = version_descriptor(git_ref);
+        let item: Item = self
+            .get(&self.project_api(&format!(
+                "git/repositories/{repository_id}/items?path={}&includeContent=true\
+                 &versionDescriptor.versionType={version_type}&versionDescriptor.version={}",
+                urlencoding::encode(path),
+                urlencoding::encode(version)
+            )))
+            .await?;
+        Ok(item.content)
+    }
+
+    async fn list_pr_policies(&self, pr_id: i32) -> Result<Vec<PRPolicy>> {
+        #[derive(Deserialize)]
+        struct Project {
+            id: String,
+        }
+
+        let project: Project = self
+            .get(&format!(
+                "{}/_apis/projects/{}",
+                self.org_url(),
+                urlencoding::encode(&self.project)
+            ))
+            .await?;
+
+        let artifact_id = format!("vstfs:///CodeReview/CodeReviewId/{}/{pr_id}", project.id);
+        self.get_list(&self.project_api(&format!(
+            "policy/evaluations?artifactId={}&api-version={API_VERSION}-preview.1",
+            urlencoding::encode(&artifact_id)
+        )))
+        .await
+    }
+
+    async fn set_pr_vote(&self, pr_id: i32, vote: &str) -> Result<()> {
+        let value = vote_value(vote).with_context(|| format!("Unknown vote: {vote}"))?;
+        let repository_id = self.pr_repository_id(pr_id).await?;
+        let (user_id, _) = self.authenticated_user().await?;
+
+        self.send_json_no_content(
+            Method::PUT,
+            &self.pr_api(&repository_id, pr_id, &format!("/reviewers/{user_id}")),
+            &serde_json::json!({ "vote": value }),
+        )
+        .await
+    }
+
+    async fn update_pr(
+        &self,
+        pr_id: i32,
+        status: Option<&str>,
+        title: Option<&str>,
+        description: Option<&str>,
+        draft: Option<bool>,
+    ) -> Result<PullRequest> {
+        let url = format!("{}/_apis/git/pullrequests/{pr_id}", self.org_url());
+        let current: serde_json::Value = self.get(&url).await?;
+        let repository_id = current
+            .pointer("/repository/id")
+            .and_then(|v| v.as_str())
+            .with_context(|| format!("PR #{pr_id} has no repository"))?
+            .to_string();
+
+        let mut body = serde_json::Map::new();
+        if let Some(s) = status {
+            body.insert("status".into(), s.into());
+            // Completing requires the commit the PR is merged at
+            if s == "completed" {
+                if let Some(commit) = current.get("lastMergeSourceCommit") {
+                    body.insert("lastMergeSourceCommit".into(), commit.clone());
+                }
+            }
+        }
+        if let Some(t) = title {
+            body.insert("title".into(), t.into());
+        }
+        if let Some(d) = description {
+            body.insert("description".into(), d.into());
+        }
+        if let Some(is_draft) = draft {
+            body.insert("isDraft".into(), is_draft.into());
+        }
+
+        self.send_json(
+            Method::PATCH,
+            &self.pr_api(&repository_id, pr_id, ""),
+            &serde_json::Value::Object(body),
+        )
+        .await
+    }
+
+    async fn complete_pr(
+        &self,
+        pr_id: i32,
+        options: &PRCompletionOptions,
+        auto_complete: bool,
+    ) -> Result<PullRequest> {
+        let url = format!("{}/_apis/git/pullrequests/{pr_id}", self.org_url());
+        let current: serde_json::Value = self.get(&url).await?;
+        let repository_id = current
+            .pointer("/repository/id")
+            .and_then(|v| v.as_str())
+            .with_context(|| format!("PR #{pr_id} has no repository"))?
+            .to_string();
+
+        let mut body = serde_json::json!({
+            "completionOptions": completion_options_body(options),
+        });
+        if auto_complete {
+            let (user_id, _) = self.authenticated_user().await?;
+            body["autoCompleteSetBy"] = serde_json::json!({ "id": user_id });
+        } else {
+            body["status"] = "completed".into();
+            if let Some(commit) = current.get("lastMergeSourceCommit") {
+                body["lastMergeSourceCommit"] = commit.clone();
+            }
+        }
+
+        self.send_json(
+            Method::PATCH,
+            &self.pr_api(&repository_id, pr_id, ""),
+            &body,
+        )
+        .await
+    }
+
+    async fn list_branches(&self, repository: &str) -> Result<Vec<GitRef>> {
+        self.get_list(&self.project_api(&format!(
+            "git/repositories/{}/refs?filter=heads/",
+            urlencoding::encode(repository)
+        )))
+        .await
+    }
+
+    async fn list_tags(&self, repository: &str) -> Result<Vec<GitRef>> {
+        self.get_list(&self.project_api(&format!(
+            "git/repositories/{}/refs?filter=tags/",
+            urlencoding::encode(repository)
+        )))
+        .await
+    }
+
+    async fn create_pr(&self, pr: &NewPullRequest) -> Result<PullRequest> {
+        let mut reviewer_ids = Vec::with_capacity(pr.reviewers.len());
+        for reviewer in &pr.reviewers {
+            reviewer_ids.push(self.resolve_identity(reviewer).await?);
+        }
+
+        let created: PullRequest = self
+            .send_json(
+                Method::POST,
+                &self.project_api(&format!(
+                    "git/repositories/{}/pullrequests",
+                    urlencoding::encode(&pr.repository)
+                )),
+                &create_pr_body(pr, &reviewer_ids),
+            )
+            .await?;
+
+        if !pr.auto_complete {
+            return Ok(created);
+        }
+
+        // Auto-complete can only be set once the PR exists
+        let repository_id = created
+            .repository
+            .as_ref()
+            .map(|r| r.id.clone())
+            .unwrap_or_else(|| pr.repository.clone());
+        let (user_id, _) = self.authenticated_user().await?;
+        self.send_json(
+            Method::PATCH,
+            &self.pr_api(&repository_id, created.pull_request_id, ""),
+            &serde_json::json!({
+                "autoCompleteSetBy": { "id": user_id },
+                "completionOptions": completion_options_body(&pr.completion_options),
+            }),
+        )
+        .await
+    }
+
+    async fn add_pr_comment(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+        content: &str,
+        anchor: Option<&PRCommentAnchor>,
+    ) -> Result<()> {
+        let body = new_thread_body(content, anchor);
+        self.send_json_no_content(
+            Method::POST,
+            &self.pr_api(repository_id, pr_id, "/threads"),
+            &body,
+        )
+        .await
+    }
+
+    async fn reply_pr_thread(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+        thread_id: i32,
+        content: &str,
+    ) -> Result<()> {
+        let body = serde_json::json!({
+            "content": content,
+            "parentCommentId": 1,
+            "commentType": 1
+        });
+        self.send_json_no_content(
+            Method::POST,
+            &self.pr_api(
+                repository_id,
+                pr_id,
+                &format!("/threads/{thread_id}/comments"),
+            ),
+            &body,
+        )
+        .await
+    }
+
+    async fn set_pr_thread_status(
+        &self,
+        repository_id: &str,
+        pr_id: i32,
+        thread_id: i32,
+        status: &str,
+    ) -> Result<()> {
+        self.send_json_no_content(
+            Method::PATCH,
+            &self.pr_api(repository_id, pr_id, &format!("/threads/{thread_id}")),
+            &serde_json::json!({ "status": status }),
+        )
+        .await
+    }
+
+    async fn list_pr_work_items(&self, pr_id: i32) -> Result<serde_json::Value> {
+        #[derive(Deserialize)]
+        struct WorkItemRef {
+            id: String,
+        }
+
+        let repository_id = self.pr_repository_id(pr_id).await?;
+        let refs: Vec<WorkItemRef> = self
+            .get_list(&self.pr_api(&repository_id, pr_id, "/workitems"))
+            .await?;
+        if refs.is_empty() {
+            return Ok(serde_json::Value::Array(vec![]));
+        }
+
+        let ids: Vec<String> = refs.into_iter().map(|r| r.id).collect();
+        let items: Vec<serde_json::Value> = self
+            .get_list(&format!(
+                "{}/_apis/wit/workitems?ids={}",
+                self.org_url(),
+                ids.join(",")
+            ))
+            .await?;
+        Ok(serde_json::Value::Array(items))
+    }
+}
+
+/// Map an organization URL to another Azure DevOps service host
+/// (e.g. "vsrm" for releases, "vssps" for identities)
+fn service_url(org_url: &str, service: &str) -> String {
+    if let Some(rest) = org_url.strip_prefix("https://dev.azure.com/") {
+        return format!("https://{service}.dev.azure.com/{rest}");
+    }
+    if let Some(host) = org_url
+        .strip_prefix("https://")
+        .and_then(|h| h.strip_suffix(".visualstudio.com"))
+    {
+        return format!("https://{host}.{service}.visualstudio.com");
+    }
+    org_url.to_string()
+}
+
+/// Append the default api-version unless the URL already has one
+fn with_api_version(url: &str) -> String {
+    if url.contains("api-version=") {
+        url.to_string()
+    } else if url.contains('?') {
+        format!("{url}&api-version={API_VERSION}")
+    } else {
+        format!("{url}?api-version={API_VERSION}")
+    }
+}
+
+/// Turn a short branch name into a full ref name
+fn branch_ref(branch: &str) -> String {
+    if branch.starts_with("refs/") {
+        branch.to_string()
+    } else {
+        format!("refs/heads/{branch}")
+    }
+}
+
+/// Version descriptor type and name of a full ref: `refs/tags/v1` is the tag
+/// `v1`, anything else a branch
+pub(crate) fn version_descriptor(git_ref: &str) -> (&'static str, &str) {
+    match git_ref.strip_prefix("refs/tags/") {
+        Some(tag) => ("tag", tag),
+        None => (
+            "branch",
+            git_ref.strip_prefix("refs/heads/").unwrap_or(git_ref),
+        ),
+    }
+}
+
+/// `vstfs:///` URI of a build, which test runs are filtered by
+pub(crate) fn build_uri(build_id: i32) -> String {
+    format!("vstfs:///Build/Build/{build_id}")
+}
+
+/// File container API URL of `item_path` in a container artifact
+pub(crate) fn container_url(
+    org_url: &str,
+    container_id: i64,
+    item_path: &str,
+    query: &str,
+) -> String {
+    format!(
+        "{}/_apis/resources/Containers/{container_id}?itemPath={}&{query}&api-version={API_VERSION}-preview",
+        org_url.trim_end_matches('/'),
+        urlencoding::encode(item_path)
+    )
+}
+
+/// URL downloading a whole artifact, or a file (or zipped folder) inside a
+/// container artifact
+pub(crate) fn artifact_download_url(
+    org_url: &str,
+    artifact: &BuildArtifact,
+    item: Option<&ArtifactItem>,
+) -> Result<String> {
+    match item {
+        Some(item) => {
+            let format = if item.is_folder() {
+                "zip"
+            } else {
+                "OctetStream"
+            };
+            Ok(container_url(
+                org_url,
+                item.container_id,
+                &item.path,
+                &format!("$format={format}"),
+            ))
+        }
+        None => artifact
+            .resource
+            .download_url
+            .clone()
+            .with_context(|| format!("Artifact {} has no download URL", artifact.name)),
+    }
+}
+
+/// Request body for the pipeline Runs API
+pub(crate) fn run_pipeline_body(request: &PipelineRunRequest) -> serde_json::Value {
+    let mut body = serde_json::json!({
+        "resources": {
+            "repositories": { "self": { "refName": branch_ref(&request.branch) } }
+        }
+    });
+    if !request.parameters.is_empty() {
+        body["templateParameters"] = serde_json::json!(request.parameters);
+    }
+    if !request.variables.is_empty() {
+        let variables: serde_json::Map<String, serde_json::Value> = request
+            .variables
+            .iter()
+            .map(|(name, value)| (name.clone(), serde_json::json!({ "value": value })))
+            .collect();
+        body["variables"] = serde_json::Value::Object(variables);
+    }
+    if !request.stages_to_skip.is_empty() {
+        body["stagesToSkip"] = serde_json::json!(request.stages_to_skip);
+    }
+    body
+}
+
+/// Request body for creating a pull request
+/// JSON Patch creating a work item, with a parent link when requested
+pub(crate) fn create_work_item_body(item: &NewWorkItem, org_url: &str) -> serde_json::Value {
+    let mut ops = match FieldUpdate::patch_document(&item.fields) {
+        serde_json::Value::Array(ops) => ops,
+        _ => Vec::new(),
+    };
+    if let Some(parent_id) = item.parent_id {
+        ops.push(serde_json::json!({
+            "op": "add",
+            "path": "/relations/-",
+            "value": {
+                "rel": "System.LinkTypes.Hierarchy-Reverse",
+                "url": format!("{org_url}/_apis/wit/workItems/{parent_id}"),
+            }
+        }));
+    }
+    serde_json::Value::Array(ops)
+}
+
+fn create_pr_body(pr: &NewPullRequest, reviewer_ids: &[String]) -> serde_json::Value {
+    serde_json::json!({
+        "sourceRefName": branch_ref(&pr.source_branch),
+        "targetRefName": branch_ref(&pr.target_branch),
+        "title": pr.title,
+        "description": pr.description,
+        "isDraft": pr.draft,
+        "reviewers": reviewer_ids
+            .iter()
+            .map(|id| serde_json::json!({ "id": id }))
+            .collect::<Vec<_>>(),
+        "workItemRefs": pr
+            .work_item_ids
+            .iter()
+            .map(|id| serde_json::json!({ "id": id.to_string() }))
+            .collect::<Vec<_>>(),
+        "completionOptions": completion_options_body(&pr.completion_options),
+    })
+}
+
+/// `completionOptions` as the REST API expects them (also used by the CLI backend)
+pub(crate) fn completion_options_body(options: &PRCompletionOptions) -> serde_json::Value {
+    let squash = options.squash_merge.unwrap_or(false);
+    let mut body = serde_json::json!({
+        "deleteSourceBranch": options.delete_source_branch.unwrap_or(false),
+        "squashMerge": squash,
+        "mergeStrategy": options
+            .merge_strategy
+            .clone()
+            .unwrap_or_else(|| if squash { "squash" } else { "noFastForward" }.to_string()),
+    });
+    if let Some(message) = &options.merge_commit_message {
+        body["mergeCommitMessage"] = message.clone().into();
+    }
+    if let Some(transition) = options.transition_work_items {
+        body["transitionWorkItems"] = transition.into();
+    }
+    if options.bypass_policy == Some(true) {
+        body["bypassPolicy"] = true.into();
+        body["bypassReason"] = options.bypass_reason.clone().unwrap_or_default().into();
+    }
+    body
+}
+
+/// Request body for a new comment thread (also used by the CLI backend)
+pub(crate) fn new_thread_body(
+    content: &str,
+    anchor: Option<&PRCommentAnchor>,
+) -> serde_json::Value {
+    let mut body = serde_json::json!({
+        "comments": [{
+            "content": content,
+            "parentCommentId": 0,
+            "commentType": 1
+        }],
+        "status": 1
+    });
+    if let Some(serde_json::Value::Object(fields)) = anchor.map(PRCommentAnchor::to_json) {
+        body.as_object_mut().unwrap().extend(fields);
+    }
+    body
+}
+
+/// Numeric reviewer vote for the names used by `az repos pr set-vote`
+pub(crate) fn vote_value(vote: &str) -> Option<i32> {
+    match vote {
+        "approve" => Some(10),
+        "approve-with-suggestions" => Some(5),
+        "reset" => Some(0),
+        "wait-for-author" => Some(-5),
+        "reject" => Some(-10),
+        _ => None,
+    }
+}
+
+/// Pull the human-readable message out of an Azure DevOps error body
+fn error_message(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v.get("message").and_then(|m| m.as_str()).map(String::from))
+        .unwrap_or_else(|| body.lines().next().unwrap_or_default().to_string())
+}
+
+/// Error for a 404: the resource asked for, and the service's reason if it gave one
+fn not_found_message(path: &str, body: &str) -> String {
+    match error_message(body) {
+        reason if reason.is_empty() => format!("Not found: {path}"),
+        reason => format!("Not found: {path} ({reason})"),
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_url_dev_azure() {
+        assert_eq!(
+            service_url("https://dev.azure.com/contoso", "vsrm"),
+            "https://vsrm.dev.azure.com/contoso"
+        );
+    }
+
+    #[test]
+    fn test_service_url_visualstudio() {
+        assert_eq!(
+            service_url("https://contoso.visualstudio.com", "vssps"),
+            "https://contoso.vssps.visualstudio.com"
+        );
+    }
+
+    #[test]
+    fn test_with_api_version() {
+        assert_eq!(
+            with_api_version("https://x/a"),
+            "https://x/a?api-version=7.1"
+        );
+        assert_eq!(
+            with_api_version("https://x/a?b=1"),
+            "https://x/a?b=1&api-version=7.1"
+        );
+        assert_eq!(
+            with_api_version("https://x/a?api-version=7.1-preview.1"),
+            "https://x/a?api-version=7.1-preview.1"
+        );
+    }
+
+    #[test]
+    fn test_artifact_download_url() {
+        let artifact = BuildArtifact {
+            name: "drop".to_string(),
+            resource: ArtifactResource {
+                download_url: Some("https://x/drop.zip".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let file = ArtifactItem {
+            container_id: 42,
+            item_type: "file".to_string(),
+            path: "drop/bin/app 1.zip".to_string(),
+            file_length: Some(10),
+        };
+        let folder = ArtifactItem {
+            item_type: "folder".to_string(),
+            path: "drop/bin".to_string(),
+            ..file.clone()
+        };
+        let org = "https://dev.azure.com/org/";
+        assert_eq!(
+            artifact_download_url(org, &artifact, None).unwrap(),
+            "https://x/drop.zip"
+        );
+        assert_eq!(
+            artifact_download_url(org, &artifact, Some(&file)).unwrap(),
+            "https://dev.azure.com/org/_apis/resources/Containers/42\
+             ?itemPath=drop%2Fbin%2Fapp%201.zip&$format=OctetStream&api-version=7.1-preview"
+        );
+        assert!(artifact_download_url(org, &artifact, Some(&folder))
+            .unwrap()
+            .contains("itemPath=drop%2Fbin&$format=zip"));
+        assert!(artifact_download_url(org, &BuildArtifact::default(), None).is_err());
+    }
+
+    #[test]
+    fn test_branch_ref() {
+        assert_eq!(branch_ref("main"), "refs/heads/main");
+        assert_eq!(branch_ref("refs/heads/dev"), "refs/heads/dev");
+    }
+
+    #[test]
+    fn test_vote_value() {
+        assert_eq!(vote_value("approve"), Some(10));
+        assert_eq!(vote_value("wait-for-author"), Some(-5));
+        assert_eq!(vote_value("maybe"), None);
+    }
+
+    #[test]
+    fn test_create_pr_body() {
+        let pr = NewPullRequest {
+            repository: "web".to_string(),
+            source_branch: "feature/x".to_string(),
+            target_branch: "main".to_string(),
+            title: "X".to_string(),
+            work_item_ids: vec![1001],
+            completion_options: PRCompletionOptions {
+                squash_merge: Some(true),
+                delete_source_branch: Some(true),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let body = create_pr_body(&pr, &["user-1".to_string()]);
+        assert_eq!(body["sourceRefName"], "refs/heads/feature/x");
+        assert_eq!(body["reviewers"][0]["id"], "user-1");
+        assert_eq!(body["workItemRefs"][0]["id"], "1001");
+        assert_eq!(body["completionOptions"]["mergeStrategy"], "squash");
+        assert_eq!(body["completionOptions"]["deleteSourceBranch"], true);
+    }
+
+    #[test]
+    fn test_create_work_item_body_links_parent() {
+        let item = NewWorkItem {
+            work_item_type: "Task".into(),
+            fields: vec![
+                FieldUpdate::set(WorkItemFields::TITLE, "Design form"),
+                FieldUpdate::set(WorkItemFields::REMAINING_WORK, 3.0),
+            ],
+            parent_id: Some(1001),
+        };
+        let body = create_work_item_body(&item, "https://dev.azure.com/org");
+        assert_eq!(body[0]["path"], "/fields/System.Title");
+        assert_eq!(body[1]["value"], 3.0);
+        assert_eq!(body[2]["path"], "/relations/-");
+        assert_eq!(
+            body[2]["value"]["rel"],
+            "System.LinkTypes.Hierarchy-Reverse"
+        );
+        assert_eq!(
+            body[2]["value"]["url"],
+            "https://dev.azure.com/org/_apis/wit/workItems/1001"
+        );
+    }
+
+    #[test]
+    fn test_run_pipeline_body() {
+        let body = run_pipeline_body(&PipelineRunRequest::branch("main"));
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "resources": { "repositories": { "self": { "refName": "refs/heads/main" } } }
+            })
+        );
+
+        let request = PipelineRunRequest {
+            branch: "refs/tags/v1.2".to_string(),
+            parameters: [("runE2E".to_string(), "true".to_string())].into(),
+            variables: [("verbosity".to_string(), "detailed".to_string())].into(),
+            stages_to_skip: vec!["Deploy".to_string()],
+        };
+        let body = run_pipeline_body(&request);
+        assert_eq!(
+            body["resources"]["repositories"]["self"]["refName"],
+            "refs/tags/v1.2"
+        );
+        assert_eq!(body["templateParameters"]["runE2E"], "true");
+        assert_eq!(body["variables"]["verbosity"]["value"], "detailed");
+        assert_eq!(body["stagesToSkip"][0], "Deploy");
+
+        assert_eq!(version_descriptor("refs/tags/v1.2"), ("tag", "v1.2"));
+        assert_eq!(
+            version_descriptor("refs/heads/feature/x"),
+            ("branch", "feature/x")
+        );
+        assert_eq!(version_descriptor("main"), ("branch", "main"));
+    }
+
+    #[test]
+    fn test_completion_options_body_bypass() {
+        let body = completion_options_body(&PRCompletionOptions {
+            merge_strategy: Some("rebaseMerge".to_string()),
+            transition_work_items: Some(true),
+            bypass_policy: Some(true),
+            bypass_reason: Some("Hotfix".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(body["mergeStrategy"], "rebaseMerge");
+        assert_eq!(body["transitionWorkItems"], true);
+        assert_eq!(body["bypassReason"], "Hotfix");
+
+        let body = completion_options_body(&PRCompletionOptions::default());
+        assert!(body.get("bypassPolicy").is_none());
+    }
+
+    #[test]
+    fn test_new_thread_body_with_anchor() {
+        let body = new_thread_body("Hi", None);
+        assert!(body.get("threadContext").is_none());
+
+        let anchor = PRCommentAnchor {
+            thread_context: PRThreadContext {
+                file_path: "/src/app.ts".to_string(),
+                right_file_start: Some(PRFilePosition { line: 3, offset: 1 }),
+                right_file_end: Some(PRFilePosition { line: 5, offset: 9 }),
+                ..Default::default()
+            },
+            change_tracking_id: 2,
+            first_iteration: 3,
+            second_iteration: 3,
+        };
+        let body = new_thread_body("Hi", Some(&anchor));
+        assert_eq!(body["comments"][0]["content"], "Hi");
+        assert_eq!(body["threadContext"]["rightFileEnd"]["line"], 5);
+        assert_eq!(
+            body["pullRequestThreadContext"]["iterationContext"]["firstComparingIteration"],
+            3
+        );
+        assert_eq!(
+            body["pullRequestThreadContext"]["iterationContext"]["secondComparingIteration"],
+            3
+        );
+    }
+
+    #[test]
+    fn test_project_urls_encode_names() {
+        let client = RestClient::new().for_project(&ProjectConfig {
+            name: "p".to_string(),
+            organization: "https://dev.azure.com/contoso/".to_string(),
+            project: "My Project".to_string(),
+            team: "My Team".to_string(),
+            repository: None,
+            overrides: Default::default(),
+        });
+        assert_eq!(
+            client.team_api("wit/wiql"),
+            "https://dev.azure.com/contoso/My%20Project/My%20Team/_apis/wit/wiql"
+        );
+        assert_eq!(
+            client.release_api("approvals"),
+            "https://vsrm.dev.azure.com/contoso/My%20Project/_apis/release/approvals"
+        );
+    }
+
+    #[test]
+    fn test_error_message_prefers_json_message() {
+        assert_eq!(
+            error_message(r#"{"message":"TF400813: denied","typeKey":"X"}"#),
+            "TF400813: denied"
+        );
+        assert_eq!(error_message("plain\nsecond"), "plain");
+    }
+
+    #[test]
+    fn test_not_found_names_the_resource() {
+        assert_eq!(
+            not_found_message(
+                "/org/proj/_apis/git/repositories/web",
+                r#"{"message":"TF401019: The Git repository with name or identifier web does not exist."}"#
+            ),
+            "Not found: /org/proj/_apis/git/repositories/web \
+             (TF401019: The Git repository with name or identifier web does not exist.)"
+        );
+        assert_eq!(
+            not_found_message("/org/_apis/wit/workitems/7", ""),
+            "Not found: /org/_apis/wit/workitems/7"
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkItem {
@@ -14,7 +15,7 @@ pub struct WorkItem {
     pub depth: usize,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct WorkItemFields {
     #[serde(rename = "System.Title")]
     pub title: String,
@@ -42,6 +43,214 @@ pub struct WorkItemFields {
     pub original_estimate: Option<f64>,
     #[serde(rename = "Microsoft.VSTS.Scheduling.CompletedWork")]
     pub completed_work: Option<f64>,
+    #[serde(rename = "System.AreaPath")]
+    pub area_path: Option<String>,
+    #[serde(rename = "Microsoft.VSTS.Common.Priority")]
+    pub priority: Option<i64>,
+    /// Fields without a member above (custom fields etc.), by reference name
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+impl WorkItemFields {
+    pub const TITLE: &'static str = "System.Title";
+    pub const STATE: &'static str = "System.State";
+    pub const ASSIGNED_TO: &'static str = "System.AssignedTo";
+    pub const DESCRIPTION: &'static str = "System.Description";
+    pub const TAGS: &'static str = "System.Tags";
+    pub const ITERATION_PATH: &'static str = "System.IterationPath";
+    pub const AREA_PATH: &'static str = "System.AreaPath";
+    pub const PRIORITY: &'static str = "Microsoft.VSTS.Common.Priority";
+    pub const ORIGINAL_ESTIMATE: &'static str = "Microsoft.VSTS.Scheduling.OriginalEstimate";
+    pub const REMAINING_WORK: &'static str = "Microsoft.VSTS.Scheduling.RemainingWork";
+    pub const COMPLETED_WORK: &'static str = "Microsoft.VSTS.Scheduling.CompletedWork";
+    /// Read-only: column of the item on its backlog level's board
+    pub const BOARD_COLUMN: &'static str = "System.BoardColumn";
+    /// Read-only: swimlane of the item on its backlog level's board
+    pub const BOARD_LANE: &'static str = "System.BoardLane";
+
+    /// Current value of a field by reference name
+    pub fn get(&self, name: &str) -> Option<serde_json::Value> {
+        use serde_json::Value;
+        match name {
+            Self::TITLE => Some(Value::from(self.title.as_str())),
+            Self::STATE => Some(Value::from(self.state.as_str())),
+            Self::ASSIGNED_TO => self
+                .assigned_to
+                .as_ref()
+                .map(|a| Value::from(a.unique_name.as_str())),
+            Self::DESCRIPTION => self.description.clone().map(Value::from),
+            Self::TAGS => self.tags.clone().map(Value::from),
+            Self::ITERATION_PATH => self.iteration_path.clone().map(Value::from),
+            Self::AREA_PATH => self.area_path.clone().map(Value::from),
+            Self::PRIORITY => self.priority.map(Value::from),
+            Self::ORIGINAL_ESTIMATE => self.original_estimate.map(Value::from),
+            Self::REMAINING_WORK => self.remaining_work.map(Value::from),
+            Self::COMPLETED_WORK => self.completed_work.map(Value::from),
+            _ => self.other.get(name).filter(|v| !v.is_null()).cloned(),
+        }
+    }
+
+    /// Apply one field update locally (`None` clears the field)
+    pub fn set(&mut self, name: &str, value: Option<&serde_json::Value>) {
+        let text = || value.and_then(|v| v.as_str()).map(str::to_string);
+        let number = || value.and_then(|v| v.as_f64());
+        match name {
+            Self::TITLE => self.title = text().unwrap_or_default(),
+            Self::STATE => self.state = text().unwrap_or_default(),
+            Self::ASSIGNED_TO => {
+                self.assigned_to = text().map(|name| AssignedTo {
+                    display_name: name.clone(),
+                    unique_name: name,
+                })
+            }
+            Self::DESCRIPTION => self.description = text(),
+            Self::TAGS => self.tags = text(),
+            Self::ITERATION_PATH => self.iteration_path = text(),
+            Self::AREA_PATH => self.area_path = text(),
+            Self::PRIORITY => self.priority = value.and_then(|v| v.as_i64()),
+            Self::ORIGINAL_ESTIMATE => self.original_estimate = number(),
+            Self::REMAINING_WORK => self.remaining_work = number(),
+            Self::COMPLETED_WORK => self.completed_work = number(),
+            _ => match value {
+                Some(v) => {
+                    self.other.insert(name.to_string(), v.clone());
+                }
+                None => {
+                    self.other.remove(name);
+                }
+            },
+        }
+    }
+}
+
+/// One change in a work item JSON Patch update
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldUpdate {
+    /// Field reference name, e.g. `System.Title` or `Custom.Team`
+    pub field: String,
+    /// New value; `None` removes the field's value
+    pub value: Option<serde_json::Value>,
+}
+
+impl FieldUpdate {
+    pub fn set(field: &str, value: impl Into<serde_json::Value>) -> Self {
+        Self {
+            field: field.to_string(),
+            value: Some(value.into()),
+        }
+    }
+
+    pub fn clear(field: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            value: None,
+        }
+    }
+
+    /// JSON Patch document for a work item update
+    pub fn patch_document(updates: &[FieldUpdate]) -> serde_json::Value {
+        updates
+            .iter()
+            .map(|u| {
+                let path = format!("/fields/{}", u.field);
+                match &u.value {
+                    Some(value) => serde_json::json!({ "op": "add", "path": path, "value": value }),
+                    None => serde_json::json!({ "op": "remove", "path": path }),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Folder or query in the project's query hierarchy ("My Queries", "Shared Queries")
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryHierarchyItem {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub path: String,
+    #[serde(default)]
+    pub is_folder: bool,
+    #[serde(default)]
+    pub wiql: Option<String>,
+    #[serde(default)]
+    pub children: Vec<QueryHierarchyItem>,
+}
+
+/// Kanban board of one backlog level (Stories, Features, ...) of a team
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Board {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub columns: Vec<BoardColumn>,
+    /// Swimlanes; the default lane has no name
+    #[serde(default)]
+    pub rows: Vec<BoardRow>,
+    #[serde(default)]
+    pub fields: BoardFields,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardColumn {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// WIP limit, 0 for none
+    #[serde(default)]
+    pub item_limit: u32,
+    /// State each work item type has in this column
+    #[serde(default)]
+    pub state_mappings: BTreeMap<String, String>,
+    /// `incoming`, `inProgress` or `outgoing`
+    #[serde(default)]
+    pub column_type: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardRow {
+    #[serde(default)]
+    pub id: String,
+    pub name: Option<String>,
+}
+
+/// Team-specific fields that store an item's column and lane on this board
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardFields {
+    pub column_field: Option<FieldReference>,
+    pub row_field: Option<FieldReference>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldReference {
+    pub reference_name: String,
+}
+
+/// Everything needed to create a work item
+#[derive(Debug, Clone, Default)]
+pub struct NewWorkItem {
+    pub work_item_type: String,
+    /// Initial field values, including `System.Title`
+    pub fields: Vec<FieldUpdate>,
+    /// Work item to link the new one under
+    pub parent_id: Option<i32>,
+}
+
+impl NewWorkItem {
+    pub fn title(&self) -> &str {
+        self.fields
+            .iter()
+            .find(|u| u.field == WorkItemFields::TITLE)
+            .and_then(|u| u.value.as_ref()?.as_str())
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,7 +261,7 @@ pub struct AssignedTo {
     pub unique_name: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct WorkItemRelation {
     pub rel: String,
     pub url: String,
@@ -60,12 +269,184 @@ pub struct WorkItemRelation {
     pub attributes: WorkItemRelationAttributes,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct WorkItemRelationAttributes {
     #[serde(rename = "name")]
     pub name: Option<String>,
 }
 
+impl WorkItemRelation {
+    pub const PARENT: &'static str = "System.LinkTypes.Hierarchy-Reverse";
+    pub const CHILD: &'static str = "System.LinkTypes.Hierarchy-Forward";
+    pub const ARTIFACT: &'static str = "ArtifactLink";
+
+    /// Link of type `rel` to work item `id` in the organization at `org_url`
+    pub fn work_item(org_url: &str, rel: &str, id: i32) -> Self {
+        Self {
+            rel: rel.to_string(),
+            url: format!("{}/_apis/wit/workItems/{id}", org_url.trim_end_matches('/')),
+            attributes: WorkItemRelationAttributes::default(),
+        }
+    }
+
+    /// Link to a `vstfs:///` artifact such as a commit or pull request;
+    /// `name` is what the server calls the link type ("Pull Request", ...)
+    pub fn artifact(url: String, name: &str) -> Self {
+        Self {
+            rel: Self::ARTIFACT.to_string(),
+            url,
+            attributes: WorkItemRelationAttributes {
+                name: Some(name.to_string()),
+            },
+        }
+    }
+
+    /// Type the other end records for a work item link of type `rel`
+    pub fn reverse_rel(rel: &str) -> Option<&'static str> {
+        Some(match rel {
+            Self::PARENT => Self::CHILD,
+            Self::CHILD => Self::PARENT,
+            "System.LinkTypes.Related" => "System.LinkTypes.Related",
+            "System.LinkTypes.Dependency-Forward" => "System.LinkTypes.Dependency-Reverse",
+            "System.LinkTypes.Dependency-Reverse" => "System.LinkTypes.Dependency-Forward",
+            "System.LinkTypes.Duplicate-Forward" => "System.LinkTypes.Duplicate-Reverse",
+            "System.LinkTypes.Duplicate-Reverse" => "System.LinkTypes.Duplicate-Forward",
+            _ => return None,
+        })
+    }
+
+    /// Work item the link points to, for links to work items
+    pub fn target_id(&self) -> Option<i32> {
+        if !self.rel.starts_with("System.LinkTypes.") {
+            return None;
+        }
+        self.url.rsplit('/').next()?.parse().ok()
+    }
+
+    /// Whether both are the same kind of link to the same target; work item
+    /// URLs differ in form between API versions, so those compare by ID
+    pub fn same_link(&self, other: &WorkItemRelation) -> bool {
+        self.rel == other.rel
+            && match (self.target_id(), other.target_id()) {
+                (Some(a), Some(b)) => a == b,
+                _ => self.url.eq_ignore_ascii_case(&other.url),
+            }
+    }
+
+    /// JSON Patch document that removes the `remove` links from `item` and adds
+    /// the `add` ones; a new parent replaces the current one
+    pub fn patch_document(
+        item: &WorkItem,
+        add: &[WorkItemRelation],
+        remove: &[WorkItemRelation],
+    ) -> anyhow::Result<serde_json::Value> {
+        let current = item.relations.as_deref().unwrap_or_default();
+        let mut indexes = Vec::new();
+        for relation in remove {
+            let Some(idx) = current.iter().position(|r| r.same_link(relation)) else {
+                anyhow::bail!("#{} has no such link", item.id);
+            };
+            indexes.push(idx);
+        }
+        if add.iter().any(|r| r.rel == Self::PARENT) {
+            indexes.extend(current.iter().position(|r| r.rel == Self::PARENT));
+        }
+        for relation in add {
+            if current.iter().any(|r| r.same_link(relation)) {
+                anyhow::bail!("#{} is already linked to that", item.id);
+            }
+        }
+        indexes.sort_unstable();
+        indexes.dedup();
+
+        // Guard against concurrent edits moving the indexes
+        let mut ops = vec![serde_json::json!({ "op": "test", "path": "/rev", "value": item.rev })];
+        // From the back, so the remaining indexes stay valid
+        for idx in indexes.into_iter().rev() {
+            ops.push(serde_json::json!({ "op": "remove", "path": format!("/relations/{idx}") }));
+        }
+        for relation in add {
+            let mut value = serde_json::json!({ "rel": relation.rel, "url": relation.url });
+            if let Some(name) = &relation.attributes.name {
+                value["attributes"] = serde_json::json!({ "name": name });
+            }
+            ops.push(serde_json::json!({ "op": "add", "path": "/relations/-", "value": value }));
+        }
+        Ok(serde_json::Value::Array(ops))
+    }
+}
+
+/// One entry of a work item's update history: the fields it changed, from
+/// what to what, and the links it added or removed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkItemUpdate {
+    pub id: i32,
+    #[serde(default)]
+    pub rev: Option<i32>,
+    #[serde(default)]
+    pub revised_by: Option<IdentityRef>,
+    /// When this revision was superseded; `9999-01-01` for the latest one
+    #[serde(default)]
+    pub revised_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub fields: BTreeMap<String, WorkItemFieldChange>,
+    #[serde(default)]
+    pub relations: Option<WorkItemRelationChanges>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkItemFieldChange {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_value: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_value: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkItemRelationChanges {
+    #[serde(default)]
+    pub added: Vec<WorkItemRelation>,
+    #[serde(default)]
+    pub removed: Vec<WorkItemRelation>,
+}
+
+/// Comment in a work item's discussion; `text` is HTML
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkItemComment {
+    pub id: i32,
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub created_by: Option<IdentityRef>,
+    #[serde(default)]
+    pub created_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub modified_date: Option<DateTime<Utc>>,
+}
+
+impl WorkItemComment {
+    /// Changed after it was posted
+    pub fn is_edited(&self) -> bool {
+        match (self.created_date, self.modified_date) {
+            (Some(created), Some(modified)) => (modified - created).num_seconds() > 0,
+            _ => false,
+        }
+    }
+}
+
+/// One page of a work item's comments
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkItemCommentList {
+    #[serde(default)]
+    pub comments: Vec<WorkItemComment>,
+    #[serde(default)]
+    pub continuation_token: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Sprint {
     pub id: String,
@@ -84,7 +465,74 @@ pub struct SprintAttributes {
     pub time_frame: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Capacity the team planned for one sprint
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamCapacity {
+    /// Older API versions list the members under `value`
+    #[serde(default, alias = "value")]
+    pub team_members: Vec<TeamMemberCapacity>,
+    /// Days off for the whole team (the sprint's team days off)
+    #[serde(default)]
+    pub team_days_off: Vec<DateRange>,
+    /// Days of the week the team works, in lower case ("monday", ...)
+    #[serde(default)]
+    pub working_days: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamMemberCapacity {
+    pub team_member: TeamMember,
+    #[serde(default)]
+    pub activities: Vec<CapacityActivity>,
+    #[serde(default)]
+    pub days_off: Vec<DateRange>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamMember {
+    pub display_name: String,
+    #[serde(default)]
+    pub unique_name: String,
+}
+
+/// Hours a day a member spends on one kind of work ("Development", ...)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapacityActivity {
+    #[serde(default)]
+    pub capacity_per_day: f64,
+    /// Empty when no activity is set
+    #[serde(default)]
+    pub name: String,
+}
+
+/// Days off the whole team takes in a sprint
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamDaysOff {
+    #[serde(default)]
+    pub days_off: Vec<DateRange>,
+}
+
+/// The parts of a team's settings lazyops uses
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamSettings {
+    #[serde(default)]
+    pub working_days: Vec<String>,
+}
+
+/// Days from `start` to `end`, both included
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DateRange {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct User {
     #[serde(rename = "displayName")]
     pub display_name: String,
@@ -147,6 +595,83 @@ pub struct PipelineDefinitionRef {
     pub name: Option<String>,
 }
 
+/// Build definition behind a pipeline: where its YAML lives and the variables
+/// it declares
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineDefinition {
+    pub id: i32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub repository: Option<DefinitionRepository>,
+    #[serde(default)]
+    pub process: Option<DefinitionProcess>,
+    #[serde(default)]
+    pub variables: BTreeMap<String, DefinitionVariable>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefinitionRepository {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    /// `TfsGit` for Azure Repos, `GitHub`, ...
+    #[serde(default, rename = "type")]
+    pub repo_type: String,
+    #[serde(default)]
+    pub default_branch: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefinitionProcess {
+    /// Path of the pipeline's YAML file (none for classic pipelines)
+    #[serde(default)]
+    pub yaml_filename: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefinitionVariable {
+    /// Not returned for secrets
+    #[serde(default)]
+    pub value: Option<String>,
+    /// Whether the variable can be set when queueing a run
+    #[serde(default)]
+    pub allow_override: bool,
+    #[serde(default)]
+    pub is_secret: bool,
+}
+
+/// A pipeline run to queue. The trigger dialog also keeps the last one per
+/// pipeline as its preset
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineRunRequest {
+    /// Branch or tag: a short branch name (`main`) or a full ref
+    pub branch: String,
+    /// Runtime parameters declared in the YAML, by name
+    #[serde(default)]
+    pub parameters: BTreeMap<String, String>,
+    /// Queue-time variable overrides, by name
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    #[serde(default)]
+    pub stages_to_skip: Vec<String>,
+}
+
+impl PipelineRunRequest {
+    /// Run on a branch with the pipeline's defaults
+    pub fn branch(branch: &str) -> Self {
+        Self {
+            branch: branch.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
 // Release types
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -298,6 +823,58 @@ pub struct TimelineRecord {
     pub start_time: Option<String>,
     #[serde(default)]
     pub finish_time: Option<String>,
+    #[serde(default)]
+    pub issues: Option<Vec<TimelineIssue>>,
+    #[serde(default)]
+    pub error_count: Option<i32>,
+    #[serde(default)]
+    pub warning_count: Option<i32>,
+}
+
+/// Error or warning reported by a timeline record
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineIssue {
+    #[serde(rename = "type")]
+    #[serde(default)]
+    pub issue_type: Option<String>, // error, warning
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+    /// sourcepath, linenumber, logFileLineNumber, ... (numbers as strings)
+    #[serde(default)]
+    pub data: Option<BTreeMap<String, serde_json::Value>>,
+}
+
+impl TimelineIssue {
+    fn data(&self, key: &str) -> Option<String> {
+        let (_, value) = self
+            .data
+            .as_ref()?
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))?;
+        match value {
+            serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        }
+    }
+
+    /// File the issue points at, as the task reported it
+    pub fn source_path(&self) -> Option<String> {
+        self.data("sourcepath")
+    }
+
+    /// Line in `source_path`
+    pub fn line_number(&self) -> Option<usize> {
+        self.data("linenumber")?.parse().ok()
+    }
+
+    /// 1-based line of the task's log that logged the issue
+    pub fn log_line(&self) -> Option<usize> {
+        self.data("logFileLineNumber")?.parse().ok()
+    }
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -328,6 +905,127 @@ pub struct BuildLogResponse {
     pub value: Vec<String>,
 }
 
+/// Test run published by a build
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestRun {
+    pub id: i32,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub total_tests: i32,
+    #[serde(default)]
+    pub passed_tests: i32,
+    /// Result counts per outcome (with `includeRunDetails=true`)
+    #[serde(default)]
+    pub run_statistics: Vec<TestRunStatistic>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestRunStatistic {
+    #[serde(default)]
+    pub outcome: String, // Passed, Failed, NotExecuted, ...
+    #[serde(default)]
+    pub count: i32,
+}
+
+/// Result of one test in a test run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCaseResult {
+    pub id: i32,
+    #[serde(default)]
+    pub test_case_title: Option<String>,
+    #[serde(default)]
+    pub automated_test_name: Option<String>,
+    /// Assembly or file the test lives in
+    #[serde(default)]
+    pub automated_test_storage: Option<String>,
+    #[serde(default)]
+    pub outcome: Option<String>,
+    #[serde(default)]
+    pub error_message: Option<String>,
+    #[serde(default)]
+    pub stack_trace: Option<String>,
+    #[serde(default)]
+    pub duration_in_ms: Option<f64>,
+}
+
+/// Artifact published by a build
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildArtifact {
+    #[serde(default)]
+    pub id: i32,
+    pub name: String,
+    #[serde(default)]
+    pub resource: ArtifactResource,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactResource {
+    /// "Container" (file container), "PipelineArtifact", ...
+    #[serde(rename = "type", default)]
+    pub resource_type: Option<String>,
+    /// `#/{containerId}/{folder}` for container artifacts
+    #[serde(default)]
+    pub data: Option<String>,
+    /// The whole artifact as a zip
+    #[serde(default)]
+    pub download_url: Option<String>,
+    #[serde(default)]
+    pub properties: Option<BTreeMap<String, serde_json::Value>>,
+}
+
+impl BuildArtifact {
+    /// File container and the folder in it holding a container artifact
+    pub fn container(&self) -> Option<(i64, &str)> {
+        if !self
+            .resource
+            .resource_type
+            .as_deref()
+            .is_some_and(|t| t.eq_ignore_ascii_case("container"))
+        {
+            return None;
+        }
+        let data = self.resource.data.as_deref()?.strip_prefix("#/")?;
+        let (id, folder) = data.split_once('/')?;
+        Some((id.parse().ok()?, folder))
+    }
+
+    /// Size reported with the artifact (pipeline artifacts report one)
+    pub fn reported_size(&self) -> Option<u64> {
+        match self.resource.properties.as_ref()?.get("artifactsize")? {
+            serde_json::Value::String(s) => s.parse().ok(),
+            serde_json::Value::Number(n) => n.as_u64(),
+            _ => None,
+        }
+    }
+}
+
+/// File or folder in a file container
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactItem {
+    #[serde(default)]
+    pub container_id: i64,
+    /// "file" or "folder"
+    #[serde(default)]
+    pub item_type: String,
+    /// Slash-separated, starting with the artifact's folder
+    pub path: String,
+    #[serde(default)]
+    pub file_length: Option<u64>,
+}
+
+impl ArtifactItem {
+    pub fn is_folder(&self) -> bool {
+        self.item_type.eq_ignore_ascii_case("folder")
+    }
+}
+
 // Approval types for release management
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -427,6 +1125,36 @@ pub struct ReleaseArtifact {
 pub struct Repository {
     pub id: String,
     pub name: String,
+    /// Needed to link commits and pull requests to work items
+    #[serde(default)]
+    pub project: Option<PRProject>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRef {
+    /// Full ref name (`refs/heads/main`)
+    pub name: String,
+    #[serde(default)]
+    pub object_id: Option<String>,
+}
+
+/// Everything needed to open a pull request
+#[derive(Debug, Clone, Default)]
+pub struct NewPullRequest {
+    /// Repository name or ID
+    pub repository: String,
+    pub source_branch: String,
+    pub target_branch: String,
+    pub title: String,
+    pub description: String,
+    pub draft: bool,
+    /// Reviewer unique names (emails)
+    pub reviewers: Vec<String>,
+    pub work_item_ids: Vec<i32>,
+    pub completion_options: PRCompletionOptions,
+    /// Complete automatically once policies pass
+    pub auto_complete: bool,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -513,6 +1241,13 @@ pub struct PRCompletionOptions {
     pub squash_merge: Option<bool>,
     #[serde(default)]
     pub merge_commit_message: Option<String>,
+    #[serde(default)]
+    pub transition_work_items: Option<bool>,
+    /// Complete even though blocking policies have not passed
+    #[serde(default)]
+    pub bypass_policy: Option<bool>,
+    #[serde(default)]
+    pub bypass_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -546,7 +1281,193 @@ pub struct PRThread {
     #[serde(default)]
     pub comments: Vec<PRComment>,
     #[serde(default)]
-    pub thread_context: Option<serde_json::Value>,
+    pub thread_context: Option<PRThreadContext>,
+}
+
+/// File and line range a thread is anchored to. Left is the base side of the
+/// diff, right the changed side; lines are 1-based.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PRThreadContext {
+    pub file_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub left_file_start: Option<PRFilePosition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub left_file_end: Option<PRFilePosition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub right_file_start: Option<PRFilePosition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub right_file_end: Option<PRFilePosition>,
+}
+
+impl PRThreadContext {
+    /// Lines the context covers (`L12-14`, or `L7 (old)` on the base side)
+    pub fn line_label(&self) -> Option<String> {
+        let (start, end, side) = match (self.right_file_start, self.right_file_end) {
+            (Some(start), end) => (start, end.unwrap_or(start), ""),
+            (None, Some(end)) => (end, end, ""),
+            (None, None) => {
+                let start = self.left_file_start.or(self.left_file_end)?;
+                (start, self.left_file_end.unwrap_or(start), " (old)")
+            }
+        };
+        Some(if start.line == end.line {
+            format!("L{}{side}", start.line)
+        } else {
+            format!("L{}-{}{side}", start.line, end.line)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PRFilePosition {
+    pub line: u32,
+    /// 1-based character offset within the line
+    pub offset: u32,
+}
+
+/// Anchor for a new file comment: the lines plus the iterations and file
+/// change they were written against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PRCommentAnchor {
+    pub thread_context: PRThreadContext,
+    pub change_tracking_id: i32,
+    /// Base iteration of the compared range; equal to `second_iteration`
+    /// when comparing to the target branch
+    pub first_iteration: i32,
+    pub second_iteration: i32,
+}
+
+impl PRCommentAnchor {
+    /// Thread fields for the create-thread request body
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "threadContext": self.thread_context,
+            "pullRequestThreadContext": {
+                "changeTrackingId": self.change_tracking_id,
+                "iterationContext": {
+                    "firstComparingIteration": self.first_iteration,
+                    "secondComparingIteration": self.second_iteration,
+                }
+            }
+        })
+    }
+}
+
+/// A push to the PR source branch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PRIteration {
+    pub id: i32,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub created_date: Option<String>,
+    #[serde(default)]
+    pub author: Option<PRIdentityRef>,
+    #[serde(default)]
+    pub source_ref_commit: Option<GitCommitRef>,
+    #[serde(default)]
+    pub target_ref_commit: Option<GitCommitRef>,
+    /// Merge base of source and target
+    #[serde(default)]
+    pub common_ref_commit: Option<GitCommitRef>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommitRef {
+    pub commit_id: String,
+    #[serde(default)]
+    pub comment: String,
+}
+
+/// A file changed in a PR iteration
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PRChange {
+    pub path: String,
+    /// Previous path for renames
+    pub original_path: Option<String>,
+    /// `add`, `edit`, `delete`, `rename` or a combination (`edit, rename`)
+    pub change_type: String,
+    pub change_tracking_id: i32,
+}
+
+impl PRChange {
+    pub fn is_add(&self) -> bool {
+        self.change_type.contains("add")
+    }
+
+    pub fn is_delete(&self) -> bool {
+        self.change_type.contains("delete")
+    }
+
+    /// One-letter marker shown in the file list
+    pub fn marker(&self) -> &'static str {
+        if self.is_add() {
+            "A"
+        } else if self.is_delete() {
+            "D"
+        } else if self.change_type.contains("rename") {
+            "R"
+        } else {
+            "M"
+        }
+    }
+}
+
+/// Raw entry of the iteration changes API
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PRChangeEntry {
+    #[serde(default)]
+    pub change_tracking_id: i32,
+    #[serde(default)]
+    pub change_type: String,
+    #[serde(default)]
+    pub item: Option<PRChangeItem>,
+    #[serde(default)]
+    pub original_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PRChangeItem {
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub is_folder: bool,
+    #[serde(default)]
+    pub git_object_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PRChangesResponse {
+    #[serde(default)]
+    pub change_entries: Vec<PRChangeEntry>,
+}
+
+impl PRChangesResponse {
+    /// Changed files, skipping folder entries
+    pub fn files(self) -> Vec<PRChange> {
+        self.change_entries
+            .into_iter()
+            .filter_map(|entry| {
+                let item = entry.item?;
+                if item.is_folder || item.git_object_type.as_deref() == Some("tree") {
+                    return None;
+                }
+                Some(PRChange {
+                    path: item.path?,
+                    original_path: entry.original_path,
+                    change_type: entry.change_type,
+                    change_tracking_id: entry.change_tracking_id,
+                })
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -574,6 +1495,29 @@ pub struct PRPolicy {
     pub configuration: Option<PRPolicyConfig>,
 }
 
+impl PRPolicy {
+    pub fn display_name(&self) -> &str {
+        self.configuration
+            .as_ref()
+            .and_then(|c| c.policy_type.as_ref())
+            .and_then(|t| t.display_name.as_deref())
+            .unwrap_or("Unknown Policy")
+    }
+
+    /// Enabled blocking policy that has not (yet) passed
+    pub fn is_blocking_failure(&self) -> bool {
+        let required = self
+            .configuration
+            .as_ref()
+            .is_some_and(|c| c.is_blocking && c.is_enabled);
+        required
+            && !matches!(
+                self.status.as_deref(),
+                Some("approved") | Some("notApplicable")
+            )
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PRPolicyConfig {
@@ -653,6 +1597,22 @@ impl PullRequest {
     }
 }
 
+impl PRThread {
+    /// Whether the thread has user comments and hasn't been deleted
+    pub fn is_visible(&self) -> bool {
+        !self.is_deleted
+            && self
+                .comments
+                .iter()
+                .any(|c| c.comment_type.as_deref() != Some("system"))
+    }
+
+    /// File the thread is anchored to, if it's a file comment
+    pub fn file_path(&self) -> Option<&str> {
+        self.thread_context.as_ref().map(|c| c.file_path.as_str())
+    }
+}
+
 impl WorkItem {
     /// State icons - consistent progression from empty to filled to complete
     pub fn state_icon(&self) -> &'static str {
@@ -668,8 +1628,24 @@ impl WorkItem {
         }
     }
 
+    /// Work item types with a dedicated icon, in the order the create dialog offers them
+    pub const TYPES: &'static [&'static str] = &[
+        "Task",
+        "Bug",
+        "User Story",
+        "Product Backlog Item",
+        "Feature",
+        "Epic",
+        "Issue",
+        "Test Case",
+    ];
+
     pub fn type_icon(&self) -> &'static str {
-        match self.fields.work_item_type.as_str() {
+        Self::icon_for_type(&self.fields.work_item_type)
+    }
+
+    pub fn icon_for_type(work_item_type: &str) -> &'static str {
+        match work_item_type {
             "Bug" => "⊗",
             "User Story" => "◈",
             "Task" => "☑",
@@ -752,6 +1728,9 @@ mod tests {
                 remaining_work: None,
                 original_estimate: None,
                 completed_work: None,
+                area_path: None,
+                priority: None,
+                other: Default::default(),
             },
             relations: None,
             children: vec![],
@@ -935,4 +1914,162 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_changes_response_skips_folders() {
+        let json = r#"{
+            "changeEntries": [
+                { "changeTrackingId": 1, "changeType": "edit", "item": { "path": "/src/app.ts" } },
+                { "changeTrackingId": 2, "changeType": "add", "item": { "path": "/src", "isFolder": true } },
+                { "changeTrackingId": 3, "changeType": "rename", "item": { "path": "/b.ts" }, "originalPath": "/a.ts" }
+            ]
+        }"#;
+        let changes = serde_json::from_str::<PRChangesResponse>(json)
+            .unwrap()
+            .files();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].marker(), "M");
+        assert_eq!(changes[1].original_path.as_deref(), Some("/a.ts"));
+        assert_eq!(changes[1].marker(), "R");
+    }
+
+    #[test]
+    fn test_build_artifact_container() {
+        let json = r##"[
+            {"id": 1, "name": "drop", "resource": {"type": "Container", "data": "#/4242/drop",
+                "downloadUrl": "https://dev.azure.com/org/p/_apis/build/builds/7/artifacts?artifactName=drop&$format=zip"}},
+            {"id": 2, "name": "web", "resource": {"type": "PipelineArtifact", "data": "E1B2C3",
+                "properties": {"artifactsize": "5242880"}}}
+        ]"##;
+        let artifacts: Vec<BuildArtifact> = serde_json::from_str(json).unwrap();
+        assert_eq!(artifacts[0].container(), Some((4242, "drop")));
+        assert_eq!(artifacts[0].reported_size(), None);
+        assert_eq!(artifacts[1].container(), None);
+        assert_eq!(artifacts[1].reported_size(), Some(5_242_880));
+    }
+
+    #[test]
+    fn test_timeline_issues_parse() {
+        let record: TimelineRecord = serde_json::from_value(serde_json::json!({
+            "id": "task-test",
+            "type": "Task",
+            "errorCount": 1,
+            "warningCount": 0,
+            "issues": [{
+                "type": "error",
+                "category": "General",
+                "message": "Expected 1200",
+                "data": {
+                    "type": "error",
+                    "sourcepath": "src/checkout.test.ts",
+                    "linenumber": "42",
+                    "logFileLineNumber": 6
+                }
+            }]
+        }))
+        .unwrap();
+        assert_eq!(record.error_count, Some(1));
+        let issue = &record.issues.unwrap()[0];
+        assert_eq!(issue.issue_type.as_deref(), Some("error"));
+        assert_eq!(issue.source_path().as_deref(), Some("src/checkout.test.ts"));
+        assert_eq!(issue.line_number(), Some(42));
+        assert_eq!(issue.log_line(), Some(6));
+
+        // Older timelines have no issues at all
+        let record: TimelineRecord =
+            serde_json::from_value(serde_json::json!({"id": "job", "issues": null})).unwrap();
+        assert!(record.issues.is_none());
+    }
+
+    #[test]
+    fn test_thread_context_roundtrip() {
+        let json = r#"{
+            "filePath": "/src/app.ts",
+            "rightFileStart": { "line": 4, "offset": 1 },
+            "rightFileEnd": { "line": 6, "offset": 12 }
+        }"#;
+        let context: PRThreadContext = serde_json::from_str(json).unwrap();
+        assert_eq!(context.right_file_end.unwrap().line, 6);
+        assert!(context.left_file_start.is_none());
+
+        // Unset sides are left out of requests
+        let value = serde_json::to_value(&context).unwrap();
+        assert!(value.get("leftFileStart").is_none());
+    }
+    #[test]
+    fn test_field_patch_document_removes_cleared_fields() {
+        let doc = FieldUpdate::patch_document(&[
+            FieldUpdate::set(WorkItemFields::PRIORITY, 2),
+            FieldUpdate::clear(WorkItemFields::TAGS),
+        ]);
+        assert_eq!(
+            doc,
+            serde_json::json!([
+                { "op": "add", "path": "/fields/Microsoft.VSTS.Common.Priority", "value": 2 },
+                { "op": "remove", "path": "/fields/System.Tags" }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_link_patch_document() {
+        let org = "https://dev.azure.com/org/";
+        let related = WorkItemRelation::work_item(org, "System.LinkTypes.Related", 7);
+        let item: WorkItem = serde_json::from_value(serde_json::json!({
+            "id": 5,
+            "rev": 3,
+            "fields": { "System.Title": "Checkout", "System.State": "New", "System.WorkItemType": "Task" },
+            "relations": [
+                { "rel": "System.LinkTypes.Hierarchy-Reverse", "url": "https://dev.azure.com/org/_apis/wit/workItems/1" },
+                { "rel": "System.LinkTypes.Related", "url": "https://dev.azure.com/org/_apis/wit/WorkItems/7" }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(item.relations.as_ref().unwrap()[1].target_id(), Some(7));
+        assert!(item.relations.as_ref().unwrap()[1].same_link(&related));
+
+        // A new parent replaces the old one; removals go back to front
+        let parent = WorkItemRelation::work_item(org, WorkItemRelation::PARENT, 2);
+        let commit = WorkItemRelation::artifact(
+            "vstfs:///Git/Commit/p%2Fr%2Fabc".to_string(),
+            "Fixed in Commit",
+        );
+        let doc = WorkItemRelation::patch_document(
+            &item,
+            &[parent, commit],
+            std::slice::from_ref(&related),
+        )
+        .unwrap();
+        assert_eq!(
+            doc,
+            serde_json::json!([
+                { "op": "test", "path": "/rev", "value": 3 },
+                { "op": "remove", "path": "/relations/1" },
+                { "op": "remove", "path": "/relations/0" },
+                { "op": "add", "path": "/relations/-", "value": {
+                    "rel": "System.LinkTypes.Hierarchy-Reverse",
+                    "url": "https://dev.azure.com/org/_apis/wit/workItems/2"
+                } },
+                { "op": "add", "path": "/relations/-", "value": {
+                    "rel": "ArtifactLink",
+                    "url": "vstfs:///Git/Commit/p%2Fr%2Fabc",
+                    "attributes": { "name": "Fixed in Commit" }
+                } }
+            ])
+        );
+
+        assert!(
+            WorkItemRelation::patch_document(&item, std::slice::from_ref(&related), &[]).is_err()
+        );
+        let other = WorkItemRelation::work_item(org, "System.LinkTypes.Related", 8);
+        assert!(WorkItemRelation::patch_document(&item, &[], &[other]).is_err());
+        assert_eq!(
+            WorkItemRelation::reverse_rel("System.LinkTypes.Dependency-Forward"),
+            Some("System.LinkTypes.Dependency-Reverse")
+        );
+        assert_eq!(
+            WorkItemRelation::reverse_rel(WorkItemRelation::ARTIFACT),
+            None
+        );
+    }
 }
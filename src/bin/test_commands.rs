@@ -1,7 +1,7 @@
 //! Test binary to verify Azure CLI commands work correctly
 //! Run with: cargo run --bin test_commands
 
-use lazyops::azure::AzureCli;
+use lazyops::azure::{AzureCli, DevOpsBackend};
 use lazyops::config::Config;
 
 #[tokio::main]
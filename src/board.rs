@@ -0,0 +1,412 @@
+//! Layout of the Kanban board view: sprint items in the team's board columns
+//! and swimlanes, or one column per state when the team has no board
+
+use crate::app::VisibleWorkItem;
+use crate::azure::{Board, FieldUpdate, WorkItem, WorkItemFields};
+use std::collections::{BTreeMap, HashSet};
+
+/// Column of the board
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KanbanColumn {
+    pub name: String,
+    /// WIP limit, 0 for none
+    pub item_limit: u32,
+    /// State each work item type has in this column; empty for state columns
+    pub state_mappings: BTreeMap<String, String>,
+}
+
+impl KanbanColumn {
+    /// Whether the column holds more cards than its WIP limit allows
+    pub fn over_limit(&self, count: usize) -> bool {
+        self.item_limit > 0 && count > self.item_limit as usize
+    }
+}
+
+/// Columns and swimlanes the sprint items are laid out in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KanbanBoard {
+    /// Board name, e.g. `Stories`; `None` when the columns are states
+    pub name: Option<String>,
+    pub columns: Vec<KanbanColumn>,
+    /// Swimlane names; the default lane is `""`
+    pub lanes: Vec<String>,
+    /// Team-specific field storing the item's column (`WEF_..._Kanban.Column`)
+    pub column_field: Option<String>,
+}
+
+impl KanbanBoard {
+    /// Board of the team, as configured in Azure DevOps
+    pub fn from_board(board: &Board) -> Self {
+        let mut lanes: Vec<String> = board
+            .rows
+            .iter()
+            .map(|r| r.name.clone().unwrap_or_default())
+            .collect();
+        if !lanes.iter().any(String::is_empty) {
+            lanes.insert(0, String::new());
+        }
+        Self {
+            name: Some(board.name.clone()),
+            columns: board
+                .columns
+                .iter()
+                .map(|c| KanbanColumn {
+                    name: c.name.clone(),
+                    item_limit: c.item_limit,
+                    state_mappings: c.state_mappings.clone(),
+                })
+                .collect(),
+            lanes,
+            column_field: board
+                .fields
+                .column_field
+                .as_ref()
+                .map(|f| f.reference_name.clone()),
+        }
+    }
+
+    /// One column per configured state, plus any other state the items are in
+    pub fn from_states<'a>(states: &[&str], items: impl IntoIterator<Item = &'a WorkItem>) -> Self {
+        let mut names: Vec<String> = states
+            .iter()
+            .filter(|s| **s != "All")
+            .map(|s| s.to_string())
+            .collect();
+        for item in items {
+            if !names.contains(&item.fields.state) {
+                names.push(item.fields.state.clone());
+            }
+        }
+        Self {
+            name: None,
+            columns: names
+                .into_iter()
+                .map(|name| KanbanColumn {
+                    name,
+                    item_limit: 0,
+                    state_mappings: BTreeMap::new(),
+                })
+                .collect(),
+            lanes: vec![String::new()],
+            column_field: None,
+        }
+    }
+
+    /// The board covering most of the top-level items, else state columns
+    pub fn pick(boards: &[Board], states: &[&str], visible: &[VisibleWorkItem]) -> Self {
+        let covered = |board: &Board| {
+            visible
+                .iter()
+                .filter(|v| {
+                    board.columns.first().is_some_and(|c| {
+                        c.state_mappings.contains_key(&v.item.fields.work_item_type)
+                    })
+                })
+                .count()
+        };
+        boards
+            .iter()
+            .map(|b| (covered(b), b))
+            .filter(|(count, _)| *count > 0)
+            .max_by_key(|(count, _)| *count)
+            .map(|(_, board)| Self::from_board(board))
+            .unwrap_or_else(|| {
+                let top_level = visible.iter().filter(|v| v.depth == 0);
+                Self::from_states(states, top_level.map(|v| &v.item))
+            })
+    }
+
+    /// Whether the item is shown as a card: its type is on the board, or it
+    /// is top-level when the columns are states
+    pub fn shows(&self, item: &VisibleWorkItem) -> bool {
+        match self.columns.first() {
+            Some(column) if self.column_field.is_some() => column
+                .state_mappings
+                .contains_key(&item.item.fields.work_item_type),
+            _ => item.depth == 0,
+        }
+    }
+
+    /// Column the item is in: its board column, else the first column mapped
+    /// to its state
+    pub fn column_of(&self, item: &WorkItem) -> Option<usize> {
+        if self.column_field.is_none() {
+            return self
+                .columns
+                .iter()
+                .position(|c| c.name == item.fields.state);
+        }
+        let board_column = item.fields.get(WorkItemFields::BOARD_COLUMN);
+        if let Some(name) = board_column.as_ref().and_then(|v| v.as_str()) {
+            if let Some(idx) = self.columns.iter().position(|c| c.name == name) {
+                return Some(idx);
+            }
+        }
+        self.columns.iter().position(|c| {
+            c.state_mappings.get(&item.fields.work_item_type) == Some(&item.fields.state)
+        })
+    }
+
+    /// Swimlane the item is in; unknown lanes fall back to the default one
+    pub fn lane_of(&self, item: &WorkItem) -> usize {
+        let lane = item.fields.get(WorkItemFields::BOARD_LANE);
+        let name = lane.as_ref().and_then(|v| v.as_str()).unwrap_or_default();
+        self.lanes
+            .iter()
+            .position(|l| l == name)
+            .or_else(|| self.lanes.iter().position(String::is_empty))
+            .unwrap_or(0)
+    }
+
+    /// Cards of the visible items in each lane and column; collapsed lanes
+    /// stay empty but still count towards the WIP limits
+    pub fn layout(&self, visible: &[VisibleWorkItem], collapsed: &HashSet<String>) -> BoardLayout {
+        let mut cells = vec![vec![Vec::new(); self.columns.len()]; self.lanes.len()];
+        let mut counts = vec![0; self.columns.len()];
+        for (idx, entry) in visible.iter().enumerate() {
+            if !self.shows(entry) {
+                continue;
+            }
+            let Some(col) = self.column_of(&entry.item) else {
+                continue;
+            };
+            counts[col] += 1;
+            let lane = self.lane_of(&entry.item);
+            if !collapsed.contains(&self.lanes[lane]) {
+                cells[lane][col].push(idx);
+            }
+        }
+        BoardLayout { cells, counts }
+    }
+
+    /// Field updates moving the item into a column; the state follows the
+    /// column's mapping for the item's type
+    pub fn move_updates(&self, item: &WorkItem, col: usize) -> Vec<FieldUpdate> {
+        let Some(column) = self.columns.get(col) else {
+            return Vec::new();
+        };
+        let Some(field) = &self.column_field else {
+            return vec![FieldUpdate::set(
+                WorkItemFields::STATE,
+                column.name.as_str(),
+            )];
+        };
+        let mut updates = vec![FieldUpdate::set(field, column.name.as_str())];
+        match column.state_mappings.get(&item.fields.work_item_type) {
+            Some(state) if *state != item.fields.state => {
+                updates.push(FieldUpdate::set(WorkItemFields::STATE, state.as_str()))
+            }
+            _ => {}
+        }
+        updates
+    }
+}
+
+/// Visible-item indices placed on the board
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BoardLayout {
+    /// Cards by lane, then column
+    pub cells: Vec<Vec<Vec<usize>>>,
+    /// Cards per column across all lanes, for the WIP limits
+    pub counts: Vec<usize>,
+}
+
+impl BoardLayout {
+    /// Cards of a column, top to bottom through the expanded lanes
+    pub fn column_cards(&self, col: usize) -> Vec<usize> {
+        self.cells
+            .iter()
+            .flat_map(|lane| lane.get(col).into_iter().flatten().copied())
+            .collect()
+    }
+
+    /// Lane and column of a card
+    pub fn find(&self, idx: usize) -> Option<(usize, usize)> {
+        self.cells.iter().enumerate().find_map(|(lane, columns)| {
+            columns
+                .iter()
+                .position(|cards| cards.contains(&idx))
+                .map(|col| (lane, col))
+        })
+    }
+
+    /// First card, going column by column
+    pub fn first(&self) -> Option<usize> {
+        (0..self.counts.len()).find_map(|col| self.column_cards(col).first().copied())
+    }
+
+    /// Card `dy` rows down the column, or in the next non-empty column `dx`
+    /// columns over at about the same height
+    pub fn step(&self, current: Option<usize>, dx: isize, dy: isize) -> Option<usize> {
+        let Some((_, col)) = current.and_then(|idx| self.find(idx)) else {
+            return self.first();
+        };
+        let cards = self.column_cards(col);
+        let pos = cards.iter().position(|c| Some(*c) == current).unwrap_or(0);
+        if dx == 0 {
+            let target = pos.saturating_add_signed(dy).min(cards.len() - 1);
+            return Some(cards[target]);
+        }
+        let mut target = col as isize + dx.signum();
+        while target >= 0 && (target as usize) < self.counts.len() {
+            let cards = self.column_cards(target as usize);
+            if !cards.is_empty() {
+                return Some(cards[pos.min(cards.len() - 1)]);
+            }
+            target += dx.signum();
+        }
+        current
+    }
+
+    /// First or last card of the current card's column
+    pub fn edge(&self, current: Option<usize>, last: bool) -> Option<usize> {
+        let Some((_, col)) = current.and_then(|idx| self.find(idx)) else {
+            return self.first();
+        };
+        let cards = self.column_cards(col);
+        if last {
+            cards.last().copied()
+        } else {
+            cards.first().copied()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board() -> Board {
+        serde_json::from_value(serde_json::json!({
+            "id": "b1",
+            "name": "Stories",
+            "columns": [
+                {"name": "New", "stateMappings": {"User Story": "New", "Bug": "New"}},
+                {"name": "Active", "itemLimit": 1,
+                 "stateMappings": {"User Story": "Active", "Bug": "Active"}},
+                {"name": "Review", "itemLimit": 2,
+                 "stateMappings": {"User Story": "Active", "Bug": "Active"}},
+                {"name": "Closed", "stateMappings": {"User Story": "Closed", "Bug": "Closed"}}
+            ],
+            "rows": [{"name": null}, {"name": "Expedite"}],
+            "fields": {
+                "columnField": {"referenceName": "WEF_1_Kanban.Column"},
+                "rowField": {"referenceName": "WEF_1_Kanban.Lane"}
+            }
+        }))
+        .unwrap()
+    }
+
+    fn visible(id: i32, item_type: &str, state: &str, extra: serde_json::Value) -> VisibleWorkItem {
+        let mut fields = serde_json::json!({
+            "System.Title": format!("Item {id}"),
+            "System.State": state,
+            "System.WorkItemType": item_type
+        });
+        fields
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        VisibleWorkItem {
+            item: serde_json::from_value(serde_json::json!({"id": id, "rev": 1, "fields": fields}))
+                .unwrap(),
+            depth: if item_type == "Task" { 1 } else { 0 },
+            has_children: false,
+            is_expanded: false,
+            is_pinned: false,
+        }
+    }
+
+    fn items() -> Vec<VisibleWorkItem> {
+        vec![
+            visible(
+                1,
+                "User Story",
+                "Active",
+                serde_json::json!({"System.BoardColumn": "Active"}),
+            ),
+            visible(2, "Task", "Active", serde_json::json!({})),
+            visible(
+                3,
+                "User Story",
+                "Active",
+                serde_json::json!({"System.BoardColumn": "Active"}),
+            ),
+            visible(
+                4,
+                "Bug",
+                "New",
+                serde_json::json!({"System.BoardLane": "Expedite"}),
+            ),
+            visible(5, "User Story", "Closed", serde_json::json!({})),
+        ]
+    }
+
+    #[test]
+    fn test_layout_columns_lanes_and_limits() {
+        let board = KanbanBoard::pick(&[board()], &[], &items());
+        assert_eq!(board.name.as_deref(), Some("Stories"));
+        assert_eq!(board.lanes, vec!["".to_string(), "Expedite".to_string()]);
+
+        let layout = board.layout(&items(), &HashSet::new());
+        // Tasks aren't on the Stories board; unplaced items follow their state
+        assert_eq!(layout.cells[0], vec![vec![], vec![0, 2], vec![], vec![4]]);
+        assert_eq!(layout.cells[1], vec![vec![3], vec![], vec![], vec![]]);
+        assert_eq!(layout.counts, vec![1, 2, 0, 1]);
+        assert!(board.columns[1].over_limit(layout.counts[1]));
+        assert!(!board.columns[2].over_limit(layout.counts[2]));
+
+        // Collapsed lanes hide their cards but keep counting
+        let collapsed = HashSet::from(["Expedite".to_string()]);
+        let layout = board.layout(&items(), &collapsed);
+        assert!(layout.column_cards(0).is_empty());
+        assert_eq!(layout.counts[0], 1);
+    }
+
+    #[test]
+    fn test_navigation() {
+        let board = KanbanBoard::from_board(&board());
+        let layout = board.layout(&items(), &HashSet::new());
+        assert_eq!(layout.first(), Some(3));
+        assert_eq!(layout.step(None, 0, 1), Some(3));
+        // Empty columns are skipped
+        assert_eq!(layout.step(Some(3), 1, 0), Some(0));
+        assert_eq!(layout.step(Some(0), 0, 1), Some(2));
+        assert_eq!(layout.step(Some(2), 1, 0), Some(4));
+        assert_eq!(layout.step(Some(4), 1, 0), Some(4));
+        assert_eq!(layout.edge(Some(2), false), Some(0));
+        assert_eq!(layout.find(3), Some((1, 0)));
+    }
+
+    #[test]
+    fn test_move_updates() {
+        let board = KanbanBoard::from_board(&board());
+        let story = &items()[0].item;
+        // Review keeps the state, Closed changes it
+        assert_eq!(
+            board.move_updates(story, 2),
+            vec![FieldUpdate::set("WEF_1_Kanban.Column", "Review")]
+        );
+        assert_eq!(
+            board.move_updates(story, 3),
+            vec![
+                FieldUpdate::set("WEF_1_Kanban.Column", "Closed"),
+                FieldUpdate::set(WorkItemFields::STATE, "Closed")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_state_columns_fallback() {
+        let board = KanbanBoard::pick(&[], &["All", "New", "Active"], &items());
+        let names: Vec<&str> = board.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["New", "Active", "Closed"]);
+        let layout = board.layout(&items(), &HashSet::new());
+        // Child tasks stay with their parents
+        assert_eq!(layout.cells[0], vec![vec![3], vec![0, 2], vec![4]]);
+        assert_eq!(
+            board.move_updates(&items()[3].item, 1),
+            vec![FieldUpdate::set(WorkItemFields::STATE, "Active")]
+        );
+    }
+}
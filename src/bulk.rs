@@ -0,0 +1,206 @@
+//! Bulk updates applied to the marked work items
+
+use crate::azure::{FieldUpdate, User, WorkItem, WorkItemFields};
+
+/// Change applied to every target work item
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkOperation {
+    SetState(String),
+    Assign(User),
+    /// Tags to add and to remove, compared case-insensitively
+    Tags {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    MoveIteration {
+        path: String,
+        name: String,
+    },
+}
+
+impl BulkOperation {
+    /// Tag edit from `+tag -tag tag` input (bare words are added)
+    pub fn parse_tags(input: &str) -> Option<Self> {
+        let mut add = Vec::new();
+        let mut remove = Vec::new();
+        for word in input.split([',', ';', ' ']).filter(|w| !w.is_empty()) {
+            match word.strip_prefix('-') {
+                Some(tag) if !tag.is_empty() => remove.push(tag.to_string()),
+                Some(_) => {}
+                None => {
+                    let tag = word.trim_start_matches('+');
+                    if !tag.is_empty() {
+                        add.push(tag.to_string());
+                    }
+                }
+            }
+        }
+        (!add.is_empty() || !remove.is_empty()).then_some(BulkOperation::Tags { add, remove })
+    }
+
+    /// Field updates for one item, or nothing when it already matches
+    pub fn updates_for(&self, item: &WorkItem) -> Vec<FieldUpdate> {
+        match self {
+            BulkOperation::SetState(state) => {
+                vec![FieldUpdate::set(WorkItemFields::STATE, state.as_str())]
+            }
+            BulkOperation::Assign(user) => vec![FieldUpdate::set(
+                WorkItemFields::ASSIGNED_TO,
+                user.unique_name.as_str(),
+            )],
+            BulkOperation::Tags { add, remove } => {
+                let current: Vec<&str> = item
+                    .fields
+                    .tags
+                    .as_deref()
+                    .unwrap_or_default()
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .collect();
+                let mut tags: Vec<&str> = current
+                    .iter()
+                    .copied()
+                    .filter(|t| !remove.iter().any(|r| r.eq_ignore_ascii_case(t)))
+                    .collect();
+                for tag in add {
+                    if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                        tags.push(tag);
+                    }
+                }
+                if tags == current {
+                    Vec::new()
+                } else if tags.is_empty() {
+                    vec![FieldUpdate::clear(WorkItemFields::TAGS)]
+                } else {
+                    vec![FieldUpdate::set(WorkItemFields::TAGS, tags.join("; "))]
+                }
+            }
+            BulkOperation::MoveIteration { path, .. } => {
+                vec![FieldUpdate::set(
+                    WorkItemFields::ITERATION_PATH,
+                    path.as_str(),
+                )]
+            }
+        }
+    }
+
+    /// What was done, e.g. `Moved 12 items to Sprint 43`
+    pub fn describe(&self, count: usize) -> String {
+        let items = if count == 1 { "item" } else { "items" };
+        match self {
+            BulkOperation::SetState(state) => format!("Set {count} {items} to {state}"),
+            BulkOperation::Assign(user) => {
+                format!("Assigned {count} {items} to {}", user.display_name)
+            }
+            BulkOperation::Tags { add, remove } => {
+                let changes: Vec<String> = add
+                    .iter()
+                    .map(|t| format!("+{t}"))
+                    .chain(remove.iter().map(|t| format!("-{t}")))
+                    .collect();
+                format!("Tagged {count} {items} {}", changes.join(" "))
+            }
+            BulkOperation::MoveIteration { name, .. } => {
+                format!("Moved {count} {items} to {name}")
+            }
+        }
+    }
+}
+
+/// Result of a bulk update, sent back from the background task
+#[derive(Debug)]
+pub struct BulkOutcome {
+    pub operation: BulkOperation,
+    /// Updated items, as returned by the server
+    pub updated: Vec<WorkItem>,
+    /// Items that needed no change
+    pub unchanged: usize,
+    pub failed: Vec<(i32, String)>,
+}
+
+impl BulkOutcome {
+    /// One status line for the whole run
+    pub fn summary(&self) -> String {
+        let done = self.operation.describe(self.updated.len() + self.unchanged);
+        if self.failed.is_empty() {
+            return done;
+        }
+        let failures: Vec<String> = self
+            .failed
+            .iter()
+            .map(|(id, error)| format!("#{id}: {error}"))
+            .collect();
+        format!(
+            "{done}; {} failed (still marked) - {}",
+            self.failed.len(),
+            failures.join("; ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(tags: Option<&str>) -> WorkItem {
+        serde_json::from_value(serde_json::json!({
+            "id": 1001,
+            "rev": 1,
+            "fields": {
+                "System.Title": "Checkout flow",
+                "System.State": "Active",
+                "System.WorkItemType": "User Story",
+                "System.Tags": tags
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_tag_edits() {
+        let op = BulkOperation::parse_tags("+urgent -UX, triage").unwrap();
+        assert_eq!(
+            op,
+            BulkOperation::Tags {
+                add: vec!["urgent".into(), "triage".into()],
+                remove: vec!["UX".into()]
+            }
+        );
+        assert_eq!(
+            op.updates_for(&item(Some("frontend; ux"))),
+            vec![FieldUpdate::set(
+                WorkItemFields::TAGS,
+                "frontend; urgent; triage"
+            )]
+        );
+
+        // Nothing to do when the tags already match
+        let op = BulkOperation::parse_tags("frontend -later").unwrap();
+        assert!(op.updates_for(&item(Some("frontend"))).is_empty());
+        // Removing the last tag clears the field
+        let op = BulkOperation::parse_tags("-frontend").unwrap();
+        assert_eq!(
+            op.updates_for(&item(Some("frontend"))),
+            vec![FieldUpdate::clear(WorkItemFields::TAGS)]
+        );
+        assert!(BulkOperation::parse_tags(" - + ").is_none());
+    }
+
+    #[test]
+    fn test_summary_reports_failures() {
+        let outcome = BulkOutcome {
+            operation: BulkOperation::MoveIteration {
+                path: "Demo\\Sprint 43".into(),
+                name: "Sprint 43".into(),
+            },
+            updated: vec![item(None)],
+            unchanged: 0,
+            failed: vec![(1002, "TF401320: rule error".into())],
+        };
+        assert_eq!(
+            outcome.summary(),
+            "Moved 1 item to Sprint 43; 1 failed (still marked) - #1002: TF401320: rule error"
+        );
+    }
+}
@@ -0,0 +1,364 @@
+//! Sprint analytics: progress by state, scope added after the sprint started,
+//! and a burndown of remaining work rebuilt from work item revisions
+
+use crate::azure::{Sprint, WorkItem};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::HashMap;
+
+/// States that count as finished work
+const DONE_STATES: &[&str] = &["Done", "Closed", "Resolved", "Completed"];
+
+/// States of work taken out of the sprint; left out of the totals
+const REMOVED_STATES: &[&str] = &["Removed", "Cut"];
+
+pub fn is_done(state: &str) -> bool {
+    DONE_STATES.contains(&state)
+}
+
+pub fn is_removed(state: &str) -> bool {
+    REMOVED_STATES.contains(&state)
+}
+
+/// Snapshot of the sprint's work items as they are now
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SprintProgress {
+    /// Work items, not counting removed ones
+    pub total: usize,
+    pub done: usize,
+    /// Item count per state, most common first
+    pub by_state: Vec<(String, usize)>,
+    pub remaining_work: f64,
+    pub original_estimate: f64,
+    pub completed_work: f64,
+}
+
+impl SprintProgress {
+    /// Tally a work item hierarchy, children included
+    pub fn from_items(items: &[WorkItem]) -> Self {
+        fn walk(items: &[WorkItem], progress: &mut SprintProgress) {
+            for item in items {
+                let fields = &item.fields;
+                match progress
+                    .by_state
+                    .iter_mut()
+                    .find(|(s, _)| *s == fields.state)
+                {
+                    Some((_, count)) => *count += 1,
+                    None => progress.by_state.push((fields.state.clone(), 1)),
+                }
+                if !is_removed(&fields.state) {
+                    progress.total += 1;
+                    progress.done += usize::from(is_done(&fields.state));
+                    progress.remaining_work += fields.remaining_work.unwrap_or(0.0);
+                    progress.original_estimate += fields.original_estimate.unwrap_or(0.0);
+                    progress.completed_work += fields.completed_work.unwrap_or(0.0);
+                }
+                walk(&item.children, progress);
+            }
+        }
+
+        let mut progress = Self::default();
+        walk(items, &mut progress);
+        // Stable, so equal counts keep the order the states were first seen in
+        progress
+            .by_state
+            .sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        progress
+    }
+
+    /// Share of the items that are done, 0.0 - 1.0
+    pub fn ratio(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.done as f64 / self.total as f64
+        }
+    }
+
+    pub fn percent(&self) -> u16 {
+        (self.ratio() * 100.0).round() as u16
+    }
+}
+
+/// What the burndown counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BurndownUnit {
+    /// Remaining Work of the open items
+    Hours,
+    /// Open items, when nothing in the sprint is estimated
+    Items,
+}
+
+impl BurndownUnit {
+    pub fn label(self) -> &'static str {
+        match self {
+            BurndownUnit::Hours => "hours",
+            BurndownUnit::Items => "items",
+        }
+    }
+}
+
+/// Remaining work per sprint day, rebuilt from the items' revision history
+#[derive(Debug, Clone, PartialEq)]
+pub struct Burndown {
+    /// Iteration path of the sprint this was built for
+    pub sprint_path: String,
+    pub unit: BurndownUnit,
+    pub start: NaiveDate,
+    pub finish: NaiveDate,
+    /// Remaining at the end of each day from the start up to today
+    pub actual: Vec<f64>,
+    /// Items that joined the sprint after its first day, in ID order
+    pub added: Vec<i32>,
+}
+
+impl Burndown {
+    /// Replay the revisions of the sprint's items (oldest first per item) day
+    /// by day. `None` when the sprint has no dates.
+    pub fn build(
+        sprint: &Sprint,
+        revisions: &HashMap<i32, Vec<WorkItem>>,
+        today: NaiveDate,
+    ) -> Option<Self> {
+        let start = sprint.attributes.start_date?.date_naive();
+        let finish = sprint.attributes.finish_date?.date_naive().max(start);
+        let in_sprint = |rev: &WorkItem| rev.fields.iteration_path.as_deref() == Some(&sprint.path);
+
+        let unit = if revisions
+            .values()
+            .flatten()
+            .any(|rev| in_sprint(rev) && rev.fields.remaining_work.is_some())
+        {
+            BurndownUnit::Hours
+        } else {
+            BurndownUnit::Items
+        };
+        let remaining = |rev: &WorkItem| {
+            if !in_sprint(rev) || is_done(&rev.fields.state) || is_removed(&rev.fields.state) {
+                0.0
+            } else {
+                match unit {
+                    BurndownUnit::Hours => rev.fields.remaining_work.unwrap_or(0.0),
+                    BurndownUnit::Items => 1.0,
+                }
+            }
+        };
+
+        let last_day = finish.min(today);
+        let actual = start
+            .iter_days()
+            .take_while(|day| *day <= last_day)
+            .map(|day| {
+                let end_of_day = end_of_day(day);
+                revisions
+                    .values()
+                    .filter_map(|revs| {
+                        revs.iter()
+                            .take_while(|rev| changed_at(rev) <= end_of_day)
+                            .last()
+                    })
+                    .map(remaining)
+                    .sum()
+            })
+            .collect();
+
+        let first_day_end = end_of_day(start);
+        let mut added: Vec<i32> = revisions
+            .iter()
+            .filter(|(_, revs)| {
+                revs.iter()
+                    .find(|rev| in_sprint(rev))
+                    .is_some_and(|rev| changed_at(rev) > first_day_end)
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        added.sort_unstable();
+
+        Some(Self {
+            sprint_path: sprint.path.clone(),
+            unit,
+            start,
+            finish,
+            actual,
+            added,
+        })
+    }
+
+    /// Days in the sprint, first and last included
+    pub fn days(&self) -> usize {
+        (self.finish - self.start).num_days() as usize + 1
+    }
+
+    /// Ideal trend: a straight line from the first day's remaining work to
+    /// zero on the last day
+    pub fn ideal(&self, day: usize) -> f64 {
+        let start = self.actual.first().copied().unwrap_or(0.0);
+        let span = self.days().saturating_sub(1).max(1) as f64;
+        (start * (1.0 - day as f64 / span)).max(0.0)
+    }
+
+    /// Highest value on either line, for the chart's scale
+    pub fn max_value(&self) -> f64 {
+        self.actual
+            .iter()
+            .copied()
+            .chain([self.ideal(0)])
+            .fold(0.0, f64::max)
+    }
+}
+
+/// When a revision was made; revisions without a date count as the oldest
+fn changed_at(rev: &WorkItem) -> DateTime<Utc> {
+    rev.fields.changed_date.unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+fn end_of_day(day: NaiveDate) -> DateTime<Utc> {
+    day.succ_opt()
+        .unwrap_or(day)
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sprint() -> Sprint {
+        serde_json::from_value(serde_json::json!({
+            "id": "s1",
+            "name": "Sprint 1",
+            "path": "P\\Sprint 1",
+            "attributes": {
+                "startDate": "2026-09-28T00:00:00Z",
+                "finishDate": "2026-10-02T00:00:00Z"
+            }
+        }))
+        .unwrap()
+    }
+
+    fn rev(id: i32, changed: &str, state: &str, path: &str, remaining: Option<f64>) -> WorkItem {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "rev": 1,
+            "fields": {
+                "System.Title": format!("Item {id}"),
+                "System.State": state,
+                "System.WorkItemType": "Task",
+                "System.IterationPath": path,
+                "System.ChangedDate": changed,
+                "Microsoft.VSTS.Scheduling.RemainingWork": remaining
+            }
+        }))
+        .unwrap()
+    }
+
+    fn day(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_progress_counts_states_and_work() {
+        let mut story = rev(1, "2026-09-28T09:00:00Z", "Active", "P\\Sprint 1", None);
+        story.children = vec![
+            rev(2, "2026-09-28T09:00:00Z", "Done", "P\\Sprint 1", Some(0.0)),
+            rev(
+                3,
+                "2026-09-28T09:00:00Z",
+                "Active",
+                "P\\Sprint 1",
+                Some(4.0),
+            ),
+            rev(
+                4,
+                "2026-09-28T09:00:00Z",
+                "Removed",
+                "P\\Sprint 1",
+                Some(8.0),
+            ),
+        ];
+        let progress = SprintProgress::from_items(&[story]);
+        assert_eq!(progress.total, 3);
+        assert_eq!(progress.done, 1);
+        assert_eq!(progress.percent(), 33);
+        assert_eq!(progress.remaining_work, 4.0);
+        assert_eq!(
+            progress.by_state,
+            vec![
+                ("Active".to_string(), 2),
+                ("Done".to_string(), 1),
+                ("Removed".to_string(), 1)
+            ]
+        );
+        assert_eq!(SprintProgress::default().percent(), 0);
+    }
+
+    #[test]
+    fn test_burndown_replays_revisions_per_day() {
+        let path = "P\\Sprint 1";
+        let revisions = HashMap::from([
+            (
+                1,
+                vec![
+                    rev(1, "2026-09-28T09:00:00Z", "New", path, Some(8.0)),
+                    rev(1, "2026-09-29T17:00:00Z", "Active", path, Some(5.0)),
+                    rev(1, "2026-09-30T12:00:00Z", "Done", path, Some(2.0)),
+                ],
+            ),
+            (
+                // Planned for the sprint before it started
+                2,
+                vec![
+                    rev(2, "2026-09-20T09:00:00Z", "New", "P", Some(4.0)),
+                    rev(2, "2026-09-25T09:00:00Z", "New", path, Some(4.0)),
+                ],
+            ),
+            (
+                // Added on day three, then moved out on day four
+                3,
+                vec![
+                    rev(3, "2026-09-30T10:00:00Z", "New", path, Some(3.0)),
+                    rev(3, "2026-10-01T10:00:00Z", "New", "P\\Sprint 2", Some(3.0)),
+                ],
+            ),
+        ]);
+
+        let burndown = Burndown::build(&sprint(), &revisions, day("2026-10-01")).unwrap();
+        assert_eq!(burndown.unit, BurndownUnit::Hours);
+        assert_eq!(burndown.days(), 5);
+        // Done counts as no remaining work; today is the last day shown
+        assert_eq!(burndown.actual, vec![12.0, 9.0, 7.0, 4.0]);
+        assert_eq!(burndown.added, vec![3]);
+        assert_eq!(burndown.ideal(0), 12.0);
+        assert_eq!(burndown.ideal(2), 6.0);
+        assert_eq!(burndown.ideal(4), 0.0);
+        assert_eq!(burndown.max_value(), 12.0);
+
+        // Before the sprint starts there is nothing to plot yet
+        let early = Burndown::build(&sprint(), &revisions, day("2026-09-01")).unwrap();
+        assert!(early.actual.is_empty());
+    }
+
+    #[test]
+    fn test_burndown_counts_items_without_estimates() {
+        let path = "P\\Sprint 1";
+        let revisions = HashMap::from([
+            (1, vec![rev(1, "2026-09-27T09:00:00Z", "New", path, None)]),
+            (
+                2,
+                vec![
+                    rev(2, "2026-09-27T09:00:00Z", "New", path, None),
+                    rev(2, "2026-09-29T09:00:00Z", "Closed", path, None),
+                ],
+            ),
+        ]);
+        let burndown = Burndown::build(&sprint(), &revisions, day("2026-12-01")).unwrap();
+        assert_eq!(burndown.unit, BurndownUnit::Items);
+        assert_eq!(burndown.actual, vec![2.0, 1.0, 1.0, 1.0, 1.0]);
+        assert!(burndown.added.is_empty());
+
+        let mut undated = sprint();
+        undated.attributes.start_date = None;
+        assert!(Burndown::build(&undated, &revisions, day("2026-12-01")).is_none());
+    }
+}
@@ -1,14 +1,17 @@
 use crate::azure::{
-    Pipeline, PipelineRun, PullRequest, Release, ReleaseDefinition, Repository, Sprint,
-    TimelineRecord, User, WorkItem,
+    Pipeline, PipelineRun, PipelineRunRequest, PullRequest, Release, ReleaseDefinition, Repository,
+    Sprint, TimelineRecord, User, WorkItem, WorkItemRelation,
 };
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
+use tempfile::TempDir;
 
-/// Default CI/CD cache TTL: 10 minutes (600 seconds)
+/// Upper bound for CI/CD cache TTL: 10 minutes (600 seconds), since run and
+/// release status changes much faster than work items
 pub const CICD_CACHE_TTL_SECS: u64 = 600;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,6 +27,10 @@ pub struct CacheEntry {
     pub filter_assignee: Option<String>,
     #[serde(default)]
     pub pinned_items: HashSet<i32>,
+    /// Relations of the work items loaded so far, so References is filled
+    /// right away on the next start
+    #[serde(default)]
+    pub relations: HashMap<i32, Vec<WorkItemRelation>>,
 }
 
 impl CacheEntry {
@@ -48,6 +55,7 @@ impl CacheEntry {
             filter_state,
             filter_assignee,
             pinned_items,
+            relations: HashMap::new(),
         }
     }
 
@@ -59,44 +67,82 @@ impl CacheEntry {
     }
 }
 
-fn cache_dir() -> Option<PathBuf> {
-    dirs::cache_dir().map(|d| d.join("lazyops"))
+/// Directory the caches are kept in
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: Option<PathBuf>,
+    /// Removes a temporary directory once the last handle is dropped
+    _temp: Option<Arc<TempDir>>,
 }
 
-pub fn save_last_project(name: &str) -> Result<()> {
-    let dir = cache_dir().ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
-    std::fs::create_dir_all(&dir)?;
-    let path = dir.join("last_project.txt");
-    std::fs::write(path, name)?;
-    Ok(())
-}
+impl Cache {
+    /// Caches in `dir`, by default `lazyops` in the user cache directory
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir: dir.or_else(|| dirs::cache_dir().map(|d| d.join("lazyops"))),
+            _temp: None,
+        }
+    }
 
-pub fn load_last_project() -> Option<String> {
-    let path = cache_dir()?.join("last_project.txt");
-    std::fs::read_to_string(path)
-        .ok()
-        .map(|s| s.trim().to_string())
-}
+    /// Caches in a fresh temporary directory, for demo mode and tests
+    pub fn temporary() -> Self {
+        match TempDir::with_prefix("lazyops-") {
+            Ok(temp) => Self {
+                dir: Some(temp.path().to_path_buf()),
+                _temp: Some(Arc::new(temp)),
+            },
+            // Without a temporary directory nothing is cached
+            Err(_) => Self {
+                dir: None,
+                _temp: None,
+            },
+        }
+    }
 
-pub fn save_last_repo(project: &str, repo_name: &str) -> Result<()> {
-    let dir = cache_dir().ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
-    std::fs::create_dir_all(&dir)?;
-    let sanitized = sanitize_filename(project);
-    let path = dir.join(format!("{sanitized}_last_repo.txt"));
-    std::fs::write(path, repo_name)?;
-    Ok(())
-}
+    fn cache_dir(&self) -> Option<PathBuf> {
+        self.dir.clone()
+    }
 
-pub fn load_last_repo(project: &str) -> Option<String> {
-    let sanitized = sanitize_filename(project);
-    let path = cache_dir()?.join(format!("{sanitized}_last_repo.txt"));
-    std::fs::read_to_string(path)
-        .ok()
-        .map(|s| s.trim().to_string())
-}
+    pub fn save_last_project(&self, name: &str) -> Result<()> {
+        let dir = self
+            .cache_dir()
+            .ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join("last_project.txt");
+        std::fs::write(path, name)?;
+        Ok(())
+    }
+
+    pub fn load_last_project(&self) -> Option<String> {
+        let path = self.cache_dir()?.join("last_project.txt");
+        std::fs::read_to_string(path)
+            .ok()
+            .map(|s| s.trim().to_string())
+    }
+
+    pub fn save_last_repo(&self, project: &str, repo_name: &str) -> Result<()> {
+        let dir = self
+            .cache_dir()
+            .ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
+        std::fs::create_dir_all(&dir)?;
+        let sanitized = sanitize_filename(project);
+        let path = dir.join(format!("{sanitized}_last_repo.txt"));
+        std::fs::write(path, repo_name)?;
+        Ok(())
+    }
+
+    pub fn load_last_repo(&self, project: &str) -> Option<String> {
+        let sanitized = sanitize_filename(project);
+        let path = self.cache_dir()?.join(format!("{sanitized}_last_repo.txt"));
+        std::fs::read_to_string(path)
+            .ok()
+            .map(|s| s.trim().to_string())
+    }
 
-fn cache_path(project: &str) -> Option<PathBuf> {
-    cache_dir().map(|d| d.join(format!("{}.json", sanitize_filename(project))))
+    fn cache_path(&self, project: &str) -> Option<PathBuf> {
+        self.cache_dir()
+            .map(|d| d.join(format!("{}.json", sanitize_filename(project))))
+    }
 }
 
 fn sanitize_filename(s: &str) -> String {
@@ -111,19 +157,25 @@ fn sanitize_filename(s: &str) -> String {
         .collect()
 }
 
-pub fn load(project: &str) -> Option<CacheEntry> {
-    let path = cache_path(project)?;
-    let contents = std::fs::read_to_string(&path).ok()?;
-    serde_json::from_str(&contents).ok()
-}
+impl Cache {
+    pub fn load(&self, project: &str) -> Option<CacheEntry> {
+        let path = self.cache_path(project)?;
+        let contents = std::fs::read_to_string(&path).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
 
-pub fn save(project: &str, entry: &CacheEntry) -> Result<()> {
-    let dir = cache_dir().ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
-    std::fs::create_dir_all(&dir)?;
-    let path = cache_path(project).ok_or_else(|| anyhow::anyhow!("No cache path"))?;
-    let contents = serde_json::to_string_pretty(entry)?;
-    std::fs::write(path, contents)?;
-    Ok(())
+    pub fn save(&self, project: &str, entry: &CacheEntry) -> Result<()> {
+        let dir = self
+            .cache_dir()
+            .ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
+        std::fs::create_dir_all(&dir)?;
+        let path = self
+            .cache_path(project)
+            .ok_or_else(|| anyhow::anyhow!("No cache path"))?;
+        let contents = serde_json::to_string_pretty(entry)?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
 }
 
 // ============================================
@@ -174,30 +226,37 @@ impl CICDCacheEntry {
     }
 }
 
-fn cicd_cache_path(project: &str) -> Option<PathBuf> {
-    let sanitized = sanitize_filename(project);
-    cache_dir().map(|d| d.join(format!("{sanitized}_cicd.json")))
-}
+impl Cache {
+    fn cicd_cache_path(&self, project: &str) -> Option<PathBuf> {
+        let sanitized = sanitize_filename(project);
+        self.cache_dir()
+            .map(|d| d.join(format!("{sanitized}_cicd.json")))
+    }
 
-pub fn load_cicd(project: &str) -> Option<CICDCacheEntry> {
-    let path = cicd_cache_path(project)?;
-    let contents = std::fs::read_to_string(&path).ok()?;
-    let entry: CICDCacheEntry = serde_json::from_str(&contents).ok()?;
-    // Only return if cache is still valid
-    if entry.is_valid() {
-        Some(entry)
-    } else {
-        None
+    pub fn load_cicd(&self, project: &str) -> Option<CICDCacheEntry> {
+        let path = self.cicd_cache_path(project)?;
+        let contents = std::fs::read_to_string(&path).ok()?;
+        let entry: CICDCacheEntry = serde_json::from_str(&contents).ok()?;
+        // Only return if cache is still valid
+        if entry.is_valid() {
+            Some(entry)
+        } else {
+            None
+        }
     }
-}
 
-pub fn save_cicd(project: &str, entry: &CICDCacheEntry) -> Result<()> {
-    let dir = cache_dir().ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
-    std::fs::create_dir_all(&dir)?;
-    let path = cicd_cache_path(project).ok_or_else(|| anyhow::anyhow!("No cache path"))?;
-    let contents = serde_json::to_string_pretty(entry)?;
-    std::fs::write(path, contents)?;
-    Ok(())
+    pub fn save_cicd(&self, project: &str, entry: &CICDCacheEntry) -> Result<()> {
+        let dir = self
+            .cache_dir()
+            .ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
+        std::fs::create_dir_all(&dir)?;
+        let path = self
+            .cicd_cache_path(project)
+            .ok_or_else(|| anyhow::anyhow!("No cache path"))?;
+        let contents = serde_json::to_string_pretty(entry)?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
 }
 
 // ============================================
@@ -236,37 +295,45 @@ impl PipelineRunsCacheEntry {
         true
     }
 
-    /// Check if cache should be refreshed in background
-    pub fn needs_refresh(&self) -> bool {
-        self.age_seconds() >= CICD_CACHE_TTL_SECS
+    /// Check if cache is older than `ttl_secs` and should be refreshed in background
+    pub fn needs_refresh(&self, ttl_secs: u64) -> bool {
+        self.age_seconds() >= ttl_secs
     }
 }
 
-fn pipeline_runs_cache_path(project: &str, pipeline_id: i32) -> Option<PathBuf> {
-    let sanitized = sanitize_filename(project);
-    cache_dir().map(|d| d.join(format!("{sanitized}_pipeline_{pipeline_id}_runs.json")))
-}
+impl Cache {
+    fn pipeline_runs_cache_path(&self, project: &str, pipeline_id: i32) -> Option<PathBuf> {
+        let sanitized = sanitize_filename(project);
+        self.cache_dir()
+            .map(|d| d.join(format!("{sanitized}_pipeline_{pipeline_id}_runs.json")))
+    }
 
-/// Load pipeline runs cache. Returns (entry, needs_refresh) if cache exists.
-pub fn load_pipeline_runs(
-    project: &str,
-    pipeline_id: i32,
-) -> Option<(PipelineRunsCacheEntry, bool)> {
-    let path = pipeline_runs_cache_path(project, pipeline_id)?;
-    let contents = std::fs::read_to_string(&path).ok()?;
-    let entry: PipelineRunsCacheEntry = serde_json::from_str(&contents).ok()?;
-    let needs_refresh = entry.needs_refresh();
-    Some((entry, needs_refresh))
-}
+    /// Load pipeline runs cache. Returns (entry, needs_refresh) if cache exists.
+    pub fn load_pipeline_runs(
+        &self,
+        project: &str,
+        pipeline_id: i32,
+        ttl_secs: u64,
+    ) -> Option<(PipelineRunsCacheEntry, bool)> {
+        let path = self.pipeline_runs_cache_path(project, pipeline_id)?;
+        let contents = std::fs::read_to_string(&path).ok()?;
+        let entry: PipelineRunsCacheEntry = serde_json::from_str(&contents).ok()?;
+        let needs_refresh = entry.needs_refresh(ttl_secs);
+        Some((entry, needs_refresh))
+    }
 
-pub fn save_pipeline_runs(project: &str, entry: &PipelineRunsCacheEntry) -> Result<()> {
-    let dir = cache_dir().ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
-    std::fs::create_dir_all(&dir)?;
-    let path = pipeline_runs_cache_path(project, entry.pipeline_id)
-        .ok_or_else(|| anyhow::anyhow!("No cache path"))?;
-    let contents = serde_json::to_string_pretty(entry)?;
-    std::fs::write(path, contents)?;
-    Ok(())
+    pub fn save_pipeline_runs(&self, project: &str, entry: &PipelineRunsCacheEntry) -> Result<()> {
+        let dir = self
+            .cache_dir()
+            .ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
+        std::fs::create_dir_all(&dir)?;
+        let path = self
+            .pipeline_runs_cache_path(project, entry.pipeline_id)
+            .ok_or_else(|| anyhow::anyhow!("No cache path"))?;
+        let contents = serde_json::to_string_pretty(entry)?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
 }
 
 // ============================================
@@ -305,34 +372,86 @@ impl ReleasesCacheEntry {
         true
     }
 
-    /// Check if cache should be refreshed in background
-    pub fn needs_refresh(&self) -> bool {
-        self.age_seconds() >= CICD_CACHE_TTL_SECS
+    /// Check if cache is older than `ttl_secs` and should be refreshed in background
+    pub fn needs_refresh(&self, ttl_secs: u64) -> bool {
+        self.age_seconds() >= ttl_secs
     }
 }
 
-fn releases_cache_path(project: &str, definition_id: i32) -> Option<PathBuf> {
-    let sanitized = sanitize_filename(project);
-    cache_dir().map(|d| d.join(format!("{sanitized}_release_def_{definition_id}.json")))
-}
+impl Cache {
+    fn releases_cache_path(&self, project: &str, definition_id: i32) -> Option<PathBuf> {
+        let sanitized = sanitize_filename(project);
+        self.cache_dir()
+            .map(|d| d.join(format!("{sanitized}_release_def_{definition_id}.json")))
+    }
+
+    /// Load releases cache. Returns (entry, needs_refresh) if cache exists.
+    pub fn load_releases(
+        &self,
+        project: &str,
+        definition_id: i32,
+        ttl_secs: u64,
+    ) -> Option<(ReleasesCacheEntry, bool)> {
+        let path = self.releases_cache_path(project, definition_id)?;
+        let contents = std::fs::read_to_string(&path).ok()?;
+        let entry: ReleasesCacheEntry = serde_json::from_str(&contents).ok()?;
+        let needs_refresh = entry.needs_refresh(ttl_secs);
+        Some((entry, needs_refresh))
+    }
 
-/// Load releases cache. Returns (entry, needs_refresh) if cache exists.
-pub fn load_releases(project: &str, definition_id: i32) -> Option<(ReleasesCacheEntry, bool)> {
-    let path = releases_cache_path(project, definition_id)?;
-    let contents = std::fs::read_to_string(&path).ok()?;
-    let entry: ReleasesCacheEntry = serde_json::from_str(&contents).ok()?;
-    let needs_refresh = entry.needs_refresh();
-    Some((entry, needs_refresh))
+    pub fn save_releases(&self, project: &str, entry: &ReleasesCacheEntry) -> Result<()> {
+        let dir = self
+            .cache_dir()
+            .ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
+        std::fs::create_dir_all(&dir)?;
+        let path = self
+            .releases_cache_path(project, entry.definition_id)
+            .ok_or_else(|| anyhow::anyhow!("No cache path"))?;
+        let contents = serde_json::to_string_pretty(entry)?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
 }
 
-pub fn save_releases(project: &str, entry: &ReleasesCacheEntry) -> Result<()> {
-    let dir = cache_dir().ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
-    std::fs::create_dir_all(&dir)?;
-    let path = releases_cache_path(project, entry.definition_id)
-        .ok_or_else(|| anyhow::anyhow!("No cache path"))?;
-    let contents = serde_json::to_string_pretty(entry)?;
-    std::fs::write(path, contents)?;
-    Ok(())
+// ============================================
+// Pipeline Trigger Presets (per pipeline)
+// ============================================
+
+impl Cache {
+    fn trigger_preset_path(&self, project: &str, pipeline_id: i32) -> Option<PathBuf> {
+        let sanitized = sanitize_filename(project);
+        self.cache_dir()
+            .map(|d| d.join(format!("{sanitized}_pipeline_{pipeline_id}_preset.json")))
+    }
+
+    /// Settings the pipeline was last triggered with from the trigger dialog
+    pub fn load_trigger_preset(
+        &self,
+        project: &str,
+        pipeline_id: i32,
+    ) -> Option<PipelineRunRequest> {
+        let path = self.trigger_preset_path(project, pipeline_id)?;
+        let contents = std::fs::read_to_string(&path).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    pub fn save_trigger_preset(
+        &self,
+        project: &str,
+        pipeline_id: i32,
+        request: &PipelineRunRequest,
+    ) -> Result<()> {
+        let dir = self
+            .cache_dir()
+            .ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
+        std::fs::create_dir_all(&dir)?;
+        let path = self
+            .trigger_preset_path(project, pipeline_id)
+            .ok_or_else(|| anyhow::anyhow!("No cache path"))?;
+        let contents = serde_json::to_string_pretty(request)?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
 }
 
 // ============================================
@@ -371,34 +490,45 @@ impl TimelineCacheEntry {
         true
     }
 
-    /// Check if cache should be refreshed in background
-    pub fn needs_refresh(&self) -> bool {
-        self.age_seconds() >= CICD_CACHE_TTL_SECS
+    /// Check if cache is older than `ttl_secs` and should be refreshed in background
+    pub fn needs_refresh(&self, ttl_secs: u64) -> bool {
+        self.age_seconds() >= ttl_secs
     }
 }
 
-fn timeline_cache_path(project: &str, build_id: i32) -> Option<PathBuf> {
-    let sanitized = sanitize_filename(project);
-    cache_dir().map(|d| d.join(format!("{sanitized}_build_{build_id}_timeline.json")))
-}
+impl Cache {
+    fn timeline_cache_path(&self, project: &str, build_id: i32) -> Option<PathBuf> {
+        let sanitized = sanitize_filename(project);
+        self.cache_dir()
+            .map(|d| d.join(format!("{sanitized}_build_{build_id}_timeline.json")))
+    }
 
-/// Load timeline cache. Returns (entry, needs_refresh) if cache exists.
-pub fn load_timeline(project: &str, build_id: i32) -> Option<(TimelineCacheEntry, bool)> {
-    let path = timeline_cache_path(project, build_id)?;
-    let contents = std::fs::read_to_string(&path).ok()?;
-    let entry: TimelineCacheEntry = serde_json::from_str(&contents).ok()?;
-    let needs_refresh = entry.needs_refresh();
-    Some((entry, needs_refresh))
-}
+    /// Load timeline cache. Returns (entry, needs_refresh) if cache exists.
+    pub fn load_timeline(
+        &self,
+        project: &str,
+        build_id: i32,
+        ttl_secs: u64,
+    ) -> Option<(TimelineCacheEntry, bool)> {
+        let path = self.timeline_cache_path(project, build_id)?;
+        let contents = std::fs::read_to_string(&path).ok()?;
+        let entry: TimelineCacheEntry = serde_json::from_str(&contents).ok()?;
+        let needs_refresh = entry.needs_refresh(ttl_secs);
+        Some((entry, needs_refresh))
+    }
 
-pub fn save_timeline(project: &str, entry: &TimelineCacheEntry) -> Result<()> {
-    let dir = cache_dir().ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
-    std::fs::create_dir_all(&dir)?;
-    let path = timeline_cache_path(project, entry.build_id)
-        .ok_or_else(|| anyhow::anyhow!("No cache path"))?;
-    let contents = serde_json::to_string_pretty(entry)?;
-    std::fs::write(path, contents)?;
-    Ok(())
+    pub fn save_timeline(&self, project: &str, entry: &TimelineCacheEntry) -> Result<()> {
+        let dir = self
+            .cache_dir()
+            .ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
+        std::fs::create_dir_all(&dir)?;
+        let path = self
+            .timeline_cache_path(project, entry.build_id)
+            .ok_or_else(|| anyhow::anyhow!("No cache path"))?;
+        let contents = serde_json::to_string_pretty(entry)?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
 }
 
 // ============================================
@@ -439,38 +569,46 @@ impl BuildLogCacheEntry {
         true
     }
 
-    /// Check if cache should be refreshed in background
-    pub fn needs_refresh(&self) -> bool {
-        self.age_seconds() >= CICD_CACHE_TTL_SECS
+    /// Check if cache is older than `ttl_secs` and should be refreshed in background
+    pub fn needs_refresh(&self, ttl_secs: u64) -> bool {
+        self.age_seconds() >= ttl_secs
     }
 }
 
-fn build_log_cache_path(project: &str, build_id: i32, log_id: i32) -> Option<PathBuf> {
-    let sanitized = sanitize_filename(project);
-    cache_dir().map(|d| d.join(format!("{sanitized}_build_{build_id}_log_{log_id}.json")))
-}
+impl Cache {
+    fn build_log_cache_path(&self, project: &str, build_id: i32, log_id: i32) -> Option<PathBuf> {
+        let sanitized = sanitize_filename(project);
+        self.cache_dir()
+            .map(|d| d.join(format!("{sanitized}_build_{build_id}_log_{log_id}.json")))
+    }
 
-/// Load build log cache. Returns (entry, needs_refresh) if cache exists.
-pub fn load_build_log(
-    project: &str,
-    build_id: i32,
-    log_id: i32,
-) -> Option<(BuildLogCacheEntry, bool)> {
-    let path = build_log_cache_path(project, build_id, log_id)?;
-    let contents = std::fs::read_to_string(&path).ok()?;
-    let entry: BuildLogCacheEntry = serde_json::from_str(&contents).ok()?;
-    let needs_refresh = entry.needs_refresh();
-    Some((entry, needs_refresh))
-}
+    /// Load build log cache. Returns (entry, needs_refresh) if cache exists.
+    pub fn load_build_log(
+        &self,
+        project: &str,
+        build_id: i32,
+        log_id: i32,
+        ttl_secs: u64,
+    ) -> Option<(BuildLogCacheEntry, bool)> {
+        let path = self.build_log_cache_path(project, build_id, log_id)?;
+        let contents = std::fs::read_to_string(&path).ok()?;
+        let entry: BuildLogCacheEntry = serde_json::from_str(&contents).ok()?;
+        let needs_refresh = entry.needs_refresh(ttl_secs);
+        Some((entry, needs_refresh))
+    }
 
-pub fn save_build_log(project: &str, entry: &BuildLogCacheEntry) -> Result<()> {
-    let dir = cache_dir().ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
-    std::fs::create_dir_all(&dir)?;
-    let path = build_log_cache_path(project, entry.build_id, entry.log_id)
-        .ok_or_else(|| anyhow::anyhow!("No cache path"))?;
-    let contents = serde_json::to_string_pretty(entry)?;
-    std::fs::write(path, contents)?;
-    Ok(())
+    pub fn save_build_log(&self, project: &str, entry: &BuildLogCacheEntry) -> Result<()> {
+        let dir = self
+            .cache_dir()
+            .ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
+        std::fs::create_dir_all(&dir)?;
+        let path = self
+            .build_log_cache_path(project, entry.build_id, entry.log_id)
+            .ok_or_else(|| anyhow::anyhow!("No cache path"))?;
+        let contents = serde_json::to_string_pretty(entry)?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
 }
 
 // ============================================
@@ -508,30 +646,37 @@ impl PRCacheEntry {
     }
 }
 
-fn pr_cache_path(project: &str) -> Option<PathBuf> {
-    let sanitized = sanitize_filename(project);
-    cache_dir().map(|d| d.join(format!("{sanitized}_pr.json")))
-}
+impl Cache {
+    fn pr_cache_path(&self, project: &str) -> Option<PathBuf> {
+        let sanitized = sanitize_filename(project);
+        self.cache_dir()
+            .map(|d| d.join(format!("{sanitized}_pr.json")))
+    }
 
-pub fn load_pr(project: &str) -> Option<PRCacheEntry> {
-    let path = pr_cache_path(project)?;
-    let contents = std::fs::read_to_string(&path).ok()?;
-    let entry: PRCacheEntry = serde_json::from_str(&contents).ok()?;
-    // Only return if cache is still valid
-    if entry.is_valid() {
-        Some(entry)
-    } else {
-        None
+    pub fn load_pr(&self, project: &str) -> Option<PRCacheEntry> {
+        let path = self.pr_cache_path(project)?;
+        let contents = std::fs::read_to_string(&path).ok()?;
+        let entry: PRCacheEntry = serde_json::from_str(&contents).ok()?;
+        // Only return if cache is still valid
+        if entry.is_valid() {
+            Some(entry)
+        } else {
+            None
+        }
     }
-}
 
-pub fn save_pr(project: &str, entry: &PRCacheEntry) -> Result<()> {
-    let dir = cache_dir().ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
-    std::fs::create_dir_all(&dir)?;
-    let path = pr_cache_path(project).ok_or_else(|| anyhow::anyhow!("No cache path"))?;
-    let contents = serde_json::to_string_pretty(entry)?;
-    std::fs::write(path, contents)?;
-    Ok(())
+    pub fn save_pr(&self, project: &str, entry: &PRCacheEntry) -> Result<()> {
+        let dir = self
+            .cache_dir()
+            .ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
+        std::fs::create_dir_all(&dir)?;
+        let path = self
+            .pr_cache_path(project)
+            .ok_or_else(|| anyhow::anyhow!("No cache path"))?;
+        let contents = serde_json::to_string_pretty(entry)?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
 }
 
 // ============================================
@@ -581,37 +726,43 @@ impl PRListCacheEntry {
         true
     }
 
-    /// Check if cache should be refreshed in background
-    pub fn needs_refresh(&self) -> bool {
-        self.age_seconds() >= CICD_CACHE_TTL_SECS
+    /// Check if cache is older than `ttl_secs` and should be refreshed in background
+    pub fn needs_refresh(&self, ttl_secs: u64) -> bool {
+        self.age_seconds() >= ttl_secs
     }
 }
 
-fn pr_list_cache_path(project: &str, repo_name: &str) -> Option<PathBuf> {
-    let sanitized_project = sanitize_filename(project);
-    let sanitized_repo = sanitize_filename(repo_name);
-    cache_dir().map(|d| d.join(format!("{sanitized_project}_pr_list_{sanitized_repo}.json")))
-}
+impl Cache {
+    fn pr_list_cache_path(&self, project: &str, repo_name: &str) -> Option<PathBuf> {
+        let sanitized_project = sanitize_filename(project);
+        let sanitized_repo = sanitize_filename(repo_name);
+        self.cache_dir()
+            .map(|d| d.join(format!("{sanitized_project}_pr_list_{sanitized_repo}.json")))
+    }
 
-pub fn load_pr_list(project: &str, repo_name: &str) -> Option<PRListCacheEntry> {
-    let path = pr_list_cache_path(project, repo_name)?;
-    let contents = std::fs::read_to_string(&path).ok()?;
-    let entry: PRListCacheEntry = serde_json::from_str(&contents).ok()?;
-    if entry.is_valid() {
-        Some(entry)
-    } else {
-        None
+    pub fn load_pr_list(&self, project: &str, repo_name: &str) -> Option<PRListCacheEntry> {
+        let path = self.pr_list_cache_path(project, repo_name)?;
+        let contents = std::fs::read_to_string(&path).ok()?;
+        let entry: PRListCacheEntry = serde_json::from_str(&contents).ok()?;
+        if entry.is_valid() {
+            Some(entry)
+        } else {
+            None
+        }
     }
-}
 
-pub fn save_pr_list(project: &str, entry: &PRListCacheEntry) -> Result<()> {
-    let dir = cache_dir().ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
-    std::fs::create_dir_all(&dir)?;
-    let path = pr_list_cache_path(project, &entry.repo_name)
-        .ok_or_else(|| anyhow::anyhow!("No cache path"))?;
-    let contents = serde_json::to_string_pretty(entry)?;
-    std::fs::write(path, contents)?;
-    Ok(())
+    pub fn save_pr_list(&self, project: &str, entry: &PRListCacheEntry) -> Result<()> {
+        let dir = self
+            .cache_dir()
+            .ok_or_else(|| anyhow::anyhow!("No cache directory"))?;
+        std::fs::create_dir_all(&dir)?;
+        let path = self
+            .pr_list_cache_path(project, &entry.repo_name)
+            .ok_or_else(|| anyhow::anyhow!("No cache path"))?;
+        let contents = serde_json::to_string_pretty(entry)?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -680,12 +831,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cache_entry_relations() {
+        let mut entry = CacheEntry::new(vec![], vec![], vec![], "test", None, None, HashSet::new());
+        entry.relations.insert(7, vec![]);
+        let json = serde_json::to_value(&entry).unwrap();
+        let restored: CacheEntry = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(restored.relations.get(&7), Some(&vec![]));
+
+        // Caches written before relations were kept still load
+        let mut old = json;
+        old.as_object_mut().unwrap().remove("relations");
+        let restored: CacheEntry = serde_json::from_value(old).unwrap();
+        assert!(restored.relations.is_empty());
+    }
+
     #[test]
     fn test_pipeline_runs_needs_refresh_fresh() {
         let entry = PipelineRunsCacheEntry::new(123, vec![]);
 
         assert!(
-            !entry.needs_refresh(),
+            !entry.needs_refresh(CICD_CACHE_TTL_SECS),
             "Fresh cache should not need refresh"
         );
     }
@@ -701,7 +867,10 @@ mod tests {
             .as_secs();
         entry.timestamp = now - CICD_CACHE_TTL_SECS - 1;
 
-        assert!(entry.needs_refresh(), "Stale cache should need refresh");
+        assert!(
+            entry.needs_refresh(CICD_CACHE_TTL_SECS),
+            "Stale cache should need refresh"
+        );
     }
 
     #[test]
@@ -716,7 +885,7 @@ mod tests {
         entry.timestamp = now - CICD_CACHE_TTL_SECS;
 
         assert!(
-            entry.needs_refresh(),
+            entry.needs_refresh(CICD_CACHE_TTL_SECS),
             "Cache at TTL boundary should need refresh"
         );
     }
@@ -753,6 +922,33 @@ mod tests {
         assert_eq!(loaded.pinned_items, original.pinned_items);
     }
 
+    #[test]
+    fn test_cache_in_configured_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("nested");
+        let cache = Cache::new(Some(dir.clone()));
+        assert!(cache.load_last_project().is_none());
+
+        cache.save_last_project("web").unwrap();
+        assert_eq!(cache.load_last_project().as_deref(), Some("web"));
+        assert!(dir.join("last_project.txt").exists());
+    }
+
+    #[test]
+    fn test_temporary_cache_is_removed() {
+        let cache = Cache::temporary();
+        cache.save_last_repo("demo", "web").unwrap();
+        let dir = cache.cache_dir().unwrap();
+        assert_eq!(cache.load_last_repo("demo").as_deref(), Some("web"));
+
+        // Clones share the directory until the last one is dropped
+        let clone = cache.clone();
+        drop(cache);
+        assert_eq!(clone.load_last_repo("demo").as_deref(), Some("web"));
+        drop(clone);
+        assert!(!dir.exists());
+    }
+
     #[test]
     fn test_load_nonexistent_cache() {
         let temp_dir = TempDir::new().unwrap();
@@ -798,7 +994,7 @@ mod tests {
     fn test_releases_cache_needs_refresh() {
         let entry = ReleasesCacheEntry::new(789, vec![]);
         assert!(
-            !entry.needs_refresh(),
+            !entry.needs_refresh(CICD_CACHE_TTL_SECS),
             "Fresh releases cache should not need refresh"
         );
 
@@ -809,7 +1005,7 @@ mod tests {
             .as_secs();
         old_entry.timestamp = now - CICD_CACHE_TTL_SECS - 1;
         assert!(
-            old_entry.needs_refresh(),
+            old_entry.needs_refresh(CICD_CACHE_TTL_SECS),
             "Stale releases cache should need refresh"
         );
     }
@@ -818,7 +1014,7 @@ mod tests {
     fn test_timeline_cache_needs_refresh() {
         let entry = TimelineCacheEntry::new(999, vec![]);
         assert!(
-            !entry.needs_refresh(),
+            !entry.needs_refresh(CICD_CACHE_TTL_SECS),
             "Fresh timeline cache should not need refresh"
         );
 
@@ -829,7 +1025,7 @@ mod tests {
             .as_secs();
         old_entry.timestamp = now - CICD_CACHE_TTL_SECS - 1;
         assert!(
-            old_entry.needs_refresh(),
+            old_entry.needs_refresh(CICD_CACHE_TTL_SECS),
             "Stale timeline cache should need refresh"
         );
     }
@@ -838,7 +1034,7 @@ mod tests {
     fn test_build_log_cache_needs_refresh() {
         let entry = BuildLogCacheEntry::new(111, 222, vec![]);
         assert!(
-            !entry.needs_refresh(),
+            !entry.needs_refresh(CICD_CACHE_TTL_SECS),
             "Fresh build log cache should not need refresh"
         );
 
@@ -849,7 +1045,7 @@ mod tests {
             .as_secs();
         old_entry.timestamp = now - CICD_CACHE_TTL_SECS - 1;
         assert!(
-            old_entry.needs_refresh(),
+            old_entry.needs_refresh(CICD_CACHE_TTL_SECS),
             "Stale build log cache should need refresh"
         );
     }
@@ -0,0 +1,351 @@
+//! Team capacity for a sprint: hours each member has left, after days off,
+//! against the remaining work assigned to them
+
+use crate::azure::{DateRange, Sprint, TeamCapacity, User, WorkItem};
+use crate::burndown::{is_done, is_removed};
+use chrono::{Datelike, NaiveDate, Weekday};
+
+/// Working days when the team settings don't list any
+const DEFAULT_WORKING_DAYS: [Weekday; 5] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+];
+
+/// One member's load for the rest of the sprint
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemberLoad {
+    pub display_name: String,
+    pub unique_name: String,
+    /// Hours a day over all activities
+    pub capacity_per_day: f64,
+    /// Activities with capacity, e.g. "Development"
+    pub activities: Vec<String>,
+    /// Working days left, not counting personal or team days off
+    pub days: usize,
+    /// Personal days off among the working days left
+    pub days_off: usize,
+    /// Hours available for the rest of the sprint
+    pub available: f64,
+    /// Remaining Work of the open items assigned to the member
+    pub assigned: f64,
+    /// Whether capacity is set up for the member at all
+    pub has_capacity: bool,
+}
+
+impl MemberLoad {
+    pub fn overloaded(&self) -> bool {
+        self.assigned > self.available + f64::EPSILON
+    }
+
+    /// Assigned work as a share of the available hours (can exceed 1.0)
+    pub fn ratio(&self) -> f64 {
+        if self.available > 0.0 {
+            self.assigned / self.available
+        } else if self.assigned > 0.0 {
+            f64::INFINITY
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Capacity against remaining work for every member of a sprint
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapacityPlan {
+    /// First day counted: today, or the start of a sprint yet to begin
+    pub from: NaiveDate,
+    pub finish: NaiveDate,
+    /// Working days left for the team, after team days off
+    pub team_days: usize,
+    /// Team days off among the working days left
+    pub team_days_off: usize,
+    /// Members by display name; those with work but no capacity included
+    pub members: Vec<MemberLoad>,
+    /// Remaining Work of open items nobody is assigned to
+    pub unassigned: f64,
+}
+
+impl CapacityPlan {
+    /// Capacity from `today` (or the sprint start) to the end of the sprint.
+    /// `users` are the people found on the sprint's work items; `None` when
+    /// the sprint has no dates
+    pub fn build(
+        sprint: &Sprint,
+        capacity: &TeamCapacity,
+        users: &[User],
+        items: &[WorkItem],
+        today: NaiveDate,
+    ) -> Option<Self> {
+        let start = sprint.attributes.start_date?.date_naive();
+        let finish = sprint.attributes.finish_date?.date_naive().max(start);
+        let from = today.max(start);
+
+        let working_days: Vec<Weekday> = if capacity.working_days.is_empty() {
+            DEFAULT_WORKING_DAYS.to_vec()
+        } else {
+            capacity
+                .working_days
+                .iter()
+                .filter_map(|d| d.parse().ok())
+                .collect()
+        };
+        let days: Vec<NaiveDate> = from
+            .iter_days()
+            .take_while(|day| *day <= finish)
+            .filter(|day| working_days.contains(&day.weekday()))
+            .collect();
+        let off = |ranges: &[DateRange], day: &NaiveDate| {
+            ranges
+                .iter()
+                .any(|r| (r.start.date_naive()..=r.end.date_naive()).contains(day))
+        };
+        let team_days: Vec<NaiveDate> = days
+            .iter()
+            .copied()
+            .filter(|day| !off(&capacity.team_days_off, day))
+            .collect();
+        let team_days_off = days.len() - team_days.len();
+
+        let mut members: Vec<MemberLoad> = capacity
+            .team_members
+            .iter()
+            .map(|member| {
+                let days_off = team_days
+                    .iter()
+                    .filter(|day| off(&member.days_off, day))
+                    .count();
+                let capacity_per_day: f64 =
+                    member.activities.iter().map(|a| a.capacity_per_day).sum();
+                let days = team_days.len() - days_off;
+                MemberLoad {
+                    display_name: member.team_member.display_name.clone(),
+                    unique_name: member.team_member.unique_name.clone(),
+                    capacity_per_day,
+                    activities: member
+                        .activities
+                        .iter()
+                        .filter(|a| a.capacity_per_day > 0.0 && !a.name.is_empty())
+                        .map(|a| a.name.clone())
+                        .collect(),
+                    days,
+                    days_off,
+                    available: capacity_per_day * days as f64,
+                    assigned: 0.0,
+                    has_capacity: true,
+                }
+            })
+            .collect();
+
+        // Everyone with work in the sprint shows up, capacity or not
+        for user in users {
+            if !members
+                .iter()
+                .any(|m| m.unique_name.eq_ignore_ascii_case(&user.unique_name))
+            {
+                members.push(MemberLoad {
+                    display_name: user.display_name.clone(),
+                    unique_name: user.unique_name.clone(),
+                    capacity_per_day: 0.0,
+                    activities: Vec::new(),
+                    days: team_days.len(),
+                    days_off: 0,
+                    available: 0.0,
+                    assigned: 0.0,
+                    has_capacity: false,
+                });
+            }
+        }
+
+        let mut unassigned = 0.0;
+        fn walk(items: &[WorkItem], members: &mut [MemberLoad], unassigned: &mut f64) {
+            for item in items {
+                let fields = &item.fields;
+                let remaining = fields.remaining_work.unwrap_or(0.0);
+                if remaining > 0.0 && !is_done(&fields.state) && !is_removed(&fields.state) {
+                    let member = fields.assigned_to.as_ref().and_then(|a| {
+                        members
+                            .iter_mut()
+                            .find(|m| m.unique_name.eq_ignore_ascii_case(&a.unique_name))
+                    });
+                    match member {
+                        Some(member) => member.assigned += remaining,
+                        None => *unassigned += remaining,
+                    }
+                }
+                walk(&item.children, members, unassigned);
+            }
+        }
+        walk(items, &mut members, &mut unassigned);
+
+        // Members without capacity or work are noise
+        members.retain(|m| m.has_capacity || m.assigned > 0.0);
+        members.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+
+        Some(Self {
+            from,
+            finish,
+            team_days: team_days.len(),
+            team_days_off,
+            members,
+            unassigned,
+        })
+    }
+
+    pub fn available(&self) -> f64 {
+        self.members.iter().map(|m| m.available).sum()
+    }
+
+    /// Remaining work of the whole sprint, unassigned included
+    pub fn assigned(&self) -> f64 {
+        self.members.iter().map(|m| m.assigned).sum::<f64>() + self.unassigned
+    }
+
+    pub fn overloaded(&self) -> usize {
+        self.members.iter().filter(|m| m.overloaded()).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sprint() -> Sprint {
+        serde_json::from_value(serde_json::json!({
+            "id": "s1",
+            "name": "Sprint 1",
+            "path": "P\\Sprint 1",
+            "attributes": {
+                "startDate": "2026-09-28T00:00:00Z",
+                "finishDate": "2026-10-09T00:00:00Z"
+            }
+        }))
+        .unwrap()
+    }
+
+    fn capacity() -> TeamCapacity {
+        serde_json::from_value(serde_json::json!({
+            "teamMembers": [
+                {
+                    "teamMember": { "displayName": "Ada", "uniqueName": "ada@x.com" },
+                    "activities": [
+                        { "capacityPerDay": 4, "name": "Development" },
+                        { "capacityPerDay": 2, "name": "Testing" }
+                    ],
+                    "daysOff": [{ "start": "2026-10-05T00:00:00Z", "end": "2026-10-06T00:00:00Z" }]
+                },
+                {
+                    "teamMember": { "displayName": "Grace", "uniqueName": "grace@x.com" },
+                    "activities": [{ "capacityPerDay": 2, "name": "" }]
+                },
+                {
+                    "teamMember": { "displayName": "Idle", "uniqueName": "idle@x.com" },
+                    "activities": [{ "capacityPerDay": 0, "name": "" }]
+                }
+            ],
+            "teamDaysOff": [{ "start": "2026-10-09T00:00:00Z", "end": "2026-10-09T00:00:00Z" }],
+            "workingDays": ["monday", "tuesday", "wednesday", "thursday", "friday"]
+        }))
+        .unwrap()
+    }
+
+    fn task(id: i32, state: &str, assignee: Option<&str>, remaining: f64) -> WorkItem {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "rev": 1,
+            "fields": {
+                "System.Title": format!("Task {id}"),
+                "System.State": state,
+                "System.WorkItemType": "Task",
+                "System.AssignedTo": assignee.map(|a| serde_json::json!({
+                    "displayName": a,
+                    "uniqueName": format!("{}@x.com", a.to_lowercase())
+                })),
+                "Microsoft.VSTS.Scheduling.RemainingWork": remaining
+            }
+        }))
+        .unwrap()
+    }
+
+    fn user(name: &str) -> User {
+        User {
+            display_name: name.to_string(),
+            unique_name: format!("{}@x.com", name.to_lowercase()),
+        }
+    }
+
+    fn day(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_capacity_for_the_whole_sprint() {
+        let mut story = task(1, "Active", Some("Ada"), 0.0);
+        story.children = vec![
+            task(2, "Active", Some("Ada"), 30.0),
+            task(3, "New", Some("Grace"), 12.0),
+            task(4, "Done", Some("Grace"), 8.0),
+            task(5, "New", Some("Linus"), 5.0),
+            task(6, "New", None, 3.0),
+        ];
+        let users = [user("Ada"), user("Grace"), user("Linus")];
+        let plan = CapacityPlan::build(&sprint(), &capacity(), &users, &[story], day("2026-09-01"))
+            .unwrap();
+
+        // Ten working days, one of them a team day off
+        assert_eq!(plan.from, day("2026-09-28"));
+        assert_eq!((plan.team_days, plan.team_days_off), (9, 1));
+        let names: Vec<&str> = plan
+            .members
+            .iter()
+            .map(|m| m.display_name.as_str())
+            .collect();
+        assert_eq!(names, ["Ada", "Grace", "Idle", "Linus"]);
+
+        let ada = &plan.members[0];
+        assert_eq!((ada.days, ada.days_off), (7, 2));
+        assert_eq!(ada.available, 42.0);
+        assert_eq!(ada.activities, ["Development", "Testing"]);
+        assert!(!ada.overloaded());
+
+        // Done work doesn't count
+        let grace = &plan.members[1];
+        assert_eq!((grace.available, grace.assigned), (18.0, 12.0));
+
+        // Work for someone without capacity always overloads them
+        let linus = &plan.members[3];
+        assert!(!linus.has_capacity);
+        assert!(linus.overloaded());
+        assert_eq!(linus.ratio(), f64::INFINITY);
+
+        assert_eq!(plan.unassigned, 3.0);
+        assert_eq!(plan.available(), 60.0);
+        assert_eq!(plan.assigned(), 50.0);
+        assert_eq!(plan.overloaded(), 1);
+    }
+
+    #[test]
+    fn test_capacity_counts_days_left() {
+        let items = [task(1, "Active", Some("Ada"), 30.0)];
+        // Thursday of the second week: Thursday and the team's Friday off
+        let plan =
+            CapacityPlan::build(&sprint(), &capacity(), &[], &items, day("2026-10-08")).unwrap();
+        assert_eq!((plan.team_days, plan.team_days_off), (1, 1));
+        assert_eq!(plan.members[0].available, 6.0);
+        assert!(plan.members[0].overloaded());
+
+        // After the sprint nothing is left
+        let plan =
+            CapacityPlan::build(&sprint(), &capacity(), &[], &items, day("2026-11-01")).unwrap();
+        assert_eq!(plan.team_days, 0);
+        assert_eq!(plan.available(), 0.0);
+
+        let mut undated = sprint();
+        undated.attributes.finish_date = None;
+        assert!(
+            CapacityPlan::build(&undated, &capacity(), &[], &items, day("2026-10-01")).is_none()
+        );
+    }
+}
@@ -0,0 +1,653 @@
+//! Command-line arguments and the non-interactive subcommands.
+//!
+//! Subcommands run against the same config, backend and project cache as the
+//! TUI, and print either an aligned table or JSON for scripts.
+
+use crate::app::View;
+use crate::azure::{
+    AzureCli, DevOpsBackend, FieldUpdate, PipelineRun, PipelineRunRequest, Sprint, WorkItem,
+    WorkItemFields,
+};
+use crate::cache::{Cache, CacheEntry};
+use crate::config::{Config, ProjectConfig};
+use anyhow::{anyhow, bail, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::io::Write;
+use std::time::Duration;
+
+/// How often `pipeline run --wait` polls the run status
+const WAIT_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Parser)]
+#[command(name = "lazyops", version, about = "A terminal UI for Azure DevOps")]
+pub struct Cli {
+    /// Project name from config (defaults to the last used project)
+    #[arg(long, global = true)]
+    pub project: Option<String>,
+
+    /// Sprint name (defaults to the current sprint)
+    #[arg(long, global = true)]
+    pub sprint: Option<String>,
+
+    /// Use bundled fixture data instead of Azure DevOps
+    #[arg(long, global = true)]
+    pub demo: bool,
+
+    /// Output format for subcommands
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    pub output: OutputFormat,
+
+    /// View to open the TUI in
+    #[arg(long, value_enum)]
+    pub view: Option<StartView>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StartView {
+    Tasks,
+    Prs,
+    Cicd,
+    Board,
+}
+
+impl From<StartView> for View {
+    fn from(view: StartView) -> Self {
+        match view {
+            StartView::Tasks => View::Tasks,
+            StartView::Prs => View::PRs,
+            StartView::Cicd => View::CICD,
+            StartView::Board => View::Board,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Sprint work items
+    Items {
+        #[command(subcommand)]
+        command: ItemsCommand,
+    },
+    /// Change a single work item
+    Item {
+        #[command(subcommand)]
+        command: ItemCommand,
+    },
+    /// Build pipelines
+    Pipeline {
+        #[command(subcommand)]
+        command: PipelineCommand,
+    },
+    /// Pull requests
+    Pr {
+        #[command(subcommand)]
+        command: PrCommand,
+    },
+    /// List release approvals waiting on you
+    Approvals,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ItemsCommand {
+    /// List work items in the sprint
+    List {
+        /// Only items in this state
+        #[arg(long)]
+        state: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ItemCommand {
+    /// Change the state of a work item
+    SetState { id: i32, state: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PipelineCommand {
+    /// Queue a pipeline run
+    Run {
+        /// Pipeline definition ID
+        id: i32,
+        /// Branch to build
+        #[arg(long, default_value = "main")]
+        branch: String,
+        /// Wait for the run to finish; fails unless it succeeds
+        #[arg(long)]
+        wait: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PrCommand {
+    /// List pull requests
+    List {
+        /// Only pull requests you created
+        #[arg(long)]
+        mine: bool,
+        /// Repository name (defaults to the project's `repository`, then the
+        /// one last open in the TUI, else all)
+        #[arg(long)]
+        repo: Option<String>,
+        /// active, completed, abandoned or all
+        #[arg(long, default_value = "active")]
+        status: String,
+    },
+}
+
+/// Find a configured project by name
+pub fn project_idx(config: &Config, name: &str) -> Result<usize> {
+    config
+        .projects
+        .iter()
+        .position(|p| p.name == name)
+        .ok_or_else(|| {
+            let names: Vec<&str> = config.projects.iter().map(|p| p.name.as_str()).collect();
+            anyhow!(
+                "Unknown project '{name}' (configured: {})",
+                names.join(", ")
+            )
+        })
+}
+
+/// The project cache the TUI keeps, read while younger than `expiry` seconds
+pub struct ProjectCache {
+    pub cache: Cache,
+    pub expiry: u64,
+}
+
+impl ProjectCache {
+    /// Cached sprints and work items of the project, unless expired
+    fn fresh_entry(&self, project: &ProjectConfig) -> Option<CacheEntry> {
+        self.cache
+            .load(&project.name)
+            .filter(|entry| entry.age_seconds() < self.expiry)
+    }
+}
+
+/// Run a subcommand, writing its output to `out`
+pub async fn run<B: DevOpsBackend>(
+    cli: &Cli,
+    command: &Command,
+    project: &ProjectConfig,
+    client: &B,
+    cache: &ProjectCache,
+    out: &mut impl Write,
+) -> Result<()> {
+    let format = cli.output;
+    match command {
+        Command::Items {
+            command: ItemsCommand::List { state },
+        } => {
+            let cached = cache.fresh_entry(project);
+            let sprints = match &cached {
+                Some(entry) if !entry.sprints.is_empty() => entry.sprints.clone(),
+                _ => client.get_sprints().await?,
+            };
+            let sprint = find_sprint(&sprints, cli.sprint.as_deref())?;
+            let tree = match cached {
+                Some(entry) if entry.sprint_path == sprint.path => {
+                    AzureCli::build_hierarchy(entry.work_items)
+                }
+                _ => client.get_sprint_work_items(&sprint.path).await?,
+            };
+            let mut items = Vec::new();
+            flatten(tree, &mut items);
+            items.retain(|w| {
+                state
+                    .as_deref()
+                    .is_none_or(|s| w.fields.state.eq_ignore_ascii_case(s))
+            });
+            print(out, format, &items, item_rows)
+        }
+        Command::Item {
+            command: ItemCommand::SetState { id, state },
+        } => {
+            let update = FieldUpdate::set(WorkItemFields::STATE, state.as_str());
+            let item = client.update_work_item(*id, &[update]).await?;
+            // Keep the TUI's cached copy in step
+            if let Some(mut entry) = cache.cache.load(&project.name) {
+                if let Some(cached) = entry.work_items.iter_mut().find(|w| w.id == item.id) {
+                    cached.fields.state = item.fields.state.clone();
+                    let _ = cache.cache.save(&project.name, &entry);
+                }
+            }
+            print(out, format, std::slice::from_ref(&item), item_rows)
+        }
+        Command::Pipeline {
+            command: PipelineCommand::Run { id, branch, wait },
+        } => {
+            let mut run = client
+                .trigger_pipeline(*id, &PipelineRunRequest::branch(branch))
+                .await?;
+            if *wait {
+                eprintln!("Waiting for run #{}...", run.id);
+                while run.status.as_deref() != Some("completed") {
+                    tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+                    run = client.get_pipeline_run(run.id).await?;
+                }
+            }
+            print(out, format, std::slice::from_ref(&run), run_rows)?;
+            if *wait && run.result.as_deref() != Some("succeeded") {
+                bail!(
+                    "Run #{} finished: {}",
+                    run.id,
+                    run.result.as_deref().unwrap_or("unknown")
+                );
+            }
+            Ok(())
+        }
+        Command::Pr {
+            command: PrCommand::List { mine, repo, status },
+        } => {
+            let creator = if *mine {
+                Some(client.get_current_user().await?)
+            } else {
+                None
+            };
+            // Else the configured repository, then the one last open in the TUI
+            let repo = repo
+                .clone()
+                .or_else(|| project.repository.clone())
+                .or_else(|| cache.cache.load_last_repo(&project.name));
+            let prs = client
+                .list_pull_requests(repo.as_deref(), status, creator.as_deref(), None)
+                .await?;
+            print(out, format, &prs, |prs| {
+                let rows = prs
+                    .iter()
+                    .map(|pr| {
+                        vec![
+                            pr.pull_request_id.to_string(),
+                            pr.repository
+                                .as_ref()
+                                .map(|r| r.name.clone())
+                                .unwrap_or_default(),
+                            pr.created_by
+                                .as_ref()
+                                .map(|u| u.display_name.clone())
+                                .unwrap_or_default(),
+                            branch_name(pr.source_branch.as_deref()),
+                            pr.title.clone(),
+                        ]
+                    })
+                    .collect();
+                (vec!["ID", "REPO", "AUTHOR", "BRANCH", "TITLE"], rows)
+            })
+        }
+        Command::Approvals => {
+            let approvals = client.get_pending_approvals().await?;
+            print(out, format, &approvals, |approvals| {
+                let rows = approvals
+                    .iter()
+                    .map(|a| {
+                        vec![
+                            a.id.to_string(),
+                            a.release
+                                .as_ref()
+                                .and_then(|r| r.name.clone())
+                                .unwrap_or_default(),
+                            a.release_environment
+                                .as_ref()
+                                .and_then(|e| e.name.clone())
+                                .unwrap_or_default(),
+                            a.approval_type.clone().unwrap_or_default(),
+                        ]
+                    })
+                    .collect();
+                (vec!["ID", "RELEASE", "STAGE", "TYPE"], rows)
+            })
+        }
+    }
+}
+
+/// Sprint by name, or the current sprint
+fn find_sprint(sprints: &[Sprint], name: Option<&str>) -> Result<Sprint> {
+    let found = match name {
+        Some(name) => sprints
+            .iter()
+            .find(|s| s.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| anyhow!("Sprint not found: {name}"))?,
+        None => sprints
+            .iter()
+            .find(|s| s.attributes.time_frame.as_deref() == Some("current"))
+            .or(sprints.first())
+            .ok_or_else(|| anyhow!("No sprints found"))?,
+    };
+    Ok(found.clone())
+}
+
+/// Flatten the work item hierarchy, keeping depth for indentation
+fn flatten(items: Vec<WorkItem>, out: &mut Vec<WorkItem>) {
+    for mut item in items {
+        let children = std::mem::take(&mut item.children);
+        out.push(item);
+        flatten(children, out);
+    }
+}
+
+fn branch_name(branch: Option<&str>) -> String {
+    branch
+        .map(|b| b.trim_start_matches("refs/heads/").to_string())
+        .unwrap_or_default()
+}
+
+type Table = (Vec<&'static str>, Vec<Vec<String>>);
+
+fn item_rows(items: &[WorkItem]) -> Table {
+    let rows = items
+        .iter()
+        .map(|w| {
+            vec![
+                w.id.to_string(),
+                w.fields.work_item_type.clone(),
+                w.fields.state.clone(),
+                w.fields
+                    .assigned_to
+                    .as_ref()
+                    .map(|a| a.display_name.clone())
+                    .unwrap_or_default(),
+                format!("{}{}", "  ".repeat(w.depth), w.fields.title),
+            ]
+        })
+        .collect();
+    (vec!["ID", "TYPE", "STATE", "ASSIGNEE", "TITLE"], rows)
+}
+
+fn run_rows(runs: &[PipelineRun]) -> Table {
+    let rows = runs
+        .iter()
+        .map(|r| {
+            vec![
+                r.id.to_string(),
+                r.build_number.clone().unwrap_or_default(),
+                branch_name(r.source_branch.as_deref()),
+                r.status.clone().unwrap_or_default(),
+                r.result.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    (vec!["ID", "BUILD", "BRANCH", "STATUS", "RESULT"], rows)
+}
+
+/// Write values as pretty JSON, or as a table built by `to_table`
+fn print<T: Serialize>(
+    out: &mut impl Write,
+    format: OutputFormat,
+    values: &[T],
+    to_table: impl FnOnce(&[T]) -> Table,
+) -> Result<()> {
+    match format {
+        OutputFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(values)?)?,
+        OutputFormat::Table => {
+            let (headers, rows) = to_table(values);
+            write!(out, "{}", format_table(&headers, &rows))?;
+        }
+    }
+    Ok(())
+}
+
+/// Left-aligned columns separated by two spaces; the last column is not padded
+fn format_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let header: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+    let mut text = String::new();
+    for row in std::iter::once(&header).chain(rows) {
+        let last = row.len().saturating_sub(1);
+        let line: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                if i == last {
+                    cell.clone()
+                } else {
+                    format!("{cell:<width$}", width = widths[i])
+                }
+            })
+            .collect();
+        text.push_str(&line.join("  "));
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::azure::FakeBackend;
+
+    async fn run_args(args: &[&str], backend: &FakeBackend) -> Result<String> {
+        let cache = ProjectCache {
+            cache: Cache::temporary(),
+            expiry: 3600,
+        };
+        run_cached(args, backend, &cache).await
+    }
+
+    async fn run_cached(
+        args: &[&str],
+        backend: &FakeBackend,
+        cache: &ProjectCache,
+    ) -> Result<String> {
+        let cli = Cli::try_parse_from(std::iter::once("lazyops").chain(args.iter().copied()))?;
+        let command = cli.command.as_ref().expect("subcommand");
+        let mut out = Vec::new();
+        run(
+            &cli,
+            command,
+            &FakeBackend::demo_project(),
+            backend,
+            cache,
+            &mut out,
+        )
+        .await?;
+        Ok(String::from_utf8(out)?)
+    }
+
+    #[test]
+    fn test_parse_tui_start_options() {
+        let cli = Cli::try_parse_from([
+            "lazyops",
+            "--project",
+            "backoffice",
+            "--view",
+            "cicd",
+            "--sprint",
+            "Sprint 42",
+        ])
+        .unwrap();
+        assert_eq!(cli.project.as_deref(), Some("backoffice"));
+        assert_eq!(cli.view, Some(StartView::Cicd));
+        assert_eq!(cli.sprint.as_deref(), Some("Sprint 42"));
+        assert!(cli.command.is_none());
+    }
+
+    #[test]
+    fn test_format_table_aligns_columns() {
+        let rows = vec![
+            vec!["1".to_string(), "Done".to_string(), "Short".to_string()],
+            vec!["1234".to_string(), "To Do".to_string(), "Long".to_string()],
+        ];
+        assert_eq!(
+            format_table(&["ID", "STATE", "TITLE"], &rows),
+            "ID    STATE  TITLE\n1     Done   Short\n1234  To Do  Long\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_items_list_json() {
+        let backend = FakeBackend::demo();
+        let out = run_args(&["items", "list", "--output", "json"], &backend)
+            .await
+            .unwrap();
+        let items: Vec<serde_json::Value> = serde_json::from_str(&out).unwrap();
+        assert!(!items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_item_set_state_updates_backend() {
+        let backend = FakeBackend::demo();
+        let id = backend.fixtures().work_items[0].id;
+        let out = run_args(&["item", "set-state", &id.to_string(), "Done"], &backend)
+            .await
+            .unwrap();
+        assert!(out.starts_with("ID"));
+        assert_eq!(
+            backend.get_work_item(id).await.unwrap().fields.state,
+            "Done"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pr_list_mine_filters_by_creator() {
+        let backend = FakeBackend::demo();
+        let all = run_args(
+            &["pr", "list", "--status", "all", "--output", "json"],
+            &backend,
+        )
+        .await
+        .unwrap();
+        let mine = run_args(
+            &[
+                "pr", "list", "--mine", "--status", "all", "--output", "json",
+            ],
+            &backend,
+        )
+        .await
+        .unwrap();
+        let all: Vec<serde_json::Value> = serde_json::from_str(&all).unwrap();
+        let mine: Vec<serde_json::Value> = serde_json::from_str(&mine).unwrap();
+        assert!(!mine.is_empty());
+        assert!(mine.len() < all.len());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_pipeline_run_wait_follows_the_run() {
+        let backend = FakeBackend::demo();
+        let other = backend.clone();
+        let finish = async move {
+            tokio::time::sleep(Duration::from_secs(15)).await;
+            let run_id = other.list_pipeline_runs(11, Some(1)).await.unwrap()[0].id;
+            // More runs queued after it than fit on a page of the run list
+            for _ in 0..25 {
+                other
+                    .trigger_pipeline(11, &PipelineRunRequest::branch("main"))
+                    .await
+                    .unwrap();
+            }
+            other.cancel_pipeline_run(run_id).await.unwrap();
+        };
+        let (result, ()) = tokio::join!(
+            run_args(&["pipeline", "run", "11", "--wait"], &backend),
+            finish
+        );
+        let err = result.unwrap_err().to_string();
+        assert!(err.ends_with("finished: canceled"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_commands_use_the_project_cache() {
+        let backend = FakeBackend::demo();
+        let project = FakeBackend::demo_project();
+        let cache = ProjectCache {
+            cache: Cache::temporary(),
+            expiry: 3600,
+        };
+        let sprints = backend.get_sprints().await.unwrap();
+        let sprint = sprints
+            .iter()
+            .find(|s| s.name == "Sprint 42")
+            .unwrap()
+            .clone();
+        let mut items = backend.get_sprint_work_items(&sprint.path).await.unwrap();
+        items.truncate(1);
+        items[0].fields.title = "Cached title".into();
+        let id = items[0].id;
+        let entry = CacheEntry::new(
+            sprints,
+            items,
+            Vec::new(),
+            &sprint.path,
+            None,
+            None,
+            Default::default(),
+        );
+        cache.cache.save(&project.name, &entry).unwrap();
+
+        // Sprint items come from the cache while it is fresh
+        let out = run_cached(
+            &["items", "list", "--sprint", "Sprint 42"],
+            &backend,
+            &cache,
+        )
+        .await
+        .unwrap();
+        assert!(out.contains("Cached title"), "{out}");
+        assert_eq!(out.lines().count(), 2, "{out}");
+
+        // A state change also lands in the cached copy
+        run_cached(
+            &["item", "set-state", &id.to_string(), "Done"],
+            &backend,
+            &cache,
+        )
+        .await
+        .unwrap();
+        let entry = cache.cache.load(&project.name).unwrap();
+        assert_eq!(entry.work_items[0].fields.state, "Done");
+
+        // Expired, the sprint is fetched again
+        let expired = ProjectCache {
+            cache: cache.cache.clone(),
+            expiry: 0,
+        };
+        let out = run_cached(
+            &["items", "list", "--sprint", "Sprint 42"],
+            &backend,
+            &expired,
+        )
+        .await
+        .unwrap();
+        assert!(!out.contains("Cached title"), "{out}");
+
+        // Pull requests default to the repository last open in the TUI
+        cache.cache.save_last_repo(&project.name, "api").unwrap();
+        let out = run_cached(
+            &["pr", "list", "--status", "all", "--output", "json"],
+            &backend,
+            &cache,
+        )
+        .await
+        .unwrap();
+        let prs: Vec<serde_json::Value> = serde_json::from_str(&out).unwrap();
+        assert!(
+            prs.iter().all(|pr| pr["repository"]["name"] == "api"),
+            "{out}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_sprint_is_an_error() {
+        let backend = FakeBackend::demo();
+        let err = run_args(&["items", "list", "--sprint", "Nope"], &backend)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Sprint not found"));
+    }
+}
@@ -1,4 +1,7 @@
-use serde::Deserialize;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Deserialize, Default)]
 #[serde(default)]
@@ -8,6 +11,47 @@ pub struct Config {
     pub settings: Settings,
     pub keybindings: Keybindings,
     pub default_project: Option<String>,
+    /// Named WIQL queries offered in the Queries view
+    pub queries: Vec<SavedQuery>,
+    /// Where cached data is kept, instead of `lazyops` in the user cache
+    /// directory
+    pub cache_dir: Option<PathBuf>,
+}
+
+/// A named WIQL query saved under `[[queries]]`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedQuery {
+    pub name: String,
+    pub wiql: String,
+}
+
+/// Write a `[[queries]]` table to a config file, replacing the query of the
+/// same name and keeping the rest of the file as it is
+fn store_query(path: &Path, query: &SavedQuery) -> anyhow::Result<()> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    let mut doc: toml_edit::DocumentMut = text
+        .parse()
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    let queries = doc
+        .entry("queries")
+        .or_insert_with(|| toml_edit::ArrayOfTables::new().into())
+        .as_array_of_tables_mut()
+        .context("`queries` is not a list of [[queries]] tables")?;
+    queries.retain(|table| table.get("name").and_then(|n| n.as_str()) != Some(&query.name));
+    let mut table = toml_edit::Table::new();
+    table["name"] = toml_edit::value(&query.name);
+    table["wiql"] = toml_edit::value(&query.wiql);
+    queries.push(table);
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, doc.to_string())
+        .with_context(|| format!("Failed to write {}", path.display()))
 }
 
 /// General application settings
@@ -22,43 +66,130 @@ pub struct Settings {
     pub api_timeout: u64,
     /// Delay between parallel API requests (ms) to avoid rate limiting
     pub api_delay_ms: u64,
-    /// Cache expiry in seconds (default 1 hour)
+    /// Age in seconds after which cached data is refreshed in the background
     pub cache_expiry: u64,
     /// Custom work item states (leave empty for defaults)
     pub states: Vec<String>,
+    /// Where Azure DevOps data comes from
+    pub backend: BackendKind,
 }
 
-/// Customizable keybindings (single character keys)
+/// Azure DevOps backend implementation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendKind {
+    /// Shell out to the Azure CLI (`az`) for every request
+    #[default]
+    Cli,
+    /// Call the REST API directly over HTTPS
+    Rest,
+}
+
+/// Customizable keybindings.
+///
+/// Each value is a key spec: a single key (`"j"`, `"G"`, `"enter"`), a modifier
+/// combo (`"ctrl+r"`), or a sequence (`"gg"`, `"g t"`). The `tasks`, `prs` and
+/// `cicd` tables override bindings for one view only.
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 pub struct Keybindings {
     // Navigation
-    pub down: char,
-    pub up: char,
-    pub left: char,
-    pub right: char,
-    pub top: char,
-    pub bottom: char,
+    pub down: String,
+    pub up: String,
+    pub left: String,
+    pub right: String,
+    pub top: String,
+    pub bottom: String,
+    pub page_down: String,
+    pub page_up: String,
+    pub next_tab: String,
+    pub prev_tab: String,
+    pub back: String,
     // Actions
-    pub open: char,
-    pub expand: char,
-    pub toggle_all: char,
-    pub pin: char,
-    pub copy_id: char,
+    pub open: String,
+    pub expand: String,
+    pub toggle_all: String,
+    pub pin: String,
+    pub copy_id: String,
+    pub copy_link: String,
     // Filters
-    pub search: char,
-    pub filter_state: char,
-    pub filter_assignee: char,
-    pub clear_filters: char,
+    pub search: String,
+    pub filter_state: String,
+    pub filter_assignee: String,
+    pub clear_filters: String,
     // Editing
-    pub edit_state: char,
-    pub edit_assignee: char,
+    pub edit_state: String,
+    pub edit_assignee: String,
+    pub edit_fields: String,
+    pub create_work_item: String,
+    pub edit_tags: String,
+    pub move_iteration: String,
+    // Discussion
+    pub add_comment: String,
+    pub delete_comment: String,
+    // Links
+    pub add_link: String,
+    pub remove_link: String,
+    // Multi-select
+    pub mark: String,
+    pub visual_select: String,
+    pub mark_children: String,
+    pub mark_all: String,
+    // Board
+    pub move_card_left: String,
+    pub move_card_right: String,
     // Selection
-    pub select_sprint: char,
-    pub select_project: char,
-    pub refresh: char,
-    pub help: char,
-    pub quit: char,
+    pub select_sprint: String,
+    pub queries: String,
+    pub sprint_stats: String,
+    pub team_capacity: String,
+    pub select_project: String,
+    pub change_repo: String,
+    pub refresh: String,
+    pub help: String,
+    pub quit: String,
+    // Views
+    pub view_tasks: String,
+    pub view_prs: String,
+    pub view_cicd: String,
+    pub view_board: String,
+    // CI/CD
+    pub cancel: String,
+    pub trigger: String,
+    pub trigger_preset: String,
+    pub approve: String,
+    pub approve_all: String,
+    pub open_log: String,
+    pub load_all_runs: String,
+    pub live_preview: String,
+    pub failure_summary: String,
+    pub artifacts: String,
+    // Log preview
+    pub log_search: String,
+    pub next_match: String,
+    pub prev_match: String,
+    pub next_error: String,
+    pub prev_error: String,
+    pub next_warning: String,
+    pub prev_warning: String,
+    pub log_filter: String,
+    pub toggle_timestamps: String,
+    // Pull requests
+    pub create_pr: String,
+    pub vote: String,
+    pub comment: String,
+    pub reply: String,
+    pub resolve_thread: String,
+    pub toggle_draft: String,
+    pub abandon: String,
+    pub complete: String,
+    pub files: String,
+    pub select_lines: String,
+    // Per-view overrides (action name -> key spec)
+    pub tasks: HashMap<String, String>,
+    pub prs: HashMap<String, String>,
+    pub cicd: HashMap<String, String>,
+    pub board: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -67,9 +198,23 @@ pub struct ProjectConfig {
     pub organization: String,
     pub project: String,
     pub team: String,
+    /// Default repository for `lazyops pr list` and PRs created from the Tasks view
     #[serde(default)]
-    #[allow(dead_code)]
     pub repository: Option<String>,
+    /// Project-specific values for `[settings]` keys
+    #[serde(flatten)]
+    pub overrides: SettingsOverrides,
+}
+
+/// `[settings]` keys that can be set per project inside `[[projects]]`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SettingsOverrides {
+    pub refresh_interval: Option<u64>,
+    pub page_jump: Option<usize>,
+    pub api_timeout: Option<u64>,
+    pub api_delay_ms: Option<u64>,
+    pub cache_expiry: Option<u64>,
+    pub states: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -101,47 +246,133 @@ impl Default for Settings {
             api_delay_ms: 50,
             cache_expiry: 3600, // 1 hour
             states: vec![],     // Use defaults
+            backend: BackendKind::Cli,
         }
     }
 }
 
 impl Default for Keybindings {
     fn default() -> Self {
+        let key = |spec: &str| spec.to_string();
         Self {
             // Navigation (vim-style)
-            down: 'j',
-            up: 'k',
-            left: 'h',
-            right: 'l',
-            top: 'g',
-            bottom: 'G',
+            down: key("j"),
+            up: key("k"),
+            left: key("h"),
+            right: key("l"),
+            top: key("g"),
+            bottom: key("G"),
+            page_down: key("ctrl+d"),
+            page_up: key("ctrl+u"),
+            next_tab: key("tab"),
+            prev_tab: key("shift+tab"),
+            back: key("esc"),
             // Actions
-            open: 'o',
-            expand: '\n', // Enter (handled separately)
-            toggle_all: 't',
-            pin: 'p',
-            copy_id: 'y',
+            open: key("o"),
+            expand: key("enter"),
+            toggle_all: key("t"),
+            pin: key("p"),
+            copy_id: key("y"),
+            copy_link: key("Y"),
             // Filters
-            search: 'f',
-            filter_state: 's',
-            filter_assignee: 'a',
-            clear_filters: 'c',
+            search: key("f"),
+            filter_state: key("s"),
+            filter_assignee: key("a"),
+            clear_filters: key("c"),
             // Editing
-            edit_state: 'S',
-            edit_assignee: 'A',
+            edit_state: key("S"),
+            edit_assignee: key("A"),
+            edit_fields: key("e"),
+            create_work_item: key("N"),
+            edit_tags: key("T"),
+            move_iteration: key("M"),
+            // Discussion
+            add_comment: key("C"),
+            delete_comment: key("D"),
+            // Links
+            add_link: key("L"),
+            remove_link: key("X"),
+            // Multi-select
+            mark: key("space"),
+            visual_select: key("v"),
+            mark_children: key("*"),
+            mark_all: key("ctrl+a"),
+            // Board
+            move_card_left: key("H"),
+            move_card_right: key("L"),
             // Selection
-            select_sprint: 'I',
-            select_project: 'P',
-            refresh: 'R',
-            help: '?',
-            quit: 'q',
+            select_sprint: key("I"),
+            queries: key("Q"),
+            sprint_stats: key("B"),
+            team_capacity: key("K"),
+            select_project: key("P"),
+            change_repo: key("R"),
+            refresh: key("r"),
+            help: key("?"),
+            quit: key("q"),
+            // Views
+            view_tasks: key("1"),
+            view_prs: key("2"),
+            view_cicd: key("3"),
+            view_board: key("4"),
+            // CI/CD
+            cancel: key("C"),
+            trigger: key("T"),
+            trigger_preset: key("t"),
+            approve: key("a"),
+            approve_all: key("A"),
+            open_log: key("e"),
+            load_all_runs: key("L"),
+            live_preview: key("w"),
+            failure_summary: key("E"),
+            artifacts: key("F"),
+            // Log preview
+            log_search: key("/"),
+            next_match: key("n"),
+            prev_match: key("N"),
+            next_error: key("]e"),
+            prev_error: key("[e"),
+            next_warning: key("]w"),
+            prev_warning: key("[w"),
+            log_filter: key("&"),
+            toggle_timestamps: key("s"),
+            // Pull requests
+            create_pr: key("n"),
+            vote: key("v"),
+            comment: key("c"),
+            reply: key("C"),
+            resolve_thread: key("x"),
+            toggle_draft: key("d"),
+            abandon: key("X"),
+            complete: key("m"),
+            files: key("F"),
+            select_lines: key("V"),
+            tasks: HashMap::new(),
+            prs: HashMap::new(),
+            cicd: HashMap::new(),
+            board: HashMap::new(),
         }
     }
 }
 
 impl Settings {
+    /// Settings with a project's overrides applied
+    pub fn with_overrides(&self, overrides: &SettingsOverrides) -> Settings {
+        Settings {
+            refresh_interval: overrides.refresh_interval.unwrap_or(self.refresh_interval),
+            page_jump: overrides.page_jump.unwrap_or(self.page_jump),
+            api_timeout: overrides.api_timeout.unwrap_or(self.api_timeout),
+            api_delay_ms: overrides.api_delay_ms.unwrap_or(self.api_delay_ms),
+            cache_expiry: overrides.cache_expiry.unwrap_or(self.cache_expiry),
+            states: overrides
+                .states
+                .clone()
+                .unwrap_or_else(|| self.states.clone()),
+            backend: self.backend,
+        }
+    }
+
     /// Get work item states (custom or defaults)
-    #[allow(dead_code)] // Public API for custom states configuration
     pub fn get_states(&self) -> Vec<&str> {
         if self.states.is_empty() {
             vec![
@@ -184,46 +415,58 @@ impl Default for Theme {
 }
 
 impl Config {
-    pub fn load() -> Self {
-        // 1. Try XDG config path first (~/.config/lazyops/config.toml)
-        // This is the standard on Linux and commonly used on macOS too
+    /// Config file locations, in lookup order:
+    /// 1. XDG config path (`~/.config/lazyops/config.toml`), the standard on
+    ///    Linux and commonly used on macOS too
+    /// 2. Platform-specific config dir (`~/Library/Application Support/` on macOS)
+    /// 3. `~/.lazyops.toml`
+    fn candidate_paths() -> Vec<PathBuf> {
+        let mut paths = Vec::new();
         if let Some(home_dir) = dirs::home_dir() {
-            let xdg_path = home_dir.join(".config").join("lazyops").join("config.toml");
-            if let Ok(contents) = std::fs::read_to_string(&xdg_path) {
-                if let Ok(config) = toml::from_str::<Config>(&contents) {
-                    if !config.projects.is_empty() {
-                        return config;
-                    }
-                }
-            }
+            paths.push(home_dir.join(".config").join("lazyops").join("config.toml"));
         }
-
-        // 2. Try platform-specific config dir (~/Library/Application Support/ on macOS)
         if let Some(config_dir) = dirs::config_dir() {
-            let config_path = config_dir.join("lazyops").join("config.toml");
-            if let Ok(contents) = std::fs::read_to_string(&config_path) {
-                if let Ok(config) = toml::from_str::<Config>(&contents) {
-                    if !config.projects.is_empty() {
-                        return config;
-                    }
-                }
-            }
+            paths.push(config_dir.join("lazyops").join("config.toml"));
         }
-
-        // 3. Try ~/.lazyops.toml
         if let Some(home_dir) = dirs::home_dir() {
-            let config_path = home_dir.join(".lazyops.toml");
-            if let Ok(contents) = std::fs::read_to_string(&config_path) {
-                if let Ok(config) = toml::from_str::<Config>(&contents) {
-                    if !config.projects.is_empty() {
-                        return config;
-                    }
-                }
-            }
+            paths.push(home_dir.join(".lazyops.toml"));
         }
+        paths
+    }
 
+    /// First config file that parses and has projects
+    fn find() -> Option<(PathBuf, Config)> {
+        Self::candidate_paths().into_iter().find_map(|path| {
+            let contents = std::fs::read_to_string(&path).ok()?;
+            let config = toml::from_str::<Config>(&contents).ok()?;
+            (!config.projects.is_empty()).then_some((path, config))
+        })
+    }
+
+    pub fn load() -> Self {
         // Fall back to default (empty)
-        Config::default()
+        Self::find().map(|(_, config)| config).unwrap_or_default()
+    }
+
+    /// Save a named WIQL query to the config file in use (or the XDG path),
+    /// replacing one of the same name
+    pub fn save_query(&mut self, query: SavedQuery) -> anyhow::Result<PathBuf> {
+        let path = match Self::find() {
+            Some((path, _)) => path,
+            None => Self::candidate_paths()
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("No home directory for the config file"))?,
+        };
+        store_query(&path, &query)?;
+        self.remember_query(query);
+        Ok(path)
+    }
+
+    /// Offer a named WIQL query for this session only
+    pub fn remember_query(&mut self, query: SavedQuery) {
+        self.queries.retain(|q| q.name != query.name);
+        self.queries.push(query);
     }
 }
 
@@ -309,6 +552,46 @@ mod tests {
     use super::*;
     use ratatui::style::Color;
 
+    #[test]
+    fn test_store_query_round_trip() {
+        let path =
+            std::env::temp_dir().join(format!("lazyops-queries-{}.toml", std::process::id()));
+        std::fs::write(&path, "default_project = \"shop\"\n").unwrap();
+        let query = SavedQuery {
+            name: "Urgent bugs".into(),
+            wiql: "SELECT [System.Id] FROM WorkItems WHERE [System.Tags] CONTAINS 'urgent'".into(),
+        };
+        store_query(&path, &query).unwrap();
+
+        let config: Config = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.default_project.as_deref(), Some("shop"));
+        assert_eq!(config.queries, vec![query]);
+    }
+
+    #[test]
+    fn test_store_query_replaces_same_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "# My settings\ndefault_project = \"shop\"\n\n[[queries]]\nname = \"Bugs\"\nwiql = \"old\"\n\n[[queries]]\nname = \"Other\"\nwiql = \"other\"\n",
+        )
+        .unwrap();
+        let query = |wiql: &str| SavedQuery {
+            name: "Bugs".into(),
+            wiql: wiql.into(),
+        };
+        store_query(&path, &query("new")).unwrap();
+        store_query(&path, &query("newer")).unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("# My settings"), "{text}");
+        let config: Config = toml::from_str(&text).unwrap();
+        let names: Vec<_> = config.queries.iter().map(|q| q.wiql.as_str()).collect();
+        assert_eq!(names, ["other", "newer"]);
+    }
+
     // Tests for Theme::parse_color
     #[test]
     fn test_parse_color_valid_blue() {
@@ -439,6 +722,7 @@ mod tests {
             api_delay_ms: 50,
             cache_expiry: 3600,
             states: vec!["Open".to_string(), "Closed".to_string()],
+            backend: BackendKind::Cli,
         };
         let states = settings.get_states();
         assert_eq!(states, vec!["Open", "Closed"]);
@@ -454,19 +738,78 @@ mod tests {
         assert_eq!(settings.api_delay_ms, 50);
         assert_eq!(settings.cache_expiry, 3600);
         assert!(settings.states.is_empty());
+        assert_eq!(settings.backend, BackendKind::Cli);
+    }
+
+    #[test]
+    fn test_settings_backend_from_toml() {
+        let config: Config = toml::from_str("[settings]\nbackend = \"rest\"").unwrap();
+        assert_eq!(config.settings.backend, BackendKind::Rest);
+    }
+
+    #[test]
+    fn test_project_overrides_from_toml() {
+        let config: Config = toml::from_str(
+            r#"
+[settings]
+api_timeout = 30
+page_jump = 5
+
+[[projects]]
+name = "onprem"
+organization = "https://tfs.contoso.local/tfs/Default"
+project = "Legacy"
+team = "Legacy Team"
+api_timeout = 120
+states = ["Open", "Closed"]
+
+[[projects]]
+name = "cloud"
+organization = "https://dev.azure.com/contoso"
+project = "Cloud"
+team = "Cloud Team"
+"#,
+        )
+        .unwrap();
+
+        let onprem = config
+            .settings
+            .with_overrides(&config.projects[0].overrides);
+        assert_eq!(onprem.api_timeout, 120);
+        assert_eq!(onprem.page_jump, 5);
+        assert_eq!(onprem.states, vec!["Open", "Closed"]);
+
+        let cloud = config
+            .settings
+            .with_overrides(&config.projects[1].overrides);
+        assert_eq!(cloud.api_timeout, 30);
+        assert!(cloud.states.is_empty());
     }
 
     #[test]
     fn test_keybindings_default_vim_style() {
         let keys = Keybindings::default();
-        assert_eq!(keys.down, 'j');
-        assert_eq!(keys.up, 'k');
-        assert_eq!(keys.left, 'h');
-        assert_eq!(keys.right, 'l');
-        assert_eq!(keys.top, 'g');
-        assert_eq!(keys.bottom, 'G');
-        assert_eq!(keys.open, 'o');
-        assert_eq!(keys.quit, 'q');
+        assert_eq!(keys.down, "j");
+        assert_eq!(keys.up, "k");
+        assert_eq!(keys.left, "h");
+        assert_eq!(keys.right, "l");
+        assert_eq!(keys.top, "g");
+        assert_eq!(keys.bottom, "G");
+        assert_eq!(keys.open, "o");
+        assert_eq!(keys.quit, "q");
+    }
+
+    #[test]
+    fn test_keybindings_view_overrides_from_toml() {
+        let config: Config = toml::from_str(
+            "[keybindings]\ndown = \"n\"\ntop = \"gg\"\n\n[keybindings.cicd]\napprove = \"ctrl+a\"",
+        )
+        .unwrap();
+        assert_eq!(config.keybindings.down, "n");
+        assert_eq!(config.keybindings.top, "gg");
+        assert_eq!(config.keybindings.up, "k");
+        assert_eq!(config.keybindings.cicd["approve"], "ctrl+a");
+        assert!(config.keybindings.tasks.is_empty());
     }
 
     #[test]
@@ -0,0 +1,298 @@
+//! Work item discussion: the comment being composed, with `@mention`
+//! completion from the known users, and the result of comment requests
+
+use crate::azure::{User, WorkItemComment};
+use crate::markdown::markdown_to_html;
+
+/// Users offered at once while completing a mention
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Longest text after `@` still treated as a mention being typed
+const MAX_MENTION_QUERY: usize = 30;
+
+/// Comment being written in the discussion composer
+#[derive(Debug, Clone, Default)]
+pub struct CommentDraft {
+    pub work_item_id: i32,
+    /// Comment being edited; `None` posts a new one
+    pub comment_id: Option<i32>,
+    /// Markdown
+    pub text: String,
+    /// Users picked from the mention suggestions
+    pub mentions: Vec<User>,
+    /// Highlighted mention suggestion
+    pub suggestion: usize,
+}
+
+impl CommentDraft {
+    pub fn new(work_item_id: i32) -> Self {
+        Self {
+            work_item_id,
+            ..Default::default()
+        }
+    }
+
+    /// Edit an existing comment, given as Markdown
+    pub fn edit(work_item_id: i32, comment_id: i32, text: String) -> Self {
+        Self {
+            work_item_id,
+            comment_id: Some(comment_id),
+            text,
+            ..Default::default()
+        }
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.text.push(c);
+        self.suggestion = 0;
+    }
+
+    pub fn pop(&mut self) {
+        self.text.pop();
+        self.suggestion = 0;
+    }
+
+    /// Start of the `@mention` being typed at the end of the text, and what
+    /// follows the `@`
+    fn mention_query(&self) -> Option<(usize, &str)> {
+        for (at, _) in self.text.rmatch_indices('@') {
+            let query = &self.text[at + 1..];
+            if query.contains('\n') || query.chars().count() > MAX_MENTION_QUERY {
+                return None;
+            }
+            // Not inside a word, so the `@` of `ada@contoso.com` starts none
+            let before = self.text[..at].chars().next_back();
+            if before.is_none_or(|c| !c.is_alphanumeric()) {
+                return Some((at, query));
+            }
+        }
+        None
+    }
+
+    /// Users matching the mention being typed, best first
+    pub fn suggestions<'a>(&self, users: &'a [User]) -> Vec<&'a User> {
+        let Some((_, query)) = self.mention_query() else {
+            return Vec::new();
+        };
+        let query = query.to_lowercase();
+        let mut matches: Vec<(bool, &User)> = users
+            .iter()
+            .filter_map(|user| {
+                let name = user.display_name.to_lowercase();
+                let prefix = name.starts_with(&query)
+                    || name.split_whitespace().any(|word| word.starts_with(&query));
+                (prefix || user.unique_name.to_lowercase().starts_with(&query))
+                    .then_some((prefix, user))
+            })
+            .collect();
+        // Stable: name prefix matches first, otherwise in the given order
+        matches.sort_by_key(|(prefix, _)| !prefix);
+        matches
+            .into_iter()
+            .map(|(_, user)| user)
+            .take(MAX_SUGGESTIONS)
+            .collect()
+    }
+
+    pub fn next_suggestion(&mut self, count: usize) {
+        if count > 0 {
+            self.suggestion = (self.suggestion + 1) % count;
+        }
+    }
+
+    pub fn prev_suggestion(&mut self, count: usize) {
+        if count > 0 {
+            self.suggestion = (self.suggestion + count - 1) % count;
+        }
+    }
+
+    /// Complete the mention being typed with `user`
+    pub fn accept_mention(&mut self, user: &User) {
+        let Some((at, _)) = self.mention_query() else {
+            return;
+        };
+        self.text.truncate(at);
+        self.text.push('@');
+        self.text.push_str(&user.display_name);
+        self.text.push(' ');
+        if !self
+            .mentions
+            .iter()
+            .any(|m| m.unique_name == user.unique_name)
+        {
+            self.mentions.push(user.clone());
+        }
+        self.suggestion = 0;
+    }
+
+    /// HTML to post: the Markdown rendered, with the picked mentions as
+    /// `mailto:` links (the backends turn those into identity mentions)
+    pub fn html(&self) -> String {
+        let mut html = markdown_to_html(self.text.trim());
+        for user in &self.mentions {
+            html = link_mention(&html, user);
+        }
+        html
+    }
+}
+
+/// Link each `@Display Name` of `user` that is not already a link
+fn link_mention(html: &str, user: &User) -> String {
+    let mention = format!("@{}", user.display_name);
+    let link = format!(r#"<a href="mailto:{}">{mention}</a>"#, user.unique_name);
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(pos) = rest.find(&mention) {
+        out.push_str(&rest[..pos]);
+        if out.ends_with('>') && out.rfind("<a ") > out.rfind("</a>") {
+            out.push_str(&mention);
+        } else {
+            out.push_str(&link);
+        }
+        rest = &rest[pos + mention.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Addresses of the `mailto:` mention links in a comment, each once
+pub fn mention_addresses(html: &str) -> Vec<String> {
+    const START: &str = r#"<a href="mailto:"#;
+    let mut addresses: Vec<String> = Vec::new();
+    let mut rest = html;
+    while let Some(pos) = rest.find(START) {
+        rest = &rest[pos + START.len()..];
+        let Some(end) = rest.find('"') else {
+            break;
+        };
+        let address = &rest[..end];
+        if rest[end..].starts_with("\">@") && !addresses.iter().any(|a| a == address) {
+            addresses.push(address.to_string());
+        }
+        rest = &rest[end..];
+    }
+    addresses
+}
+
+/// Replace the `mailto:` mention links of `address` with an identity mention,
+/// which notifies the person mentioned
+pub fn link_identity(html: &str, address: &str, identity_id: &str) -> String {
+    html.replace(
+        &format!(r#"<a href="mailto:{address}">@"#),
+        &format!(r##"<a href="#" data-vss-mention="version:2.0,{identity_id}">@"##),
+    )
+}
+
+/// Whether the signed-in user wrote the comment
+pub fn is_own(comment: &WorkItemComment, current_user: Option<&str>) -> bool {
+    let author = comment
+        .created_by
+        .as_ref()
+        .and_then(|by| by.unique_name.as_deref());
+    matches!((author, current_user), (Some(a), Some(me)) if a.eq_ignore_ascii_case(me))
+}
+
+/// Result of loading or changing a work item's comments
+#[derive(Debug)]
+pub struct CommentsUpdate {
+    pub work_item_id: i32,
+    /// Fresh comment list, or why it could not be loaded; `None` keeps the
+    /// one shown
+    pub comments: Option<Result<Vec<WorkItemComment>, String>>,
+    /// What a post, edit or delete did, or why it failed
+    pub outcome: Option<Result<String, String>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn users() -> Vec<User> {
+        [
+            ("Ada Lovelace", "ada@contoso.com"),
+            ("Grace Hopper", "grace@contoso.com"),
+            ("Alan Turing", "alan@contoso.com"),
+        ]
+        .iter()
+        .map(|(name, unique)| User {
+            display_name: name.to_string(),
+            unique_name: unique.to_string(),
+        })
+        .collect()
+    }
+
+    fn typed(text: &str) -> CommentDraft {
+        let mut draft = CommentDraft::new(1);
+        text.chars().for_each(|c| draft.push(c));
+        draft
+    }
+
+    #[test]
+    fn test_mention_suggestions() {
+        let users = users();
+        let names = |draft: &CommentDraft| -> Vec<String> {
+            draft
+                .suggestions(&users)
+                .iter()
+                .map(|u| u.display_name.clone())
+                .collect()
+        };
+
+        assert_eq!(names(&typed("Thanks @")).len(), 3);
+        assert_eq!(names(&typed("Thanks @a")), ["Ada Lovelace", "Alan Turing"]);
+        // Any word of the name, then the address
+        assert_eq!(names(&typed("@hop")), ["Grace Hopper"]);
+        assert_eq!(names(&typed("@grace@")), ["Grace Hopper"]);
+        // Not a mention: mid-word, finished line, or no @ at all
+        assert!(names(&typed("mail ada@contoso")).is_empty());
+        assert!(names(&typed("@Ada\nsee")).is_empty());
+        assert!(names(&typed("hello")).is_empty());
+    }
+
+    #[test]
+    fn test_accept_mention_and_render_html() {
+        let users = users();
+        let mut draft = typed("Ping @gr");
+        draft.accept_mention(&users[1]);
+        assert_eq!(draft.text, "Ping @Grace Hopper ");
+        "and (@Gr".chars().for_each(|c| draft.push(c));
+        draft.accept_mention(&users[1]);
+        draft.push(')');
+        assert_eq!(draft.mentions.len(), 1);
+
+        assert_eq!(
+            draft.html(),
+            "<p>Ping <a href=\"mailto:grace@contoso.com\">@Grace Hopper</a> and \
+             (<a href=\"mailto:grace@contoso.com\">@Grace Hopper</a> )</p>"
+        );
+
+        // Editing a comment keeps mentions that are links already
+        let mut edit =
+            CommentDraft::edit(1, 7, "[@Grace Hopper](mailto:grace@contoso.com) ok".into());
+        edit.mentions.push(users[1].clone());
+        assert_eq!(
+            edit.html(),
+            "<p><a href=\"mailto:grace@contoso.com\">@Grace Hopper</a> ok</p>"
+        );
+    }
+
+    #[test]
+    fn test_mention_addresses_and_ownership() {
+        let html = r#"<p><a href="mailto:a@x.com">@A</a>, <a href="mailto:b@x.com">mail B</a>, <a href="mailto:a@x.com">@A</a></p>"#;
+        assert_eq!(mention_addresses(html), ["a@x.com"]);
+        assert_eq!(
+            link_identity(html, "a@x.com", "id-a"),
+            r##"<p><a href="#" data-vss-mention="version:2.0,id-a">@A</a>, <a href="mailto:b@x.com">mail B</a>, <a href="#" data-vss-mention="version:2.0,id-a">@A</a></p>"##
+        );
+
+        let comment: WorkItemComment = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "text": "<p>Hi</p>",
+            "createdBy": {"uniqueName": "Ada@Contoso.com"}
+        }))
+        .unwrap();
+        assert!(is_own(&comment, Some("ada@contoso.com")));
+        assert!(!is_own(&comment, Some("grace@contoso.com")));
+        assert!(!is_own(&comment, None));
+    }
+}
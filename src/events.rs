@@ -1,6 +1,18 @@
+// Key arms check the active view inside the arm on purpose: turning those
+// checks into match guards would let keys fall through to later arms.
+#![allow(clippy::collapsible_match)]
+
 use crate::app::{App, Focus, InputMode, View};
-use crate::azure::WorkItem;
+use crate::azure::{DevOpsBackend, FieldUpdate, WorkItem, WorkItemFields};
+use crate::bulk::BulkOperation;
+use crate::config::SavedQuery;
+use crate::keymap::{Action, KeyChord, Lookup};
+use crate::log_view::{LogTarget, Severity};
+use crate::pr_complete::PRCompleteField;
+use crate::pr_create::{PRCreateField, PRCreateOption};
+use crate::query_browser::QueryRow;
 use crate::ui;
+use crate::work_item_create::WorkItemCreateField;
 use anyhow::Result;
 use arboard::Clipboard;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
@@ -31,9 +43,19 @@ fn format_ticket_content(item: &WorkItem) -> String {
     content
 }
 
-pub async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
+/// Run the TUI. `sprint` opens that sprint (by name) instead of the cached or
+/// current one.
+pub async fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    sprint: Option<&str>,
+) -> Result<()> {
     // Try loading from cache first for instant startup
     let has_cache = app.load_from_cache();
+    // Cached data older than `cache_expiry` is shown, then refreshed right away
+    let mut refresh_due = app
+        .cache_age
+        .is_some_and(|age| age >= app.settings().cache_expiry);
 
     if has_cache {
         // Cache hit: show data immediately, skip blocking refresh
@@ -45,6 +67,20 @@ pub async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> R
                 app.set_status(format!("Cached ({age}s ago) - press r to refresh"));
             }
         }
+
+        let cached_idx = app.selected_sprint_idx;
+        if let Some(name) = sprint {
+            if !app.select_sprint_by_name(name) {
+                app.set_error(format!("Sprint not found: {name}"));
+            } else if app.selected_sprint_idx != cached_idx {
+                app.set_loading(true, "Loading sprint...");
+                terminal.draw(|f| ui::draw(f, app))?;
+                if let Err(e) = app.load_work_items().await {
+                    app.set_error(format!("Failed to load work items: {e}"));
+                }
+                app.set_loading(false, "");
+            }
+        }
     } else {
         // No cache: must load from API (blocking)
         app.set_loading(true, "Loading sprints...");
@@ -53,6 +89,11 @@ pub async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> R
         if let Err(e) = app.load_sprints().await {
             app.set_error(format!("Failed to load sprints: {e}"));
         } else {
+            if let Some(name) = sprint {
+                if !app.select_sprint_by_name(name) {
+                    app.set_error(format!("Sprint not found: {name}"));
+                }
+            }
             app.set_loading(true, "Loading work items...");
             terminal.draw(|f| ui::draw(f, app))?;
 
@@ -70,9 +111,16 @@ pub async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> R
     // Start background relation loader (non-blocking)
     app.start_relations_loader();
 
+    // Views other than Tasks load their data in the background
+    match app.current_view {
+        View::Tasks => {}
+        View::PRs => app.start_pr_loader(),
+        View::CICD => app.start_cicd_loader(),
+        View::Board => app.load_boards().await,
+    }
+
     let mut last_refresh = Instant::now();
     let mut last_spinner_tick = Instant::now();
-    let refresh_interval = Duration::from_secs(300); // Full refresh every 5 minutes
     let spinner_interval = Duration::from_millis(80);
 
     loop {
@@ -89,6 +137,19 @@ pub async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> R
         app.poll_pr_results();
         app.poll_live_preview();
         app.poll_release_refresh();
+        app.poll_editor();
+        app.poll_bulk_results();
+        app.poll_burndown();
+        app.poll_team_capacity();
+        app.poll_pipeline_trigger();
+        app.poll_run_issue_counts();
+        app.poll_failure_summary();
+        app.poll_build_tests();
+        app.poll_artifacts();
+        app.poll_artifact_download();
+        app.poll_history();
+        app.poll_comments();
+        app.poll_links();
 
         // Start titles loader once some relations have been loaded
         if !app.titles_loader_active && !app.relations_loaded.is_empty() {
@@ -109,46 +170,97 @@ pub async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> R
             }
         }
 
-        // Full data refresh every 5 minutes
-        if !app.loading
-            && app.input_mode == InputMode::Normal
-            && last_refresh.elapsed() >= refresh_interval
-        {
+        // Full data refresh every `refresh_interval` seconds (0 disables)
+        let refresh_interval = app.settings().refresh_interval;
+        if refresh_interval > 0 && last_refresh.elapsed() >= Duration::from_secs(refresh_interval) {
+            refresh_due = true;
+        }
+        if refresh_due && !app.loading && app.input_mode == InputMode::Normal {
             background_full_refresh(app).await;
             last_refresh = Instant::now();
+            refresh_due = false;
+        }
+    }
+}
+
+/// Feed a key press into the keymap, returning the action once a binding
+/// (or sequence) is complete
+fn resolve_action<D: DevOpsBackend>(app: &mut App<D>, key: &KeyEvent) -> Option<Action> {
+    let chord = KeyChord::from_event(key);
+    app.pending_keys.push(chord);
+
+    match app.keymap.lookup(app.current_view, &app.pending_keys) {
+        Lookup::Action(action) => {
+            app.pending_keys.clear();
+            Some(action)
+        }
+        Lookup::Pending => None,
+        Lookup::None => {
+            // A dead-end sequence: drop it and retry the last key on its own
+            let retry = app.pending_keys.len() > 1;
+            app.pending_keys.clear();
+            if !retry {
+                return None;
+            }
+            match app.keymap.lookup(app.current_view, &[chord]) {
+                Lookup::Action(action) => Some(action),
+                Lookup::Pending => {
+                    app.pending_keys.push(chord);
+                    None
+                }
+                Lookup::None => None,
+            }
         }
     }
 }
 
+/// Board view navigation and card moves; returns false for actions that
+/// work the same as in the Tasks view
+async fn handle_board_action<D: DevOpsBackend>(app: &mut App<D>, action: Action) -> bool {
+    let jump = app.settings().page_jump as isize;
+    match action {
+        Action::Down => app.board_step(0, 1),
+        Action::Up => app.board_step(0, -1),
+        Action::PageDown => app.board_step(0, jump),
+        Action::PageUp => app.board_step(0, -jump),
+        Action::Left => app.board_step(-1, 0),
+        Action::Right => app.board_step(1, 0),
+        Action::Top => app.board_edge(false),
+        Action::Bottom => app.board_edge(true),
+        Action::Expand => app.collapse_board_lane(),
+        Action::ToggleAll => app.toggle_board_lanes(),
+        Action::MoveCardLeft => app.move_board_card(-1).await,
+        Action::MoveCardRight => app.move_board_card(1).await,
+        _ => return false,
+    }
+    true
+}
+
 /// Full refresh - reloads work items and restarts relation loader
-async fn background_full_refresh(app: &mut App) {
+async fn background_full_refresh<D: DevOpsBackend>(app: &mut App<D>) {
     // Cache relations before refresh
     let relations_cache = app.cache_relations();
 
-    if let Some(client) = app.client() {
-        if let Some(sprint) = app.selected_sprint() {
-            let path = sprint.path.clone();
-            if let Ok(items) = client.get_sprint_work_items(&path).await {
-                let selected_id = app.selected_work_item().map(|w| w.item.id);
-
-                app.work_items = items;
-                app.extract_users_from_work_items();
-                app.restore_relations(relations_cache);
-                app.rebuild_visible_items();
-
-                if let Some(id) = selected_id {
-                    if let Some(pos) = app.visible_items.iter().position(|v| v.item.id == id) {
-                        app.work_item_list_state.select(Some(pos));
-                    }
-                }
+    if let Ok(Some(items)) = app.fetch_work_items().await {
+        let selected_id = app.selected_work_item().map(|w| w.item.id);
 
-                app.cache_age = Some(0);
+        app.work_items = items;
+        app.extract_users_from_work_items();
+        app.restore_relations(relations_cache);
+        app.rebuild_visible_items();
 
-                // Restart relation loader for any new items
-                app.relations_loader_active = false;
-                app.start_relations_loader();
+        if let Some(id) = selected_id {
+            if let Some(pos) = app.visible_items.iter().position(|v| v.item.id == id) {
+                app.work_item_list_state.select(Some(pos));
             }
         }
+
+        app.cache_age = Some(0);
+
+        // Refetch relations, the restored ones may be stale
+        app.relations_loaded.clear();
+        app.relations_loader_active = false;
+        app.start_relations_loader();
     }
 }
 
@@ -199,7 +311,7 @@ fn key_to_terminal_bytes(key: &KeyEvent) -> Option<Vec<u8>> {
     }
 }
 
-async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
+async fn handle_key<D: DevOpsBackend>(app: &mut App<D>, key: KeyEvent) -> Result<bool> {
     // Clear status on any keypress
     app.clear_status();
 
@@ -207,8 +319,13 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
     if app.terminal_mode {
         // Ctrl+q exits terminal mode
         if key.code == KeyCode::Char('q') && key.modifiers.contains(KeyModifiers::CONTROL) {
-            app.close_embedded_terminal();
-            app.set_status("Exited log viewer");
+            if app.editor_file.is_some() {
+                // Keep whatever the editor last saved
+                app.finish_editor();
+            } else {
+                app.close_embedded_terminal();
+                app.set_status("Exited log viewer");
+            }
             return Ok(false);
         }
 
@@ -221,20 +338,52 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
 
     // Handle based on input mode
     match app.input_mode {
-        InputMode::Help => match key.code {
-            KeyCode::Esc | KeyCode::Char('?') | KeyCode::Char('q') => {
+        InputMode::Help => {
+            let view = app.current_view;
+            if [Action::Back, Action::Help, Action::Quit]
+                .iter()
+                .any(|a| app.keymap.is(view, *a, &key))
+            {
                 app.input_mode = InputMode::Normal;
             }
-            _ => {}
-        },
+        }
+
+        InputMode::SprintStats => {
+            let view = app.current_view;
+            if [Action::Back, Action::SprintStats, Action::Quit]
+                .iter()
+                .any(|a| app.keymap.is(view, *a, &key))
+            {
+                app.input_mode = InputMode::Normal;
+            } else if app.keymap.is(view, Action::Refresh, &key) && app.burndown_rx.is_none() {
+                app.load_burndown();
+            }
+        }
+
+        InputMode::TeamCapacity => {
+            let view = app.current_view;
+            if [Action::Back, Action::TeamCapacity, Action::Quit]
+                .iter()
+                .any(|a| app.keymap.is(view, *a, &key))
+            {
+                app.input_mode = InputMode::Normal;
+            } else if app.keymap.is(view, Action::Refresh, &key) && app.capacity_rx.is_none() {
+                app.load_team_capacity();
+            }
+        }
 
         InputMode::SprintSelect => match key.code {
             KeyCode::Esc => app.input_mode = InputMode::Normal,
-            KeyCode::Char('j') | KeyCode::Down => app.dropdown_next(app.sprints.len()),
-            KeyCode::Char('k') | KeyCode::Up => app.dropdown_prev(app.sprints.len()),
+            _ if app.keymap.is(app.current_view, Action::Down, &key) => {
+                app.dropdown_next(app.sprints.len())
+            }
+            _ if app.keymap.is(app.current_view, Action::Up, &key) => {
+                app.dropdown_prev(app.sprints.len())
+            }
             KeyCode::Enter => {
                 if let Some(idx) = app.dropdown_list_state.selected() {
                     app.selected_sprint_idx = idx;
+                    app.clear_active_query();
                     app.input_mode = InputMode::Normal;
                     app.set_loading(true, "Loading sprint...");
                     if let Err(e) = app.load_work_items().await {
@@ -246,24 +395,337 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
             _ => {}
         },
 
+        InputMode::PRVote => match key.code {
+            KeyCode::Esc => app.input_mode = InputMode::Normal,
+            _ if app.keymap.is(app.current_view, Action::Down, &key) => {
+                app.dropdown_next(crate::app::PR_VOTES.len())
+            }
+            _ if app.keymap.is(app.current_view, Action::Up, &key) => {
+                app.dropdown_prev(crate::app::PR_VOTES.len())
+            }
+            KeyCode::Enter => {
+                app.input_mode = InputMode::Normal;
+                if let Some(idx) = app.dropdown_list_state.selected() {
+                    let (vote, _) = crate::app::PR_VOTES[idx];
+                    app.vote_on_pr(vote);
+                }
+            }
+            _ => {}
+        },
+
+        InputMode::PRCreate => {
+            let Some(dialog) = &mut app.pr_create_dialog else {
+                app.input_mode = InputMode::Normal;
+                return Ok(false);
+            };
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            match key.code {
+                KeyCode::Esc => {
+                    app.pr_create_dialog = None;
+                    app.input_mode = InputMode::Normal;
+                }
+                KeyCode::Char('s') if ctrl => app.submit_pr_create(),
+                KeyCode::Char('x') if ctrl => dialog.swap_branches(),
+                KeyCode::Tab => dialog.next_field(),
+                KeyCode::BackTab => dialog.prev_field(),
+                KeyCode::Down => {
+                    let count = if dialog.field == PRCreateField::Options {
+                        PRCreateOption::ALL.len()
+                    } else {
+                        app.pr_create_candidates().len()
+                    };
+                    if let Some(dialog) = &mut app.pr_create_dialog {
+                        if count > 0 {
+                            dialog.cursor = (dialog.cursor + 1).min(count - 1);
+                        }
+                    }
+                }
+                KeyCode::Up => dialog.cursor = dialog.cursor.saturating_sub(1),
+                KeyCode::Enter => match dialog.field {
+                    PRCreateField::Title => dialog.next_field(),
+                    PRCreateField::Description => dialog.insert('\n'),
+                    PRCreateField::Options => dialog.toggle_option(),
+                    _ => app.pick_pr_create_candidate(),
+                },
+                KeyCode::Char(' ') if dialog.field == PRCreateField::Options => {
+                    dialog.toggle_option()
+                }
+                KeyCode::Backspace => dialog.backspace(),
+                KeyCode::Char(c) if !ctrl => dialog.insert(c),
+                _ => {}
+            }
+        }
+
+        InputMode::PRComplete => {
+            let Some(dialog) = &mut app.pr_complete_dialog else {
+                app.input_mode = InputMode::Normal;
+                return Ok(false);
+            };
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            match key.code {
+                KeyCode::Esc => {
+                    app.pr_complete_dialog = None;
+                    app.input_mode = InputMode::Normal;
+                }
+                KeyCode::Char('s') if ctrl => app.submit_pr_complete(),
+                KeyCode::Tab => dialog.next_field(),
+                KeyCode::BackTab => dialog.prev_field(),
+                KeyCode::Down => dialog.move_cursor(1),
+                KeyCode::Up => dialog.move_cursor(-1),
+                KeyCode::Enter => match dialog.field {
+                    PRCompleteField::Message => dialog.insert('\n'),
+                    PRCompleteField::Options => dialog.toggle_option(),
+                    _ => dialog.next_field(),
+                },
+                KeyCode::Char(' ') if dialog.field == PRCompleteField::Options => {
+                    dialog.toggle_option()
+                }
+                KeyCode::Backspace => dialog.backspace(),
+                KeyCode::Char(c) if !ctrl => dialog.insert(c),
+                _ => {}
+            }
+        }
+
+        InputMode::WorkItemForm => {
+            let Some(form) = &mut app.work_item_form else {
+                app.input_mode = InputMode::Normal;
+                return Ok(false);
+            };
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            if form.editing {
+                match key.code {
+                    KeyCode::Char('s') if ctrl => app.save_work_item_form().await,
+                    KeyCode::Enter | KeyCode::Tab => form.finish_edit(),
+                    KeyCode::Esc => form.cancel_edit(),
+                    KeyCode::Backspace => form.backspace(),
+                    KeyCode::Char(c) if !ctrl => form.insert(c),
+                    _ => {}
+                }
+                return Ok(false);
+            }
+            match key.code {
+                KeyCode::Esc => app.discard_work_item_form(),
+                KeyCode::Char('s') if ctrl => app.save_work_item_form().await,
+                KeyCode::Enter => {
+                    if !form.start_edit() {
+                        let (cols, rows) = crossterm::terminal::size().unwrap_or((80, 24));
+                        if let Err(e) = app.edit_form_field_in_editor(cols, rows) {
+                            app.set_error(format!("Failed to open editor: {e}"));
+                        }
+                    }
+                }
+                _ if app.keymap.is(app.current_view, Action::Down, &key) => form.move_selection(1),
+                _ if app.keymap.is(app.current_view, Action::Up, &key) => form.move_selection(-1),
+                _ => {}
+            }
+        }
+
+        InputMode::WorkItemCreate => {
+            let Some(dialog) = &mut app.work_item_create_dialog else {
+                app.input_mode = InputMode::Normal;
+                return Ok(false);
+            };
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            let candidates = dialog.assignee_candidates(&app.users);
+            match key.code {
+                KeyCode::Esc => {
+                    app.work_item_create_dialog = None;
+                    app.input_mode = InputMode::Normal;
+                }
+                KeyCode::Char('s') if ctrl => app.submit_work_item_create().await,
+                KeyCode::Tab => dialog.next_field(),
+                KeyCode::BackTab => dialog.prev_field(),
+                KeyCode::Down => dialog.move_cursor(1, candidates.len()),
+                KeyCode::Up => dialog.move_cursor(-1, candidates.len()),
+                KeyCode::Enter => match dialog.field {
+                    WorkItemCreateField::Titles => dialog.insert('\n'),
+                    WorkItemCreateField::Assignee => match candidates.get(dialog.cursor) {
+                        Some(user) => dialog.pick_assignee(user.clone()),
+                        None => dialog.next_field(),
+                    },
+                    _ => dialog.next_field(),
+                },
+                KeyCode::Backspace => dialog.backspace(),
+                KeyCode::Char(c) if !ctrl => dialog.insert(c),
+                _ => {}
+            }
+        }
+
+        InputMode::LinkWorkItem => {
+            let candidates = app.link_candidates().len();
+            let Some(dialog) = &mut app.link_dialog else {
+                app.input_mode = InputMode::Normal;
+                return Ok(false);
+            };
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            match key.code {
+                KeyCode::Esc => {
+                    app.link_dialog = None;
+                    app.input_mode = InputMode::Normal;
+                }
+                KeyCode::Tab => dialog.next_kind(),
+                KeyCode::BackTab => dialog.prev_kind(),
+                KeyCode::Down => dialog.move_cursor(1, candidates),
+                KeyCode::Up => dialog.move_cursor(-1, candidates),
+                KeyCode::Char('j') if ctrl => dialog.move_cursor(1, candidates),
+                KeyCode::Char('k') if ctrl => dialog.move_cursor(-1, candidates),
+                KeyCode::Enter => app.submit_link(),
+                KeyCode::Backspace => dialog.backspace(),
+                KeyCode::Char(c) if !ctrl => dialog.insert(c),
+                _ => {}
+            }
+        }
+
+        InputMode::QueryBrowser => {
+            let Some(browser) = &mut app.query_browser else {
+                app.input_mode = InputMode::Normal;
+                return Ok(false);
+            };
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            match key.code {
+                KeyCode::Esc => {
+                    app.query_browser = None;
+                    app.input_mode = InputMode::Normal;
+                }
+                KeyCode::Down => browser.move_cursor(1),
+                KeyCode::Up => browser.move_cursor(-1),
+                KeyCode::Char('j') if ctrl => browser.move_cursor(1),
+                KeyCode::Char('k') if ctrl => browser.move_cursor(-1),
+                KeyCode::Char('e') if ctrl => {
+                    browser.open_editor();
+                    app.input_mode = InputMode::QueryEditor;
+                }
+                KeyCode::Enter => match browser.selected() {
+                    Some(QueryRow::NewQuery) => {
+                        browser.open_editor();
+                        app.input_mode = InputMode::QueryEditor;
+                    }
+                    Some(QueryRow::Entry(entry)) => {
+                        if let Some(wiql) = entry.wiql.clone() {
+                            let query = SavedQuery {
+                                name: entry.name.clone(),
+                                wiql,
+                            };
+                            app.run_query(query).await;
+                        }
+                    }
+                    None => {}
+                },
+                KeyCode::Backspace => browser.backspace(),
+                KeyCode::Char(c) if !ctrl => browser.insert(c),
+                _ => {}
+            }
+        }
+
+        InputMode::QueryEditor => {
+            let Some(editor) = app.query_browser.as_mut().and_then(|b| b.editor.as_mut()) else {
+                app.input_mode = InputMode::Normal;
+                return Ok(false);
+            };
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            match key.code {
+                KeyCode::Esc => {
+                    if let Some(browser) = &mut app.query_browser {
+                        browser.editor = None;
+                    }
+                    app.input_mode = InputMode::QueryBrowser;
+                }
+                KeyCode::Char('s') if ctrl => app.run_query_editor().await,
+                KeyCode::Tab | KeyCode::BackTab => editor.toggle_field(),
+                KeyCode::Enter => editor.insert('\n'),
+                KeyCode::Backspace => editor.backspace(),
+                KeyCode::Char(c) if !ctrl => editor.insert(c),
+                _ => {}
+            }
+        }
+
+        InputMode::PRComment => match key.code {
+            KeyCode::Esc => {
+                app.pr_comment_draft = None;
+                app.input_mode = InputMode::Normal;
+            }
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.submit_pr_comment();
+            }
+            KeyCode::Enter => {
+                if let Some(draft) = &mut app.pr_comment_draft {
+                    draft.text.push('\n');
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(draft) = &mut app.pr_comment_draft {
+                    draft.text.pop();
+                }
+            }
+            KeyCode::Char(c) => {
+                if let Some(draft) = &mut app.pr_comment_draft {
+                    draft.text.push(c);
+                }
+            }
+            _ => {}
+        },
+
+        InputMode::CommentComposer => {
+            let Some(draft) = &mut app.comment_draft else {
+                app.input_mode = InputMode::Normal;
+                return Ok(false);
+            };
+            let suggestions = draft.suggestions(&app.users).len();
+            match key.code {
+                KeyCode::Esc => {
+                    app.comment_draft = None;
+                    app.input_mode = InputMode::Normal;
+                }
+                KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    app.submit_comment();
+                }
+                KeyCode::Tab if suggestions > 0 => {
+                    if let Some(user) = draft.suggestions(&app.users).get(draft.suggestion) {
+                        let user = (*user).clone();
+                        draft.accept_mention(&user);
+                    }
+                }
+                KeyCode::Down if suggestions > 0 => draft.next_suggestion(suggestions),
+                KeyCode::Up if suggestions > 0 => draft.prev_suggestion(suggestions),
+                KeyCode::Enter => draft.push('\n'),
+                KeyCode::Backspace => draft.pop(),
+                KeyCode::Char(c) => draft.push(c),
+                _ => {}
+            }
+        }
+
         InputMode::ProjectSelect => {
             match key.code {
                 KeyCode::Esc => app.input_mode = InputMode::Normal,
-                KeyCode::Char('j') | KeyCode::Down => app.dropdown_next(app.config.projects.len()),
-                KeyCode::Char('k') | KeyCode::Up => app.dropdown_prev(app.config.projects.len()),
+                _ if app.keymap.is(app.current_view, Action::Down, &key) => {
+                    app.dropdown_next(app.config.projects.len())
+                }
+                _ if app.keymap.is(app.current_view, Action::Up, &key) => {
+                    app.dropdown_prev(app.config.projects.len())
+                }
                 KeyCode::Enter => {
                     if let Some(idx) = app.dropdown_list_state.selected() {
                         app.current_project_idx = idx;
+                        app.clear_active_query();
                         app.input_mode = InputMode::Normal;
                         // Persist last project choice
                         if let Some(p) = app.current_project() {
-                            let _ = crate::cache::save_last_project(&p.name);
+                            let _ = app.cache.save_last_project(&p.name);
                         }
                         app.set_loading(true, "Loading project...");
                         let _ = app.load_sprints().await;
                         let _ = app.load_work_items().await;
                         let _ = app.load_users().await;
                         app.set_loading(false, "");
+                        app.boards = None;
+                        app.collapsed_lanes.clear();
+                        app.burndown = None;
+                        app.work_item_history.clear();
+                        app.history_rx = None;
+                        app.clear_comments();
+                        if app.current_view == View::Board {
+                            app.load_boards().await;
+                        }
                     }
                 }
                 _ => {}
@@ -279,6 +741,17 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                 }
                 KeyCode::Down => app.dropdown_next(states.len()),
                 KeyCode::Up => app.dropdown_prev(states.len()),
+                KeyCode::Enter if app.has_marks() => {
+                    if let Some(state) = app
+                        .dropdown_list_state
+                        .selected()
+                        .and_then(|i| states.get(i))
+                    {
+                        app.start_bulk(BulkOperation::SetState(state.clone()));
+                    }
+                    app.filter_input.clear();
+                    app.input_mode = InputMode::Normal;
+                }
                 KeyCode::Enter => {
                     if let (Some(idx), Some(work_item)) =
                         (app.dropdown_list_state.selected(), app.selected_work_item())
@@ -286,7 +759,9 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                         if let Some(state) = states.get(idx) {
                             let id = work_item.item.id;
                             if let Some(client) = app.client() {
-                                match client.update_work_item(id, "state", state).await {
+                                let update =
+                                    FieldUpdate::set(WorkItemFields::STATE, state.as_str());
+                                match client.update_work_item(id, &[update]).await {
                                     Ok(_) => {
                                         app.set_status(format!("Updated state to {state}"));
                                         let _ = app.load_work_items().await;
@@ -320,6 +795,17 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                 }
                 KeyCode::Down => app.dropdown_next(assignees.len()),
                 KeyCode::Up => app.dropdown_prev(assignees.len()),
+                KeyCode::Enter if app.has_marks() => {
+                    if let Some(user) = app
+                        .dropdown_list_state
+                        .selected()
+                        .and_then(|i| assignees.get(i))
+                    {
+                        app.start_bulk(BulkOperation::Assign((*user).clone()));
+                    }
+                    app.filter_input.clear();
+                    app.input_mode = InputMode::Normal;
+                }
                 KeyCode::Enter => {
                     if let (Some(idx), Some(work_item)) =
                         (app.dropdown_list_state.selected(), app.selected_work_item())
@@ -327,10 +813,11 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                         if let Some(user) = assignees.get(idx) {
                             let id = work_item.item.id;
                             if let Some(client) = app.client() {
-                                match client
-                                    .update_work_item(id, "assigned-to", &user.unique_name)
-                                    .await
-                                {
+                                let update = FieldUpdate::set(
+                                    WorkItemFields::ASSIGNED_TO,
+                                    user.unique_name.as_str(),
+                                );
+                                match client.update_work_item(id, &[update]).await {
                                     Ok(_) => {
                                         app.set_status(format!(
                                             "Assigned to {}",
@@ -358,6 +845,51 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
             }
         }
 
+        InputMode::EditTags => match key.code {
+            KeyCode::Esc => {
+                app.filter_input.clear();
+                app.input_mode = InputMode::Normal;
+            }
+            KeyCode::Enter => {
+                match BulkOperation::parse_tags(&app.filter_input) {
+                    Some(operation) => app.start_bulk(operation),
+                    None => app.set_error("Nothing to change - use +tag or -tag"),
+                }
+                app.filter_input.clear();
+                app.input_mode = InputMode::Normal;
+            }
+            KeyCode::Backspace => {
+                app.filter_input.pop();
+            }
+            KeyCode::Char(c) => app.filter_input.push(c),
+            _ => {}
+        },
+
+        InputMode::MoveIteration => match key.code {
+            KeyCode::Esc => app.input_mode = InputMode::Normal,
+            _ if app.keymap.is(app.current_view, Action::Down, &key) => {
+                app.dropdown_next(app.sprints.len())
+            }
+            _ if app.keymap.is(app.current_view, Action::Up, &key) => {
+                app.dropdown_prev(app.sprints.len())
+            }
+            KeyCode::Enter => {
+                app.input_mode = InputMode::Normal;
+                if let Some(sprint) = app
+                    .dropdown_list_state
+                    .selected()
+                    .and_then(|i| app.sprints.get(i))
+                {
+                    let operation = BulkOperation::MoveIteration {
+                        path: sprint.path.clone(),
+                        name: sprint.name.clone(),
+                    };
+                    app.start_bulk(operation);
+                }
+            }
+            _ => {}
+        },
+
         InputMode::Search => {
             match key.code {
                 KeyCode::Esc => {
@@ -400,6 +932,7 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                             match app.pr_drill_down {
                                 crate::app::PRDrillDown::Repos => app.selected_repo_idx = 0,
                                 crate::app::PRDrillDown::PRs => app.selected_pr_idx = 0,
+                                crate::app::PRDrillDown::Files => app.select_pr_file(0),
                             }
                         }
                         _ => {
@@ -443,6 +976,7 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                             match app.pr_drill_down {
                                 crate::app::PRDrillDown::Repos => app.selected_repo_idx = 0,
                                 crate::app::PRDrillDown::PRs => app.selected_pr_idx = 0,
+                                crate::app::PRDrillDown::Files => app.select_pr_file(0),
                             }
                         }
                         _ => {
@@ -561,7 +1095,7 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                     app.release_trigger_dialog = None;
                     app.input_mode = InputMode::Normal;
                 }
-                KeyCode::Char('j') | KeyCode::Down => {
+                _ if app.keymap.is(app.current_view, Action::Down, &key) => {
                     if let Some(dialog) = &mut app.release_trigger_dialog {
                         if !dialog.stages.is_empty() {
                             dialog.selected_idx =
@@ -569,7 +1103,7 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                         }
                     }
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
+                _ if app.keymap.is(app.current_view, Action::Up, &key) => {
                     if let Some(dialog) = &mut app.release_trigger_dialog {
                         dialog.selected_idx = dialog.selected_idx.saturating_sub(1);
                     }
@@ -610,6 +1144,139 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
             }
         }
 
+        InputMode::PipelineTriggerDialog => {
+            let Some(dialog) = &mut app.pipeline_trigger_dialog else {
+                app.input_mode = InputMode::Normal;
+                return Ok(false);
+            };
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            if dialog.ref_query.is_some() {
+                // Branch / tag picker
+                match key.code {
+                    KeyCode::Esc => dialog.ref_query = None,
+                    KeyCode::Enter => dialog.pick_ref(),
+                    KeyCode::Down => dialog.move_ref_cursor(1),
+                    KeyCode::Up => dialog.move_ref_cursor(-1),
+                    KeyCode::Char('j') if ctrl => dialog.move_ref_cursor(1),
+                    KeyCode::Char('k') if ctrl => dialog.move_ref_cursor(-1),
+                    KeyCode::Backspace => dialog.backspace(),
+                    KeyCode::Char(c) if !ctrl => dialog.insert(c),
+                    _ => {}
+                }
+            } else if dialog.editing.is_some() {
+                match key.code {
+                    KeyCode::Esc => dialog.editing = None,
+                    KeyCode::Enter => dialog.commit_edit(),
+                    KeyCode::Backspace => dialog.backspace(),
+                    KeyCode::Char(c) if !ctrl => dialog.insert(c),
+                    _ => {}
+                }
+            } else {
+                match key.code {
+                    KeyCode::Esc => {
+                        app.pipeline_trigger_dialog = None;
+                        app.pipeline_trigger_rx = None;
+                        app.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Char('s') if ctrl => app.submit_pipeline_trigger(),
+                    _ if dialog.loading => {}
+                    _ if app.keymap.is(app.current_view, Action::Down, &key) => {
+                        dialog.move_cursor(1)
+                    }
+                    _ if app.keymap.is(app.current_view, Action::Up, &key) => {
+                        dialog.move_cursor(-1)
+                    }
+                    KeyCode::Enter => dialog.activate(),
+                    KeyCode::Char(' ') => dialog.toggle(),
+                    _ => {}
+                }
+            }
+        }
+
+        InputMode::LogSearch => match key.code {
+            KeyCode::Esc => {
+                app.log_view.cancel_search();
+                app.input_mode = InputMode::Normal;
+            }
+            KeyCode::Enter => app.finish_log_search(),
+            KeyCode::Backspace => app.log_view.backspace(),
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.log_view.insert(c)
+            }
+            _ => {}
+        },
+
+        InputMode::FailureSummary => {
+            let view = app.current_view;
+            if [Action::Back, Action::FailureSummary, Action::Quit]
+                .iter()
+                .any(|a| app.keymap.is(view, *a, &key))
+            {
+                app.close_failure_summary();
+            } else if key.code == KeyCode::Enter {
+                app.open_failure_issue();
+            } else if let Some(summary) = &mut app.failure_summary {
+                if app.keymap.is(view, Action::Down, &key) {
+                    summary.next();
+                } else if app.keymap.is(view, Action::Up, &key) {
+                    summary.prev();
+                }
+            }
+        }
+
+        InputMode::Artifacts => {
+            let view = app.current_view;
+            let asking = app
+                .artifact_browser
+                .as_ref()
+                .is_some_and(|b| b.destination.is_some());
+            if asking {
+                match key.code {
+                    KeyCode::Esc => {
+                        if let Some(browser) = &mut app.artifact_browser {
+                            browser.destination = None;
+                        }
+                    }
+                    KeyCode::Enter => app.start_artifact_download(),
+                    KeyCode::Backspace => {
+                        if let Some(browser) = &mut app.artifact_browser {
+                            browser.backspace();
+                        }
+                    }
+                    KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        if let Some(browser) = &mut app.artifact_browser {
+                            browser.insert(c);
+                        }
+                    }
+                    _ => {}
+                }
+            } else if [Action::Artifacts, Action::Quit]
+                .iter()
+                .any(|a| app.keymap.is(view, *a, &key))
+            {
+                app.close_artifacts();
+            } else if app.keymap.is(view, Action::Back, &key)
+                || app.keymap.is(view, Action::Left, &key)
+                || key.code == KeyCode::Backspace
+            {
+                // Up a folder; Esc leaves the panel from the list of artifacts
+                let up = app.artifact_browser.as_mut().is_some_and(|b| b.up());
+                if !up && app.keymap.is(view, Action::Back, &key) {
+                    app.close_artifacts();
+                }
+            } else if key.code == KeyCode::Enter || app.keymap.is(view, Action::Right, &key) {
+                app.artifacts_enter();
+            } else if key.code == KeyCode::Char('d') {
+                app.ask_download_destination();
+            } else if let Some(browser) = &mut app.artifact_browser {
+                if app.keymap.is(view, Action::Down, &key) {
+                    browser.next();
+                } else if app.keymap.is(view, Action::Up, &key) {
+                    browser.prev();
+                }
+            }
+        }
+
         InputMode::ApprovalConfirm => {
             // Placeholder for approval confirm mode
             if key.code == KeyCode::Esc {
@@ -618,195 +1285,206 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
         }
 
         InputMode::Normal => {
-            // Check for Ctrl modifiers first
-            if key.modifiers.contains(KeyModifiers::CONTROL) {
-                match key.code {
-                    KeyCode::Char('d') => {
-                        match app.current_view {
-                            View::Tasks => match app.focus {
-                                Focus::WorkItems => app.list_jump_down(),
-                                Focus::Preview => {
-                                    if app.preview_tab == crate::app::PreviewTab::References {
-                                        app.relations_page_down();
-                                    } else {
-                                        app.preview_scroll = app
-                                            .preview_scroll
-                                            .saturating_add(20)
-                                            .min(app.preview_scroll_max);
-                                    }
+            let Some(action) = resolve_action(app, &key) else {
+                return Ok(false);
+            };
+            if app.current_view == View::Board && handle_board_action(app, action).await {
+                return Ok(false);
+            }
+
+            match action {
+                // Paging
+                Action::PageDown => {
+                    let jump = app.settings().page_jump;
+                    match app.current_view {
+                        View::Board => {} // See handle_board_action
+                        View::Tasks => match app.focus {
+                            Focus::WorkItems => app.list_jump_down(),
+                            Focus::Preview => {
+                                if app.preview_tab == crate::app::PreviewTab::References {
+                                    app.relations_page_down();
+                                } else if app.preview_tab == crate::app::PreviewTab::Discussion {
+                                    app.comments_page_down();
+                                } else {
+                                    app.preview_scroll = app
+                                        .preview_scroll
+                                        .saturating_add(20)
+                                        .min(app.preview_scroll_max);
                                 }
-                            },
-                            View::PRs => match app.pr_focus {
-                                crate::app::PRFocus::Active
-                                | crate::app::PRFocus::Mine
-                                | crate::app::PRFocus::Completed
-                                | crate::app::PRFocus::Abandoned => match app.pr_drill_down {
-                                    crate::app::PRDrillDown::Repos => {
-                                        if !app.repositories.is_empty() {
-                                            app.selected_repo_idx = (app.selected_repo_idx + 10)
-                                                .min(app.repositories.len() - 1);
-                                        }
+                            }
+                        },
+                        View::PRs => match app.pr_focus {
+                            crate::app::PRFocus::Active
+                            | crate::app::PRFocus::Mine
+                            | crate::app::PRFocus::Completed
+                            | crate::app::PRFocus::Abandoned => match app.pr_drill_down {
+                                crate::app::PRDrillDown::Repos => {
+                                    if !app.repositories.is_empty() {
+                                        app.selected_repo_idx = (app.selected_repo_idx + jump)
+                                            .min(app.repositories.len() - 1);
                                     }
-                                    crate::app::PRDrillDown::PRs => {
-                                        if !app.pull_requests().is_empty() {
-                                            app.selected_pr_idx = (app.selected_pr_idx + 10)
-                                                .min(app.pull_requests().len() - 1);
-                                        }
+                                }
+                                crate::app::PRDrillDown::PRs => {
+                                    if !app.pull_requests().is_empty() {
+                                        app.selected_pr_idx = (app.selected_pr_idx + jump)
+                                            .min(app.pull_requests().len() - 1);
                                     }
-                                },
-                                crate::app::PRFocus::Preview => {
+                                }
+                                crate::app::PRDrillDown::Files => app.move_pr_file(jump as isize),
+                            },
+                            crate::app::PRFocus::Preview => {
+                                if let Some(diff) = app.focused_file_diff_mut() {
+                                    diff.move_cursor(jump as isize);
+                                } else {
                                     app.pr_preview_scroll =
                                         app.pr_preview_scroll.saturating_add(10);
                                 }
-                            },
-                            View::CICD => {
-                                // Page down based on focus
-                                if app.cicd_focus == crate::app::CICDFocus::Preview {
-                                    // Scroll preview pane or logs
-                                    if app.release_drill_down == crate::app::ReleaseDrillDown::Tasks
-                                        && !app.release_task_logs.is_empty()
-                                    {
-                                        app.log_scroll = app.log_scroll.saturating_add(20);
-                                    } else {
-                                        app.cicd_preview_scroll =
-                                            app.cicd_preview_scroll.saturating_add(10);
-                                    }
-                                } else if app.pipeline_drill_down
-                                    == crate::app::PipelineDrillDown::Tasks
-                                {
-                                    if !app.build_log_lines.is_empty() {
-                                        app.log_scroll = app.log_scroll.saturating_add(20);
-                                    } else {
-                                        let task_count = app.get_timeline_tasks().len();
-                                        if task_count > 0 {
-                                            app.selected_task_idx =
-                                                (app.selected_task_idx + 10).min(task_count - 1);
-                                        }
-                                    }
-                                } else if app.pipeline_drill_down
-                                    == crate::app::PipelineDrillDown::Runs
-                                {
-                                    if !app.pipeline_runs.is_empty() {
-                                        app.selected_pipeline_run_idx =
-                                            (app.selected_pipeline_run_idx + 10)
-                                                .min(app.pipeline_runs.len() - 1);
-                                    }
-                                } else if app.release_drill_down
-                                    == crate::app::ReleaseDrillDown::Tasks
-                                {
-                                    if !app.release_tasks.is_empty() {
-                                        app.selected_release_task_idx =
-                                            (app.selected_release_task_idx + 10)
-                                                .min(app.release_tasks.len() - 1);
-                                    }
-                                } else if app.release_drill_down
-                                    == crate::app::ReleaseDrillDown::Stages
+                            }
+                        },
+                        View::CICD => {
+                            // Page down based on focus
+                            if app.cicd_focus == crate::app::CICDFocus::Preview {
+                                // Scroll preview pane or logs
+                                if app.release_drill_down == crate::app::ReleaseDrillDown::Tasks
+                                    && !app.release_task_logs.is_empty()
                                 {
-                                    if !app.release_stages.is_empty() {
-                                        app.selected_release_stage_idx =
-                                            (app.selected_release_stage_idx + 10)
-                                                .min(app.release_stages.len() - 1);
+                                    app.log_scroll = app.log_scroll.saturating_add(20);
+                                } else {
+                                    app.cicd_preview_scroll =
+                                        app.cicd_preview_scroll.saturating_add(10);
+                                }
+                            } else if app.pipeline_drill_down
+                                == crate::app::PipelineDrillDown::Tasks
+                            {
+                                if !app.build_log_lines.is_empty() {
+                                    app.log_scroll = app.log_scroll.saturating_add(20);
+                                } else {
+                                    let task_count = app.get_timeline_tasks().len();
+                                    if task_count > 0 {
+                                        app.selected_task_idx =
+                                            (app.selected_task_idx + jump).min(task_count - 1);
                                     }
-                                } else if app.release_drill_down
-                                    == crate::app::ReleaseDrillDown::Items
-                                    && !app.release_list.is_empty()
-                                {
-                                    app.selected_release_item_idx = (app.selected_release_item_idx
-                                        + 10)
-                                        .min(app.release_list.len() - 1);
                                 }
+                            } else if app.pipeline_drill_down == crate::app::PipelineDrillDown::Runs
+                            {
+                                if !app.pipeline_runs.is_empty() {
+                                    app.selected_pipeline_run_idx = (app.selected_pipeline_run_idx
+                                        + jump)
+                                        .min(app.pipeline_runs.len() - 1);
+                                }
+                            } else if app.release_drill_down == crate::app::ReleaseDrillDown::Tasks
+                            {
+                                if !app.release_tasks.is_empty() {
+                                    app.selected_release_task_idx = (app.selected_release_task_idx
+                                        + jump)
+                                        .min(app.release_tasks.len() - 1);
+                                }
+                            } else if app.release_drill_down == crate::app::ReleaseDrillDown::Stages
+                            {
+                                if !app.release_stages.is_empty() {
+                                    app.selected_release_stage_idx =
+                                        (app.selected_release_stage_idx + jump)
+                                            .min(app.release_stages.len() - 1);
+                                }
+                            } else if app.release_drill_down == crate::app::ReleaseDrillDown::Items
+                                && !app.release_list.is_empty()
+                            {
+                                app.selected_release_item_idx = (app.selected_release_item_idx
+                                    + jump)
+                                    .min(app.release_list.len() - 1);
                             }
                         }
                     }
-                    KeyCode::Char('u') => {
-                        match app.current_view {
-                            View::Tasks => match app.focus {
-                                Focus::WorkItems => app.list_jump_up(),
-                                Focus::Preview => {
-                                    if app.preview_tab == crate::app::PreviewTab::References {
-                                        app.relations_page_up();
-                                    } else {
-                                        app.preview_scroll = app.preview_scroll.saturating_sub(20);
-                                    }
+                }
+                Action::PageUp => {
+                    let jump = app.settings().page_jump;
+                    match app.current_view {
+                        View::Board => {} // See handle_board_action
+                        View::Tasks => match app.focus {
+                            Focus::WorkItems => app.list_jump_up(),
+                            Focus::Preview => {
+                                if app.preview_tab == crate::app::PreviewTab::References {
+                                    app.relations_page_up();
+                                } else if app.preview_tab == crate::app::PreviewTab::Discussion {
+                                    app.comments_page_up();
+                                } else {
+                                    app.preview_scroll = app.preview_scroll.saturating_sub(20);
+                                }
+                            }
+                        },
+                        View::PRs => match app.pr_focus {
+                            crate::app::PRFocus::Active
+                            | crate::app::PRFocus::Mine
+                            | crate::app::PRFocus::Completed
+                            | crate::app::PRFocus::Abandoned => match app.pr_drill_down {
+                                crate::app::PRDrillDown::Repos => {
+                                    app.selected_repo_idx =
+                                        app.selected_repo_idx.saturating_sub(jump);
+                                }
+                                crate::app::PRDrillDown::PRs => {
+                                    app.selected_pr_idx = app.selected_pr_idx.saturating_sub(jump);
+                                }
+                                crate::app::PRDrillDown::Files => {
+                                    app.move_pr_file(-(jump as isize))
                                 }
                             },
-                            View::PRs => match app.pr_focus {
-                                crate::app::PRFocus::Active
-                                | crate::app::PRFocus::Mine
-                                | crate::app::PRFocus::Completed
-                                | crate::app::PRFocus::Abandoned => match app.pr_drill_down {
-                                    crate::app::PRDrillDown::Repos => {
-                                        app.selected_repo_idx =
-                                            app.selected_repo_idx.saturating_sub(10);
-                                    }
-                                    crate::app::PRDrillDown::PRs => {
-                                        app.selected_pr_idx =
-                                            app.selected_pr_idx.saturating_sub(10);
-                                    }
-                                },
-                                crate::app::PRFocus::Preview => {
+                            crate::app::PRFocus::Preview => {
+                                if let Some(diff) = app.focused_file_diff_mut() {
+                                    diff.move_cursor(-(jump as isize));
+                                } else {
                                     app.pr_preview_scroll =
                                         app.pr_preview_scroll.saturating_sub(10);
                                 }
-                            },
-                            View::CICD => {
-                                // Page up based on focus
-                                if app.cicd_focus == crate::app::CICDFocus::Preview {
-                                    // Scroll preview pane or logs
-                                    if app.release_drill_down == crate::app::ReleaseDrillDown::Tasks
-                                        && !app.release_task_logs.is_empty()
-                                    {
-                                        app.log_scroll = app.log_scroll.saturating_sub(20);
-                                    } else {
-                                        app.cicd_preview_scroll =
-                                            app.cicd_preview_scroll.saturating_sub(10);
-                                    }
-                                } else if app.pipeline_drill_down
-                                    == crate::app::PipelineDrillDown::Tasks
-                                {
-                                    if !app.build_log_lines.is_empty() {
-                                        app.log_scroll = app.log_scroll.saturating_sub(20);
-                                    } else {
-                                        app.selected_task_idx =
-                                            app.selected_task_idx.saturating_sub(10);
-                                    }
-                                } else if app.pipeline_drill_down
-                                    == crate::app::PipelineDrillDown::Runs
-                                {
-                                    app.selected_pipeline_run_idx =
-                                        app.selected_pipeline_run_idx.saturating_sub(10);
-                                } else if app.release_drill_down
-                                    == crate::app::ReleaseDrillDown::Tasks
-                                {
-                                    app.selected_release_task_idx =
-                                        app.selected_release_task_idx.saturating_sub(10);
-                                } else if app.release_drill_down
-                                    == crate::app::ReleaseDrillDown::Stages
-                                {
-                                    app.selected_release_stage_idx =
-                                        app.selected_release_stage_idx.saturating_sub(10);
-                                } else if app.release_drill_down
-                                    == crate::app::ReleaseDrillDown::Items
+                            }
+                        },
+                        View::CICD => {
+                            // Page up based on focus
+                            if app.cicd_focus == crate::app::CICDFocus::Preview {
+                                // Scroll preview pane or logs
+                                if app.release_drill_down == crate::app::ReleaseDrillDown::Tasks
+                                    && !app.release_task_logs.is_empty()
                                 {
-                                    app.selected_release_item_idx =
-                                        app.selected_release_item_idx.saturating_sub(10);
+                                    app.log_scroll = app.log_scroll.saturating_sub(20);
+                                } else {
+                                    app.cicd_preview_scroll =
+                                        app.cicd_preview_scroll.saturating_sub(10);
+                                }
+                            } else if app.pipeline_drill_down
+                                == crate::app::PipelineDrillDown::Tasks
+                            {
+                                if !app.build_log_lines.is_empty() {
+                                    app.log_scroll = app.log_scroll.saturating_sub(20);
+                                } else {
+                                    app.selected_task_idx =
+                                        app.selected_task_idx.saturating_sub(jump);
                                 }
+                            } else if app.pipeline_drill_down == crate::app::PipelineDrillDown::Runs
+                            {
+                                app.selected_pipeline_run_idx =
+                                    app.selected_pipeline_run_idx.saturating_sub(jump);
+                            } else if app.release_drill_down == crate::app::ReleaseDrillDown::Tasks
+                            {
+                                app.selected_release_task_idx =
+                                    app.selected_release_task_idx.saturating_sub(jump);
+                            } else if app.release_drill_down == crate::app::ReleaseDrillDown::Stages
+                            {
+                                app.selected_release_stage_idx =
+                                    app.selected_release_stage_idx.saturating_sub(jump);
+                            } else if app.release_drill_down == crate::app::ReleaseDrillDown::Items
+                            {
+                                app.selected_release_item_idx =
+                                    app.selected_release_item_idx.saturating_sub(jump);
                             }
                         }
                     }
-                    KeyCode::Char('c') => return Ok(true), // Ctrl+C to quit
-                    _ => {}
                 }
-                return Ok(false);
-            }
 
-            match key.code {
                 // View switching
-                KeyCode::Char('1') => {
-                    app.current_view = crate::app::View::Tasks;
+                Action::ViewTasks => {
+                    app.switch_work_item_view(crate::app::View::Tasks);
                     app.set_status("Tasks view");
                 }
-                KeyCode::Char('2') => {
+                Action::ViewPRs => {
                     app.current_view = crate::app::View::PRs;
                     // Start background PR loader if not already loaded
                     if app.repositories.is_empty() && !app.pr_loading {
@@ -814,7 +1492,7 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                     }
                     app.set_status("PRs view");
                 }
-                KeyCode::Char('3') => {
+                Action::ViewCICD => {
                     app.current_view = crate::app::View::CICD;
                     // Start background CI/CD loader if not already loaded
                     if app.pipelines.is_empty() && !app.cicd_loading {
@@ -822,18 +1500,31 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                     }
                     app.set_status("CI/CD view");
                 }
+                Action::ViewBoard => {
+                    app.switch_work_item_view(crate::app::View::Board);
+                    app.focus = Focus::WorkItems;
+                    if app.boards.is_none() {
+                        app.load_boards().await;
+                    } else {
+                        app.select_board_card();
+                    }
+                    app.set_status("Board view");
+                }
 
-                KeyCode::Char('q') => return Ok(true),
-                KeyCode::Char('?') => app.input_mode = InputMode::Help,
+                Action::Quit => return Ok(true),
+                Action::Help => app.input_mode = InputMode::Help,
 
                 // Navigation
-                KeyCode::Char('j') | KeyCode::Down => {
+                Action::Down => {
                     match app.current_view {
+                        View::Board => {} // See handle_board_action
                         View::Tasks => match app.focus {
                             Focus::WorkItems => app.list_next(),
                             Focus::Preview => {
                                 if app.preview_tab == crate::app::PreviewTab::References {
                                     app.relations_next();
+                                } else if app.preview_tab == crate::app::PreviewTab::Discussion {
+                                    app.comments_next();
                                 } else {
                                     app.scroll_preview_down();
                                 }
@@ -856,9 +1547,20 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                                             .min(app.pull_requests().len() - 1);
                                     }
                                 }
+                                crate::app::PRDrillDown::Files => app.move_pr_file(1),
                             },
                             crate::app::PRFocus::Preview => {
-                                app.pr_preview_scroll = app.pr_preview_scroll.saturating_add(1);
+                                if let Some(diff) = app.focused_file_diff_mut() {
+                                    diff.move_cursor(1);
+                                } else if app.pr_preview_tab == crate::app::PRPreviewTab::Threads {
+                                    let count = app.visible_pr_threads().len();
+                                    if count > 0 {
+                                        app.selected_thread_idx =
+                                            (app.selected_thread_idx + 1).min(count - 1);
+                                    }
+                                } else {
+                                    app.pr_preview_scroll = app.pr_preview_scroll.saturating_add(1);
+                                }
                             }
                         },
                         View::CICD => {
@@ -936,13 +1638,16 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                         }
                     }
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
+                Action::Up => {
                     match app.current_view {
+                        View::Board => {} // See handle_board_action
                         View::Tasks => match app.focus {
                             Focus::WorkItems => app.list_prev(),
                             Focus::Preview => {
                                 if app.preview_tab == crate::app::PreviewTab::References {
                                     app.relations_prev();
+                                } else if app.preview_tab == crate::app::PreviewTab::Discussion {
+                                    app.comments_prev();
                                 } else {
                                     app.scroll_preview_up();
                                 }
@@ -959,9 +1664,17 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                                 crate::app::PRDrillDown::PRs => {
                                     app.selected_pr_idx = app.selected_pr_idx.saturating_sub(1);
                                 }
+                                crate::app::PRDrillDown::Files => app.move_pr_file(-1),
                             },
                             crate::app::PRFocus::Preview => {
-                                app.pr_preview_scroll = app.pr_preview_scroll.saturating_sub(1);
+                                if let Some(diff) = app.focused_file_diff_mut() {
+                                    diff.move_cursor(-1);
+                                } else if app.pr_preview_tab == crate::app::PRPreviewTab::Threads {
+                                    app.selected_thread_idx =
+                                        app.selected_thread_idx.saturating_sub(1);
+                                } else {
+                                    app.pr_preview_scroll = app.pr_preview_scroll.saturating_sub(1);
+                                }
                             }
                         },
                         View::CICD => {
@@ -1028,12 +1741,13 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                         }
                     }
                 }
-                KeyCode::Char('g') => app.list_top(),
-                KeyCode::Char('G') => app.list_bottom(),
+                Action::Top => app.list_top(),
+                Action::Bottom => app.list_bottom(),
 
                 // Focus switching (view-aware)
-                KeyCode::Char('h') => {
+                Action::Left => {
                     match app.current_view {
+                        View::Board => {} // See handle_board_action
                         View::Tasks => app.focus = Focus::WorkItems,
                         View::PRs => {
                             if app.pr_focus == crate::app::PRFocus::Preview {
@@ -1045,13 +1759,16 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                                 app.pr_focus = prev;
                                 app.pr_last_list_focus = prev;
                                 app.restore_pr_idx();
+                            } else if app.pr_drill_down == crate::app::PRDrillDown::Files {
+                                app.cycle_pr_iteration(false);
                             }
                         }
                         View::CICD => app.cicd_focus = crate::app::CICDFocus::Pipelines,
                     }
                 }
-                KeyCode::Char('l') => {
+                Action::Right => {
                     match app.current_view {
+                        View::Board => {} // See handle_board_action
                         View::Tasks => app.focus = Focus::Preview,
                         View::PRs => {
                             if app.pr_drill_down == crate::app::PRDrillDown::PRs
@@ -1063,23 +1780,31 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                                 app.pr_focus = next;
                                 app.pr_last_list_focus = next;
                                 app.restore_pr_idx();
+                            } else if app.pr_drill_down == crate::app::PRDrillDown::Files
+                                && app.pr_focus.is_list()
+                            {
+                                app.cycle_pr_iteration(true);
                             }
                         }
                         View::CICD => app.cicd_focus = crate::app::CICDFocus::Releases,
                     }
                 }
-                KeyCode::Tab => {
+                Action::NextTab => {
                     if app.current_view == View::PRs {
                         app.pr_preview_tab = app.pr_preview_tab.next();
+                    } else if app.current_view == View::CICD {
+                        app.toggle_run_preview_tab();
                     } else {
                         app.next_tab();
                         // Reset relations selection when switching tabs
                         app.relations_list_state.select(None);
                     }
                 }
-                KeyCode::BackTab => {
+                Action::PrevTab => {
                     if app.current_view == View::PRs {
                         app.pr_preview_tab = app.pr_preview_tab.prev();
+                    } else if app.current_view == View::CICD {
+                        app.toggle_run_preview_tab();
                     } else {
                         app.prev_tab();
                         app.relations_list_state.select(None);
@@ -1087,8 +1812,9 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                 }
 
                 // Actions
-                KeyCode::Enter => {
+                Action::Expand => {
                     match app.current_view {
+                        View::Board => {} // See handle_board_action
                         View::Tasks => {
                             match app.focus {
                                 Focus::WorkItems => app.toggle_expand(),
@@ -1119,6 +1845,10 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                                                 app.pr_focus = crate::app::PRFocus::Preview;
                                             }
                                         }
+                                        crate::app::PRDrillDown::Files => {
+                                            // Show the selected file's diff
+                                            app.open_pr_file_diff();
+                                        }
                                     }
                                 }
                                 crate::app::PRFocus::Preview => {}
@@ -1249,14 +1979,21 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                 }
 
                 // Escape handling
-                KeyCode::Esc => {
+                Action::Back => {
                     match app.current_view {
-                        View::Tasks => {} // No action in Tasks view
+                        View::Tasks => app.clear_marks(),
+                        View::Board => {}
                         View::PRs => {
-                            if app.pr_focus == crate::app::PRFocus::Preview {
+                            if let Some(diff) =
+                                app.focused_file_diff_mut().filter(|d| d.anchor.is_some())
+                            {
+                                // Drop the line range before leaving the diff
+                                diff.anchor = None;
+                            } else if app.pr_focus == crate::app::PRFocus::Preview {
                                 app.pr_focus = app.pr_last_list_focus;
                             } else {
                                 match app.pr_drill_down {
+                                    crate::app::PRDrillDown::Files => app.close_pr_files(),
                                     crate::app::PRDrillDown::PRs => {
                                         app.pr_drill_down = crate::app::PRDrillDown::Repos;
                                         app.pr_active.clear();
@@ -1341,16 +2078,16 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                 }
 
                 // Task-specific keybindings
-                KeyCode::Char('t') => {
+                Action::ToggleAll => {
                     if app.current_view == View::Tasks {
                         app.toggle_expand_all();
                     }
                 }
 
-                KeyCode::Char('p') => {
+                Action::Pin => {
                     match app.current_view {
                         View::Tasks => app.toggle_pin(),
-                        View::PRs => {} // No pinning for PRs yet
+                        View::PRs | View::Board => {} // No pinning for PRs yet
                         View::CICD => {
                             // Only allow pinning at parent level (not in drill-down)
                             match app.cicd_focus {
@@ -1373,27 +2110,124 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                 }
 
                 // Modes - Sprint select is Tasks only, Project select works for both
-                KeyCode::Char('I') => {
-                    if app.current_view == View::Tasks {
+                Action::SelectSprint => {
+                    if app.current_view.shows_work_items() {
                         app.input_mode = InputMode::SprintSelect;
                         app.dropdown_list_state
                             .select(Some(app.selected_sprint_idx));
                     }
                 }
-                KeyCode::Char('P') => {
+                Action::SelectProject => {
                     app.input_mode = InputMode::ProjectSelect;
                     app.dropdown_list_state
                         .select(Some(app.current_project_idx));
                 }
-                KeyCode::Char('S') => {
-                    if app.current_view == View::Tasks && app.selected_work_item().is_some() {
+                Action::EditState => {
+                    if app.current_view.shows_work_items() && app.selected_work_item().is_some() {
                         app.input_mode = InputMode::EditState;
                         app.dropdown_list_state.select(Some(0));
                     }
                 }
-                KeyCode::Char('f') => {
+                Action::CreateWorkItem => {
+                    if app.current_view == View::Tasks {
+                        app.open_work_item_create();
+                    }
+                }
+                Action::EditTags => {
+                    if app.current_view == View::Tasks && app.selected_work_item().is_some() {
+                        app.filter_input.clear();
+                        app.input_mode = InputMode::EditTags;
+                    }
+                }
+                Action::MoveIteration => {
+                    if app.current_view == View::Tasks && app.selected_work_item().is_some() {
+                        app.input_mode = InputMode::MoveIteration;
+                        app.dropdown_list_state
+                            .select(Some(app.selected_sprint_idx));
+                    }
+                }
+                Action::Mark => {
+                    if app.current_view == View::Tasks {
+                        app.toggle_mark();
+                        app.list_next();
+                    }
+                }
+                Action::VisualSelect => {
+                    if app.current_view == View::Tasks {
+                        app.toggle_visual();
+                    }
+                }
+                Action::MarkChildren => {
+                    if app.current_view == View::Tasks {
+                        app.mark_children();
+                    }
+                }
+                Action::MarkAll => {
+                    if app.current_view == View::Tasks {
+                        app.mark_visible();
+                    }
+                }
+                // Board only, see handle_board_action
+                Action::MoveCardLeft | Action::MoveCardRight => {}
+                Action::Queries => {
+                    if app.current_view.shows_work_items() {
+                        app.set_loading(true, "Loading queries...");
+                        app.open_query_browser().await;
+                        app.set_loading(false, "");
+                    }
+                }
+                Action::SprintStats => {
+                    if app.current_view.shows_work_items() {
+                        app.open_sprint_stats();
+                    }
+                }
+                Action::TeamCapacity => {
+                    if app.current_view.shows_work_items() {
+                        app.open_team_capacity();
+                    }
+                }
+                Action::EditFields => {
+                    if app.current_view == View::Tasks
+                        && app.focus == Focus::Preview
+                        && app.preview_tab == crate::app::PreviewTab::Discussion
+                    {
+                        app.edit_selected_comment();
+                    } else if app.current_view == View::Tasks && app.selected_work_item().is_some()
+                    {
+                        app.open_work_item_form().await;
+                    }
+                }
+                Action::AddComment => {
+                    if app.current_view == View::Tasks {
+                        app.open_comment_composer();
+                    }
+                }
+                Action::DeleteComment => {
+                    if app.current_view == View::Tasks {
+                        if app.preview_tab == crate::app::PreviewTab::Discussion {
+                            app.confirm_delete_comment();
+                        } else {
+                            app.set_status("Open the Discussion tab to pick a comment");
+                        }
+                    }
+                }
+                Action::AddLink => {
+                    if app.current_view == View::Tasks {
+                        app.open_link_dialog().await;
+                    }
+                }
+                Action::RemoveLink => {
+                    if app.current_view == View::Tasks {
+                        if app.preview_tab == crate::app::PreviewTab::References {
+                            app.confirm_remove_link();
+                        } else {
+                            app.set_status("Open the References tab to pick a link");
+                        }
+                    }
+                }
+                Action::Search => {
                     match app.current_view {
-                        View::Tasks => {
+                        View::Tasks | View::Board => {
                             app.search_query.clear();
                             app.input_mode = InputMode::Search;
                         }
@@ -1407,7 +2241,7 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                         }
                     }
                 }
-                KeyCode::Char('R') => {
+                Action::ChangeRepo => {
                     if app.current_view == View::PRs
                         && app.pr_drill_down == crate::app::PRDrillDown::PRs
                     {
@@ -1427,14 +2261,17 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                         app.pr_focus = crate::app::PRFocus::Active;
                     }
                 }
-                KeyCode::Char('A') => {
-                    if app.current_view == View::Tasks
+                Action::EditAssignee => {
+                    if app.current_view.shows_work_items()
                         && app.selected_work_item().is_some()
                         && !app.users.is_empty()
                     {
                         app.input_mode = InputMode::EditAssignee;
                         app.dropdown_list_state.select(Some(0));
-                    } else if app.current_view == View::CICD
+                    }
+                }
+                Action::ApproveAll => {
+                    if app.current_view == View::CICD
                         && app.cicd_focus == crate::app::CICDFocus::Releases
                         && app.release_drill_down == crate::app::ReleaseDrillDown::Stages
                     {
@@ -1444,7 +2281,7 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                 }
 
                 // Edit/open log in nvim (CICD view only, when logs are available)
-                KeyCode::Char('e') => {
+                Action::OpenLog => {
                     if app.current_view == View::CICD
                         && app.pipeline_drill_down == crate::app::PipelineDrillDown::Tasks
                         && !app.build_log_lines.is_empty()
@@ -1458,7 +2295,7 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                 }
 
                 // Cancel pipeline run or release (C key in CICD view)
-                KeyCode::Char('C') => {
+                Action::Cancel => {
                     if app.current_view == View::CICD {
                         match app.cicd_focus {
                             crate::app::CICDFocus::Pipelines => {
@@ -1570,7 +2407,7 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                 }
 
                 // Trigger/Retrigger (T key in CICD view - context-sensitive)
-                KeyCode::Char('T') => {
+                Action::Trigger => {
                     if app.current_view == View::CICD {
                         match app.cicd_focus {
                             crate::app::CICDFocus::Pipelines => {
@@ -1608,6 +2445,16 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                                             app.set_status("Can only retrigger completed builds");
                                         }
                                     }
+                                } else if app.pipeline_drill_down
+                                    == crate::app::PipelineDrillDown::None
+                                {
+                                    // New run with parameters, variables and stages
+                                    if let Some(pipeline) =
+                                        app.pipelines.get(app.selected_pipeline_idx)
+                                    {
+                                        let (id, name) = (pipeline.id, pipeline.name.clone());
+                                        app.open_pipeline_trigger(id, name);
+                                    }
                                 }
                             }
                             crate::app::CICDFocus::Releases => {
@@ -1659,32 +2506,80 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                     }
                 }
 
-                // Approve stage (CICD view, Stages drill-down) or Filter assignee (Tasks view)
-                KeyCode::Char('a') => {
-                    match app.current_view {
-                        View::Tasks => {
-                            app.input_mode = InputMode::FilterAssignee;
-                            app.dropdown_list_state.select(Some(0));
-                        }
-                        View::PRs => {} // No assignee filter for PRs
-                        View::CICD => {
-                            if app.cicd_focus == crate::app::CICDFocus::Releases
-                                && app.release_drill_down == crate::app::ReleaseDrillDown::Stages
-                            {
-                                if let Some(stage) =
-                                    app.release_stages.get(app.selected_release_stage_idx)
-                                {
-                                    let env_id = stage.id;
-                                    let stage_name = stage.name.clone();
-                                    app.approve_stage(env_id, &stage_name);
-                                }
+                // Run the pipeline again with the settings it was last triggered with
+                Action::TriggerPreset => {
+                    if app.current_view == View::CICD
+                        && app.cicd_focus == crate::app::CICDFocus::Pipelines
+                    {
+                        let pipeline = match app.pipeline_drill_down {
+                            crate::app::PipelineDrillDown::None => {
+                                app.pipelines.get(app.selected_pipeline_idx)
                             }
+                            _ => app
+                                .current_pipeline_id
+                                .and_then(|id| app.pipelines.iter().find(|p| p.id == id)),
+                        };
+                        if let Some(pipeline) = pipeline {
+                            let (id, name) = (pipeline.id, pipeline.name.clone());
+                            app.trigger_pipeline_preset(id, name);
+                        }
+                    }
+                }
+
+                // Errors and warnings of the selected run
+                Action::FailureSummary => {
+                    if app.current_view == View::CICD {
+                        app.open_failure_summary();
+                    }
+                }
+
+                // Artifacts of the selected run
+                Action::Artifacts => {
+                    if app.current_view == View::CICD {
+                        app.open_artifacts();
+                    }
+                }
+
+                // Log preview: search, jumps, filter and timestamps
+                Action::LogSearch => app.start_log_search(),
+                Action::NextMatch => app.log_jump(LogTarget::Match, true),
+                Action::PrevMatch => app.log_jump(LogTarget::Match, false),
+                Action::NextError => app.log_jump(LogTarget::Severity(Severity::Error), true),
+                Action::PrevError => app.log_jump(LogTarget::Severity(Severity::Error), false),
+                Action::NextWarning => app.log_jump(LogTarget::Severity(Severity::Warning), true),
+                Action::PrevWarning => app.log_jump(LogTarget::Severity(Severity::Warning), false),
+                Action::LogFilter => app.toggle_log_filter(),
+                Action::ToggleTimestamps => {
+                    if app.shown_log().is_some() {
+                        app.log_view.hide_timestamps = !app.log_view.hide_timestamps;
+                    }
+                }
+
+                // Filter assignee (Tasks view)
+                Action::FilterAssignee => {
+                    if app.current_view.shows_work_items() {
+                        app.input_mode = InputMode::FilterAssignee;
+                        app.dropdown_list_state.select(Some(0));
+                    }
+                }
+
+                // Approve stage (CICD view, Stages drill-down)
+                Action::Approve => {
+                    if app.current_view == View::CICD
+                        && app.cicd_focus == crate::app::CICDFocus::Releases
+                        && app.release_drill_down == crate::app::ReleaseDrillDown::Stages
+                    {
+                        if let Some(stage) = app.release_stages.get(app.selected_release_stage_idx)
+                        {
+                            let env_id = stage.id;
+                            let stage_name = stage.name.clone();
+                            app.approve_stage(env_id, &stage_name);
                         }
                     }
                 }
 
                 // Load all runs (CICD view, PipelineRuns drill-down)
-                KeyCode::Char('L') => {
+                Action::LoadAllRuns => {
                     if app.current_view == View::CICD
                         && app.pipeline_drill_down == crate::app::PipelineDrillDown::Runs
                         && app.pipeline_runs_limited
@@ -1697,7 +2592,7 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                 }
 
                 // Toggle live preview (CICD view, when viewing build timeline)
-                KeyCode::Char('w') => {
+                Action::LivePreview => {
                     if app.current_view == View::CICD
                         && app.pipeline_drill_down == crate::app::PipelineDrillDown::Tasks
                     {
@@ -1711,10 +2606,46 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                     }
                 }
 
+                Action::CreatePR => app.open_pr_create(),
+
+                // Pull request actions (PRs view, once a repository is open)
+                Action::Vote
+                | Action::Comment
+                | Action::Reply
+                | Action::ResolveThread
+                | Action::ToggleDraft
+                | Action::Abandon
+                | Action::CompletePR => {
+                    if app.current_view == View::PRs
+                        && app.pr_drill_down != crate::app::PRDrillDown::Repos
+                    {
+                        match action {
+                            Action::Vote => app.open_pr_vote(),
+                            Action::Comment => app.open_pr_comment(false),
+                            Action::Reply => app.open_pr_comment(true),
+                            Action::ResolveThread => app.toggle_pr_thread_status(),
+                            Action::ToggleDraft => app.confirm_pr_update(true),
+                            Action::CompletePR => app.open_pr_complete(),
+                            _ => app.confirm_pr_update(false),
+                        }
+                    }
+                }
+                Action::Files => {
+                    if app.current_view == View::PRs
+                        && app.pr_drill_down == crate::app::PRDrillDown::PRs
+                    {
+                        app.open_pr_files();
+                    }
+                }
+                Action::SelectLines => match app.focused_file_diff_mut() {
+                    Some(diff) => diff.toggle_selection(),
+                    None => app.set_status("Open a file diff to select lines"),
+                },
+
                 // Open in browser - view-aware
-                KeyCode::Char('o') => {
+                Action::Open => {
                     match app.current_view {
-                        View::Tasks => {
+                        View::Tasks | View::Board => {
                             // Existing Tasks open logic
                             match app.focus {
                                 Focus::WorkItems => {
@@ -1809,6 +2740,22 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                                             }
                                         }
                                     }
+                                    crate::app::PRDrillDown::Files => {
+                                        if let (Some(pr), Some(change), Some(repo)) = (
+                                            app.selected_pr_detail.as_ref(),
+                                            app.selected_pr_change(),
+                                            app.repositories.get(app.selected_repo_idx),
+                                        ) {
+                                            let url = format!(
+                                                "{org}/{proj_encoded}/_git/{}/pullrequest/{}?_a=files&path={}",
+                                                repo.name,
+                                                pr.pull_request_id,
+                                                urlencoding::encode(&change.path)
+                                            );
+                                            let _ = open::that(&url);
+                                            app.set_status(format!("Opened {}", change.path));
+                                        }
+                                    }
                                 }
                             }
                         }
@@ -1946,8 +2893,8 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                 }
 
                 // Copy to clipboard
-                KeyCode::Char('y') => {
-                    if app.current_view == View::Tasks {
+                Action::CopyId => {
+                    if app.current_view.shows_work_items() {
                         if let Some(item) = app.selected_work_item() {
                             let id = item.item.id.to_string();
                             if let Ok(mut clipboard) = Clipboard::new() {
@@ -1965,8 +2912,8 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                         }
                     }
                 }
-                KeyCode::Char('Y') => {
-                    if app.current_view == View::Tasks {
+                Action::CopyLink => {
+                    if app.current_view.shows_work_items() {
                         if let Some(item) = app.selected_work_item() {
                             let id = item.item.id;
                             if let Some(project) = app.current_project() {
@@ -2004,14 +2951,14 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                 }
 
                 // Filters - Tasks only
-                KeyCode::Char('s') => {
-                    if app.current_view == View::Tasks {
+                Action::FilterState => {
+                    if app.current_view.shows_work_items() {
                         app.input_mode = InputMode::FilterState;
                         app.dropdown_list_state.select(Some(0));
                     }
                 }
-                KeyCode::Char('c') => {
-                    if app.current_view == View::Tasks && app.has_active_filters() {
+                Action::ClearFilters => {
+                    if app.current_view.shows_work_items() && app.has_active_filters() {
                         app.clear_filters();
                         app.save_to_cache();
                         app.set_status("Filters cleared");
@@ -2019,9 +2966,9 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                 }
 
                 // Refresh
-                KeyCode::Char('r') => {
+                Action::Refresh => {
                     match app.current_view {
-                        View::Tasks => {
+                        View::Tasks | View::Board => {
                             app.set_loading(true, "Refreshing...");
                             // Cache relations before refresh
                             let relations_cache = app.cache_relations();
@@ -2034,13 +2981,20 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                                 let _ = app.load_users().await;
                                 // Restore relations after refresh
                                 app.restore_relations(relations_cache);
+                                app.work_item_history.clear();
+                                app.work_item_comments.clear();
                                 app.save_to_cache();
                                 app.set_status("Refreshed & cached");
-                                // Restart relation loader for any new items
+                                // Refetch relations, the restored ones may be stale
+                                app.relations_loaded.clear();
                                 app.relations_loader_active = false;
                                 app.start_relations_loader();
                             }
                             app.set_loading(false, "");
+                            if app.current_view == View::Board {
+                                // Columns may have been reconfigured too
+                                app.load_boards().await;
+                            }
                         }
                         View::PRs => {
                             match app.pr_drill_down {
@@ -2053,6 +3007,13 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                                     app.load_prs_for_repo(); // Refresh all 3 panes
                                     app.set_status("Refreshing pull requests...");
                                 }
+                                crate::app::PRDrillDown::Files => {
+                                    app.load_pr_iterations();
+                                    if let Some(pr_id) = app.pr_files.as_ref().map(|f| f.pr_id) {
+                                        app.reload_pr_threads(pr_id);
+                                    }
+                                    app.set_status("Refreshing changed files...");
+                                }
                             }
                         }
                         View::CICD => {
@@ -2068,6 +3029,10 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                                             app.force_refresh_pipeline_runs(pipeline_id);
                                             app.set_status("Refreshing runs...");
                                         }
+                                        // The Tests tab loads its run's results again
+                                        if let Some(build_id) = app.tests_tab_run().map(|r| r.id) {
+                                            app.build_tests.remove(&build_id);
+                                        }
                                     }
                                     crate::app::PipelineDrillDown::Tasks => {
                                         if let Some(run) =
@@ -2120,8 +3085,6 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
                         }
                     }
                 }
-
-                _ => {}
             }
         }
 
@@ -2142,3 +3105,1581 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
 
     Ok(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::azure::FakeBackend;
+    use crate::burndown::BurndownUnit;
+    use crate::config::{Config, Keybindings};
+
+    fn press(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    async fn demo_app() -> (App<FakeBackend>, FakeBackend) {
+        demo_app_with_keys(Keybindings::default()).await
+    }
+
+    async fn demo_app_with_keys(keybindings: Keybindings) -> (App<FakeBackend>, FakeBackend) {
+        let backend = FakeBackend::demo();
+        let config = Config {
+            projects: vec![FakeBackend::demo_project()],
+            keybindings,
+            ..Config::default()
+        };
+        let mut app = App::with_backend(config, backend.clone());
+        app.cache = crate::cache::Cache::temporary();
+        app.demo = true;
+        app.load_sprints().await.unwrap();
+        app.load_work_items().await.unwrap();
+        (app, backend)
+    }
+
+    #[tokio::test]
+    async fn test_quit_keys() {
+        let (mut app, _) = demo_app().await;
+
+        // q inside help closes help instead of quitting
+        assert!(!handle_key(&mut app, press('?')).await.unwrap());
+        assert_eq!(app.input_mode, InputMode::Help);
+        assert!(!handle_key(&mut app, press('q')).await.unwrap());
+        assert_eq!(app.input_mode, InputMode::Normal);
+
+        assert!(handle_key(&mut app, press('q')).await.unwrap());
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert!(handle_key(&mut app, ctrl_c).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_navigate_work_items() {
+        let (mut app, _) = demo_app().await;
+        assert!(app.visible_items.len() > 1);
+        assert_eq!(app.work_item_list_state.selected(), Some(0));
+
+        handle_key(&mut app, press('j')).await.unwrap();
+        assert_eq!(app.work_item_list_state.selected(), Some(1));
+        handle_key(&mut app, press('k')).await.unwrap();
+        assert_eq!(app.work_item_list_state.selected(), Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_remapped_keys_and_sequences() {
+        let keys = Keybindings {
+            down: "n".to_string(),
+            top: "gg".to_string(),
+            ..Default::default()
+        };
+        let (mut app, _) = demo_app_with_keys(keys).await;
+
+        handle_key(&mut app, press('n')).await.unwrap();
+        handle_key(&mut app, press('n')).await.unwrap();
+        assert_eq!(app.work_item_list_state.selected(), Some(2));
+
+        // j is no longer bound
+        handle_key(&mut app, press('j')).await.unwrap();
+        assert_eq!(app.work_item_list_state.selected(), Some(2));
+
+        // First g waits for the rest of the sequence
+        handle_key(&mut app, press('g')).await.unwrap();
+        assert_eq!(app.work_item_list_state.selected(), Some(2));
+        handle_key(&mut app, press('g')).await.unwrap();
+        assert_eq!(app.work_item_list_state.selected(), Some(0));
+
+        // A broken sequence falls back to the last key
+        handle_key(&mut app, press('g')).await.unwrap();
+        handle_key(&mut app, press('n')).await.unwrap();
+        assert_eq!(app.work_item_list_state.selected(), Some(1));
+        assert!(app.pending_keys.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_edit_state_updates_backend() {
+        let (mut app, backend) = demo_app().await;
+        let id = app.selected_work_item().unwrap().item.id;
+
+        handle_key(&mut app, press('S')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::EditState);
+        let state = app.filtered_edit_states()[0].clone();
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))
+            .await
+            .unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(backend.get_work_item(id).await.unwrap().fields.state, state);
+
+        // Reloaded list reflects the change
+        let reloaded = app.visible_items.iter().find(|v| v.item.id == id).unwrap();
+        assert_eq!(reloaded.item.fields.state, state);
+    }
+
+    #[tokio::test]
+    async fn test_edit_fields_form_saves_changes() {
+        let (mut app, backend) = demo_app().await;
+        let id = app.selected_work_item().unwrap().item.id;
+        let enter = || KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+
+        handle_key(&mut app, press('e')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::WorkItemForm);
+        assert_eq!(app.focus, Focus::Preview);
+
+        // Title, Description, Tags, Priority
+        handle_key(&mut app, press('j')).await.unwrap();
+        handle_key(&mut app, press('j')).await.unwrap();
+        handle_key(&mut app, enter()).await.unwrap();
+        for c in ", triage".chars() {
+            handle_key(&mut app, press(c)).await.unwrap();
+        }
+        handle_key(&mut app, enter()).await.unwrap();
+        handle_key(&mut app, press('j')).await.unwrap();
+        handle_key(&mut app, enter()).await.unwrap();
+        handle_key(&mut app, press('x')).await.unwrap();
+
+        // Invalid numbers keep the form open
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        handle_key(&mut app, ctrl_s).await.unwrap();
+        assert!(app.status_is_error);
+        assert_eq!(app.input_mode, InputMode::WorkItemForm);
+
+        handle_key(&mut app, enter()).await.unwrap();
+        handle_key(
+            &mut app,
+            KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE),
+        )
+        .await
+        .unwrap();
+        handle_key(&mut app, press('2')).await.unwrap();
+        handle_key(&mut app, ctrl_s).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(app.work_item_form.is_none());
+
+        let item = backend.get_work_item(id).await.unwrap();
+        assert_eq!(item.fields.tags.as_deref(), Some("frontend; ux; triage"));
+        assert_eq!(item.fields.priority, Some(2));
+
+        // Esc throws edits away
+        handle_key(&mut app, press('e')).await.unwrap();
+        handle_key(&mut app, enter()).await.unwrap();
+        handle_key(&mut app, press('!')).await.unwrap();
+        handle_key(&mut app, enter()).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Esc)).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        let title = backend.get_work_item(id).await.unwrap().fields.title;
+        assert!(!title.ends_with('!'));
+    }
+
+    #[tokio::test]
+    async fn test_create_child_tasks_in_place() {
+        let (mut app, backend) = demo_app().await;
+        let parent = app.selected_work_item().unwrap().item.id;
+        let before = backend.fixtures().work_items.len();
+
+        handle_key(&mut app, press('N')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::WorkItemCreate);
+        for c in "Design form".chars() {
+            handle_key(&mut app, press(c)).await.unwrap();
+        }
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        for c in "Wire up API".chars() {
+            handle_key(&mut app, press(c)).await.unwrap();
+        }
+        // Tab to the estimate
+        handle_key(&mut app, key(KeyCode::Tab)).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Tab)).await.unwrap();
+        handle_key(&mut app, press('3')).await.unwrap();
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        handle_key(&mut app, ctrl_s).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+
+        let created: Vec<WorkItem> = backend.fixtures().work_items[before..].to_vec();
+        assert_eq!(created.len(), 2);
+        assert!(created.iter().all(|w| w.fields.work_item_type == "Task"
+            && w.fields.parent_id == Some(parent)
+            && w.fields.remaining_work == Some(3.0)));
+
+        // Inserted under the expanded parent without a reload, first new item selected
+        let selected = app.selected_work_item().unwrap();
+        assert_eq!(selected.item.fields.title, "Design form");
+        assert_eq!(selected.depth, 1);
+        let parent_item = app.work_items.iter().find(|w| w.id == parent).unwrap();
+        assert!(parent_item.children.iter().any(|c| c.id == created[1].id));
+    }
+
+    #[tokio::test]
+    async fn test_run_project_and_adhoc_queries() {
+        let (mut app, _) = demo_app().await;
+        let ids = |app: &App<FakeBackend>| -> Vec<i32> {
+            let mut ids: Vec<i32> = App::flatten_work_items(&app.work_items)
+                .iter()
+                .map(|w| w.id)
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        handle_key(&mut app, press('Q')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::QueryBrowser);
+        for c in "stories".chars() {
+            handle_key(&mut app, press(c)).await.unwrap();
+        }
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(app.active_query.as_ref().unwrap().name, "All user stories");
+        // Crosses sprints
+        assert_eq!(ids(&app), vec![990, 1001, 1005]);
+
+        // Ad-hoc WIQL from the "new query" row
+        handle_key(&mut app, press('Q')).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::QueryEditor);
+        let editor = app.query_browser.as_mut().unwrap().editor.as_mut().unwrap();
+        editor.wiql = "SELECT [System.Id] FROM WorkItems WHERE [System.Tags] CONTAINS 'ux'".into();
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        handle_key(&mut app, ctrl_s).await.unwrap();
+        assert_eq!(ids(&app), vec![1001]);
+
+        // Named queries are offered again, but demo mode leaves the config file alone
+        for wiql in ["checkout", "ux"] {
+            handle_key(&mut app, press('Q')).await.unwrap();
+            handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+            let editor = app.query_browser.as_mut().unwrap().editor.as_mut().unwrap();
+            editor.name = "Tagged".into();
+            editor.wiql =
+                format!("SELECT [System.Id] FROM WorkItems WHERE [System.Tags] CONTAINS '{wiql}'");
+            handle_key(&mut app, ctrl_s).await.unwrap();
+            assert!(!app.status_is_error, "{:?}", app.status_message);
+        }
+        let saved: Vec<_> = app
+            .config
+            .queries
+            .iter()
+            .filter(|q| q.name == "Tagged")
+            .collect();
+        assert_eq!(saved.len(), 1);
+        assert!(saved[0].wiql.ends_with("'ux'"));
+        assert!(app.status_message.as_deref().unwrap().contains("(demo)"));
+
+        // Unsupported WIQL keeps the previous results
+        handle_key(&mut app, press('Q')).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        let editor = app.query_browser.as_mut().unwrap().editor.as_mut().unwrap();
+        editor.wiql = "SELECT [System.Id] FROM WorkItems WHERE [System.Id] IN (1, 2)".into();
+        handle_key(&mut app, ctrl_s).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::QueryEditor);
+        assert!(app.status_is_error);
+        assert_eq!(ids(&app), vec![1001]);
+        handle_key(&mut app, key(KeyCode::Esc)).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Esc)).await.unwrap();
+
+        // Picking a sprint goes back to the sprint
+        handle_key(&mut app, press('I')).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        assert!(app.active_query.is_none());
+        assert!(ids(&app).contains(&1002));
+    }
+
+    #[tokio::test]
+    async fn test_bulk_tag_and_move_marked_items() {
+        let (mut app, backend) = demo_app().await;
+        async fn finish_bulk(app: &mut App<FakeBackend>) {
+            poll_until(app, |app| {
+                app.poll_bulk_results();
+                app.bulk_rx.is_none()
+            })
+            .await
+        }
+
+        // The story and its two children
+        handle_key(&mut app, press('*')).await.unwrap();
+        assert_eq!(app.marked_ids(), vec![1001, 1002, 1003]);
+        handle_key(&mut app, press('T')).await.unwrap();
+        for c in "+carryover -ux".chars() {
+            handle_key(&mut app, press(c)).await.unwrap();
+        }
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        finish_bulk(&mut app).await;
+        assert!(!app.status_is_error, "{:?}", app.status_message);
+        assert!(!app.has_marks());
+        let tags = |id: i32| {
+            let fixtures = backend.fixtures();
+            let item = fixtures.work_items.iter().find(|w| w.id == id).unwrap();
+            item.fields.tags.clone().unwrap_or_default()
+        };
+        assert_eq!(tags(1001), "frontend; carryover");
+        assert_eq!(tags(1003), "carryover");
+
+        // A range of two top-level items moves out of the sprint
+        let first = app
+            .visible_items
+            .iter()
+            .position(|vi| vi.item.id == 1004)
+            .unwrap();
+        app.work_item_list_state.select(Some(first));
+        handle_key(&mut app, press('v')).await.unwrap();
+        handle_key(&mut app, press('j')).await.unwrap();
+        assert_eq!(app.marked_ids(), vec![1004, 1005]);
+        handle_key(&mut app, press('M')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::MoveIteration);
+        let other = app
+            .sprints
+            .iter()
+            .position(|s| s.path != app.sprints[app.selected_sprint_idx].path)
+            .unwrap();
+        app.dropdown_list_state.select(Some(other));
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        finish_bulk(&mut app).await;
+        assert!(app
+            .status_message
+            .as_deref()
+            .unwrap()
+            .starts_with("Moved 2 items"));
+        assert!(!app
+            .visible_items
+            .iter()
+            .any(|vi| vi.item.id == 1004 || vi.item.id == 1005));
+    }
+
+    #[tokio::test]
+    async fn test_cached_relations_are_refetched() {
+        let (mut app, backend) = demo_app().await;
+        app.start_relations_loader();
+        poll_until(&mut app, |app| {
+            app.poll_relations();
+            !app.relations_loader_active
+        })
+        .await;
+        assert!(app.relations_loaded.contains(&1003));
+
+        // The next start shows the cached relations, then loads them again
+        let mut next = App::with_backend(app.config.clone(), backend);
+        next.cache = app.cache.clone();
+        assert!(next.load_from_cache());
+        let item = App::flatten_work_items(&next.work_items)
+            .into_iter()
+            .find(|w| w.id == 1003)
+            .unwrap();
+        assert!(item.relations.is_some_and(|r| !r.is_empty()));
+        assert!(next.get_ids_needing_relations().contains(&1003));
+    }
+
+    #[tokio::test]
+    async fn test_board_shows_cards_under_collapsed_parents() {
+        let (mut app, _) = demo_app().await;
+        // The bug becomes a child of the invoice story, folded in the tree
+        let pos = app.work_items.iter().position(|w| w.id == 1004).unwrap();
+        let bug = app.work_items.remove(pos);
+        let story = app.work_items.iter_mut().find(|w| w.id == 1005).unwrap();
+        story.children.push(bug);
+        app.expanded_items.remove(&1005);
+        app.rebuild_visible_items();
+        assert!(!app.visible_items.iter().any(|v| v.item.id == 1004));
+
+        handle_key(&mut app, press('4')).await.unwrap();
+        let (_, layout) = app.board_layout();
+        let idx = app
+            .visible_items
+            .iter()
+            .position(|v| v.item.id == 1004)
+            .unwrap();
+        assert!(layout.find(idx).is_some());
+        app.work_item_list_state.select(Some(idx));
+
+        // Back in the list the parent is still folded
+        handle_key(&mut app, press('1')).await.unwrap();
+        assert!(!app.visible_items.iter().any(|v| v.item.id == 1004));
+        assert!(app.selected_work_item().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_board_moves_cards_between_columns() {
+        let (mut app, backend) = demo_app().await;
+        let selected_id = |app: &App<FakeBackend>| app.selected_work_item().map(|vi| vi.item.id);
+        let field = |id: i32, name: &str| {
+            let fixtures = backend.fixtures();
+            let item = fixtures.work_items.iter().find(|w| w.id == id).unwrap();
+            item.fields
+                .get(name)
+                .and_then(|v| v.as_str().map(str::to_string))
+        };
+
+        handle_key(&mut app, press('4')).await.unwrap();
+        assert_eq!(app.current_view, View::Board);
+        let (board, _) = app.board_layout();
+        assert_eq!(board.name.as_deref(), Some("Stories"));
+        // The selection carries over from the Tasks view
+        assert_eq!(selected_id(&app), Some(1001));
+        handle_key(&mut app, press('h')).await.unwrap();
+        assert_eq!(selected_id(&app), Some(1004));
+
+        // Into Active, which is already at its WIP limit
+        handle_key(&mut app, press('L')).await.unwrap();
+        assert!(app.status_is_error);
+        assert!(app
+            .status_message
+            .as_deref()
+            .unwrap()
+            .ends_with("over its WIP limit of 1"));
+        assert_eq!(
+            field(1004, "WEF_D3M0_Kanban.Column").as_deref(),
+            Some("Active")
+        );
+        assert_eq!(
+            field(1004, WorkItemFields::STATE).as_deref(),
+            Some("In Progress")
+        );
+
+        // Review has the same state, so only the column changes
+        handle_key(&mut app, press('L')).await.unwrap();
+        assert_eq!(app.status_message.as_deref(), Some("Moved #1004 to Review"));
+        assert_eq!(selected_id(&app), Some(1004));
+        assert_eq!(
+            field(1004, WorkItemFields::BOARD_COLUMN).as_deref(),
+            Some("Review")
+        );
+
+        // Collapsing the Expedite lane moves the cursor to a visible card
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        assert!(app.collapsed_lanes.contains("Expedite"));
+        assert_eq!(selected_id(&app), Some(1001));
+        handle_key(&mut app, press('l')).await.unwrap();
+        assert_eq!(selected_id(&app), Some(1001));
+        handle_key(&mut app, press('t')).await.unwrap();
+        assert!(app.collapsed_lanes.is_empty());
+        handle_key(&mut app, press('l')).await.unwrap();
+        assert_eq!(selected_id(&app), Some(1004));
+    }
+
+    #[tokio::test]
+    async fn test_sprint_stats_build_burndown_from_revisions() {
+        let (mut app, _) = demo_app().await;
+        async fn finish_burndown(app: &mut App<FakeBackend>) {
+            poll_until(app, |app| {
+                app.poll_burndown();
+                app.burndown_rx.is_none()
+            })
+            .await
+        }
+
+        let progress = app.sprint_progress();
+        assert_eq!((progress.done, progress.total), (1, 6));
+        assert_eq!(progress.remaining_work, 9.0);
+
+        handle_key(&mut app, press('B')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::SprintStats);
+        finish_burndown(&mut app).await;
+        assert!(!app.status_is_error, "{:?}", app.status_message);
+        let burndown = app.burndown.clone().unwrap();
+        assert_eq!(burndown.sprint_path, "Demo\\Sprint 42");
+        assert_eq!(burndown.unit, BurndownUnit::Hours);
+        assert_eq!(burndown.days(), 12);
+        // The bug and the CSV task joined after the first day
+        assert_eq!(burndown.added, vec![1004, 1006]);
+
+        // Reopening the same sprint keeps the loaded burndown
+        handle_key(&mut app, key(KeyCode::Esc)).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        handle_key(&mut app, press('B')).await.unwrap();
+        assert!(app.burndown_rx.is_none());
+        handle_key(&mut app, press('r')).await.unwrap();
+        assert!(app.burndown_rx.is_some());
+        finish_burndown(&mut app).await;
+        assert_eq!(app.burndown, Some(burndown));
+        handle_key(&mut app, press('B')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+    }
+
+    #[tokio::test]
+    async fn test_team_capacity_against_assigned_work() {
+        use crate::capacity::CapacityPlan;
+
+        let (mut app, _) = demo_app().await;
+        async fn finish_capacity(app: &mut App<FakeBackend>) {
+            poll_until(app, |app| {
+                app.poll_team_capacity();
+                app.capacity_rx.is_none()
+            })
+            .await
+        }
+
+        handle_key(&mut app, press('K')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::TeamCapacity);
+        finish_capacity(&mut app).await;
+        assert!(!app.status_is_error, "{:?}", app.status_message);
+        assert!(app.capacity_plan().is_some());
+
+        // Planned on the first day of the sprint
+        let (_, capacity) = app.team_capacity.clone().unwrap();
+        let capacity = capacity.unwrap();
+        let sprint = app.selected_sprint().unwrap();
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 9, 28).unwrap();
+        let plan =
+            CapacityPlan::build(sprint, &capacity, &app.users, &app.work_items, today).unwrap();
+        assert_eq!((plan.team_days, plan.team_days_off), (9, 1));
+        let loads: Vec<(&str, f64, f64)> = plan
+            .members
+            .iter()
+            .map(|m| (m.display_name.as_str(), m.available, m.assigned))
+            .collect();
+        assert_eq!(
+            loads,
+            [
+                ("Ada Lovelace", 42.0, 0.0),
+                ("Grace Hopper", 54.0, 6.0),
+                ("Linus Torvalds", 4.5, 3.0),
+            ]
+        );
+        assert_eq!(plan.overloaded(), 0);
+
+        // Reopening keeps the loaded capacity, refresh reloads it
+        handle_key(&mut app, key(KeyCode::Esc)).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        handle_key(&mut app, press('K')).await.unwrap();
+        assert!(app.capacity_rx.is_none());
+        handle_key(&mut app, press('r')).await.unwrap();
+        assert!(app.capacity_rx.is_some());
+        finish_capacity(&mut app).await;
+        assert_eq!(app.team_capacity.as_ref().unwrap().1, Ok(capacity));
+        handle_key(&mut app, press('K')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+    }
+
+    #[tokio::test]
+    async fn test_trigger_pipeline_with_parameters_and_preset() {
+        use crate::pipeline_trigger::TriggerField;
+
+        let (mut app, backend) = demo_app().await;
+        async fn wait_for(app: &mut App<FakeBackend>, done: impl Fn(&App<FakeBackend>) -> bool) {
+            poll_until(app, |app| {
+                app.poll_cicd();
+                app.poll_pipeline_trigger();
+                done(app)
+            })
+            .await
+        }
+        let type_text = |text: &'static str| text.chars().map(press).collect::<Vec<_>>();
+
+        handle_key(&mut app, press('3')).await.unwrap();
+        wait_for(&mut app, |a| !a.pipelines.is_empty()).await;
+        app.selected_pipeline_idx = app.pipelines.iter().position(|p| p.id == 11).unwrap();
+
+        handle_key(&mut app, press('T')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::PipelineTriggerDialog);
+        wait_for(&mut app, |a| {
+            a.pipeline_trigger_dialog
+                .as_ref()
+                .is_some_and(|d| !d.loading)
+        })
+        .await;
+        let dialog = app.pipeline_trigger_dialog.as_ref().unwrap();
+        assert_eq!(dialog.branch, "refs/heads/main");
+        assert!(dialog.refs.contains(&"refs/tags/v1.1.0".to_string()));
+        let names: Vec<&str> = dialog
+            .parameters
+            .iter()
+            .map(|p| p.parameter.name.as_str())
+            .collect();
+        assert_eq!(names, ["environment", "runE2E", "nodeVersion", "shards"]);
+        let variables: Vec<&str> = dialog.variables.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(variables, ["deployToken", "verbosity"]);
+        assert_eq!(dialog.stages.len(), 3);
+
+        // Pick a branch by fuzzy search
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        for k in type_text("node22") {
+            handle_key(&mut app, k).await.unwrap();
+        }
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        // Deploy to production, with 8 shards
+        handle_key(&mut app, press('j')).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        for k in type_text("jjj") {
+            handle_key(&mut app, k).await.unwrap();
+        }
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Backspace)).await.unwrap();
+        handle_key(&mut app, press('8')).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        // Skip the Deploy stage
+        for k in type_text("jjjjj") {
+            handle_key(&mut app, k).await.unwrap();
+        }
+        let dialog = app.pipeline_trigger_dialog.as_ref().unwrap();
+        assert_eq!(dialog.selected(), TriggerField::Stage(2));
+        handle_key(&mut app, press(' ')).await.unwrap();
+
+        let before = backend.fixtures().run_requests.len();
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        handle_key(&mut app, ctrl_s).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        wait_for(&mut app, |a| {
+            a.pipeline_drill_down == crate::app::PipelineDrillDown::Runs && !a.cicd_loading
+        })
+        .await;
+        let requests = backend.fixtures().run_requests;
+        assert_eq!(requests.len(), before + 1);
+        let (&run_id, request) = requests.iter().max_by_key(|(id, _)| **id).unwrap();
+        assert_eq!(request.branch, "refs/heads/chore/node22");
+        assert_eq!(
+            request.parameters,
+            [
+                ("environment".to_string(), "production".to_string()),
+                ("shards".to_string(), "8".to_string()),
+            ]
+            .into()
+        );
+        assert!(request.variables.is_empty());
+        assert_eq!(request.stages_to_skip, ["Deploy"]);
+        assert_eq!(app.pipeline_runs.first().map(|r| r.id), Some(run_id));
+
+        // One keystroke runs it again with the same settings
+        handle_key(&mut app, press('t')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        wait_for(&mut app, |a| {
+            a.pipeline_runs.first().is_some_and(|r| r.id != run_id)
+        })
+        .await;
+        let requests = backend.fixtures().run_requests;
+        assert_eq!(requests.len(), before + 2);
+        let (_, again) = requests.iter().max_by_key(|(id, _)| **id).unwrap();
+        assert_eq!(again, request);
+    }
+
+    #[tokio::test]
+    async fn test_search_log_and_jump_to_errors() {
+        let (mut app, backend) = demo_app().await;
+        handle_key(&mut app, press('3')).await.unwrap();
+        app.pipeline_drill_down = crate::app::PipelineDrillDown::Tasks;
+        app.cicd_focus = crate::app::CICDFocus::Preview;
+        app.build_log_lines = backend.fixtures().build_logs[&22].clone();
+        async fn keys(app: &mut App<FakeBackend>, text: &str) {
+            for c in text.chars() {
+                handle_key(app, press(c)).await.unwrap();
+            }
+        }
+
+        keys(&mut app, "/fail|jpy").await;
+        assert_eq!(app.input_mode, InputMode::LogSearch);
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(app.log_view.matches(&app.build_log_lines), [4, 5]);
+        assert_eq!(app.log_view.current, Some(4));
+        keys(&mut app, "n").await;
+        assert_eq!(app.log_view.current, Some(5));
+        keys(&mut app, "N").await;
+        assert_eq!(app.log_view.current, Some(4));
+
+        // Errors and warnings, whatever the search
+        keys(&mut app, "]e").await;
+        assert_eq!(app.log_view.current, Some(8));
+        assert_eq!(app.log_scroll, 3);
+        keys(&mut app, "[w").await;
+        assert_eq!(app.log_view.current, Some(2));
+
+        // Only the matching lines
+        keys(&mut app, "&").await;
+        assert!(app.log_view.filter);
+        assert_eq!(app.log_view.rows(&app.build_log_lines), [4, 5]);
+        assert_eq!(app.log_scroll, 0);
+        keys(&mut app, "s").await;
+        assert_eq!(
+            app.log_view.display(&app.build_log_lines[5]),
+            "  ● totals › rounds JPY to whole units"
+        );
+
+        // A bad pattern keeps the prompt open; Esc goes back to the last one
+        keys(&mut app, "/(").await;
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::LogSearch);
+        assert!(app.log_view.error.is_some());
+        handle_key(&mut app, key(KeyCode::Esc)).await.unwrap();
+        assert_eq!(app.log_view.query, "fail|jpy");
+        assert!(app.log_view.filter);
+    }
+
+    #[tokio::test]
+    async fn test_failure_summary_jumps_to_log_lines() {
+        use crate::app::{CICDFocus, PipelineDrillDown};
+        use crate::failure_summary::IssueCounts;
+
+        let (mut app, _) = demo_app().await;
+        async fn wait_for(app: &mut App<FakeBackend>, done: impl Fn(&App<FakeBackend>) -> bool) {
+            poll_until(app, |app| {
+                app.poll_cicd();
+                app.poll_run_issue_counts();
+                app.poll_failure_summary();
+                done(app)
+            })
+            .await
+        }
+
+        handle_key(&mut app, press('3')).await.unwrap();
+        wait_for(&mut app, |a| !a.pipelines.is_empty()).await;
+        app.selected_pipeline_idx = app.pipelines.iter().position(|p| p.id == 11).unwrap();
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        assert_eq!(app.pipeline_drill_down, PipelineDrillDown::Runs);
+
+        // Finished runs get their counts; the running one has none yet
+        wait_for(&mut app, |a| {
+            a.run_issue_counts.contains_key(&7002) && a.run_issue_counts.contains_key(&7001)
+        })
+        .await;
+        assert_eq!(
+            app.run_issue_counts.get(&7002),
+            Some(&IssueCounts {
+                errors: 2,
+                warnings: 1
+            })
+        );
+        assert_eq!(
+            app.run_issue_counts.get(&7001),
+            Some(&IssueCounts {
+                errors: 0,
+                warnings: 1
+            })
+        );
+        assert!(!app.run_issue_counts.contains_key(&7003));
+
+        app.selected_pipeline_run_idx =
+            app.pipeline_runs.iter().position(|r| r.id == 7002).unwrap();
+        handle_key(&mut app, press('E')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::FailureSummary);
+        wait_for(&mut app, |a| {
+            a.failure_summary.as_ref().is_some_and(|s| !s.loading)
+        })
+        .await;
+        let summary = app.failure_summary.as_ref().unwrap();
+        let listed: Vec<(Severity, &str)> = summary
+            .issues
+            .iter()
+            .map(|i| (i.severity, i.message.as_str()))
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                (Severity::Error, "totals › rounds JPY to whole units"),
+                (Severity::Error, "Bash exited with code '1'."),
+                (Severity::Warning, "npm WARN deprecated inflight@1.0.6"),
+            ]
+        );
+        assert_eq!(summary.issues[0].task, "npm test");
+        assert_eq!(
+            summary.issues[0].location().as_deref(),
+            Some("src/checkout.test.ts:42")
+        );
+
+        // Straight to the line that logged the exit code
+        handle_key(&mut app, press('j')).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(app.pipeline_drill_down, PipelineDrillDown::Tasks);
+        assert_eq!(app.cicd_focus, CICDFocus::Preview);
+        assert_eq!(app.selected_run_id, Some(7002));
+        let task = app.get_timeline_tasks()[app.selected_task_idx].id.clone();
+        assert_eq!(task, "task-test");
+        wait_for(&mut app, |a| a.pending_log_issue.is_none()).await;
+        assert_eq!(app.log_view.current, Some(8));
+        assert_eq!(app.log_scroll, 3);
+
+        // From inside the run; an issue without a log line is found by its message
+        handle_key(&mut app, press('E')).await.unwrap();
+        assert!(app.failure_summary.as_ref().is_some_and(|s| !s.loading));
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        wait_for(&mut app, |a| a.pending_log_issue.is_none()).await;
+        assert_eq!(app.log_view.current, Some(5));
+        assert_eq!(app.log_scroll, 0);
+
+        handle_key(&mut app, press('E')).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Esc)).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(app.failure_summary.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_run_tests_tab_with_flaky_history() {
+        use crate::app::{PipelineDrillDown, RunPreviewTab};
+        use crate::test_results::{history, TestHistory, TestTotals};
+
+        let (mut app, _) = demo_app().await;
+        async fn wait_for(app: &mut App<FakeBackend>, done: impl Fn(&App<FakeBackend>) -> bool) {
+            poll_until(app, |app| {
+                app.poll_cicd();
+                app.poll_build_tests();
+                done(app)
+            })
+            .await
+        }
+
+        handle_key(&mut app, press('3')).await.unwrap();
+        wait_for(&mut app, |a| !a.pipelines.is_empty()).await;
+        app.selected_pipeline_idx = app.pipelines.iter().position(|p| p.id == 11).unwrap();
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        wait_for(&mut app, |a| a.pipeline_runs.iter().any(|r| r.id == 6999)).await;
+        assert_eq!(app.pipeline_drill_down, PipelineDrillDown::Runs);
+
+        // Nothing is fetched until the tab is shown
+        app.selected_pipeline_run_idx =
+            app.pipeline_runs.iter().position(|r| r.id == 7002).unwrap();
+        app.poll_build_tests();
+        assert!(app.build_tests.is_empty());
+
+        handle_key(&mut app, key(KeyCode::Tab)).await.unwrap();
+        assert_eq!(app.run_preview_tab, RunPreviewTab::Tests);
+        wait_for(&mut app, |a| {
+            [7002, 7001, 7000, 6999]
+                .iter()
+                .all(|id| a.build_tests.contains_key(id))
+        })
+        .await;
+
+        let tests = app.build_tests[&7002].as_ref().unwrap();
+        assert_eq!(
+            tests.totals,
+            TestTotals {
+                passed: 45,
+                failed: 2,
+                skipped: 1
+            }
+        );
+        let failed: Vec<&str> = tests.failed.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            failed,
+            [
+                "totals › rounds JPY to whole units",
+                "coupons › rejects expired codes"
+            ]
+        );
+        assert!(tests.failed[0]
+            .error_message
+            .as_deref()
+            .is_some_and(|m| m.contains("Received: 1200.5")));
+
+        // JPY also failed two runs ago; the coupon test only broke now
+        let earlier = app.earlier_build_tests(7002);
+        assert_eq!(
+            history(&tests.failed[0].key, &earlier),
+            TestHistory {
+                runs: 3,
+                failures: 1,
+                flaky: true
+            }
+        );
+        assert_eq!(
+            history(&tests.failed[1].key, &earlier),
+            TestHistory {
+                runs: 3,
+                failures: 0,
+                flaky: false
+            }
+        );
+
+        // The running build has no results yet
+        app.selected_pipeline_run_idx =
+            app.pipeline_runs.iter().position(|r| r.id == 7003).unwrap();
+        app.poll_build_tests();
+        assert!(app.build_tests_rx.is_none());
+        assert!(!app.build_tests.contains_key(&7003));
+
+        handle_key(&mut app, key(KeyCode::BackTab)).await.unwrap();
+        assert_eq!(app.run_preview_tab, RunPreviewTab::Details);
+    }
+
+    #[tokio::test]
+    async fn test_artifacts_browse_and_download() {
+        use crate::app::PipelineDrillDown;
+
+        let (mut app, _) = demo_app().await;
+        async fn wait_for(app: &mut App<FakeBackend>, done: impl Fn(&App<FakeBackend>) -> bool) {
+            poll_until(app, |app| {
+                app.poll_cicd();
+                app.poll_artifacts();
+                app.poll_artifact_download();
+                done(app)
+            })
+            .await
+        }
+        let dir = tempfile::TempDir::new().unwrap();
+
+        handle_key(&mut app, press('3')).await.unwrap();
+        wait_for(&mut app, |a| !a.pipelines.is_empty()).await;
+        app.selected_pipeline_idx = app.pipelines.iter().position(|p| p.id == 11).unwrap();
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        wait_for(&mut app, |a| a.pipeline_runs.iter().any(|r| r.id == 7002)).await;
+        assert_eq!(app.pipeline_drill_down, PipelineDrillDown::Runs);
+        app.selected_pipeline_run_idx =
+            app.pipeline_runs.iter().position(|r| r.id == 7002).unwrap();
+
+        handle_key(&mut app, press('F')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Artifacts);
+        wait_for(&mut app, |a| {
+            a.artifact_browser.as_ref().is_some_and(|b| !b.loading)
+        })
+        .await;
+        let browser = app.artifact_browser.as_ref().unwrap();
+        let listed: Vec<(&str, Option<u64>)> = browser
+            .artifacts
+            .iter()
+            .map(|a| (a.artifact.name.as_str(), a.size()))
+            .collect();
+        assert_eq!(
+            listed,
+            [("drop", Some(5_777_920)), ("coverage", Some(1_843_200))]
+        );
+
+        // Into drop/web, then download app.js
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        let browser = app.artifact_browser.as_ref().unwrap();
+        assert_eq!(browser.location(), Some("drop/web"));
+        let names: Vec<&str> = browser.entries().iter().map(|e| e.name).collect();
+        assert_eq!(names, ["assets", "app.js", "app.js.map", "index.html"]);
+        handle_key(&mut app, press('j')).await.unwrap();
+        handle_key(&mut app, press('d')).await.unwrap();
+        let browser = app.artifact_browser.as_mut().unwrap();
+        assert!(browser.destination.is_some());
+        browser.destination = Some(String::new());
+        for c in dir.path().join("qa").to_string_lossy().chars() {
+            handle_key(&mut app, press(c)).await.unwrap();
+        }
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        assert_eq!(
+            app.artifact_download.as_ref().unwrap().total,
+            Some(1_572_864)
+        );
+        wait_for(&mut app, |a| a.artifact_download.is_none()).await;
+        let path = dir.path().join("qa").join("app.js");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "drop/web/app.js\n");
+        assert!(!dir.path().join("qa").join("app.js.part").exists());
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some(format!("Downloaded {}", path.display()).as_str())
+        );
+
+        // h goes up to drop and back to the artifacts; the whole coverage
+        // artifact comes as a zip to the directory used last
+        handle_key(&mut app, press('h')).await.unwrap();
+        handle_key(&mut app, press('h')).await.unwrap();
+        let browser = app.artifact_browser.as_ref().unwrap();
+        assert_eq!(browser.location(), None);
+        handle_key(&mut app, press('j')).await.unwrap();
+        // Pipeline artifacts can't be opened, so Enter asks where it goes
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        wait_for(&mut app, |a| a.artifact_download.is_none()).await;
+        assert!(dir.path().join("qa").join("coverage.zip").exists());
+
+        handle_key(&mut app, key(KeyCode::Esc)).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(app.artifact_browser.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_history_tab_loads_timeline() {
+        use crate::app::PreviewTab;
+        use crate::history::HistoryChange;
+
+        let (mut app, _) = demo_app().await;
+        app.expanded_items.insert(1001);
+        app.rebuild_visible_items();
+        let idx = app
+            .visible_items
+            .iter()
+            .position(|v| v.item.id == 1003)
+            .unwrap();
+        app.work_item_list_state.select(Some(idx));
+
+        // Nothing loads until the tab is shown
+        app.poll_history();
+        assert!(app.history_rx.is_none());
+        handle_key(&mut app, press('l')).await.unwrap();
+        for _ in 0..3 {
+            handle_key(&mut app, key(KeyCode::Tab)).await.unwrap();
+        }
+        assert_eq!(app.preview_tab, PreviewTab::History);
+        poll_until(&mut app, |app| {
+            app.poll_history();
+            app.work_item_history.contains_key(&1003)
+        })
+        .await;
+
+        assert!(app.history_rx.is_none());
+        let entries = app.work_item_history[&1003].clone().unwrap();
+        let authors: Vec<&str> = entries.iter().map(|e| e.author.as_str()).collect();
+        assert_eq!(
+            authors,
+            [
+                "Ada Lovelace",
+                "Ada Lovelace",
+                "Ada Lovelace",
+                "Ada Lovelace",
+                "Grace Hopper"
+            ]
+        );
+        assert!(entries[4].created);
+
+        // Newest first: closed with a commit link
+        assert_eq!(
+            entries[0].changes[0],
+            HistoryChange::State {
+                from: Some("In Progress".into()),
+                to: Some("Done".into())
+            }
+        );
+        assert!(matches!(
+            entries[0].changes.last(),
+            Some(HistoryChange::Link { added: true, relation })
+                if relation.attributes.name.as_deref() == Some("Fixed in Commit")
+        ));
+        // Only the new paragraph of the description shows as a change
+        assert!(entries[2].changes.contains(&HistoryChange::Text {
+            name: "Description".into(),
+            lines: vec![(true, "Use the maps API; debounce by 300 ms.".into())]
+        }));
+        assert_eq!(
+            entries[3].changes[1],
+            HistoryChange::Assigned {
+                from: Some("Grace Hopper".into()),
+                to: Some("Ada Lovelace".into())
+            }
+        );
+
+        // Leaving the tab stops loading for newly selected items
+        handle_key(&mut app, key(KeyCode::Tab)).await.unwrap();
+        assert_eq!(app.preview_tab, PreviewTab::Details);
+        handle_key(&mut app, press('h')).await.unwrap();
+        handle_key(&mut app, press('k')).await.unwrap();
+        app.poll_history();
+        assert!(app.history_rx.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_relations_load_in_batches_with_outside_parents() {
+        use crate::app::RelationsLoad;
+
+        let (mut app, _) = demo_app().await;
+        // A parent from another sprint
+        for item in app.work_items.iter_mut().filter(|w| w.id == 1004) {
+            item.fields.parent_id = Some(990);
+        }
+        assert_eq!(app.get_outside_parent_ids(), [990]);
+
+        app.start_relations_loader();
+        let mut rx = app.relations_rx.take().unwrap();
+        let mut loads = Vec::new();
+        while let Some(load) = rx.recv().await {
+            loads.push(load);
+        }
+        assert_eq!(loads.len(), 7);
+        for load in loads {
+            match load {
+                RelationsLoad::Relations(id, relations) => {
+                    app.update_work_item_relations(id, relations)
+                }
+                RelationsLoad::ParentTitle(id, title) => {
+                    app.relation_titles.insert(format!("wi:{id}"), title);
+                }
+                RelationsLoad::Failed(e) => panic!("{e}"),
+            }
+        }
+
+        let items = App::flatten_work_items(&app.work_items);
+        let relations = |id: i32| {
+            items
+                .iter()
+                .find(|w| w.id == id)
+                .and_then(|w| w.relations.as_ref())
+                .map(Vec::len)
+        };
+        // Items without links count as loaded, so they are not fetched again
+        assert_eq!(relations(1001), Some(1));
+        assert_eq!(relations(1002), Some(0));
+        assert!(app.get_ids_needing_relations().is_empty());
+        assert!(app.get_outside_parent_ids().is_empty());
+        assert_eq!(
+            app.find_work_item_title(990).as_deref(),
+            Some("Login rate limiting")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_relations_batch_failure_is_reported() {
+        use crate::app::RelationsLoad;
+
+        let (mut app, _) = demo_app().await;
+        let (tx, rx) = tokio::sync::mpsc::channel(4);
+        tx.send(RelationsLoad::Relations(1002, Some(Vec::new())))
+            .await
+            .unwrap();
+        tx.send(RelationsLoad::Failed("Azure CLI request timed out".into()))
+            .await
+            .unwrap();
+        drop(tx);
+        app.relations_rx = Some(rx);
+        app.relations_loader_active = true;
+
+        app.poll_relations();
+        assert!(app.status_is_error);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Failed to load links: Azure CLI request timed out")
+        );
+        // The failed items are fetched again on the next start
+        assert!(!app.relations_loader_active);
+        let pending = app.get_ids_needing_relations();
+        assert!(pending.contains(&1001) && !pending.contains(&1002));
+    }
+
+    #[tokio::test]
+    async fn test_discussion_comments() {
+        use crate::app::PreviewTab;
+
+        let (mut app, backend) = demo_app().await;
+        app.load_users().await.unwrap();
+        app.expanded_items.insert(1001);
+        app.rebuild_visible_items();
+        let idx = app
+            .visible_items
+            .iter()
+            .position(|v| v.item.id == 1003)
+            .unwrap();
+        app.work_item_list_state.select(Some(idx));
+
+        async fn wait(app: &mut App<FakeBackend>, done: impl Fn(&App<FakeBackend>) -> bool) {
+            poll_until(app, |app| {
+                app.poll_comments();
+                done(app)
+            })
+            .await
+        }
+
+        handle_key(&mut app, press('l')).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Tab)).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Tab)).await.unwrap();
+        assert_eq!(app.preview_tab, PreviewTab::Discussion);
+        wait(&mut app, |a| a.work_item_comments.contains_key(&1003)).await;
+        let ids: Vec<i32> = app.selected_comments().iter().map(|c| c.id).collect();
+        assert_eq!(ids, [2, 1]);
+
+        // Someone else's comment cannot be changed
+        handle_key(&mut app, press('j')).await.unwrap();
+        assert_eq!(app.selected_comment_index(), 1);
+        handle_key(&mut app, press('e')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(app.comment_draft.is_none());
+
+        // Post with a mention completed from the known users
+        handle_key(&mut app, press('C')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::CommentComposer);
+        for c in "Reviewed, thanks @gra".chars() {
+            handle_key(&mut app, press(c)).await.unwrap();
+        }
+        handle_key(&mut app, key(KeyCode::Tab)).await.unwrap();
+        assert_eq!(
+            app.comment_draft.as_ref().unwrap().text,
+            "Reviewed, thanks @Grace Hopper "
+        );
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        handle_key(&mut app, ctrl_s).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        wait(&mut app, |a| a.selected_comments().len() == 3).await;
+        let posted = &backend.fixtures().comments[&1003][0];
+        assert!(posted
+            .text
+            .contains(r#"<a href="mailto:grace@contoso.com">@Grace Hopper</a>"#));
+        assert_eq!(app.selected_comment_index(), 0);
+
+        // Edit our own comment
+        handle_key(&mut app, press('e')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::CommentComposer);
+        handle_key(&mut app, press('!')).await.unwrap();
+        handle_key(&mut app, ctrl_s).await.unwrap();
+        wait(&mut app, |a| {
+            a.selected_work_item_comment()
+                .is_some_and(|c| c.text.contains("Hopper</a>!"))
+        })
+        .await;
+        assert_eq!(backend.fixtures().comments[&1003][0].id, posted.id);
+
+        // Delete it after confirming
+        handle_key(&mut app, press('D')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::ConfirmAction);
+        handle_key(&mut app, press('y')).await.unwrap();
+        wait(&mut app, |a| a.selected_comments().len() == 2).await;
+        assert_eq!(backend.fixtures().comments[&1003].len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_link_management() {
+        use crate::app::PreviewTab;
+        use crate::work_item_link::LinkKind;
+
+        let (mut app, backend) = demo_app().await;
+        app.expanded_items.insert(1001);
+        app.rebuild_visible_items();
+        let idx = app
+            .visible_items
+            .iter()
+            .position(|v| v.item.id == 1003)
+            .unwrap();
+        app.work_item_list_state.select(Some(idx));
+
+        async fn link(app: &mut App<FakeBackend>, keys: &str) {
+            for c in keys.chars() {
+                handle_key(app, press(c)).await.unwrap();
+            }
+            handle_key(app, key(KeyCode::Enter)).await.unwrap();
+            poll_until(app, |app| {
+                app.poll_links();
+                app.link_rx.is_none()
+            })
+            .await
+        }
+        let item = |app: &App<FakeBackend>, id: i32| {
+            App::flatten_work_items(&app.work_items)
+                .into_iter()
+                .find(|w| w.id == id)
+                .unwrap()
+        };
+
+        // Reparent under another story; the item stays selected in its new place
+        handle_key(&mut app, press('L')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::LinkWorkItem);
+        assert_eq!(app.link_dialog.as_ref().unwrap().kind, LinkKind::Parent);
+        link(&mut app, "1005").await;
+        assert!(!app.status_is_error, "{:?}", app.status_message);
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(item(&app, 1003).fields.parent_id, Some(1005));
+        let parent = app.work_items.iter().find(|w| w.id == 1005).unwrap();
+        assert!(parent.children.iter().any(|c| c.id == 1003));
+        assert_eq!(app.selected_work_item().unwrap().item.id, 1003);
+
+        // A related work item, then a pull request
+        handle_key(&mut app, press('L')).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Tab)).await.unwrap();
+        assert_eq!(app.link_dialog.as_ref().unwrap().kind, LinkKind::Related);
+        link(&mut app, "1004").await;
+        handle_key(&mut app, press('L')).await.unwrap();
+        handle_key(&mut app, key(KeyCode::BackTab)).await.unwrap();
+        handle_key(&mut app, key(KeyCode::BackTab)).await.unwrap();
+        assert_eq!(
+            app.link_dialog.as_ref().unwrap().kind,
+            LinkKind::PullRequest
+        );
+        link(&mut app, "502").await;
+        assert!(!app.status_is_error, "{:?}", app.status_message);
+        let relations = item(&app, 1003).relations.unwrap();
+        assert_eq!(relations.len(), 3);
+        assert!(relations
+            .iter()
+            .any(|r| r.rel == "System.LinkTypes.Related" && r.target_id() == Some(1004)));
+        assert!(relations.iter().any(|r| r.url.ends_with("%2F502")));
+        // The other end reloads its links
+        assert!(!app.relations_loaded.contains(&1004) || app.relations_loader_active);
+
+        // Remove the highlighted reference after confirming
+        handle_key(&mut app, press('X')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        handle_key(&mut app, press('l')).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Tab)).await.unwrap();
+        assert_eq!(app.preview_tab, PreviewTab::References);
+        app.relations_list_state.select(Some(0));
+        let removed = app.selected_relation().unwrap().clone();
+        handle_key(&mut app, press('X')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::ConfirmAction);
+        handle_key(&mut app, press('y')).await.unwrap();
+        poll_until(&mut app, |app| {
+            app.poll_links();
+            app.link_rx.is_none()
+        })
+        .await;
+        let fixture = &backend.fixtures().work_items;
+        let stored = fixture.iter().find(|w| w.id == 1003).unwrap();
+        assert_eq!(stored.relations.as_ref().unwrap().len(), 2);
+        assert!(!stored
+            .relations
+            .as_ref()
+            .unwrap()
+            .iter()
+            .any(|r| r.same_link(&removed)));
+        assert_eq!(item(&app, 1003).relations.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_switch_to_prs_loads_repositories() {
+        let (mut app, _) = demo_app().await;
+
+        handle_key(&mut app, press('2')).await.unwrap();
+        assert_eq!(app.current_view, View::PRs);
+
+        poll_until(&mut app, |app| {
+            app.poll_pr_results();
+            !app.repositories.is_empty()
+        })
+        .await;
+        assert_eq!(app.repositories.len(), 2);
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    /// Run `step` (poll what the test waits on, then check it) until it
+    /// returns true; fails the test after ~1s
+    async fn poll_until(
+        app: &mut App<FakeBackend>,
+        mut step: impl FnMut(&mut App<FakeBackend>) -> bool,
+    ) {
+        for _ in 0..100 {
+            if step(app) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("condition not reached after polling");
+    }
+
+    /// Poll PR results until `done` holds
+    async fn wait_for_prs(app: &mut App<FakeBackend>, done: impl Fn(&App<FakeBackend>) -> bool) {
+        poll_until(app, |app| {
+            app.poll_pr_results();
+            done(app)
+        })
+        .await
+    }
+
+    /// Open the PRs view on the `web` repository with PR 501 selected
+    async fn demo_app_on_pr() -> (App<FakeBackend>, FakeBackend) {
+        let (mut app, backend) = demo_app().await;
+        handle_key(&mut app, press('2')).await.unwrap();
+        wait_for_prs(&mut app, |a| !a.repositories.is_empty()).await;
+
+        app.selected_repo_idx = app
+            .repositories
+            .iter()
+            .position(|r| r.name == "web")
+            .unwrap();
+        // Drill in directly
+        app.pr_drill_down = crate::app::PRDrillDown::PRs;
+        app.load_prs_for_repo();
+        wait_for_prs(&mut app, |a| {
+            a.pr_active.iter().any(|p| p.pull_request_id == 501)
+        })
+        .await;
+
+        app.selected_pr_idx = app
+            .pull_requests()
+            .iter()
+            .position(|p| p.pull_request_id == 501)
+            .unwrap();
+        (app, backend)
+    }
+
+    #[tokio::test]
+    async fn test_vote_and_comment_on_pr() {
+        let (mut app, backend) = demo_app_on_pr().await;
+
+        handle_key(&mut app, press('v')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::PRVote);
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        let my_vote = || async {
+            let pr = backend.get_pull_request(501).await.unwrap();
+            pr.reviewers
+                .into_iter()
+                .find(|r| r.unique_name.as_deref() == Some("ada@contoso.com"))
+                .map(|r| r.vote)
+        };
+        for _ in 0..100 {
+            if my_vote().await.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(my_vote().await, Some(10));
+
+        let before = backend.list_pr_threads("", 501).await.unwrap().len();
+        handle_key(&mut app, press('c')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::PRComment);
+        for c in "Looks good".chars() {
+            handle_key(&mut app, press(c)).await.unwrap();
+        }
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        handle_key(&mut app, ctrl_s).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        for _ in 0..100 {
+            if backend.list_pr_threads("", 501).await.unwrap().len() > before {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let threads = backend.list_pr_threads("", 501).await.unwrap();
+        let last = threads.last().unwrap();
+        assert_eq!(last.comments[0].content.as_deref(), Some("Looks good"));
+    }
+
+    #[tokio::test]
+    async fn test_complete_pr_with_auto_complete() {
+        let (mut app, backend) = demo_app_on_pr().await;
+
+        handle_key(&mut app, press('m')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::PRComplete);
+        wait_for_prs(&mut app, |a| {
+            a.pr_complete_dialog
+                .as_ref()
+                .is_some_and(|d| !d.loading_policies)
+        })
+        .await;
+        assert_eq!(
+            app.pr_complete_dialog
+                .as_ref()
+                .unwrap()
+                .blocking_failures()
+                .len(),
+            1
+        );
+
+        // The running build blocks completing right away
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        handle_key(&mut app, ctrl_s).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::PRComplete);
+        assert!(!app.pr_complete_dialog.as_ref().unwrap().submitting);
+
+        // Squash, then auto-complete once policies pass
+        handle_key(&mut app, key(KeyCode::Down)).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Tab)).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Tab)).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Down)).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Down)).await.unwrap();
+        handle_key(&mut app, press(' ')).await.unwrap();
+        assert!(app.pr_complete_dialog.as_ref().unwrap().auto_complete);
+        handle_key(&mut app, ctrl_s).await.unwrap();
+        wait_for_prs(&mut app, |a| a.pr_complete_dialog.is_none()).await;
+        assert_eq!(app.input_mode, InputMode::Normal);
+
+        let pr = backend.get_pull_request(501).await.unwrap();
+        assert!(pr.auto_complete_set_by.is_some());
+        assert_eq!(pr.status.as_deref(), Some("active"));
+        let options = pr.completion_options.unwrap();
+        assert_eq!(options.merge_strategy.as_deref(), Some("squash"));
+    }
+
+    #[tokio::test]
+    async fn test_comment_on_diff_lines() {
+        let (mut app, backend) = demo_app_on_pr().await;
+
+        handle_key(&mut app, press('F')).await.unwrap();
+        assert_eq!(app.pr_drill_down, crate::app::PRDrillDown::Files);
+        wait_for_prs(&mut app, |a| {
+            a.pr_files
+                .as_ref()
+                .is_some_and(|f| !f.loading && !f.changes.is_empty())
+        })
+        .await;
+        let files = app.pr_files.as_ref().unwrap();
+        assert_eq!(files.changes.len(), 3);
+        let idx = files
+            .changes
+            .iter()
+            .position(|c| c.path == "/src/checkout/PaymentStep.tsx")
+            .unwrap();
+        app.select_pr_file(idx);
+
+        handle_key(&mut app, key(KeyCode::Enter)).await.unwrap();
+        wait_for_prs(&mut app, |a| a.focused_file_diff().is_some()).await;
+        let diff = app.focused_file_diff().unwrap();
+        // Ada's existing thread is anchored to the PayButton line
+        let threads = diff.threads(&app.pr_threads);
+        assert!(threads.iter().any(|(row, t)| row.is_some() && t.id == 2));
+
+        // Select two lines and comment on them
+        handle_key(&mut app, press('V')).await.unwrap();
+        handle_key(&mut app, press('j')).await.unwrap();
+        let before = backend.list_pr_threads("", 501).await.unwrap().len();
+        handle_key(&mut app, press('c')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::PRComment);
+        for c in "Extract this".chars() {
+            handle_key(&mut app, press(c)).await.unwrap();
+        }
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        handle_key(&mut app, ctrl_s).await.unwrap();
+        for _ in 0..100 {
+            if backend.list_pr_threads("", 501).await.unwrap().len() > before {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let threads = backend.list_pr_threads("", 501).await.unwrap();
+        let context = threads.last().unwrap().thread_context.clone().unwrap();
+        assert_eq!(context.file_path, "/src/checkout/PaymentStep.tsx");
+        let start = context.right_file_start.unwrap().line;
+        let end = context.right_file_end.unwrap().line;
+        assert_eq!(end, start + 1);
+        assert!(app.focused_file_diff().unwrap().anchor.is_none());
+
+        // Esc walks back to the PR list
+        handle_key(&mut app, key(KeyCode::Esc)).await.unwrap();
+        handle_key(&mut app, key(KeyCode::Esc)).await.unwrap();
+        assert_eq!(app.pr_drill_down, crate::app::PRDrillDown::PRs);
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_from_work_item() {
+        let (mut app, backend) = demo_app().await;
+        app.config.projects[0].repository = Some("web".to_string());
+        app.expanded_items.insert(1001);
+        app.rebuild_visible_items();
+        let idx = app
+            .visible_items
+            .iter()
+            .position(|v| v.item.id == 1003)
+            .unwrap();
+        app.work_item_list_state.select(Some(idx));
+
+        handle_key(&mut app, press('n')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::PRCreate);
+        wait_for_prs(&mut app, |a| {
+            a.pr_create_dialog
+                .as_ref()
+                .is_some_and(|d| !d.loading_branches)
+        })
+        .await;
+        let dialog = app.pr_create_dialog.as_ref().unwrap();
+        assert_eq!(dialog.source, "feature/1003-address-autocomplete");
+        assert_eq!(dialog.target, "main");
+
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        handle_key(&mut app, ctrl_s).await.unwrap();
+        wait_for_prs(&mut app, |a| a.pr_create_dialog.is_none()).await;
+        assert_eq!(app.input_mode, InputMode::Normal);
+
+        let fixtures = backend.fixtures();
+        let pr = &fixtures.pull_requests[0];
+        assert_eq!(pr.title, "Address autocomplete");
+        assert_eq!(fixtures.pr_work_items[&pr.pull_request_id], [1003]);
+    }
+
+    #[tokio::test]
+    async fn test_abandon_pr_requires_confirmation() {
+        let (mut app, backend) = demo_app_on_pr().await;
+
+        handle_key(&mut app, press('X')).await.unwrap();
+        assert_eq!(app.input_mode, InputMode::ConfirmAction);
+
+        // Declining leaves the PR alone
+        handle_key(&mut app, press('n')).await.unwrap();
+        assert!(app.confirm_action_dialog.is_none());
+        let pr = backend.get_pull_request(501).await.unwrap();
+        assert_eq!(pr.status.as_deref(), Some("active"));
+
+        handle_key(&mut app, press('X')).await.unwrap();
+        handle_key(&mut app, press('y')).await.unwrap();
+        wait_for_prs(&mut app, |a| {
+            a.pr_abandoned.iter().any(|p| p.pull_request_id == 501)
+                && a.pr_active.iter().all(|p| p.pull_request_id != 501)
+        })
+        .await;
+        let pr = backend.get_pull_request(501).await.unwrap();
+        assert_eq!(pr.status.as_deref(), Some("abandoned"));
+        assert!(app.pr_active.iter().all(|p| p.pull_request_id != 501));
+    }
+}
@@ -0,0 +1,292 @@
+//! "Why did it fail?" panel of a build: the errors and warnings its timeline
+//! records report, with the task, file and log line of each
+
+use crate::azure::TimelineRecord;
+use crate::log_view::{clean_line, strip_timestamp, Severity};
+
+/// An error or warning reported by a timeline record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailureIssue {
+    pub severity: Severity,
+    pub message: String,
+    /// Task (or job / stage) that reported it
+    pub task: String,
+    pub record_id: String,
+    /// Log of the record, if it has one
+    pub log_id: Option<i32>,
+    /// 1-based line of the log that logged it
+    pub log_line: Option<usize>,
+    pub file: Option<String>,
+    pub line: Option<usize>,
+}
+
+impl FailureIssue {
+    /// `file:line`, or just the file
+    pub fn location(&self) -> Option<String> {
+        let file = self.file.as_deref()?;
+        Some(match self.line {
+            Some(line) => format!("{file}:{line}"),
+            None => file.to_string(),
+        })
+    }
+
+    /// Index of the log line the issue was logged on: the reported line,
+    /// else the first line quoting the message, else the first line of the
+    /// same severity
+    pub fn find_in(&self, lines: &[String]) -> Option<usize> {
+        if let Some(n) = self.log_line.filter(|&n| n >= 1 && n <= lines.len()) {
+            return Some(n - 1);
+        }
+        let message = self.message.lines().next().unwrap_or("").trim();
+        let quotes = |line: &String| {
+            let text = clean_line(line);
+            let text = strip_timestamp(&text);
+            !message.is_empty() && text.contains(message)
+        };
+        lines.iter().position(quotes).or_else(|| {
+            lines
+                .iter()
+                .position(|l| Severity::of(&clean_line(l)) == Some(self.severity))
+        })
+    }
+}
+
+/// Errors and warnings of a build
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IssueCounts {
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+fn severity(issue_type: Option<&str>) -> Option<Severity> {
+    match issue_type?.to_ascii_lowercase().as_str() {
+        "error" => Some(Severity::Error),
+        "warning" => Some(Severity::Warning),
+        _ => None,
+    }
+}
+
+/// Tasks first, then the jobs and stages around them
+fn rank(record: &TimelineRecord) -> u8 {
+    match record.record_type.as_deref() {
+        Some("Task") => 0,
+        Some("Job") | Some("Phase") => 1,
+        Some("Stage") => 2,
+        _ => 3,
+    }
+}
+
+/// Every error, then every warning, of the build's records
+pub fn summarize(records: &[TimelineRecord]) -> Vec<FailureIssue> {
+    let mut sorted: Vec<&TimelineRecord> = records.iter().collect();
+    sorted.sort_by_key(|r| (rank(r), r.order.unwrap_or(i32::MAX)));
+
+    let mut issues: Vec<FailureIssue> = sorted
+        .into_iter()
+        .flat_map(|record| {
+            record.issues.iter().flatten().filter_map(move |issue| {
+                Some(FailureIssue {
+                    severity: severity(issue.issue_type.as_deref())?,
+                    message: issue.message.clone().unwrap_or_default(),
+                    task: record.name.clone().unwrap_or_else(|| record.id.clone()),
+                    record_id: record.id.clone(),
+                    log_id: record.log.as_ref().map(|l| l.id),
+                    log_line: issue.log_line(),
+                    file: issue.source_path(),
+                    line: issue.line_number(),
+                })
+            })
+        })
+        .collect();
+    issues.sort_by_key(|i| i.severity != Severity::Error);
+    issues
+}
+
+/// Errors and warnings of the build; the API only lists the first issues
+/// of a record, so its counts win when they are higher
+pub fn counts(records: &[TimelineRecord]) -> IssueCounts {
+    let mut counts = IssueCounts::default();
+    for record in records {
+        let listed = |wanted: Severity| {
+            record
+                .issues
+                .iter()
+                .flatten()
+                .filter(|i| severity(i.issue_type.as_deref()) == Some(wanted))
+                .count()
+        };
+        let reported = |count: Option<i32>| count.unwrap_or(0).max(0) as usize;
+        counts.errors += listed(Severity::Error).max(reported(record.error_count));
+        counts.warnings += listed(Severity::Warning).max(reported(record.warning_count));
+    }
+    counts
+}
+
+/// The panel listing a build's issues
+#[derive(Debug, Clone)]
+pub struct FailureSummary {
+    pub build_id: i32,
+    pub build_number: String,
+    /// Timeline the issues come from, shown when jumping into the build
+    pub records: Vec<TimelineRecord>,
+    pub issues: Vec<FailureIssue>,
+    pub counts: IssueCounts,
+    pub loading: bool,
+    pub selected: usize,
+}
+
+impl FailureSummary {
+    pub fn new(build_id: i32, build_number: String) -> Self {
+        Self {
+            build_id,
+            build_number,
+            records: Vec::new(),
+            issues: Vec::new(),
+            counts: IssueCounts::default(),
+            loading: true,
+            selected: 0,
+        }
+    }
+
+    pub fn load(&mut self, records: Vec<TimelineRecord>) {
+        self.issues = summarize(&records);
+        self.counts = counts(&records);
+        self.records = records;
+        self.loading = false;
+        self.selected = 0;
+    }
+
+    pub fn next(&mut self) {
+        if self.selected + 1 < self.issues.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn selected_issue(&self) -> Option<&FailureIssue> {
+        self.issues.get(self.selected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::azure::{TimelineIssue, TimelineLog};
+
+    fn issue(issue_type: &str, message: &str, data: serde_json::Value) -> TimelineIssue {
+        TimelineIssue {
+            issue_type: Some(issue_type.to_string()),
+            message: Some(message.to_string()),
+            data: serde_json::from_value(data).ok(),
+            ..Default::default()
+        }
+    }
+
+    fn records() -> Vec<TimelineRecord> {
+        vec![
+            TimelineRecord {
+                id: "job".to_string(),
+                name: Some("Build web".to_string()),
+                record_type: Some("Job".to_string()),
+                issues: Some(vec![issue(
+                    "error",
+                    "The job exceeded its timeout",
+                    serde_json::json!({}),
+                )]),
+                ..Default::default()
+            },
+            TimelineRecord {
+                id: "test".to_string(),
+                name: Some("npm test".to_string()),
+                record_type: Some("Task".to_string()),
+                parent_id: Some("job".to_string()),
+                order: Some(2),
+                log: Some(TimelineLog { id: 22, url: None }),
+                error_count: Some(12),
+                warning_count: Some(1),
+                issues: Some(vec![
+                    issue(
+                        "warning",
+                        "npm WARN deprecated",
+                        serde_json::json!({"logFileLineNumber": "2"}),
+                    ),
+                    issue(
+                        "error",
+                        "rounds JPY",
+                        serde_json::json!({"sourcepath": "src/checkout.test.ts", "linenumber": "42"}),
+                    ),
+                ]),
+                ..Default::default()
+            },
+            TimelineRecord {
+                id: "restore".to_string(),
+                name: Some("npm ci".to_string()),
+                record_type: Some("Task".to_string()),
+                order: Some(1),
+                issues: Some(vec![issue(
+                    "error",
+                    "lockfile out of date",
+                    serde_json::json!({}),
+                )]),
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_summarize_orders_errors_by_task() {
+        let issues = summarize(&records());
+        let listed: Vec<(&str, Severity)> = issues
+            .iter()
+            .map(|i| (i.task.as_str(), i.severity))
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                ("npm ci", Severity::Error),
+                ("npm test", Severity::Error),
+                ("Build web", Severity::Error),
+                ("npm test", Severity::Warning),
+            ]
+        );
+        assert_eq!(
+            issues[1].location().as_deref(),
+            Some("src/checkout.test.ts:42")
+        );
+        assert_eq!(issues[1].log_id, Some(22));
+        assert_eq!(issues[3].log_line, Some(2));
+
+        // Counts beyond the listed issues are kept
+        assert_eq!(
+            counts(&records()),
+            IssueCounts {
+                errors: 14,
+                warnings: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_find_issue_in_log() {
+        let lines: Vec<String> = [
+            "2026-10-14T09:41:00.0000000Z > web test",
+            "2026-10-14T09:41:01.0000000Z ##[warning]npm WARN deprecated",
+            "2026-10-14T09:41:02.0000000Z ● totals › rounds JPY",
+            "2026-10-14T09:41:03.0000000Z ##[error]Bash exited with code '1'.",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let issues = summarize(&records());
+        // The reported line
+        assert_eq!(issues[3].find_in(&lines), Some(1));
+        // The message
+        assert_eq!(issues[1].find_in(&lines), Some(2));
+        // The first error
+        assert_eq!(issues[0].find_in(&lines), Some(3));
+        assert_eq!(issues[0].find_in(&[]), None);
+    }
+}
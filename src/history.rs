@@ -0,0 +1,355 @@
+//! Timeline of a work item's update history for the History tab: who changed
+//! which field from what to what, with long text fields shown as diffs
+
+use crate::azure::{WorkItemFieldChange, WorkItemRelation, WorkItemUpdate};
+use crate::markdown::html_to_markdown;
+use crate::pr_diff::{unified_diff, DiffLineKind};
+use chrono::{DateTime, Datelike, Utc};
+use serde_json::Value;
+
+/// Fields the server maintains on every save; they only add noise
+const HIDDEN_FIELDS: &[&str] = &[
+    "System.Id",
+    "System.Rev",
+    "System.Watermark",
+    "System.ChangedDate",
+    "System.ChangedBy",
+    "System.AuthorizedDate",
+    "System.AuthorizedAs",
+    "System.RevisedDate",
+    "System.PersonId",
+    "System.CreatedDate",
+    "System.CreatedBy",
+    "System.TeamProject",
+    "System.AreaId",
+    "System.IterationId",
+    "System.NodeName",
+    "System.CommentCount",
+    "System.BoardColumnDone",
+    "Microsoft.VSTS.Common.StateChangeDate",
+];
+
+/// Rich text fields, shown as a diff of their Markdown
+const HTML_FIELDS: &[&str] = &[
+    "System.Description",
+    "Microsoft.VSTS.Common.AcceptanceCriteria",
+    "Microsoft.VSTS.TCM.ReproSteps",
+];
+
+/// Discussion comments are saved as changes of this field
+const COMMENT_FIELD: &str = "System.History";
+
+/// One save of the work item
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub rev: Option<i32>,
+    pub author: String,
+    pub date: Option<DateTime<Utc>>,
+    /// The first revision, which created the item
+    pub created: bool,
+    pub changes: Vec<HistoryChange>,
+}
+
+/// One readable change of an entry
+#[derive(Debug, Clone, PartialEq)]
+pub enum HistoryChange {
+    State {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    Assigned {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    Field {
+        name: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+    /// Rich text edit: the added (`true`) and removed lines
+    Text {
+        name: String,
+        lines: Vec<(bool, String)>,
+    },
+    Comment(String),
+    Link {
+        added: bool,
+        relation: WorkItemRelation,
+    },
+}
+
+/// Readable timeline of the updates, newest first; saves that only touched
+/// hidden fields are left out
+pub fn timeline(updates: &[WorkItemUpdate]) -> Vec<HistoryEntry> {
+    let mut entries: Vec<HistoryEntry> = updates
+        .iter()
+        .enumerate()
+        .map(|(idx, update)| entry(update, idx == 0))
+        .filter(|entry| !entry.changes.is_empty())
+        .collect();
+    entries.reverse();
+    entries
+}
+
+fn entry(update: &WorkItemUpdate, first: bool) -> HistoryEntry {
+    let mut changes = Vec::new();
+    let field = |name: &str| update.fields.get(name);
+
+    if let Some(change) = field("System.State") {
+        changes.push(HistoryChange::State {
+            from: text(change.old_value.as_ref()),
+            to: text(change.new_value.as_ref()),
+        });
+    }
+    if let Some(change) = field("System.AssignedTo") {
+        changes.push(HistoryChange::Assigned {
+            from: text(change.old_value.as_ref()),
+            to: text(change.new_value.as_ref()),
+        });
+    }
+    for (name, change) in &update.fields {
+        if HIDDEN_FIELDS.contains(&name.as_str())
+            || matches!(name.as_str(), "System.State" | "System.AssignedTo")
+            // Team-specific copies of the board column and lane
+            || name.starts_with("WEF_")
+        {
+            continue;
+        }
+        if name == COMMENT_FIELD {
+            if let Some(comment) = change.new_value.as_ref().and_then(Value::as_str) {
+                let plain = html2text::from_read(comment.as_bytes(), 200);
+                changes.push(HistoryChange::Comment(plain.trim().to_string()));
+            }
+        } else if HTML_FIELDS.contains(&name.as_str()) {
+            changes.push(HistoryChange::Text {
+                name: field_label(name),
+                lines: text_diff(change),
+            });
+        } else {
+            changes.push(HistoryChange::Field {
+                name: field_label(name),
+                from: text(change.old_value.as_ref()),
+                to: text(change.new_value.as_ref()),
+            });
+        }
+    }
+    if let Some(relations) = &update.relations {
+        let links = |list: &[WorkItemRelation], added: bool| {
+            list.iter()
+                .map(|relation| HistoryChange::Link {
+                    added,
+                    relation: relation.clone(),
+                })
+                .collect::<Vec<_>>()
+        };
+        changes.extend(links(&relations.added, true));
+        changes.extend(links(&relations.removed, false));
+    }
+
+    let author = update
+        .revised_by
+        .as_ref()
+        .and_then(|by| by.display_name.clone().or_else(|| by.unique_name.clone()))
+        .or_else(|| text(field("System.ChangedBy").and_then(|c| c.new_value.as_ref())))
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    // revisedDate is when the revision was superseded; the change itself is dated
+    // by ChangedDate, and the latest revision has a placeholder far in the future
+    let date = field("System.ChangedDate")
+        .and_then(|c| c.new_value.as_ref())
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .or(update.revised_date.filter(|d| d.year() < 9999));
+
+    HistoryEntry {
+        rev: update.rev,
+        author,
+        date,
+        created: first && update.rev.is_none_or(|rev| rev <= 1),
+        changes,
+    }
+}
+
+/// Display text of a field value: identities by name, whole numbers without `.0`
+fn text(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::Null => None,
+        Value::String(s) if s.is_empty() => None,
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(match n.as_f64() {
+            Some(f) if f.fract() == 0.0 && !n.is_i64() => format!("{f:.0}"),
+            _ => n.to_string(),
+        }),
+        Value::Object(obj) => obj
+            .get("displayName")
+            .or_else(|| obj.get("uniqueName"))
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        other => Some(other.to_string()),
+    }
+}
+
+/// `Microsoft.VSTS.Scheduling.RemainingWork` -> `Remaining Work`
+fn field_label(reference_name: &str) -> String {
+    let last = reference_name.rsplit('.').next().unwrap_or(reference_name);
+    let mut label = String::new();
+    for (idx, c) in last.chars().enumerate() {
+        if idx > 0 && c.is_uppercase() && !label.ends_with(' ') {
+            label.push(' ');
+        }
+        label.push(c);
+    }
+    label
+}
+
+/// Added and removed lines of a rich text field, compared as Markdown
+fn text_diff(change: &WorkItemFieldChange) -> Vec<(bool, String)> {
+    let markdown = |value: Option<&Value>| {
+        value
+            .and_then(Value::as_str)
+            .map(html_to_markdown)
+            // Same line ending on both sides, or an unchanged last line
+            // shows up as removed and added again
+            .map(|md| format!("{}\n", md.trim_end()))
+            .unwrap_or_default()
+    };
+    let old = markdown(change.old_value.as_ref());
+    let new = markdown(change.new_value.as_ref());
+    unified_diff(&old, &new)
+        .into_iter()
+        .filter(|line| !line.text.trim().is_empty())
+        .filter_map(|line| match line.kind {
+            DiffLineKind::Added => Some((true, line.text)),
+            DiffLineKind::Removed => Some((false, line.text)),
+            DiffLineKind::Hunk | DiffLineKind::Context => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(rev: i32, json: serde_json::Value) -> WorkItemUpdate {
+        let mut update: WorkItemUpdate = serde_json::from_value(json).unwrap();
+        update.rev = Some(rev);
+        update
+    }
+
+    #[test]
+    fn test_timeline_summarises_changes() {
+        let updates = vec![
+            update(
+                1,
+                serde_json::json!({
+                    "id": 1,
+                    "revisedBy": {"displayName": "Grace Hopper"},
+                    "revisedDate": "2026-09-29T11:00:00Z",
+                    "fields": {
+                        "System.State": {"newValue": "New"},
+                        "System.Title": {"newValue": "Autocomplete"},
+                        "System.CreatedDate": {"newValue": "2026-09-28T09:30:00Z"}
+                    }
+                }),
+            ),
+            update(
+                2,
+                serde_json::json!({
+                    "id": 2,
+                    "revisedBy": {"displayName": "Ada Lovelace"},
+                    "revisedDate": "9999-01-01T00:00:00Z",
+                    "fields": {
+                        "System.State": {"oldValue": "New", "newValue": "Active"},
+                        "System.AssignedTo": {
+                            "oldValue": {"displayName": "Grace Hopper"},
+                            "newValue": {"displayName": "Ada Lovelace"}
+                        },
+                        "System.ChangedDate": {"newValue": "2026-10-01T17:00:00Z"},
+                        "Microsoft.VSTS.Scheduling.RemainingWork": {"oldValue": 4.0, "newValue": 2.5},
+                        "System.Description": {
+                            "oldValue": "<p>Suggest addresses.</p>",
+                            "newValue": "<p>Suggest addresses.</p><p>Debounce by 300 ms.</p>"
+                        },
+                        "WEF_1_Kanban.Column": {"oldValue": "New", "newValue": "Active"}
+                    },
+                    "relations": {
+                        "added": [{"rel": "ArtifactLink", "url": "vstfs:///Git/Commit/p%2Fr%2Fabc",
+                                   "attributes": {"name": "Fixed in Commit"}}]
+                    }
+                }),
+            ),
+            // Only server-maintained fields: left out
+            update(
+                3,
+                serde_json::json!({"id": 3, "fields": {"System.Watermark": {"newValue": 9}}}),
+            ),
+        ];
+
+        let entries = timeline(&updates);
+        assert_eq!(entries.len(), 2);
+        let (latest, created) = (&entries[0], &entries[1]);
+
+        assert!(created.created);
+        assert_eq!(created.author, "Grace Hopper");
+        assert_eq!(
+            created.date.unwrap().to_rfc3339(),
+            "2026-09-29T11:00:00+00:00"
+        );
+        assert_eq!(
+            created.changes[1],
+            HistoryChange::Field {
+                name: "Title".into(),
+                from: None,
+                to: Some("Autocomplete".into())
+            }
+        );
+
+        assert!(!latest.created);
+        assert_eq!(latest.author, "Ada Lovelace");
+        // ChangedDate dates the change, not the placeholder revisedDate
+        assert_eq!(
+            latest.date.unwrap().to_rfc3339(),
+            "2026-10-01T17:00:00+00:00"
+        );
+        assert_eq!(
+            latest.changes[..2],
+            [
+                HistoryChange::State {
+                    from: Some("New".into()),
+                    to: Some("Active".into())
+                },
+                HistoryChange::Assigned {
+                    from: Some("Grace Hopper".into()),
+                    to: Some("Ada Lovelace".into())
+                },
+            ]
+        );
+        assert!(latest.changes.contains(&HistoryChange::Field {
+            name: "Remaining Work".into(),
+            from: Some("4".into()),
+            to: Some("2.5".into())
+        }));
+        assert!(latest.changes.contains(&HistoryChange::Text {
+            name: "Description".into(),
+            lines: vec![(true, "Debounce by 300 ms.".into())]
+        }));
+        assert!(matches!(
+            latest.changes.last(),
+            Some(HistoryChange::Link { added: true, relation }) if relation.url.ends_with("abc")
+        ));
+        assert_eq!(latest.changes.len(), 5);
+    }
+
+    #[test]
+    fn test_field_labels_and_values() {
+        assert_eq!(field_label("System.IterationPath"), "Iteration Path");
+        assert_eq!(field_label("Custom.Team"), "Team");
+        assert_eq!(text(Some(&serde_json::json!(3.0))), Some("3".into()));
+        assert_eq!(text(Some(&serde_json::json!(2))), Some("2".into()));
+        assert_eq!(text(Some(&serde_json::json!(""))), None);
+        assert_eq!(text(Some(&serde_json::json!(true))), Some("true".into()));
+        assert_eq!(
+            text(Some(&serde_json::json!({"uniqueName": "ada@contoso.com"}))),
+            Some("ada@contoso.com".into())
+        );
+    }
+}
@@ -0,0 +1,932 @@
+use crate::app::View;
+use crate::config::Keybindings;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::fmt;
+
+const ALL_VIEWS: &[View] = &[View::Tasks, View::PRs, View::CICD, View::Board];
+
+/// Something a key (or key sequence) does in normal mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    // Navigation
+    Down,
+    Up,
+    Left,
+    Right,
+    Top,
+    Bottom,
+    PageDown,
+    PageUp,
+    NextTab,
+    PrevTab,
+    Back,
+    // Actions
+    Open,
+    Expand,
+    ToggleAll,
+    Pin,
+    CopyId,
+    CopyLink,
+    // Filters
+    Search,
+    FilterState,
+    FilterAssignee,
+    ClearFilters,
+    // Editing
+    EditState,
+    EditAssignee,
+    EditFields,
+    CreateWorkItem,
+    EditTags,
+    MoveIteration,
+    // Discussion
+    AddComment,
+    DeleteComment,
+    // Links
+    AddLink,
+    RemoveLink,
+    // Multi-select
+    Mark,
+    VisualSelect,
+    MarkChildren,
+    MarkAll,
+    // Board
+    MoveCardLeft,
+    MoveCardRight,
+    // Selection
+    SelectSprint,
+    Queries,
+    SprintStats,
+    TeamCapacity,
+    SelectProject,
+    ChangeRepo,
+    Refresh,
+    Help,
+    Quit,
+    // Views
+    ViewTasks,
+    ViewPRs,
+    ViewCICD,
+    ViewBoard,
+    // CI/CD
+    Cancel,
+    Trigger,
+    TriggerPreset,
+    Approve,
+    ApproveAll,
+    OpenLog,
+    LoadAllRuns,
+    LivePreview,
+    FailureSummary,
+    Artifacts,
+    // Log preview
+    LogSearch,
+    NextMatch,
+    PrevMatch,
+    NextError,
+    PrevError,
+    NextWarning,
+    PrevWarning,
+    LogFilter,
+    ToggleTimestamps,
+    // Pull requests
+    CreatePR,
+    Vote,
+    Comment,
+    Reply,
+    ResolveThread,
+    ToggleDraft,
+    Abandon,
+    CompletePR,
+    Files,
+    SelectLines,
+}
+
+impl Action {
+    pub const ALL: &'static [Action] = &[
+        Action::Down,
+        Action::Up,
+        Action::Left,
+        Action::Right,
+        Action::Top,
+        Action::Bottom,
+        Action::PageDown,
+        Action::PageUp,
+        Action::NextTab,
+        Action::PrevTab,
+        Action::Back,
+        Action::Open,
+        Action::Expand,
+        Action::ToggleAll,
+        Action::Pin,
+        Action::CopyId,
+        Action::CopyLink,
+        Action::Search,
+        Action::FilterState,
+        Action::FilterAssignee,
+        Action::ClearFilters,
+        Action::EditState,
+        Action::EditAssignee,
+        Action::EditFields,
+        Action::CreateWorkItem,
+        Action::EditTags,
+        Action::MoveIteration,
+        Action::AddComment,
+        Action::DeleteComment,
+        Action::AddLink,
+        Action::RemoveLink,
+        Action::Mark,
+        Action::VisualSelect,
+        Action::MarkChildren,
+        Action::MarkAll,
+        Action::MoveCardLeft,
+        Action::MoveCardRight,
+        Action::SelectSprint,
+        Action::Queries,
+        Action::SprintStats,
+        Action::TeamCapacity,
+        Action::SelectProject,
+        Action::ChangeRepo,
+        Action::Refresh,
+        Action::Help,
+        Action::Quit,
+        Action::ViewTasks,
+        Action::ViewPRs,
+        Action::ViewCICD,
+        Action::ViewBoard,
+        Action::Cancel,
+        Action::Trigger,
+        Action::TriggerPreset,
+        Action::Approve,
+        Action::ApproveAll,
+        Action::OpenLog,
+        Action::LoadAllRuns,
+        Action::LivePreview,
+        Action::FailureSummary,
+        Action::Artifacts,
+        Action::LogSearch,
+        Action::NextMatch,
+        Action::PrevMatch,
+        Action::NextError,
+        Action::PrevError,
+        Action::NextWarning,
+        Action::PrevWarning,
+        Action::LogFilter,
+        Action::ToggleTimestamps,
+        Action::CreatePR,
+        Action::Vote,
+        Action::Comment,
+        Action::Reply,
+        Action::ResolveThread,
+        Action::ToggleDraft,
+        Action::Abandon,
+        Action::CompletePR,
+        Action::Files,
+        Action::SelectLines,
+    ];
+
+    /// Name used in the `[keybindings]` config section
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Down => "down",
+            Action::Up => "up",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::Top => "top",
+            Action::Bottom => "bottom",
+            Action::PageDown => "page_down",
+            Action::PageUp => "page_up",
+            Action::NextTab => "next_tab",
+            Action::PrevTab => "prev_tab",
+            Action::Back => "back",
+            Action::Open => "open",
+            Action::Expand => "expand",
+            Action::ToggleAll => "toggle_all",
+            Action::Pin => "pin",
+            Action::CopyId => "copy_id",
+            Action::CopyLink => "copy_link",
+            Action::Search => "search",
+            Action::FilterState => "filter_state",
+            Action::FilterAssignee => "filter_assignee",
+            Action::ClearFilters => "clear_filters",
+            Action::EditState => "edit_state",
+            Action::EditAssignee => "edit_assignee",
+            Action::EditFields => "edit_fields",
+            Action::CreateWorkItem => "create_work_item",
+            Action::EditTags => "edit_tags",
+            Action::MoveIteration => "move_iteration",
+            Action::AddComment => "add_comment",
+            Action::DeleteComment => "delete_comment",
+            Action::AddLink => "add_link",
+            Action::RemoveLink => "remove_link",
+            Action::Mark => "mark",
+            Action::VisualSelect => "visual_select",
+            Action::MarkChildren => "mark_children",
+            Action::MarkAll => "mark_all",
+            Action::MoveCardLeft => "move_card_left",
+            Action::MoveCardRight => "move_card_right",
+            Action::SelectSprint => "select_sprint",
+            Action::Queries => "queries",
+            Action::SprintStats => "sprint_stats",
+            Action::TeamCapacity => "team_capacity",
+            Action::SelectProject => "select_project",
+            Action::ChangeRepo => "change_repo",
+            Action::Refresh => "refresh",
+            Action::Help => "help",
+            Action::Quit => "quit",
+            Action::ViewTasks => "view_tasks",
+            Action::ViewPRs => "view_prs",
+            Action::ViewCICD => "view_cicd",
+            Action::ViewBoard => "view_board",
+            Action::Cancel => "cancel",
+            Action::Trigger => "trigger",
+            Action::TriggerPreset => "trigger_preset",
+            Action::Approve => "approve",
+            Action::ApproveAll => "approve_all",
+            Action::OpenLog => "open_log",
+            Action::LoadAllRuns => "load_all_runs",
+            Action::LivePreview => "live_preview",
+            Action::FailureSummary => "failure_summary",
+            Action::Artifacts => "artifacts",
+            Action::LogSearch => "log_search",
+            Action::NextMatch => "next_match",
+            Action::PrevMatch => "prev_match",
+            Action::NextError => "next_error",
+            Action::PrevError => "prev_error",
+            Action::NextWarning => "next_warning",
+            Action::PrevWarning => "prev_warning",
+            Action::LogFilter => "log_filter",
+            Action::ToggleTimestamps => "toggle_timestamps",
+            Action::CreatePR => "create_pr",
+            Action::Vote => "vote",
+            Action::Comment => "comment",
+            Action::Reply => "reply",
+            Action::ResolveThread => "resolve_thread",
+            Action::ToggleDraft => "toggle_draft",
+            Action::Abandon => "abandon",
+            Action::CompletePR => "complete",
+            Action::Files => "files",
+            Action::SelectLines => "select_lines",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|a| a.name() == name)
+    }
+
+    /// Views the action is bound in
+    pub fn views(self) -> &'static [View] {
+        match self {
+            Action::Pin => &[View::Tasks, View::CICD],
+            Action::CopyId | Action::CopyLink => &[View::Tasks, View::PRs, View::Board],
+            Action::CreatePR => &[View::Tasks, View::PRs],
+            Action::ToggleAll
+            | Action::FilterState
+            | Action::FilterAssignee
+            | Action::ClearFilters
+            | Action::EditState
+            | Action::EditAssignee
+            | Action::SelectSprint
+            | Action::Queries
+            | Action::SprintStats
+            | Action::TeamCapacity => &[View::Tasks, View::Board],
+            Action::MoveCardLeft | Action::MoveCardRight => &[View::Board],
+            Action::EditFields
+            | Action::CreateWorkItem
+            | Action::EditTags
+            | Action::MoveIteration
+            | Action::AddComment
+            | Action::DeleteComment
+            | Action::AddLink
+            | Action::RemoveLink
+            | Action::Mark
+            | Action::VisualSelect
+            | Action::MarkChildren
+            | Action::MarkAll => &[View::Tasks],
+            Action::ChangeRepo
+            | Action::Vote
+            | Action::Comment
+            | Action::Reply
+            | Action::ResolveThread
+            | Action::ToggleDraft
+            | Action::Abandon
+            | Action::CompletePR
+            | Action::Files
+            | Action::SelectLines => &[View::PRs],
+            Action::Cancel
+            | Action::Trigger
+            | Action::TriggerPreset
+            | Action::Approve
+            | Action::ApproveAll
+            | Action::OpenLog
+            | Action::LoadAllRuns
+            | Action::LivePreview
+            | Action::FailureSummary
+            | Action::Artifacts
+            | Action::LogSearch
+            | Action::NextMatch
+            | Action::PrevMatch
+            | Action::NextError
+            | Action::PrevError
+            | Action::NextWarning
+            | Action::PrevWarning
+            | Action::LogFilter
+            | Action::ToggleTimestamps => &[View::CICD],
+            _ => ALL_VIEWS,
+        }
+    }
+
+    /// Key spec from the global `[keybindings]` section
+    fn configured(self, keys: &Keybindings) -> &str {
+        match self {
+            Action::Down => &keys.down,
+            Action::Up => &keys.up,
+            Action::Left => &keys.left,
+            Action::Right => &keys.right,
+            Action::Top => &keys.top,
+            Action::Bottom => &keys.bottom,
+            Action::PageDown => &keys.page_down,
+            Action::PageUp => &keys.page_up,
+            Action::NextTab => &keys.next_tab,
+            Action::PrevTab => &keys.prev_tab,
+            Action::Back => &keys.back,
+            Action::Open => &keys.open,
+            Action::Expand => &keys.expand,
+            Action::ToggleAll => &keys.toggle_all,
+            Action::Pin => &keys.pin,
+            Action::CopyId => &keys.copy_id,
+            Action::CopyLink => &keys.copy_link,
+            Action::Search => &keys.search,
+            Action::FilterState => &keys.filter_state,
+            Action::FilterAssignee => &keys.filter_assignee,
+            Action::ClearFilters => &keys.clear_filters,
+            Action::EditState => &keys.edit_state,
+            Action::EditAssignee => &keys.edit_assignee,
+            Action::EditFields => &keys.edit_fields,
+            Action::CreateWorkItem => &keys.create_work_item,
+            Action::EditTags => &keys.edit_tags,
+            Action::MoveIteration => &keys.move_iteration,
+            Action::AddComment => &keys.add_comment,
+            Action::DeleteComment => &keys.delete_comment,
+            Action::AddLink => &keys.add_link,
+            Action::RemoveLink => &keys.remove_link,
+            Action::Mark => &keys.mark,
+            Action::VisualSelect => &keys.visual_select,
+            Action::MarkChildren => &keys.mark_children,
+            Action::MarkAll => &keys.mark_all,
+            Action::MoveCardLeft => &keys.move_card_left,
+            Action::MoveCardRight => &keys.move_card_right,
+            Action::SelectSprint => &keys.select_sprint,
+            Action::Queries => &keys.queries,
+            Action::SprintStats => &keys.sprint_stats,
+            Action::TeamCapacity => &keys.team_capacity,
+            Action::SelectProject => &keys.select_project,
+            Action::ChangeRepo => &keys.change_repo,
+            Action::Refresh => &keys.refresh,
+            Action::Help => &keys.help,
+            Action::Quit => &keys.quit,
+            Action::ViewTasks => &keys.view_tasks,
+            Action::ViewPRs => &keys.view_prs,
+            Action::ViewCICD => &keys.view_cicd,
+            Action::ViewBoard => &keys.view_board,
+            Action::Cancel => &keys.cancel,
+            Action::Trigger => &keys.trigger,
+            Action::TriggerPreset => &keys.trigger_preset,
+            Action::Approve => &keys.approve,
+            Action::ApproveAll => &keys.approve_all,
+            Action::OpenLog => &keys.open_log,
+            Action::LoadAllRuns => &keys.load_all_runs,
+            Action::LivePreview => &keys.live_preview,
+            Action::FailureSummary => &keys.failure_summary,
+            Action::Artifacts => &keys.artifacts,
+            Action::LogSearch => &keys.log_search,
+            Action::NextMatch => &keys.next_match,
+            Action::PrevMatch => &keys.prev_match,
+            Action::NextError => &keys.next_error,
+            Action::PrevError => &keys.prev_error,
+            Action::NextWarning => &keys.next_warning,
+            Action::PrevWarning => &keys.prev_warning,
+            Action::LogFilter => &keys.log_filter,
+            Action::ToggleTimestamps => &keys.toggle_timestamps,
+            Action::CreatePR => &keys.create_pr,
+            Action::Vote => &keys.vote,
+            Action::Comment => &keys.comment,
+            Action::Reply => &keys.reply,
+            Action::ResolveThread => &keys.resolve_thread,
+            Action::ToggleDraft => &keys.toggle_draft,
+            Action::Abandon => &keys.abandon,
+            Action::CompletePR => &keys.complete,
+            Action::Files => &keys.files,
+            Action::SelectLines => &keys.select_lines,
+        }
+    }
+}
+
+/// Extra bindings that always work alongside the configured ones
+const ALIASES: &[(KeyCode, KeyModifiers, Action)] = &[
+    (KeyCode::Down, KeyModifiers::NONE, Action::Down),
+    (KeyCode::Up, KeyModifiers::NONE, Action::Up),
+    (KeyCode::Char('c'), KeyModifiers::CONTROL, Action::Quit),
+];
+
+/// A single key press with the modifiers we care about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is already part of the character ('G', '?') and of BackTab
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self {
+            code,
+            modifiers: modifiers
+                & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT),
+        }
+    }
+
+    pub fn from_event(key: &KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+
+    /// Parse one chord: `j`, `enter`, `ctrl+r`, `shift+tab`
+    fn parse(token: &str) -> Result<Self, String> {
+        if let Some(c) = single_char(token) {
+            return Ok(Self::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+
+        let mut parts: Vec<&str> = token.split('+').collect();
+        let key = parts.pop().unwrap_or_default();
+        if key.is_empty() {
+            return Err(format!("missing key in \"{token}\""));
+        }
+
+        let mut modifiers = KeyModifiers::NONE;
+        for part in parts {
+            modifiers |= match part.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                other => return Err(format!("unknown modifier \"{other}\"")),
+            };
+        }
+
+        let mut code = parse_key_name(key)
+            .or_else(|| single_char(key).map(KeyCode::Char))
+            .ok_or_else(|| format!("unknown key \"{key}\""))?;
+
+        if modifiers.contains(KeyModifiers::SHIFT) {
+            match code {
+                KeyCode::Tab => code = KeyCode::BackTab,
+                KeyCode::Char(c) => code = KeyCode::Char(c.to_ascii_uppercase()),
+                _ => {}
+            }
+        }
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Delete => write!(f, "Del"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            other => write!(f, "{other:?}"),
+        }
+    }
+}
+
+fn single_char(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+fn parse_key_name(name: &str) -> Option<KeyCode> {
+    let code = match name.to_lowercase().as_str() {
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "space" => KeyCode::Char(' '),
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" | "pgup" => KeyCode::PageUp,
+        "pagedown" | "pgdn" => KeyCode::PageDown,
+        other => {
+            let n: u8 = other.strip_prefix('f')?.parse().ok()?;
+            if !(1..=12).contains(&n) {
+                return None;
+            }
+            KeyCode::F(n)
+        }
+    };
+    Some(code)
+}
+
+/// Parse a key spec into a sequence of chords.
+///
+/// Whitespace separates chords; a word that is neither a key name nor a
+/// modifier combo is read as one chord per character, so `"gg"` and `"g g"`
+/// are the same sequence.
+pub fn parse_sequence(spec: &str) -> Result<Vec<KeyChord>, String> {
+    let mut keys = Vec::new();
+    for token in spec.split_whitespace() {
+        if single_char(token).is_some() || token.contains('+') || parse_key_name(token).is_some() {
+            keys.push(KeyChord::parse(token)?);
+        } else {
+            keys.extend(
+                token
+                    .chars()
+                    .map(|c| KeyChord::new(KeyCode::Char(c), KeyModifiers::NONE)),
+            );
+        }
+    }
+    if keys.is_empty() {
+        return Err("empty key".to_string());
+    }
+    Ok(keys)
+}
+
+/// Human-readable form of a key sequence (`gg`, `Ctrl+r`, `g Enter`)
+pub fn format_sequence(keys: &[KeyChord]) -> String {
+    let plain = keys
+        .iter()
+        .all(|k| k.modifiers.is_empty() && matches!(k.code, KeyCode::Char(c) if c != ' '));
+    let parts: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+    parts.join(if plain { "" } else { " " })
+}
+
+fn view_name(view: View) -> &'static str {
+    match view {
+        View::Tasks => "tasks",
+        View::PRs => "prs",
+        View::CICD => "cicd",
+        View::Board => "board",
+    }
+}
+
+fn view_overrides(keys: &Keybindings, view: View) -> &HashMap<String, String> {
+    match view {
+        View::Tasks => &keys.tasks,
+        View::PRs => &keys.prs,
+        View::CICD => &keys.cicd,
+        View::Board => &keys.board,
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Binding {
+    keys: Vec<KeyChord>,
+    action: Action,
+}
+
+/// Result of feeding pressed keys into the keymap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lookup {
+    Action(Action),
+    /// The keys so far start a longer sequence
+    Pending,
+    None,
+}
+
+/// Key-to-action table for each view, built from `[keybindings]`
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    tasks: Vec<Binding>,
+    prs: Vec<Binding>,
+    cicd: Vec<Binding>,
+    board: Vec<Binding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&Keybindings::default())
+    }
+}
+
+impl Keymap {
+    /// Build the keymap, skipping bindings that don't parse
+    pub fn from_config(keys: &Keybindings) -> Self {
+        Self::build(keys).0
+    }
+
+    /// Problems with the configured bindings: bad key specs, unknown
+    /// actions in view overrides, and conflicting or shadowed sequences
+    pub fn check(keys: &Keybindings) -> Vec<String> {
+        Self::build(keys).1
+    }
+
+    fn build(keys: &Keybindings) -> (Self, Vec<String>) {
+        let mut problems = Vec::new();
+
+        for view in ALL_VIEWS {
+            let section = view_name(*view);
+            let mut names: Vec<&String> = view_overrides(keys, *view).keys().collect();
+            names.sort();
+            for name in names {
+                match Action::from_name(name) {
+                    None => problems.push(format!(
+                        "[keybindings.{section}]: unknown action \"{name}\""
+                    )),
+                    Some(action) if !action.views().contains(view) => problems.push(format!(
+                        "[keybindings.{section}]: \"{name}\" is not available in this view"
+                    )),
+                    Some(_) => {}
+                }
+            }
+        }
+
+        let mut parse_problem = |problem: String| {
+            if !problems.contains(&problem) {
+                problems.push(problem);
+            }
+        };
+        let mut bindings_for = |view: View| -> Vec<Binding> {
+            let overrides = view_overrides(keys, view);
+            let mut bindings = Vec::new();
+            for action in Action::ALL.iter().copied() {
+                if !action.views().contains(&view) {
+                    continue;
+                }
+                let (spec, section) = match overrides.get(action.name()) {
+                    Some(spec) => (spec.as_str(), format!("keybindings.{}", view_name(view))),
+                    None => (action.configured(keys), "keybindings".to_string()),
+                };
+                match parse_sequence(spec) {
+                    Ok(keys) => bindings.push(Binding { keys, action }),
+                    Err(e) => parse_problem(format!("[{section}] {}: {e}", action.name())),
+                }
+            }
+            for (code, modifiers, action) in ALIASES {
+                bindings.push(Binding {
+                    keys: vec![KeyChord::new(*code, *modifiers)],
+                    action: *action,
+                });
+            }
+            bindings
+        };
+
+        let keymap = Self {
+            tasks: bindings_for(View::Tasks),
+            prs: bindings_for(View::PRs),
+            cicd: bindings_for(View::CICD),
+            board: bindings_for(View::Board),
+        };
+
+        // Conflicts, grouped so a clash between global actions is reported once
+        let mut conflicts: Vec<(String, Vec<&str>)> = Vec::new();
+        for view in ALL_VIEWS {
+            let bindings = keymap.bindings(*view);
+            for (i, a) in bindings.iter().enumerate() {
+                for b in &bindings[i + 1..] {
+                    if a.action == b.action {
+                        continue;
+                    }
+                    let message = if a.keys == b.keys {
+                        format!(
+                            "\"{}\" is bound to both {} and {}",
+                            format_sequence(&a.keys),
+                            a.action.name(),
+                            b.action.name()
+                        )
+                    } else if a.keys.starts_with(&b.keys) || b.keys.starts_with(&a.keys) {
+                        let (short, long) = if a.keys.len() < b.keys.len() {
+                            (a, b)
+                        } else {
+                            (b, a)
+                        };
+                        format!(
+                            "\"{}\" ({}) shadows \"{}\" ({})",
+                            format_sequence(&short.keys),
+                            short.action.name(),
+                            format_sequence(&long.keys),
+                            long.action.name()
+                        )
+                    } else {
+                        continue;
+                    };
+                    match conflicts.iter_mut().find(|(m, _)| *m == message) {
+                        Some((_, views)) => views.push(view_name(*view)),
+                        None => conflicts.push((message, vec![view_name(*view)])),
+                    }
+                }
+            }
+        }
+        for (message, views) in conflicts {
+            problems.push(format!("{message} ({} view)", views.join(", ")));
+        }
+
+        (keymap, problems)
+    }
+
+    fn bindings(&self, view: View) -> &[Binding] {
+        match view {
+            View::Tasks => &self.tasks,
+            View::PRs => &self.prs,
+            View::CICD => &self.cicd,
+            View::Board => &self.board,
+        }
+    }
+
+    /// Resolve the keys pressed so far in a view
+    pub fn lookup(&self, view: View, keys: &[KeyChord]) -> Lookup {
+        let bindings = self.bindings(view);
+        if let Some(binding) = bindings.iter().find(|b| b.keys == keys) {
+            return Lookup::Action(binding.action);
+        }
+        if bindings.iter().any(|b| b.keys.starts_with(keys)) {
+            return Lookup::Pending;
+        }
+        Lookup::None
+    }
+
+    /// Whether a single key press triggers `action` (for dialogs that only
+    /// reuse a few bindings, like j/k in dropdowns)
+    pub fn is(&self, view: View, action: Action, key: &KeyEvent) -> bool {
+        let chord = KeyChord::from_event(key);
+        self.bindings(view)
+            .iter()
+            .any(|b| b.action == action && b.keys == [chord])
+    }
+
+    /// Configured keys for an action, formatted for display
+    pub fn keys_for(&self, view: View, action: Action) -> Option<String> {
+        self.bindings(view)
+            .iter()
+            .find(|b| b.action == action)
+            .map(|b| format_sequence(&b.keys))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(c: char) -> KeyChord {
+        KeyChord::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_parse_sequence_forms() {
+        assert_eq!(parse_sequence("j").unwrap(), vec![chord('j')]);
+        assert_eq!(parse_sequence("gg").unwrap(), vec![chord('g'), chord('g')]);
+        assert_eq!(parse_sequence("g g").unwrap(), vec![chord('g'), chord('g')]);
+        assert_eq!(
+            parse_sequence("ctrl+r").unwrap(),
+            vec![KeyChord::new(KeyCode::Char('r'), KeyModifiers::CONTROL)]
+        );
+        assert_eq!(
+            parse_sequence("shift+tab").unwrap(),
+            vec![KeyChord::new(KeyCode::BackTab, KeyModifiers::NONE)]
+        );
+        assert_eq!(
+            parse_sequence("Enter").unwrap(),
+            vec![KeyChord::new(KeyCode::Enter, KeyModifiers::NONE)]
+        );
+        assert!(parse_sequence("").is_err());
+        assert!(parse_sequence("hyper+x").is_err());
+        assert!(parse_sequence("ctrl+").is_err());
+    }
+
+    #[test]
+    fn test_chord_from_event_ignores_shift_on_chars() {
+        let event = KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(KeyChord::from_event(&event), chord('G'));
+    }
+
+    #[test]
+    fn test_format_sequence() {
+        assert_eq!(format_sequence(&parse_sequence("gg").unwrap()), "gg");
+        assert_eq!(
+            format_sequence(&parse_sequence("ctrl+d").unwrap()),
+            "Ctrl+d"
+        );
+        assert_eq!(
+            format_sequence(&parse_sequence("g enter").unwrap()),
+            "g Enter"
+        );
+    }
+
+    #[test]
+    fn test_default_keymap_has_no_conflicts() {
+        assert_eq!(Keymap::check(&Keybindings::default()), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_lookup_per_view() {
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.lookup(View::Tasks, &[chord('a')]),
+            Lookup::Action(Action::FilterAssignee)
+        );
+        assert_eq!(
+            keymap.lookup(View::CICD, &[chord('a')]),
+            Lookup::Action(Action::Approve)
+        );
+        assert_eq!(keymap.lookup(View::PRs, &[chord('a')]), Lookup::None);
+        assert_eq!(
+            keymap.lookup(
+                View::PRs,
+                &[KeyChord::new(KeyCode::Down, KeyModifiers::NONE)]
+            ),
+            Lookup::Action(Action::Down)
+        );
+    }
+
+    #[test]
+    fn test_lookup_sequence_is_pending_until_complete() {
+        let keys = Keybindings {
+            top: "gg".to_string(),
+            ..Default::default()
+        };
+        let keymap = Keymap::from_config(&keys);
+        assert_eq!(keymap.lookup(View::Tasks, &[chord('g')]), Lookup::Pending);
+        assert_eq!(
+            keymap.lookup(View::Tasks, &[chord('g'), chord('g')]),
+            Lookup::Action(Action::Top)
+        );
+    }
+
+    #[test]
+    fn test_view_override_only_applies_to_that_view() {
+        let mut keys = Keybindings::default();
+        keys.tasks.insert("down".to_string(), "n".to_string());
+        let keymap = Keymap::from_config(&keys);
+        assert_eq!(
+            keymap.lookup(View::Tasks, &[chord('n')]),
+            Lookup::Action(Action::Down)
+        );
+        assert_eq!(keymap.lookup(View::Tasks, &[chord('j')]), Lookup::None);
+        assert_eq!(
+            keymap.lookup(View::CICD, &[chord('j')]),
+            Lookup::Action(Action::Down)
+        );
+    }
+
+    #[test]
+    fn test_check_reports_conflicts_and_bad_overrides() {
+        let mut keys = Keybindings {
+            down: "q".to_string(),
+            top: "gg".to_string(),
+            bottom: "g".to_string(),
+            ..Default::default()
+        };
+        keys.cicd.insert("launch".to_string(), "x".to_string());
+        keys.prs.insert("approve".to_string(), "x".to_string());
+        keys.tasks
+            .insert("refresh".to_string(), "ctrl+".to_string());
+
+        let problems = Keymap::check(&keys);
+        assert!(problems.contains(
+            &"\"q\" is bound to both down and quit (tasks, prs, cicd, board view)".to_string()
+        ));
+        assert!(problems.contains(
+            &"\"g\" (bottom) shadows \"gg\" (top) (tasks, prs, cicd, board view)".to_string()
+        ));
+        assert!(problems.contains(&"[keybindings.cicd]: unknown action \"launch\"".to_string()));
+        assert!(problems
+            .contains(&"[keybindings.prs]: \"approve\" is not available in this view".to_string()));
+        assert!(
+            problems.contains(&"[keybindings.tasks] refresh: missing key in \"ctrl+\"".to_string())
+        );
+    }
+}
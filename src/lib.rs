@@ -1,7 +1,28 @@
 pub mod app;
+pub mod artifacts;
 pub mod azure;
+pub mod board;
+pub mod bulk;
+pub mod burndown;
 pub mod cache;
+pub mod capacity;
+pub mod cli;
 pub mod config;
+pub mod discussion;
 pub mod events;
+pub mod failure_summary;
+pub mod history;
+pub mod keymap;
+pub mod log_view;
+pub mod markdown;
+pub mod pipeline_trigger;
+pub mod pr_complete;
+pub mod pr_create;
+pub mod pr_diff;
+pub mod query_browser;
 pub mod terminal;
+pub mod test_results;
 pub mod ui;
+pub mod work_item_create;
+pub mod work_item_form;
+pub mod work_item_link;
@@ -0,0 +1,405 @@
+//! State of the build and release task log previews: regex search, jumps
+//! between matches, errors and warnings, a filter showing only matching lines
+//! and hiding the timestamp prefixes
+
+use regex::{Regex, RegexBuilder};
+
+/// Lines kept above the line a jump lands on
+const JUMP_CONTEXT: usize = 5;
+
+/// Strip ANSI escape sequences and control characters from a string
+pub fn clean_line(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip ANSI escape sequence: ESC [ ... letter
+            if chars.peek() == Some(&'[') {
+                chars.next(); // consume '['
+                              // Skip until we hit a letter (the terminator)
+                while let Some(&next) = chars.peek() {
+                    chars.next();
+                    if next.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+            // Also handle ESC followed by other sequences
+        } else if c == '\t' {
+            // Keep tabs, convert to spaces for consistent width
+            result.push_str("    ");
+        } else if !c.is_control() {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// A line without its `2024-05-14T10:22:31.1234567Z ` prefix
+pub fn strip_timestamp(line: &str) -> &str {
+    let Some((stamp, rest)) = line.split_once(' ') else {
+        return line;
+    };
+    let b = stamp.as_bytes();
+    let is_stamp = b.len() >= 20
+        && b[..4].iter().all(u8::is_ascii_digit)
+        && b[4] == b'-'
+        && b[7] == b'-'
+        && b[10] == b'T'
+        && stamp.ends_with('Z');
+    if is_stamp {
+        rest
+    } else {
+        line
+    }
+}
+
+/// Lines logged with `##[error]` / `##[warning]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn of(line: &str) -> Option<Self> {
+        let text = strip_timestamp(line);
+        if text.starts_with("##[error]") {
+            Some(Severity::Error)
+        } else if text.starts_with("##[warning]") {
+            Some(Severity::Warning)
+        } else {
+            None
+        }
+    }
+}
+
+/// What a jump looks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogTarget {
+    Match,
+    Severity(Severity),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LogView {
+    /// Search pattern, a regex; case-insensitive unless it has capitals
+    pub query: String,
+    /// Whether the search prompt is open
+    pub typing: bool,
+    /// Pattern before the prompt was opened, back on Esc
+    previous: String,
+    regex: Option<Regex>,
+    /// Why the pattern is not a valid regex
+    pub error: Option<String>,
+    /// Show only the lines matching the pattern
+    pub filter: bool,
+    pub hide_timestamps: bool,
+    /// Line the last jump landed on
+    pub current: Option<usize>,
+}
+
+impl LogView {
+    /// A log line as shown: without escape codes and, if hidden, timestamp
+    pub fn display(&self, line: &str) -> String {
+        let clean = clean_line(line);
+        if self.hide_timestamps {
+            strip_timestamp(&clean).to_string()
+        } else {
+            clean
+        }
+    }
+
+    pub fn has_pattern(&self) -> bool {
+        self.regex.is_some()
+    }
+
+    fn compile(&mut self) {
+        self.regex = None;
+        self.error = None;
+        if self.query.is_empty() {
+            return;
+        }
+        let smart_case = !self.query.chars().any(char::is_uppercase);
+        match RegexBuilder::new(&self.query)
+            .case_insensitive(smart_case)
+            .build()
+        {
+            Ok(regex) => self.regex = Some(regex),
+            Err(e) => {
+                // The message ends with what is wrong, after the pattern
+                let message = e.to_string();
+                let message = message
+                    .lines()
+                    .rfind(|l| l.starts_with("error:"))
+                    .unwrap_or("invalid regex");
+                self.error = Some(message.to_string());
+            }
+        }
+    }
+
+    /// Open the search prompt for a new pattern
+    pub fn start_search(&mut self) {
+        self.previous = std::mem::take(&mut self.query);
+        self.compile();
+        self.typing = true;
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.query.push(c);
+        self.compile();
+    }
+
+    pub fn backspace(&mut self) {
+        self.query.pop();
+        self.compile();
+    }
+
+    /// Close the prompt keeping the pattern; `false` while it is invalid
+    pub fn finish_search(&mut self) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+        self.typing = false;
+        self.current = None;
+        if self.regex.is_none() {
+            self.filter = false;
+        }
+        true
+    }
+
+    /// Close the prompt and go back to the previous pattern
+    pub fn cancel_search(&mut self) {
+        self.query = std::mem::take(&mut self.previous);
+        self.typing = false;
+        self.compile();
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.as_ref().is_some_and(|r| r.is_match(text))
+    }
+
+    /// Byte ranges of the pattern's matches in a displayed line
+    pub fn match_ranges(&self, text: &str) -> Vec<(usize, usize)> {
+        match &self.regex {
+            Some(regex) => regex
+                .find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| (m.start(), m.end()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Lines matching the pattern
+    pub fn matches(&self, lines: &[String]) -> Vec<usize> {
+        if self.regex.is_none() {
+            return Vec::new();
+        }
+        (0..lines.len())
+            .filter(|&i| self.is_match(&self.display(&lines[i])))
+            .collect()
+    }
+
+    /// Lines shown, top to bottom; `log_scroll` is a position in these
+    pub fn rows(&self, lines: &[String]) -> Vec<usize> {
+        if self.filter && self.regex.is_some() {
+            self.matches(lines)
+        } else {
+            (0..lines.len()).collect()
+        }
+    }
+
+    fn hits(&self, target: LogTarget, line: &str) -> bool {
+        match target {
+            LogTarget::Match => self.is_match(&self.display(line)),
+            LogTarget::Severity(severity) => Severity::of(&clean_line(line)) == Some(severity),
+        }
+    }
+
+    /// Move to the next (or previous) line with `target`, wrapping around,
+    /// from the last jump or the top of the view; the new scroll position
+    pub fn jump(
+        &mut self,
+        lines: &[String],
+        scroll: usize,
+        target: LogTarget,
+        forward: bool,
+    ) -> Option<usize> {
+        let rows = self.rows(lines);
+        let n = rows.len();
+        if n == 0 {
+            return None;
+        }
+        let origin = self
+            .current
+            .and_then(|line| rows.iter().position(|&r| r == line));
+        let start = match (origin, forward) {
+            (Some(pos), true) => pos + 1,
+            (Some(pos), false) => pos + n - 1,
+            // The top line counts when nothing was jumped to yet
+            (None, true) => scroll.min(n - 1),
+            (None, false) => scroll.min(n - 1) + n - 1,
+        };
+        let pos = (0..n)
+            .map(|step| {
+                if forward {
+                    (start + step) % n
+                } else {
+                    (start + n - step) % n
+                }
+            })
+            .find(|&pos| self.hits(target, &lines[rows[pos]]))?;
+        self.current = Some(rows[pos]);
+        Some(pos.saturating_sub(JUMP_CONTEXT))
+    }
+
+    /// Jump to `line`, showing all lines again if the filter hides it; the
+    /// new scroll position
+    pub fn land(&mut self, lines: &[String], line: usize) -> usize {
+        let mut rows = self.rows(lines);
+        if !rows.contains(&line) {
+            self.filter = false;
+            rows = self.rows(lines);
+        }
+        self.current = Some(line);
+        rows.partition_point(|&r| r < line)
+            .saturating_sub(JUMP_CONTEXT)
+    }
+
+    /// Show only matching lines, or all of them again; the new scroll
+    /// position keeps the line at the top (or the last jump) in view
+    pub fn toggle_filter(&mut self, lines: &[String], scroll: usize) -> usize {
+        let anchor = self
+            .current
+            .or_else(|| self.rows(lines).get(scroll).copied())
+            .unwrap_or(0);
+        self.filter = !self.filter && self.regex.is_some();
+        let rows = self.rows(lines);
+        let pos = rows.partition_point(|&r| r < anchor);
+        if self.current.is_some() {
+            pos.saturating_sub(JUMP_CONTEXT)
+        } else {
+            pos
+        }
+    }
+
+    /// Forget the last jump, for a newly loaded log
+    pub fn reset(&mut self) {
+        self.current = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log() -> Vec<String> {
+        [
+            "2026-10-14T09:30:00.1234567Z ##[section]Starting: Build",
+            "2026-10-14T09:30:01.0000000Z npm ci",
+            "2026-10-14T09:30:02.0000000Z ##[warning]npm WARN deprecated glob@7",
+            "2026-10-14T09:30:03.0000000Z \x1b[32mcompiled\x1b[0m web",
+            "2026-10-14T09:30:04.0000000Z ##[error]Test failed: checkout total",
+            "2026-10-14T09:30:05.0000000Z Error: 1 test failed",
+            "2026-10-14T09:30:06.0000000Z ##[error]Process completed with exit code 1",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+    }
+
+    fn search(view: &mut LogView, query: &str) {
+        view.start_search();
+        for c in query.chars() {
+            view.insert(c);
+        }
+        assert!(view.finish_search());
+    }
+
+    #[test]
+    fn test_timestamps_and_severity() {
+        let line = "2026-10-14T09:30:04.0000000Z ##[error]Test failed";
+        assert_eq!(strip_timestamp(line), "##[error]Test failed");
+        assert_eq!(strip_timestamp("Build 2026 started"), "Build 2026 started");
+        assert_eq!(Severity::of(line), Some(Severity::Error));
+        assert_eq!(Severity::of("##[warning]careful"), Some(Severity::Warning));
+        assert_eq!(Severity::of("no ##[error] here"), None);
+
+        let mut view = LogView::default();
+        assert_eq!(
+            view.display(&log()[3]),
+            "2026-10-14T09:30:03.0000000Z compiled web"
+        );
+        view.hide_timestamps = true;
+        assert_eq!(view.display(&log()[3]), "compiled web");
+    }
+
+    #[test]
+    fn test_search_and_jump() {
+        let lines = log();
+        let mut view = LogView::default();
+        // Smart case: lowercase matches both "failed" lines and "Error:"
+        search(&mut view, "error|fail");
+        assert_eq!(view.matches(&lines), [4, 5, 6]);
+        assert_eq!(
+            view.match_ranges(&view.display(&lines[5])),
+            [(29, 34), (43, 47)]
+        );
+
+        assert_eq!(view.jump(&lines, 0, LogTarget::Match, true), Some(0));
+        assert_eq!(view.current, Some(4));
+        view.jump(&lines, 0, LogTarget::Match, true);
+        view.jump(&lines, 0, LogTarget::Match, true);
+        assert_eq!(view.current, Some(6));
+        // Wraps around
+        view.jump(&lines, 0, LogTarget::Match, true);
+        assert_eq!(view.current, Some(4));
+        view.jump(&lines, 0, LogTarget::Match, false);
+        assert_eq!(view.current, Some(6));
+
+        // Capitals make it case-sensitive
+        search(&mut view, "Error");
+        assert_eq!(view.matches(&lines), [5]);
+
+        // An invalid pattern keeps the prompt open, Esc restores the old one
+        view.start_search();
+        view.insert('(');
+        assert!(view.error.is_some());
+        assert!(!view.finish_search());
+        view.cancel_search();
+        assert_eq!(view.query, "Error");
+        assert!(view.error.is_none() && !view.typing);
+    }
+
+    #[test]
+    fn test_errors_warnings_and_filter() {
+        let lines = log();
+        let mut view = LogView::default();
+        let errors = LogTarget::Severity(Severity::Error);
+        view.jump(&lines, 0, errors, true);
+        assert_eq!(view.current, Some(4));
+        view.jump(&lines, 0, errors, true);
+        assert_eq!(view.current, Some(6));
+        view.jump(&lines, 0, LogTarget::Severity(Severity::Warning), false);
+        assert_eq!(view.current, Some(2));
+
+        // Filtering without a pattern does nothing
+        view.toggle_filter(&lines, 0);
+        assert!(!view.filter);
+
+        search(&mut view, "failed");
+        assert_eq!(view.toggle_filter(&lines, 3), 0);
+        assert_eq!(view.rows(&lines), [4, 5]);
+        // Errors that don't match are filtered out too
+        assert_eq!(view.jump(&lines, 0, errors, true), Some(0));
+        assert_eq!(view.current, Some(4));
+        view.jump(&lines, 0, errors, true);
+        assert_eq!(view.current, Some(4));
+
+        // Back to all lines around the last jump
+        assert_eq!(view.toggle_filter(&lines, 0), 0);
+        assert_eq!(view.rows(&lines).len(), 7);
+    }
+}
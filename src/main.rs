@@ -1,13 +1,38 @@
 mod app;
+mod artifacts;
 mod azure;
+mod board;
+mod bulk;
+mod burndown;
 mod cache;
+mod capacity;
+mod cli;
 mod config;
+mod discussion;
 mod events;
+mod failure_summary;
+mod history;
+mod keymap;
+mod log_view;
+mod markdown;
+mod pipeline_trigger;
+mod pr_complete;
+mod pr_create;
+mod pr_diff;
+mod query_browser;
 mod terminal;
+mod test_results;
 mod ui;
+mod work_item_create;
+mod work_item_form;
+mod work_item_link;
 
 use anyhow::Result;
 use app::App;
+use azure::{Backend, DevOpsBackend, FakeBackend};
+use cache::Cache;
+use clap::Parser;
+use cli::Cli;
 use config::Config;
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
@@ -27,8 +52,25 @@ async fn main() -> Result<()> {
         original_hook(panic);
     }));
 
-    // Load config
-    let config = Config::load();
+    let cli = Cli::parse();
+
+    // Load config (--demo swaps the projects for the bundled offline fixtures)
+    let demo = cli.demo;
+    let mut config = Config::load();
+    if demo {
+        let project = FakeBackend::demo_project();
+        config.default_project = Some(project.name.clone());
+        config.projects = vec![project];
+    }
+
+    let keymap_problems = keymap::Keymap::check(&config.keybindings);
+    if !keymap_problems.is_empty() {
+        eprintln!("Invalid [keybindings] in config:");
+        for problem in &keymap_problems {
+            eprintln!("  {problem}");
+        }
+        std::process::exit(1);
+    }
 
     if config.projects.is_empty() {
         eprintln!("No projects configured. Create ~/.config/lazyops/config.toml");
@@ -36,6 +78,41 @@ async fn main() -> Result<()> {
         std::process::exit(1);
     }
 
+    let project_idx = match cli.project.as_deref() {
+        Some(name) => cli::project_idx(&config, name).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }),
+        None => app::startup_project_idx(&config),
+    };
+
+    // Subcommands print their result and exit without starting the TUI
+    if let Some(command) = &cli.command {
+        let project = &config.projects[project_idx];
+        let backend = if demo {
+            Backend::Fake(FakeBackend::demo())
+        } else {
+            Backend::from_settings(&config.settings)
+        };
+        let client = backend.for_project(project);
+        let cache = cli::ProjectCache {
+            cache: if demo {
+                Cache::temporary()
+            } else {
+                Cache::new(config.cache_dir.clone())
+            },
+            expiry: config
+                .settings
+                .with_overrides(&project.overrides)
+                .cache_expiry,
+        };
+        if let Err(e) = cli::run(&cli, command, project, &client, &cache, &mut io::stdout()).await {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -44,8 +121,20 @@ async fn main() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // Create app and run
-    let mut app = App::new(config);
-    let res = events::run_app(&mut terminal, &mut app).await;
+    let mut app = if demo {
+        let mut app = App::with_backend(config, Backend::Fake(FakeBackend::demo()));
+        // Demo data never mixes with the real caches or config
+        app.cache = Cache::temporary();
+        app.demo = true;
+        app
+    } else {
+        App::new(config)
+    };
+    app.current_project_idx = project_idx;
+    if let Some(view) = cli.view {
+        app.current_view = view.into();
+    }
+    let res = events::run_app(&mut terminal, &mut app, cli.sprint.as_deref()).await;
 
     // Restore terminal
     disable_raw_mode()?;
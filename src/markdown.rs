@@ -0,0 +1,72 @@
+//! Conversion between the HTML Azure DevOps stores in rich text fields
+//! (descriptions, comments) and the Markdown edited in `$EDITOR`
+
+use pulldown_cmark::{html, Options, Parser};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// An entity (`&lt;`, `&#39;`) in Markdown text
+static ENTITY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"&(#?\w+;)").expect("valid regex"));
+
+/// Markdown for an HTML field value
+pub fn html_to_markdown(html: &str) -> String {
+    if html.trim().is_empty() {
+        return String::new();
+    }
+    let markdown = html2md::parse_html(html);
+    // html2md decodes entities itself, so an entity left in its output is
+    // text (`&amp;lt;` gives `&lt;`); escape it so Markdown reads it as text
+    ENTITY.replace_all(&markdown, r"\&$1").trim().to_string()
+}
+
+/// HTML field value for Markdown text
+pub fn markdown_to_html(markdown: &str) -> String {
+    if markdown.trim().is_empty() {
+        return String::new();
+    }
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut out = String::new();
+    html::push_html(&mut out, Parser::new_ext(markdown, options));
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_markdown() {
+        let html = "<div>Pay with <b>cards</b> &amp; wallets</div><ul><li>Visa</li><li>Mastercard</li></ul>";
+        let markdown = html_to_markdown(html);
+        assert!(markdown.contains("**cards** & wallets"), "{markdown}");
+        assert!(markdown.contains("* Visa"), "{markdown}");
+        assert_eq!(html_to_markdown("  "), "");
+    }
+
+    #[test]
+    fn test_markdown_round_trip() {
+        let markdown = "Steps:\n\n1. Open *checkout*\n2. Pay\n\nSee [docs](https://example.com)";
+        let html = markdown_to_html(markdown);
+        assert!(html.contains("<ol>"), "{html}");
+        assert!(html.contains("<em>checkout</em>"), "{html}");
+        assert!(
+            html.contains(r#"<a href="https://example.com">docs</a>"#),
+            "{html}"
+        );
+
+        let back = html_to_markdown(&html);
+        assert!(back.contains("*checkout*"), "{back}");
+        assert!(back.contains("[docs](https://example.com)"), "{back}");
+        assert_eq!(markdown_to_html(""), "");
+    }
+
+    #[test]
+    fn test_escaped_entity_round_trip() {
+        // A literal `&lt;` is stored as `&amp;lt;`
+        let html = "<p>Write &amp;lt;br&amp;gt; for a break, 1 &lt; 2</p>";
+        let markdown = html_to_markdown(html);
+        assert!(markdown.starts_with(r"Write \&lt;br\&gt;"), "{markdown}");
+        assert_eq!(markdown_to_html(&markdown), html);
+    }
+}
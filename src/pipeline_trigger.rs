@@ -0,0 +1,940 @@
+//! State of the pipeline trigger dialog: branch or tag, runtime parameters
+//! declared in the pipeline's YAML, queue-time variables and stages to skip
+
+use crate::azure::{DevOpsBackend, PipelineDefinition, PipelineRunRequest};
+use anyhow::Result;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+
+/// Runtime parameter declared under `parameters:` in a pipeline's YAML
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PipelineParameter {
+    pub name: String,
+    pub display_name: Option<String>,
+    /// `string`, `number`, `boolean`, `object`, `stepList`, ...
+    pub kind: String,
+    pub default: Option<String>,
+    /// Allowed values, when the YAML restricts them
+    pub values: Vec<String>,
+}
+
+impl PipelineParameter {
+    pub fn label(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.name)
+    }
+
+    pub fn is_boolean(&self) -> bool {
+        self.kind.eq_ignore_ascii_case("boolean")
+    }
+
+    pub fn is_number(&self) -> bool {
+        self.kind.eq_ignore_ascii_case("number")
+    }
+
+    /// Value the pipeline runs with when none is given
+    pub fn initial_value(&self) -> String {
+        match &self.default {
+            Some(default) => default.clone(),
+            None if self.is_boolean() => "false".to_string(),
+            None => self.values.first().cloned().unwrap_or_default(),
+        }
+    }
+
+    /// Whether the value can be set here; objects, step lists and the like
+    /// keep their YAML default
+    pub fn is_scalar(&self) -> bool {
+        self.is_boolean() || self.is_number() || self.kind.eq_ignore_ascii_case("string")
+    }
+}
+
+/// What the trigger dialog needs from a pipeline's YAML
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PipelineYaml {
+    pub parameters: Vec<PipelineParameter>,
+    /// Names of the top-level stages
+    pub stages: Vec<String>,
+    /// A `parameters:` key is there but none could be read from it, e.g.
+    /// because they are written in flow style
+    pub parameters_unread: bool,
+}
+
+impl PipelineYaml {
+    /// Read the top-level `parameters:` and `stages:` of a pipeline. This is
+    /// not a YAML parser: block sequences of simple mappings are understood,
+    /// and stages pulled in from templates are not seen
+    pub fn parse(yaml: &str) -> Self {
+        let parameters: Vec<PipelineParameter> =
+            sequence_items(&top_level_block(yaml, "parameters"))
+                .into_iter()
+                .filter_map(|item| {
+                    let kind = scalar(&item, "type").unwrap_or_else(|| "string".to_string());
+                    let mut default = scalar(&item, "default");
+                    if kind.eq_ignore_ascii_case("boolean") {
+                        default = default.map(|d| d.to_lowercase());
+                    }
+                    Some(PipelineParameter {
+                        name: scalar(&item, "name")?,
+                        display_name: scalar(&item, "displayName"),
+                        kind,
+                        default,
+                        values: list(&item, "values"),
+                    })
+                })
+                .collect();
+        let stages = sequence_items(&top_level_block(yaml, "stages"))
+            .into_iter()
+            .filter_map(|item| scalar(&item, "stage"))
+            .collect();
+        let parameters_unread = parameters.is_empty()
+            && top_level_value(yaml, "parameters").is_some_and(|value| value != "[]");
+        Self {
+            parameters,
+            stages,
+            parameters_unread,
+        }
+    }
+}
+
+/// Value of a mapping key: a scalar, or the items of a list (also what an
+/// empty value followed by a nested block turns into)
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Scalar(String),
+    List(Vec<String>),
+}
+
+type Mapping = Vec<(String, Node)>;
+
+fn scalar(mapping: &Mapping, key: &str) -> Option<String> {
+    mapping.iter().find_map(|(k, node)| match node {
+        Node::Scalar(value) if k == key => Some(value.clone()),
+        _ => None,
+    })
+}
+
+fn list(mapping: &Mapping, key: &str) -> Vec<String> {
+    mapping
+        .iter()
+        .find_map(|(k, node)| match node {
+            Node::List(values) if k == key => Some(values.clone()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// A line without its comment and trailing whitespace
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut prev = ' ';
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if c == '#' && prev.is_whitespace() => return line[..i].trim_end(),
+            None => {}
+        }
+        prev = c;
+    }
+    line.trim_end()
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    if value.len() >= 2 {
+        if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+            return inner.replace("''", "'");
+        }
+        if let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+            return inner.to_string();
+        }
+    }
+    value.to_string()
+}
+
+/// What follows a top-level `key:` on its line (empty for a block), if the
+/// key is there
+fn top_level_value<'a>(yaml: &'a str, key: &str) -> Option<&'a str> {
+    yaml.lines()
+        .map(strip_comment)
+        .filter(|line| indent_of(line) == 0)
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+        .map(str::trim)
+}
+
+/// Lines of a top-level `key:` block, without blank lines and comments
+fn top_level_block<'a>(yaml: &'a str, key: &str) -> Vec<&'a str> {
+    let header = format!("{key}:");
+    let mut inside = false;
+    let mut block = Vec::new();
+    for line in yaml.lines().map(strip_comment) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        // Sequences may sit at column 0 right under their key
+        if indent_of(line) == 0 && !line.starts_with('-') {
+            inside = line == header;
+        } else if inside {
+            block.push(line);
+        }
+    }
+    block
+}
+
+/// Items of a block sequence of mappings
+fn sequence_items(lines: &[&str]) -> Vec<Mapping> {
+    let Some(indent) = lines
+        .iter()
+        .find(|l| l.trim_start().starts_with('-'))
+        .map(|l| indent_of(l))
+    else {
+        return Vec::new();
+    };
+
+    let mut items: Vec<Vec<(usize, &str)>> = Vec::new();
+    for line in lines {
+        let n = indent_of(line);
+        let text = &line[n..];
+        if n == indent && (text == "-" || text.starts_with("- ")) {
+            let rest = text[1..].trim_start();
+            let mut item = Vec::new();
+            if !rest.is_empty() {
+                item.push((n + text.len() - rest.len(), rest));
+            }
+            items.push(item);
+        } else if n > indent {
+            if let Some(item) = items.last_mut() {
+                item.push((n, text));
+            }
+        }
+    }
+    items.iter().map(|item| mapping(item)).collect()
+}
+
+/// Keys of a block mapping, which sit at the indent of its first line
+fn mapping(lines: &[(usize, &str)]) -> Mapping {
+    let Some(&(indent, _)) = lines.first() else {
+        return Vec::new();
+    };
+    let mut entries: Mapping = Vec::new();
+    for &(n, text) in lines {
+        let nested = n > indent || text.starts_with("- ");
+        if nested {
+            // Items of the list under the last key; deeper mappings are skipped
+            if let (Some((_, Node::List(values))), Some(item)) =
+                (entries.last_mut(), text.strip_prefix('-'))
+            {
+                if n == indent || values.is_empty() || !item.contains(": ") {
+                    values.push(unquote(item));
+                }
+            }
+        } else if n == indent {
+            if let Some((key, value)) = text.split_once(':') {
+                let value = value.trim();
+                let node = if value.is_empty() {
+                    Node::List(Vec::new())
+                } else if let Some(inline) =
+                    value.strip_prefix('[').and_then(|v| v.strip_suffix(']'))
+                {
+                    Node::List(
+                        inline
+                            .split(',')
+                            .map(unquote)
+                            .filter(|v| !v.is_empty())
+                            .collect(),
+                    )
+                } else {
+                    Node::Scalar(unquote(value))
+                };
+                entries.push((key.trim().to_string(), node));
+            }
+        }
+    }
+    entries
+}
+
+/// Everything the dialog loads before it can be filled in
+#[derive(Debug, Clone, Default)]
+pub struct TriggerOptions {
+    pub definition: PipelineDefinition,
+    /// Branches, then tags, as full ref names
+    pub refs: Vec<String>,
+    pub yaml: PipelineYaml,
+    /// What could not be loaded, if anything
+    pub warning: Option<String>,
+}
+
+/// Load the pipeline's definition, the branches and tags of its repository
+/// and the parameters and stages of its YAML at `git_ref` (the default
+/// branch when `None`). Only Azure Repos are read; other repositories get
+/// the branch typed in
+pub async fn load_options<B: DevOpsBackend>(
+    client: &B,
+    pipeline_id: i32,
+    git_ref: Option<&str>,
+) -> Result<TriggerOptions> {
+    let definition = client.get_pipeline_definition(pipeline_id).await?;
+    let mut options = TriggerOptions::default();
+    let mut missing = Vec::new();
+
+    if let Some(repo) = definition
+        .repository
+        .as_ref()
+        .filter(|r| r.repo_type.is_empty() || r.repo_type == "TfsGit")
+    {
+        match client.list_branches(&repo.id).await {
+            Ok(refs) => options.refs.extend(refs.into_iter().map(|r| r.name)),
+            Err(e) => missing.push(format!("branches ({e})")),
+        }
+        match client.list_tags(&repo.id).await {
+            Ok(refs) => options.refs.extend(refs.into_iter().map(|r| r.name)),
+            Err(e) => missing.push(format!("tags ({e})")),
+        }
+        if let Some(path) = definition
+            .process
+            .as_ref()
+            .and_then(|p| p.yaml_filename.as_deref())
+        {
+            let git_ref = git_ref
+                .map(full_ref)
+                .or_else(|| repo.default_branch.clone())
+                .unwrap_or_else(|| "refs/heads/main".to_string());
+            match client.get_file_at_ref(&repo.id, path, &git_ref).await {
+                Ok(yaml) => {
+                    options.yaml = PipelineYaml::parse(&yaml);
+                    if options.yaml.parameters_unread {
+                        missing.push(format!("the parameters of {path}"));
+                    }
+                }
+                Err(e) => missing.push(format!("{path} ({e})")),
+            }
+        }
+    }
+
+    if !missing.is_empty() {
+        options.warning = Some(format!("Could not load {}", missing.join(", ")));
+    }
+    options.definition = definition;
+    Ok(options)
+}
+
+/// Full ref name of a short branch name
+pub fn full_ref(branch: &str) -> String {
+    if branch.starts_with("refs/") {
+        branch.to_string()
+    } else {
+        format!("refs/heads/{branch}")
+    }
+}
+
+/// `main` for a branch, `v1.0 (tag)` for a tag
+pub fn ref_label(git_ref: &str) -> String {
+    match git_ref.strip_prefix("refs/tags/") {
+        Some(tag) => format!("{tag} (tag)"),
+        None => git_ref
+            .strip_prefix("refs/heads/")
+            .unwrap_or(git_ref)
+            .to_string(),
+    }
+}
+
+/// Rows of the dialog, top to bottom
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerField {
+    Branch,
+    Parameter(usize),
+    Variable(usize),
+    Stage(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriggerParameter {
+    pub parameter: PipelineParameter,
+    pub value: String,
+}
+
+/// Variable of the definition that can be set at queue time
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriggerVariable {
+    pub name: String,
+    pub default: String,
+    pub value: String,
+    pub secret: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriggerStage {
+    pub name: String,
+    pub run: bool,
+}
+
+/// Pipeline trigger dialog state
+#[derive(Debug, Clone)]
+pub struct PipelineTriggerDialog {
+    pub pipeline_id: i32,
+    pub pipeline_name: String,
+    pub loading: bool,
+    /// Branch or tag to run, as a full ref
+    pub branch: String,
+    pub refs: Vec<String>,
+    /// Search of the branch/tag picker while it is open
+    pub ref_query: Option<String>,
+    pub ref_cursor: usize,
+    pub parameters: Vec<TriggerParameter>,
+    pub variables: Vec<TriggerVariable>,
+    pub stages: Vec<TriggerStage>,
+    /// Highlighted row
+    pub cursor: usize,
+    /// Text of the parameter or variable being typed
+    pub editing: Option<String>,
+    pub warning: Option<String>,
+    /// Last run's settings, applied once the options are loaded
+    preset: Option<PipelineRunRequest>,
+}
+
+impl PipelineTriggerDialog {
+    pub fn new(
+        pipeline_id: i32,
+        pipeline_name: String,
+        preset: Option<PipelineRunRequest>,
+    ) -> Self {
+        Self {
+            pipeline_id,
+            pipeline_name,
+            loading: true,
+            branch: String::new(),
+            refs: Vec::new(),
+            ref_query: None,
+            ref_cursor: 0,
+            parameters: Vec::new(),
+            variables: Vec::new(),
+            stages: Vec::new(),
+            cursor: 0,
+            editing: None,
+            warning: None,
+            preset,
+        }
+    }
+
+    /// Branch the YAML should be read at: the preset's, if any
+    pub fn preset_branch(&self) -> Option<&str> {
+        self.preset
+            .as_ref()
+            .map(|p| p.branch.as_str())
+            .filter(|b| !b.is_empty())
+    }
+
+    /// Fill the dialog with the pipeline's defaults, then the preset
+    pub fn load(&mut self, options: TriggerOptions) {
+        let definition = options.definition;
+        self.branch = definition
+            .repository
+            .as_ref()
+            .and_then(|r| r.default_branch.clone())
+            .or_else(|| {
+                options
+                    .refs
+                    .iter()
+                    .find(|r| r.starts_with("refs/heads/"))
+                    .cloned()
+            })
+            .unwrap_or_default();
+        self.refs = options.refs;
+        self.parameters = options
+            .yaml
+            .parameters
+            .into_iter()
+            .map(|parameter| TriggerParameter {
+                value: parameter.initial_value(),
+                parameter,
+            })
+            .collect();
+        self.variables = definition
+            .variables
+            .into_iter()
+            .filter(|(_, v)| v.allow_override)
+            .map(|(name, v)| {
+                let default = v.value.unwrap_or_default();
+                TriggerVariable {
+                    name,
+                    value: default.clone(),
+                    default,
+                    secret: v.is_secret,
+                }
+            })
+            .collect();
+        self.stages = options
+            .yaml
+            .stages
+            .into_iter()
+            .map(|name| TriggerStage { name, run: true })
+            .collect();
+        self.warning = options.warning;
+        self.loading = false;
+
+        if let Some(preset) = self.preset.take() {
+            self.apply(&preset);
+        }
+    }
+
+    /// Settings of an earlier run, for the parameters, variables and stages
+    /// the pipeline still has
+    fn apply(&mut self, preset: &PipelineRunRequest) {
+        if !preset.branch.is_empty() {
+            self.branch = full_ref(&preset.branch);
+        }
+        for p in &mut self.parameters {
+            if let Some(value) = preset.parameters.get(&p.parameter.name) {
+                if p.parameter.is_scalar() {
+                    p.value = value.clone();
+                }
+            }
+        }
+        for v in &mut self.variables {
+            if let Some(value) = preset.variables.get(&v.name) {
+                v.value = value.clone();
+            }
+        }
+        for stage in &mut self.stages {
+            stage.run = !preset.stages_to_skip.contains(&stage.name);
+        }
+    }
+
+    pub fn fields(&self) -> Vec<TriggerField> {
+        std::iter::once(TriggerField::Branch)
+            .chain((0..self.parameters.len()).map(TriggerField::Parameter))
+            .chain((0..self.variables.len()).map(TriggerField::Variable))
+            .chain((0..self.stages.len()).map(TriggerField::Stage))
+            .collect()
+    }
+
+    pub fn selected(&self) -> TriggerField {
+        self.fields()
+            .get(self.cursor)
+            .copied()
+            .unwrap_or(TriggerField::Branch)
+    }
+
+    pub fn move_cursor(&mut self, delta: isize) {
+        let last = self.fields().len() - 1;
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+    }
+
+    /// Enter on a row: pick the branch, type a value, or toggle / cycle it
+    pub fn activate(&mut self) {
+        match self.selected() {
+            TriggerField::Branch => {
+                self.ref_query = Some(String::new());
+                self.ref_cursor = 0;
+            }
+            TriggerField::Parameter(i) => {
+                let p = &self.parameters[i];
+                if p.parameter.is_boolean() || !p.parameter.values.is_empty() {
+                    self.toggle();
+                } else if p.parameter.is_scalar() {
+                    self.editing = Some(p.value.clone());
+                }
+            }
+            TriggerField::Variable(i) => self.editing = Some(self.variables[i].value.clone()),
+            TriggerField::Stage(_) => self.toggle(),
+        }
+    }
+
+    /// Flip a boolean parameter or stage, or move to the next allowed value
+    pub fn toggle(&mut self) {
+        match self.selected() {
+            TriggerField::Parameter(i) => {
+                let p = &mut self.parameters[i];
+                if p.parameter.is_boolean() {
+                    p.value = if p.value == "true" { "false" } else { "true" }.to_string();
+                } else if !p.parameter.values.is_empty() {
+                    let values = &p.parameter.values;
+                    let next = values
+                        .iter()
+                        .position(|v| *v == p.value)
+                        .map_or(0, |idx| (idx + 1) % values.len());
+                    p.value = values[next].clone();
+                }
+            }
+            TriggerField::Stage(i) => self.stages[i].run = !self.stages[i].run,
+            _ => {}
+        }
+    }
+
+    pub fn insert(&mut self, c: char) {
+        if let Some(query) = &mut self.ref_query {
+            query.push(c);
+            self.ref_cursor = 0;
+        } else if let Some(text) = &mut self.editing {
+            text.push(c);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(query) = &mut self.ref_query {
+            query.pop();
+            self.ref_cursor = 0;
+        } else if let Some(text) = &mut self.editing {
+            text.pop();
+        }
+    }
+
+    /// Keep the typed value
+    pub fn commit_edit(&mut self) {
+        let Some(text) = self.editing.take() else {
+            return;
+        };
+        match self.selected() {
+            TriggerField::Parameter(i) => self.parameters[i].value = text.trim().to_string(),
+            TriggerField::Variable(i) => self.variables[i].value = text,
+            _ => {}
+        }
+    }
+
+    /// Branches and tags matching the picker's search, best first
+    pub fn ref_matches(&self) -> Vec<&str> {
+        let query = self.ref_query.as_deref().unwrap_or_default().trim();
+        if query.is_empty() {
+            return self.refs.iter().map(String::as_str).collect();
+        }
+        let matcher = SkimMatcherV2::default();
+        let mut scored: Vec<(i64, &str)> = self
+            .refs
+            .iter()
+            .filter_map(|r| Some((matcher.fuzzy_match(&ref_label(r), query)?, r.as_str())))
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored.into_iter().map(|(_, r)| r).collect()
+    }
+
+    pub fn move_ref_cursor(&mut self, delta: isize) {
+        let matches = self.ref_matches().len();
+        if matches > 0 {
+            self.ref_cursor = self
+                .ref_cursor
+                .saturating_add_signed(delta)
+                .min(matches - 1);
+        }
+    }
+
+    /// Take the highlighted branch or tag, or the typed name when nothing
+    /// matches, and close the picker
+    pub fn pick_ref(&mut self) {
+        let picked = self
+            .ref_matches()
+            .get(self.ref_cursor)
+            .map(|r| r.to_string());
+        let Some(query) = self.ref_query.take() else {
+            return;
+        };
+        match picked {
+            Some(git_ref) => self.branch = git_ref,
+            None if !query.trim().is_empty() => self.branch = full_ref(query.trim()),
+            None => {}
+        }
+    }
+
+    /// Why the run can't be queued as filled in, if anything
+    pub fn validate(&self) -> Option<String> {
+        if self.loading {
+            return Some("Still loading the pipeline".to_string());
+        }
+        if self.branch.is_empty() {
+            return Some("Pick a branch or tag".to_string());
+        }
+        self.parameters
+            .iter()
+            .find(|p| p.parameter.is_number() && p.value.parse::<f64>().is_err())
+            .map(|p| format!("{} must be a number", p.parameter.label()))
+    }
+
+    /// The run as filled in; only values that differ from the defaults are
+    /// sent, so it also works as the pipeline's preset
+    pub fn request(&self) -> PipelineRunRequest {
+        PipelineRunRequest {
+            branch: self.branch.clone(),
+            parameters: self
+                .parameters
+                .iter()
+                .filter(|p| p.parameter.is_scalar() && p.value != p.parameter.initial_value())
+                .map(|p| (p.parameter.name.clone(), p.value.clone()))
+                .collect(),
+            variables: self
+                .variables
+                .iter()
+                .filter(|v| v.value != v.default)
+                .map(|v| (v.name.clone(), v.value.clone()))
+                .collect(),
+            stages_to_skip: self
+                .stages
+                .iter()
+                .filter(|s| !s.run)
+                .map(|s| s.name.clone())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::azure::{DefinitionRepository, DefinitionVariable};
+
+    const YAML: &str = r#"
+trigger:
+- main
+
+parameters:
+- name: environment
+  displayName: Deploy to   # where to
+  type: string
+  default: staging
+  values:
+  - staging
+  - production
+- name: runE2E
+  type: boolean
+  default: False
+- name: nodeVersion
+  type: string
+  default: '22'
+- name: shards
+  type: number
+  default: 4
+- name: regions
+  type: string
+  values: [eu, "us"]
+- name: extraSteps
+  type: stepList
+  default:
+    - script: echo hi
+
+variables:
+  buildConfiguration: Release
+
+stages:
+  - stage: Build
+    jobs:
+      - job: build
+        steps:
+          - script: npm run build # not a stage
+  - template: stages/notify.yml
+  - stage: 'Deploy'
+    displayName: Deploy to ${{ parameters.environment }}
+"#;
+
+    fn dialog() -> PipelineTriggerDialog {
+        let definition = PipelineDefinition {
+            id: 11,
+            name: "web-ci".into(),
+            repository: Some(DefinitionRepository {
+                id: "repo-web".into(),
+                default_branch: Some("refs/heads/main".into()),
+                ..Default::default()
+            }),
+            process: None,
+            variables: [
+                (
+                    "verbosity".to_string(),
+                    DefinitionVariable {
+                        value: Some("minimal".into()),
+                        allow_override: true,
+                        is_secret: false,
+                    },
+                ),
+                (
+                    "fixed".to_string(),
+                    DefinitionVariable {
+                        value: Some("1".into()),
+                        ..Default::default()
+                    },
+                ),
+            ]
+            .into(),
+        };
+        let mut dialog = PipelineTriggerDialog::new(11, "web-ci".into(), None);
+        dialog.load(TriggerOptions {
+            definition,
+            refs: vec![
+                "refs/heads/main".into(),
+                "refs/heads/feature/checkout".into(),
+                "refs/tags/v1.0.0".into(),
+            ],
+            yaml: PipelineYaml::parse(YAML),
+            warning: None,
+        });
+        dialog
+    }
+
+    #[test]
+    fn test_parse_parameters_and_stages() {
+        let yaml = PipelineYaml::parse(YAML);
+        let names: Vec<&str> = yaml.parameters.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "environment",
+                "runE2E",
+                "nodeVersion",
+                "shards",
+                "regions",
+                "extraSteps"
+            ]
+        );
+
+        let environment = &yaml.parameters[0];
+        assert_eq!(environment.label(), "Deploy to");
+        assert_eq!(environment.default.as_deref(), Some("staging"));
+        assert_eq!(environment.values, ["staging", "production"]);
+        assert_eq!(yaml.parameters[1].default.as_deref(), Some("false"));
+        assert_eq!(yaml.parameters[2].default.as_deref(), Some("22"));
+        assert!(yaml.parameters[3].is_number());
+        assert_eq!(yaml.parameters[4].values, ["eu", "us"]);
+        assert!(!yaml.parameters[5].is_scalar());
+        assert_eq!(yaml.parameters[5].default, None);
+
+        assert_eq!(yaml.stages, ["Build", "Deploy"]);
+        assert_eq!(
+            PipelineYaml::parse("steps:\n- script: make\n"),
+            PipelineYaml::default()
+        );
+    }
+
+    #[test]
+    fn test_parse_other_document_shapes() {
+        // A document marker and a comment after the key
+        let yaml = PipelineYaml::parse(
+            "---\nparameters: # set when queuing\n- name: target\n  default: eu\nstages:\n- stage: Build\n",
+        );
+        assert_eq!(yaml.parameters.len(), 1);
+        assert_eq!(yaml.parameters[0].initial_value(), "eu");
+        assert_eq!(yaml.stages, ["Build"]);
+        assert!(!yaml.parameters_unread);
+
+        // Empty flow sequences are nothing to read
+        let yaml = PipelineYaml::parse("parameters: []\nstages: []\nsteps:\n- script: make\n");
+        assert_eq!(yaml, PipelineYaml::default());
+
+        // Flow-style parameters are not read, and that is reported
+        let yaml = PipelineYaml::parse(
+            "---\nparameters: [{name: target, type: string, default: eu}]\nstages:\n- stage: Build\n",
+        );
+        assert!(yaml.parameters.is_empty());
+        assert!(yaml.parameters_unread);
+        assert_eq!(yaml.stages, ["Build"]);
+
+        // So are parameters nested too deep to be seen
+        let yaml = PipelineYaml::parse("parameters:\n  target: eu\n");
+        assert!(yaml.parameters_unread);
+    }
+
+    #[test]
+    fn test_fill_in_and_build_request() {
+        let mut dialog = dialog();
+        assert_eq!(dialog.branch, "refs/heads/main");
+        // Only variables settable at queue time are offered
+        assert_eq!(dialog.variables.len(), 1);
+        // Nothing changed: defaults only
+        assert_eq!(
+            dialog.request(),
+            PipelineRunRequest::branch("refs/heads/main")
+        );
+
+        // Pick a tag through the picker
+        dialog.activate();
+        for c in "v1".chars() {
+            dialog.insert(c);
+        }
+        assert_eq!(dialog.ref_matches(), ["refs/tags/v1.0.0"]);
+        dialog.pick_ref();
+        assert_eq!(dialog.branch, "refs/tags/v1.0.0");
+        assert!(dialog.ref_query.is_none());
+
+        // environment cycles through its values, runE2E toggles
+        dialog.move_cursor(1);
+        dialog.activate();
+        dialog.move_cursor(1);
+        dialog.activate();
+        // shards is typed
+        dialog.move_cursor(2);
+        dialog.activate();
+        dialog.backspace();
+        dialog.insert('x');
+        dialog.commit_edit();
+        assert_eq!(
+            dialog.validate().as_deref(),
+            Some("shards must be a number")
+        );
+        dialog.activate();
+        dialog.backspace();
+        dialog.insert('8');
+        dialog.commit_edit();
+        assert_eq!(dialog.validate(), None);
+
+        // verbosity, then skip the Deploy stage
+        dialog.move_cursor(3);
+        assert_eq!(dialog.selected(), TriggerField::Variable(0));
+        dialog.activate();
+        dialog.editing = Some("detailed".into());
+        dialog.commit_edit();
+        dialog.move_cursor(10);
+        assert_eq!(dialog.selected(), TriggerField::Stage(1));
+        dialog.toggle();
+
+        let request = dialog.request();
+        assert_eq!(request.branch, "refs/tags/v1.0.0");
+        assert_eq!(
+            request.parameters,
+            [
+                ("environment".to_string(), "production".to_string()),
+                ("runE2E".to_string(), "true".to_string()),
+                ("shards".to_string(), "8".to_string()),
+            ]
+            .into()
+        );
+        assert_eq!(
+            request.variables,
+            [("verbosity".to_string(), "detailed".to_string())].into()
+        );
+        assert_eq!(request.stages_to_skip, ["Deploy"]);
+
+        // The same request as a preset fills in a new dialog the same way
+        let mut again = PipelineTriggerDialog::new(11, "web-ci".into(), Some(request.clone()));
+        assert_eq!(again.preset_branch(), Some("refs/tags/v1.0.0"));
+        let mut options = TriggerOptions {
+            definition: PipelineDefinition::default(),
+            refs: dialog.refs.clone(),
+            yaml: PipelineYaml::parse(YAML),
+            warning: None,
+        };
+        options.definition.variables = [(
+            "verbosity".to_string(),
+            DefinitionVariable {
+                value: Some("minimal".into()),
+                allow_override: true,
+                is_secret: false,
+            },
+        )]
+        .into();
+        again.load(options);
+        assert_eq!(again.request(), request);
+    }
+
+    #[test]
+    fn test_typed_branch_when_nothing_matches() {
+        let mut dialog = dialog();
+        dialog.activate();
+        for c in "release/2.0".chars() {
+            dialog.insert(c);
+        }
+        assert!(dialog.ref_matches().is_empty());
+        dialog.pick_ref();
+        assert_eq!(dialog.branch, "refs/heads/release/2.0");
+        assert_eq!(ref_label("refs/tags/v1.0.0"), "v1.0.0 (tag)");
+        assert_eq!(ref_label(&dialog.branch), "release/2.0");
+    }
+}
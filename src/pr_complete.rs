@@ -0,0 +1,403 @@
+//! State of the pull request completion dialog
+
+use crate::azure::{PRCompletionOptions, PRPolicy, PullRequest};
+
+/// Merge strategies offered when completing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    Merge,
+    Squash,
+    Rebase,
+    SemiLinear,
+}
+
+impl MergeStrategy {
+    pub const ALL: [MergeStrategy; 4] = [
+        MergeStrategy::Merge,
+        MergeStrategy::Squash,
+        MergeStrategy::Rebase,
+        MergeStrategy::SemiLinear,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MergeStrategy::Merge => "Merge (no fast-forward)",
+            MergeStrategy::Squash => "Squash commit",
+            MergeStrategy::Rebase => "Rebase and fast-forward",
+            MergeStrategy::SemiLinear => "Semi-linear merge",
+        }
+    }
+
+    /// `mergeStrategy` value used by the REST API
+    pub fn api_name(self) -> &'static str {
+        match self {
+            MergeStrategy::Merge => "noFastForward",
+            MergeStrategy::Squash => "squash",
+            MergeStrategy::Rebase => "rebase",
+            MergeStrategy::SemiLinear => "rebaseMerge",
+        }
+    }
+
+    fn from_options(options: &PRCompletionOptions) -> Self {
+        match options.merge_strategy.as_deref() {
+            Some("squash") => MergeStrategy::Squash,
+            Some("rebase") => MergeStrategy::Rebase,
+            Some("rebaseMerge") => MergeStrategy::SemiLinear,
+            Some(_) => MergeStrategy::Merge,
+            None if options.squash_merge == Some(true) => MergeStrategy::Squash,
+            None => MergeStrategy::Merge,
+        }
+    }
+
+    /// Whether the strategy creates a commit that takes the message
+    pub fn has_commit_message(self) -> bool {
+        self != MergeStrategy::Rebase
+    }
+}
+
+/// Dialog fields in Tab order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PRCompleteField {
+    Strategy,
+    Message,
+    Options,
+    /// Only reachable while bypassing policies
+    BypassReason,
+}
+
+impl PRCompleteField {
+    const ALL: [PRCompleteField; 4] = [
+        PRCompleteField::Strategy,
+        PRCompleteField::Message,
+        PRCompleteField::Options,
+        PRCompleteField::BypassReason,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PRCompleteField::Strategy => "Merge type",
+            PRCompleteField::Message => "Merge commit message",
+            PRCompleteField::Options => "Options - Space:toggle",
+            PRCompleteField::BypassReason => "Bypass reason",
+        }
+    }
+}
+
+/// Toggles in the Options field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PRCompleteOption {
+    DeleteSourceBranch,
+    TransitionWorkItems,
+    AutoComplete,
+    BypassPolicy,
+}
+
+impl PRCompleteOption {
+    pub const ALL: [PRCompleteOption; 4] = [
+        PRCompleteOption::DeleteSourceBranch,
+        PRCompleteOption::TransitionWorkItems,
+        PRCompleteOption::AutoComplete,
+        PRCompleteOption::BypassPolicy,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PRCompleteOption::DeleteSourceBranch => "Delete source branch",
+            PRCompleteOption::TransitionWorkItems => "Complete linked work items",
+            PRCompleteOption::AutoComplete => "Set auto-complete instead",
+            PRCompleteOption::BypassPolicy => "Bypass blocking policies",
+        }
+    }
+}
+
+/// Pull request completion dialog state
+#[derive(Debug, Clone)]
+pub struct PRCompleteDialog {
+    pub pr_id: i32,
+    pub title: String,
+    pub source: String,
+    pub target: String,
+    pub policies: Vec<PRPolicy>,
+    pub loading_policies: bool,
+    pub strategy: MergeStrategy,
+    pub message: String,
+    pub delete_source_branch: bool,
+    pub transition_work_items: bool,
+    pub auto_complete: bool,
+    pub bypass_policy: bool,
+    pub bypass_reason: String,
+    pub field: PRCompleteField,
+    /// Highlighted option in the Options field
+    pub cursor: usize,
+    pub submitting: bool,
+}
+
+impl PRCompleteDialog {
+    /// Dialog for `pr`, starting from the completion options already set on it
+    pub fn new(pr: &PullRequest) -> Self {
+        let options = pr.completion_options.clone().unwrap_or_default();
+        let message = options
+            .merge_commit_message
+            .clone()
+            .filter(|m| !m.is_empty())
+            .unwrap_or_else(|| format!("Merged PR {}: {}", pr.pull_request_id, pr.title));
+        Self {
+            pr_id: pr.pull_request_id,
+            title: pr.title.clone(),
+            source: PullRequest::short_branch(pr.source_branch.as_deref().unwrap_or_default())
+                .to_string(),
+            target: PullRequest::short_branch(pr.target_branch.as_deref().unwrap_or_default())
+                .to_string(),
+            policies: Vec::new(),
+            loading_policies: true,
+            strategy: MergeStrategy::from_options(&options),
+            message,
+            delete_source_branch: options.delete_source_branch.unwrap_or(true),
+            transition_work_items: options.transition_work_items.unwrap_or(true),
+            auto_complete: false,
+            bypass_policy: false,
+            bypass_reason: String::new(),
+            field: PRCompleteField::Strategy,
+            cursor: 0,
+            submitting: false,
+        }
+    }
+
+    pub fn set_policies(&mut self, policies: Vec<PRPolicy>) {
+        self.policies = policies;
+        self.loading_policies = false;
+    }
+
+    /// Enabled blocking policies that have not passed
+    pub fn blocking_failures(&self) -> Vec<&PRPolicy> {
+        self.policies
+            .iter()
+            .filter(|p| p.is_blocking_failure())
+            .collect()
+    }
+
+    fn fields(&self) -> Vec<PRCompleteField> {
+        PRCompleteField::ALL
+            .into_iter()
+            .filter(|f| *f != PRCompleteField::BypassReason || self.bypass_policy)
+            .collect()
+    }
+
+    pub fn next_field(&mut self) {
+        let fields = self.fields();
+        let idx = fields.iter().position(|f| *f == self.field).unwrap_or(0);
+        self.focus(fields[(idx + 1) % fields.len()]);
+    }
+
+    pub fn prev_field(&mut self) {
+        let fields = self.fields();
+        let idx = fields.iter().position(|f| *f == self.field).unwrap_or(0);
+        self.focus(fields[(idx + fields.len() - 1) % fields.len()]);
+    }
+
+    fn focus(&mut self, field: PRCompleteField) {
+        self.field = field;
+        self.cursor = 0;
+    }
+
+    /// Move the highlight: picks the merge strategy, or the option to toggle
+    pub fn move_cursor(&mut self, delta: isize) {
+        match self.field {
+            PRCompleteField::Strategy => {
+                let all = MergeStrategy::ALL;
+                let idx = all.iter().position(|s| *s == self.strategy).unwrap_or(0);
+                let idx = idx.saturating_add_signed(delta).min(all.len() - 1);
+                self.strategy = all[idx];
+            }
+            PRCompleteField::Options => {
+                let last = PRCompleteOption::ALL.len() - 1;
+                self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn option_enabled(&self, option: PRCompleteOption) -> bool {
+        match option {
+            PRCompleteOption::DeleteSourceBranch => self.delete_source_branch,
+            PRCompleteOption::TransitionWorkItems => self.transition_work_items,
+            PRCompleteOption::AutoComplete => self.auto_complete,
+            PRCompleteOption::BypassPolicy => self.bypass_policy,
+        }
+    }
+
+    /// Toggle the highlighted option
+    pub fn toggle_option(&mut self) {
+        let Some(option) = PRCompleteOption::ALL.get(self.cursor) else {
+            return;
+        };
+        let flag = match option {
+            PRCompleteOption::DeleteSourceBranch => &mut self.delete_source_branch,
+            PRCompleteOption::TransitionWorkItems => &mut self.transition_work_items,
+            PRCompleteOption::AutoComplete => &mut self.auto_complete,
+            PRCompleteOption::BypassPolicy => &mut self.bypass_policy,
+        };
+        *flag = !*flag;
+    }
+
+    /// Type a character into the focused field
+    pub fn insert(&mut self, c: char) {
+        match self.field {
+            PRCompleteField::Message => self.message.push(c),
+            PRCompleteField::BypassReason if c != '\n' => self.bypass_reason.push(c),
+            _ => {}
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        match self.field {
+            PRCompleteField::Message => {
+                self.message.pop();
+            }
+            PRCompleteField::BypassReason => {
+                self.bypass_reason.pop();
+            }
+            _ => {}
+        }
+    }
+
+    /// Why the PR can't be completed as configured, if anything
+    pub fn validate(&self) -> Option<&'static str> {
+        if self.auto_complete {
+            // Auto-complete waits for the policies itself
+            None
+        } else if self.loading_policies {
+            Some("Waiting for policy evaluations")
+        } else if self.bypass_policy && self.bypass_reason.trim().is_empty() {
+            Some("Enter a reason for bypassing policies")
+        } else if !self.bypass_policy && !self.blocking_failures().is_empty() {
+            Some("Blocking policies have not passed: bypass them or set auto-complete")
+        } else {
+            None
+        }
+    }
+
+    pub fn to_options(&self) -> PRCompletionOptions {
+        let bypass = self.bypass_policy && !self.auto_complete;
+        let message = self.message.trim_end();
+        PRCompletionOptions {
+            merge_strategy: Some(self.strategy.api_name().to_string()),
+            squash_merge: Some(self.strategy == MergeStrategy::Squash),
+            delete_source_branch: Some(self.delete_source_branch),
+            merge_commit_message: (self.strategy.has_commit_message() && !message.is_empty())
+                .then(|| message.to_string()),
+            transition_work_items: Some(self.transition_work_items),
+            bypass_policy: bypass.then_some(true),
+            bypass_reason: bypass.then(|| self.bypass_reason.trim().to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::azure::{PRPolicyConfig, PRPolicyType};
+
+    fn pr() -> PullRequest {
+        PullRequest {
+            pull_request_id: 501,
+            title: "Checkout: new payment step".to_string(),
+            source_branch: Some("refs/heads/feature/payment".to_string()),
+            target_branch: Some("refs/heads/main".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn policy(name: &str, status: &str, is_blocking: bool) -> PRPolicy {
+        PRPolicy {
+            evaluation_id: None,
+            status: Some(status.to_string()),
+            configuration: Some(PRPolicyConfig {
+                is_blocking,
+                is_enabled: true,
+                policy_type: Some(PRPolicyType {
+                    display_name: Some(name.to_string()),
+                }),
+                settings: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_defaults_from_pr() {
+        let mut pr = pr();
+        let dialog = PRCompleteDialog::new(&pr);
+        assert_eq!(dialog.message, "Merged PR 501: Checkout: new payment step");
+        assert_eq!(dialog.strategy, MergeStrategy::Merge);
+        assert_eq!(dialog.target, "main");
+        assert!(dialog.delete_source_branch);
+
+        // Options picked when auto-complete was set carry over
+        pr.completion_options = Some(PRCompletionOptions {
+            merge_strategy: Some("rebaseMerge".to_string()),
+            delete_source_branch: Some(false),
+            ..Default::default()
+        });
+        let dialog = PRCompleteDialog::new(&pr);
+        assert_eq!(dialog.strategy, MergeStrategy::SemiLinear);
+        assert!(!dialog.delete_source_branch);
+    }
+
+    #[test]
+    fn test_failing_blocking_policy_needs_bypass() {
+        let mut dialog = PRCompleteDialog::new(&pr());
+        assert!(dialog.validate().is_some());
+
+        dialog.set_policies(vec![
+            policy("Build", "rejected", true),
+            policy("Comment requirements", "running", false),
+        ]);
+        assert_eq!(dialog.blocking_failures().len(), 1);
+        assert!(dialog.validate().is_some());
+
+        // Bypassing needs a reason, typed in the field that appears for it
+        dialog.field = PRCompleteField::Options;
+        dialog.cursor = 3;
+        dialog.toggle_option();
+        assert_eq!(
+            dialog.validate(),
+            Some("Enter a reason for bypassing policies")
+        );
+        dialog.next_field();
+        assert_eq!(dialog.field, PRCompleteField::BypassReason);
+        for c in "Hotfix".chars() {
+            dialog.insert(c);
+        }
+        assert_eq!(dialog.validate(), None);
+        let options = dialog.to_options();
+        assert_eq!(options.bypass_policy, Some(true));
+        assert_eq!(options.bypass_reason.as_deref(), Some("Hotfix"));
+
+        // Auto-complete waits for policies instead of bypassing them
+        dialog.bypass_policy = false;
+        dialog.auto_complete = true;
+        assert_eq!(dialog.validate(), None);
+        assert_eq!(dialog.to_options().bypass_policy, None);
+    }
+
+    #[test]
+    fn test_strategy_options() {
+        let mut dialog = PRCompleteDialog::new(&pr());
+        dialog.set_policies(vec![policy("Build", "approved", true)]);
+        assert_eq!(dialog.validate(), None);
+        // Without bypass the reason field is skipped
+        dialog.prev_field();
+        assert_eq!(dialog.field, PRCompleteField::Options);
+
+        dialog.field = PRCompleteField::Strategy;
+        dialog.move_cursor(1);
+        let options = dialog.to_options();
+        assert_eq!(options.merge_strategy.as_deref(), Some("squash"));
+        assert_eq!(options.squash_merge, Some(true));
+
+        dialog.move_cursor(1);
+        let options = dialog.to_options();
+        assert_eq!(options.merge_strategy.as_deref(), Some("rebase"));
+        assert_eq!(options.merge_commit_message, None);
+    }
+}
@@ -0,0 +1,549 @@
+//! State of the full-screen pull request creation dialog
+
+use crate::azure::{NewPullRequest, PRCompletionOptions, PullRequest, User, WorkItem};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+
+/// Branches preferred as the default target, in order
+const DEFAULT_TARGETS: &[&str] = &["main", "master", "develop"];
+
+/// Dialog fields in Tab order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PRCreateField {
+    Source,
+    Target,
+    Title,
+    Description,
+    Reviewers,
+    WorkItems,
+    Options,
+}
+
+impl PRCreateField {
+    const ALL: [PRCreateField; 7] = [
+        PRCreateField::Source,
+        PRCreateField::Target,
+        PRCreateField::Title,
+        PRCreateField::Description,
+        PRCreateField::Reviewers,
+        PRCreateField::WorkItems,
+        PRCreateField::Options,
+    ];
+
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let idx = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(idx + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PRCreateField::Source => "Source branch",
+            PRCreateField::Target => "Target branch",
+            PRCreateField::Title => "Title",
+            PRCreateField::Description => "Description",
+            PRCreateField::Reviewers => "Reviewers",
+            PRCreateField::WorkItems => "Work items",
+            PRCreateField::Options => "Options",
+        }
+    }
+
+    /// Fields that pick from a filtered candidate list
+    pub fn is_picker(self) -> bool {
+        matches!(
+            self,
+            PRCreateField::Source
+                | PRCreateField::Target
+                | PRCreateField::Reviewers
+                | PRCreateField::WorkItems
+        )
+    }
+}
+
+/// Toggles in the Options field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PRCreateOption {
+    Draft,
+    Squash,
+    DeleteSourceBranch,
+    AutoComplete,
+}
+
+impl PRCreateOption {
+    pub const ALL: [PRCreateOption; 4] = [
+        PRCreateOption::Draft,
+        PRCreateOption::Squash,
+        PRCreateOption::DeleteSourceBranch,
+        PRCreateOption::AutoComplete,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PRCreateOption::Draft => "Create as draft",
+            PRCreateOption::Squash => "Squash merge",
+            PRCreateOption::DeleteSourceBranch => "Delete source branch",
+            PRCreateOption::AutoComplete => "Auto-complete",
+        }
+    }
+}
+
+/// Work item linked to the new PR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkedWorkItem {
+    pub id: i32,
+    pub title: String,
+}
+
+/// Entry offered by a picker field
+#[derive(Debug, Clone)]
+pub enum Candidate {
+    Branch(String),
+    Reviewer(User),
+    WorkItem(LinkedWorkItem),
+}
+
+impl Candidate {
+    pub fn label(&self) -> String {
+        match self {
+            Candidate::Branch(name) => name.clone(),
+            Candidate::Reviewer(user) if user.display_name == user.unique_name => {
+                user.unique_name.clone()
+            }
+            Candidate::Reviewer(user) => format!("{} <{}>", user.display_name, user.unique_name),
+            Candidate::WorkItem(item) => format!("#{} {}", item.id, item.title),
+        }
+    }
+}
+
+/// Pull request creation dialog state
+#[derive(Debug, Clone)]
+pub struct PRCreateDialog {
+    pub repository: String,
+    /// Short branch names; empty until loaded
+    pub branches: Vec<String>,
+    pub loading_branches: bool,
+    pub source: String,
+    pub target: String,
+    pub title: String,
+    pub description: String,
+    pub reviewers: Vec<User>,
+    pub work_items: Vec<LinkedWorkItem>,
+    pub draft: bool,
+    pub squash: bool,
+    pub delete_source_branch: bool,
+    pub auto_complete: bool,
+    pub field: PRCreateField,
+    /// Filter typed into the focused picker
+    pub query: String,
+    /// Highlighted candidate, or option in the Options field
+    pub cursor: usize,
+    pub submitting: bool,
+    /// Title typed (or taken from a ticket); stop deriving it from the branch
+    title_fixed: bool,
+}
+
+impl PRCreateDialog {
+    pub fn new(repository: String) -> Self {
+        Self {
+            repository,
+            branches: Vec::new(),
+            loading_branches: true,
+            source: String::new(),
+            target: String::new(),
+            title: String::new(),
+            description: String::new(),
+            reviewers: Vec::new(),
+            work_items: Vec::new(),
+            draft: false,
+            squash: false,
+            delete_source_branch: true,
+            auto_complete: false,
+            field: PRCreateField::Source,
+            query: String::new(),
+            cursor: 0,
+            submitting: false,
+            title_fixed: false,
+        }
+    }
+
+    /// Dialog started from a work item: linked, titled after it
+    pub fn for_work_item(repository: String, id: i32, title: String) -> Self {
+        let mut dialog = Self::new(repository);
+        dialog.title = title.clone();
+        dialog.title_fixed = true;
+        dialog.work_items.push(LinkedWorkItem { id, title });
+        dialog
+    }
+
+    /// Store loaded branches and fill in default source/target
+    pub fn set_branches(&mut self, branches: Vec<String>) {
+        self.branches = branches;
+        self.loading_branches = false;
+
+        if self.target.is_empty() {
+            if let Some(target) = DEFAULT_TARGETS
+                .iter()
+                .find(|t| self.branches.iter().any(|b| b == *t))
+            {
+                self.target = target.to_string();
+            }
+        }
+        if self.source.is_empty() {
+            // A branch named after the linked ticket is most likely the source
+            let source = self.work_items.first().and_then(|item| {
+                let id = item.id.to_string();
+                self.branches
+                    .iter()
+                    .find(|b| **b != self.target && b.contains(&id))
+                    .cloned()
+            });
+            if let Some(source) = source {
+                self.set_source(source);
+            }
+        }
+    }
+
+    fn set_source(&mut self, branch: String) {
+        if !self.title_fixed {
+            self.title = title_from_branch(&branch);
+        }
+        self.source = branch;
+    }
+
+    /// Exchange source and target branches
+    pub fn swap_branches(&mut self) {
+        let source = std::mem::take(&mut self.source);
+        let target = std::mem::take(&mut self.target);
+        self.target = source;
+        self.set_source(target);
+    }
+
+    pub fn next_field(&mut self) {
+        self.focus(self.field.next());
+    }
+
+    pub fn prev_field(&mut self) {
+        self.focus(self.field.prev());
+    }
+
+    fn focus(&mut self, field: PRCreateField) {
+        self.field = field;
+        self.query.clear();
+        self.cursor = 0;
+    }
+
+    /// Candidates for the focused picker, best matches first
+    pub fn candidates(
+        &self,
+        users: &[User],
+        work_items: &[WorkItem],
+        current_user: Option<&str>,
+    ) -> Vec<Candidate> {
+        let matcher = SkimMatcherV2::default();
+        let query = self.query.as_str();
+        let mut scored: Vec<(i64, Candidate)> = match self.field {
+            PRCreateField::Source | PRCreateField::Target => self
+                .branches
+                .iter()
+                .filter_map(|b| {
+                    score(&matcher, b, query).map(|s| (s, Candidate::Branch(b.clone())))
+                })
+                .collect(),
+            PRCreateField::Reviewers => users
+                .iter()
+                .filter(|u| Some(u.unique_name.as_str()) != current_user)
+                .filter(|u| {
+                    !self
+                        .reviewers
+                        .iter()
+                        .any(|r| r.unique_name == u.unique_name)
+                })
+                .filter_map(|u| {
+                    let text = format!("{} {}", u.display_name, u.unique_name);
+                    score(&matcher, &text, query).map(|s| (s, Candidate::Reviewer(u.clone())))
+                })
+                .collect(),
+            PRCreateField::WorkItems => work_items
+                .iter()
+                .filter(|w| !self.work_items.iter().any(|l| l.id == w.id))
+                .filter_map(|w| {
+                    let text = format!("{} {}", w.id, w.fields.title);
+                    score(&matcher, &text, query).map(|s| {
+                        let item = LinkedWorkItem {
+                            id: w.id,
+                            title: w.fields.title.clone(),
+                        };
+                        (s, Candidate::WorkItem(item))
+                    })
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        // Stable sort keeps the original order for equal scores (and empty queries)
+        scored.sort_by_key(|(s, _)| std::cmp::Reverse(*s));
+        let mut candidates: Vec<Candidate> = scored.into_iter().map(|(_, c)| c).collect();
+
+        // Anyone can be added by email, even if they aren't on a loaded work item
+        if self.field == PRCreateField::Reviewers
+            && query.contains('@')
+            && !candidates.iter().any(|c| c.label().contains(query))
+        {
+            candidates.push(Candidate::Reviewer(User {
+                display_name: query.to_string(),
+                unique_name: query.to_string(),
+            }));
+        }
+        candidates
+    }
+
+    /// Apply a picked candidate to the focused field
+    pub fn pick(&mut self, candidate: Candidate) {
+        match (self.field, candidate) {
+            (PRCreateField::Source, Candidate::Branch(b)) => {
+                self.set_source(b);
+                self.next_field();
+            }
+            (PRCreateField::Target, Candidate::Branch(b)) => {
+                self.target = b;
+                self.next_field();
+            }
+            (PRCreateField::Reviewers, Candidate::Reviewer(u)) => {
+                self.reviewers.push(u);
+                self.focus(PRCreateField::Reviewers);
+            }
+            (PRCreateField::WorkItems, Candidate::WorkItem(w)) => {
+                self.work_items.push(w);
+                self.focus(PRCreateField::WorkItems);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn option_enabled(&self, option: PRCreateOption) -> bool {
+        match option {
+            PRCreateOption::Draft => self.draft,
+            PRCreateOption::Squash => self.squash,
+            PRCreateOption::DeleteSourceBranch => self.delete_source_branch,
+            PRCreateOption::AutoComplete => self.auto_complete,
+        }
+    }
+
+    /// Toggle the highlighted option
+    pub fn toggle_option(&mut self) {
+        let Some(option) = PRCreateOption::ALL.get(self.cursor) else {
+            return;
+        };
+        let flag = match option {
+            PRCreateOption::Draft => &mut self.draft,
+            PRCreateOption::Squash => &mut self.squash,
+            PRCreateOption::DeleteSourceBranch => &mut self.delete_source_branch,
+            PRCreateOption::AutoComplete => &mut self.auto_complete,
+        };
+        *flag = !*flag;
+    }
+
+    /// Type a character into the focused field
+    pub fn insert(&mut self, c: char) {
+        match self.field {
+            PRCreateField::Title => {
+                self.title.push(c);
+                self.title_fixed = true;
+            }
+            PRCreateField::Description => self.description.push(c),
+            PRCreateField::Options => {}
+            _ => {
+                self.query.push(c);
+                self.cursor = 0;
+            }
+        }
+    }
+
+    /// Delete backwards; on an empty picker filter, drop the last reviewer/work item
+    pub fn backspace(&mut self) {
+        match self.field {
+            PRCreateField::Title => {
+                self.title.pop();
+                self.title_fixed = true;
+            }
+            PRCreateField::Description => {
+                self.description.pop();
+            }
+            PRCreateField::Options => {}
+            PRCreateField::Reviewers if self.query.is_empty() => {
+                self.reviewers.pop();
+            }
+            PRCreateField::WorkItems if self.query.is_empty() => {
+                self.work_items.pop();
+            }
+            _ => {
+                self.query.pop();
+                self.cursor = 0;
+            }
+        }
+    }
+
+    /// Why the PR can't be created yet, if anything is missing
+    pub fn validate(&self) -> Option<&'static str> {
+        if self.source.is_empty() {
+            Some("Pick a source branch")
+        } else if self.target.is_empty() {
+            Some("Pick a target branch")
+        } else if self.source == self.target {
+            Some("Source and target branch must differ")
+        } else if self.title.trim().is_empty() {
+            Some("Enter a title")
+        } else {
+            None
+        }
+    }
+
+    pub fn to_request(&self) -> NewPullRequest {
+        NewPullRequest {
+            repository: self.repository.clone(),
+            source_branch: self.source.clone(),
+            target_branch: self.target.clone(),
+            title: self.title.trim().to_string(),
+            description: self.description.trim_end().to_string(),
+            draft: self.draft,
+            reviewers: self
+                .reviewers
+                .iter()
+                .map(|r| r.unique_name.clone())
+                .collect(),
+            work_item_ids: self.work_items.iter().map(|w| w.id).collect(),
+            completion_options: PRCompletionOptions {
+                squash_merge: Some(self.squash),
+                delete_source_branch: Some(self.delete_source_branch),
+                ..Default::default()
+            },
+            auto_complete: self.auto_complete,
+        }
+    }
+}
+
+fn score(matcher: &SkimMatcherV2, text: &str, query: &str) -> Option<i64> {
+    if query.is_empty() {
+        Some(0)
+    } else {
+        matcher.fuzzy_match(text, query)
+    }
+}
+
+/// PR title suggested by a branch name:
+/// `feature/1003-address-autocomplete` -> `Address autocomplete`
+pub fn title_from_branch(branch: &str) -> String {
+    let name = PullRequest::short_branch(branch);
+    let name = name.rsplit('/').next().unwrap_or(name);
+    // Drop a leading ticket number
+    let name = name.trim_start_matches(|c: char| c.is_ascii_digit() || c == '-' || c == '_');
+    let words = name.replace(['-', '_'], " ");
+    let mut chars = words.trim().chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn branches() -> Vec<String> {
+        [
+            "main",
+            "feature/checkout",
+            "feature/1003-address-autocomplete",
+        ]
+        .iter()
+        .map(|b| b.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn test_title_from_branch() {
+        assert_eq!(
+            title_from_branch("refs/heads/feature/1003-address-autocomplete"),
+            "Address autocomplete"
+        );
+        assert_eq!(title_from_branch("bugfix/jpy_rounding"), "Jpy rounding");
+        assert_eq!(title_from_branch("main"), "Main");
+    }
+
+    #[test]
+    fn test_defaults_from_work_item() {
+        let mut dialog =
+            PRCreateDialog::for_work_item("web".into(), 1003, "Address autocomplete".into());
+        dialog.set_branches(branches());
+
+        assert_eq!(dialog.target, "main");
+        assert_eq!(dialog.source, "feature/1003-address-autocomplete");
+        assert_eq!(dialog.title, "Address autocomplete");
+        assert_eq!(dialog.to_request().work_item_ids, [1003]);
+    }
+
+    #[test]
+    fn test_pick_and_swap_branches() {
+        let mut dialog = PRCreateDialog::new("web".into());
+        dialog.set_branches(branches());
+        assert!(dialog.source.is_empty());
+
+        dialog.insert('c');
+        dialog.insert('h');
+        dialog.insert('k');
+        let candidates = dialog.candidates(&[], &[], None);
+        assert_eq!(candidates[0].label(), "feature/checkout");
+
+        dialog.pick(candidates[0].clone());
+        assert_eq!(dialog.source, "feature/checkout");
+        assert_eq!(dialog.title, "Checkout");
+        assert_eq!(dialog.field, PRCreateField::Target);
+
+        dialog.swap_branches();
+        assert_eq!(dialog.source, "main");
+        assert_eq!(dialog.target, "feature/checkout");
+        assert_eq!(dialog.validate(), None);
+    }
+
+    #[test]
+    fn test_reviewer_picker() {
+        let users = vec![
+            User {
+                display_name: "Ada Lovelace".into(),
+                unique_name: "ada@contoso.com".into(),
+            },
+            User {
+                display_name: "Grace Hopper".into(),
+                unique_name: "grace@contoso.com".into(),
+            },
+        ];
+        let mut dialog = PRCreateDialog::new("web".into());
+        dialog.field = PRCreateField::Reviewers;
+
+        // The current user isn't offered
+        let candidates = dialog.candidates(&users, &[], Some("ada@contoso.com"));
+        assert_eq!(candidates.len(), 1);
+        dialog.pick(candidates[0].clone());
+        assert!(dialog
+            .candidates(&users, &[], Some("ada@contoso.com"))
+            .is_empty());
+
+        // Unknown people can be added by email
+        for c in "linus@contoso.com".chars() {
+            dialog.insert(c);
+        }
+        let candidates = dialog.candidates(&users, &[], None);
+        dialog.pick(candidates.last().unwrap().clone());
+        assert_eq!(
+            dialog.to_request().reviewers,
+            ["grace@contoso.com", "linus@contoso.com"]
+        );
+
+        // Backspace on an empty filter removes the last reviewer
+        dialog.backspace();
+        assert_eq!(dialog.reviewers.len(), 1);
+    }
+}
@@ -0,0 +1,501 @@
+use crate::azure::{
+    PRChange, PRCommentAnchor, PRFilePosition, PRIteration, PRThread, PRThreadContext,
+};
+use similar::{ChangeTag, TextDiff};
+
+/// Unchanged lines shown around each change
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    /// `@@ -a,b +c,d @@` separator between hunks
+    Hunk,
+    Context,
+    Added,
+    Removed,
+}
+
+/// One row of a unified diff, with 1-based line numbers on each side
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub old_line: Option<u32>,
+    pub new_line: Option<u32>,
+    pub text: String,
+}
+
+/// Unified diff of two file versions, grouped into hunks
+pub fn unified_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let diff = TextDiff::from_lines(old, new);
+    let mut lines = Vec::new();
+
+    for group in diff.grouped_ops(CONTEXT_LINES) {
+        let (Some(first), Some(last)) = (group.first(), group.last()) else {
+            continue;
+        };
+        let old_start = first.old_range().start;
+        let new_start = first.new_range().start;
+        lines.push(DiffLine {
+            kind: DiffLineKind::Hunk,
+            old_line: None,
+            new_line: None,
+            text: format!(
+                "@@ -{},{} +{},{} @@",
+                old_start + 1,
+                last.old_range().end - old_start,
+                new_start + 1,
+                last.new_range().end - new_start,
+            ),
+        });
+
+        for op in &group {
+            for change in diff.iter_changes(op) {
+                lines.push(DiffLine {
+                    kind: match change.tag() {
+                        ChangeTag::Equal => DiffLineKind::Context,
+                        ChangeTag::Insert => DiffLineKind::Added,
+                        ChangeTag::Delete => DiffLineKind::Removed,
+                    },
+                    old_line: change.old_index().map(|i| i as u32 + 1),
+                    new_line: change.new_index().map(|i| i as u32 + 1),
+                    text: change
+                        .value()
+                        .trim_end_matches(['\n', '\r'])
+                        .replace('\t', "    "),
+                });
+            }
+        }
+    }
+    lines
+}
+
+/// Changed files of a pull request (the `PRDrillDown::Files` level)
+#[derive(Debug, Clone)]
+pub struct PRFiles {
+    pub pr_id: i32,
+    pub repository_id: String,
+    pub iterations: Vec<PRIteration>,
+    /// 0 shows all changes, N the changes pushed in iteration N
+    pub iteration: usize,
+    pub changes: Vec<PRChange>,
+    /// Index into the (filtered) file list
+    pub selected: usize,
+    pub loading: bool,
+    /// Diff open in the preview pane
+    pub diff: Option<FileDiff>,
+}
+
+impl PRFiles {
+    pub fn new(pr_id: i32, repository_id: String) -> Self {
+        Self {
+            pr_id,
+            repository_id,
+            iterations: Vec::new(),
+            iteration: 0,
+            changes: Vec::new(),
+            selected: 0,
+            loading: true,
+            diff: None,
+        }
+    }
+
+    /// Iteration and base iteration to request changes for (base 0 is the target branch)
+    pub fn compared_iterations(&self) -> Option<(i32, i32)> {
+        match self.iteration {
+            0 => self.iterations.last().map(|it| (it.id, 0)),
+            n => {
+                let iteration = self.iterations.get(n - 1)?;
+                let base = n
+                    .checked_sub(2)
+                    .and_then(|i| self.iterations.get(i))
+                    .map(|it| it.id)
+                    .unwrap_or(0);
+                Some((iteration.id, base))
+            }
+        }
+    }
+
+    /// Base and head commits of the compared range
+    pub fn compared_commits(&self) -> Option<(String, String)> {
+        let commit = |it: &PRIteration, base: bool| {
+            if base {
+                it.common_ref_commit
+                    .as_ref()
+                    .or(it.target_ref_commit.as_ref())
+            } else {
+                it.source_ref_commit.as_ref()
+            }
+            .map(|c| c.commit_id.clone())
+        };
+
+        let (head, base) = match self.iteration {
+            0 => {
+                let last = self.iterations.last()?;
+                (commit(last, false)?, commit(last, true)?)
+            }
+            n => {
+                let iteration = self.iterations.get(n - 1)?;
+                let base = match n.checked_sub(2).and_then(|i| self.iterations.get(i)) {
+                    Some(previous) => commit(previous, false)?,
+                    None => commit(iteration, true)?,
+                };
+                (commit(iteration, false)?, base)
+            }
+        };
+        Some((base, head))
+    }
+
+    /// Step through "all changes" and each iteration; false when there's only one view
+    pub fn cycle_iteration(&mut self, forward: bool) -> bool {
+        // A single iteration shows the same changes as "all"
+        if self.iterations.len() < 2 {
+            return false;
+        }
+        let count = self.iterations.len() + 1;
+        self.iteration = if forward {
+            (self.iteration + 1) % count
+        } else {
+            (self.iteration + count - 1) % count
+        };
+        self.selected = 0;
+        self.changes.clear();
+        self.diff = None;
+        self.loading = true;
+        true
+    }
+
+    pub fn iteration_label(&self) -> String {
+        match self.iteration {
+            0 => "All changes".to_string(),
+            n => format!("Iteration {n} of {}", self.iterations.len()),
+        }
+    }
+}
+
+/// A file diff open for review
+#[derive(Debug, Clone)]
+pub struct FileDiff {
+    pub change: PRChange,
+    pub lines: Vec<DiffLine>,
+    /// Line under the cursor
+    pub cursor: usize,
+    /// Other end of the line range being selected
+    pub anchor: Option<usize>,
+    /// Iterations compared, as in `PRFiles::compared_iterations`
+    pub iterations: (i32, i32),
+    /// First rendered row (kept by the UI so the cursor stays visible)
+    pub scroll: usize,
+}
+
+impl FileDiff {
+    pub fn new(change: PRChange, old: &str, new: &str, iterations: (i32, i32)) -> Self {
+        let lines = unified_diff(old, new);
+        let cursor = lines
+            .iter()
+            .position(|l| l.kind != DiffLineKind::Hunk)
+            .unwrap_or(0);
+        Self {
+            change,
+            lines,
+            cursor,
+            anchor: None,
+            iterations,
+            scroll: 0,
+        }
+    }
+
+    pub fn move_cursor(&mut self, delta: isize) {
+        if self.lines.is_empty() {
+            return;
+        }
+        self.cursor = self
+            .cursor
+            .saturating_add_signed(delta)
+            .min(self.lines.len() - 1);
+    }
+
+    /// Start a line range at the cursor, or drop the one in progress
+    pub fn toggle_selection(&mut self) {
+        self.anchor = match self.anchor {
+            Some(_) => None,
+            None if self.lines.is_empty() => None,
+            None => Some(self.cursor),
+        };
+    }
+
+    /// Selected rows (inclusive); just the cursor line without a range.
+    /// `None` when the diff has no lines (renamed or unchanged files)
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        let last = self.lines.len().checked_sub(1)?;
+        let cursor = self.cursor.min(last);
+        let anchor = self.anchor.unwrap_or(cursor).min(last);
+        Some((anchor.min(cursor), anchor.max(cursor)))
+    }
+
+    pub fn is_selected(&self, idx: usize) -> bool {
+        self.selection()
+            .is_some_and(|(start, end)| (start..=end).contains(&idx))
+    }
+
+    /// Anchor for a comment on the selected lines. Ranges that touch the new
+    /// version are anchored on the right; ranges of removed lines on the left.
+    pub fn comment_anchor(&self) -> Option<PRCommentAnchor> {
+        let (start, end) = self.selection()?;
+        let selected = &self.lines[start..=end];
+
+        let right: Vec<(u32, &DiffLine)> = selected
+            .iter()
+            .filter_map(|l| l.new_line.map(|n| (n, l)))
+            .collect();
+        let left: Vec<(u32, &DiffLine)> = selected
+            .iter()
+            .filter_map(|l| l.old_line.map(|n| (n, l)))
+            .collect();
+        let (lines, on_right) = if right.is_empty() {
+            (left, false)
+        } else {
+            (right, true)
+        };
+        let (&(first, _), &(last, last_line)) = (lines.first()?, lines.last()?);
+
+        let range = (
+            Some(PRFilePosition {
+                line: first,
+                offset: 1,
+            }),
+            Some(PRFilePosition {
+                line: last,
+                offset: last_line.text.chars().count() as u32 + 1,
+            }),
+        );
+        let mut thread_context = PRThreadContext {
+            file_path: self.change.path.clone(),
+            ..Default::default()
+        };
+        if on_right {
+            (
+                thread_context.right_file_start,
+                thread_context.right_file_end,
+            ) = range;
+        } else {
+            (thread_context.left_file_start, thread_context.left_file_end) = range;
+        }
+
+        Some(PRCommentAnchor {
+            thread_context,
+            change_tracking_id: self.change.change_tracking_id,
+            // Against the target branch, the threads API wants the same
+            // iteration twice rather than 0
+            first_iteration: match self.iterations.1 {
+                0 => self.iterations.0,
+                compare_to => compare_to,
+            },
+            second_iteration: self.iterations.0,
+        })
+    }
+
+    /// Human-readable selected range (`L12-14`, or `L7 (old)` for removed lines)
+    pub fn selection_label(&self) -> Option<String> {
+        self.comment_anchor()?.thread_context.line_label()
+    }
+
+    /// Row a thread is shown under, if its last line is part of the diff
+    pub fn thread_row(&self, context: &PRThreadContext) -> Option<usize> {
+        if let Some(pos) = context.right_file_end.or(context.right_file_start) {
+            return self
+                .lines
+                .iter()
+                .position(|l| l.kind != DiffLineKind::Removed && l.new_line == Some(pos.line));
+        }
+        let pos = context.left_file_end.or(context.left_file_start)?;
+        self.lines
+            .iter()
+            .position(|l| l.kind != DiffLineKind::Added && l.old_line == Some(pos.line))
+    }
+
+    /// Visible threads on this file, with the row each is anchored to
+    /// (`None` for file-level threads and lines outside the diff)
+    pub fn threads<'a>(&self, threads: &'a [PRThread]) -> Vec<(Option<usize>, &'a PRThread)> {
+        threads
+            .iter()
+            .filter(|t| t.is_visible() && t.file_path() == Some(self.change.path.as_str()))
+            .map(|t| {
+                (
+                    t.thread_context.as_ref().and_then(|c| self.thread_row(c)),
+                    t,
+                )
+            })
+            .collect()
+    }
+
+    /// First thread anchored to the cursor line
+    pub fn thread_at_cursor<'a>(&self, threads: &'a [PRThread]) -> Option<&'a PRThread> {
+        self.threads(threads)
+            .into_iter()
+            .find(|(row, _)| *row == Some(self.cursor))
+            .map(|(_, t)| t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::azure::GitCommitRef;
+
+    const OLD: &str = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+    const NEW: &str = "a\nb\nc\nd\nE\nf\ng\nh\ni\nj\nk\n";
+
+    fn change() -> PRChange {
+        PRChange {
+            path: "/src/app.ts".to_string(),
+            change_type: "edit".to_string(),
+            change_tracking_id: 7,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_unified_diff_hunks() {
+        let lines = unified_diff(OLD, NEW);
+        assert_eq!(lines[0].kind, DiffLineKind::Hunk);
+        assert_eq!(lines[0].text, "@@ -2,9 +2,10 @@");
+
+        let removed = lines
+            .iter()
+            .find(|l| l.kind == DiffLineKind::Removed)
+            .unwrap();
+        assert_eq!((removed.old_line, removed.new_line), (Some(5), None));
+        assert_eq!(removed.text, "e");
+
+        let added: Vec<_> = lines
+            .iter()
+            .filter(|l| l.kind == DiffLineKind::Added)
+            .map(|l| (l.new_line.unwrap(), l.text.as_str()))
+            .collect();
+        assert_eq!(added, [(5, "E"), (11, "k")]);
+    }
+
+    #[test]
+    fn test_comment_anchor_prefers_new_side() {
+        let mut diff = FileDiff::new(change(), OLD, NEW, (3, 1));
+        let removed = diff
+            .lines
+            .iter()
+            .position(|l| l.kind == DiffLineKind::Removed)
+            .unwrap();
+
+        // Only the removed line: anchored on the left
+        diff.cursor = removed;
+        let anchor = diff.comment_anchor().unwrap();
+        assert_eq!(anchor.thread_context.left_file_start.unwrap().line, 5);
+        assert!(anchor.thread_context.right_file_start.is_none());
+        assert_eq!(diff.selection_label().as_deref(), Some("L5 (old)"));
+
+        // Range over the removed and added line plus context: right side only
+        diff.toggle_selection();
+        diff.move_cursor(2);
+        let anchor = diff.comment_anchor().unwrap();
+        let context = &anchor.thread_context;
+        assert_eq!(context.right_file_start.unwrap().line, 5);
+        assert_eq!(
+            context.right_file_end.unwrap(),
+            PRFilePosition { line: 6, offset: 2 }
+        );
+        assert_eq!(anchor.change_tracking_id, 7);
+        assert_eq!((anchor.first_iteration, anchor.second_iteration), (1, 3));
+        assert_eq!(diff.selection_label().as_deref(), Some("L5-6"));
+
+        // "All changes" compares to the target branch
+        diff.iterations = (3, 0);
+        let anchor = diff.comment_anchor().unwrap();
+        assert_eq!((anchor.first_iteration, anchor.second_iteration), (3, 3));
+    }
+
+    #[test]
+    fn test_rename_only_file_has_nothing_to_comment_on() {
+        let renamed = PRChange {
+            change_type: "rename".to_string(),
+            ..change()
+        };
+        let mut diff = FileDiff::new(renamed, OLD, OLD, (1, 0));
+        assert!(diff.lines.is_empty());
+        assert_eq!(diff.selection(), None);
+        assert!(!diff.is_selected(0));
+        assert!(diff.comment_anchor().is_none());
+        assert_eq!(diff.selection_label(), None);
+
+        diff.toggle_selection();
+        assert_eq!(diff.anchor, None);
+        diff.move_cursor(3);
+        assert!(diff.comment_anchor().is_none());
+    }
+
+    #[test]
+    fn test_threads_anchor_to_rows() {
+        let diff = FileDiff::new(change(), OLD, NEW, (1, 0));
+        let on_line = |side_right: bool, line: u32| PRThread {
+            id: line as i32,
+            thread_context: Some(PRThreadContext {
+                file_path: "/src/app.ts".to_string(),
+                right_file_end: side_right.then_some(PRFilePosition { line, offset: 1 }),
+                left_file_end: (!side_right).then_some(PRFilePosition { line, offset: 1 }),
+                ..Default::default()
+            }),
+            comments: vec![crate::azure::PRComment {
+                id: 1,
+                content: Some("hm".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let threads = vec![on_line(true, 11), on_line(false, 5), on_line(true, 1)];
+
+        let rows = diff.threads(&threads);
+        assert_eq!(diff.lines[rows[0].0.unwrap()].text, "k");
+        assert_eq!(diff.lines[rows[1].0.unwrap()].kind, DiffLineKind::Removed);
+        // Line 1 is outside the hunk
+        assert_eq!(rows[2].0, None);
+    }
+
+    #[test]
+    fn test_compared_commits_per_iteration() {
+        let iteration = |id: i32| PRIteration {
+            id,
+            source_ref_commit: Some(GitCommitRef {
+                commit_id: format!("s{id}"),
+                ..Default::default()
+            }),
+            common_ref_commit: Some(GitCommitRef {
+                commit_id: "base".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut files = PRFiles::new(1, "repo".to_string());
+        files.iterations = vec![iteration(1), iteration(2)];
+
+        assert_eq!(files.compared_iterations(), Some((2, 0)));
+        assert_eq!(
+            files.compared_commits(),
+            Some(("base".to_string(), "s2".to_string()))
+        );
+
+        assert!(files.cycle_iteration(true));
+        assert_eq!(files.compared_iterations(), Some((1, 0)));
+        assert_eq!(
+            files.compared_commits(),
+            Some(("base".to_string(), "s1".to_string()))
+        );
+
+        assert!(files.cycle_iteration(true));
+        assert_eq!(files.compared_iterations(), Some((2, 1)));
+        assert_eq!(
+            files.compared_commits(),
+            Some(("s1".to_string(), "s2".to_string()))
+        );
+
+        // Wraps back to all changes
+        assert!(files.cycle_iteration(true));
+        assert_eq!(files.iteration_label(), "All changes");
+    }
+}
@@ -0,0 +1,282 @@
+//! State of the Queries view: saved WIQL queries from the config, the
+//! project's "My Queries" / "Shared Queries" folders, and an ad-hoc WIQL editor
+
+use crate::azure::QueryHierarchyItem;
+use crate::config::SavedQuery;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+
+/// Folder holding the queries saved in the config file
+pub const SAVED_FOLDER: &str = "Saved (config)";
+
+/// Starting point for a new ad-hoc query
+const NEW_QUERY_WIQL: &str =
+    "SELECT [System.Id] FROM WorkItems\nWHERE [System.TeamProject] = @project\n  AND [System.State] <> 'Done'\nORDER BY [System.ChangedDate] DESC";
+
+/// One row of the query tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryEntry {
+    pub name: String,
+    /// Folder path including the name, e.g. `Shared Queries/Bugs/Open`
+    pub path: String,
+    pub depth: usize,
+    /// `None` for folders
+    pub wiql: Option<String>,
+}
+
+impl QueryEntry {
+    pub fn is_folder(&self) -> bool {
+        self.wiql.is_none()
+    }
+}
+
+/// Row of the browser list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryRow<'a> {
+    /// Opens an empty WIQL editor
+    NewQuery,
+    Entry(&'a QueryEntry),
+}
+
+/// Field of the WIQL editor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WiqlField {
+    Name,
+    Wiql,
+}
+
+/// Ad-hoc WIQL editor; a name saves the query to the config when it runs
+#[derive(Debug, Clone)]
+pub struct WiqlEditor {
+    pub name: String,
+    pub wiql: String,
+    pub field: WiqlField,
+}
+
+impl WiqlEditor {
+    pub fn new(wiql: Option<&str>) -> Self {
+        Self {
+            name: String::new(),
+            wiql: wiql.unwrap_or(NEW_QUERY_WIQL).to_string(),
+            field: WiqlField::Wiql,
+        }
+    }
+
+    pub fn toggle_field(&mut self) {
+        self.field = match self.field {
+            WiqlField::Name => WiqlField::Wiql,
+            WiqlField::Wiql => WiqlField::Name,
+        };
+    }
+
+    pub fn insert(&mut self, c: char) {
+        match self.field {
+            WiqlField::Name if c != '\n' => self.name.push(c),
+            WiqlField::Name => self.field = WiqlField::Wiql,
+            WiqlField::Wiql => self.wiql.push(c),
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        match self.field {
+            WiqlField::Name => self.name.pop(),
+            WiqlField::Wiql => self.wiql.pop(),
+        };
+    }
+
+    /// Query to run, or why it can't run yet
+    pub fn query(&self) -> Result<SavedQuery, &'static str> {
+        if self.wiql.trim().is_empty() {
+            return Err("Enter a WIQL query");
+        }
+        Ok(SavedQuery {
+            name: self.name.trim().to_string(),
+            wiql: self.wiql.trim().to_string(),
+        })
+    }
+}
+
+/// Queries picker state
+#[derive(Debug, Clone, Default)]
+pub struct QueryBrowser {
+    pub entries: Vec<QueryEntry>,
+    /// Fuzzy filter typed into the picker
+    pub filter: String,
+    pub cursor: usize,
+    pub editor: Option<WiqlEditor>,
+}
+
+impl QueryBrowser {
+    /// Browser listing the saved queries, followed by the project's folders
+    pub fn new(saved: &[SavedQuery], project: &[QueryHierarchyItem]) -> Self {
+        let mut entries = Vec::new();
+        if !saved.is_empty() {
+            entries.push(QueryEntry {
+                name: SAVED_FOLDER.to_string(),
+                path: SAVED_FOLDER.to_string(),
+                depth: 0,
+                wiql: None,
+            });
+            entries.extend(saved.iter().map(|q| QueryEntry {
+                name: q.name.clone(),
+                path: format!("{SAVED_FOLDER}/{}", q.name),
+                depth: 1,
+                wiql: Some(q.wiql.clone()),
+            }));
+        }
+        fn flatten(items: &[QueryHierarchyItem], depth: usize, entries: &mut Vec<QueryEntry>) {
+            for item in items {
+                entries.push(QueryEntry {
+                    name: item.name.clone(),
+                    path: if item.path.is_empty() {
+                        item.name.clone()
+                    } else {
+                        item.path.clone()
+                    },
+                    depth,
+                    wiql: if item.is_folder {
+                        None
+                    } else {
+                        Some(item.wiql.clone().unwrap_or_default())
+                    },
+                });
+                flatten(&item.children, depth + 1, entries);
+            }
+        }
+        flatten(project, 0, &mut entries);
+
+        Self {
+            entries,
+            ..Self::default()
+        }
+    }
+
+    /// Rows to show: the whole tree, or the matching queries while filtering
+    pub fn rows(&self) -> Vec<QueryRow<'_>> {
+        let mut rows = vec![QueryRow::NewQuery];
+        if self.filter.is_empty() {
+            rows.extend(self.entries.iter().map(QueryRow::Entry));
+            return rows;
+        }
+
+        let matcher = SkimMatcherV2::default();
+        let mut scored: Vec<(i64, &QueryEntry)> = self
+            .entries
+            .iter()
+            .filter(|e| !e.is_folder())
+            .filter_map(|e| matcher.fuzzy_match(&e.path, &self.filter).map(|s| (s, e)))
+            .collect();
+        scored.sort_by_key(|(s, _)| std::cmp::Reverse(*s));
+        rows.extend(scored.into_iter().map(|(_, e)| QueryRow::Entry(e)));
+        rows
+    }
+
+    pub fn selected(&self) -> Option<QueryRow<'_>> {
+        self.rows().get(self.cursor).copied()
+    }
+
+    pub fn move_cursor(&mut self, delta: isize) {
+        let len = self.rows().len();
+        self.cursor = self.cursor.saturating_add_signed(delta).min(len - 1);
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.filter.push(c);
+        // Jump to the best match rather than the "new query" row
+        self.cursor = 1.min(self.rows().len() - 1);
+    }
+
+    pub fn backspace(&mut self) {
+        self.filter.pop();
+        self.cursor = 0;
+    }
+
+    /// Open the WIQL editor, starting from the selected query if any
+    pub fn open_editor(&mut self) {
+        let wiql = match self.selected() {
+            Some(QueryRow::Entry(entry)) => entry.wiql.clone(),
+            _ => None,
+        };
+        self.editor = Some(WiqlEditor::new(wiql.as_deref()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project_queries() -> Vec<QueryHierarchyItem> {
+        serde_json::from_value(serde_json::json!([
+            {
+                "id": "1",
+                "name": "Shared Queries",
+                "path": "Shared Queries",
+                "isFolder": true,
+                "children": [
+                    {
+                        "id": "2",
+                        "name": "Open bugs",
+                        "path": "Shared Queries/Open bugs",
+                        "wiql": "SELECT [System.Id] FROM WorkItems WHERE [System.WorkItemType] = 'Bug'"
+                    }
+                ]
+            }
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_tree_and_filter() {
+        let saved = vec![SavedQuery {
+            name: "Urgent".into(),
+            wiql: "SELECT [System.Id] FROM WorkItems WHERE [System.Tags] CONTAINS 'urgent'".into(),
+        }];
+        let mut browser = QueryBrowser::new(&saved, &project_queries());
+        let names: Vec<(&str, usize)> = browser
+            .entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.depth))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (SAVED_FOLDER, 0),
+                ("Urgent", 1),
+                ("Shared Queries", 0),
+                ("Open bugs", 1)
+            ]
+        );
+        assert_eq!(browser.rows().len(), 5);
+        assert_eq!(browser.selected(), Some(QueryRow::NewQuery));
+
+        // Filtering drops folders and selects the best match
+        for c in "bugs".chars() {
+            browser.insert(c);
+        }
+        assert_eq!(browser.rows().len(), 2);
+        match browser.selected() {
+            Some(QueryRow::Entry(entry)) => assert_eq!(entry.name, "Open bugs"),
+            other => panic!("unexpected selection {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_editor_from_selected_query() {
+        let mut browser = QueryBrowser::new(&[], &project_queries());
+        browser.move_cursor(2);
+        browser.open_editor();
+        let editor = browser.editor.as_mut().unwrap();
+        assert!(editor.wiql.contains("'Bug'"));
+        assert_eq!(editor.query().unwrap().name, "");
+
+        editor.toggle_field();
+        for c in "Bugs\n".chars() {
+            editor.insert(c);
+        }
+        assert_eq!(editor.field, WiqlField::Wiql);
+        assert_eq!(editor.query().unwrap().name, "Bugs");
+
+        editor.wiql.clear();
+        assert_eq!(editor.query().unwrap_err(), "Enter a WIQL query");
+    }
+}
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use portable_pty::{native_pty_system, CommandBuilder, PtyPair, PtySize};
+use portable_pty::{native_pty_system, Child, CommandBuilder, PtyPair, PtySize};
 use std::io::{Read, Write};
 use std::sync::{Arc, Mutex};
 use std::thread;
@@ -9,6 +9,8 @@ pub struct EmbeddedTerminal {
     parser: Arc<Mutex<vt100::Parser>>,
     writer: Box<dyn Write + Send>,
     running: Arc<Mutex<bool>>,
+    /// Editor process, kept to notice when the user quits it
+    editor: Option<Box<dyn Child + Send + Sync>>,
 }
 
 /// Escape a string for safe use in single-quoted shell arguments.
@@ -41,6 +43,7 @@ impl EmbeddedTerminal {
             parser,
             writer,
             running,
+            editor: None,
         })
     }
 
@@ -104,8 +107,7 @@ impl EmbeddedTerminal {
         Ok(())
     }
 
-    /// Spawn a generic editor for a file
-    #[allow(dead_code)]
+    /// Spawn `$EDITOR` (falling back to nvim) for a file
     pub fn spawn_editor(&mut self, file_path: &str) -> Result<()> {
         if file_path.is_empty() {
             return Ok(());
@@ -122,11 +124,19 @@ impl EmbeddedTerminal {
 
         self.start_reader_thread()?;
 
-        drop(child);
+        self.editor = Some(child);
 
         Ok(())
     }
 
+    /// Whether the editor started by `spawn_editor` has quit
+    pub fn editor_exited(&mut self) -> bool {
+        match self.editor.as_mut() {
+            Some(child) => !matches!(child.try_wait(), Ok(None)),
+            None => false,
+        }
+    }
+
     pub fn write(&mut self, data: &[u8]) -> Result<()> {
         self.writer.write_all(data)?;
         self.writer.flush()?;
@@ -0,0 +1,254 @@
+//! Tests tab of a pipeline run: pass / fail / skip totals of the test runs a
+//! build published, its failed tests, and how each of them fared in the runs
+//! of the pipeline before it
+
+use crate::azure::{DevOpsBackend, TestCaseResult, TestRun};
+use anyhow::Result;
+
+/// Earlier runs of the pipeline looked at for flaky tests
+pub const FLAKY_HISTORY_RUNS: usize = 10;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TestTotals {
+    pub passed: usize,
+    pub failed: usize,
+    /// Not executed, inconclusive, ...
+    pub skipped: usize,
+}
+
+impl TestTotals {
+    pub fn total(&self) -> usize {
+        self.passed + self.failed + self.skipped
+    }
+}
+
+/// A test that failed in the build
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailedTest {
+    /// Identifies the test across builds
+    pub key: String,
+    pub name: String,
+    /// File or assembly of the test
+    pub storage: Option<String>,
+    /// Test run it was reported in
+    pub run: String,
+    pub error_message: Option<String>,
+    pub stack_trace: Option<String>,
+    pub duration_ms: Option<f64>,
+}
+
+impl FailedTest {
+    fn new(run: &str, result: &TestCaseResult) -> Self {
+        let name = result
+            .automated_test_name
+            .clone()
+            .or_else(|| result.test_case_title.clone())
+            .unwrap_or_else(|| format!("Test {}", result.id));
+        let storage = result.automated_test_storage.clone();
+        let key = match &storage {
+            Some(storage) => format!("{storage}::{name}"),
+            None => name.clone(),
+        };
+        Self {
+            key,
+            name,
+            storage,
+            run: run.to_string(),
+            error_message: result.error_message.clone(),
+            stack_trace: result.stack_trace.clone(),
+            duration_ms: result.duration_in_ms,
+        }
+    }
+}
+
+/// Test results of a build
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BuildTests {
+    pub totals: TestTotals,
+    pub failed: Vec<FailedTest>,
+}
+
+impl BuildTests {
+    /// Totals from the runs' counts per outcome; runs without them count
+    /// their failed results as failed and the rest not passed as skipped
+    pub fn new(runs: &[(TestRun, Vec<TestCaseResult>)]) -> Self {
+        let mut totals = TestTotals::default();
+        let mut failed = Vec::new();
+        for (run, results) in runs {
+            let name = run
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("Run {}", run.id));
+            failed.extend(results.iter().map(|r| FailedTest::new(&name, r)));
+
+            if run.run_statistics.is_empty() {
+                let passed = run.passed_tests.max(0) as usize;
+                totals.passed += passed;
+                totals.failed += results.len();
+                totals.skipped +=
+                    (run.total_tests.max(0) as usize).saturating_sub(passed + results.len());
+                continue;
+            }
+            for statistic in &run.run_statistics {
+                let count = statistic.count.max(0) as usize;
+                match statistic.outcome.as_str() {
+                    "Passed" => totals.passed += count,
+                    "Failed" | "Aborted" | "Timeout" | "Error" => totals.failed += count,
+                    _ => totals.skipped += count,
+                }
+            }
+        }
+        Self { totals, failed }
+    }
+
+    pub fn has_tests(&self) -> bool {
+        self.totals.total() > 0
+    }
+
+    fn failed(&self, key: &str) -> bool {
+        self.failed.iter().any(|t| t.key == key)
+    }
+}
+
+/// Test runs of a build with their failed results
+pub async fn load<B: DevOpsBackend>(client: &B, build_id: i32) -> Result<BuildTests> {
+    let runs = client.list_test_runs(build_id).await?;
+    let mut with_results = Vec::with_capacity(runs.len());
+    for run in runs {
+        let results = client.get_failed_test_results(run.id).await?;
+        with_results.push((run, results));
+    }
+    Ok(BuildTests::new(&with_results))
+}
+
+/// How a failed test fared in the earlier runs of the pipeline
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TestHistory {
+    /// Earlier runs that ran tests
+    pub runs: usize,
+    /// Those the test failed in
+    pub failures: usize,
+    /// Passing and failing back and forth, rather than broken once
+    pub flaky: bool,
+}
+
+/// History of a test failing now, from the earlier builds, newest first
+pub fn history(key: &str, earlier: &[&BuildTests]) -> TestHistory {
+    let outcomes: Vec<bool> = earlier
+        .iter()
+        .filter(|b| b.has_tests())
+        .map(|b| b.failed(key))
+        .collect();
+    // Oldest first, ending with the failure now
+    let sequence: Vec<bool> = outcomes
+        .iter()
+        .rev()
+        .copied()
+        .chain(std::iter::once(true))
+        .collect();
+    let flips = sequence.windows(2).filter(|w| w[0] != w[1]).count();
+    TestHistory {
+        runs: outcomes.len(),
+        failures: outcomes.iter().filter(|&&failed| failed).count(),
+        flaky: flips >= 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::azure::TestRunStatistic;
+
+    fn result(name: &str, storage: &str) -> TestCaseResult {
+        TestCaseResult {
+            automated_test_name: Some(name.to_string()),
+            automated_test_storage: Some(storage.to_string()),
+            outcome: Some("Failed".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn build(failed: &[&str]) -> BuildTests {
+        let run = TestRun {
+            id: 1,
+            total_tests: 10,
+            passed_tests: 10 - failed.len() as i32,
+            ..Default::default()
+        };
+        let results = failed
+            .iter()
+            .map(|name| result(name, "a.test.ts"))
+            .collect();
+        BuildTests::new(&[(run, results)])
+    }
+
+    #[test]
+    fn test_totals_and_failed_tests() {
+        let unit = TestRun {
+            id: 1,
+            name: Some("Unit".to_string()),
+            total_tests: 12,
+            run_statistics: vec![
+                TestRunStatistic {
+                    outcome: "Passed".to_string(),
+                    count: 9,
+                },
+                TestRunStatistic {
+                    outcome: "Failed".to_string(),
+                    count: 1,
+                },
+                TestRunStatistic {
+                    outcome: "NotExecuted".to_string(),
+                    count: 2,
+                },
+            ],
+            ..Default::default()
+        };
+        // No counts per outcome: the rest of the total is skipped
+        let e2e = TestRun {
+            id: 2,
+            total_tests: 5,
+            passed_tests: 3,
+            ..Default::default()
+        };
+        let tests = BuildTests::new(&[
+            (unit, vec![result("rounds JPY", "checkout.test.ts")]),
+            (e2e, vec![TestCaseResult::default()]),
+        ]);
+        assert_eq!(
+            tests.totals,
+            TestTotals {
+                passed: 12,
+                failed: 2,
+                skipped: 3
+            }
+        );
+        assert_eq!(tests.failed[0].key, "checkout.test.ts::rounds JPY");
+        assert_eq!(tests.failed[0].run, "Unit");
+        assert_eq!(tests.failed[1].name, "Test 0");
+        assert_eq!(tests.failed[1].run, "Run 2");
+    }
+
+    #[test]
+    fn test_flaky_history() {
+        let key = "a.test.ts::checkout";
+        let passed = build(&[]);
+        let failed = build(&["checkout"]);
+        let no_tests = BuildTests::default();
+
+        // Fails on and off
+        let history = super::history(key, &[&passed, &failed, &no_tests, &passed]);
+        assert_eq!(
+            history,
+            TestHistory {
+                runs: 3,
+                failures: 1,
+                flaky: true
+            }
+        );
+        // Broke in this run, or has been failing for a while
+        assert!(!super::history(key, &[&passed, &passed]).flaky);
+        assert!(!super::history(key, &[&failed, &failed, &passed]).flaky);
+        assert_eq!(super::history(key, &[]), TestHistory::default());
+    }
+}
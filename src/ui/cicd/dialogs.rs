@@ -2,11 +2,15 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, Gauge, List, ListItem, Paragraph},
     Frame,
 };
 
 use crate::app::{DialogCursor, ReleaseTriggerDialog};
+use crate::artifacts::{format_size, ArtifactBrowser, ArtifactDownload};
+use crate::failure_summary::FailureSummary;
+use crate::log_view::Severity;
+use crate::pipeline_trigger::{ref_label, PipelineTriggerDialog, TriggerField};
 
 /// Render the release trigger dialog as a centered popup
 pub fn render_release_trigger_dialog(f: &mut Frame, dialog: &ReleaseTriggerDialog) {
@@ -112,6 +116,448 @@ pub fn render_release_trigger_dialog(f: &mut Frame, dialog: &ReleaseTriggerDialo
     f.render_widget(cancel, buttons_layout[1]);
 }
 
+/// Render the pipeline trigger dialog: branch, parameters, variables and
+/// stages, or the branch/tag picker while it is open
+pub fn render_pipeline_trigger_dialog(f: &mut Frame, dialog: &PipelineTriggerDialog) {
+    let area = centered_rect(60, 70, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" Run Pipeline: {} ", dialog.pipeline_name))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if dialog.loading {
+        let loading = Paragraph::new("Loading pipeline...")
+            .style(Style::default().fg(Color::Yellow))
+            .alignment(Alignment::Center);
+        f.render_widget(loading, inner);
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(3),    // Fields or picker
+            Constraint::Length(1), // Warning
+        ])
+        .split(inner);
+
+    if let Some(warning) = &dialog.warning {
+        f.render_widget(
+            Paragraph::new(warning.as_str()).style(Style::default().fg(Color::Yellow)),
+            chunks[1],
+        );
+    }
+
+    if let Some(query) = &dialog.ref_query {
+        render_ref_picker(f, dialog, query, chunks[0]);
+        return;
+    }
+
+    let selected = dialog.selected();
+    let muted = Style::default().fg(Color::DarkGray);
+    let heading = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let mut lines = Vec::new();
+    let mut cursor_line = 0;
+    let mut row = |lines: &mut Vec<Line<'static>>,
+                   field: TriggerField,
+                   label: String,
+                   value: Span<'static>| {
+        let style = if field == selected {
+            cursor_line = lines.len();
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let marker = if field == selected { "> " } else { "  " };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{marker}{label:<24}"), style),
+            value,
+        ]));
+    };
+    let editing = |field: TriggerField| {
+        dialog
+            .editing
+            .as_ref()
+            .filter(|_| field == selected)
+            .map(|text| Span::styled(format!("{text}▏"), Style::default().fg(Color::Cyan)))
+    };
+
+    row(
+        &mut lines,
+        TriggerField::Branch,
+        "Branch / tag".to_string(),
+        Span::styled(ref_label(&dialog.branch), Style::default().fg(Color::Green)),
+    );
+
+    if !dialog.parameters.is_empty() {
+        lines.push(Line::raw(""));
+        lines.push(Line::styled("Parameters", heading));
+    }
+    for (i, p) in dialog.parameters.iter().enumerate() {
+        let field = TriggerField::Parameter(i);
+        let parameter = &p.parameter;
+        let value = if let Some(text) = editing(field) {
+            text
+        } else if !parameter.is_scalar() {
+            Span::styled(format!("({}, set in YAML)", parameter.kind), muted)
+        } else if parameter.is_boolean() {
+            let checked = if p.value == "true" { "[x]" } else { "[ ]" };
+            Span::raw(checked)
+        } else if !parameter.values.is_empty() {
+            Span::raw(format!("‹{}›  {}", p.value, parameter.values.join(" / ")))
+        } else {
+            Span::raw(format!("{}  ({})", p.value, parameter.kind))
+        };
+        row(&mut lines, field, parameter.label().to_string(), value);
+    }
+
+    if !dialog.variables.is_empty() {
+        lines.push(Line::raw(""));
+        lines.push(Line::styled("Variables", heading));
+    }
+    for (i, v) in dialog.variables.iter().enumerate() {
+        let field = TriggerField::Variable(i);
+        let value = match editing(field) {
+            Some(text) => text,
+            None if v.secret && v.value.is_empty() => Span::styled("(secret)", muted),
+            None if v.secret => Span::raw("••••••"),
+            None if v.value != v.default => {
+                Span::styled(v.value.clone(), Style::default().fg(Color::Green))
+            }
+            None => Span::raw(v.value.clone()),
+        };
+        row(&mut lines, field, v.name.clone(), value);
+    }
+
+    if !dialog.stages.is_empty() {
+        lines.push(Line::raw(""));
+        lines.push(Line::styled("Stages", heading));
+    }
+    for (i, stage) in dialog.stages.iter().enumerate() {
+        let (checkbox, style) = if stage.run {
+            ("[x] run", Style::default().fg(Color::Green))
+        } else {
+            ("[ ] skip", Style::default().fg(Color::DarkGray))
+        };
+        row(
+            &mut lines,
+            TriggerField::Stage(i),
+            stage.name.clone(),
+            Span::styled(checkbox, style),
+        );
+    }
+
+    // Keep the highlighted row in view
+    let height = chunks[0].height as usize;
+    let scroll = (cursor_line + 1).saturating_sub(height);
+    f.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), chunks[0]);
+}
+
+fn render_ref_picker(f: &mut Frame, dialog: &PipelineTriggerDialog, query: &str, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(1)])
+        .split(area);
+    f.render_widget(
+        Paragraph::new(format!("🔍 {query}▏")).style(Style::default().fg(Color::Cyan)),
+        chunks[0],
+    );
+
+    let matches = dialog.ref_matches();
+    if matches.is_empty() {
+        let hint = if query.trim().is_empty() {
+            "No branches or tags loaded; type a branch name".to_string()
+        } else {
+            format!("No match; Enter runs on {}", query.trim())
+        };
+        f.render_widget(
+            Paragraph::new(hint).style(Style::default().fg(Color::DarkGray)),
+            chunks[1],
+        );
+        return;
+    }
+
+    let height = chunks[1].height as usize;
+    let skip = (dialog.ref_cursor + 1).saturating_sub(height);
+    let items: Vec<ListItem> = matches
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .take(height)
+        .map(|(i, git_ref)| {
+            let style = if i == dialog.ref_cursor {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else if **git_ref == dialog.branch {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default()
+            };
+            ListItem::new(format!(" {}", ref_label(git_ref))).style(style)
+        })
+        .collect();
+    f.render_widget(List::new(items), chunks[1]);
+}
+
+/// Render the "Why did it fail?" panel of a run
+pub fn render_failure_summary(f: &mut Frame, summary: &FailureSummary) {
+    let area = centered_rect(70, 70, f.area());
+    f.render_widget(Clear, area);
+
+    let counts = summary.counts;
+    let block = Block::default()
+        .title(format!(
+            " Why did #{} fail?  ✗ {}  ⚠ {} ",
+            summary.build_number, counts.errors, counts.warnings
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(if counts.errors > 0 {
+            Color::Red
+        } else {
+            Color::Yellow
+        }));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Min(2), Constraint::Length(1)])
+        .split(inner);
+    f.render_widget(
+        Paragraph::new("j/k:nav  Enter:go to log line  Esc:close")
+            .style(Style::default().fg(Color::DarkGray)),
+        chunks[1],
+    );
+
+    let muted = Style::default().fg(Color::DarkGray);
+    if summary.loading || summary.issues.is_empty() {
+        let text = if summary.loading {
+            "Loading timeline..."
+        } else {
+            "No errors or warnings reported."
+        };
+        f.render_widget(
+            Paragraph::new(text)
+                .style(muted)
+                .alignment(Alignment::Center),
+            chunks[0],
+        );
+        return;
+    }
+
+    // Two lines per issue: task and location, then the message
+    let fits = (chunks[0].height as usize / 2).max(1);
+    let skip = (summary.selected + 1).saturating_sub(fits);
+    let width = chunks[0].width.saturating_sub(4) as usize;
+    let items: Vec<ListItem> = summary
+        .issues
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .take(fits)
+        .map(|(i, issue)| {
+            let (icon, color) = match issue.severity {
+                Severity::Error => ("✗", Color::Red),
+                Severity::Warning => ("⚠", Color::Yellow),
+            };
+            let selected = i == summary.selected;
+            let task_style = if selected {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let mut header = vec![
+                Span::raw(if selected { "▸ " } else { "  " }),
+                Span::styled(icon, Style::default().fg(color)),
+                Span::raw(" "),
+                Span::styled(issue.task.clone(), task_style),
+            ];
+            if let Some(location) = issue.location() {
+                header.push(Span::styled(format!("  {location}"), muted));
+            }
+            if let Some(line) = issue.log_line {
+                header.push(Span::styled(format!("  log line {line}"), muted));
+            }
+            let message: String = issue
+                .message
+                .lines()
+                .next()
+                .unwrap_or("")
+                .chars()
+                .take(width)
+                .collect();
+            ListItem::new(vec![
+                Line::from(header),
+                Line::from(Span::styled(
+                    format!("    {message}"),
+                    Style::default().fg(color),
+                )),
+            ])
+        })
+        .collect();
+    f.render_widget(List::new(items), chunks[0]);
+}
+
+/// Render the artifacts panel of a run, with the download in progress
+pub fn render_artifact_browser(
+    f: &mut Frame,
+    browser: &ArtifactBrowser,
+    download: Option<&ArtifactDownload>,
+) {
+    let area = centered_rect(70, 70, f.area());
+    f.render_widget(Clear, area);
+
+    let title = match browser.location() {
+        Some(folder) => format!(" Artifacts of #{} › {folder} ", browser.build_number),
+        None => format!(" Artifacts of #{} ", browser.build_number),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(2),    // Artifacts or files
+            Constraint::Length(1), // Destination prompt / download progress
+            Constraint::Length(1), // Keys
+        ])
+        .split(inner);
+    f.render_widget(
+        Paragraph::new("j/k:nav  Enter/l:open  h:up  d:download  Esc:close")
+            .style(Style::default().fg(Color::DarkGray)),
+        chunks[2],
+    );
+
+    if let Some(dir) = &browser.destination {
+        let name = browser
+            .selected_download()
+            .map(|d| d.file_name())
+            .unwrap_or_default();
+        f.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled(
+                    format!("Download {name} to: "),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw(dir.clone()),
+                Span::styled("█", Style::default().fg(Color::Yellow)),
+            ])),
+            chunks[1],
+        );
+    } else if let Some(download) = download {
+        match download.ratio() {
+            Some(ratio) => f.render_widget(
+                Gauge::default()
+                    .gauge_style(Style::default().fg(Color::Green).bg(Color::Black))
+                    .ratio(ratio)
+                    .label(download.label()),
+                chunks[1],
+            ),
+            None => f.render_widget(
+                Paragraph::new(format!("⇣ {}", download.label()))
+                    .style(Style::default().fg(Color::Green)),
+                chunks[1],
+            ),
+        }
+    }
+
+    let muted = Style::default().fg(Color::DarkGray);
+    // Name, then the size right-aligned
+    let width = chunks[0].width.saturating_sub(2) as usize;
+    let row = |selected: bool, name: String, detail: String, style: Style| {
+        let pad = width.saturating_sub(name.chars().count() + detail.chars().count() + 2);
+        let name_style = if selected {
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            style
+        };
+        ListItem::new(Line::from(vec![
+            Span::raw(if selected { "▸ " } else { "  " }),
+            Span::styled(name, name_style),
+            Span::raw(" ".repeat(pad)),
+            Span::styled(detail, muted),
+        ]))
+    };
+
+    let rows: Vec<ListItem> = match browser.location() {
+        _ if browser.loading => Vec::new(),
+        None => browser
+            .artifacts
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                let kind = if a.items.is_some() {
+                    "files"
+                } else {
+                    "download only"
+                };
+                let size = a.size().map(format_size).unwrap_or_else(|| "-".into());
+                row(
+                    i == browser.selected,
+                    a.artifact.name.clone(),
+                    format!("{kind}  {size:>9}"),
+                    Style::default().fg(Color::White),
+                )
+            })
+            .collect(),
+        Some(_) => browser
+            .entries()
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                let (name, style) = if e.item.is_folder() {
+                    (format!("{}/", e.name), Style::default().fg(Color::Blue))
+                } else {
+                    (e.name.to_string(), Style::default().fg(Color::White))
+                };
+                row(i == browser.selected, name, format_size(e.size), style)
+            })
+            .collect(),
+    };
+
+    if rows.is_empty() {
+        let text = if browser.loading {
+            "Loading artifacts..."
+        } else if browser.location().is_some() {
+            "Empty folder"
+        } else {
+            "This run published no artifacts."
+        };
+        f.render_widget(
+            Paragraph::new(text)
+                .style(muted)
+                .alignment(Alignment::Center),
+            chunks[0],
+        );
+        return;
+    }
+
+    let fits = (chunks[0].height as usize).max(1);
+    let skip = (browser.selected + 1).saturating_sub(fits);
+    let rows: Vec<ListItem> = rows.into_iter().skip(skip).take(fits).collect();
+    f.render_widget(List::new(rows), chunks[0]);
+}
+
 /// Render approval confirmation dialog
 pub fn render_approval_dialog(f: &mut Frame, approval_type: &str, stage_name: &str) {
     let area = centered_rect(50, 20, f.area());
@@ -157,11 +603,17 @@ pub fn render_confirm_action_dialog(f: &mut Frame, dialog: &crate::app::ConfirmA
         crate::app::ConfirmActionType::CancelPipelineRun { .. }
         | crate::app::ConfirmActionType::CancelRelease { .. }
         | crate::app::ConfirmActionType::CancelReleaseEnvironment { .. }
-        | crate::app::ConfirmActionType::RejectApproval { .. } => (Color::Red, Color::Red),
+        | crate::app::ConfirmActionType::RejectApproval { .. }
+        | crate::app::ConfirmActionType::DeleteComment { .. }
+        | crate::app::ConfirmActionType::RemoveLink { .. } => (Color::Red, Color::Red),
         crate::app::ConfirmActionType::RetriggerPipelineRun { .. }
         | crate::app::ConfirmActionType::RetriggerReleaseEnvironment { .. } => {
             (Color::Green, Color::Green)
         }
+        crate::app::ConfirmActionType::UpdatePullRequest { update, .. } => match update {
+            crate::app::PRUpdate::Abandon => (Color::Red, Color::Red),
+            _ => (Color::Green, Color::Green),
+        },
     };
 
     let block = Block::default()
@@ -207,6 +659,14 @@ pub fn render_confirm_action_dialog(f: &mut Frame, dialog: &crate::app::ConfirmA
         crate::app::ConfirmActionType::RejectApproval { .. } => "[y] Yes, Reject",
         crate::app::ConfirmActionType::RetriggerPipelineRun { .. }
         | crate::app::ConfirmActionType::RetriggerReleaseEnvironment { .. } => "[y] Yes, Retrigger",
+        crate::app::ConfirmActionType::UpdatePullRequest { update, .. } => match update {
+            crate::app::PRUpdate::Abandon => "[y] Yes, Abandon",
+            crate::app::PRUpdate::Reactivate => "[y] Yes, Reactivate",
+            crate::app::PRUpdate::MarkDraft => "[y] Yes, Mark Draft",
+            crate::app::PRUpdate::Publish => "[y] Yes, Publish",
+        },
+        crate::app::ConfirmActionType::DeleteComment { .. } => "[y] Yes, Delete",
+        crate::app::ConfirmActionType::RemoveLink { .. } => "[y] Yes, Remove",
     };
 
     let confirm = Paragraph::new(confirm_text)
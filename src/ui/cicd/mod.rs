@@ -29,6 +29,18 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
         dialogs::render_release_trigger_dialog(f, dialog);
     }
 
+    if let Some(ref dialog) = app.pipeline_trigger_dialog {
+        dialogs::render_pipeline_trigger_dialog(f, dialog);
+    }
+
+    if let Some(ref summary) = app.failure_summary {
+        dialogs::render_failure_summary(f, summary);
+    }
+
+    if let Some(ref browser) = app.artifact_browser {
+        dialogs::render_artifact_browser(f, browser, app.artifact_download.as_ref());
+    }
+
     if let Some((ref approval_type, ref stage_name)) = app.approval_dialog {
         dialogs::render_approval_dialog(f, approval_type, stage_name);
     }
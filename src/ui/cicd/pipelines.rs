@@ -299,7 +299,7 @@ fn draw_pipeline_runs(
         .get(selected_idx)
         .map(|run| match run.status.as_deref() {
             Some("inProgress") => " [C]ancel",
-            Some("completed") => " [T]rigger",
+            Some("completed") => " [T]rigger [E]rrors",
             _ => "",
         })
         .unwrap_or("");
@@ -365,14 +365,31 @@ fn draw_pipeline_runs(
                     Style::default().fg(Color::White)
                 };
 
-                ListItem::new(Line::from(vec![
+                let mut spans = vec![
                     Span::raw(prefix),
                     Span::styled(icon, Style::default().fg(icon_color)),
                     Span::raw(" "),
                     Span::styled(format!("#{build_num}"), style),
                     Span::raw("  "),
                     Span::styled(branch, Style::default().fg(Color::DarkGray)),
-                ]))
+                ];
+                // Errors and warnings from the run's timeline
+                let counts = app.run_issue_counts.get(&app.pipeline_runs[*orig_idx].id);
+                if let Some(counts) = counts {
+                    if counts.errors > 0 {
+                        spans.push(Span::styled(
+                            format!("  ✗{}", counts.errors),
+                            Style::default().fg(Color::Red),
+                        ));
+                    }
+                    if counts.warnings > 0 {
+                        spans.push(Span::styled(
+                            format!("  ⚠{}", counts.warnings),
+                            Style::default().fg(Color::Yellow),
+                        ));
+                    }
+                }
+                ListItem::new(Line::from(spans))
             })
             .collect();
 
@@ -1,38 +1,10 @@
-use crate::app::{App, CICDFocus, PipelineDrillDown, ReleaseDrillDown};
+use crate::app::{App, CICDFocus, InputMode, PipelineDrillDown, ReleaseDrillDown, RunPreviewTab};
+use crate::log_view::Severity;
 use ratatui::prelude::*;
 use ratatui::widgets::{
-    Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap,
+    Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Tabs, Wrap,
 };
 
-/// Strip ANSI escape sequences and control characters from a string
-fn strip_ansi_and_control(s: &str) -> String {
-    let mut result = String::with_capacity(s.len());
-    let mut chars = s.chars().peekable();
-
-    while let Some(c) = chars.next() {
-        if c == '\x1b' {
-            // Skip ANSI escape sequence: ESC [ ... letter
-            if chars.peek() == Some(&'[') {
-                chars.next(); // consume '['
-                              // Skip until we hit a letter (the terminator)
-                while let Some(&next) = chars.peek() {
-                    chars.next();
-                    if next.is_ascii_alphabetic() {
-                        break;
-                    }
-                }
-            }
-            // Also handle ESC followed by other sequences
-        } else if c == '\t' {
-            // Keep tabs, convert to spaces for consistent width
-            result.push_str("    ");
-        } else if !c.is_control() {
-            result.push(c);
-        }
-    }
-    result
-}
-
 pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
     let focused = app.cicd_focus == CICDFocus::Preview;
 
@@ -122,10 +94,43 @@ fn draw_default_preview(f: &mut Frame, app: &mut App, area: Rect, border_color:
 }
 
 fn draw_run_preview(f: &mut Frame, app: &mut App, area: Rect, border_color: Color) {
+    // Vertical split: tab bar (3) + content
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
+
+    let selected = match app.run_preview_tab {
+        RunPreviewTab::Details => 0,
+        RunPreviewTab::Tests => 1,
+    };
+    let tabs = Tabs::new(vec!["Details", "Tests"])
+        .select(selected)
+        .style(Style::default().fg(Color::DarkGray))
+        .highlight_style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .divider(" | ")
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border_color))
+                .title(" Run [Tab] "),
+        );
+    f.render_widget(tabs, chunks[0]);
+
+    match app.run_preview_tab {
+        RunPreviewTab::Details => draw_run_details(f, app, chunks[1], border_color),
+        RunPreviewTab::Tests => draw_run_tests(f, app, chunks[1], border_color),
+    }
+}
+
+fn draw_run_details(f: &mut Frame, app: &mut App, area: Rect, border_color: Color) {
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(border_color))
-        .title(" Run Details ");
+        .border_style(Style::default().fg(border_color));
 
     let inner = block.inner(area);
     f.render_widget(block, area);
@@ -168,6 +173,7 @@ fn draw_run_preview(f: &mut Frame, app: &mut App, area: Rect, border_color: Colo
              Started: {}\n\
              Finished: {}\n\n\
              Press [Enter] to view tasks\n\
+             Press [Tab] for test results\n\
              Press [o] to open in browser\n\
              Press [Esc] to go back",
             build_num,
@@ -190,6 +196,153 @@ fn draw_run_preview(f: &mut Frame, app: &mut App, area: Rect, border_color: Colo
     f.render_widget(paragraph, inner);
 }
 
+fn draw_run_tests(f: &mut Frame, app: &mut App, area: Rect, border_color: Color) {
+    let focused = app.cicd_focus == CICDFocus::Preview;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(border_color));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let muted = Style::default().fg(Color::DarkGray);
+    let message = |f: &mut Frame, text: String, style: Style| {
+        f.render_widget(
+            Paragraph::new(text).style(style).wrap(Wrap { trim: true }),
+            inner,
+        );
+    };
+    let Some(run) = app.pipeline_runs.get(app.selected_pipeline_run_idx) else {
+        message(f, "Select a run to view its tests".to_string(), muted);
+        return;
+    };
+    if run.status.as_deref() != Some("completed") {
+        message(
+            f,
+            "Test results are shown once the run finishes".to_string(),
+            muted,
+        );
+        return;
+    }
+    let tests = match app.build_tests.get(&run.id) {
+        None => {
+            message(f, "Loading test results...".to_string(), muted);
+            return;
+        }
+        Some(Err(e)) => {
+            message(
+                f,
+                format!("Failed to load test results: {e}"),
+                Style::default().fg(Color::Red),
+            );
+            return;
+        }
+        Some(Ok(tests)) if !tests.has_tests() => {
+            message(f, "This run published no test results".to_string(), muted);
+            return;
+        }
+        Some(Ok(tests)) => tests,
+    };
+
+    let totals = tests.totals;
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                format!("✓ {} passed", totals.passed),
+                Style::default().fg(Color::Green),
+            ),
+            Span::raw("   "),
+            Span::styled(
+                format!("✗ {} failed", totals.failed),
+                Style::default().fg(if totals.failed > 0 {
+                    Color::Red
+                } else {
+                    Color::DarkGray
+                }),
+            ),
+            Span::raw("   "),
+            Span::styled(
+                format!("○ {} skipped", totals.skipped),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(format!("   ({} tests)", totals.total()), muted),
+        ]),
+        Line::from(""),
+    ];
+    if tests.failed.is_empty() {
+        lines.push(Line::styled(
+            "No failed tests",
+            Style::default().fg(Color::Green),
+        ));
+    }
+
+    let earlier = app.earlier_build_tests(run.id);
+    for test in &tests.failed {
+        let history = crate::test_results::history(&test.key, &earlier);
+        let mut header = vec![Span::styled(
+            format!("✗ {}", test.name),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )];
+        if history.flaky {
+            header.push(Span::styled(
+                "  ⚡ flaky",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+        if history.runs > 0 {
+            header.push(Span::styled(
+                format!(
+                    "  failed {}/{} earlier runs",
+                    history.failures, history.runs
+                ),
+                muted,
+            ));
+        }
+        lines.push(Line::from(header));
+
+        let mut details: Vec<String> = test.storage.iter().cloned().collect();
+        details.push(test.run.clone());
+        if let Some(ms) = test.duration_ms {
+            details.push(format!("{ms:.0} ms"));
+        }
+        lines.push(Line::styled(format!("  {}", details.join(" · ")), muted));
+
+        for line in test.error_message.iter().flat_map(|m| m.lines()) {
+            lines.push(Line::styled(
+                format!("    {line}"),
+                Style::default().fg(Color::White),
+            ));
+        }
+        for line in test.stack_trace.iter().flat_map(|t| t.lines()) {
+            lines.push(Line::styled(format!("    {}", line.trim_end()), muted));
+        }
+        lines.push(Line::from(""));
+    }
+
+    let total_lines = lines.len();
+    let visible_height = inner.height as usize;
+
+    // Clamp scroll position and update app state
+    let max_scroll = total_lines.saturating_sub(visible_height);
+    if app.cicd_preview_scroll as usize > max_scroll {
+        app.cicd_preview_scroll = max_scroll as u16;
+    }
+    let scroll = app.cicd_preview_scroll as usize;
+
+    f.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), inner);
+
+    if total_lines > visible_height && focused {
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("▲"))
+            .end_symbol(Some("▼"));
+        let mut scrollbar_state = ScrollbarState::new(max_scroll.max(1))
+            .position(scroll)
+            .viewport_content_length(visible_height);
+        f.render_stateful_widget(scrollbar, inner, &mut scrollbar_state);
+    }
+}
+
 fn draw_release_preview(f: &mut Frame, app: &mut App, area: Rect, border_color: Color) {
     let focused = app.cicd_focus == CICDFocus::Preview;
     let block = Block::default()
@@ -318,99 +471,9 @@ fn draw_log_preview(f: &mut Frame, app: &mut App, area: Rect, border_color: Colo
     let task_name = tasks
         .get(app.selected_task_idx)
         .and_then(|t| t.name.as_deref())
-        .unwrap_or("Task");
-
-    let title = format!(" Log: {task_name} ");
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(border_color))
-        .title(title);
-
-    let inner = block.inner(area);
-    f.render_widget(block, area);
-
-    if app.build_log_lines.is_empty() {
-        let msg = if app.cicd_loading {
-            "Loading logs..."
-        } else {
-            "No logs available. Press [Enter] on a task to load logs."
-        };
-        let paragraph = Paragraph::new(msg).style(Style::default().fg(Color::DarkGray));
-        f.render_widget(paragraph, inner);
-    } else {
-        // Calculate visible area
-        let visible_height = inner.height as usize;
-        let total_lines = app.build_log_lines.len();
-
-        // Clamp scroll position
-        let max_scroll = total_lines.saturating_sub(visible_height);
-        if app.log_scroll > max_scroll {
-            app.log_scroll = max_scroll;
-        }
-
-        // Get visible lines
-        let start = app.log_scroll;
-        let end = (start + visible_height).min(total_lines);
-
-        // Calculate max width for truncation (inner width minus scrollbar space)
-        let max_width = inner.width.saturating_sub(2) as usize;
-
-        let visible_lines: Vec<Line> = app.build_log_lines[start..end]
-            .iter()
-            .map(|line| {
-                // Strip ANSI escape sequences and control characters
-                let clean_line = strip_ansi_and_control(line);
-
-                // Truncate to visible width to prevent overflow
-                let truncated: String = clean_line.chars().take(max_width).collect();
-
-                // Color code log lines based on content
-                let style = if truncated.contains("error")
-                    || truncated.contains("Error")
-                    || truncated.contains("ERROR")
-                {
-                    Style::default().fg(Color::Red)
-                } else if truncated.contains("warning")
-                    || truncated.contains("Warning")
-                    || truncated.contains("WARN")
-                {
-                    Style::default().fg(Color::Yellow)
-                } else if truncated.contains("##[section]") {
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD)
-                } else if truncated.starts_with("##[") {
-                    Style::default().fg(Color::Blue)
-                } else {
-                    Style::default().fg(Color::White)
-                };
-                Line::styled(truncated, style)
-            })
-            .collect();
-
-        let paragraph = Paragraph::new(visible_lines);
-        f.render_widget(paragraph, inner);
-
-        // Draw scrollbar if needed
-        if total_lines > visible_height {
-            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-                .begin_symbol(Some("↑"))
-                .end_symbol(Some("↓"));
-            // Use max_scroll as the scrollable range so position matches correctly
-            // At bottom: position == max_scroll, scrollbar at bottom
-            let mut scrollbar_state =
-                ScrollbarState::new(max_scroll.max(1)).position(app.log_scroll);
-
-            f.render_stateful_widget(
-                scrollbar,
-                area.inner(Margin {
-                    vertical: 1,
-                    horizontal: 0,
-                }),
-                &mut scrollbar_state,
-            );
-        }
-    }
+        .unwrap_or("Task")
+        .to_string();
+    draw_log(f, app, area, border_color, &task_name, false);
 }
 
 fn draw_stage_preview(f: &mut Frame, app: &mut App, area: Rect, border_color: Color) {
@@ -515,9 +578,28 @@ fn draw_release_task_log(f: &mut Frame, app: &mut App, area: Rect, border_color:
         .release_tasks
         .get(app.selected_release_task_idx)
         .and_then(|t| t.name.as_deref())
-        .unwrap_or("Task");
+        .unwrap_or("Task")
+        .to_string();
+    draw_log(f, app, area, border_color, &task_name, true);
+}
 
-    let title = format!(" Log: {task_name} ");
+/// A build or release task log, with the search prompt or its status at the bottom
+fn draw_log(
+    f: &mut Frame,
+    app: &mut App,
+    area: Rect,
+    border_color: Color,
+    task_name: &str,
+    release: bool,
+) {
+    let view = &app.log_view;
+    let mut title = format!(" Log: {task_name} ");
+    if view.filter {
+        title.push_str("[filtered] ");
+    }
+    if view.hide_timestamps {
+        title.push_str("[no timestamps] ");
+    }
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(border_color))
@@ -526,7 +608,12 @@ fn draw_release_task_log(f: &mut Frame, app: &mut App, area: Rect, border_color:
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    if app.release_task_logs.is_empty() {
+    let lines = if release {
+        &app.release_task_logs
+    } else {
+        &app.build_log_lines
+    };
+    if lines.is_empty() {
         let msg = if app.cicd_loading {
             "Loading logs..."
         } else {
@@ -534,80 +621,156 @@ fn draw_release_task_log(f: &mut Frame, app: &mut App, area: Rect, border_color:
         };
         let paragraph = Paragraph::new(msg).style(Style::default().fg(Color::DarkGray));
         f.render_widget(paragraph, inner);
-    } else {
-        // Same approach as pipeline logs - no wrapping, manual line slicing
-        let visible_height = inner.height as usize;
-        let total_lines = app.release_task_logs.len();
-
-        // Clamp scroll position
-        let max_scroll = total_lines.saturating_sub(visible_height);
-        if app.log_scroll > max_scroll {
-            app.log_scroll = max_scroll;
-        }
+        return;
+    }
 
-        // Get visible lines
-        let start = app.log_scroll;
-        let end = (start + visible_height).min(total_lines);
+    // Search prompt, or the pattern and its matches
+    let searching = app.input_mode == InputMode::LogSearch;
+    let mut log_area = inner;
+    if searching || !view.query.is_empty() {
+        log_area.height = inner.height.saturating_sub(1);
+        let status_area = Rect {
+            y: inner.y + log_area.height,
+            height: 1,
+            ..inner
+        };
+        f.render_widget(search_status(view, lines, searching), status_area);
+    }
 
-        // Calculate max width for truncation (inner width minus scrollbar space)
-        let max_width = inner.width.saturating_sub(2) as usize;
+    // No wrapping, manual line slicing
+    let rows = view.rows(lines);
+    let visible_height = log_area.height as usize;
+    let total_lines = rows.len();
 
-        let visible_lines: Vec<Line> = app.release_task_logs[start..end]
-            .iter()
-            .map(|line| {
-                // Strip ANSI escape sequences and control characters
-                let clean_line = strip_ansi_and_control(line);
-
-                // Truncate to visible width to prevent overflow
-                let truncated: String = clean_line.chars().take(max_width).collect();
-
-                // Color code log lines based on content
-                let style = if truncated.contains("error")
-                    || truncated.contains("Error")
-                    || truncated.contains("ERROR")
-                {
-                    Style::default().fg(Color::Red)
-                } else if truncated.contains("warning")
-                    || truncated.contains("Warning")
-                    || truncated.contains("WARN")
-                {
-                    Style::default().fg(Color::Yellow)
-                } else if truncated.contains("##[section]") {
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD)
-                } else if truncated.starts_with("##[") {
-                    Style::default().fg(Color::Blue)
-                } else {
-                    Style::default().fg(Color::White)
-                };
-                Line::styled(truncated, style)
-            })
-            .collect();
+    // Clamp scroll position
+    let max_scroll = total_lines.saturating_sub(visible_height);
+    if app.log_scroll > max_scroll {
+        app.log_scroll = max_scroll;
+    }
 
-        let paragraph = Paragraph::new(visible_lines);
-        f.render_widget(paragraph, inner);
+    // Get visible lines
+    let start = app.log_scroll;
+    let end = (start + visible_height).min(total_lines);
+
+    // Calculate max width for truncation (inner width minus scrollbar space)
+    let max_width = log_area.width.saturating_sub(2) as usize;
+
+    let visible_lines: Vec<Line> = rows[start..end]
+        .iter()
+        .map(|&i| log_line(view, &lines[i], max_width, view.current == Some(i)))
+        .collect();
+
+    let paragraph = Paragraph::new(visible_lines);
+    f.render_widget(paragraph, log_area);
+
+    // Draw scrollbar if needed
+    if total_lines > visible_height {
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("↑"))
+            .end_symbol(Some("↓"));
+        // Use max_scroll as the scrollable range so position matches correctly
+        // At bottom: position == max_scroll, scrollbar at bottom
+        let mut scrollbar_state = ScrollbarState::new(max_scroll.max(1)).position(app.log_scroll);
+
+        f.render_stateful_widget(
+            scrollbar,
+            Rect {
+                height: log_area.height + 2,
+                ..area
+            }
+            .inner(Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut scrollbar_state,
+        );
+    }
+}
 
-        // Draw scrollbar if needed
-        if total_lines > visible_height {
-            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-                .begin_symbol(Some("↑"))
-                .end_symbol(Some("↓"));
-            // Use max_scroll as the scrollable range so position matches correctly
-            // At bottom: position == max_scroll, scrollbar at bottom
-            let mut scrollbar_state =
-                ScrollbarState::new(max_scroll.max(1)).position(app.log_scroll);
-
-            f.render_stateful_widget(
-                scrollbar,
-                area.inner(Margin {
-                    vertical: 1,
-                    horizontal: 0,
-                }),
-                &mut scrollbar_state,
-            );
+/// One log line, colored by what it reports, with the search matches highlighted
+fn log_line(
+    view: &crate::log_view::LogView,
+    line: &str,
+    max_width: usize,
+    current: bool,
+) -> Line<'static> {
+    let text = view.display(line);
+    // Truncate to visible width to prevent overflow
+    let truncated: String = text.chars().take(max_width).collect();
+
+    // Color code log lines based on content
+    let style = match Severity::of(&truncated) {
+        Some(Severity::Error) => Style::default().fg(Color::Red),
+        Some(Severity::Warning) => Style::default().fg(Color::Yellow),
+        None if truncated.contains("error")
+            || truncated.contains("Error")
+            || truncated.contains("ERROR") =>
+        {
+            Style::default().fg(Color::Red)
         }
+        None if truncated.contains("warning")
+            || truncated.contains("Warning")
+            || truncated.contains("WARN") =>
+        {
+            Style::default().fg(Color::Yellow)
+        }
+        None if truncated.contains("##[section]") => Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+        None if crate::log_view::strip_timestamp(&truncated).starts_with("##[") => {
+            Style::default().fg(Color::Blue)
+        }
+        None => Style::default().fg(Color::White),
+    };
+
+    let highlight = if current {
+        Style::default().fg(Color::Black).bg(Color::LightMagenta)
+    } else {
+        Style::default().fg(Color::Black).bg(Color::Yellow)
+    };
+    let mut spans = Vec::new();
+    let mut at = 0;
+    for (from, to) in view.match_ranges(&truncated) {
+        spans.push(Span::styled(truncated[at..from].to_string(), style));
+        spans.push(Span::styled(truncated[from..to].to_string(), highlight));
+        at = to;
+    }
+    spans.push(Span::styled(truncated[at..].to_string(), style));
+    Line::from(spans)
+}
+
+/// `/pattern▏` while typing, then the pattern with the match count
+fn search_status(
+    view: &crate::log_view::LogView,
+    lines: &[String],
+    searching: bool,
+) -> Paragraph<'static> {
+    let muted = Style::default().fg(Color::DarkGray);
+    let mut spans = vec![Span::styled(
+        format!("/{}", view.query),
+        Style::default().fg(Color::Cyan),
+    )];
+    if searching {
+        spans.push(Span::styled("▏", Style::default().fg(Color::Cyan)));
+    }
+    if let Some(error) = &view.error {
+        spans.push(Span::styled(
+            format!("  {error}"),
+            Style::default().fg(Color::Red),
+        ));
+    } else if view.has_pattern() {
+        let matches = view.matches(lines);
+        let text = match view
+            .current
+            .and_then(|c| matches.iter().position(|&m| m == c))
+        {
+            Some(pos) => format!("  {}/{} lines", pos + 1, matches.len()),
+            None if matches.is_empty() => "  no matches".to_string(),
+            None => format!("  {} lines", matches.len()),
+        };
+        spans.push(Span::styled(text, muted));
     }
+    Paragraph::new(Line::from(spans))
 }
 
 /// Format ISO timestamp to a more readable format
@@ -1,127 +1,410 @@
 use crate::app::{App, View};
+use crate::keymap::{Action, Keymap};
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 
-pub fn draw_popup(f: &mut Frame, app: &App, area: Rect) {
-    let (title, help_text) = match app.current_view {
-        View::Tasks => (
-            " Tasks Help - Press ? or Esc to close ",
-            r#"
-NAVIGATION
-  j/k ↑/↓       Move up/down
-  h/l           Focus left/right panel
-  g/G           Go to top/bottom
-  Ctrl+d/u      Page down/up
-  Tab           Switch preview tabs
-  Enter         Expand/collapse item
-  t             Toggle expand all
-
-FILTERS
-  f             Search by text
-  s             Filter by state
-  a             Filter by assignee
-  c             Clear all filters
-
-ACTIONS
-  o             Open in browser
-  S             Edit state
-  A             Edit assignee
-  p             Pin/unpin item
-  y             Copy ticket ID
-
-VIEWS
-  1             Tasks view
-  2             PRs view
-  3             CI/CD view
-  I             Select sprint
-  P             Select project
-  r             Refresh data
-  ?             Toggle help
-  q             Quit
-"#,
-        ),
-        View::PRs => (
-            " PRs Help - Press ? or Esc to close ",
-            r#"
-NAVIGATION
-  j/k ↑/↓       Move up/down
-  h/l           Switch pane (Active/Mine/Completed)
-  Enter         Drill into PRs / view details
-  Esc           Go back / Exit drill-down
-  Tab           Switch preview tabs
-
-REPOSITORIES
-  Enter         View PRs for repository
-  f             Search repositories
-
-PULL REQUESTS
-  h/l           Switch pane (Active/Mine/Completed)
-  Enter         View PR details
-  f             Search PRs
-  o             Open in browser
+/// Key column of a help row: actions from the keymap (joined as `j/k`), or a
+/// fixed key that isn't part of normal-mode dispatch
+enum Keys {
+    Actions(&'static [Action]),
+    Fixed(&'static str),
+}
 
-PREVIEW TABS
-  Tab           Next tab (Details/Policies/Threads)
-  Shift+Tab     Previous tab
-  h             Back to list
-  j/k           Scroll content
+type Section = (&'static str, &'static [(Keys, &'static str)]);
 
-VIEWS
-  1             Tasks view
-  2             PRs view
-  3             CI/CD view
-  P             Select project
-  r             Refresh data
-  ?             Toggle help
-  q             Quit
-"#,
-        ),
-        View::CICD => (
-            " CI/CD Help - Press ? or Esc to close ",
-            r#"
-NAVIGATION
-  j/k ↑/↓       Move up/down
-  h             Focus Pipelines pane
-  l             Focus Releases pane
-  Enter         Drill into runs/releases
-  Esc           Go back / Exit drill-down
+const TASKS_HELP: &[Section] = &[
+    (
+        "NAVIGATION",
+        &[
+            (Keys::Actions(&[Action::Down, Action::Up]), "Move down/up"),
+            (
+                Keys::Actions(&[Action::Left, Action::Right]),
+                "Focus left/right panel",
+            ),
+            (
+                Keys::Actions(&[Action::Top, Action::Bottom]),
+                "Go to top/bottom",
+            ),
+            (
+                Keys::Actions(&[Action::PageDown, Action::PageUp]),
+                "Page down/up",
+            ),
+            (Keys::Actions(&[Action::NextTab]), "Switch preview tabs"),
+            (Keys::Actions(&[Action::Expand]), "Expand/collapse item"),
+            (Keys::Actions(&[Action::ToggleAll]), "Toggle expand all"),
+        ],
+    ),
+    (
+        "FILTERS",
+        &[
+            (Keys::Actions(&[Action::Search]), "Search by text"),
+            (Keys::Actions(&[Action::FilterState]), "Filter by state"),
+            (
+                Keys::Actions(&[Action::FilterAssignee]),
+                "Filter by assignee",
+            ),
+            (Keys::Actions(&[Action::ClearFilters]), "Clear all filters"),
+        ],
+    ),
+    (
+        "ACTIONS",
+        &[
+            (Keys::Actions(&[Action::Open]), "Open in browser"),
+            (Keys::Actions(&[Action::EditState]), "Edit state"),
+            (Keys::Actions(&[Action::EditAssignee]), "Edit assignee"),
+            (Keys::Actions(&[Action::EditFields]), "Edit fields"),
+            (
+                Keys::Actions(&[Action::CreateWorkItem]),
+                "New work item / child tasks",
+            ),
+            (Keys::Actions(&[Action::EditTags]), "Add/remove tags"),
+            (Keys::Actions(&[Action::MoveIteration]), "Move to sprint"),
+            (Keys::Actions(&[Action::AddComment]), "Comment on item"),
+            (
+                Keys::Actions(&[Action::DeleteComment]),
+                "Delete own comment (Discussion)",
+            ),
+            (
+                Keys::Actions(&[Action::AddLink]),
+                "Set parent / link item, PR, commit",
+            ),
+            (
+                Keys::Actions(&[Action::RemoveLink]),
+                "Remove selected link (References)",
+            ),
+            (Keys::Actions(&[Action::Pin]), "Pin/unpin item"),
+            (Keys::Actions(&[Action::CopyId]), "Copy ticket ID"),
+            (Keys::Actions(&[Action::CopyLink]), "Copy ticket link"),
+            (Keys::Actions(&[Action::CreatePR]), "Create PR for item"),
+        ],
+    ),
+    (
+        "MULTI-SELECT",
+        &[
+            (Keys::Actions(&[Action::Mark]), "Mark/unmark item"),
+            (Keys::Actions(&[Action::VisualSelect]), "Start/end range"),
+            (
+                Keys::Actions(&[Action::MarkChildren]),
+                "Mark item and children",
+            ),
+            (
+                Keys::Actions(&[Action::MarkAll]),
+                "Mark all (filtered) items",
+            ),
+            (Keys::Actions(&[Action::Back]), "Clear marks"),
+        ],
+    ),
+    (
+        "VIEWS",
+        &[
+            (Keys::Actions(&[Action::ViewTasks]), "Tasks view"),
+            (Keys::Actions(&[Action::ViewPRs]), "PRs view"),
+            (Keys::Actions(&[Action::ViewCICD]), "CI/CD view"),
+            (Keys::Actions(&[Action::ViewBoard]), "Board view"),
+            (Keys::Actions(&[Action::SelectSprint]), "Select sprint"),
+            (Keys::Actions(&[Action::Queries]), "WIQL queries"),
+            (Keys::Actions(&[Action::SprintStats]), "Sprint burndown"),
+            (Keys::Actions(&[Action::TeamCapacity]), "Team capacity"),
+            (Keys::Actions(&[Action::SelectProject]), "Select project"),
+            (Keys::Actions(&[Action::Refresh]), "Refresh data"),
+            (Keys::Actions(&[Action::Help]), "Toggle help"),
+            (Keys::Actions(&[Action::Quit]), "Quit"),
+        ],
+    ),
+];
 
-PIPELINES
-  Enter         View pipeline runs
-  p             Pin/unpin pipeline
-  o             Open in browser
-  w             Toggle live preview (auto-refresh)
+const PRS_HELP: &[Section] = &[
+    (
+        "NAVIGATION",
+        &[
+            (Keys::Actions(&[Action::Down, Action::Up]), "Move down/up"),
+            (
+                Keys::Actions(&[Action::Left, Action::Right]),
+                "Switch pane (Active/Mine/Completed)",
+            ),
+            (
+                Keys::Actions(&[Action::Expand]),
+                "Drill into PRs / view details",
+            ),
+            (Keys::Actions(&[Action::Back]), "Go back / Exit drill-down"),
+            (Keys::Actions(&[Action::ChangeRepo]), "Change repository"),
+        ],
+    ),
+    (
+        "PULL REQUESTS",
+        &[
+            (Keys::Actions(&[Action::Search]), "Search repositories/PRs"),
+            (Keys::Actions(&[Action::Open]), "Open in browser"),
+            (Keys::Actions(&[Action::CopyId]), "Copy PR ID"),
+            (Keys::Actions(&[Action::CopyLink]), "Copy PR link"),
+        ],
+    ),
+    (
+        "ACTIONS",
+        &[
+            (Keys::Actions(&[Action::CreatePR]), "New pull request"),
+            (Keys::Actions(&[Action::Files]), "Changed files / diff"),
+            (
+                Keys::Actions(&[Action::SelectLines]),
+                "Select lines to comment on (diff)",
+            ),
+            (Keys::Actions(&[Action::Vote]), "Vote on PR"),
+            (Keys::Actions(&[Action::Comment]), "Add comment"),
+            (Keys::Actions(&[Action::Reply]), "Reply to selected thread"),
+            (
+                Keys::Actions(&[Action::ResolveThread]),
+                "Resolve / reactivate thread",
+            ),
+            (
+                Keys::Actions(&[Action::ToggleDraft]),
+                "Mark draft / publish",
+            ),
+            (Keys::Actions(&[Action::Abandon]), "Abandon / reactivate PR"),
+            (
+                Keys::Actions(&[Action::CompletePR]),
+                "Complete / set auto-complete",
+            ),
+        ],
+    ),
+    (
+        "PREVIEW TABS",
+        &[
+            (
+                Keys::Actions(&[Action::NextTab]),
+                "Next tab (Details/Policies/Threads)",
+            ),
+            (Keys::Actions(&[Action::PrevTab]), "Previous tab"),
+            (Keys::Actions(&[Action::Left]), "Back to list"),
+            (
+                Keys::Actions(&[Action::Down, Action::Up]),
+                "Scroll content / select thread",
+            ),
+        ],
+    ),
+    (
+        "VIEWS",
+        &[
+            (Keys::Actions(&[Action::ViewTasks]), "Tasks view"),
+            (Keys::Actions(&[Action::ViewPRs]), "PRs view"),
+            (Keys::Actions(&[Action::ViewCICD]), "CI/CD view"),
+            (Keys::Actions(&[Action::ViewBoard]), "Board view"),
+            (Keys::Actions(&[Action::SelectProject]), "Select project"),
+            (Keys::Actions(&[Action::Refresh]), "Refresh data"),
+            (Keys::Actions(&[Action::Help]), "Toggle help"),
+            (Keys::Actions(&[Action::Quit]), "Quit"),
+        ],
+    ),
+];
 
-RELEASES
-  Enter         View releases
-  p             Pin/unpin release
-  o             Open in browser
+const CICD_HELP: &[Section] = &[
+    (
+        "NAVIGATION",
+        &[
+            (Keys::Actions(&[Action::Down, Action::Up]), "Move down/up"),
+            (Keys::Actions(&[Action::Left]), "Focus Pipelines pane"),
+            (Keys::Actions(&[Action::Right]), "Focus Releases pane"),
+            (Keys::Actions(&[Action::Expand]), "Drill into runs/releases"),
+            (Keys::Actions(&[Action::Back]), "Go back / Exit drill-down"),
+            (
+                Keys::Actions(&[Action::PageDown, Action::PageUp]),
+                "Page down/up",
+            ),
+        ],
+    ),
+    (
+        "PIPELINES & RELEASES",
+        &[
+            (Keys::Actions(&[Action::Search]), "Search"),
+            (
+                Keys::Actions(&[Action::Pin]),
+                "Pin/unpin pipeline or release",
+            ),
+            (Keys::Actions(&[Action::Open]), "Open in browser"),
+            (
+                Keys::Actions(&[Action::LivePreview]),
+                "Toggle live preview (auto-refresh)",
+            ),
+            (Keys::Actions(&[Action::LoadAllRuns]), "Load all runs"),
+            (
+                Keys::Actions(&[Action::FailureSummary]),
+                "Why did it fail? (errors/warnings)",
+            ),
+            (
+                Keys::Actions(&[Action::NextTab]),
+                "Run details/tests (failed, flaky)",
+            ),
+            (
+                Keys::Actions(&[Action::Artifacts]),
+                "Browse and download run artifacts",
+            ),
+        ],
+    ),
+    (
+        "LOG VIEWER",
+        &[
+            (Keys::Actions(&[Action::LogSearch]), "Search log (regex)"),
+            (
+                Keys::Actions(&[Action::NextMatch, Action::PrevMatch]),
+                "Next/previous match",
+            ),
+            (
+                Keys::Actions(&[Action::NextError, Action::PrevError]),
+                "Next/previous ##[error]",
+            ),
+            (
+                Keys::Actions(&[Action::NextWarning, Action::PrevWarning]),
+                "Next/previous ##[warning]",
+            ),
+            (
+                Keys::Actions(&[Action::LogFilter]),
+                "Only show matching lines",
+            ),
+            (
+                Keys::Actions(&[Action::ToggleTimestamps]),
+                "Show/hide timestamps",
+            ),
+            (Keys::Actions(&[Action::OpenLog]), "Edit/open log in nvim"),
+            (Keys::Fixed("Ctrl+q"), "Exit nvim viewer"),
+        ],
+    ),
+    (
+        "ACTIONS",
+        &[
+            (Keys::Actions(&[Action::Trigger]), "Trigger / retrigger"),
+            (
+                Keys::Actions(&[Action::TriggerPreset]),
+                "Run pipeline with last settings",
+            ),
+            (Keys::Actions(&[Action::Cancel]), "Cancel run / release"),
+            (Keys::Actions(&[Action::Approve]), "Approve selected stage"),
+            (
+                Keys::Actions(&[Action::ApproveAll]),
+                "Approve ALL pending stages",
+            ),
+        ],
+    ),
+    (
+        "VIEWS",
+        &[
+            (Keys::Actions(&[Action::ViewTasks]), "Tasks view"),
+            (Keys::Actions(&[Action::ViewPRs]), "PRs view"),
+            (Keys::Actions(&[Action::ViewCICD]), "CI/CD view"),
+            (Keys::Actions(&[Action::ViewBoard]), "Board view"),
+            (Keys::Actions(&[Action::SelectProject]), "Select project"),
+            (Keys::Actions(&[Action::Refresh]), "Refresh data"),
+            (Keys::Actions(&[Action::Help]), "Toggle help"),
+            (Keys::Actions(&[Action::Quit]), "Quit"),
+        ],
+    ),
+];
 
-LOG VIEWER
-  e             Edit/open log in nvim
-  Ctrl+q        Exit nvim viewer
+const BOARD_HELP: &[Section] = &[
+    (
+        "NAVIGATION",
+        &[
+            (Keys::Actions(&[Action::Down, Action::Up]), "Move down/up"),
+            (
+                Keys::Actions(&[Action::Left, Action::Right]),
+                "Previous/next column",
+            ),
+            (
+                Keys::Actions(&[Action::Top, Action::Bottom]),
+                "Top/bottom of column",
+            ),
+            (Keys::Actions(&[Action::Expand]), "Collapse swimlane"),
+            (
+                Keys::Actions(&[Action::ToggleAll]),
+                "Collapse/expand all swimlanes",
+            ),
+        ],
+    ),
+    (
+        "CARDS",
+        &[
+            (
+                Keys::Actions(&[Action::MoveCardLeft, Action::MoveCardRight]),
+                "Move card left/right",
+            ),
+            (Keys::Actions(&[Action::Open]), "Open in browser"),
+            (Keys::Actions(&[Action::EditState]), "Edit state"),
+            (Keys::Actions(&[Action::EditAssignee]), "Edit assignee"),
+            (Keys::Actions(&[Action::CopyId]), "Copy ticket ID"),
+            (Keys::Actions(&[Action::CopyLink]), "Copy ticket link"),
+        ],
+    ),
+    (
+        "FILTERS",
+        &[
+            (Keys::Actions(&[Action::Search]), "Search by text"),
+            (Keys::Actions(&[Action::FilterState]), "Filter by state"),
+            (
+                Keys::Actions(&[Action::FilterAssignee]),
+                "Filter by assignee",
+            ),
+            (Keys::Actions(&[Action::ClearFilters]), "Clear all filters"),
+        ],
+    ),
+    (
+        "VIEWS",
+        &[
+            (Keys::Actions(&[Action::ViewTasks]), "Tasks view"),
+            (Keys::Actions(&[Action::ViewPRs]), "PRs view"),
+            (Keys::Actions(&[Action::ViewCICD]), "CI/CD view"),
+            (Keys::Actions(&[Action::ViewBoard]), "Board view"),
+            (Keys::Actions(&[Action::SelectSprint]), "Select sprint"),
+            (Keys::Actions(&[Action::Queries]), "WIQL queries"),
+            (Keys::Actions(&[Action::SprintStats]), "Sprint burndown"),
+            (Keys::Actions(&[Action::TeamCapacity]), "Team capacity"),
+            (Keys::Actions(&[Action::SelectProject]), "Select project"),
+            (Keys::Actions(&[Action::Refresh]), "Refresh data"),
+            (Keys::Actions(&[Action::Help]), "Toggle help"),
+            (Keys::Actions(&[Action::Quit]), "Quit"),
+        ],
+    ),
+];
 
-ACTIONS
-  T             Trigger new release
-  a             Approve selected stage
-  A             Approve ALL pending stages
-  x             Reject stage (TODO)
+/// Render the help sections for a view using the active bindings
+fn help_text(keymap: &Keymap, view: View, sections: &[Section]) -> String {
+    let mut text = String::from("\n");
+    for (i, (title, rows)) in sections.iter().enumerate() {
+        if i > 0 {
+            text.push('\n');
+        }
+        text.push_str(title);
+        text.push('\n');
+        for (keys, description) in rows.iter() {
+            let keys = match keys {
+                Keys::Actions(actions) => actions
+                    .iter()
+                    .map(|a| keymap.keys_for(view, *a).unwrap_or_else(|| "-".into()))
+                    .collect::<Vec<_>>()
+                    .join("/"),
+                Keys::Fixed(key) => key.to_string(),
+            };
+            text.push_str(&format!("  {keys:<14}{description}\n"));
+        }
+    }
+    text
+}
 
-VIEWS
-  1             Tasks view
-  2             PRs view
-  3             CI/CD view
-  P             Select project
-  r             Refresh data
-  ?             Toggle help
-  q             Quit
-"#,
-        ),
+pub fn draw_popup(f: &mut Frame, app: &App, area: Rect) {
+    let (name, sections) = match app.current_view {
+        View::Tasks => ("Tasks", TASKS_HELP),
+        View::PRs => ("PRs", PRS_HELP),
+        View::CICD => ("CI/CD", CICD_HELP),
+        View::Board => ("Board", BOARD_HELP),
     };
+    let help_key = app
+        .keymap
+        .keys_for(app.current_view, Action::Help)
+        .unwrap_or_else(|| "?".into());
+    let title = format!(" {name} Help - Press {help_key} or Esc to close ");
+    let help_text = help_text(&app.keymap, app.current_view, sections);
 
     let block = Block::default().borders(Borders::ALL).title(title);
 
-    let inner = super::centered_rect(50, 30, area);
+    let height = help_text.lines().count() as u16 + 2;
+    let inner = super::centered_rect(50, height, area);
     f.render_widget(Clear, inner);
 
     let paragraph = Paragraph::new(help_text)
@@ -129,3 +412,26 @@ VIEWS
         .wrap(Wrap { trim: false });
     f.render_widget(paragraph, inner);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Keybindings;
+
+    #[test]
+    fn test_help_text_follows_keymap() {
+        let keys = Keybindings {
+            down: "n".to_string(),
+            up: "e".to_string(),
+            top: "gg".to_string(),
+            ..Default::default()
+        };
+        let keymap = Keymap::from_config(&keys);
+        let text = help_text(&keymap, View::Tasks, TASKS_HELP);
+
+        assert!(text.contains("  n/e           Move down/up"));
+        assert!(text.contains("  gg/G          Go to top/bottom"));
+        assert!(text.contains("  Ctrl+d/Ctrl+u Page down/up"));
+        assert!(!text.contains("j/k"));
+    }
+}
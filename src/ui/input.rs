@@ -26,6 +26,33 @@ pub fn draw_sprint_dropdown(f: &mut Frame, app: &mut App, area: Rect) {
     );
 }
 
+/// Sprint picker for moving the selected / marked work items
+pub fn draw_move_iteration_dropdown(f: &mut Frame, app: &mut App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .sprints
+        .iter()
+        .enumerate()
+        .map(|(idx, s)| {
+            let marker = if idx == app.selected_sprint_idx {
+                "● "
+            } else {
+                "  "
+            };
+            ListItem::new(format!("{}{}", marker, s.name))
+        })
+        .collect();
+
+    let title = app.edit_title("Move to Sprint");
+    draw_dropdown(
+        f,
+        app,
+        area,
+        &title,
+        items,
+        &mut app.dropdown_list_state.clone(),
+    );
+}
+
 pub fn draw_project_dropdown(f: &mut Frame, app: &mut App, area: Rect) {
     let items: Vec<ListItem> = app
         .config
@@ -48,9 +75,10 @@ pub fn draw_state_dropdown(f: &mut Frame, app: &mut App, area: Rect) {
     let states = app.filtered_edit_states();
     let filter_input = app.filter_input.clone();
 
-    let items: Vec<ListItem> = states.iter().map(|s| ListItem::new(*s)).collect();
+    let items: Vec<ListItem> = states.iter().map(|s| ListItem::new(s.as_str())).collect();
 
-    draw_searchable_dropdown(f, app, area, " Change State ", items, &filter_input);
+    let title = app.edit_title("Change State");
+    draw_searchable_dropdown(f, app, area, &title, items, &filter_input);
 }
 
 pub fn draw_assignee_dropdown(f: &mut Frame, app: &mut App, area: Rect) {
@@ -61,7 +89,130 @@ pub fn draw_assignee_dropdown(f: &mut Frame, app: &mut App, area: Rect) {
         .map(|u| ListItem::new(u.display_name.clone()))
         .collect();
 
-    draw_searchable_dropdown(f, app, area, " Change Assignee ", items, &filter_input);
+    let title = app.edit_title("Change Assignee");
+    draw_searchable_dropdown(f, app, area, &title, items, &filter_input);
+}
+
+pub fn draw_vote_dropdown(f: &mut Frame, app: &mut App, area: Rect) {
+    let items: Vec<ListItem> = crate::app::PR_VOTES
+        .iter()
+        .map(|(_, label)| ListItem::new(*label))
+        .collect();
+
+    draw_dropdown(
+        f,
+        app,
+        area,
+        " Vote ",
+        items,
+        &mut app.dropdown_list_state.clone(),
+    );
+}
+
+pub fn draw_comment_input(f: &mut Frame, app: &App, area: Rect) {
+    let Some(draft) = &app.pr_comment_draft else {
+        return;
+    };
+    let title = match &draft.target {
+        crate::app::CommentTarget::NewThread => format!(" Comment on PR #{} ", draft.pr_id),
+        crate::app::CommentTarget::Reply { .. } => format!(" Reply on PR #{} ", draft.pr_id),
+        crate::app::CommentTarget::Lines(anchor) => format!(
+            " Comment on {} {} ",
+            anchor.thread_context.file_path,
+            anchor.thread_context.line_label().unwrap_or_default()
+        ),
+    };
+
+    let inner = super::centered_rect(70, 12, area);
+    f.render_widget(Clear, inner);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_bottom(" Ctrl+S:post  Enter:newline  Esc:cancel ");
+
+    let paragraph = Paragraph::new(draft.text.as_str())
+        .block(block)
+        .wrap(ratatui::widgets::Wrap { trim: false });
+    f.render_widget(paragraph, inner);
+
+    // Cursor at end of last line (ignores soft wrapping)
+    let lines = draft.text.split('\n').count().max(1) as u16;
+    let last_len = draft.text.rsplit('\n').next().unwrap_or("").chars().count() as u16;
+    f.set_cursor_position(Position::new(
+        (inner.x + 1 + last_len).min(inner.right().saturating_sub(2)),
+        (inner.y + lines).min(inner.bottom().saturating_sub(2)),
+    ));
+}
+
+pub fn draw_work_item_comment_input(f: &mut Frame, app: &App, area: Rect) {
+    let Some(draft) = &app.comment_draft else {
+        return;
+    };
+    let title = match draft.comment_id {
+        Some(_) => format!(" Edit comment on #{} ", draft.work_item_id),
+        None => format!(" Comment on #{} ", draft.work_item_id),
+    };
+
+    let inner = super::centered_rect(70, 12, area);
+    f.render_widget(Clear, inner);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_bottom(" Ctrl+S:post  Enter:newline  @:mention  Esc:cancel ");
+
+    let paragraph = Paragraph::new(draft.text.as_str())
+        .block(block)
+        .wrap(ratatui::widgets::Wrap { trim: false });
+    f.render_widget(paragraph, inner);
+
+    // Cursor at end of last line (ignores soft wrapping)
+    let lines = draft.text.split('\n').count().max(1) as u16;
+    let last_len = draft.text.rsplit('\n').next().unwrap_or("").chars().count() as u16;
+    let cursor = Position::new(
+        (inner.x + 1 + last_len).min(inner.right().saturating_sub(2)),
+        (inner.y + lines).min(inner.bottom().saturating_sub(2)),
+    );
+    f.set_cursor_position(cursor);
+
+    // Mention suggestions below the cursor line
+    let suggestions = draft.suggestions(&app.users);
+    if suggestions.is_empty() {
+        return;
+    }
+    let items: Vec<ListItem> = suggestions
+        .iter()
+        .map(|user| {
+            ListItem::new(Line::from(vec![
+                Span::raw(user.display_name.clone()),
+                Span::styled(
+                    format!("  {}", user.unique_name),
+                    Style::default().fg(app.config.theme.parse_color(&app.config.theme.text_muted)),
+                ),
+            ]))
+        })
+        .collect();
+    let width = suggestions
+        .iter()
+        .map(|u| u.display_name.chars().count() + u.unique_name.chars().count() + 4)
+        .max()
+        .unwrap_or(20) as u16;
+    let popup = Rect::new(
+        cursor.x.min(area.right().saturating_sub(width)),
+        cursor.y + 1,
+        width.min(area.width),
+        items.len() as u16 + 2,
+    )
+    .intersection(area);
+    f.render_widget(Clear, popup);
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL))
+        .highlight_style(
+            Style::default().bg(app.config.theme.parse_color(&app.config.theme.selected_bg)),
+        );
+    let mut state = ratatui::widgets::ListState::default().with_selected(Some(draft.suggestion));
+    f.render_stateful_widget(list, popup, &mut state);
 }
 
 fn draw_dropdown(
@@ -143,6 +294,23 @@ pub fn draw_search_input(f: &mut Frame, app: &App, area: Rect) {
     ));
 }
 
+/// `+tag -tag` prompt for the selected / marked work items
+pub fn draw_tags_input(f: &mut Frame, app: &App, area: Rect) {
+    let inner = Rect::new(area.x, area.y, area.width, 3);
+    f.render_widget(Clear, inner);
+
+    let title = app.edit_title("Tags: +add -remove");
+    let block = Block::default().borders(Borders::ALL).title(title);
+
+    let paragraph = Paragraph::new(app.filter_input.as_str()).block(block);
+    f.render_widget(paragraph, inner);
+
+    f.set_cursor_position(Position::new(
+        inner.x + 1 + app.filter_input.chars().count() as u16,
+        inner.y + 1,
+    ));
+}
+
 fn state_icon_and_color(state: &str) -> (&'static str, Color) {
     match state {
         "All" => ("○", Color::White),
@@ -170,7 +338,7 @@ pub fn draw_filter_state_dropdown(f: &mut Frame, app: &mut App, area: Rect) {
     let items: Vec<ListItem> = states
         .iter()
         .map(|s| {
-            let selected = app.filter_state.as_deref() == Some(*s);
+            let selected = app.filter_state.as_deref() == Some(s.as_str());
             let (icon, color) = state_icon_and_color(s);
             let line = Line::from(vec![
                 Span::styled(format!("{icon} "), Style::default().fg(color)),
@@ -37,6 +37,7 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         View::Tasks => draw_tasks_view(f, app, chunks[1]),
         View::PRs => prs::draw(f, app, chunks[1]),
         View::CICD => cicd::draw(f, app, chunks[1]),
+        View::Board => tasks::draw_board(f, app, chunks[1]),
     }
 
     // Bottom: Status/help bar
@@ -52,10 +53,28 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         InputMode::Search => input::draw_search_input(f, app, size),
         InputMode::FilterState => input::draw_filter_state_dropdown(f, app, size),
         InputMode::FilterAssignee => input::draw_filter_assignee_dropdown(f, app, size),
-        InputMode::CICDSearch => {} // Handled inline in panels
+        InputMode::PRVote => input::draw_vote_dropdown(f, app, size),
+        InputMode::PRComment => input::draw_comment_input(f, app, size),
+        InputMode::CommentComposer => input::draw_work_item_comment_input(f, app, size),
+        InputMode::PRCreate => prs::draw_create_dialog(f, app, size),
+        InputMode::PRComplete => prs::draw_complete_dialog(f, app, size),
+        InputMode::CICDSearch => {}   // Handled inline in panels
+        InputMode::WorkItemForm => {} // Drawn in the Details tab
+        InputMode::WorkItemCreate => tasks::draw_create_dialog(f, app, size),
+        InputMode::LinkWorkItem => tasks::draw_link_dialog(f, app, size),
+        InputMode::EditTags => input::draw_tags_input(f, app, size),
+        InputMode::MoveIteration => input::draw_move_iteration_dropdown(f, app, size),
+        InputMode::QueryBrowser | InputMode::QueryEditor => tasks::draw_queries(f, app, size),
+        InputMode::SprintStats => tasks::draw_sprint_stats(f, app, size),
+        InputMode::TeamCapacity => tasks::draw_team_capacity(f, app, size),
         InputMode::Normal => {}
-        InputMode::ReleaseTriggerDialog | InputMode::ApprovalConfirm | InputMode::ConfirmAction => {
-        } // Dialogs rendered in cicd module
+        InputMode::ReleaseTriggerDialog
+        | InputMode::PipelineTriggerDialog
+        | InputMode::LogSearch
+        | InputMode::FailureSummary
+        | InputMode::Artifacts
+        | InputMode::ApprovalConfirm
+        | InputMode::ConfirmAction => {} // Dialogs rendered in cicd module
     }
 
     // Loading overlay
@@ -76,6 +95,11 @@ fn draw_tasks_view(f: &mut Frame, app: &mut App, area: Rect) {
 
     // Right: Preview pane
     tasks::draw_preview(f, app, content[1]);
+
+    // Comment deletions share the CI/CD dialog
+    if let Some(ref dialog) = app.confirm_action_dialog {
+        cicd::dialogs::render_confirm_action_dialog(f, dialog);
+    }
 }
 
 fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
@@ -104,7 +128,15 @@ fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
                     parts.push(format!("Search:\"{query}\""));
                 }
 
-                if !parts.is_empty() {
+                if app.has_marks() && app.input_mode == InputMode::Normal {
+                    let count = app.marked_ids().len();
+                    let range = if app.visual_anchor.is_some() {
+                        " (range)"
+                    } else {
+                        ""
+                    };
+                    format!("{count} marked{range}  │  space:mark  v:range  *:children  ^a:all  S:state  A:assign  T:tags  M:move  p:pin  Esc:clear")
+                } else if !parts.is_empty() {
                     let joined = parts.join("  ");
                     format!("Filters: {joined}  │  c:clear  s:state  a:user  f:search")
                 } else {
@@ -112,40 +144,89 @@ fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
                         InputMode::Normal => {
                             match app.focus {
                                 crate::app::Focus::WorkItems => {
-                                    "j/k:nav  Enter:expand  t:toggle  o:open  s:state  a:user  S:edit  A:assign  f:search  I:sprint  l:preview  r:refresh  ?:help  q:quit".into()
+                                    "j/k:nav  Enter:expand  t:toggle  o:open  s:state  a:user  S:edit  A:assign  e:fields  T:tags  M:move  C:comment  L:link  space/v:mark  N:new  n:PR  f:search  I:sprint  Q:queries  B:burndown  K:capacity  l:preview  r:refresh  ?:help  q:quit".into()
                                 }
                                 crate::app::Focus::Preview => {
                                     match app.preview_tab {
                                         crate::app::PreviewTab::Details => {
-                                            "j/k:scroll  Tab:refs  h:back  o:open  r:refresh  ?:help  q:quit".into()
+                                            "j/k:scroll  Tab:refs  h:back  e:edit  o:open  r:refresh  ?:help  q:quit".into()
                                         }
                                         crate::app::PreviewTab::References => {
-                                            "j/k:select  ^d/^u:page  Tab:details  h:back  o:open  r:refresh  ?:help  q:quit".into()
+                                            "j/k:select  ^d/^u:page  Tab:discussion  h:back  o:open  L:link  X:unlink  r:refresh  ?:help  q:quit".into()
+                                        }
+                                        crate::app::PreviewTab::Discussion => {
+                                            "j/k:select  C:comment  e:edit  D:delete  Tab:history  h:back  r:refresh  ?:help  q:quit".into()
+                                        }
+                                        crate::app::PreviewTab::History => {
+                                            "j/k:scroll  ^d/^u:page  Tab:details  h:back  o:open  r:refresh  ?:help  q:quit".into()
                                         }
                                     }
                                 }
                             }
                         }
                         InputMode::Search => "Enter:confirm  Esc:cancel".into(),
+                        InputMode::CommentComposer => {
+                            "@:mention  Tab:complete  Ctrl+S:post  Enter:newline  Esc:cancel".into()
+                        }
+                        InputMode::SprintStats => "r:reload history  B/Esc:close".into(),
+                        InputMode::TeamCapacity => "r:reload capacity  K/Esc:close".into(),
+                        InputMode::LinkWorkItem => {
+                            "Tab:link type  ↑/↓:select  Enter:link  Esc:cancel".into()
+                        }
+                        InputMode::WorkItemForm => {
+                            "j/k:field  Enter:edit ($EDITOR for description)  Ctrl+s:save  Esc:discard".into()
+                        }
                         _ => "j/k:select  Enter:confirm  Esc:cancel".into(),
                     }
                 }
             }
+            View::Board => match app.input_mode {
+                InputMode::Normal if app.has_active_filters() => {
+                    let mut parts: Vec<String> = Vec::new();
+                    if let Some(state) = &app.filter_state {
+                        parts.push(format!("State:{state}"));
+                    }
+                    if let Some(assignee) = &app.filter_assignee {
+                        parts.push(format!("Assignee:{assignee}"));
+                    }
+                    if !app.search_query.is_empty() {
+                        let query = &app.search_query;
+                        parts.push(format!("Search:\"{query}\""));
+                    }
+                    let joined = parts.join("  ");
+                    format!("Filters: {joined}  │  c:clear  s:state  a:user  f:search")
+                }
+                InputMode::Normal => {
+                    "h/j/k/l:nav  H/L:move card  Enter:collapse lane  t:all lanes  S:state  A:assign  o:open  f:search  I:sprint  B:burndown  K:capacity  r:refresh  ?:help  q:quit".into()
+                }
+                InputMode::Search => "Enter:confirm  Esc:cancel".into(),
+                InputMode::SprintStats => "r:reload history  B/Esc:close".into(),
+                InputMode::TeamCapacity => "r:reload capacity  K/Esc:close".into(),
+                _ => "j/k:select  Enter:confirm  Esc:cancel".into(),
+            },
             View::PRs => {
                 // PR view keybindings based on focus and drill-down
                 match app.input_mode {
                     InputMode::Normal => {
                         match app.pr_focus {
+                            crate::app::PRFocus::Preview
+                                if app.pr_drill_down == crate::app::PRDrillDown::Files =>
+                            {
+                                "j/k:line  V:select  c:comment  C:reply  x:resolve  o:open  Esc:back  ?:help  q:quit".into()
+                            }
                             crate::app::PRFocus::Preview => {
-                                "j/k:scroll  Tab:switch tab  h:back  o:open  ?:help  q:quit".into()
+                                "j/k:scroll  Tab:switch tab  v:vote  c:comment  C:reply  x:resolve  h:back  o:open  ?:help  q:quit".into()
                             }
                             _ => {
                                 match app.pr_drill_down {
                                     crate::app::PRDrillDown::Repos => {
-                                        "j/k:nav  f:search  Enter:PRs  o:open  r:refresh  ?:help  q:quit".into()
+                                        "j/k:nav  f:search  Enter:PRs  n:new PR  o:open  r:refresh  ?:help  q:quit".into()
                                     }
                                     crate::app::PRDrillDown::PRs => {
-                                        "j/k:nav  h/l:pane  f:search  Enter:details  Esc:back  o:open  r:refresh  ?:help  q:quit".into()
+                                        "j/k:nav  h/l:pane  Enter:details  F:files  n:new  m:complete  v:vote  c:comment  d:draft  X:abandon  Esc:back  ?:help  q:quit".into()
+                                    }
+                                    crate::app::PRDrillDown::Files => {
+                                        "j/k:nav  h/l:iteration  f:search  Enter:diff  c:comment  o:open  r:refresh  Esc:back  ?:help  q:quit".into()
                                     }
                                 }
                             }
@@ -162,17 +243,17 @@ fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
                             crate::app::CICDFocus::Pipelines => {
                                 match app.pipeline_drill_down {
                                     crate::app::PipelineDrillDown::None => {
-                                        "j/k:nav  f:search  Enter:runs  p:pin  h/l:panes  o:open  r:refresh  ?:help  q:quit".into()
+                                        "j/k:nav  f:search  Enter:runs  T:run…  t:run preset  p:pin  h/l:panes  o:open  r:refresh  ?:help  q:quit".into()
                                     }
                                     crate::app::PipelineDrillDown::Runs => {
                                         if app.pipeline_runs_limited {
-                                            "j/k:nav  f:search  ^d/^u:page  Enter:details  L:all  Esc:back  o:open  ?:help  q:quit".into()
+                                            "j/k:nav  f:search  ^d/^u:page  Enter:details  Tab:tests  E:why failed  F:artifacts  L:all  T:retrigger  t:run preset  Esc:back  o:open  ?:help  q:quit".into()
                                         } else {
-                                            "j/k:nav  f:search  ^d/^u:page  Enter:details  Esc:back  o:open  ?:help  q:quit".into()
+                                            "j/k:nav  f:search  ^d/^u:page  Enter:details  Tab:tests  E:why failed  F:artifacts  T:retrigger  t:run preset  Esc:back  o:open  ?:help  q:quit".into()
                                         }
                                     }
                                     crate::app::PipelineDrillDown::Tasks => {
-                                        "j/k:nav  f:search  ^d/^u:page  Enter:logs  e:edit  Esc:back  o:open  ?:help  q:quit".into()
+                                        "j/k:nav  f:search  ^d/^u:page  Enter:logs  E:why failed  F:artifacts  /:find  ]e/]w:errors/warnings  e:edit  Esc:back  ?:help".into()
                                     }
                                 }
                             }
@@ -188,15 +269,37 @@ fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
                                         "j/k:nav  f:search  Enter:tasks  a:approve  T:trigger  Esc:back  o:open  ?:help  q:quit".into()
                                     }
                                     crate::app::ReleaseDrillDown::Tasks => {
-                                        "j/k:nav  f:search  ^d/^u:page  Enter:logs  T:trigger  Esc:back  o:open  ?:help  q:quit".into()
+                                        "j/k:nav  f:search  ^d/^u:page  Enter:logs  /:find  ]e/]w:errors/warnings  T:trigger  Esc:back  ?:help".into()
                                     }
                                 }
                             }
+                            crate::app::CICDFocus::Preview if app.shown_log().is_some() => {
+                                "j/k:scroll  /:find  n/N:next/prev  ]e/[e:errors  ]w/[w:warnings  &:filter  s:timestamps  h:back".into()
+                            }
                             crate::app::CICDFocus::Preview => {
                                 "j/k:scroll  h:back  o:open  ?:help  q:quit".into()
                             }
                         }
                     }
+                    InputMode::LogSearch => {
+                        "type:regex (smart case)  Enter:search  Esc:cancel".into()
+                    }
+                    InputMode::FailureSummary => {
+                        "j/k:select  Enter:go to log line  Esc:close".into()
+                    }
+                    InputMode::Artifacts => match app.artifact_browser.as_ref() {
+                        Some(b) if b.destination.is_some() => {
+                            "type:directory  Enter:download  Esc:cancel".into()
+                        }
+                        _ => "j/k:select  Enter/l:open  h:up  d:download  Esc:close".into(),
+                    },
+                    InputMode::PipelineTriggerDialog => match app.pipeline_trigger_dialog.as_ref() {
+                        Some(d) if d.ref_query.is_some() => {
+                            "type:filter  ↑/↓:select  Enter:pick  Esc:back".into()
+                        }
+                        Some(d) if d.editing.is_some() => "Enter:keep  Esc:discard".into(),
+                        _ => "j/k:move  Enter:edit/pick  Space:toggle  Ctrl+s:run  Esc:close".into(),
+                    },
                     _ => "j/k:select  Enter:confirm  Esc:cancel".into(),
                 }
             }
@@ -259,12 +362,17 @@ pub fn styled_block<'a>(
         .title(format!(" {title} "))
 }
 
-/// Draw embedded terminal (nvim log viewer) fullscreen
+/// Draw embedded terminal (nvim log viewer or `$EDITOR`) fullscreen
 fn draw_embedded_terminal(f: &mut Frame, app: &mut App, area: Rect) {
+    let title = if app.editor_file.is_some() {
+        " Editor (save and quit, or Ctrl+q to return) "
+    } else {
+        " Log Viewer (Ctrl+q to exit) "
+    };
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan))
-        .title(" Log Viewer (Ctrl+q to exit) ");
+        .title(title);
 
     let inner = block.inner(area);
     f.render_widget(block, area);
@@ -0,0 +1,232 @@
+use crate::app::App;
+use crate::pr_complete::{MergeStrategy, PRCompleteDialog, PRCompleteField, PRCompleteOption};
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap};
+
+/// Pull request completion dialog: merge options on the left, policies on the right
+pub fn draw_complete_dialog(f: &mut Frame, app: &App, area: Rect) {
+    let Some(dialog) = &app.pr_complete_dialog else {
+        return;
+    };
+    let theme = &app.config.theme;
+
+    let popup = super::super::centered_rect(100, 26, area);
+    f.render_widget(Clear, popup);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(format!(
+            " Complete PR #{}: {} ({} → {}) ",
+            dialog.pr_id, dialog.title, dialog.source, dialog.target
+        ));
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(rows[0]);
+
+    let fields = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(MergeStrategy::ALL.len() as u16 + 2), // Strategy
+            Constraint::Min(4),                                      // Message
+            Constraint::Length(PRCompleteOption::ALL.len() as u16 + 2), // Options
+            Constraint::Length(3),                                   // Bypass reason
+        ])
+        .split(columns[0]);
+
+    // Merge type
+    let focused = dialog.field == PRCompleteField::Strategy;
+    let block = super::super::styled_block(PRCompleteField::Strategy.label(), focused, theme);
+    let items: Vec<ListItem> = MergeStrategy::ALL
+        .iter()
+        .map(|strategy| {
+            let picked = *strategy == dialog.strategy;
+            let radio = if picked { "(•)" } else { "( )" };
+            let style = if picked && focused {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else if picked {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            ListItem::new(format!(" {radio} {}", strategy.label())).style(style)
+        })
+        .collect();
+    f.render_widget(List::new(items).block(block), fields[0]);
+
+    // Merge commit message
+    let focused = dialog.field == PRCompleteField::Message;
+    let block = super::super::styled_block(PRCompleteField::Message.label(), focused, theme);
+    let text_area = block.inner(fields[1]);
+    let (text, style) = if dialog.strategy.has_commit_message() {
+        (dialog.message.as_str(), Style::default().fg(Color::White))
+    } else {
+        (
+            "(rebasing keeps the source commits)",
+            Style::default().fg(Color::DarkGray),
+        )
+    };
+    f.render_widget(
+        Paragraph::new(text)
+            .style(style)
+            .wrap(Wrap { trim: false })
+            .block(block),
+        fields[1],
+    );
+    if focused {
+        let lines = dialog.message.split('\n').count().saturating_sub(1);
+        let last = dialog.message.rsplit('\n').next().unwrap_or("");
+        set_cursor(f, text_area, lines, last.chars().count());
+    }
+
+    // Options
+    let focused = dialog.field == PRCompleteField::Options;
+    let block = super::super::styled_block(PRCompleteField::Options.label(), focused, theme);
+    let items: Vec<ListItem> = PRCompleteOption::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, option)| {
+            let enabled = dialog.option_enabled(*option);
+            let checkbox = if enabled { "[x]" } else { "[ ]" };
+            let style = if focused && i == dialog.cursor {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else if enabled {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            ListItem::new(format!(" {checkbox} {}", option.label())).style(style)
+        })
+        .collect();
+    f.render_widget(List::new(items).block(block), fields[2]);
+
+    // Bypass reason
+    let focused = dialog.field == PRCompleteField::BypassReason;
+    let block = super::super::styled_block(PRCompleteField::BypassReason.label(), focused, theme);
+    let text_area = block.inner(fields[3]);
+    let (text, style) = if dialog.bypass_policy {
+        (
+            dialog.bypass_reason.as_str(),
+            Style::default().fg(Color::White),
+        )
+    } else {
+        (
+            "(only when bypassing policies)",
+            Style::default().fg(Color::DarkGray),
+        )
+    };
+    f.render_widget(Paragraph::new(text).style(style).block(block), fields[3]);
+    if focused {
+        set_cursor(f, text_area, 0, dialog.bypass_reason.chars().count());
+    }
+
+    draw_policies(f, app, dialog, columns[1]);
+
+    let hint = if dialog.submitting {
+        "Completing pull request...".to_string()
+    } else if let Some(problem) = dialog.validate() {
+        format!("{problem}  |  Tab:field  ↑/↓:select  Space:toggle  Esc:cancel")
+    } else if dialog.auto_complete {
+        "Tab/Shift+Tab:field  ↑/↓:select  Space:toggle  Ctrl+s:set auto-complete  Esc:cancel"
+            .to_string()
+    } else {
+        "Tab/Shift+Tab:field  ↑/↓:select  Space:toggle  Ctrl+s:complete  Esc:cancel".to_string()
+    };
+    f.render_widget(
+        Paragraph::new(hint).style(Style::default().fg(Color::DarkGray)),
+        rows[1],
+    );
+}
+
+/// Policy evaluations, with blocking failures called out
+fn draw_policies(f: &mut Frame, app: &App, dialog: &PRCompleteDialog, area: Rect) {
+    let block = super::super::styled_block("Policies", false, &app.config.theme);
+
+    if dialog.loading_policies {
+        let paragraph = Paragraph::new("Evaluating policies...")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(block);
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let failures = dialog.blocking_failures().len();
+    let summary = if failures == 0 {
+        ListItem::new("All blocking policies passed").style(Style::default().fg(Color::Green))
+    } else if dialog.auto_complete {
+        ListItem::new(format!(
+            "Auto-complete waits for {failures} blocking polic{}",
+            if failures == 1 { "y" } else { "ies" }
+        ))
+        .style(Style::default().fg(Color::Yellow))
+    } else {
+        ListItem::new(format!(
+            "{failures} blocking polic{} not passed",
+            if failures == 1 { "y has" } else { "ies have" }
+        ))
+        .style(Style::default().fg(Color::Red))
+    };
+
+    let mut items = vec![summary, ListItem::new("")];
+    items.extend(dialog.policies.iter().map(|policy| {
+        let status = policy.status.as_deref().unwrap_or("unknown");
+        let (icon, icon_color) = match status {
+            "approved" => ("✓", Color::Green),
+            "rejected" | "broken" => ("✗", Color::Red),
+            "running" => ("◐", Color::Yellow),
+            "queued" => ("○", Color::DarkGray),
+            "notApplicable" => ("-", Color::DarkGray),
+            _ => ("?", Color::DarkGray),
+        };
+        let name_style = if policy.is_blocking_failure() {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let blocking = policy
+            .configuration
+            .as_ref()
+            .map(|c| {
+                if c.is_blocking {
+                    "blocking"
+                } else {
+                    "optional"
+                }
+            })
+            .unwrap_or("unknown");
+        ListItem::new(Line::from(vec![
+            Span::styled(format!(" {icon} "), Style::default().fg(icon_color)),
+            Span::styled(policy.display_name().to_string(), name_style),
+            Span::styled(
+                format!(" ({blocking}, {status})"),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]))
+    }));
+    if dialog.policies.is_empty() {
+        items.push(
+            ListItem::new("No policy evaluations").style(Style::default().fg(Color::DarkGray)),
+        );
+    }
+
+    f.render_widget(List::new(items).block(block), area);
+}
+
+/// Place the terminal cursor inside a text field
+fn set_cursor(f: &mut Frame, area: Rect, line: usize, col: usize) {
+    f.set_cursor_position(Position::new(
+        (area.x + col as u16).min(area.right().saturating_sub(1)),
+        (area.y + line as u16).min(area.bottom().saturating_sub(1)),
+    ));
+}
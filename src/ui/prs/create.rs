@@ -0,0 +1,220 @@
+use crate::app::App;
+use crate::pr_create::{PRCreateDialog, PRCreateField, PRCreateOption};
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap};
+
+/// Full-screen pull request creation dialog
+pub fn draw_create_dialog(f: &mut Frame, app: &App, area: Rect) {
+    let Some(dialog) = &app.pr_create_dialog else {
+        return;
+    };
+    let theme = &app.config.theme;
+
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(format!(" New Pull Request: {} ", dialog.repository));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(rows[0]);
+
+    let fields = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),                                    // Source
+            Constraint::Length(3),                                    // Target
+            Constraint::Length(3),                                    // Title
+            Constraint::Min(5),                                       // Description
+            Constraint::Length(3),                                    // Reviewers
+            Constraint::Length(3),                                    // Work items
+            Constraint::Length(PRCreateOption::ALL.len() as u16 + 2), // Options
+        ])
+        .split(columns[0]);
+
+    let areas = [
+        (PRCreateField::Source, fields[0]),
+        (PRCreateField::Target, fields[1]),
+        (PRCreateField::Title, fields[2]),
+        (PRCreateField::Description, fields[3]),
+        (PRCreateField::Reviewers, fields[4]),
+        (PRCreateField::WorkItems, fields[5]),
+    ];
+    for (field, field_area) in areas {
+        let focused = dialog.field == field;
+        let block = super::super::styled_block(field.label(), focused, theme);
+        let text_area = block.inner(field_area);
+
+        let (text, style) = field_text(dialog, field);
+        let paragraph = Paragraph::new(text).style(style).block(block);
+        let paragraph = if field == PRCreateField::Description {
+            paragraph.wrap(Wrap { trim: false })
+        } else {
+            paragraph
+        };
+        f.render_widget(paragraph, field_area);
+
+        if focused {
+            set_cursor(f, dialog, text_area);
+        }
+    }
+
+    draw_options(f, app, dialog, fields[6]);
+    draw_candidates(f, app, dialog, columns[1]);
+
+    let hint = if dialog.submitting {
+        "Creating pull request...".to_string()
+    } else {
+        "Tab/Shift+Tab:field  ↑/↓:select  Enter:pick  Ctrl+x:swap branches  Ctrl+s:create  Esc:cancel"
+            .to_string()
+    };
+    f.render_widget(
+        Paragraph::new(hint).style(Style::default().fg(Color::DarkGray)),
+        rows[1],
+    );
+}
+
+/// Text shown in a field, with its style
+fn field_text(dialog: &PRCreateDialog, field: PRCreateField) -> (String, Style) {
+    let focused = dialog.field == field;
+    if focused && field.is_picker() && !dialog.query.is_empty() {
+        return (
+            format!("🔍 {}", dialog.query),
+            Style::default().fg(Color::Yellow),
+        );
+    }
+
+    let value = match field {
+        PRCreateField::Source => dialog.source.clone(),
+        PRCreateField::Target => dialog.target.clone(),
+        PRCreateField::Title => dialog.title.clone(),
+        PRCreateField::Description => dialog.description.clone(),
+        PRCreateField::Reviewers => dialog
+            .reviewers
+            .iter()
+            .map(|r| r.display_name.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+        PRCreateField::WorkItems => dialog
+            .work_items
+            .iter()
+            .map(|w| format!("#{}", w.id))
+            .collect::<Vec<_>>()
+            .join(", "),
+        PRCreateField::Options => String::new(),
+    };
+    if value.is_empty() && !focused {
+        let placeholder = match field {
+            PRCreateField::Source | PRCreateField::Target if dialog.loading_branches => {
+                "Loading branches..."
+            }
+            PRCreateField::Reviewers | PRCreateField::WorkItems => "(none)",
+            _ => "",
+        };
+        return (placeholder.into(), Style::default().fg(Color::DarkGray));
+    }
+    (value, Style::default().fg(Color::White))
+}
+
+/// Place the terminal cursor at the end of the focused field's input
+fn set_cursor(f: &mut Frame, dialog: &PRCreateDialog, area: Rect) {
+    let (line, col) = match dialog.field {
+        PRCreateField::Title => (0, dialog.title.chars().count()),
+        PRCreateField::Description => {
+            let lines = dialog.description.split('\n').count().saturating_sub(1);
+            let last = dialog.description.rsplit('\n').next().unwrap_or("");
+            (lines, last.chars().count())
+        }
+        field if field.is_picker() && !dialog.query.is_empty() => {
+            (0, 3 + dialog.query.chars().count())
+        }
+        _ => return,
+    };
+    f.set_cursor_position(Position::new(
+        (area.x + col as u16).min(area.right().saturating_sub(1)),
+        (area.y + line as u16).min(area.bottom().saturating_sub(1)),
+    ));
+}
+
+fn draw_options(f: &mut Frame, app: &App, dialog: &PRCreateDialog, area: Rect) {
+    let focused = dialog.field == PRCreateField::Options;
+    let block = super::super::styled_block("Options - Space:toggle", focused, &app.config.theme);
+
+    let items: Vec<ListItem> = PRCreateOption::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, option)| {
+            let enabled = dialog.option_enabled(*option);
+            let checkbox = if enabled { "[x]" } else { "[ ]" };
+            let style = if focused && i == dialog.cursor {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else if enabled {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            ListItem::new(format!(" {checkbox} {}", option.label())).style(style)
+        })
+        .collect();
+
+    f.render_widget(List::new(items).block(block), area);
+}
+
+/// Right-hand list of candidates for the focused picker
+fn draw_candidates(f: &mut Frame, app: &App, dialog: &PRCreateDialog, area: Rect) {
+    let title = match dialog.field {
+        PRCreateField::Source | PRCreateField::Target => "Branches",
+        PRCreateField::Reviewers => "Add reviewer",
+        PRCreateField::WorkItems => "Link work item",
+        _ => "Linked work items",
+    };
+    let block = super::super::styled_block(title, dialog.field.is_picker(), &app.config.theme);
+
+    if !dialog.field.is_picker() {
+        // Outside the pickers, show what will be linked
+        let items: Vec<ListItem> = dialog
+            .work_items
+            .iter()
+            .map(|w| ListItem::new(format!("#{} {}", w.id, w.title)))
+            .collect();
+        f.render_widget(List::new(items).block(block), area);
+        return;
+    }
+
+    let candidates = app.pr_create_candidates();
+    if candidates.is_empty() {
+        let msg = if dialog.loading_branches
+            && matches!(dialog.field, PRCreateField::Source | PRCreateField::Target)
+        {
+            "Loading branches..."
+        } else {
+            "No matches"
+        };
+        let paragraph = Paragraph::new(msg)
+            .style(Style::default().fg(Color::DarkGray))
+            .block(block);
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = candidates
+        .iter()
+        .map(|c| ListItem::new(c.label()))
+        .collect();
+    let list = List::new(items).block(block).highlight_style(
+        Style::default().bg(app.config.theme.parse_color(&app.config.theme.selected_bg)),
+    );
+    let mut state = ListState::default();
+    state.select(Some(dialog.cursor.min(candidates.len() - 1)));
+    f.render_stateful_widget(list, area, &mut state);
+}
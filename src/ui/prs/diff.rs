@@ -0,0 +1,188 @@
+use crate::app::{App, PRFocus};
+use crate::azure::PRThread;
+use crate::pr_diff::{DiffLineKind, FileDiff};
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+
+/// Diff of the selected file with its comment threads inline
+pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
+    let focused = app.pr_focus == PRFocus::Preview;
+    let theme = &app.config.theme;
+    let selected_bg = theme.parse_color(&theme.selected_bg);
+
+    let change = app.selected_pr_change().cloned();
+    let Some(files) = &mut app.pr_files else {
+        return;
+    };
+    let diff = files
+        .diff
+        .as_mut()
+        .filter(|d| Some(&d.change) == change.as_ref());
+
+    let Some(diff) = diff else {
+        let block = super::super::styled_block("Diff", focused, theme);
+        let msg = match &change {
+            Some(_) if focused => "Loading diff...",
+            Some(_) => "Press Enter to open the diff",
+            None => "Select a file to view its diff",
+        };
+        let paragraph = Paragraph::new(msg)
+            .style(Style::default().fg(Color::DarkGray))
+            .block(block);
+        f.render_widget(paragraph, area);
+        return;
+    };
+
+    let selection = diff
+        .anchor
+        .and_then(|_| diff.selection_label())
+        .map(|label| format!("[{label}] "))
+        .unwrap_or_default();
+    let title = format!("{} {} {selection}", diff.change.marker(), diff.change.path);
+    let block = super::super::styled_block(title.trim_end(), focused, theme);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let threads = diff.threads(&app.pr_threads);
+    let mut rows: Vec<Line> = Vec::new();
+
+    // File-level threads and threads on lines outside the shown hunks
+    let unanchored: Vec<&PRThread> = threads
+        .iter()
+        .filter(|(row, _)| row.is_none())
+        .map(|(_, t)| *t)
+        .collect();
+    if !unanchored.is_empty() {
+        rows.push(Line::styled(
+            "Comments on this file",
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ));
+        for thread in unanchored {
+            push_thread(&mut rows, thread);
+        }
+        rows.push(Line::raw(""));
+    }
+
+    let mut cursor_row = 0;
+    for idx in 0..diff.lines.len() {
+        if idx == diff.cursor {
+            cursor_row = rows.len();
+        }
+        rows.push(diff_line(diff, idx, focused, selected_bg));
+        for (_, thread) in threads.iter().filter(|(row, _)| *row == Some(idx)) {
+            push_thread(&mut rows, thread);
+        }
+    }
+    if diff.lines.is_empty() {
+        rows.push(Line::styled(
+            "No textual changes",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    // Keep the cursor on screen
+    let height = inner.height as usize;
+    if cursor_row < diff.scroll {
+        diff.scroll = cursor_row;
+    } else if height > 0 && cursor_row >= diff.scroll + height {
+        diff.scroll = cursor_row + 1 - height;
+    }
+
+    let paragraph = Paragraph::new(rows).scroll((diff.scroll as u16, 0));
+    f.render_widget(paragraph, inner);
+}
+
+/// One diff row: line numbers, +/- marker and text
+fn diff_line(diff: &FileDiff, idx: usize, focused: bool, selected_bg: Color) -> Line<'static> {
+    let line = &diff.lines[idx];
+    let number = |n: Option<u32>| {
+        n.map(|n| format!("{n:>4}"))
+            .unwrap_or_else(|| "    ".into())
+    };
+
+    let (sign, color) = match line.kind {
+        DiffLineKind::Hunk => ("", Color::Cyan),
+        DiffLineKind::Context => (" ", Color::White),
+        DiffLineKind::Added => ("+", Color::Green),
+        DiffLineKind::Removed => ("-", Color::Red),
+    };
+    let cursor = focused && idx == diff.cursor;
+    let mut text_style = Style::default().fg(color);
+    if focused && diff.is_selected(idx) {
+        text_style = text_style.bg(selected_bg);
+    }
+    if cursor {
+        text_style = text_style.add_modifier(Modifier::BOLD);
+    }
+
+    Line::from(vec![
+        Span::styled(
+            if cursor { "▸" } else { " " },
+            Style::default().fg(Color::Cyan),
+        ),
+        Span::styled(
+            format!("{} {} ", number(line.old_line), number(line.new_line)),
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::styled(format!("{sign}{}", line.text), text_style),
+    ])
+}
+
+/// Thread comments indented under the line they belong to
+fn push_thread(rows: &mut Vec<Line<'static>>, thread: &PRThread) {
+    let (icon, icon_color) = match thread.status.as_deref() {
+        Some("active") => ("●", Color::Green),
+        Some("pending") => ("◐", Color::Yellow),
+        Some("byDesign") => ("✓", Color::Blue),
+        _ => ("✓", Color::DarkGray),
+    };
+    let location = thread
+        .thread_context
+        .as_ref()
+        .and_then(|c| c.line_label())
+        .map(|label| format!(" {label}"))
+        .unwrap_or_default();
+
+    let comments = thread
+        .comments
+        .iter()
+        .filter(|c| c.comment_type.as_deref() != Some("system"));
+    for (i, comment) in comments.enumerate() {
+        let author = comment
+            .author
+            .as_ref()
+            .map(|a| a.display_name.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
+        let content = comment.content.as_deref().unwrap_or("(empty)");
+        for (j, text) in content.lines().enumerate() {
+            let mut spans = vec![Span::styled(
+                "          ┃ ",
+                Style::default().fg(Color::Magenta),
+            )];
+            if j == 0 {
+                if i == 0 {
+                    spans.push(Span::styled(icon, Style::default().fg(icon_color)));
+                    spans.push(Span::styled(
+                        format!("{location} "),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                } else {
+                    spans.push(Span::raw("  "));
+                }
+                spans.push(Span::styled(
+                    format!("{author}: "),
+                    Style::default().fg(Color::Yellow),
+                ));
+            } else {
+                spans.push(Span::raw("    "));
+            }
+            spans.push(Span::styled(
+                text.to_string(),
+                Style::default().fg(Color::White),
+            ));
+            rows.push(Line::from(spans));
+        }
+    }
+}
@@ -18,8 +18,8 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
     // Check if search input should be shown above this panel
     let show_search = app.input_mode == InputMode::CICDSearch && focused;
 
-    // When in PR drill-down, show pane tab bar at top
-    let show_pane_tabs = app.pr_drill_down == PRDrillDown::PRs;
+    // When in PR drill-down, show pane (or iteration) tab bar at top
+    let show_pane_tabs = app.pr_drill_down != PRDrillDown::Repos;
 
     let mut constraints: Vec<Constraint> = Vec::new();
     if show_pane_tabs {
@@ -38,7 +38,11 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
     let mut chunk_idx = 0;
 
     if show_pane_tabs {
-        draw_pane_tabs(f, app, chunks[chunk_idx], border_color);
+        if app.pr_drill_down == PRDrillDown::Files {
+            draw_iteration_tabs(f, app, chunks[chunk_idx], border_color);
+        } else {
+            draw_pane_tabs(f, app, chunks[chunk_idx], border_color);
+        }
         chunk_idx += 1;
     }
 
@@ -52,9 +56,39 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
     match app.pr_drill_down {
         PRDrillDown::Repos => draw_repo_list(f, app, list_area, border_color, focused),
         PRDrillDown::PRs => draw_pr_list(f, app, list_area, border_color, focused),
+        PRDrillDown::Files => draw_file_list(f, app, list_area, border_color, focused),
     }
 }
 
+/// Tab bar for the file list: all changes, then each iteration
+fn draw_iteration_tabs(f: &mut Frame, app: &App, area: Rect, border_color: Color) {
+    let (count, selected) = app
+        .pr_files
+        .as_ref()
+        .map(|files| (files.iterations.len(), files.iteration))
+        .unwrap_or_default();
+    let titles: Vec<String> = std::iter::once("All".to_string())
+        .chain((1..=count).map(|n| n.to_string()))
+        .collect();
+
+    let tabs = Tabs::new(titles)
+        .select(selected)
+        .style(Style::default().fg(Color::DarkGray))
+        .highlight_style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .divider(" | ");
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(border_color))
+        .title(" Iterations h/l ");
+
+    f.render_widget(tabs.block(block), area);
+}
+
 fn draw_pane_tabs(f: &mut Frame, app: &App, area: Rect, border_color: Color) {
     let titles = vec!["Active", "Mine", "Completed", "Abandoned"];
     let active_pane = if app.pr_focus.is_list() {
@@ -404,3 +438,102 @@ fn format_vote_summary(reviewers: &[crate::azure::PRReviewer]) -> String {
         .collect();
     format!("[{}]", icons.join(""))
 }
+
+fn draw_file_list(f: &mut Frame, app: &mut App, area: Rect, border_color: Color, focused: bool) {
+    let Some(files) = &app.pr_files else {
+        return;
+    };
+    let search_query = app.pr_search_query.clone();
+    let visible = app.pr_visible_changes();
+    let search_indicator = if !search_query.is_empty() {
+        format!(" \"{search_query}\"")
+    } else {
+        String::new()
+    };
+    let title = format!(
+        " Files - PR #{} ({}/{}) [{}]{} ",
+        files.pr_id,
+        visible.len(),
+        files.changes.len(),
+        files.iteration_label(),
+        search_indicator
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(border_color))
+        .title(title);
+
+    if visible.is_empty() {
+        let msg = if files.loading {
+            "Loading changed files..."
+        } else if !search_query.is_empty() {
+            "No matches. Press Esc to clear search."
+        } else {
+            "No changed files."
+        };
+        let items = vec![ListItem::new(format!("  {msg}"))];
+        let list = List::new(items)
+            .block(block)
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(list, area);
+        return;
+    }
+
+    let selected_idx = files.selected;
+    let items: Vec<ListItem> = visible
+        .iter()
+        .enumerate()
+        .map(|(i, change)| {
+            let selected = i == selected_idx;
+            let prefix = if selected && focused { "▸ " } else { "  " };
+
+            let marker_color = match change.marker() {
+                "A" => Color::Green,
+                "D" => Color::Red,
+                "R" => Color::Cyan,
+                _ => Color::Yellow,
+            };
+            let style = if selected && focused {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+
+            let (dir, name) = match change.path.rsplit_once('/') {
+                Some((dir, name)) => (format!("{dir}/"), name),
+                None => (String::new(), change.path.as_str()),
+            };
+            let threads = app
+                .pr_threads
+                .iter()
+                .filter(|t| t.is_visible() && t.file_path() == Some(change.path.as_str()))
+                .count();
+            let threads = if threads > 0 {
+                format!(" [{threads}]")
+            } else {
+                String::new()
+            };
+            let renamed = change
+                .original_path
+                .as_deref()
+                .map(|p| format!(" ← {p}"))
+                .unwrap_or_default();
+
+            ListItem::new(Line::from(vec![
+                Span::raw(prefix),
+                Span::styled(change.marker(), Style::default().fg(marker_color)),
+                Span::raw(" "),
+                Span::styled(dir, Style::default().fg(Color::DarkGray)),
+                Span::styled(name.to_string(), style),
+                Span::styled(threads, Style::default().fg(Color::Magenta)),
+                Span::styled(renamed, Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+
+    app.pr_file_list_state.select(Some(selected_idx));
+    let list = List::new(items).block(block);
+    f.render_stateful_widget(list, area, &mut app.pr_file_list_state);
+}
@@ -1,7 +1,13 @@
+mod complete;
+mod create;
+mod diff;
 mod list;
 mod preview;
 
-use crate::app::App;
+pub use complete::draw_complete_dialog;
+pub use create::draw_create_dialog;
+
+use crate::app::{App, PRDrillDown};
 use ratatui::prelude::*;
 
 pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
@@ -12,5 +18,14 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
         .split(area);
 
     list::draw(f, app, chunks[0]);
-    preview::draw(f, app, chunks[1]);
+    if app.pr_drill_down == PRDrillDown::Files {
+        diff::draw(f, app, chunks[1]);
+    } else {
+        preview::draw(f, app, chunks[1]);
+    }
+
+    // Abandon/reactivate/draft confirmations share the CI/CD dialog
+    if let Some(ref dialog) = app.confirm_action_dialog {
+        super::cicd::dialogs::render_confirm_action_dialog(f, dialog);
+    }
 }
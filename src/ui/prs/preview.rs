@@ -249,12 +249,7 @@ fn draw_policies(f: &mut Frame, app: &mut App, area: Rect, border_color: Color)
                 _ => ("?", Color::DarkGray),
             };
 
-            let policy_name = policy
-                .configuration
-                .as_ref()
-                .and_then(|c| c.policy_type.as_ref())
-                .and_then(|t| t.display_name.as_deref())
-                .unwrap_or("Unknown Policy");
+            let policy_name = policy.display_name();
 
             let blocking = policy
                 .configuration
@@ -305,17 +300,7 @@ fn draw_threads(f: &mut Frame, app: &mut App, area: Rect, border_color: Color) {
         .pr_threads
         .iter()
         .enumerate()
-        .filter(|(_, t)| {
-            if t.is_deleted {
-                return false;
-            }
-            // Filter out threads that are purely system-generated (no user comments)
-            let has_user_comment = t
-                .comments
-                .iter()
-                .any(|c| c.comment_type.as_deref() != Some("system"));
-            has_user_comment
-        })
+        .filter(|(_, t)| t.is_visible())
         .collect();
 
     let block = Block::default()
@@ -0,0 +1,146 @@
+use crate::app::App;
+use crate::board::{BoardLayout, KanbanBoard};
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+/// Kanban board: one bordered column per board column, swimlanes as bands
+pub fn draw(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.config.theme;
+    let (board, layout) = app.board_layout();
+    let title = match &board.name {
+        Some(name) => format!("Board: {name}"),
+        None => "Board: states".to_string(),
+    };
+    let block = crate::ui::styled_block(&title, true, theme);
+
+    if board.columns.is_empty() || app.visible_items.is_empty() {
+        let empty = Paragraph::new("No work items in this sprint")
+            .block(block)
+            .style(Style::default().fg(theme.parse_color(&theme.text_muted)));
+        f.render_widget(empty, area);
+        return;
+    }
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            board
+                .columns
+                .iter()
+                .map(|_| Constraint::Ratio(1, board.columns.len() as u32)),
+        )
+        .split(inner);
+    for (col, column_area) in columns.iter().enumerate() {
+        draw_column(f, app, &board, &layout, col, *column_area);
+    }
+}
+
+fn draw_column(
+    f: &mut Frame,
+    app: &App,
+    board: &KanbanBoard,
+    layout: &BoardLayout,
+    col: usize,
+    area: Rect,
+) {
+    let theme = &app.config.theme;
+    let column = &board.columns[col];
+    let count = layout.counts[col];
+    let selected = app.work_item_list_state.selected();
+
+    let header = if column.item_limit > 0 {
+        format!(" {} {count}/{} ", column.name, column.item_limit)
+    } else {
+        format!(" {} {count} ", column.name)
+    };
+    let has_selection = layout
+        .column_cards(col)
+        .iter()
+        .any(|c| Some(*c) == selected);
+    let (border, header_style) = if column.over_limit(count) {
+        let red = Color::Rgb(224, 108, 117);
+        (red, Style::default().fg(red).add_modifier(Modifier::BOLD))
+    } else if has_selection {
+        let active = theme.parse_color(&theme.border_active);
+        (active, Style::default().fg(active))
+    } else {
+        let color = theme.parse_color(&theme.border);
+        (color, Style::default().fg(theme.parse_color(&theme.text)))
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(border))
+        .title(Span::styled(header, header_style));
+    let inner = block.inner(area);
+    let width = inner.width as usize;
+
+    let muted = Style::default().fg(theme.parse_color(&theme.text_muted));
+    let mut lines: Vec<Line> = Vec::new();
+    let mut selected_line = None;
+    for (lane, name) in board.lanes.iter().enumerate() {
+        // The default lane only gets a header when there are others
+        if board.lanes.len() > 1 {
+            let collapsed = app.collapsed_lanes.contains(name);
+            let name = if name.is_empty() { "Default" } else { name };
+            let header = if collapsed {
+                format!("▶ {name}")
+            } else {
+                format!("▼ {name} ({})", layout.cells[lane][col].len())
+            };
+            lines.push(Line::styled(
+                truncate(&header, width),
+                muted.add_modifier(Modifier::BOLD),
+            ));
+        }
+        for idx in &layout.cells[lane][col] {
+            let Some(entry) = app.visible_items.get(*idx) else {
+                continue;
+            };
+            let item = &entry.item;
+            let is_selected = selected == Some(*idx);
+            if is_selected {
+                selected_line = Some(lines.len());
+            }
+            let id = format!("#{}", item.id);
+            let (id_fg, id_bg) = theme.type_badge_colors(&item.fields.work_item_type);
+            let title_width = width.saturating_sub(id.chars().count() + 4);
+            let line = Line::from(vec![
+                Span::styled(
+                    format!("{} ", item.type_icon()),
+                    Style::default().fg(theme.type_color(&item.fields.work_item_type)),
+                ),
+                Span::styled(id, Style::default().fg(id_fg).bg(id_bg)),
+                Span::raw(" "),
+                Span::raw(truncate(&item.fields.title, title_width)),
+            ]);
+            lines.push(if is_selected {
+                line.style(Style::default().bg(theme.parse_color(&theme.selected_bg)))
+            } else {
+                line
+            });
+        }
+    }
+
+    // Keep the selected card in view
+    let height = inner.height as usize;
+    let scroll = selected_line
+        .map(|line| (line + 1).saturating_sub(height))
+        .unwrap_or(0);
+    f.render_widget(
+        Paragraph::new(lines)
+            .scroll((scroll as u16, 0))
+            .block(block),
+        area,
+    );
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    if s.chars().count() > max_chars {
+        let truncated: String = s.chars().take(max_chars.saturating_sub(1)).collect();
+        format!("{truncated}…")
+    } else {
+        s.to_string()
+    }
+}
@@ -0,0 +1,127 @@
+use crate::app::App;
+use crate::capacity::CapacityPlan;
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+/// Team capacity: hours left per member against their remaining work
+pub fn draw(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.config.theme;
+    let Some(sprint) = app.selected_sprint() else {
+        return;
+    };
+    let muted = Style::default().fg(theme.parse_color(&theme.text_muted));
+
+    let popup = super::super::centered_rect(90, 24, area);
+    f.render_widget(Clear, popup);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(format!(" Capacity - {} ", sprint.name));
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Members
+            Constraint::Length(1), // Hints
+        ])
+        .split(inner);
+
+    let message = match &app.team_capacity {
+        _ if app.capacity_rx.is_some() => Some("Loading capacity...".to_string()),
+        Some((_, Err(e))) => Some(format!("Could not load capacity: {e}")),
+        _ => None,
+    };
+    let lines = match (message, app.capacity_plan()) {
+        (Some(message), _) => vec![Line::styled(message, muted)],
+        (None, Some(plan)) => plan_lines(app, &plan),
+        (None, None) => vec![Line::styled("No capacity: the sprint has no dates", muted)],
+    };
+    f.render_widget(Paragraph::new(lines), rows[0].inner(Margin::new(1, 0)));
+
+    f.render_widget(
+        Paragraph::new("r:reload capacity  K/Esc:close").style(muted),
+        rows[1],
+    );
+}
+
+fn plan_lines(app: &App, plan: &CapacityPlan) -> Vec<Line<'static>> {
+    let theme = &app.config.theme;
+    let muted = Style::default().fg(theme.parse_color(&theme.text_muted));
+    let heading = Style::default().add_modifier(Modifier::BOLD);
+    let over = Style::default().fg(Color::Red);
+    let mut lines = Vec::new();
+
+    let mut summary = format!(
+        "{} - {}  ·  {} working days left",
+        plan.from.format("%b %d"),
+        plan.finish.format("%b %d"),
+        plan.team_days
+    );
+    if plan.team_days_off > 0 {
+        summary.push_str(&format!(" ({} team days off)", plan.team_days_off));
+    }
+    lines.push(Line::styled(summary, muted));
+    lines.push(Line::raw(""));
+
+    lines.push(Line::styled(
+        format!(
+            "{:<24}{:>8}{:>6}{:>9}{:>10}{:>11}{:>7}  Activities",
+            "Member", "h/day", "Days", "Days off", "Available", "Remaining", "Load"
+        ),
+        heading,
+    ));
+    for member in &plan.members {
+        let name: String = member.display_name.chars().take(22).collect();
+        let (marker, style) = if member.overloaded() {
+            ("▲ ", over)
+        } else {
+            ("  ", Style::default())
+        };
+        let ratio = member.ratio();
+        let load = if ratio.is_finite() {
+            format!("{:.0}%", ratio * 100.0)
+        } else {
+            "-".to_string()
+        };
+        let activities = if member.has_capacity {
+            member.activities.join(", ")
+        } else {
+            "No capacity set".to_string()
+        };
+        lines.push(Line::from(vec![
+            Span::styled(marker, style),
+            Span::styled(format!("{name:<22}"), style),
+            Span::raw(format!(
+                "{:>8.1}{:>6}{:>9}{:>10.1}",
+                member.capacity_per_day, member.days, member.days_off, member.available
+            )),
+            Span::styled(format!("{:>11.1}{load:>7}", member.assigned), style),
+            Span::styled(format!("  {activities}"), muted),
+        ]));
+    }
+    if plan.unassigned > 0.0 {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<22}", "Unassigned"), muted),
+            Span::raw(format!("{:>33}{:>11.1}", "", plan.unassigned)),
+        ]));
+    }
+
+    lines.push(Line::raw(""));
+    let total = format!(
+        "Team: {:.1}h available, {:.1}h remaining",
+        plan.available(),
+        plan.assigned()
+    );
+    let overloaded = plan.overloaded();
+    if overloaded > 0 {
+        lines.push(Line::from(vec![
+            Span::styled(total, heading),
+            Span::styled(format!("  ·  {overloaded} overloaded"), over),
+        ]));
+    } else {
+        lines.push(Line::styled(total, heading));
+    }
+    lines
+}
@@ -0,0 +1,230 @@
+use crate::app::App;
+use crate::azure::WorkItem;
+use crate::work_item_create::{WorkItemCreateDialog, WorkItemCreateField};
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap};
+
+/// Work item creation dialog: fields on the left, pickers/preview on the right
+pub fn draw(f: &mut Frame, app: &App, area: Rect) {
+    let Some(dialog) = &app.work_item_create_dialog else {
+        return;
+    };
+    let theme = &app.config.theme;
+
+    let popup = super::super::centered_rect(100, 30, area);
+    f.render_widget(Clear, popup);
+    let title = match &dialog.parent {
+        Some(parent) => format!(
+            " New {} under #{} {} ",
+            dialog.work_item_type, parent.id, parent.title
+        ),
+        None => format!(" New {} ", dialog.work_item_type),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(title);
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(rows[0]);
+
+    let fields = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Type
+            Constraint::Min(5),    // Titles
+            Constraint::Length(3), // Assignee
+            Constraint::Length(3), // Estimate
+            Constraint::Length(3), // Tags
+            Constraint::Length(3), // Iteration
+            Constraint::Length(3), // Parent
+        ])
+        .split(columns[0]);
+
+    let areas = [
+        (WorkItemCreateField::Type, fields[0]),
+        (WorkItemCreateField::Titles, fields[1]),
+        (WorkItemCreateField::Assignee, fields[2]),
+        (WorkItemCreateField::Estimate, fields[3]),
+        (WorkItemCreateField::Tags, fields[4]),
+        (WorkItemCreateField::Iteration, fields[5]),
+        (WorkItemCreateField::Parent, fields[6]),
+    ];
+    for (field, field_area) in areas {
+        let focused = dialog.field == field;
+        let block = super::super::styled_block(field.label(), focused, theme);
+        let text_area = block.inner(field_area);
+
+        let (text, style) = field_text(dialog, field);
+        let paragraph = Paragraph::new(text).style(style).block(block);
+        let paragraph = if field == WorkItemCreateField::Titles {
+            paragraph.wrap(Wrap { trim: false })
+        } else {
+            paragraph
+        };
+        f.render_widget(paragraph, field_area);
+
+        if focused {
+            set_cursor(f, dialog, text_area);
+        }
+    }
+
+    draw_side_panel(f, app, dialog, columns[1]);
+
+    let hint = match dialog.validate() {
+        Some(problem) => format!("{problem}  |  Tab:field  ↑/↓:select  Esc:cancel"),
+        None => {
+            let count = dialog.titles().len();
+            format!(
+                "Tab/Shift+Tab:field  ↑/↓:select  Enter:new line/pick  Ctrl+s:create {count} item{}  Esc:cancel",
+                if count == 1 { "" } else { "s" }
+            )
+        }
+    };
+    f.render_widget(
+        Paragraph::new(hint).style(Style::default().fg(Color::DarkGray)),
+        rows[1],
+    );
+}
+
+/// Text shown in a field, with its style
+fn field_text(dialog: &WorkItemCreateDialog, field: WorkItemCreateField) -> (String, Style) {
+    let focused = dialog.field == field;
+    if focused && field == WorkItemCreateField::Assignee && !dialog.query.is_empty() {
+        return (
+            format!("🔍 {}", dialog.query),
+            Style::default().fg(Color::Yellow),
+        );
+    }
+
+    let value = match field {
+        WorkItemCreateField::Type => format!(
+            "{} {}",
+            WorkItem::icon_for_type(dialog.work_item_type),
+            dialog.work_item_type
+        ),
+        WorkItemCreateField::Titles => dialog.titles.clone(),
+        WorkItemCreateField::Assignee => dialog
+            .assignee
+            .as_ref()
+            .map(|u| u.display_name.clone())
+            .unwrap_or_default(),
+        WorkItemCreateField::Estimate => dialog.estimate.clone(),
+        WorkItemCreateField::Tags => dialog.tags.clone(),
+        WorkItemCreateField::Iteration => dialog.iteration.clone(),
+        WorkItemCreateField::Parent => dialog
+            .parent
+            .as_ref()
+            .map(|p| format!("#{} {}", p.id, p.title))
+            .unwrap_or_default(),
+    };
+    if value.is_empty() && !focused {
+        let placeholder = match field {
+            WorkItemCreateField::Assignee => "(unassigned)",
+            WorkItemCreateField::Parent => "(none)",
+            _ => "",
+        };
+        return (placeholder.into(), Style::default().fg(Color::DarkGray));
+    }
+    (value, Style::default().fg(Color::White))
+}
+
+/// Place the terminal cursor at the end of the focused field's input
+fn set_cursor(f: &mut Frame, dialog: &WorkItemCreateDialog, area: Rect) {
+    let text = match dialog.field {
+        WorkItemCreateField::Titles => dialog.titles.as_str(),
+        WorkItemCreateField::Estimate => dialog.estimate.as_str(),
+        WorkItemCreateField::Tags => dialog.tags.as_str(),
+        WorkItemCreateField::Iteration => dialog.iteration.as_str(),
+        WorkItemCreateField::Assignee if !dialog.query.is_empty() => {
+            let col = 3 + dialog.query.chars().count();
+            f.set_cursor_position(Position::new(
+                (area.x + col as u16).min(area.right().saturating_sub(1)),
+                area.y,
+            ));
+            return;
+        }
+        _ => return,
+    };
+    let line = text.split('\n').count().saturating_sub(1);
+    let col = text.rsplit('\n').next().unwrap_or("").chars().count();
+    f.set_cursor_position(Position::new(
+        (area.x + col as u16).min(area.right().saturating_sub(1)),
+        (area.y + line as u16).min(area.bottom().saturating_sub(1)),
+    ));
+}
+
+/// Types or assignees to pick from, otherwise the items that will be created
+fn draw_side_panel(f: &mut Frame, app: &App, dialog: &WorkItemCreateDialog, area: Rect) {
+    let theme = &app.config.theme;
+    let highlight = Style::default().bg(theme.parse_color(&theme.selected_bg));
+
+    match dialog.field {
+        WorkItemCreateField::Type => {
+            let block = super::super::styled_block("Type - ↑/↓:change", true, theme);
+            let items: Vec<ListItem> = WorkItem::TYPES
+                .iter()
+                .map(|t| {
+                    ListItem::new(format!(" {} {t}", WorkItem::icon_for_type(t)))
+                        .style(Style::default().fg(theme.type_color(t)))
+                })
+                .collect();
+            let selected = WorkItem::TYPES
+                .iter()
+                .position(|t| *t == dialog.work_item_type);
+            let mut state = ListState::default().with_selected(selected);
+            f.render_stateful_widget(
+                List::new(items).block(block).highlight_style(highlight),
+                area,
+                &mut state,
+            );
+        }
+        WorkItemCreateField::Assignee => {
+            let block = super::super::styled_block("Assign to", true, theme);
+            let candidates = dialog.assignee_candidates(&app.users);
+            if candidates.is_empty() {
+                let paragraph = Paragraph::new("No matches - type an email to assign anyone")
+                    .style(Style::default().fg(Color::DarkGray))
+                    .wrap(Wrap { trim: false })
+                    .block(block);
+                f.render_widget(paragraph, area);
+                return;
+            }
+            let items: Vec<ListItem> = candidates
+                .iter()
+                .map(|u| ListItem::new(format!("{} <{}>", u.display_name, u.unique_name)))
+                .collect();
+            let mut state =
+                ListState::default().with_selected(Some(dialog.cursor.min(candidates.len() - 1)));
+            f.render_stateful_widget(
+                List::new(items).block(block).highlight_style(highlight),
+                area,
+                &mut state,
+            );
+        }
+        _ => {
+            let block = super::super::styled_block("Will create", false, theme);
+            let icon = WorkItem::icon_for_type(dialog.work_item_type);
+            let color = theme.type_color(dialog.work_item_type);
+            let items: Vec<ListItem> = dialog
+                .titles()
+                .into_iter()
+                .map(|title| {
+                    ListItem::new(Line::from(vec![
+                        Span::styled(format!(" {icon} "), Style::default().fg(color)),
+                        Span::raw(title.to_string()),
+                    ]))
+                })
+                .collect();
+            f.render_widget(List::new(items).block(block), area);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use crate::app::App;
+use crate::work_item_form::FieldKind;
+use ratatui::prelude::*;
+use ratatui::widgets::{List, ListItem, ListState, Paragraph, Wrap};
+
+/// Editable fields form replacing the Details tab while a work item is being edited
+pub fn draw(f: &mut Frame, app: &App, area: Rect, focused: bool) {
+    let Some(form) = &app.work_item_form else {
+        return;
+    };
+    let theme = &app.config.theme;
+    let muted = theme.parse_color(&theme.text_muted);
+
+    let title = format!("Edit #{}", form.id);
+    let block = crate::ui::styled_block(&title, focused, theme);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(form.fields.len() as u16 + 1), // Fields
+            Constraint::Min(0),                               // Markdown preview
+            Constraint::Length(1),                            // Hints
+        ])
+        .split(inner);
+
+    let label_width = form
+        .fields
+        .iter()
+        .map(|field| field.label.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let items: Vec<ListItem> = form
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let selected = i == form.selected;
+            let marker = if field.is_dirty() {
+                Span::styled("● ", Style::default().fg(Color::Yellow))
+            } else {
+                Span::raw("  ")
+            };
+            let label_style = if selected {
+                Style::default()
+                    .fg(theme.parse_color(&theme.highlight))
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(muted)
+            };
+            let label = Span::styled(format!("{:<label_width$} ", field.label), label_style);
+
+            let value = if field.kind == FieldKind::Html {
+                let mut lines = field.value.lines().filter(|l| !l.trim().is_empty());
+                match (lines.next(), lines.next()) {
+                    (None, _) => Span::styled(
+                        "(empty) - Enter to edit in $EDITOR",
+                        Style::default().fg(muted),
+                    ),
+                    (Some(first), None) => Span::raw(first.to_string()),
+                    (Some(first), Some(_)) => Span::raw(format!("{first} …")),
+                }
+            } else if field.value.is_empty() && !(selected && form.editing) {
+                Span::styled("-", Style::default().fg(muted))
+            } else {
+                Span::raw(field.value.clone())
+            };
+
+            let style = if selected {
+                Style::default().bg(theme.parse_color(&theme.selected_bg))
+            } else {
+                Style::default()
+            };
+            ListItem::new(Line::from(vec![marker, label, value])).style(style)
+        })
+        .collect();
+
+    let mut state = ListState::default().with_selected(Some(form.selected));
+    f.render_stateful_widget(List::new(items), chunks[0], &mut state);
+
+    if form.editing {
+        if let Some(field) = form.selected_field() {
+            let row = form.selected.saturating_sub(state.offset()) as u16;
+            let col = (2 + label_width + 1 + field.value.chars().count()) as u16;
+            f.set_cursor_position(Position::new(
+                (chunks[0].x + col).min(chunks[0].right().saturating_sub(1)),
+                chunks[0].y + row,
+            ));
+        }
+    }
+
+    // Full Markdown of the selected rich text field (or the description)
+    let html_field = form
+        .selected_field()
+        .filter(|field| field.kind == FieldKind::Html)
+        .or_else(|| {
+            form.fields
+                .iter()
+                .find(|field| field.kind == FieldKind::Html)
+        });
+    if let Some(field) = html_field {
+        let lines: Vec<Line> = std::iter::once(Line::styled(
+            format!("{} (Markdown)", field.label),
+            Style::default().fg(muted).add_modifier(Modifier::BOLD),
+        ))
+        .chain(field.value.lines().map(|l| Line::raw(l.to_string())))
+        .collect();
+        f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), chunks[1]);
+    }
+
+    let hint = if form.editing {
+        "Enter:done  Esc:undo  Ctrl+s:save"
+    } else {
+        "j/k:field  Enter:edit  Ctrl+s:save  Esc:discard"
+    };
+    f.render_widget(
+        Paragraph::new(hint).style(Style::default().fg(muted)),
+        chunks[2],
+    );
+}
@@ -0,0 +1,89 @@
+use crate::app::App;
+use crate::work_item_link::{LinkKind, LinkTarget};
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+
+/// Link picker: link kinds across the top, search, then matching targets
+pub fn draw(f: &mut Frame, app: &App, area: Rect) {
+    let Some(dialog) = &app.link_dialog else {
+        return;
+    };
+    let theme = &app.config.theme;
+    let muted = Style::default().fg(theme.parse_color(&theme.text_muted));
+
+    let popup = super::super::centered_rect(80, 20, area);
+    f.render_widget(Clear, popup);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(format!(" Link #{} ", dialog.work_item_id));
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Kinds
+            Constraint::Length(1), // Search
+            Constraint::Min(0),    // Candidates
+            Constraint::Length(1), // Hints
+        ])
+        .split(inner);
+
+    let mut kinds = Vec::new();
+    for kind in LinkKind::ALL {
+        let style = if kind == dialog.kind {
+            Style::default()
+                .fg(theme.parse_color(&theme.highlight))
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else {
+            muted
+        };
+        kinds.push(Span::styled(format!(" {} ", kind.label()), style));
+        kinds.push(Span::raw(" "));
+    }
+    f.render_widget(Paragraph::new(Line::from(kinds)), rows[0]);
+
+    let search = if dialog.query.is_empty() {
+        Line::styled(dialog.kind.placeholder(), muted)
+    } else {
+        Line::styled(
+            format!("🔍 {}", dialog.query),
+            Style::default().fg(Color::Yellow),
+        )
+    };
+    f.render_widget(Paragraph::new(search), rows[1]);
+
+    let candidates = app.link_candidates();
+    if candidates.is_empty() {
+        let hint = match dialog.kind {
+            LinkKind::Commit if dialog.repositories.is_empty() => "No repositories to look in",
+            LinkKind::Commit => "Type at least 7 characters of the hash",
+            _ => "No matches",
+        };
+        f.render_widget(Paragraph::new(Line::styled(hint, muted)), rows[2]);
+    } else {
+        let items: Vec<ListItem> = candidates
+            .iter()
+            .map(|target| match target {
+                LinkTarget::NoParent(_) => ListItem::new(Line::styled(
+                    format!(" {}", target.label()),
+                    Style::default().fg(Color::Red),
+                )),
+                _ => ListItem::new(format!(" {}", target.label())),
+            })
+            .collect();
+        let mut state = ListState::default().with_selected(Some(dialog.cursor));
+        f.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::default().bg(theme.parse_color(&theme.selected_bg))),
+            rows[2],
+            &mut state,
+        );
+    }
+
+    f.render_widget(
+        Paragraph::new("Tab/S-Tab:link type  ↑/↓:select  Enter:link  Esc:close").style(muted),
+        rows[3],
+    );
+}
@@ -1,7 +1,20 @@
+mod board;
+mod capacity;
+mod create;
+mod form;
+mod link;
 mod preview;
+mod queries;
 mod sprint_bar;
+mod sprint_stats;
 mod work_items;
 
+pub use board::draw as draw_board;
+pub use capacity::draw as draw_team_capacity;
+pub use create::draw as draw_create_dialog;
+pub use link::draw as draw_link_dialog;
 pub use preview::draw as draw_preview;
+pub use queries::draw as draw_queries;
 pub use sprint_bar::draw as draw_sprint_bar;
+pub use sprint_stats::draw as draw_sprint_stats;
 pub use work_items::draw as draw_work_items;
@@ -1,4 +1,5 @@
 use crate::app::{App, Focus, PreviewTab};
+use crate::history::HistoryChange;
 use ratatui::prelude::*;
 use ratatui::widgets::{
     Block, Borders, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState,
@@ -24,16 +25,23 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
     let content_area = chunks[1];
 
     match app.preview_tab {
+        PreviewTab::Details if app.work_item_form.is_some() => {
+            super::form::draw(f, app, content_area, focused)
+        }
         PreviewTab::Details => draw_details(f, app, content_area, focused),
         PreviewTab::References => draw_references(f, app, content_area, focused),
+        PreviewTab::Discussion => draw_discussion(f, app, content_area, focused),
+        PreviewTab::History => draw_history(f, app, content_area, focused),
     }
 }
 
 fn draw_tabs(f: &mut Frame, app: &App, area: Rect, focused: bool) {
-    let titles = vec!["Details", "References"];
+    let titles = vec!["Details", "References", "Discussion", "History"];
     let selected = match app.preview_tab {
         PreviewTab::Details => 0,
         PreviewTab::References => 1,
+        PreviewTab::Discussion => 2,
+        PreviewTab::History => 3,
     };
 
     let tabs = Tabs::new(titles)
@@ -172,6 +180,25 @@ fn draw_details(f: &mut Frame, app: &mut App, area: Rect, focused: bool) {
         ]));
     }
 
+    // Parent, which may be outside the sprint
+    if let Some(parent_id) = item.fields.parent_id {
+        let parent = match app.find_work_item_title(parent_id) {
+            Some(title) => format!(" #{parent_id} {title} "),
+            None => format!(" #{parent_id} "),
+        };
+        let parent_bg = Color::Rgb(50, 40, 60);
+        lines.push(Line::from(vec![
+            Span::styled(" Parent", Style::default().fg(label_fg).bg(label_bg)),
+            Span::styled("\u{e0b4} ", Style::default().fg(label_bg)),
+            Span::styled("\u{e0b6}", Style::default().fg(parent_bg)),
+            Span::styled(
+                parent,
+                Style::default().fg(Color::Rgb(210, 190, 240)).bg(parent_bg),
+            ),
+            Span::styled("\u{e0b4}", Style::default().fg(parent_bg)),
+        ]));
+    }
+
     // Remaining work / Hours
     if let Some(hours) = item.fields.remaining_work {
         let estimate_bg = Color::Rgb(55, 45, 25);
@@ -476,3 +503,254 @@ fn draw_references(f: &mut Frame, app: &mut App, area: Rect, focused: bool) {
     let list = List::new(visible_items);
     f.render_widget(list, inner);
 }
+
+fn draw_discussion(f: &mut Frame, app: &mut App, area: Rect, focused: bool) {
+    let block = crate::ui::styled_block("", focused, &app.config.theme);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let theme = &app.config.theme;
+    let muted = Style::default().fg(theme.parse_color(&theme.text_muted));
+    let Some(id) = app.selected_work_item().map(|vi| vi.item.id) else {
+        f.render_widget(Paragraph::new("Select a work item").style(muted), inner);
+        return;
+    };
+    let comments = match app.work_item_comments.get(&id) {
+        Some(Ok(comments)) if comments.is_empty() => {
+            let empty = Paragraph::new("No comments yet. Press C to add one.").style(muted);
+            f.render_widget(empty, inner);
+            return;
+        }
+        Some(Ok(comments)) => comments,
+        Some(Err(e)) => {
+            let error = Paragraph::new(format!("Could not load comments: {e}"))
+                .style(Style::default().fg(Color::Red))
+                .wrap(Wrap { trim: false });
+            f.render_widget(error, inner);
+            return;
+        }
+        None => {
+            f.render_widget(Paragraph::new("Loading...").style(muted), inner);
+            return;
+        }
+    };
+
+    let selected = app.selected_comment_index();
+    let marker = Style::default().fg(theme.parse_color(&theme.highlight));
+    // Bodies are wrapped here, so the line count is exact for scrolling
+    let width = (inner.width as usize).saturating_sub(4).max(20);
+    let mut lines: Vec<Line> = Vec::new();
+    let mut selected_span = (0, 0);
+    for (idx, comment) in comments.iter().enumerate() {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        let start = lines.len();
+        let author = comment
+            .created_by
+            .as_ref()
+            .and_then(|by| by.display_name.clone().or_else(|| by.unique_name.clone()))
+            .unwrap_or_else(|| "Unknown".to_string());
+        let mut header = vec![Span::styled(
+            author,
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        )];
+        if crate::discussion::is_own(comment, app.current_user.as_deref()) {
+            header.push(Span::styled(" (you)", muted));
+        }
+        if let Some(date) = comment.created_date {
+            header.push(Span::styled(
+                format!("  {}", date.format("%d.%m.%Y %H:%M")),
+                muted,
+            ));
+        }
+        if comment.is_edited() {
+            header.push(Span::styled(
+                "  (edited)",
+                muted.add_modifier(Modifier::ITALIC),
+            ));
+        }
+        lines.push(Line::from(header));
+        let body = html2text::from_read(comment.text.as_bytes(), width);
+        for line in body.trim_end().lines() {
+            lines.push(Line::raw(format!("  {line}")));
+        }
+
+        let bar = if focused && idx == selected {
+            "▌ "
+        } else {
+            "  "
+        };
+        for line in &mut lines[start..] {
+            line.spans.insert(0, Span::styled(bar, marker));
+        }
+        if idx == selected {
+            selected_span = (start as u16, lines.len() as u16);
+        }
+    }
+
+    // Keep the highlighted comment in view, its header first
+    let (first, end) = selected_span;
+    if end > app.preview_scroll + inner.height {
+        app.preview_scroll = end.saturating_sub(inner.height);
+    }
+    app.preview_scroll = app.preview_scroll.min(first);
+    app.preview_scroll_max = (lines.len() as u16).saturating_sub(inner.height);
+    let paragraph = Paragraph::new(lines).scroll((app.preview_scroll, 0));
+    f.render_widget(paragraph, inner);
+
+    if app.preview_scroll_max > 0 {
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("↑"))
+            .end_symbol(Some("↓"));
+        let mut scrollbar_state = ScrollbarState::new(app.preview_scroll_max as usize)
+            .position(app.preview_scroll as usize);
+        f.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
+    }
+}
+
+fn draw_history(f: &mut Frame, app: &mut App, area: Rect, focused: bool) {
+    let block = crate::ui::styled_block("", focused, &app.config.theme);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let theme = &app.config.theme;
+    let muted = Style::default().fg(theme.parse_color(&theme.text_muted));
+    let Some(id) = app.selected_work_item().map(|vi| vi.item.id) else {
+        f.render_widget(Paragraph::new("Select a work item").style(muted), inner);
+        return;
+    };
+    let entries = match app.work_item_history.get(&id) {
+        Some(Ok(entries)) if entries.is_empty() => {
+            f.render_widget(Paragraph::new("No history").style(muted), inner);
+            return;
+        }
+        Some(Ok(entries)) => entries,
+        Some(Err(e)) => {
+            let error = Paragraph::new(format!("Could not load history: {e}"))
+                .style(Style::default().fg(Color::Red))
+                .wrap(Wrap { trim: false });
+            f.render_widget(error, inner);
+            return;
+        }
+        None => {
+            f.render_widget(Paragraph::new("Loading...").style(muted), inner);
+            return;
+        }
+    };
+
+    let added = Style::default().fg(Color::Green);
+    let removed = Style::default().fg(Color::Red);
+    let label = Style::default().fg(Color::Rgb(180, 180, 180));
+    let value = |v: &Option<String>| v.clone().unwrap_or_else(|| "(none)".to_string());
+    let change_line = |name: &str, from: &Option<String>, to: &Option<String>| {
+        let mut spans = vec![Span::styled(format!("  {name}: "), label)];
+        if from.is_some() {
+            spans.push(Span::styled(value(from), removed));
+            spans.push(Span::styled(" → ", muted));
+        }
+        spans.push(Span::styled(value(to), added));
+        Line::from(spans)
+    };
+
+    let mut lines: Vec<Line> = Vec::new();
+    for entry in entries {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        let mut header = vec![
+            Span::styled(
+                "● ",
+                Style::default().fg(theme.parse_color(&theme.highlight)),
+            ),
+            Span::styled(
+                entry.author.clone(),
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ];
+        if let Some(date) = entry.date {
+            header.push(Span::styled(
+                format!("  {}", date.format("%d.%m.%Y %H:%M")),
+                muted,
+            ));
+        }
+        if entry.created {
+            header.push(Span::styled(
+                "  created",
+                muted.add_modifier(Modifier::ITALIC),
+            ));
+        }
+        lines.push(Line::from(header));
+
+        for change in &entry.changes {
+            match change {
+                HistoryChange::State { from, to } => {
+                    let mut line = change_line("State", from, to);
+                    if let Some(state) = to {
+                        line.spans.last_mut().unwrap().style =
+                            Style::default().fg(theme.state_color(state));
+                    }
+                    lines.push(line);
+                }
+                HistoryChange::Assigned { from, to } => {
+                    lines.push(change_line("Assigned", from, to))
+                }
+                HistoryChange::Field { name, from, to } => lines.push(change_line(name, from, to)),
+                HistoryChange::Text { name, lines: diff } => {
+                    lines.push(Line::styled(format!("  {name}:"), label));
+                    for (is_added, text) in diff {
+                        let (sign, style) = if *is_added {
+                            ("+", added)
+                        } else {
+                            ("-", removed)
+                        };
+                        lines.push(Line::styled(format!("    {sign} {text}"), style));
+                    }
+                }
+                HistoryChange::Comment(text) => {
+                    lines.push(Line::styled("  Comment:", label));
+                    for line in text.lines() {
+                        lines.push(Line::raw(format!("    {line}")));
+                    }
+                }
+                HistoryChange::Link {
+                    added: is_added,
+                    relation,
+                } => {
+                    let parsed = app.parse_relation(relation);
+                    let (sign, style) = if *is_added {
+                        ("+", added)
+                    } else {
+                        ("-", removed)
+                    };
+                    let kind = relation.attributes.name.as_deref().unwrap_or("Link");
+                    lines.push(Line::from(vec![
+                        Span::styled(format!("  {sign} {kind}: "), style),
+                        Span::styled(format!("{} ", parsed.icon), label),
+                        Span::raw(parsed.description),
+                    ]));
+                }
+            }
+        }
+    }
+
+    // Wrapped lines are not counted; long values may scroll a little short
+    app.preview_scroll_max = (lines.len() as u16).saturating_sub(inner.height);
+    let paragraph = Paragraph::new(lines)
+        .scroll((app.preview_scroll, 0))
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, inner);
+
+    if app.preview_scroll_max > 0 {
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("↑"))
+            .end_symbol(Some("↓"));
+        let mut scrollbar_state = ScrollbarState::new(app.preview_scroll_max as usize)
+            .position(app.preview_scroll as usize);
+        f.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
+    }
+}
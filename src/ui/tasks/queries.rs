@@ -0,0 +1,145 @@
+use crate::app::{App, InputMode};
+use crate::query_browser::{QueryBrowser, QueryRow, WiqlEditor, WiqlField};
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap};
+
+/// Queries picker, or the WIQL editor opened from it
+pub fn draw(f: &mut Frame, app: &App, area: Rect) {
+    let Some(browser) = &app.query_browser else {
+        return;
+    };
+    match (&browser.editor, app.input_mode) {
+        (Some(editor), InputMode::QueryEditor) => draw_editor(f, app, editor, area),
+        _ => draw_browser(f, app, browser, area),
+    }
+}
+
+fn draw_browser(f: &mut Frame, app: &App, browser: &QueryBrowser, area: Rect) {
+    let theme = &app.config.theme;
+    let muted = Style::default().fg(theme.parse_color(&theme.text_muted));
+
+    let popup = super::super::centered_rect(80, 24, area);
+    f.render_widget(Clear, popup);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Queries ");
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Filter
+            Constraint::Min(0),    // Tree
+            Constraint::Length(1), // Hints
+        ])
+        .split(inner);
+
+    let filter = if browser.filter.is_empty() {
+        Line::styled("Type to filter", muted)
+    } else {
+        Line::styled(
+            format!("🔍 {}", browser.filter),
+            Style::default().fg(Color::Yellow),
+        )
+    };
+    f.render_widget(Paragraph::new(filter), rows[0]);
+
+    let items: Vec<ListItem> = browser
+        .rows()
+        .into_iter()
+        .map(|row| match row {
+            QueryRow::NewQuery => ListItem::new(Line::styled(
+                " + New WIQL query",
+                Style::default().fg(theme.parse_color(&theme.highlight)),
+            )),
+            // Filtered results are flat, so show where they live
+            QueryRow::Entry(entry) if !browser.filter.is_empty() => {
+                ListItem::new(format!(" {}", entry.path))
+            }
+            QueryRow::Entry(entry) if entry.is_folder() => ListItem::new(Line::styled(
+                format!(" {}📁 {}", "  ".repeat(entry.depth), entry.name),
+                muted.add_modifier(Modifier::BOLD),
+            )),
+            QueryRow::Entry(entry) => {
+                ListItem::new(format!(" {}  {}", "  ".repeat(entry.depth), entry.name))
+            }
+        })
+        .collect();
+    let mut state = ListState::default().with_selected(Some(browser.cursor));
+    f.render_stateful_widget(
+        List::new(items)
+            .highlight_style(Style::default().bg(theme.parse_color(&theme.selected_bg))),
+        rows[1],
+        &mut state,
+    );
+
+    f.render_widget(
+        Paragraph::new("↑/↓:select  Enter:run  Ctrl+e:edit WIQL  Esc:close").style(muted),
+        rows[2],
+    );
+}
+
+fn draw_editor(f: &mut Frame, app: &App, editor: &WiqlEditor, area: Rect) {
+    let theme = &app.config.theme;
+
+    let popup = super::super::centered_rect(90, 20, area);
+    f.render_widget(Clear, popup);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" WIQL query ");
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Name
+            Constraint::Min(3),    // WIQL
+            Constraint::Length(1), // Hints
+        ])
+        .split(inner);
+
+    let name_block = super::super::styled_block(
+        "Name (saves to config when set)",
+        editor.field == WiqlField::Name,
+        theme,
+    );
+    let name_area = name_block.inner(rows[0]);
+    f.render_widget(
+        Paragraph::new(editor.name.as_str()).block(name_block),
+        rows[0],
+    );
+
+    let wiql_block = super::super::styled_block("WIQL", editor.field == WiqlField::Wiql, theme);
+    let wiql_area = wiql_block.inner(rows[1]);
+    f.render_widget(
+        Paragraph::new(editor.wiql.as_str())
+            .wrap(Wrap { trim: false })
+            .block(wiql_block),
+        rows[1],
+    );
+
+    let (text, text_area) = match editor.field {
+        WiqlField::Name => (editor.name.as_str(), name_area),
+        WiqlField::Wiql => (editor.wiql.as_str(), wiql_area),
+    };
+    let line = text.split('\n').count().saturating_sub(1);
+    let col = text.rsplit('\n').next().unwrap_or("").chars().count();
+    f.set_cursor_position(Position::new(
+        (text_area.x + col as u16).min(text_area.right().saturating_sub(1)),
+        (text_area.y + line as u16).min(text_area.bottom().saturating_sub(1)),
+    ));
+
+    let hint = match editor.query() {
+        Err(problem) => format!("{problem}  |  Tab:field  Esc:back"),
+        Ok(query) if query.name.is_empty() => "Tab:field  Ctrl+s:run  Esc:back".to_string(),
+        Ok(_) => "Tab:field  Ctrl+s:run & save  Esc:back".to_string(),
+    };
+    f.render_widget(
+        Paragraph::new(hint).style(Style::default().fg(Color::DarkGray)),
+        rows[2],
+    );
+}
@@ -1,6 +1,6 @@
 use crate::app::{App, View};
 use ratatui::prelude::*;
-use ratatui::widgets::{Block, Borders, Paragraph, Tabs};
+use ratatui::widgets::{Block, Borders, LineGauge, Paragraph, Tabs};
 
 pub fn draw(f: &mut Frame, app: &App, area: Rect) {
     let chunks = Layout::default()
@@ -17,7 +17,7 @@ pub fn draw(f: &mut Frame, app: &App, area: Rect) {
 
     // Middle section: Sprint selector (Tasks view) or loading status (CI/CD, PRs view)
     match app.current_view {
-        View::Tasks => draw_sprint_selector(f, app, chunks[1]),
+        View::Tasks | View::Board => draw_sprint_selector(f, app, chunks[1]),
         View::PRs => draw_pr_status(f, app, chunks[1]),
         View::CICD => draw_loading_status(f, app, chunks[1]),
     }
@@ -27,11 +27,12 @@ pub fn draw(f: &mut Frame, app: &App, area: Rect) {
 }
 
 fn draw_view_tabs(f: &mut Frame, app: &App, area: Rect) {
-    let titles = vec!["[1] Tasks", "[2] PRs", "[3] CI/CD"];
+    let titles = vec!["[1] Tasks", "[2] PRs", "[3] CI/CD", "[4] Board"];
     let selected = match app.current_view {
         View::Tasks => 0,
         View::PRs => 1,
         View::CICD => 2,
+        View::Board => 3,
     };
 
     let tabs = Tabs::new(titles)
@@ -50,14 +51,42 @@ fn draw_view_tabs(f: &mut Frame, app: &App, area: Rect) {
 }
 
 fn draw_sprint_selector(f: &mut Frame, app: &App, area: Rect) {
+    if let Some(query) = &app.active_query {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Query [Q] - I:back to sprint ");
+        let text = Paragraph::new(query.name.as_str())
+            .style(Style::default().fg(Color::Yellow))
+            .block(block);
+        f.render_widget(text, area);
+        return;
+    }
+
     let sprint_name = app
         .selected_sprint()
         .map(|s| s.name.clone())
         .unwrap_or_else(|| "No sprint".into());
 
     let block = Block::default().borders(Borders::ALL).title(" Sprint [I] ");
-    let text = Paragraph::new(sprint_name).block(block);
-    f.render_widget(text, area);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    // Share of the sprint's items that are done; B opens the full analytics
+    let progress = app.sprint_progress();
+    let gauge_width = if progress.total > 0 { 16 } else { 0 };
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(gauge_width)])
+        .split(inner);
+    f.render_widget(Paragraph::new(sprint_name), chunks[0]);
+    if progress.total > 0 {
+        let gauge = LineGauge::default()
+            .filled_style(Style::default().fg(Color::Green))
+            .unfilled_style(Style::default().fg(Color::DarkGray))
+            .ratio(progress.ratio())
+            .label(format!("{:>3}%", progress.percent()));
+        f.render_widget(gauge, chunks[1]);
+    }
 }
 
 fn draw_project_selector(f: &mut Frame, app: &App, area: Rect) {
@@ -0,0 +1,205 @@
+use crate::app::App;
+use crate::burndown::{Burndown, SprintProgress};
+use ratatui::prelude::*;
+use ratatui::symbols::Marker;
+use ratatui::widgets::{
+    Axis, Block, Borders, Chart, Clear, Dataset, Gauge, GraphType, LegendPosition, Paragraph, Wrap,
+};
+
+/// Sprint analytics: progress, counts by state, scope change and burndown
+pub fn draw(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.config.theme;
+    let Some(sprint) = app.selected_sprint() else {
+        return;
+    };
+    let progress = app.sprint_progress();
+
+    let popup = super::super::centered_rect(100, 28, area);
+    f.render_widget(Clear, popup);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(format!(" {} ", sprint.name));
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Progress gauge
+            Constraint::Min(0),    // Stats and chart
+            Constraint::Length(1), // Hints
+        ])
+        .split(inner);
+
+    let gauge = Gauge::default()
+        .gauge_style(Style::default().fg(Color::Green).bg(Color::DarkGray))
+        .ratio(progress.ratio())
+        .label(format!(
+            "{}/{} done ({}%)",
+            progress.done,
+            progress.total,
+            progress.percent()
+        ));
+    f.render_widget(gauge, rows[0]);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(32), Constraint::Min(0)])
+        .split(rows[1]);
+    draw_stats(f, app, &progress, columns[0]);
+    draw_chart(f, app, columns[1]);
+
+    let hints = Paragraph::new("r:reload history  B/Esc:close")
+        .style(Style::default().fg(theme.parse_color(&theme.text_muted)));
+    f.render_widget(hints, rows[2]);
+}
+
+fn draw_stats(f: &mut Frame, app: &App, progress: &SprintProgress, area: Rect) {
+    let theme = &app.config.theme;
+    let muted = Style::default().fg(theme.parse_color(&theme.text_muted));
+    let heading = Style::default().add_modifier(Modifier::BOLD);
+    let mut lines: Vec<Line> = Vec::new();
+
+    if let Some(sprint) = app.selected_sprint() {
+        let date = |d: Option<chrono::DateTime<chrono::Utc>>| {
+            d.map(|d| d.format("%b %d").to_string())
+                .unwrap_or_else(|| "?".to_string())
+        };
+        lines.push(Line::styled(
+            format!(
+                "{} - {}",
+                date(sprint.attributes.start_date),
+                date(sprint.attributes.finish_date)
+            ),
+            muted,
+        ));
+        lines.push(Line::raw(""));
+    }
+
+    lines.push(Line::styled("By state", heading));
+    for (state, count) in &progress.by_state {
+        lines.push(Line::from(vec![
+            Span::styled("● ", Style::default().fg(theme.state_color(state))),
+            Span::raw(format!("{state:<18}")),
+            Span::raw(format!("{count:>3}")),
+        ]));
+    }
+
+    if progress.original_estimate + progress.remaining_work + progress.completed_work > 0.0 {
+        lines.push(Line::raw(""));
+        lines.push(Line::styled("Work (hours)", heading));
+        for (label, value) in [
+            ("Original estimate", progress.original_estimate),
+            ("Completed", progress.completed_work),
+            ("Remaining", progress.remaining_work),
+        ] {
+            lines.push(Line::raw(format!("  {label:<18}{value:>5.1}")));
+        }
+    }
+
+    lines.push(Line::raw(""));
+    lines.push(Line::styled("Added after start", heading));
+    match &app.burndown {
+        _ if app.burndown_rx.is_some() => lines.push(Line::styled("  Loading...", muted)),
+        Some(burndown) if !burndown.added.is_empty() => {
+            let items = App::flatten_work_items(&app.work_items);
+            for id in &burndown.added {
+                let title = items
+                    .iter()
+                    .find(|item| item.id == *id)
+                    .map(|item| item.fields.title.as_str())
+                    .unwrap_or_default();
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  #{id} "), Style::default().fg(Color::Yellow)),
+                    Span::raw(title.to_string()),
+                ]));
+            }
+        }
+        Some(_) => lines.push(Line::styled("  None", muted)),
+        None => lines.push(Line::styled("  Unknown", muted)),
+    }
+
+    f.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: false }),
+        area.inner(Margin::new(1, 0)),
+    );
+}
+
+fn draw_chart(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.config.theme;
+    let muted = Style::default().fg(theme.parse_color(&theme.text_muted));
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.parse_color(&theme.border)))
+        .title(" Burndown ");
+
+    let message = match &app.burndown {
+        _ if app.burndown_rx.is_some() => Some("Loading revision history...".to_string()),
+        None => Some("No burndown: the sprint has no dates".to_string()),
+        Some(b) if b.actual.is_empty() => {
+            Some(format!("The sprint starts on {}", b.start.format("%b %d")))
+        }
+        Some(_) => None,
+    };
+    let (Some(burndown), None) = (&app.burndown, message.as_ref()) else {
+        let text = message.unwrap_or_default();
+        f.render_widget(Paragraph::new(text).style(muted).block(block), area);
+        return;
+    };
+
+    let last_day = (burndown.days() - 1) as f64;
+    let ideal = [(0.0, burndown.ideal(0)), (last_day, 0.0)];
+    let actual: Vec<(f64, f64)> = burndown
+        .actual
+        .iter()
+        .enumerate()
+        .map(|(day, value)| (day as f64, *value))
+        .collect();
+    let datasets = vec![
+        Dataset::default()
+            .name("Ideal")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(muted)
+            .data(&ideal),
+        Dataset::default()
+            .name(format!("Remaining {}", burndown.unit.label()))
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(theme.parse_color(&theme.highlight)))
+            .data(&actual),
+    ];
+
+    let top = y_axis_top(burndown);
+    let chart = Chart::new(datasets)
+        .block(block)
+        .legend_position(Some(LegendPosition::TopRight))
+        .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)))
+        .x_axis(
+            Axis::default()
+                .style(muted)
+                .bounds([0.0, last_day.max(1.0)])
+                .labels(x_labels(burndown)),
+        )
+        .y_axis(Axis::default().style(muted).bounds([0.0, top]).labels([
+            "0".to_string(),
+            format!("{}", top / 2.0),
+            format!("{top}"),
+        ]));
+    f.render_widget(chart, area);
+}
+
+/// Round the scale up to an even number so the middle label stays whole
+fn y_axis_top(burndown: &Burndown) -> f64 {
+    let max = burndown.max_value().ceil().max(2.0);
+    max + max % 2.0
+}
+
+fn x_labels(burndown: &Burndown) -> Vec<String> {
+    let middle = burndown.start + chrono::Duration::days((burndown.days() as i64 - 1) / 2);
+    [burndown.start, middle, burndown.finish]
+        .iter()
+        .map(|d| d.format("%b %d").to_string())
+        .collect()
+}
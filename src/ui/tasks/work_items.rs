@@ -23,15 +23,28 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
         .iter()
         .enumerate()
         .map(|(idx, vi)| {
+            let mark = if app.bulk_failures.contains_key(&vi.item.id) {
+                Mark::Failed
+            } else if app.is_marked(idx, vi.item.id) {
+                Mark::Marked
+            } else {
+                Mark::None
+            };
             render_work_item(
                 vi,
                 &app.config.theme,
                 selected_idx == Some(idx),
+                mark,
                 inner_width,
             )
         })
         .collect();
 
+    let block = if app.has_marks() {
+        block.title_bottom(format!(" {} marked ", app.marked_ids().len()))
+    } else {
+        block
+    };
     let list = List::new(items)
         .block(block)
         .highlight_style(Style::default().bg(Color::Rgb(35, 55, 85)))
@@ -40,6 +53,15 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
     f.render_stateful_widget(list, area, &mut app.work_item_list_state);
 }
 
+/// Multi-select state of a row
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mark {
+    None,
+    Marked,
+    /// Marked, and the last bulk update failed for it
+    Failed,
+}
+
 fn truncate_str(s: &str, max_chars: usize) -> String {
     let chars: Vec<char> = s.chars().collect();
     if chars.len() > max_chars {
@@ -62,6 +84,7 @@ fn render_work_item(
     vi: &VisibleWorkItem,
     theme: &crate::config::Theme,
     is_selected: bool,
+    mark: Mark,
     available_width: usize,
 ) -> ListItem<'static> {
     let item = &vi.item;
@@ -113,8 +136,12 @@ fn render_work_item(
     let max_title_len = available_width.saturating_sub(prefix_width + hours_width);
     let title = truncate_str(&item.fields.title, max_title_len);
 
-    // Pin icon takes priority over state icon (always shows when pinned)
-    let first_icon = if vi.is_pinned {
+    // Mark, then pin, take priority over the state icon
+    let first_icon = if mark == Mark::Failed {
+        Span::styled("✗ ", Style::default().fg(Color::Rgb(224, 108, 117)))
+    } else if mark == Mark::Marked {
+        Span::styled("✓ ", Style::default().fg(Color::Rgb(97, 175, 239)))
+    } else if vi.is_pinned {
         Span::styled("⚑ ", Style::default().fg(Color::Rgb(220, 180, 80)))
     } else {
         Span::styled(format!("{state_icon} "), Style::default().fg(state_color))
@@ -175,7 +202,9 @@ fn render_work_item(
 
     // Subtle yellow background for pinned items (will be overwritten by selection highlight)
     let mut list_item = ListItem::new(Line::from(spans));
-    if vi.is_pinned && !is_selected {
+    if mark != Mark::None && !is_selected {
+        list_item = list_item.style(Style::default().bg(Color::Rgb(30, 40, 55)));
+    } else if vi.is_pinned && !is_selected {
         list_item = list_item.style(Style::default().bg(Color::Rgb(45, 42, 25)));
     }
     list_item
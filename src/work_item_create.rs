@@ -0,0 +1,366 @@
+//! State of the dialog that creates work items, one per title line
+
+use crate::azure::{FieldUpdate, NewWorkItem, User, WorkItem, WorkItemFields};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+
+/// Dialog fields in Tab order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkItemCreateField {
+    Type,
+    Titles,
+    Assignee,
+    Estimate,
+    Tags,
+    Iteration,
+    Parent,
+}
+
+impl WorkItemCreateField {
+    const ALL: [WorkItemCreateField; 7] = [
+        WorkItemCreateField::Type,
+        WorkItemCreateField::Titles,
+        WorkItemCreateField::Assignee,
+        WorkItemCreateField::Estimate,
+        WorkItemCreateField::Tags,
+        WorkItemCreateField::Iteration,
+        WorkItemCreateField::Parent,
+    ];
+
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let idx = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(idx + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WorkItemCreateField::Type => "Type",
+            WorkItemCreateField::Titles => "Titles (one work item per line)",
+            WorkItemCreateField::Assignee => "Assigned to",
+            WorkItemCreateField::Estimate => "Estimate (hours)",
+            WorkItemCreateField::Tags => "Tags",
+            WorkItemCreateField::Iteration => "Iteration",
+            WorkItemCreateField::Parent => "Parent",
+        }
+    }
+}
+
+/// Work item the new ones are created under
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParentItem {
+    pub id: i32,
+    pub title: String,
+}
+
+/// Work item creation dialog state
+#[derive(Debug, Clone)]
+pub struct WorkItemCreateDialog {
+    /// One of [`WorkItem::TYPES`]
+    pub work_item_type: &'static str,
+    pub titles: String,
+    pub assignee: Option<User>,
+    pub estimate: String,
+    pub tags: String,
+    pub iteration: String,
+    pub parent: Option<ParentItem>,
+    pub field: WorkItemCreateField,
+    /// Filter typed into the assignee picker
+    pub query: String,
+    /// Highlighted assignee candidate
+    pub cursor: usize,
+}
+
+impl WorkItemCreateDialog {
+    /// Dialog for the selected sprint; with a parent, defaults to child tasks
+    pub fn new(iteration: String, parent: Option<&WorkItem>) -> Self {
+        Self {
+            work_item_type: if parent.is_some() {
+                "Task"
+            } else {
+                "User Story"
+            },
+            titles: String::new(),
+            assignee: None,
+            estimate: String::new(),
+            tags: String::new(),
+            iteration,
+            parent: parent.map(|p| ParentItem {
+                id: p.id,
+                title: p.fields.title.clone(),
+            }),
+            field: WorkItemCreateField::Titles,
+            query: String::new(),
+            cursor: 0,
+        }
+    }
+
+    pub fn next_field(&mut self) {
+        self.focus(self.field.next());
+    }
+
+    pub fn prev_field(&mut self) {
+        self.focus(self.field.prev());
+    }
+
+    fn focus(&mut self, field: WorkItemCreateField) {
+        self.field = field;
+        self.query.clear();
+        self.cursor = 0;
+    }
+
+    /// Arrow keys: cycle the type, or move through assignee candidates
+    pub fn move_cursor(&mut self, delta: isize, candidates: usize) {
+        match self.field {
+            WorkItemCreateField::Type => {
+                let types = WorkItem::TYPES;
+                let idx = types
+                    .iter()
+                    .position(|t| *t == self.work_item_type)
+                    .unwrap_or(0);
+                let next = (idx as isize + delta).rem_euclid(types.len() as isize);
+                self.work_item_type = types[next as usize];
+            }
+            WorkItemCreateField::Assignee if candidates > 0 => {
+                self.cursor = self.cursor.saturating_add_signed(delta).min(candidates - 1);
+            }
+            _ => {}
+        }
+    }
+
+    /// Assignees matching the filter, best matches first
+    pub fn assignee_candidates(&self, users: &[User]) -> Vec<User> {
+        let matcher = SkimMatcherV2::default();
+        let mut scored: Vec<(i64, &User)> = users
+            .iter()
+            .filter_map(|u| {
+                if self.query.is_empty() {
+                    return Some((0, u));
+                }
+                let text = format!("{} {}", u.display_name, u.unique_name);
+                matcher.fuzzy_match(&text, &self.query).map(|s| (s, u))
+            })
+            .collect();
+        scored.sort_by_key(|(s, _)| std::cmp::Reverse(*s));
+        let mut candidates: Vec<User> = scored.into_iter().map(|(_, u)| u.clone()).collect();
+
+        // Anyone can be assigned by email
+        if self.query.contains('@') && !candidates.iter().any(|u| u.unique_name == self.query) {
+            candidates.push(User {
+                display_name: self.query.clone(),
+                unique_name: self.query.clone(),
+            });
+        }
+        candidates
+    }
+
+    pub fn pick_assignee(&mut self, user: User) {
+        self.assignee = Some(user);
+        self.next_field();
+    }
+
+    /// Type a character into the focused field
+    pub fn insert(&mut self, c: char) {
+        match self.field {
+            WorkItemCreateField::Titles => self.titles.push(c),
+            WorkItemCreateField::Estimate => self.estimate.push(c),
+            WorkItemCreateField::Tags => self.tags.push(c),
+            WorkItemCreateField::Iteration => self.iteration.push(c),
+            WorkItemCreateField::Assignee => {
+                self.query.push(c);
+                self.cursor = 0;
+            }
+            WorkItemCreateField::Type | WorkItemCreateField::Parent => {}
+        }
+    }
+
+    /// Delete backwards; clears the assignee or parent when there is nothing to delete
+    pub fn backspace(&mut self) {
+        match self.field {
+            WorkItemCreateField::Titles => {
+                self.titles.pop();
+            }
+            WorkItemCreateField::Estimate => {
+                self.estimate.pop();
+            }
+            WorkItemCreateField::Tags => {
+                self.tags.pop();
+            }
+            WorkItemCreateField::Iteration => {
+                self.iteration.pop();
+            }
+            WorkItemCreateField::Assignee if self.query.is_empty() => self.assignee = None,
+            WorkItemCreateField::Assignee => {
+                self.query.pop();
+                self.cursor = 0;
+            }
+            WorkItemCreateField::Parent => self.parent = None,
+            WorkItemCreateField::Type => {}
+        }
+    }
+
+    /// Non-empty title lines; each becomes a work item
+    pub fn titles(&self) -> Vec<&str> {
+        self.titles
+            .lines()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .collect()
+    }
+
+    /// Why nothing can be created yet, if anything is missing
+    pub fn validate(&self) -> Option<&'static str> {
+        if self.titles().is_empty() {
+            Some("Enter a title")
+        } else if !self.estimate.trim().is_empty() && self.estimate.trim().parse::<f64>().is_err() {
+            Some("Estimate must be a number of hours")
+        } else if self.iteration.trim().is_empty() {
+            Some("Enter an iteration path")
+        } else {
+            None
+        }
+    }
+
+    /// One request per title line, sharing the other fields
+    pub fn to_requests(&self) -> Vec<NewWorkItem> {
+        let mut shared = vec![FieldUpdate::set(
+            WorkItemFields::ITERATION_PATH,
+            self.iteration.trim(),
+        )];
+        if let Some(assignee) = &self.assignee {
+            shared.push(FieldUpdate::set(
+                WorkItemFields::ASSIGNED_TO,
+                assignee.unique_name.as_str(),
+            ));
+        }
+        if let Ok(hours) = self.estimate.trim().parse::<f64>() {
+            shared.push(FieldUpdate::set(WorkItemFields::ORIGINAL_ESTIMATE, hours));
+            shared.push(FieldUpdate::set(WorkItemFields::REMAINING_WORK, hours));
+        }
+        let tags: Vec<&str> = self
+            .tags
+            .split([',', ';'])
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .collect();
+        if !tags.is_empty() {
+            shared.push(FieldUpdate::set(WorkItemFields::TAGS, tags.join("; ")));
+        }
+
+        self.titles()
+            .into_iter()
+            .map(|title| {
+                let mut fields = vec![FieldUpdate::set(WorkItemFields::TITLE, title)];
+                fields.extend(shared.iter().cloned());
+                NewWorkItem {
+                    work_item_type: self.work_item_type.to_string(),
+                    fields,
+                    parent_id: self.parent.as_ref().map(|p| p.id),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn story() -> WorkItem {
+        serde_json::from_value(serde_json::json!({
+            "id": 1001,
+            "rev": 1,
+            "fields": {
+                "System.Title": "Checkout flow",
+                "System.State": "Active",
+                "System.WorkItemType": "User Story"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_defaults() {
+        let dialog = WorkItemCreateDialog::new("Demo\\Sprint 42".into(), Some(&story()));
+        assert_eq!(dialog.work_item_type, "Task");
+        assert_eq!(dialog.parent.as_ref().map(|p| p.id), Some(1001));
+        assert_eq!(dialog.field, WorkItemCreateField::Titles);
+        assert_eq!(dialog.validate(), Some("Enter a title"));
+
+        let mut dialog = WorkItemCreateDialog::new("Demo\\Sprint 42".into(), None);
+        assert_eq!(dialog.work_item_type, "User Story");
+        dialog.field = WorkItemCreateField::Type;
+        dialog.move_cursor(-1, 0);
+        assert_eq!(dialog.work_item_type, "Bug");
+    }
+
+    #[test]
+    fn test_batch_requests() {
+        let mut dialog = WorkItemCreateDialog::new("Demo\\Sprint 42".into(), Some(&story()));
+        for c in "Design form\n\n  Wire up API \n".chars() {
+            dialog.insert(c);
+        }
+        dialog.field = WorkItemCreateField::Estimate;
+        dialog.insert('x');
+        assert_eq!(
+            dialog.validate(),
+            Some("Estimate must be a number of hours")
+        );
+        dialog.backspace();
+        dialog.insert('4');
+        dialog.field = WorkItemCreateField::Tags;
+        for c in "ux, api".chars() {
+            dialog.insert(c);
+        }
+        assert_eq!(dialog.validate(), None);
+
+        let requests = dialog.to_requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].title(), "Design form");
+        assert_eq!(requests[1].title(), "Wire up API");
+        assert!(requests.iter().all(|r| r.parent_id == Some(1001)));
+        assert!(requests[1]
+            .fields
+            .contains(&FieldUpdate::set(WorkItemFields::REMAINING_WORK, 4.0)));
+        assert!(requests[1]
+            .fields
+            .contains(&FieldUpdate::set(WorkItemFields::TAGS, "ux; api")));
+
+        // Dropping the parent creates top-level items
+        dialog.field = WorkItemCreateField::Parent;
+        dialog.backspace();
+        assert!(dialog.to_requests().iter().all(|r| r.parent_id.is_none()));
+    }
+
+    #[test]
+    fn test_assignee_picker() {
+        let users = vec![
+            User {
+                display_name: "Ada Lovelace".into(),
+                unique_name: "ada@contoso.com".into(),
+            },
+            User {
+                display_name: "Grace Hopper".into(),
+                unique_name: "grace@contoso.com".into(),
+            },
+        ];
+        let mut dialog = WorkItemCreateDialog::new("Demo\\Sprint 42".into(), None);
+        dialog.field = WorkItemCreateField::Assignee;
+        for c in "grace".chars() {
+            dialog.insert(c);
+        }
+        let candidates = dialog.assignee_candidates(&users);
+        assert_eq!(candidates[0].unique_name, "grace@contoso.com");
+        dialog.pick_assignee(candidates[0].clone());
+        assert_eq!(dialog.field, WorkItemCreateField::Estimate);
+
+        // Backspace on an empty filter unassigns
+        dialog.field = WorkItemCreateField::Assignee;
+        dialog.backspace();
+        assert!(dialog.assignee.is_none());
+    }
+}
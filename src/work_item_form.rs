@@ -0,0 +1,324 @@
+//! State of the editable work item Details form
+
+use crate::azure::{FieldUpdate, WorkItem, WorkItemFields};
+use crate::markdown::{html_to_markdown, markdown_to_html};
+use serde_json::Value;
+
+/// How a form field is edited and sent back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Text,
+    /// HTML field edited as Markdown in `$EDITOR`
+    Html,
+    /// `;`-separated tags, typed with `,` or `;`
+    Tags,
+    Integer,
+    Decimal,
+    Boolean,
+}
+
+/// Fields every work item form offers, in display order
+const STANDARD_FIELDS: &[(&str, &str, FieldKind)] = &[
+    (WorkItemFields::TITLE, "Title", FieldKind::Text),
+    (WorkItemFields::DESCRIPTION, "Description", FieldKind::Html),
+    (WorkItemFields::TAGS, "Tags", FieldKind::Tags),
+    (WorkItemFields::PRIORITY, "Priority", FieldKind::Integer),
+    (
+        WorkItemFields::ORIGINAL_ESTIMATE,
+        "Original estimate",
+        FieldKind::Decimal,
+    ),
+    (
+        WorkItemFields::REMAINING_WORK,
+        "Remaining work",
+        FieldKind::Decimal,
+    ),
+    (
+        WorkItemFields::COMPLETED_WORK,
+        "Completed work",
+        FieldKind::Decimal,
+    ),
+    (
+        WorkItemFields::ITERATION_PATH,
+        "Iteration path",
+        FieldKind::Text,
+    ),
+    (WorkItemFields::AREA_PATH, "Area path", FieldKind::Text),
+];
+
+/// One editable row of the form
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormField {
+    /// Reference name, e.g. `System.Title` or `Custom.Team`
+    pub name: String,
+    pub label: String,
+    pub kind: FieldKind,
+    /// Text as loaded, to detect edits
+    pub original: String,
+    pub value: String,
+}
+
+impl FormField {
+    fn new(name: &str, label: &str, kind: FieldKind, value: Option<&Value>) -> Self {
+        let text = match (kind, value) {
+            (_, None | Some(Value::Null)) => String::new(),
+            (FieldKind::Html, Some(v)) => html_to_markdown(v.as_str().unwrap_or_default()),
+            (_, Some(Value::String(s))) => s.clone(),
+            (_, Some(v)) => v.to_string(),
+        };
+        Self {
+            name: name.to_string(),
+            label: label.to_string(),
+            kind,
+            original: text.clone(),
+            value: text,
+        }
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.value != self.original
+    }
+
+    /// Value to send, or why the typed text is invalid
+    fn update(&self) -> Result<FieldUpdate, String> {
+        let text = self.value.trim();
+        if text.is_empty() {
+            return Ok(FieldUpdate::clear(&self.name));
+        }
+        let value = match self.kind {
+            FieldKind::Text => Value::from(text),
+            FieldKind::Html => Value::from(markdown_to_html(&self.value)),
+            FieldKind::Tags => Value::from(
+                text.split([',', ';'])
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            ),
+            FieldKind::Integer => text
+                .parse::<i64>()
+                .map(Value::from)
+                .map_err(|_| format!("{} must be a whole number", self.label))?,
+            FieldKind::Decimal => text
+                .parse::<f64>()
+                .map(Value::from)
+                .map_err(|_| format!("{} must be a number", self.label))?,
+            FieldKind::Boolean => match text.to_ascii_lowercase().as_str() {
+                "true" | "yes" | "1" => Value::from(true),
+                "false" | "no" | "0" => Value::from(false),
+                _ => return Err(format!("{} must be true or false", self.label)),
+            },
+        };
+        Ok(FieldUpdate::set(&self.name, value))
+    }
+}
+
+/// Editable form for the fields of one work item
+#[derive(Debug, Clone)]
+pub struct WorkItemForm {
+    pub id: i32,
+    pub fields: Vec<FormField>,
+    pub selected: usize,
+    /// Typing into the selected (single-line) field
+    pub editing: bool,
+    /// Value of the field before the current inline edit
+    before_edit: String,
+}
+
+impl WorkItemForm {
+    pub fn new(item: &WorkItem) -> Self {
+        let mut fields: Vec<FormField> = STANDARD_FIELDS
+            .iter()
+            .map(|(name, label, kind)| {
+                FormField::new(name, label, *kind, item.fields.get(name).as_ref())
+            })
+            .collect();
+
+        // Custom fields present on the item; other system fields are read-only here
+        for (name, value) in &item.fields.other {
+            if name.starts_with("System.") || name.starts_with("Microsoft.VSTS.") {
+                continue;
+            }
+            let kind = match value {
+                Value::Bool(_) => FieldKind::Boolean,
+                Value::Number(n) if n.is_i64() => FieldKind::Integer,
+                Value::Number(_) => FieldKind::Decimal,
+                Value::String(s) if s.trim_start().starts_with('<') => FieldKind::Html,
+                Value::String(_) | Value::Null => FieldKind::Text,
+                // Identities and other objects
+                _ => continue,
+            };
+            let label = name.rsplit('.').next().unwrap_or(name);
+            fields.push(FormField::new(name, label, kind, Some(value)));
+        }
+
+        Self {
+            id: item.id,
+            fields,
+            selected: 0,
+            editing: false,
+            before_edit: String::new(),
+        }
+    }
+
+    pub fn selected_field(&self) -> Option<&FormField> {
+        self.fields.get(self.selected)
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        if self.editing || self.fields.is_empty() {
+            return;
+        }
+        self.selected = self
+            .selected
+            .saturating_add_signed(delta)
+            .min(self.fields.len() - 1);
+    }
+
+    /// Start typing into the selected field (HTML fields go to `$EDITOR` instead)
+    pub fn start_edit(&mut self) -> bool {
+        match self.fields.get(self.selected) {
+            Some(field) if field.kind != FieldKind::Html => {
+                self.before_edit = field.value.clone();
+                self.editing = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn finish_edit(&mut self) {
+        self.editing = false;
+    }
+
+    /// Abandon the inline edit, restoring the previous value
+    pub fn cancel_edit(&mut self) {
+        if let Some(field) = self.fields.get_mut(self.selected) {
+            field.value = std::mem::take(&mut self.before_edit);
+        }
+        self.editing = false;
+    }
+
+    pub fn insert(&mut self, c: char) {
+        if let Some(field) = self.fields.get_mut(self.selected).filter(|_| self.editing) {
+            field.value.push(c);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(field) = self.fields.get_mut(self.selected).filter(|_| self.editing) {
+            field.value.pop();
+        }
+    }
+
+    /// Set the value of a field by reference name (e.g. after editing in `$EDITOR`)
+    pub fn set_value(&mut self, name: &str, value: String) {
+        if let Some(field) = self.fields.iter_mut().find(|f| f.name == name) {
+            field.value = value;
+        }
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.fields.iter().any(FormField::is_dirty)
+    }
+
+    /// Updates for every edited field
+    pub fn updates(&self) -> Result<Vec<FieldUpdate>, String> {
+        self.fields
+            .iter()
+            .filter(|f| f.is_dirty())
+            .map(FormField::update)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item() -> WorkItem {
+        let json = serde_json::json!({
+            "id": 1003,
+            "rev": 4,
+            "fields": {
+                "System.Title": "Address autocomplete",
+                "System.State": "Active",
+                "System.WorkItemType": "Task",
+                "System.Description": "<div>Use the <b>places</b> API</div>",
+                "System.Tags": "checkout; ux",
+                "Microsoft.VSTS.Scheduling.RemainingWork": 3.0,
+                "System.TeamProject": "Shop",
+                "Custom.Team": "Payments",
+                "Custom.Risk": 2
+            }
+        });
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_form_fields_from_item() {
+        let form = WorkItemForm::new(&item());
+        let field = |name: &str| form.fields.iter().find(|f| f.name == name).unwrap();
+        assert_eq!(
+            field(WorkItemFields::DESCRIPTION).value,
+            "Use the **places** API"
+        );
+        assert_eq!(field(WorkItemFields::REMAINING_WORK).value, "3.0");
+        assert_eq!(field("Custom.Team").label, "Team");
+        assert_eq!(field("Custom.Risk").kind, FieldKind::Integer);
+        // Read-only system fields are left out
+        assert!(!form.fields.iter().any(|f| f.name == "System.TeamProject"));
+        assert!(!form.is_dirty());
+    }
+
+    #[test]
+    fn test_updates_for_edited_fields() {
+        let mut form = WorkItemForm::new(&item());
+        let select = |form: &mut WorkItemForm, name: &str| {
+            form.selected = form.fields.iter().position(|f| f.name == name).unwrap();
+        };
+
+        select(&mut form, WorkItemFields::TAGS);
+        assert!(form.start_edit());
+        for c in ", backend".chars() {
+            form.insert(c);
+        }
+        form.finish_edit();
+
+        select(&mut form, WorkItemFields::REMAINING_WORK);
+        form.start_edit();
+        form.backspace();
+        form.backspace();
+        form.backspace();
+        form.finish_edit();
+
+        form.set_value(WorkItemFields::DESCRIPTION, "Use the *maps* API".into());
+
+        let updates = form.updates().unwrap();
+        assert_eq!(updates.len(), 3);
+        assert!(updates.contains(&FieldUpdate::set(
+            WorkItemFields::TAGS,
+            "checkout; ux; backend"
+        )));
+        assert!(updates.contains(&FieldUpdate::clear(WorkItemFields::REMAINING_WORK)));
+        let description = updates
+            .iter()
+            .find(|u| u.field == WorkItemFields::DESCRIPTION)
+            .unwrap();
+        assert!(description
+            .value
+            .as_ref()
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .contains("<em>maps</em>"));
+
+        // Invalid numbers are reported instead of sent
+        select(&mut form, "Custom.Risk");
+        form.start_edit();
+        form.insert('x');
+        assert_eq!(form.updates().unwrap_err(), "Risk must be a whole number");
+        form.cancel_edit();
+        assert!(form.updates().is_ok());
+    }
+}
@@ -0,0 +1,540 @@
+//! State of the dialog that links a work item to another, or to a pull
+//! request or commit
+
+use crate::azure::{DevOpsBackend, PullRequest, Repository, WorkItem, WorkItemRelation};
+use anyhow::{Context, Result};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::collections::HashSet;
+
+/// Kinds of links, in the order Tab cycles through them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+    Parent,
+    Related,
+    Predecessor,
+    Successor,
+    Duplicate,
+    DuplicateOf,
+    PullRequest,
+    Commit,
+}
+
+impl LinkKind {
+    pub const ALL: [LinkKind; 8] = [
+        LinkKind::Parent,
+        LinkKind::Related,
+        LinkKind::Predecessor,
+        LinkKind::Successor,
+        LinkKind::Duplicate,
+        LinkKind::DuplicateOf,
+        LinkKind::PullRequest,
+        LinkKind::Commit,
+    ];
+
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|k| *k == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let idx = Self::ALL.iter().position(|k| *k == self).unwrap_or(0);
+        Self::ALL[(idx + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LinkKind::Parent => "Parent",
+            LinkKind::Related => "Related",
+            LinkKind::Predecessor => "Predecessor",
+            LinkKind::Successor => "Successor",
+            LinkKind::Duplicate => "Duplicate",
+            LinkKind::DuplicateOf => "Duplicate of",
+            LinkKind::PullRequest => "Pull request",
+            LinkKind::Commit => "Commit",
+        }
+    }
+
+    /// Link type for links to work items; `None` for artifact links
+    pub fn rel(self) -> Option<&'static str> {
+        match self {
+            LinkKind::Parent => Some(WorkItemRelation::PARENT),
+            LinkKind::Related => Some("System.LinkTypes.Related"),
+            LinkKind::Predecessor => Some("System.LinkTypes.Dependency-Reverse"),
+            LinkKind::Successor => Some("System.LinkTypes.Dependency-Forward"),
+            LinkKind::Duplicate => Some("System.LinkTypes.Duplicate-Forward"),
+            LinkKind::DuplicateOf => Some("System.LinkTypes.Duplicate-Reverse"),
+            LinkKind::PullRequest | LinkKind::Commit => None,
+        }
+    }
+
+    /// What to type into the search
+    pub fn placeholder(self) -> &'static str {
+        match self {
+            LinkKind::PullRequest => "PR ID or title",
+            LinkKind::Commit => "Commit hash",
+            _ => "Work item ID or title",
+        }
+    }
+}
+
+/// Something the work item can be linked to
+#[derive(Debug, Clone)]
+pub enum LinkTarget {
+    /// Title is unknown for IDs outside the loaded list
+    WorkItem {
+        id: i32,
+        title: Option<String>,
+    },
+    /// Drop the current parent, making the item top-level
+    NoParent(i32),
+    PullRequest {
+        id: i32,
+        title: Option<String>,
+    },
+    Commit {
+        hash: String,
+        repository: Repository,
+    },
+}
+
+impl LinkTarget {
+    pub fn label(&self) -> String {
+        match self {
+            LinkTarget::WorkItem { id, title } | LinkTarget::PullRequest { id, title } => {
+                let prefix = if matches!(self, LinkTarget::PullRequest { .. }) {
+                    "!"
+                } else {
+                    "#"
+                };
+                match title {
+                    Some(title) => format!("{prefix}{id} {title}"),
+                    None => format!("{prefix}{id}"),
+                }
+            }
+            LinkTarget::NoParent(id) => format!("No parent (unlink #{id})"),
+            LinkTarget::Commit { hash, repository } => format!("{hash} in {}", repository.name),
+        }
+    }
+}
+
+/// Link dialog state
+#[derive(Debug, Clone)]
+pub struct LinkDialog {
+    pub work_item_id: i32,
+    pub parent_id: Option<i32>,
+    /// The item and everything below it, which can't become its parent
+    excluded_parents: HashSet<i32>,
+    /// Repositories a commit can come from
+    pub repositories: Vec<Repository>,
+    pub kind: LinkKind,
+    pub query: String,
+    /// Highlighted candidate
+    pub cursor: usize,
+}
+
+impl LinkDialog {
+    /// Dialog for `item`, given the loaded work items (flat)
+    pub fn new(item: &WorkItem, items: &[WorkItem], repositories: Vec<Repository>) -> Self {
+        let mut excluded_parents = HashSet::from([item.id]);
+        // Walk down level by level until no more children turn up
+        loop {
+            let before = excluded_parents.len();
+            for w in items {
+                if w.fields
+                    .parent_id
+                    .is_some_and(|p| excluded_parents.contains(&p))
+                {
+                    excluded_parents.insert(w.id);
+                }
+            }
+            if excluded_parents.len() == before {
+                break;
+            }
+        }
+        Self {
+            work_item_id: item.id,
+            parent_id: item.fields.parent_id,
+            excluded_parents,
+            repositories,
+            kind: LinkKind::Parent,
+            query: String::new(),
+            cursor: 0,
+        }
+    }
+
+    pub fn next_kind(&mut self) {
+        self.set_kind(self.kind.next());
+    }
+
+    pub fn prev_kind(&mut self) {
+        self.set_kind(self.kind.prev());
+    }
+
+    fn set_kind(&mut self, kind: LinkKind) {
+        self.kind = kind;
+        self.query.clear();
+        self.cursor = 0;
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.query.push(c);
+        self.cursor = 0;
+    }
+
+    pub fn backspace(&mut self) {
+        self.query.pop();
+        self.cursor = 0;
+    }
+
+    pub fn move_cursor(&mut self, delta: isize, candidates: usize) {
+        if candidates > 0 {
+            self.cursor = self.cursor.saturating_add_signed(delta).min(candidates - 1);
+        }
+    }
+
+    /// Targets matching the search for the current kind, best matches first
+    pub fn candidates(&self, items: &[WorkItem], pull_requests: &[PullRequest]) -> Vec<LinkTarget> {
+        let query = self.query.trim().trim_start_matches(['#', '!']);
+        match self.kind {
+            LinkKind::PullRequest => {
+                let known = pull_requests
+                    .iter()
+                    .map(|pr| (pr.pull_request_id, pr.title.as_str()));
+                Self::matching(query, known)
+                    .into_iter()
+                    .map(|(id, title)| LinkTarget::PullRequest { id, title })
+                    .collect()
+            }
+            LinkKind::Commit => {
+                let is_hash =
+                    (7..=40).contains(&query.len()) && query.chars().all(|c| c.is_ascii_hexdigit());
+                if !is_hash {
+                    return Vec::new();
+                }
+                self.repositories
+                    .iter()
+                    .map(|repository| LinkTarget::Commit {
+                        hash: query.to_lowercase(),
+                        repository: repository.clone(),
+                    })
+                    .collect()
+            }
+            kind => {
+                let known = items
+                    .iter()
+                    .filter(|w| match kind {
+                        LinkKind::Parent => {
+                            !self.excluded_parents.contains(&w.id) && Some(w.id) != self.parent_id
+                        }
+                        _ => w.id != self.work_item_id,
+                    })
+                    .map(|w| (w.id, w.fields.title.as_str()));
+                let mut candidates: Vec<LinkTarget> = Self::matching(query, known)
+                    .into_iter()
+                    .filter(|(id, _)| *id != self.work_item_id)
+                    .map(|(id, title)| LinkTarget::WorkItem { id, title })
+                    .collect();
+                if let (LinkKind::Parent, Some(parent_id), true) =
+                    (kind, self.parent_id, query.is_empty())
+                {
+                    candidates.insert(0, LinkTarget::NoParent(parent_id));
+                }
+                candidates
+            }
+        }
+    }
+
+    /// Fuzzy matches on "ID title"; a number that matches nothing known is
+    /// offered as is
+    fn matching<'a>(
+        query: &str,
+        known: impl Iterator<Item = (i32, &'a str)>,
+    ) -> Vec<(i32, Option<String>)> {
+        let matcher = SkimMatcherV2::default();
+        let mut scored: Vec<(i64, i32, &str)> = known
+            .filter_map(|(id, title)| {
+                if query.is_empty() {
+                    return Some((0, id, title));
+                }
+                let score = matcher.fuzzy_match(&format!("{id} {title}"), query)?;
+                // An exact ID beats any title match
+                let bonus = if id.to_string() == query {
+                    1_000_000
+                } else {
+                    0
+                };
+                Some((score + bonus, id, title))
+            })
+            .collect();
+        scored.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+        let mut matches: Vec<(i32, Option<String>)> = scored
+            .into_iter()
+            .map(|(_, id, title)| (id, Some(title.to_string())))
+            .collect();
+        if let Ok(id) = query.parse::<i32>() {
+            if id > 0 && !matches.iter().any(|(m, _)| *m == id) {
+                matches.insert(0, (id, None));
+            }
+        }
+        matches
+    }
+}
+
+/// Result of a link change, for the work item list
+#[derive(Debug)]
+pub struct LinkOutcome {
+    /// New parent, when that changed
+    pub parent: Option<Option<i32>>,
+    /// Other work items whose links changed with it
+    pub others: Vec<i32>,
+    /// The updated item and a status message
+    pub result: Result<(WorkItem, String), String>,
+}
+
+/// Links to add and remove for linking `work_item_id` to `target`; pull
+/// requests and commits are looked up to find their project
+pub async fn link_changes<B: DevOpsBackend>(
+    client: &B,
+    org_url: &str,
+    kind: LinkKind,
+    target: &LinkTarget,
+    repositories: &[Repository],
+) -> Result<(Vec<WorkItemRelation>, Vec<WorkItemRelation>)> {
+    let change = match target {
+        LinkTarget::WorkItem { id, .. } => {
+            let rel = kind.rel().context("Not a work item link")?;
+            (
+                vec![WorkItemRelation::work_item(org_url, rel, *id)],
+                Vec::new(),
+            )
+        }
+        LinkTarget::NoParent(parent_id) => (
+            Vec::new(),
+            vec![WorkItemRelation::work_item(
+                org_url,
+                WorkItemRelation::PARENT,
+                *parent_id,
+            )],
+        ),
+        LinkTarget::PullRequest { id, .. } => {
+            let pr = client.get_pull_request(*id).await?;
+            let repository = pr
+                .repository
+                .with_context(|| format!("Unknown repository of PR !{id}"))?;
+            let project_id = match &repository.project {
+                Some(project) => project.id.clone(),
+                None => project_of(client, repositories, &repository.id).await?,
+            };
+            let url = pull_request_artifact_url(&project_id, &repository.id, *id);
+            (
+                vec![WorkItemRelation::artifact(url, "Pull Request")],
+                Vec::new(),
+            )
+        }
+        LinkTarget::Commit { hash, repository } => {
+            // Links need the full commit ID, the typed one may be abbreviated
+            let commit = client
+                .get_commit(&repository.id, hash)
+                .await
+                .with_context(|| format!("Commit {hash} not found in {}", repository.name))?;
+            let project_id = match &repository.project {
+                Some(project) => project.id.clone(),
+                None => project_of(client, repositories, &repository.id).await?,
+            };
+            let url = commit_artifact_url(&project_id, &repository.id, &commit.commit_id);
+            (
+                vec![WorkItemRelation::artifact(url, "Fixed in Commit")],
+                Vec::new(),
+            )
+        }
+    };
+    Ok(change)
+}
+
+/// Project ID of a repository, listing the repositories if it isn't known
+async fn project_of<B: DevOpsBackend>(
+    client: &B,
+    repositories: &[Repository],
+    repository_id: &str,
+) -> Result<String> {
+    let find = |repositories: &[Repository]| {
+        repositories
+            .iter()
+            .find(|r| r.id == repository_id)
+            .and_then(|r| r.project.as_ref())
+            .map(|p| p.id.clone())
+    };
+    if let Some(project_id) = find(repositories) {
+        return Ok(project_id);
+    }
+    find(&client.list_repositories().await?)
+        .with_context(|| format!("Unknown project of repository {repository_id}"))
+}
+
+/// `vstfs:///` URL of a pull request, as artifact links store it
+pub fn pull_request_artifact_url(project_id: &str, repository_id: &str, pr_id: i32) -> String {
+    format!("vstfs:///Git/PullRequestId/{project_id}%2F{repository_id}%2F{pr_id}")
+}
+
+/// `vstfs:///` URL of a commit, as artifact links store it
+pub fn commit_artifact_url(project_id: &str, repository_id: &str, hash: &str) -> String {
+    format!("vstfs:///Git/Commit/{project_id}%2F{repository_id}%2F{hash}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: i32, title: &str, parent: Option<i32>) -> WorkItem {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "rev": 1,
+            "fields": {
+                "System.Title": title,
+                "System.State": "New",
+                "System.WorkItemType": "Task",
+                "System.Parent": parent
+            }
+        }))
+        .unwrap()
+    }
+
+    fn items() -> Vec<WorkItem> {
+        vec![
+            item(1, "Checkout redesign", None),
+            item(2, "Payment form", Some(1)),
+            item(3, "Card validation", Some(2)),
+            item(4, "Order history", None),
+        ]
+    }
+
+    fn ids(candidates: &[LinkTarget]) -> Vec<String> {
+        candidates.iter().map(LinkTarget::label).collect()
+    }
+
+    #[test]
+    fn test_parent_candidates() {
+        let items = items();
+        let mut dialog = LinkDialog::new(&items[1], &items, Vec::new());
+        // Neither the item, its current parent nor its children can become
+        // its parent; an empty search offers to drop the parent
+        assert_eq!(
+            ids(&dialog.candidates(&items, &[])),
+            vec!["No parent (unlink #1)", "#4 Order history"]
+        );
+
+        for c in "#12".chars() {
+            dialog.insert(c);
+        }
+        assert_eq!(ids(&dialog.candidates(&items, &[])), vec!["#12"]);
+    }
+
+    #[test]
+    fn test_work_item_candidates() {
+        let items = items();
+        let mut dialog = LinkDialog::new(&items[1], &items, Vec::new());
+        dialog.next_kind();
+        assert_eq!(dialog.kind, LinkKind::Related);
+        assert_eq!(dialog.candidates(&items, &[]).len(), 3);
+
+        for c in "card".chars() {
+            dialog.insert(c);
+        }
+        let candidates = dialog.candidates(&items, &[]);
+        assert_eq!(ids(&candidates)[0], "#3 Card validation");
+        dialog.move_cursor(5, candidates.len());
+        assert_eq!(dialog.cursor, candidates.len() - 1);
+
+        // Switching kinds starts a new search
+        dialog.prev_kind();
+        assert!(dialog.query.is_empty());
+        assert_eq!(dialog.cursor, 0);
+    }
+
+    #[test]
+    fn test_artifact_candidates() {
+        let items = items();
+        let repositories = vec![Repository {
+            id: "repo-web".into(),
+            name: "web".into(),
+            project: None,
+        }];
+        let mut dialog = LinkDialog::new(&items[0], &items, repositories);
+        dialog.kind = LinkKind::Commit;
+        for c in "9F8E7D6".chars() {
+            dialog.insert(c);
+        }
+        assert_eq!(ids(&dialog.candidates(&items, &[])), vec!["9f8e7d6 in web"]);
+        dialog.backspace();
+        assert!(dialog.candidates(&items, &[]).is_empty());
+
+        dialog.prev_kind();
+        assert_eq!(dialog.kind, LinkKind::PullRequest);
+        for c in "!501".chars() {
+            dialog.insert(c);
+        }
+        assert_eq!(ids(&dialog.candidates(&items, &[])), vec!["!501"]);
+    }
+
+    #[tokio::test]
+    async fn test_artifact_links_find_their_project() {
+        let backend = crate::azure::FakeBackend::demo();
+        let org = "https://dev.azure.com/demo";
+        // Demo pull requests don't say which project they are in
+        let target = LinkTarget::PullRequest {
+            id: 501,
+            title: None,
+        };
+        let (add, remove) = link_changes(&backend, org, LinkKind::PullRequest, &target, &[])
+            .await
+            .unwrap();
+        assert!(remove.is_empty());
+        assert_eq!(
+            add[0].url,
+            "vstfs:///Git/PullRequestId/demo-project%2Frepo-web%2F501"
+        );
+        assert_eq!(add[0].attributes.name.as_deref(), Some("Pull Request"));
+
+        let repository = Repository {
+            id: "repo-web".into(),
+            name: "web".into(),
+            project: None,
+        };
+        let target = LinkTarget::Commit {
+            hash: "0000000".into(),
+            repository,
+        };
+        assert!(link_changes(&backend, org, LinkKind::Commit, &target, &[])
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_abbreviated_commit_links_full_id() {
+        let backend = crate::azure::FakeBackend::demo();
+        let repository = Repository {
+            id: "repo-web".into(),
+            name: "web".into(),
+            project: None,
+        };
+        let target = LinkTarget::Commit {
+            hash: "9f8e7d6".into(),
+            repository,
+        };
+        let (add, _) = link_changes(
+            &backend,
+            "https://dev.azure.com/demo",
+            LinkKind::Commit,
+            &target,
+            &[],
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            add[0].url,
+            "vstfs:///Git/Commit/demo-project%2Frepo-web%2F9f8e7d6c5b4a3210fedcba9876543210abcdef01"
+        );
+        assert_eq!(add[0].attributes.name.as_deref(), Some("Fixed in Commit"));
+    }
+}